@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/cli/build.rs
+//
+// Generates the gRPC agent protocol code (see src/fleet.rs) from proto/agent.proto
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = "../../proto/agent.proto";
+    println!("cargo:rerun-if-changed={}", proto);
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_prost_build::configure().build_client(true).build_server(true).compile_protos(&[proto], &["../../proto"])?;
+    Ok(())
+}
@@ -0,0 +1,370 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/cli/src/fleet.rs
+//
+// gRPC fleet protocol (proto/agent.proto) and `dl-driver fleet run`
+//
+// `dl-driver serve --grpc-listen 0.0.0.0:8471` exposes the agent's runs (see
+// serve.rs) over gRPC as well as REST. `fleet run` is the client side: it fans
+// one config out to the agents listed in a file as ranks of a single run,
+//
+//   dl-driver fleet run --agents agents.txt --ranks-per-agent 2 -c config.yaml
+//
+// binds the launcher's coordination server (as `launch --hosts` does), starts
+// rank r on agent r / ranks_per_agent with --rank, --world-size and
+// --coord-endpoint, prints every rank's output as it streams in, writes the
+// results each rank's exit event carries to the results directory, and
+// aggregates them. When a rank fails, the other ranks' streams are dropped,
+// which makes their agents stop them.
+//
+// Agents files list one agent per line as host[:port] (default port 8471), with
+// the hosts file syntax of `launch --hosts`.
+//
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::net::ToSocketAddrs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinSet;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{debug, info, warn};
+
+use crate::launch::{rank_results_path, LaunchOptions, RankExit};
+use crate::serve::{self, Agent, Run};
+use dl_driver_core::net_coordination::CoordinationServer;
+
+pub mod proto {
+    tonic::include_proto!("dl_driver.agent.v1");
+}
+
+use proto::agent_client::AgentClient;
+use proto::agent_server::{Agent as AgentApi, AgentServer};
+use proto::run_event::Event;
+use proto::{AgentInfo, GetInfoRequest, LogLine, RunEvent, RunExit, StartRunRequest, StopRunReply, StopRunRequest};
+
+/// Default port of the gRPC agent service
+pub const DEFAULT_GRPC_PORT: u16 = 8471;
+
+/// gRPC URI of an agents file entry (host, host:port or a full URI)
+pub fn agent_endpoint(agent: &str) -> String {
+    if agent.contains("://") {
+        return agent.to_string();
+    }
+    let has_port = agent.rsplit_once(':').is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+    if has_port {
+        format!("http://{}", agent)
+    } else {
+        format!("http://{}:{}", agent, DEFAULT_GRPC_PORT)
+    }
+}
+
+/// Agent gRPC service over the runs of `serve`
+pub struct AgentService {
+    agent: Arc<Agent>,
+}
+
+impl AgentService {
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let Some(token) = self.agent.token() else {
+            return Ok(());
+        };
+        let presented = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if presented.is_some_and(|presented| presented.trim() == token) {
+            Ok(())
+        } else {
+            Err(Status::unauthenticated("Missing or wrong bearer token"))
+        }
+    }
+}
+
+/// gRPC status for a failed REST-style response
+fn status_of(response: serve::Response) -> Status {
+    let message = response.body["error"].as_str().unwrap_or("request failed").to_string();
+    match response.code {
+        400 => Status::invalid_argument(message),
+        403 => Status::permission_denied(message),
+        404 => Status::not_found(message),
+        409 => Status::failed_precondition(message),
+        _ => Status::internal(message),
+    }
+}
+
+/// Protobuf form of a run event; exit events carry the run's results JSON
+fn to_proto(run: &Run, run_id: &str, event: &Value) -> Option<RunEvent> {
+    let event_kind = match event["type"].as_str()? {
+        "log" => Event::Log(LogLine {
+            stream: event["stream"].as_str().unwrap_or_default().to_string(),
+            line: event["line"].as_str().unwrap_or_default().to_string(),
+        }),
+        "metrics" => Event::MetricsJson(event["metrics"].to_string()),
+        "exit" => Event::Exit(RunExit {
+            exit_code: event["exit_code"].as_i64().unwrap_or(1) as i32,
+            exit_status: event["exit_status"].as_str().unwrap_or_default().to_string(),
+            results_json: dl_driver_core::artifacts::read_to_string(&run.results_path()).unwrap_or_default(),
+        }),
+        _ => return None,
+    };
+    Some(RunEvent {
+        run_id: run_id.to_string(),
+        time_unix_s: event["time_unix_s"].as_f64().unwrap_or_default(),
+        event: Some(event_kind),
+    })
+}
+
+/// Send a run's events to a StartRun caller until it exits; a caller that goes away stops the run
+async fn forward_run(run: Arc<Run>, tx: mpsc::Sender<Result<RunEvent, Status>>) {
+    let run_id = run.status().id;
+    let (past, mut live) = run.subscribe();
+    let mut pending = VecDeque::from(past);
+    loop {
+        let event = match pending.pop_front() {
+            Some(event) => event,
+            None => tokio::select! {
+                received = live.recv() => match received {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("StartRun stream of {} skipped {} events", run_id, skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                // A quiet run would otherwise only notice at its next event
+                _ = tx.closed() => break,
+            },
+        };
+        let exited = event["type"] == "exit";
+        if let Some(event) = to_proto(&run, &run_id, &event) {
+            if tx.send(Ok(event)).await.is_err() {
+                break;
+            }
+        }
+        if exited {
+            return;
+        }
+    }
+    warn!("⚠️  StartRun caller of {} went away; stopping the run", run_id);
+    serve::stop_run(&run, false).await;
+}
+
+#[tonic::async_trait]
+impl AgentApi for AgentService {
+    async fn get_info(&self, request: Request<GetInfoRequest>) -> Result<Response<AgentInfo>, Status> {
+        self.authorize(&request)?;
+        Ok(Response::new(AgentInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            host: dl_driver_core::metrics::host_name(),
+            active_run: self.agent.active_run(),
+        }))
+    }
+
+    type StartRunStream = ReceiverStream<Result<RunEvent, Status>>;
+
+    async fn start_run(&self, request: Request<StartRunRequest>) -> Result<Response<Self::StartRunStream>, Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let group = Some(request.fleet_id).filter(|id| !id.is_empty());
+        let run = self.agent.submit(&request.config_yaml, request.run_args, group).map_err(status_of)?;
+        let started = serve::start_run(&self.agent, &run);
+        if started.code != 202 {
+            return Err(status_of(started));
+        }
+        let (tx, rx) = mpsc::channel(256);
+        tokio::spawn(forward_run(run, tx));
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn stop_run(&self, request: Request<StopRunRequest>) -> Result<Response<StopRunReply>, Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let run = self.agent.run(&request.run_id).ok_or_else(|| Status::not_found(format!("No run '{}'", request.run_id)))?;
+        let stopped = serve::stop_run(&run, request.force).await;
+        if stopped.code != 202 {
+            return Err(status_of(stopped));
+        }
+        Ok(Response::new(StopRunReply {
+            graceful: stopped.body["graceful"].as_bool().unwrap_or_default(),
+            terminate_after_s: stopped.body["terminate_after_s"].as_u64().unwrap_or_default(),
+        }))
+    }
+}
+
+/// Serve the gRPC agent protocol until the process is interrupted
+pub async fn serve_grpc(listen: String, agent: Arc<Agent>) -> Result<()> {
+    let addr = listen
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid gRPC listen address {}", listen))?
+        .next()
+        .with_context(|| format!("{} resolves to no address", listen))?;
+    info!("🛰️  gRPC agent service listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(AgentServer::new(AgentService { agent }))
+        .serve(addr)
+        .await
+        .with_context(|| format!("gRPC agent service on {} failed", addr))
+}
+
+/// Where `fleet run` starts ranks
+#[derive(Debug, Clone)]
+pub struct FleetOptions {
+    pub agents: Vec<String>,
+    pub ranks_per_agent: u32,
+    /// Address ranks use to reach this launcher (default: this host's name)
+    pub coord_host: Option<String>,
+    pub coord_port: u16,
+    pub token: Option<String>,
+}
+
+/// Attach the bearer token, if any, to a request
+fn with_token<T>(message: T, token: Option<&str>) -> Result<Request<T>> {
+    let mut request = Request::new(message);
+    if let Some(token) = token {
+        let value = format!("Bearer {}", token).parse().context("Token is not a valid header value")?;
+        request.metadata_mut().insert("authorization", value);
+    }
+    Ok(request)
+}
+
+/// Start one rank on an agent, print its output, and save the results its exit event carries
+async fn run_rank(agent: String, rank: u32, start: StartRunRequest, token: Option<String>, results: PathBuf) -> Result<RankExit> {
+    let endpoint = agent_endpoint(&agent);
+    let mut client = AgentClient::connect(endpoint.clone())
+        .await
+        .with_context(|| format!("Cannot reach agent {}", endpoint))?;
+    let mut events = client
+        .start_run(with_token(start, token.as_deref())?)
+        .await
+        .with_context(|| format!("Agent {} refused rank {}", agent, rank))?
+        .into_inner();
+
+    let prefix = format!("[{} rank {}]", agent, rank);
+    while let Some(event) = events.message().await.with_context(|| format!("Lost the stream of rank {} on {}", rank, agent))? {
+        match event.event {
+            Some(Event::Log(log)) if log.stream == "stderr" => eprintln!("{} {}", prefix, log.line),
+            Some(Event::Log(log)) => println!("{} {}", prefix, log.line),
+            Some(Event::MetricsJson(metrics)) => debug!("{} metrics {}", prefix, metrics),
+            Some(Event::Exit(exit)) => {
+                if exit.results_json.is_empty() {
+                    warn!("⚠️  Rank {} on {} returned no results", rank, agent);
+                } else {
+                    std::fs::write(&results, exit.results_json)
+                        .with_context(|| format!("Failed to write results of rank {} to {:?}", rank, results))?;
+                }
+                if exit.exit_code == 0 {
+                    info!("✅ Rank {} on {} finished", rank, agent);
+                } else {
+                    warn!("❌ Rank {} on {} exited with {} ({})", rank, agent, exit.exit_code, exit.exit_status);
+                }
+                return Ok(RankExit { rank, code: exit.exit_code });
+            }
+            None => {}
+        }
+    }
+    anyhow::bail!("Agent {} closed the stream of rank {} before it exited", agent, rank)
+}
+
+/// Run the config on every agent as ranks of one run and wait for them all
+///
+/// `opts.ranks` is replaced by agents × ranks per agent. Exits are in completion
+/// order, so the first failure comes first; ranks cancelled after it exit with -1.
+pub async fn fleet_run(opts: &LaunchOptions, fleet: &FleetOptions) -> Result<Vec<RankExit>> {
+    if fleet.agents.is_empty() || fleet.ranks_per_agent == 0 {
+        anyhow::bail!("A fleet run needs at least one agent and --ranks-per-agent of at least 1");
+    }
+    std::fs::create_dir_all(&opts.results_dir)
+        .with_context(|| format!("Failed to create results directory {:?}", opts.results_dir))?;
+    let config_yaml = std::fs::read_to_string(&opts.config).with_context(|| format!("Failed to read config {:?}", opts.config))?;
+    let world_size = fleet.agents.len() as u32 * fleet.ranks_per_agent;
+
+    let server = CoordinationServer::bind(&format!("0.0.0.0:{}", fleet.coord_port), world_size).await?;
+    let coord_host = fleet.coord_host.clone().unwrap_or_else(dl_driver_core::metrics::host_name);
+    let coord_endpoint = format!("{}:{}", coord_host, server.local_addr()?.port());
+    // Ranks of this fleet run may share an agent; other runs may not
+    let fleet_id = format!("fleet_{}_{}", dl_driver_core::metrics::host_name(), coord_endpoint);
+    let server = server.spawn();
+    info!("🚀 Starting {} ranks on {} agents (coordination {})", world_size, fleet.agents.len(), coord_endpoint);
+
+    let mut ranks = JoinSet::new();
+    let placement = fleet.agents.iter().flat_map(|agent| std::iter::repeat_n(agent, fleet.ranks_per_agent as usize));
+    for (rank, agent) in placement.enumerate() {
+        let rank = rank as u32;
+        let mut run_args = vec![
+            "--rank".to_string(),
+            rank.to_string(),
+            "--world-size".to_string(),
+            world_size.to_string(),
+            "--coord-endpoint".to_string(),
+            coord_endpoint.clone(),
+        ];
+        run_args.extend(opts.run_args.iter().cloned());
+        let start = StartRunRequest { config_yaml: config_yaml.clone(), run_args, fleet_id: fleet_id.clone() };
+        let results = rank_results_path(&opts.results_dir, rank);
+        let (agent, token) = (agent.clone(), fleet.token.clone());
+        ranks.spawn(async move {
+            let exit = run_rank(agent.clone(), rank, start, token, results).await;
+            exit.unwrap_or_else(|e| {
+                warn!("❌ Rank {} on {}: {:#}", rank, agent, e);
+                RankExit { rank, code: 1 }
+            })
+        });
+    }
+
+    let mut exits = Vec::with_capacity(world_size as usize);
+    while let Some(exit) = ranks.join_next().await {
+        let exit = exit.context("Rank task panicked")?;
+        exits.push(exit);
+        // The other ranks would wait at a barrier forever; dropping their streams stops them
+        if exit.code != 0 && !ranks.is_empty() {
+            warn!("⚠️  Stopping the {} remaining ranks", ranks.len());
+            ranks.shutdown().await;
+        }
+    }
+    let finished: Vec<u32> = exits.iter().map(|e| e.rank).collect();
+    exits.extend((0..world_size).filter(|rank| !finished.contains(rank)).map(|rank| RankExit { rank, code: -1 }));
+
+    // A failed rank never reaches the finish barrier, so the server would wait forever
+    if exits.iter().all(|e| e.code == 0) {
+        if let Ok(Err(e)) = server.await {
+            warn!("Coordination server error: {:#}", e);
+        }
+    } else {
+        server.abort();
+    }
+    Ok(exits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_endpoints_and_events() {
+        assert_eq!(agent_endpoint("node1"), "http://node1:8471");
+        assert_eq!(agent_endpoint("node1:9000"), "http://node1:9000");
+        assert_eq!(agent_endpoint("10.0.0.7"), "http://10.0.0.7:8471");
+        assert_eq!(agent_endpoint("https://agent.example.com:443"), "https://agent.example.com:443");
+
+        let run = Run::new("run_1_000".to_string(), PathBuf::from("/nonexistent"), Vec::new(), None);
+        let log = serde_json::json!({"type": "log", "stream": "stdout", "line": "epoch 1", "time_unix_s": 5.0});
+        let event = to_proto(&run, "run_1_000", &log).unwrap();
+        assert_eq!(event.time_unix_s, 5.0);
+        assert_eq!(event.event, Some(Event::Log(LogLine { stream: "stdout".to_string(), line: "epoch 1".to_string() })));
+
+        let exit = serde_json::json!({"type": "exit", "exit_code": 3, "exit_status": "au_fail"});
+        let Some(Event::Exit(exit)) = to_proto(&run, "run_1_000", &exit).unwrap().event else {
+            panic!("expected an exit event");
+        };
+        assert_eq!((exit.exit_code, exit.exit_status.as_str(), exit.results_json.as_str()), (3, "au_fail", ""));
+        assert!(to_proto(&run, "run_1_000", &serde_json::json!({"type": "lagged"})).is_none());
+
+        let status = status_of(serve::Response { code: 409, body: serde_json::json!({"error": "busy"}) });
+        assert_eq!((status.code(), status.message()), (tonic::Code::FailedPrecondition, "busy"));
+    }
+}
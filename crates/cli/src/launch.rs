@@ -0,0 +1,350 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/cli/src/launch.rs
+//
+// Multi-rank launcher: `dl-driver launch --ranks N -c config.yaml`
+//
+// Starts N `dl-driver run` child processes with consistent --rank,
+// --world-size and --coord-id flags, prefixes and streams their output, then
+// aggregates the per-rank results once every rank has exited.
+//
+// With `--hosts hosts.txt --ranks-per-host N`, ranks are started over SSH on
+// every listed host instead. They synchronize through a TCP barrier server
+// run by the launcher (--coord-endpoint), and their results files are copied
+// back with scp before aggregation. The config path and the dl-driver binary
+// must be valid on every host.
+//
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tracing::{info, warn};
+
+use dl_driver_core::net_coordination::CoordinationServer;
+use dl_driver_core::results_upload;
+
+/// Default TCP port of the launcher's coordination server
+pub const DEFAULT_COORD_PORT: u16 = 29500;
+
+/// What to launch and where rank results go
+#[derive(Debug, Clone)]
+pub struct LaunchOptions {
+    pub config: PathBuf,
+    pub ranks: u32,
+    pub results_dir: PathBuf,
+    /// Extra `run` arguments passed through to every rank
+    pub run_args: Vec<String>,
+    /// Verbosity forwarded to the ranks as -v flags
+    pub verbose: u8,
+}
+
+impl LaunchOptions {
+    /// Whether the ranks run with --strict-au, so aggregation enforces the global AU threshold too
+    pub fn strict_au(&self) -> bool {
+        self.run_args.iter().any(|arg| arg == "--strict-au")
+    }
+}
+
+/// How ranks synchronize with each other
+#[derive(Debug, Clone)]
+pub enum RankCoordination {
+    /// Shared memory on one host, keyed by a coordination id
+    Local(String),
+    /// The launcher's TCP barrier server at host:port
+    Network(String),
+}
+
+impl RankCoordination {
+    fn args(&self) -> [String; 2] {
+        match self {
+            Self::Local(id) => ["--coord-id".to_string(), id.clone()],
+            Self::Network(endpoint) => ["--coord-endpoint".to_string(), endpoint.clone()],
+        }
+    }
+}
+
+/// Where and how to start ranks over SSH
+#[derive(Debug, Clone)]
+pub struct SshOptions {
+    pub hosts: Vec<String>,
+    pub ranks_per_host: u32,
+    /// Address ranks use to reach the launcher (default: this host's name)
+    pub coord_host: Option<String>,
+    pub coord_port: u16,
+    /// dl-driver executable on the remote hosts
+    pub remote_exe: String,
+    /// Remote directory for rank results (default: /tmp/dl-driver-results/<launch id>)
+    pub remote_results_dir: Option<String>,
+    /// Extra `-o` options for ssh and scp
+    pub ssh_options: Vec<String>,
+}
+
+/// Exit status of one rank process
+#[derive(Debug, Clone, Copy)]
+pub struct RankExit {
+    pub rank: u32,
+    /// Process exit code (-1 when killed by a signal)
+    pub code: i32,
+}
+
+/// Combined exit code: 0 when every rank succeeded, else the first failing rank's code
+pub fn combined_exit_code(exits: &[RankExit]) -> i32 {
+    exits.iter().find(|e| e.code != 0).map_or(0, |e| if e.code > 0 { e.code } else { 1 })
+}
+
+/// Per-rank results file inside the results directory
+pub fn rank_results_path(results_dir: &Path, rank: u32) -> PathBuf {
+    results_dir.join(results_upload::rank_results_name(rank))
+}
+
+/// Glob matching every rank results file in the results directory
+pub fn rank_results_glob(results_dir: &Path) -> String {
+    format!("{}/rank_*{}", results_dir.display(), results_upload::RESULTS_SUFFIX)
+}
+
+/// `dl-driver` arguments for one rank
+pub fn rank_args(opts: &LaunchOptions, rank: u32, coordination: &RankCoordination, results: &Path) -> Vec<String> {
+    let mut args = Vec::new();
+    if opts.verbose > 0 {
+        args.push(format!("-{}", "v".repeat(opts.verbose as usize)));
+    }
+    args.extend([
+        "run".to_string(),
+        "--config".to_string(),
+        opts.config.display().to_string(),
+        "--rank".to_string(),
+        rank.to_string(),
+        "--world-size".to_string(),
+        opts.ranks.to_string(),
+    ]);
+    args.extend(coordination.args());
+    args.extend(["--results".to_string(), results.display().to_string()]);
+    args.extend(opts.run_args.iter().cloned());
+    args
+}
+
+/// Print each line of a child's output with a rank prefix
+async fn forward_lines<R: AsyncRead + Unpin>(reader: R, prefix: String, to_stderr: bool) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if to_stderr {
+            eprintln!("{} {}", prefix, line);
+        } else {
+            println!("{} {}", prefix, line);
+        }
+    }
+}
+
+/// Host names from a hosts file: one per line, blank lines and `#` comments ignored
+pub fn parse_hosts(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn read_hosts(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read hosts file {:?}", path))?;
+    let hosts = parse_hosts(&contents);
+    if hosts.is_empty() {
+        anyhow::bail!("Hosts file {:?} lists no hosts", path);
+    }
+    Ok(hosts)
+}
+
+/// Quote one argument for the remote POSIX shell
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Remote shell command that creates the results directory and starts one rank
+pub fn remote_command(remote_exe: &str, remote_dir: &str, args: &[String]) -> String {
+    let mut command = format!("mkdir -p {} && {}", shell_quote(remote_dir), shell_quote(remote_exe));
+    for arg in args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    command
+}
+
+/// Unique per launch so concurrent launches of the same config do not share coordination state
+fn launch_id() -> String {
+    format!("dlio_launch_{}", std::process::id())
+}
+
+/// Run all ranks on this host and wait for them to exit
+pub async fn launch_local(opts: &LaunchOptions) -> Result<Vec<RankExit>> {
+    if opts.ranks == 0 {
+        anyhow::bail!("--ranks must be at least 1");
+    }
+    std::fs::create_dir_all(&opts.results_dir)
+        .with_context(|| format!("Failed to create results directory {:?}", opts.results_dir))?;
+
+    let exe = std::env::current_exe().context("Cannot locate the dl-driver executable")?;
+    let coordination = RankCoordination::Local(launch_id());
+    info!("🚀 Launching {} ranks ({:?}) → {:?}", opts.ranks, coordination, opts.results_dir);
+
+    let commands = (0..opts.ranks)
+        .map(|rank| {
+            let mut command = Command::new(&exe);
+            command.args(rank_args(opts, rank, &coordination, &rank_results_path(&opts.results_dir, rank)));
+            (rank, format!("[rank {}]", rank), command)
+        })
+        .collect();
+    run_ranks(commands).await
+}
+
+/// Run ranks over SSH on every host, then copy their results into the results directory
+///
+/// `opts.ranks` is replaced by hosts × ranks per host; rank r runs on host r / ranks_per_host.
+pub async fn launch_ssh(opts: &LaunchOptions, ssh: &SshOptions) -> Result<Vec<RankExit>> {
+    if ssh.hosts.is_empty() || ssh.ranks_per_host == 0 {
+        anyhow::bail!("SSH launch needs at least one host and --ranks-per-host of at least 1");
+    }
+    std::fs::create_dir_all(&opts.results_dir)
+        .with_context(|| format!("Failed to create results directory {:?}", opts.results_dir))?;
+    let opts = LaunchOptions { ranks: ssh.hosts.len() as u32 * ssh.ranks_per_host, ..opts.clone() };
+
+    let server = CoordinationServer::bind(&format!("0.0.0.0:{}", ssh.coord_port), opts.ranks).await?;
+    let coord_host = ssh.coord_host.clone().unwrap_or_else(dl_driver_core::metrics::host_name);
+    let coordination = RankCoordination::Network(format!("{}:{}", coord_host, server.local_addr()?.port()));
+    let server = server.spawn();
+
+    let remote_dir = ssh.remote_results_dir.clone().unwrap_or_else(|| format!("/tmp/dl-driver-results/{}", launch_id()));
+    info!(
+        "🚀 Launching {} ranks on {} hosts ({:?}) → {}",
+        opts.ranks, ssh.hosts.len(), coordination, remote_dir
+    );
+
+    let placement: Vec<(u32, &String)> = ssh
+        .hosts
+        .iter()
+        .flat_map(|host| std::iter::repeat_n(host, ssh.ranks_per_host as usize))
+        .enumerate()
+        .map(|(rank, host)| (rank as u32, host))
+        .collect();
+
+    let commands = placement
+        .iter()
+        .map(|&(rank, host)| {
+            let results = Path::new(&remote_dir).join(results_upload::rank_results_name(rank));
+            let args = rank_args(&opts, rank, &coordination, &results);
+            let mut command = ssh_command("ssh", ssh);
+            command.arg(host).arg(remote_command(&ssh.remote_exe, &remote_dir, &args));
+            (rank, format!("[{} rank {}]", host, rank), command)
+        })
+        .collect();
+    let exits = run_ranks(commands).await?;
+
+    // A failed rank never reaches the finish barrier, so the server would wait forever
+    if exits.iter().all(|e| e.code == 0) {
+        if let Ok(Err(e)) = server.await {
+            warn!("Coordination server error: {:#}", e);
+        }
+    } else {
+        server.abort();
+    }
+
+    for &(rank, host) in &placement {
+        let remote = format!("{}:{}/{}", host, remote_dir, results_upload::rank_results_name(rank));
+        let status = ssh_command("scp", ssh)
+            .arg(&remote)
+            .arg(rank_results_path(&opts.results_dir, rank))
+            .stdout(Stdio::null())
+            .status()
+            .await
+            .context("Failed to run scp")?;
+        if !status.success() {
+            warn!("⚠️  Could not copy results of rank {} from {}", rank, remote);
+        }
+    }
+    Ok(exits)
+}
+
+/// ssh or scp in batch mode with the configured `-o` options
+fn ssh_command(program: &str, ssh: &SshOptions) -> Command {
+    let mut command = Command::new(program);
+    command.args(["-o", "BatchMode=yes"]);
+    for option in &ssh.ssh_options {
+        command.arg("-o").arg(option);
+    }
+    command
+}
+
+/// Start every (rank, output prefix, command), stream their output, and wait for them all
+async fn run_ranks(commands: Vec<(u32, String, Command)>) -> Result<Vec<RankExit>> {
+    let mut children = Vec::with_capacity(commands.len());
+    for (rank, prefix, mut command) in commands {
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start rank {}", rank))?;
+
+        let stdout = tokio::spawn(forward_lines(child.stdout.take().expect("piped stdout"), prefix.clone(), false));
+        let stderr = tokio::spawn(forward_lines(child.stderr.take().expect("piped stderr"), prefix, true));
+        children.push((rank, child, stdout, stderr));
+    }
+
+    let mut exits = Vec::with_capacity(children.len());
+    for (rank, mut child, stdout, stderr) in children {
+        let status = child.wait().await.with_context(|| format!("Failed to wait for rank {}", rank))?;
+        let _ = tokio::join!(stdout, stderr);
+        let code = status.code().unwrap_or(-1);
+        if code == 0 {
+            info!("✅ Rank {} finished", rank);
+        } else {
+            warn!("❌ Rank {} exited with {}", rank, status);
+        }
+        exits.push(RankExit { rank, code });
+    }
+    Ok(exits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_args_and_exit_code() {
+        let opts = LaunchOptions {
+            config: PathBuf::from("cfg.yaml"),
+            ranks: 4,
+            results_dir: PathBuf::from("/tmp/res"),
+            run_args: vec!["--strict-au".to_string()],
+            verbose: 2,
+        };
+        let results = rank_results_path(&opts.results_dir, 2);
+        let args = rank_args(&opts, 2, &RankCoordination::Local("dlio_launch_1".to_string()), &results);
+        assert_eq!(args[..2], ["-vv", "run"]);
+        assert!(args.windows(2).any(|w| w == ["--rank", "2"]));
+        assert!(args.windows(2).any(|w| w == ["--coord-id", "dlio_launch_1"]));
+        assert!(args.windows(2).any(|w| w == ["--world-size", "4"]));
+        assert!(args.windows(2).any(|w| w == ["--results", "/tmp/res/rank_0002_results.json"]));
+        assert_eq!(args.last().unwrap(), "--strict-au");
+        assert!(opts.strict_au());
+        assert!(!LaunchOptions { run_args: Vec::new(), ..opts.clone() }.strict_au());
+
+        let ok = RankExit { rank: 0, code: 0 };
+        assert_eq!(combined_exit_code(&[ok, ok]), 0);
+        assert_eq!(combined_exit_code(&[ok, RankExit { rank: 1, code: 3 }, RankExit { rank: 2, code: 1 }]), 3);
+        assert_eq!(combined_exit_code(&[RankExit { rank: 0, code: -1 }]), 1);
+    }
+
+    #[test]
+    fn test_hosts_file_and_remote_command() {
+        let hosts = parse_hosts("# cluster\nnode1\n\n  node2  # gpu box\n");
+        assert_eq!(hosts, vec!["node1", "node2"]);
+
+        let args = vec!["run".to_string(), "--config".to_string(), "it's.yaml".to_string()];
+        assert_eq!(
+            remote_command("dl-driver", "/tmp/res", &args),
+            "mkdir -p '/tmp/res' && 'dl-driver' 'run' '--config' 'it'\\''s.yaml'"
+        );
+    }
+}
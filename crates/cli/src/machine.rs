@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/cli/src/machine.rs
+//
+// `--quiet` machine mode and signal handling for the exit-code contract
+// (see dl_driver_core::exit_status)
+//
+// With --quiet, logging is off and everything commands print to stdout is
+// discarded; stdout carries exactly one JSON document when the command ends:
+//
+//   {"status": "au_fail", "exit_code": 3, "error": "...", "results": {...}}
+//
+// `results` is the command's results JSON (rank results for `run`, the
+// aggregated results for `aggregate`, the report for the other commands), or
+// null if the command failed before producing one.
+//
+use anyhow::{Context, Result};
+use dl_driver_core::exit_status::ExitStatus;
+use std::fs::File;
+use std::io::Write;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Results JSON of the running command, reported by `--quiet`
+static RESULTS: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Record the command's results JSON for the final `--quiet` document
+pub fn set_results(results: serde_json::Value) {
+    *RESULTS.lock().unwrap() = Some(results);
+}
+
+/// Whether results must be built even without --results (`--quiet` reports them)
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// The real stdout, kept aside while fd 1 points at /dev/null
+pub struct QuietStdout {
+    stdout: File,
+}
+
+impl QuietStdout {
+    /// Silence fd 1 (including output from the core library) and keep a handle to the real stdout
+    pub fn engage() -> Result<Self> {
+        std::io::stdout().flush()?;
+        let null = std::fs::OpenOptions::new().write(true).open("/dev/null").context("Cannot open /dev/null")?;
+        // SAFETY: dup/dup2 on valid descriptors; the duplicate is owned by the returned File
+        let stdout = unsafe {
+            let saved = libc::dup(1);
+            if saved < 0 || libc::dup2(null.as_raw_fd(), 1) < 0 {
+                return Err(std::io::Error::last_os_error()).context("Cannot redirect stdout for --quiet");
+            }
+            File::from_raw_fd(saved)
+        };
+        QUIET.store(true, Ordering::Relaxed);
+        Ok(Self { stdout })
+    }
+
+    /// Print the final status document
+    pub fn finish(mut self, status: ExitStatus, error: Option<&anyhow::Error>) -> Result<()> {
+        let document = serde_json::json!({
+            "status": status.name(),
+            "exit_code": status.code(),
+            "error": error.map(|e| format!("{:#}", e)),
+            "results": RESULTS.lock().unwrap().take(),
+        });
+        writeln!(self.stdout, "{}", serde_json::to_string_pretty(&document)?)?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// Resolves on SIGINT or SIGTERM
+pub async fn interrupted() {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        },
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
@@ -4,10 +4,20 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use dl_driver_core::DlioConfig;
-use dl_driver_core::plugins::PluginManager;
+use dl_driver_core::dlio_compat::LoaderConfig;
+use dl_driver_core::exit_status::ExitStatus;
+use dl_driver_core::units;
+use dl_driver_core::artifacts;
 use tracing::{info, error, debug, warn};
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+
+// gRPC agent protocol and `fleet run`
+mod fleet;
+// Local multi-rank launcher
+mod launch;
+// `--quiet` machine mode and signal handling for the exit-code contract
+mod machine;
+// REST agent for remote orchestration
+mod serve;
 
 /// dl-driver – Unified DLIO execution engine with optional MLPerf compliance mode
 #[derive(Parser, Debug)]
@@ -17,6 +27,10 @@ struct Args {
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
 
+    /// Machine mode: no logs, only a final status JSON on stdout (exit codes in README)
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -86,6 +100,10 @@ enum Commands {
         #[arg(long)]
         use_real_gpus: bool,
 
+        /// Accelerators each rank stands for, e.g. 8 for one rank per 8-GPU node (default: train.gpus_per_rank, else 1)
+        #[arg(long)]
+        gpus_per_rank: Option<u32>,
+
         // === Multi-rank scaling options ===
         /// Read file list from specified file (one path per line)
         #[arg(long)]
@@ -103,6 +121,27 @@ enum Commands {
         #[arg(long)]
         start_at_epoch: Option<u64>,
 
+        /// Start N seconds after all ranks register; rank 0 broadcasts the gate to every rank
+        #[arg(long, conflicts_with = "start_at_epoch")]
+        start_in: Option<u64>,
+
+        /// Run the training phase N times on the same dataset and aggregate the metrics
+        #[arg(long, default_value_t = 1)]
+        repeats: u32,
+
+        /// How repeated runs are combined: mean or median
+        #[arg(long, default_value = "mean")]
+        aggregate: String,
+
+        /// Drop repetitions more than 3 scaled MADs from the median before aggregating
+        #[arg(long)]
+        discard_outliers: bool,
+
+        /// Rerun training once per shuffle seed (e.g. 1..10 or 3,7,42), changing nothing else, and report
+        /// the variance due to access order; with --repeats each seed runs N times
+        #[arg(long)]
+        seed_sweep: Option<String>,
+
         /// Sharding strategy: interleaved, contiguous, or hash
         #[arg(long, default_value = "interleaved")]
         shard_strategy: String,
@@ -110,6 +149,151 @@ enum Commands {
         /// Output JSON results to specified file
         #[arg(long)]
         results: Option<std::path::PathBuf>,
+
+        /// Write the run manifest (config hashes, versions, environment, seeds) to this file
+        /// With several ranks, `{rank}` in the name is replaced by the rank, else `_rank_NNNNN` is added
+        #[arg(long)]
+        manifest: Option<std::path::PathBuf>,
+
+        /// Results JSON from a mem:// baseline run; AU is reported relative to it
+        #[arg(long)]
+        au_baseline: Option<std::path::PathBuf>,
+
+        /// Results JSON of a reference run (e.g. local NVMe); "derived" reports efficiency against it
+        #[arg(long)]
+        baseline: Option<std::path::PathBuf>,
+
+        /// Record per-batch arrival timing to this file for later `replay`
+        #[arg(long)]
+        record_arrivals: Option<std::path::PathBuf>,
+
+        /// Log every training storage operation (op, key, size, start/end, rank) to this file in
+        /// s3dlio's op-log layout: .tsv or JSON lines, .zst compressed (e.g. ops.jsonl.zst).
+        /// With several ranks, `{rank}` in the name is replaced by the rank, else `_rank_NNNNN` is added
+        #[arg(long)]
+        record_oplog: Option<std::path::PathBuf>,
+
+        /// zstd-compress run artifacts to <path>.zst: traces (arrival traces, op logs), all (also results) or none
+        #[arg(long, default_value = "traces")]
+        compress: String,
+
+        /// Results timeseries granularity: off, epoch, step or step:N (every Nth step; step rows are capped)
+        #[arg(long, default_value = "epoch")]
+        timeline: String,
+
+        /// In builds without HDF5 support, run hdf5 configs with npz files instead of failing
+        #[arg(long)]
+        hdf5_as_npz: bool,
+
+        /// Upload this rank's results JSON (and arrival trace) under this URI, e.g. s3://bucket/results/run_id/
+        #[arg(long)]
+        results_uri: Option<String>,
+
+        /// Multi-rank coordination group id (default: derived from config name and world size)
+        #[arg(long)]
+        coord_id: Option<String>,
+
+        /// Coordinate ranks across hosts through a launcher's TCP server (host:port)
+        #[arg(long)]
+        coord_endpoint: Option<String>,
+
+        /// Coordination backend: shm://<id> (one host) or tcp://<host>:<port> (rank 0 serves on <port>)
+        #[arg(long, conflicts_with_all = ["coord_id", "coord_endpoint"])]
+        coord: Option<String>,
+
+        /// Unix socket accepting live commands (pause, resume, set-target-throughput, dump-metrics-now)
+        #[arg(long)]
+        control_socket: Option<std::path::PathBuf>,
+
+        /// Resolve relative data and checkpoint folders against this directory instead of each rank's working directory
+        #[arg(long)]
+        workdir: Option<std::path::PathBuf>,
+
+        /// Take over the data folder's lock (dataset.lock) even if another run holds it
+        #[arg(long)]
+        force_unlock: bool,
+    },
+    /// Launch N local rank processes of `run` (or ranks on SSH hosts) and aggregate their results
+    Launch {
+        /// Path to a DLIO YAML config file (must exist at the same path on every host)
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Number of local rank processes to start
+        #[arg(long, required_unless_present = "hosts", conflicts_with = "hosts")]
+        ranks: Option<u32>,
+
+        /// Hosts file (one host per line) to start ranks on over SSH
+        #[arg(long, requires = "ranks_per_host")]
+        hosts: Option<std::path::PathBuf>,
+
+        /// Rank processes per host with --hosts
+        #[arg(long)]
+        ranks_per_host: Option<u32>,
+
+        /// Address ranks use to reach this launcher (default: this host's name)
+        #[arg(long)]
+        coord_host: Option<String>,
+
+        /// TCP port of the launcher's coordination server
+        #[arg(long, default_value_t = launch::DEFAULT_COORD_PORT)]
+        coord_port: u16,
+
+        /// dl-driver executable on the remote hosts
+        #[arg(long, default_value = "dl-driver")]
+        remote_exe: String,
+
+        /// Remote directory for rank results (default: /tmp/dl-driver-results/<launch id>)
+        #[arg(long)]
+        remote_results_dir: Option<String>,
+
+        /// Extra ssh/scp option, passed as `-o <option>` (repeatable)
+        #[arg(long = "ssh-option")]
+        ssh_options: Vec<String>,
+
+        /// Directory for per-rank results and the aggregated results
+        #[arg(long, default_value = "dl-driver-results")]
+        results_dir: std::path::PathBuf,
+
+        /// Additional `run` arguments for every rank, after `--`
+        #[arg(last = true)]
+        run_args: Vec<String>,
+    },
+    /// Serve a REST API to submit configs, start/stop runs, stream progress and fetch results
+    Serve {
+        /// Address to listen on (host:port)
+        #[arg(long, default_value = serve::DEFAULT_LISTEN)]
+        listen: String,
+
+        /// Directory for submitted configs, results and control sockets
+        #[arg(long, default_value = "dl-driver-agent")]
+        state_dir: std::path::PathBuf,
+
+        /// Bearer token required on every request except /health (default: $DL_DRIVER_SERVE_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Seconds between metrics snapshots on a run's event stream (0 = none)
+        #[arg(long, default_value_t = 10)]
+        metrics_interval: u64,
+
+        /// Also serve the gRPC agent protocol for `fleet run` on this address (e.g. 0.0.0.0:8471)
+        #[arg(long)]
+        grpc_listen: Option<String>,
+
+        /// Accept configs whose clone_benchmark or tier_migration hooks run shell commands
+        #[arg(long)]
+        allow_shell_hooks: bool,
+    },
+    /// Results database: add runs (annotated with version/config changes) and show their history
+    History {
+        #[command(subcommand)]
+        action: HistoryCommands,
+    },
+    /// Drive `serve --grpc-listen` agents on many nodes
+    Fleet {
+        #[command(subcommand)]
+        action: FleetCommands,
     },
     /// Validate a DLIO config without running it
     Validate {
@@ -120,6 +304,20 @@ enum Commands {
         /// Convert YAML to JSON and print it
         #[arg(long)]
         to_json: bool,
+
+        /// Write, read and delete a canary object in the data and checkpoint folders
+        #[arg(long)]
+        probe: bool,
+    },
+    /// Check the network path to the config's storage endpoint (proxy, IP family, reachability)
+    Doctor {
+        /// Path to a DLIO YAML config file (uses `storage.network` and the data folder)
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Output JSON check results to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
     },
     /// Generate synthetic dataset from DLIO config
     Generate {
@@ -131,13 +329,18 @@ enum Commands {
         #[arg(long)]
         verbose: bool,
 
-        /// Skip generation if data folder already exists
+        /// Resume: only write files that are missing or have the wrong size
         #[arg(long)]
         skip_existing: bool,
+
+        /// Take over the data folder's lock (dataset.lock) even if another run holds it
+        #[arg(long)]
+        force_unlock: bool,
     },
     /// Aggregate results from multiple rank JSON files
     Aggregate {
-        /// Pattern or paths to rank result files (e.g., "/results/rank*.json")
+        /// Pattern or paths to rank result files (e.g., "/results/rank*.json"),
+        /// or a `--results-uri` prefix (e.g., "s3://bucket/results/run_id/")
         #[arg(short, long)]
         inputs: String,
 
@@ -152,9 +355,381 @@ enum Commands {
         /// Expected metric AU threshold (default from first rank config)
         #[arg(long)]
         au_threshold: Option<f64>,
+
+        /// Wait until this many rank results are present before aggregating
+        #[arg(long)]
+        expect_ranks: Option<usize>,
+
+        /// How long --expect-ranks waits for rank results (e.g. 90s, 10m, 1h)
+        #[arg(long, default_value = "10m", value_parser = parse_wait_duration)]
+        wait_timeout: std::time::Duration,
+
+        /// Results JSON of a reference run (e.g. local NVMe); "derived" reports efficiency against it
+        #[arg(long)]
+        baseline: Option<std::path::PathBuf>,
+
+        /// Also write per-rank rows and a global summary row as CSV
+        #[arg(long)]
+        csv: Option<std::path::PathBuf>,
+    },
+    /// Repeatedly overwrite a working set of objects (checkpoint-style churn)
+    Overwrite {
+        /// Path to a DLIO YAML config file (uses the `overwrite` section)
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Output JSON results to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Probe storage and suggest reader settings (batch_size, read_threads, prefetch) for the AU target
+    Advise {
+        /// Path to a DLIO YAML config file (dataset must exist; uses train.computation_time and metric.au)
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Objects read at each probed concurrency
+        #[arg(long, default_value_t = 64)]
+        probe_objects: usize,
+
+        /// Highest concurrency probed (levels double from 1)
+        #[arg(long, default_value_t = 64)]
+        max_threads: usize,
+
+        /// Output JSON advice to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Estimate how well the dataset content deduplicates and compresses
+    AnalyzeData {
+        /// Path to a DLIO YAML config file
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Files analyzed (the first N file indices)
+        #[arg(long, default_value_t = 32)]
+        files: usize,
+
+        /// Average dedup chunk size in KiB
+        #[arg(long, default_value_t = 8)]
+        chunk_kib: usize,
+
+        /// Read existing objects from the data folder instead of generating them in memory
+        #[arg(long)]
+        from_storage: bool,
+
+        /// Output JSON analysis to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Clone the dataset prefix via a hook, then train one epoch from the clone
+    CloneBench {
+        /// Path to a DLIO YAML config file (uses the `clone_benchmark` section)
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Output JSON results to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Replay a recorded batch arrival schedule, or re-issue an s3dlio op-log's GETs/PUTs
+    Replay {
+        /// Arrival trace written by `run --record-arrivals`
+        #[arg(short, long, required_unless_present = "op_log", conflicts_with = "op_log")]
+        trace: Option<std::path::PathBuf>,
+
+        /// Sleep for each batch's recorded compute time instead of a no-op
+        #[arg(long, conflicts_with = "op_log")]
+        compute: bool,
+
+        /// s3dlio operation log (JSONL or TSV, optionally .zst) to re-issue in recorded order
+        #[arg(long)]
+        op_log: Option<std::path::PathBuf>,
+
+        /// URI prefix to replay against, replacing the logged URIs' common prefix
+        #[arg(long, requires = "op_log")]
+        target: Option<String>,
+
+        /// Issue operations on the recorded schedule at this pace (1.0 = as logged);
+        /// without it operations run one at a time, back to back
+        #[arg(long, requires = "op_log")]
+        speed: Option<f64>,
+
+        /// Output JSON results to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Run the reference DLIO and dl-driver on the same config and compare metrics
+    Crosscheck {
+        /// Path to a DLIO YAML config file
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Path to the reference `dlio_benchmark` executable
+        #[arg(long)]
+        dlio_bin: std::path::PathBuf,
+
+        /// Relative divergence allowed per metric (0.10 = 10%)
+        #[arg(long, default_value_t = 0.10)]
+        tolerance: f64,
+
+        /// Working directory for DLIO configs and outputs
+        #[arg(long, default_value = "crosscheck_out")]
+        output_dir: std::path::PathBuf,
+
+        /// Output JSON comparison report to specified file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Run every config in a directory (or suite manifest) and write a combined report
+    Suite {
+        /// Directory of DLIO YAML configs, or a suite manifest file
+        #[arg(long)]
+        configs: std::path::PathBuf,
+
+        /// Combined suite report JSON
+        #[arg(long, default_value = "suite.json")]
+        output: std::path::PathBuf,
+
+        /// Directory for staged configs and per-run results
+        #[arg(long, default_value = "suite_out")]
+        work_dir: std::path::PathBuf,
+
+        /// Delete each generated dataset after its runs (also settable in the manifest)
+        #[arg(long)]
+        cleanup: bool,
+    },
+    /// Post-process dl-driver results files
+    Report {
+        #[command(subcommand)]
+        action: ReportCommands,
+    },
+    /// Storage micro-benchmarks: put/get/stat/list/delete at fixed sizes and queue depths (fio-style output)
+    Micro {
+        /// Target URI prefix (file://, direct://, s3://, az://, gs://); jobs write under <uri>/micro_<size>_qd<n>/
+        #[arg(long)]
+        uri: String,
+
+        /// Operations to run (comma-separated, run in the order put, get, stat, list, delete)
+        #[arg(long, value_delimiter = ',', default_value = "put,get,stat,list,delete")]
+        ops: Vec<dl_driver_core::micro::MicroOp>,
+
+        /// Object sizes (comma-separated; e.g. 4k,64k,1m in binary units)
+        #[arg(long, value_delimiter = ',', default_value = "4k,1m", value_parser = dl_driver_core::micro::parse_size)]
+        sizes: Vec<usize>,
+
+        /// Concurrent requests per job (comma-separated)
+        #[arg(long, value_delimiter = ',', default_value = "1,16")]
+        queue_depths: Vec<usize>,
+
+        /// Length of each timed phase (e.g. 10s, 1m)
+        #[arg(long, default_value = "10s", value_parser = parse_wait_duration)]
+        duration: std::time::Duration,
+
+        /// Write the JSON report to this file
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+    /// Standalone storage qualification benchmarks (no training I/O)
+    Bench {
+        #[command(subcommand)]
+        action: BenchCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BenchCommands {
+    /// Synchronized checkpoint writes from every rank at a fixed cadence (`checkpoint_storm` section)
+    Checkpoint {
+        /// Path to a DLIO YAML config file
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Rank ID for multi-process execution (0-based)
+        #[arg(long)]
+        rank: Option<u32>,
+
+        /// Total number of ranks in world
+        #[arg(long)]
+        world_size: Option<u32>,
+
+        /// Multi-rank coordination group id (default: derived from config name and world size)
+        #[arg(long)]
+        coord_id: Option<String>,
+
+        /// Coordinate ranks across hosts through a launcher's TCP server (host:port)
+        #[arg(long)]
+        coord_endpoint: Option<String>,
+
+        /// Output this rank's JSON results to the specified file (`aggregate` combines them)
+        #[arg(long)]
+        results: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommands {
+    /// Add one run to the database from its rank results
+    Add {
+        /// Results database (JSON lines; created if missing)
+        #[arg(long, default_value = "dl-driver-history.jsonl")]
+        db: std::path::PathBuf,
+
+        /// Pattern or paths to the run's rank result files (e.g., "results/rank_*_results.json")
+        #[arg(short, long)]
+        inputs: String,
+    },
+    /// Print runs with throughput changes and what changed since the previous run of each config
+    Show {
+        /// Results database (JSON lines)
+        #[arg(long, default_value = "dl-driver-history.jsonl")]
+        db: std::path::PathBuf,
+
+        /// Only runs of this config (file name, e.g. unet3d.yaml)
+        #[arg(long)]
+        config: Option<String>,
+
+        /// Show at most the N latest runs
+        #[arg(long)]
+        last: Option<usize>,
+
+        /// Print the records as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum FleetCommands {
+    /// Run a config on every agent as ranks of one run, collect their results and aggregate them
+    Run {
+        /// Path to a DLIO YAML config file (sent to the agents)
+        #[arg(short, long)]
+        config: std::path::PathBuf,
+
+        /// Agents file: one host[:port] per line (default port 8471), `#` comments allowed
+        #[arg(long)]
+        agents: std::path::PathBuf,
+
+        /// Rank processes per agent
+        #[arg(long, default_value_t = 1)]
+        ranks_per_agent: u32,
+
+        /// Address ranks use to reach this launcher (default: this host's name)
+        #[arg(long)]
+        coord_host: Option<String>,
+
+        /// TCP port of the launcher's coordination server
+        #[arg(long, default_value_t = launch::DEFAULT_COORD_PORT)]
+        coord_port: u16,
+
+        /// Bearer token of the agents (default: $DL_DRIVER_SERVE_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Directory for per-rank results and the aggregated results
+        #[arg(long, default_value = "dl-driver-results")]
+        results_dir: std::path::PathBuf,
+
+        /// Additional `run` arguments for every rank, after `--`
+        #[arg(last = true)]
+        run_args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ReportCommands {
+    /// Convert rank results JSON into another reporting format
+    Convert {
+        /// Pattern or paths to rank result files (e.g., "/results/rank*.json")
+        #[arg(short, long)]
+        inputs: String,
+
+        /// Target format (mlperf-storage)
+        #[arg(long)]
+        to: String,
+
+        /// Output directory for the converted files
+        #[arg(short, long)]
+        output: std::path::PathBuf,
+    },
+    /// Copy results JSON for sharing, optionally anonymized
+    Export {
+        /// Pattern or paths to results files (rank, aggregated or any dl-driver report)
+        #[arg(short, long)]
+        inputs: String,
+
+        /// Output directory for the exported files
+        #[arg(short, long)]
+        output: std::path::PathBuf,
+
+        /// Replace hostnames, bucket names, internal paths and IPs with pseudonyms; drop credentials
+        #[arg(long)]
+        redact: bool,
+
+        /// Write the pseudonym -> original table here (keep it private)
+        #[arg(long, requires = "redact")]
+        mapping: Option<std::path::PathBuf>,
+    },
+    /// Project aggregate throughput for N clients from single-host saturation results
+    Project {
+        /// Pattern or paths to single-host results at increasing concurrency
+        /// (rank results, or aggregated results of N local ranks)
+        #[arg(long)]
+        single_host: String,
+
+        /// Pattern or paths to measured multi-client aggregated results to check
+        #[arg(long)]
+        measured: Option<String>,
+
+        /// Client counts to project (comma-separated)
+        #[arg(long, value_delimiter = ',', default_value = "1,2,4,8,16,32")]
+        clients: Vec<usize>,
+
+        /// Cross-client contention coefficient (default: the single-host USL σ)
+        #[arg(long)]
+        contention: Option<f64>,
+
+        /// Storage-side throughput ceiling in GiB/s
+        #[arg(long)]
+        storage_limit_gib_s: Option<f64>,
+
+        /// Relative deviation from the projection that is flagged (0.15 = 15%)
+        #[arg(long, default_value_t = 0.15)]
+        tolerance: f64,
+
+        /// Output JSON report to specified file
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Summarize a run for code review (markdown, JSON, badge) and upload it for CI to post
+    Publish {
+        /// Pattern, paths or --results-uri prefix of the run's rank results
+        #[arg(short, long)]
+        inputs: String,
+
+        /// Rank results of the base run to compare against (e.g. the PR's target branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Object-store prefix to upload summary.json, summary.md and badge.json to
+        /// (default: $DL_DRIVER_PUBLISH_URI; without either nothing is uploaded)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Badge and heading label, e.g. "PR #123"
+        #[arg(long, default_value = "dl-driver")]
+        label: String,
+
+        /// Also write the three files to this local directory
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
     },
 }#[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    // Reference point for the time-to-first-batch breakdown
+    dl_driver_core::startup::process_start();
+
     // Load environment variables from .env file early for S3/Azure credentials
     dotenvy::dotenv().ok(); // Ignore errors if .env doesn't exist
 
@@ -162,6 +737,7 @@ async fn main() -> Result<()> {
 
     // Initialize logging with verbosity levels
     let (dl_driver_level, s3dlio_level) = match args.verbose {
+        _ if args.quiet => ("off", "off"),
         0 => ("warn", "warn"),    // Default: warnings only
         1 => ("info", "warn"),    // -v: dl-driver info, s3dlio warnings
         2 => ("debug", "info"),   // -vv: dl-driver debug, s3dlio info
@@ -175,8 +751,40 @@ async fn main() -> Result<()> {
 
     info!("dl-driver v{} starting", env!("CARGO_PKG_VERSION"));
 
-    match args.command {
-        Commands::Run {
+    let quiet = match args.quiet.then(machine::QuietStdout::engage).transpose() {
+        Ok(quiet) => quiet,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            std::process::exit(ExitStatus::Error.code());
+        }
+    };
+
+    // A signal ends the command wherever it is; the exit code says it was interrupted
+    let outcome = tokio::select! {
+        result = run_command(args.command, args.verbose) => result,
+        _ = machine::interrupted() => Err(anyhow::anyhow!("Received termination signal").context(ExitStatus::Interrupted)),
+    };
+    let status = outcome.as_ref().err().map_or(ExitStatus::Success, ExitStatus::of);
+
+    match quiet {
+        Some(quiet) => {
+            if let Err(e) = quiet.finish(status, outcome.as_ref().err()) {
+                eprintln!("Error: {:?}", e);
+            }
+        }
+        None => {
+            if let Err(e) = &outcome {
+                eprintln!("Error: {:?}", e);
+            }
+        }
+    }
+    std::process::exit(status.code());
+}
+
+/// Dispatch one subcommand
+async fn run_command(command: Commands, verbose: u8) -> Result<()> {
+    match command {
+        Commands::Run {
             config,
             pretty,
             mlperf,
@@ -192,12 +800,33 @@ async fn main() -> Result<()> {
             strict_au,
             gpus,
             use_real_gpus,
+            gpus_per_rank,
             filelist,
             rank,
             world_size,
             start_at_epoch,
+            start_in,
+            repeats,
+            aggregate,
+            discard_outliers,
+            seed_sweep,
             shard_strategy,
             results,
+            manifest,
+            au_baseline,
+            baseline,
+            record_arrivals,
+            record_oplog,
+            compress,
+            timeline,
+            hdf5_as_npz,
+            results_uri,
+            coord_id,
+            coord_endpoint,
+            coord,
+            control_socket,
+            workdir,
+            force_unlock,
         } => run_unified_dlio(
             &config, 
             pretty, 
@@ -214,25 +843,167 @@ async fn main() -> Result<()> {
             strict_au,
             gpus,
             use_real_gpus,
+            gpus_per_rank,
             filelist.as_deref(),
             rank,
             world_size,
             start_at_epoch,
+            start_in.map(std::time::Duration::from_secs),
+            &dl_driver_core::repeats::RepeatSettings {
+                repeats,
+                aggregate: dl_driver_core::repeats::Aggregate::parse(&aggregate).context(ExitStatus::ConfigError)?,
+                discard_outliers,
+            },
+            seed_sweep.as_deref().map(dl_driver_core::seed_sweep::parse_seeds).transpose().context(ExitStatus::ConfigError)?.as_deref(),
             &shard_strategy,
             results.as_deref(),
+            manifest.as_deref(),
+            au_baseline.as_deref(),
+            baseline.as_deref(),
+            record_arrivals.as_deref(),
+            record_oplog.as_deref(),
+            artifacts::Compression::parse(&compress).context(ExitStatus::ConfigError)?,
+            dl_driver_core::timeseries::TimelineGranularity::parse(&timeline).context(ExitStatus::ConfigError)?,
+            hdf5_as_npz,
+            results_uri.as_deref(),
+            coord_id.as_deref(),
+            coord_endpoint.as_deref(),
+            coord.as_deref(),
+            control_socket.as_deref(),
+            workdir.as_deref(),
+            force_unlock,
         ).await,
-        Commands::Validate { config, to_json } => validate_dlio_config(&config, to_json).await,
+        Commands::Launch {
+            config,
+            ranks,
+            hosts,
+            ranks_per_host,
+            coord_host,
+            coord_port,
+            remote_exe,
+            remote_results_dir,
+            ssh_options,
+            results_dir,
+            run_args,
+        } => {
+            let opts = launch::LaunchOptions {
+                config,
+                ranks: ranks.unwrap_or(0),
+                results_dir,
+                run_args,
+                verbose,
+            };
+            let ssh = match hosts {
+                Some(path) => Some(launch::SshOptions {
+                    hosts: launch::read_hosts(&path)?,
+                    ranks_per_host: ranks_per_host.unwrap_or(1),
+                    coord_host,
+                    coord_port,
+                    remote_exe,
+                    remote_results_dir,
+                    ssh_options,
+                }),
+                None => None,
+            };
+            run_launch(&opts, ssh.as_ref()).await
+        }
+        Commands::Serve { listen, state_dir, token, metrics_interval, grpc_listen, allow_shell_hooks } => {
+            let token = token.or_else(serve_token);
+            // Runs log at info at least, so their progress reaches the event stream
+            let options = serve::ServeOptions {
+                listen,
+                state_dir,
+                token,
+                grpc_listen,
+                metrics_interval,
+                verbose: verbose.max(1),
+                allow_shell_hooks,
+            };
+            serve::serve(options).await
+        }
+        Commands::History { action: HistoryCommands::Add { db, inputs } } => history_add(&db, &inputs).await,
+        Commands::History { action: HistoryCommands::Show { db, config, last, json } } => {
+            history_show(&db, config.as_deref(), last, json).await
+        }
+        Commands::Fleet {
+            action: FleetCommands::Run { config, agents, ranks_per_agent, coord_host, coord_port, token, results_dir, run_args },
+        } => {
+            let opts = launch::LaunchOptions { config, ranks: 0, results_dir, run_args, verbose };
+            let fleet = fleet::FleetOptions {
+                agents: launch::read_hosts(&agents)?,
+                ranks_per_agent,
+                coord_host,
+                coord_port,
+                token: token.or_else(serve_token),
+            };
+            let exits = fleet::fleet_run(&opts, &fleet).await?;
+            finish_launch(&opts, &exits).await
+        }
+        Commands::Validate { config, to_json, probe } => validate_dlio_config(&config, to_json, probe).await,
+        Commands::Doctor { config, results } => run_doctor(&config, results.as_deref()).await,
         Commands::Generate {
             config,
             verbose,
             skip_existing,
-        } => run_generate_only(&config, verbose, skip_existing).await,
+            force_unlock,
+        } => run_generate_only(&config, verbose, skip_existing, force_unlock).await,
         Commands::Aggregate {
             inputs,
             output,
             strict_au,
             au_threshold,
-        } => aggregate_rank_results(&inputs, &output, strict_au, au_threshold).await,
+            expect_ranks,
+            wait_timeout,
+            baseline,
+            csv,
+        } => aggregate_rank_results(&inputs, &output, strict_au, au_threshold, expect_ranks, wait_timeout, baseline.as_deref(), csv.as_deref()).await,
+        Commands::Overwrite { config, results } => run_overwrite(&config, results.as_deref()).await,
+        Commands::CloneBench { config, results } => run_clone_bench(&config, results.as_deref()).await,
+        Commands::Advise { config, probe_objects, max_threads, results } => {
+            run_advise(&config, probe_objects, max_threads, results.as_deref()).await
+        }
+        Commands::AnalyzeData { config, files, chunk_kib, from_storage, results } => {
+            run_analyze_data(&config, files, chunk_kib, from_storage, results.as_deref()).await
+        }
+        Commands::Replay { trace: Some(trace), compute, results, .. } => run_replay(&trace, compute, results.as_deref()).await,
+        Commands::Replay { op_log, target, speed, results, .. } => {
+            let op_log = op_log.expect("clap requires --trace or --op-log");
+            let options = dl_driver_core::op_replay::ReplayOptions { target, speed };
+            run_op_replay(&op_log, &options, results.as_deref()).await
+        }
+        Commands::Crosscheck {
+            config,
+            dlio_bin,
+            tolerance,
+            output_dir,
+            results,
+        } => run_crosscheck(&config, &dlio_bin, tolerance, &output_dir, results.as_deref()).await,
+        Commands::Suite { configs, output, work_dir, cleanup } => {
+            run_suite(&configs, &output, &work_dir, cleanup, verbose).await
+        }
+        Commands::Report { action: ReportCommands::Convert { inputs, to, output } } => {
+            convert_results(&inputs, &to, &output).await
+        }
+        Commands::Report { action: ReportCommands::Export { inputs, output, redact, mapping } } => {
+            export_results(&inputs, &output, redact, mapping.as_deref()).await
+        }
+        Commands::Report {
+            action: ReportCommands::Project { single_host, measured, clients, contention, storage_limit_gib_s, tolerance, output },
+        } => {
+            let options = dl_driver_core::scaling::ProjectionOptions { clients, contention, storage_limit_gib_s, tolerance };
+            project_scaling(&single_host, measured.as_deref(), &options, output.as_deref()).await
+        }
+        Commands::Report { action: ReportCommands::Publish { inputs, base, to, label, output } } => {
+            publish_report(&inputs, base.as_deref(), to.as_deref(), &label, output.as_deref()).await
+        }
+        Commands::Micro { uri, ops, sizes, queue_depths, duration, results } => {
+            run_micro(&uri, ops, sizes, queue_depths, duration, results.as_deref()).await
+        }
+        Commands::Bench {
+            action: BenchCommands::Checkpoint { config, rank, world_size, coord_id, coord_endpoint, results },
+        } => {
+            run_checkpoint_storm(&config, rank, world_size, coord_id.as_deref(), coord_endpoint.as_deref(), results.as_deref()).await
+        }
     }
 }
 
@@ -241,8 +1012,8 @@ async fn run_unified_dlio(
     config_path: &std::path::Path,
     pretty: bool,
     mlperf_mode: bool,
-    _format: &str,
-    _output_path: Option<&std::path::Path>,
+    format: &str,
+    output_path: Option<&std::path::Path>,
     max_epochs: u32,
     max_steps: u32,
     _pool_size: usize,
@@ -253,12 +1024,31 @@ async fn run_unified_dlio(
     strict_au: bool,
     gpus: Option<u32>,
     use_real_gpus: bool,
+    gpus_per_rank: Option<u32>,
     filelist: Option<&std::path::Path>,
     rank: Option<u32>,
     world_size: Option<u32>,
     start_at_epoch: Option<u64>,
+    start_in: Option<std::time::Duration>,
+    repeat: &dl_driver_core::repeats::RepeatSettings,
+    sweep_seeds: Option<&[u64]>,
     shard_strategy: &str,
     results_path: Option<&std::path::Path>,
+    manifest_path: Option<&std::path::Path>,
+    au_baseline_path: Option<&std::path::Path>,
+    baseline_path: Option<&std::path::Path>,
+    record_arrivals_path: Option<&std::path::Path>,
+    oplog_path: Option<&std::path::Path>,
+    compression: artifacts::Compression,
+    timeline: dl_driver_core::timeseries::TimelineGranularity,
+    hdf5_as_npz: bool,
+    results_uri: Option<&str>,
+    coord_id: Option<&str>,
+    coord_endpoint: Option<&str>,
+    coord: Option<&str>,
+    control_socket: Option<&std::path::Path>,
+    workdir: Option<&std::path::Path>,
+    force_unlock: bool,
 ) -> Result<()> {
     info!("Loading DLIO config from: {:?}", config_path);
 
@@ -266,14 +1056,23 @@ async fn run_unified_dlio(
     let (current_rank, total_ranks) = match (rank, world_size) {
         (Some(r), Some(w)) => {
             if r >= w {
-                return Err(anyhow::anyhow!("Rank {} must be less than world_size {}", r, w));
+                return Err(anyhow::anyhow!("Rank {} must be less than world_size {}", r, w).context(ExitStatus::ConfigError));
             }
             info!("Multi-rank mode: rank={}/{}, strategy={}", r, w, shard_strategy);
             (r, w)
         }
         (None, None) => (0, 1), // Single-process mode
-        _ => return Err(anyhow::anyhow!("Both --rank and --world-size must be specified together")),
+        _ => {
+            return Err(anyhow::anyhow!("Both --rank and --world-size must be specified together")
+                .context(ExitStatus::ConfigError))
+        }
     };
+    // Catch a bad --format before the run rather than after it
+    let mlperf_format = format.to_lowercase();
+    if mlperf_mode && !matches!(mlperf_format.as_str(), "json" | "csv") {
+        return Err(anyhow::anyhow!("Unsupported MLPerf report format '{}'. Use 'json' or 'csv'", format)
+            .context(ExitStatus::ConfigError));
+    }
 
     // Handle start_at_epoch synchronization barrier
     if let Some(start_time) = start_at_epoch {
@@ -287,15 +1086,104 @@ async fn run_unified_dlio(
         }
         info!("Rank {}: Starting synchronized execution", current_rank);
     }
+    if let Some(delay) = start_in.filter(|_| total_ranks == 1) {
+        info!("Starting in {:.3}s", delay.as_secs_f64());
+        tokio::time::sleep(delay).await;
+    }
 
     // Plan A1: Set GPU affinity for multi-GPU scaling on same host
     if total_ranks > 1 {
         setup_gpu_affinity(current_rank, total_ranks, gpus, use_real_gpus)?;
     }
 
+    // Wall-clock time per lifecycle phase, reported alongside the measured metrics
+    let phases = std::sync::Arc::new(dl_driver_core::phases::PhaseTimes::new());
+
     // Load DLIO configuration
-    let yaml_content = std::fs::read_to_string(config_path)?;
-    let dlio_config = DlioConfig::from_yaml(&yaml_content)?;
+    let mut dlio_config = DlioConfig::from_yaml_file(config_path).context(ExitStatus::ConfigError)?;
+    phases.mark(dl_driver_core::startup::CONFIG_PARSED);
+    if let Some(dir) = workdir {
+        dlio_config.anchor_relative_paths(dir).context(ExitStatus::ConfigError)?;
+    }
+
+    // Builds without libhdf5 cannot produce real HDF5 datasets
+    let configured_format = dlio_config.dataset.format.clone().unwrap_or_else(|| "npz".to_string());
+    let resolved_format = real_dlio_formats::FormatFactory::resolve_format(&configured_format, hdf5_as_npz)
+        .context("Pass --hdf5-as-npz to substitute npz files")
+        .context(ExitStatus::ConfigError)?;
+    if resolved_format != configured_format.to_lowercase() {
+        warn!("⚠️  HDF5 support not compiled in; substituting {} for {}", resolved_format, configured_format);
+        dlio_config.dataset.format = Some(resolved_format);
+    }
+
+    if sweep_seeds.is_some() {
+        dl_driver_core::seed_sweep::validate(&dlio_config, repeat).context(ExitStatus::ConfigError)?;
+    }
+    dl_driver_core::content::check_format(&dlio_config).context(ExitStatus::ConfigError)?;
+
+    // http(s):// datasets are read-only; they must be staged by the web server
+    if dlio_config.should_generate_data() && dl_driver_core::backends::is_read_only_uri(dlio_config.data_folder_uri()) {
+        return Err(anyhow::anyhow!(
+            "Data folder {} is read-only; set workflow.generate_data: false to benchmark an existing HTTP(S) dataset",
+            dlio_config.data_folder_uri()
+        ).context(ExitStatus::ConfigError));
+    }
+
+    // Proxy settings from the config reach the storage clients through the environment
+    let network_settings = dl_driver_core::network::NetworkSettings::from_config(&dlio_config).context(ExitStatus::ConfigError)?;
+    dl_driver_core::network::NetworkSettings::export_env(&dlio_config);
+    let network_path = match dl_driver_core::network::endpoint_for(&dlio_config, &network_settings) {
+        Ok(Some((host, port))) => {
+            let path = dl_driver_core::network::probe(&network_settings, &host, port).await;
+            match &path.error {
+                None => info!("🌐 Storage endpoint {} reachable via {} ({})", path.endpoint,
+                              path.proxy.as_deref().unwrap_or("direct connection"),
+                              path.connected_family.as_deref().unwrap_or("unknown family")),
+                Some(e) => warn!("⚠️  Storage endpoint {} probe failed: {}", path.endpoint, e),
+            }
+            Some(path)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("⚠️  Cannot determine the storage endpoint to probe: {:#}", e);
+            None
+        }
+    };
+    let cloud_context = dl_driver_core::cloud_context::CloudContext::from_config(&dlio_config);
+    if let Some(context) = &cloud_context {
+        info!("☁️  Cloud context: {}", context.summary());
+    }
+
+    let encryption = dl_driver_core::encryption::EncryptionSettings::from_config(&dlio_config).context(ExitStatus::ConfigError)?;
+
+    // Constrain the client before any work, so every phase runs inside the footprint
+    let client_limits = dl_driver_core::client_limits::ClientLimits::from_config(&dlio_config, current_rank)
+        .context(ExitStatus::ConfigError)?
+        .map(|limits| limits.apply());
+
+    // DirectIO targets need sector-aligned files; validate before any I/O
+    let alignment_report = dl_driver_core::directio::preflight(&mut dlio_config)
+        .context("DirectIO alignment preflight failed")?;
+
+    // Record filesystem type and mount options so NFS/Lustre/GPFS results are interpretable
+    let mount_info = dl_driver_core::mount_probe::probe(&dlio_config);
+    if let Some(mount) = &mount_info {
+        info!("📁 Target filesystem: {} mounted at {} from {} ({}, options: {})",
+              mount.fs_type, mount.mount_point, mount.source,
+              if mount.is_network { "network" } else { "local" },
+              mount.options.join(","));
+    }
+
+    // Backend features (range GET, multipart, consistency) so anomalies can be tied to a missing one
+    let capabilities = dl_driver_core::capabilities::probe(&dlio_config, current_rank).await;
+
+    // Striping hints must be in place before data generation creates files
+    let hints_applied = if dlio_config.should_generate_data() {
+        dl_driver_core::striping::apply_hints(&dlio_config, mount_info.as_ref())?
+    } else {
+        false
+    };
+    let stripe_info = dl_driver_core::striping::probe(&dlio_config, mount_info.as_ref(), hints_applied);
 
     // Handle file list sharding for multi-rank execution
     let sharded_file_list = if let Some(filelist_path) = filelist {
@@ -317,6 +1205,20 @@ async fn run_unified_dlio(
         None
     };
 
+    // Existing datasets: detect the format from content and reconcile with the config
+    let format_probe = dl_driver_core::format_probe::check(&mut dlio_config, sharded_file_list.as_deref())
+        .await
+        .context("Dataset format detection failed")?;
+    let record_sizes = dl_driver_core::record_sizes::check(&mut dlio_config, sharded_file_list.as_deref())
+        .await
+        .context("Record size discovery failed")?;
+
+    // Custom key naming is validated up front and recorded for reproducibility
+    let key_layout = dlio_config.dataset.key_layout.is_some()
+        .then(|| dl_driver_core::key_layout::KeyLayout::from_config(&dlio_config))
+        .transpose()
+        .context(ExitStatus::ConfigError)?;
+
     if pretty {
         println!("=== Parsed DLIO Configuration ===");
         println!("{:#?}", dlio_config);
@@ -327,6 +1229,12 @@ async fn run_unified_dlio(
         );
         println!("Should train: {}", dlio_config.should_train());
         println!("Should checkpoint: {}", dlio_config.should_checkpoint());
+        if let Some(report) = &alignment_report {
+            println!("DirectIO alignment: {:#?}", report);
+        }
+        if let Some(mount) = &mount_info {
+            println!("Target mount: {:#?}", mount);
+        }
         if mlperf_mode {
             println!("MLPerf compliance mode: ENABLED");
             println!("Max epochs: {}, Max steps: {}", max_epochs, max_steps);
@@ -334,187 +1242,494 @@ async fn run_unified_dlio(
         return Ok(());
     }
 
-    // Create plugin manager with CheckpointPlugin if enabled
-    let _plugins = PluginManager::new();
-    
-    // TODO: Temporarily disabled while we fix config compatibility
-    // Add CheckpointPlugin if checkpointing is enabled in config
-    // if let Some(checkpoint_plugin) = dl_driver_core::plugins::CheckpointPlugin::new(&dlio_config).await? {
-    //     plugins.push(Box::new(checkpoint_plugin));
-    //     info!("CheckpointPlugin registered");
-    // }
-    
-    // plugins.initialize(&dlio_config).await
-    //     .context("Failed to initialize plugins")?;
-
-    // Initialize metrics system (always available, enhanced in MLPerf mode)
-    let _metrics = if mlperf_mode {
-        dl_driver_core::mlperf::MlperfMetrics::new()
+    // Ranks join their coordination group up front: shared memory on one host,
+    // TCP barriers across hosts (--coord tcp://host:port, or a launcher's --coord-endpoint)
+    let coordinator = if total_ranks > 1 {
+        use dl_driver_core::coord_backend::CoordSpec;
+        let spec = match (coord, coord_endpoint) {
+            (Some(uri), _) => CoordSpec::parse(uri).context(ExitStatus::ConfigError)?,
+            (None, Some(endpoint)) => CoordSpec::Tcp { endpoint: endpoint.to_string(), serve: false },
+            (None, None) => {
+                // Use deterministic coordination ID based on config path and world size
+                let config_name = config_path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("dlio");
+                CoordSpec::SharedMemory(coord_id
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("dlio_{}_{}", config_name, total_ranks)))
+            }
+        };
+        let coord = spec.connect(current_rank, total_ranks).await?;
+        
+        info!("🔗 Rank {}: Registering with coordination group {}", current_rank, coord.describe());
+        coord.register().await
+            .context("Failed to register with coordination group")?;
+        Some(coord)
     } else {
-        dl_driver_core::mlperf::MlperfMetrics::new() // Same system for both modes
+        None
     };
 
+    // Relative paths resolve against each rank's working directory, which some
+    // launchers set differently per rank; fail before anyone generates or reads data
+    if let Some(coord) = &coordinator {
+        let data_folder = dlio_config.resolved_data_folder_uri().context(ExitStatus::ConfigError)?;
+        coord.agree("data_folder", &data_folder).await
+            .context("Ranks resolved different data folders; pass --workdir or use an absolute data_folder")
+            .context(ExitStatus::ConfigError)?;
+        info!("📁 Rank {}: All {} ranks agree on data folder {}", current_rank, total_ranks, data_folder);
+    }
+
     // Phase 1: Data Generation (if enabled)
     if dlio_config.workflow.as_ref().map_or(false, |w| w.generate_data.unwrap_or(false)) {
         info!("Phase 1: Generating data");
-        run_data_generation(&dlio_config).await
-            .context("Data generation failed")?;
+        // Rank 0 holds the dataset lock (dataset.lock) while generating
+        let lock = if current_rank == 0 {
+            match dl_driver_core::dataset_lock::DatasetLock::acquire(&dlio_config, "generate", force_unlock).await {
+                Ok(lock) => lock,
+                Err(e) => {
+                    if let Some(coord) = &coordinator {
+                        coord.abort("dataset is locked by another run");
+                    }
+                    return Err(e.context(ExitStatus::StorageError));
+                }
+            }
+        } else {
+            None
+        };
+        // No rank writes before rank 0 holds the lock...
+        if let Some(coord) = &coordinator {
+            coord.barrier("dataset_locked").await
+                .context("Ranks failed to wait for the dataset lock")
+                .context(ExitStatus::Error)?;
+        }
+        let generated = phases.time("generate", run_data_generation(&dlio_config, false)).await;
+        // ...or after it releases it
+        if let Some(coord) = &coordinator {
+            let synced = match &generated {
+                Ok(()) => coord.barrier_within("dataset_generated", GENERATION_BARRIER_TIMEOUT).await,
+                Err(_) => {
+                    coord.abort("data generation failed");
+                    Ok(())
+                }
+            };
+            if let Err(e) = synced {
+                if let Some(lock) = lock {
+                    lock.release().await.context(ExitStatus::StorageError)?;
+                }
+                return Err(e.context("Ranks failed to wait for data generation").context(ExitStatus::Error));
+            }
+        }
+        if let Some(lock) = lock {
+            lock.release().await.context(ExitStatus::StorageError)?;
+        }
+        generated
+            .context("Data generation failed")
+            .context(ExitStatus::StorageError)?;
     }
 
+    // Requested encryption must be what the store actually applied
+    let encryption_report = match encryption {
+        Some(settings) => {
+            let report = dl_driver_core::encryption::verify_dataset(settings, &dlio_config)
+                .await
+                .context("Encryption read-back failed")
+                .context(ExitStatus::StorageError)?;
+            if !report.verified {
+                return Err(anyhow::anyhow!("Objects are not stored with the requested encryption: {}", report.mismatches.join("; "))
+                    .context(ExitStatus::SloFail));
+            }
+            Some(report)
+        }
+        None => None,
+    };
+
+    // --mlperf: built from the training run's metrics
+    let mut mlperf_report = None;
+    // What ran, for reproduction; the training phase adds its seeds
+    let mut manifest = dl_driver_core::manifest::RunManifest::new(
+        config_path,
+        &dlio_config,
+        current_rank,
+        total_ranks,
+        std::env::args().skip(1).collect(),
+    )
+    .context(ExitStatus::ConfigError)?;
+
     // Phase 2: Training workload using WorkloadRunner for DLIO compliance measurement
     if dlio_config.workflow.as_ref().map_or(true, |w| w.train.unwrap_or(true)) {
         info!("Phase 2: Training workload (MEASURED for AU calculation)");
         
         // Use WorkloadRunner ONLY for training phase measurement (data generation already done)
-        // Plan A1: Multi-GPU scaling - each rank represents one GPU, so total accelerators = world_size
-        let accelerator_count = if total_ranks > 1 {
-            // Multi-GPU mode: each rank gets 1 GPU, total system has world_size GPUs
+        // Plan A1: Multi-GPU scaling - each rank represents gpus_per_rank GPUs (1 by default)
+        let layout = dl_driver_core::accelerators::AcceleratorLayout::from_config(&dlio_config, gpus_per_rank, total_ranks)
+            .context(ExitStatus::ConfigError)?;
+        let accelerator_count = if total_ranks > 1 || layout.gpus_per_rank > 1 {
             info!("Plan A1 Multi-GPU: Using {} total GPUs ({} GPUs per rank × {} ranks)", 
-                  total_ranks, 1, total_ranks);
-            total_ranks
+                  layout.total(), layout.gpus_per_rank, total_ranks);
+            layout.total()
         } else {
             // Single-GPU mode: use explicit accelerator count
             accelerators.unwrap_or(1)
         };
+        // A rank standing for several GPUs loads a batch for each of them every step
+        let step_config = layout.step_config(&dlio_config);
+        if layout.gpus_per_rank > 1 {
+            info!("🎛️  Rank {}: {} samples per step ({} GPUs × batch_size {})", current_rank,
+                  step_config.reader.batch_size.unwrap_or(1), layout.gpus_per_rank, dlio_config.reader.batch_size.unwrap_or(1));
+        }
+        // In a mixed fleet (train.compute_profiles) this rank computes for its profile's time
+        let compute_profile = dl_driver_core::compute_profiles::ComputeProfile::for_rank(&dlio_config, current_rank, total_ranks)
+            .context(ExitStatus::ConfigError)?;
+        let step_config = match &compute_profile {
+            Some(profile) => {
+                info!("🧮 Rank {}: Compute profile '{}' ({:.3}s per step)", current_rank, profile.name, profile.computation_time);
+                profile.apply(&step_config)
+            }
+            None => step_config,
+        };
 
-        // Multi-rank coordination setup
-        let coordinator = if total_ranks > 1 {
-            use dl_driver_core::coordination::RankCoordinator;
-            
-            // Use deterministic coordination ID based on config path and world size
-            let config_name = config_path.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("dlio");
-            let coord_id = format!("dlio_{}_{}", config_name, total_ranks);
-            let coord = RankCoordinator::new(current_rank, total_ranks, &coord_id)
-                .context("Failed to create rank coordinator")?;
-            
-            info!("🔗 Rank {}: Registering with coordination group", current_rank);
-            coord.register_and_wait().await
-                .context("Failed to register with coordination group")?;
-                
-            info!("🚧 Rank {}: Waiting at execution barrier", current_rank);
-            coord.barrier("execution_start").await
-                .context("Failed to synchronize at execution barrier")?;
-                
-            // Rank 0 marks global start time
-            if current_rank == 0 {
-                coord.mark_global_start()
-                    .context("Failed to mark global start time")?;
+        // All ranks start together (at the broadcast gate with --start-in); over TCP each
+        // rank also measures its clock offset from the server, while ranks sharing a host share a clock
+        let clock_offset = match &coordinator {
+            Some(coord) => {
+                info!("🚧 Rank {}: Waiting at execution barrier", current_rank);
+                coord.start(start_in).await
+                    .context("Failed to synchronize at execution barrier")?;
+                Some(coord.clock_offset())
             }
-            
-            Some(coord)
-        } else {
-            None
+            None => None,
         };
 
-        let mut workload_runner = dl_driver_core::WorkloadRunner::new(dlio_config.clone())
-            .with_accelerator_config(accelerator_count, strict_au)
-            .with_rank_config(current_rank, total_ranks, sharded_file_list.clone());
-            
-        workload_runner.run_training_phase().await
-            .context("Training workload failed")?;
+        let oplog = match oplog_path {
+            Some(path) => Some(std::sync::Arc::new(
+                dl_driver_core::oplog::OpLogWriter::create(
+                    &artifacts::output_path(&artifacts::rank_path(path, current_rank, total_ranks), compression.traces()),
+                    current_rank,
+                )
+                    .context(ExitStatus::ConfigError)?,
+            )),
+            None => None,
+        };
+
+        // With --repeats, every repetition trains on the same dataset; the last one's
+        // runner supplies the detailed per-rank results. A seed sweep runs every seed once, then again.
+        let sweep = sweep_seeds.unwrap_or_default();
+        let per_seed = (repeat.repeats as usize).max(1);
+        let total_runs = sweep.len().max(1) * per_seed;
+        let mut repetitions = Vec::new();
+        let mut sweep_runs = Vec::new();
+        let workload_runner = loop {
+            let mut runner = dl_driver_core::WorkloadRunner::new(step_config.clone())
+                .with_accelerator_config(accelerator_count, strict_au)
+                .with_rank_config(current_rank, total_ranks, sharded_file_list.clone())
+                .with_phase_times(std::sync::Arc::clone(&phases))
+                .with_timeline(timeline);
+            if let Some(path) = control_socket {
+                runner = runner.with_control_socket(path.to_path_buf());
+            }
+            if let Some(log) = &oplog {
+                runner = runner.with_oplog(std::sync::Arc::clone(log));
+            }
+            // MLPerf reports digest the keys each epoch read
+            if mlperf_mode {
+                runner = runner.with_access_order();
+            }
+            let (seed, seed_repetition) = if sweep.is_empty() {
+                (None, 1)
+            } else {
+                let (seed, repetition) = dl_driver_core::seed_sweep::run_seed(sweep, repetitions.len());
+                (Some(seed), repetition)
+            };
+            if let Some(seed) = seed {
+                runner = runner.with_shuffle_seed(seed);
+                info!("🎲 Rank {}: Shuffle seed {} ({}/{})", current_rank, seed, repetitions.len() + 1, total_runs);
+            } else if repeat.repeats > 1 {
+                info!("🔁 Rank {}: Repetition {}/{}", current_rank, repetitions.len() + 1, repeat.repeats);
+            }
+
+            runner.run_training_phase().await
+                .context("Training workload failed")?;
+
+            let results = runner.get_metrics().to_json(current_rank, &dlio_config);
+            if let Some(seed) = seed {
+                sweep_runs.push(dl_driver_core::seed_sweep::SeedRun::from_results(seed, seed_repetition, &results));
+            }
+            repetitions.push(dl_driver_core::suite::RunMetrics::from_results(&results));
+            if repetitions.len() >= total_runs {
+                break runner;
+            }
+        };
+        let sweep_report = (!sweep.is_empty())
+            .then(|| dl_driver_core::seed_sweep::SeedSweepReport::new(sweep, per_seed as u32, sweep_runs));
+        let repeat_report = (repeat.repeats > 1 && sweep.is_empty())
+            .then(|| dl_driver_core::repeats::RepeatReport::new(repetitions, repeat));
+        if let Some(report) = &repeat_report {
+            report.print_summary();
+        }
+        if let Some(report) = &sweep_report {
+            report.print_summary();
+        }
+
+        let arrival_trace = match record_arrivals_path {
+            Some(path) => {
+                let written = workload_runner.arrival_trace().save(path, compression.traces())?;
+                info!("Rank {}: Batch arrival trace saved to {:?}", current_rank, written);
+                Some(written)
+            }
+            None => None,
+        };
+        let oplog_summary = match &oplog {
+            Some(log) => {
+                let summary = log.finish()?;
+                info!("Rank {}: {} storage operations logged to {:?}", current_rank, summary.ops, summary.path);
+                Some(summary)
+            }
+            None => None,
+        };
 
         // Multi-rank coordination finish
-        if let Some(ref coord) = coordinator {
-            info!("🏁 Rank {}: Marking execution finished", current_rank);
-            coord.mark_finished_and_wait().await
+        let teardown_start = std::time::Instant::now();
+        if let Some(coord) = &coordinator {
+            info!("🏁 Rank {}: Waiting for all ranks to finish", current_rank);
+            let metrics_json = workload_runner.get_metrics().to_json(current_rank, &dlio_config);
+            let results = coord.finish(dl_driver_core::coordination::RankResults::from_metrics(&metrics_json, layout.gpus_per_rank)).await
                 .context("Failed to coordinate execution finish")?;
                 
             // Only rank 0 displays aggregated results (eliminates temp file aggregation)
             if current_rank == 0 {
-                match coord.get_aggregated_results() {
-                    Ok(results) => {
-                        println!("\n🎉 Plan A1 Multi-GPU Results (Shared Memory Coordination):");
-                        println!("================================================================");
-                        println!("Total files processed: {}", results.total_files_processed);
-                        println!("Total data read: {:.2} GiB", results.total_bytes_read as f64 / 1_073_741_824.0);
-                        println!("Combined throughput: {:.2} GiB/s", results.total_throughput_gib_s);
-                        println!("Global runtime: {:.3}s", results.global_runtime_seconds);
-                        println!("Number of ranks: {}", results.total_ranks);
-                        println!("\nPer-rank breakdown:");
-                        for detail in &results.rank_details {
-                            println!("  Rank {}: {:.2} GiB/s, {} files, AU: {:.4}%", 
-                                   detail.rank, 
-                                   detail.throughput_gib_s,
-                                   detail.files_processed,
-                                   detail.au_fraction * 100.0);
-                        }
-                        println!("✅ Multi-rank coordination successful - NO TEMP FILES USED");
-                    }
-                    Err(e) => {
-                        warn!("⚠️  Failed to get aggregated results: {}", e);
-                    }
+                println!("\n🎉 Plan A1 Multi-GPU Results ({}):", coord.describe());
+                println!("================================================================");
+                println!("Total files processed: {}", results.total_files_processed);
+                println!("Total data read: {}", units::fmt_bytes(results.total_bytes_read));
+                println!("Combined throughput: {}", units::Throughput::from_gib_s(results.total_throughput_gib_s).display());
+                println!("Global runtime: {:.3}s", results.global_runtime_seconds);
+                println!("Number of ranks: {}", results.total_ranks);
+                println!("Accelerators: {} (global AU: {:.4}%)", results.total_accelerators, results.global_au_fraction * 100.0);
+                println!("\nPer-rank breakdown:");
+                for detail in &results.rank_details {
+                    println!("  Rank {}: {:.2} GiB/s, {} files, {} GPUs, AU: {:.4}%", 
+                           detail.rank, 
+                           detail.throughput_gib_s,
+                           detail.files_processed,
+                           detail.accelerators,
+                           detail.au_fraction * 100.0);
                 }
+                println!("✅ Multi-rank coordination successful - NO TEMP FILES USED");
             }
-                
-            let stats = coord.get_stats();
-            debug!("📊 Coordination stats: {:?}", stats);
-            
-            // Cleanup coordination resources (rank 0 only)
-            coord.cleanup()
-                .context("Failed to cleanup coordination resources")?;
         }
         
+        phases.record("teardown", teardown_start.elapsed());
+        phases.print_summary();
+
         // Get final metrics from WorkloadRunner
         let workload_metrics = workload_runner.get_metrics();
+        manifest.rng = workload_runner.shuffle_report().map(|report| report.rng.clone());
+        if mlperf_mode {
+            mlperf_report = Some(dl_driver_core::mlperf::MlperfReport::from_workload(
+                workload_metrics,
+                workload_runner.shuffle_report(),
+                &dlio_config,
+                current_rank,
+            ));
+        }
 
-        // Store results in shared memory (eliminates temp files for multi-rank)
-        if let Some(coord) = coordinator.as_ref() {
-            // Get metrics as JSON to extract needed values
-            let metrics_json = workload_metrics.to_json(current_rank, &dlio_config);
-            let metrics_obj = metrics_json["metrics"].as_object().unwrap();
-            
-            let files_processed = metrics_obj["files_processed"].as_u64().unwrap_or(0);
-            let bytes_read = metrics_obj["bytes_read"].as_u64().unwrap_or(0);
-            let throughput_gib_s = metrics_obj["storage_throughput_gib_s"].as_f64().unwrap_or(0.0);
-            let wall_clock_time_ms = metrics_obj["wall_clock_time_ms"].as_u64().unwrap_or(0);
-            let au_fraction = metrics_obj["au_fraction"].as_f64().unwrap_or(0.0);
-            
-            let start_time_ns = (metrics_json["start_time"].as_f64().unwrap_or(0.0) * 1_000_000_000.0) as u64;
-            let end_time_ns = (metrics_json["end_time"].as_f64().unwrap_or(0.0) * 1_000_000_000.0) as u64;
-            
-            coord.store_results(
-                files_processed,
-                bytes_read,
-                throughput_gib_s,
-                wall_clock_time_ms as f64,
-                au_fraction,
-                start_time_ns,
-                end_time_ns
-            ).context("Failed to store results in shared memory")?;
-            
-            info!("📊 Rank {}: Results stored in shared memory", current_rank);
+        // Per-rank results JSON, saved to --results and/or uploaded to --results-uri
+        let rank_results = if results_path.is_some() || results_uri.is_some() || machine::quiet() {
+            let mut metrics_json = workload_metrics.to_json(current_rank, &dlio_config);
+            // `history` groups runs by config file name
+            if let Some(name) = config_path.file_name() {
+                metrics_json["config_file"] = serde_json::json!(name.to_string_lossy());
+            }
+            if let Some(report) = &alignment_report {
+                metrics_json["directio_alignment"] = serde_json::to_value(report)?;
+            }
+            if let Some(clock) = &clock_offset {
+                metrics_json["clock_sync"] = serde_json::to_value(clock)?;
+            }
+            if let Some(mount) = &mount_info {
+                metrics_json["mount"] = serde_json::to_value(mount)?;
+            }
+            if let Some(matrix) = &capabilities {
+                metrics_json["capabilities"] = serde_json::to_value(matrix)?;
+            }
+            if let Some(report) = &encryption_report {
+                metrics_json["encryption"] = serde_json::to_value(report)?;
+            }
+            if let Some(path) = &network_path {
+                metrics_json["network"] = serde_json::to_value(path)?;
+            }
+            if let Some(context) = &cloud_context {
+                metrics_json["cloud_context"] = serde_json::to_value(context)?;
+            }
+            if let Some(limits) = &client_limits {
+                metrics_json["client_limits"] = serde_json::to_value(limits)?;
+            }
+            if let Some(probe) = &format_probe {
+                metrics_json["format_probe"] = serde_json::to_value(probe)?;
+            }
+            if let Some(sizes) = &record_sizes {
+                metrics_json["record_sizes"] = serde_json::to_value(sizes)?;
+            }
+            if let Some(layout) = &key_layout {
+                metrics_json["key_layout"] = serde_json::to_value(layout)?;
+            }
+            if let Some(report) = workload_runner.decode_validation_report() {
+                metrics_json["decode_validation"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.content_verification_report() {
+                metrics_json["content_verification"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.shuffle_report() {
+                metrics_json["shuffle"] = serde_json::to_value(report)?;
+            }
+            metrics_json["manifest"] = serde_json::to_value(&manifest)?;
+            metrics_json["phases"] = serde_json::to_value(phases.breakdown())?;
+            if let Some(startup) = dl_driver_core::startup::StartupReport::from_marks(dl_driver_core::startup::process_start(), &phases) {
+                metrics_json["startup"] = serde_json::to_value(startup)?;
+            }
+            if let Some(timeline) = workload_runner.timeline() {
+                metrics_json["timeline"] = serde_json::to_value(timeline)?;
+            }
+            if let Some(timeseries) = workload_runner.timeseries() {
+                metrics_json["timeseries"] = serde_json::to_value(timeseries)?;
+            }
+            if let Some(stats) = workload_runner.backend_report() {
+                metrics_json["backend_stats"] = stats.clone();
+            }
+            if let Some(report) = workload_runner.request_timing_report() {
+                metrics_json["request_timing"] = serde_json::to_value(report)?;
+            }
+            if let Some(concurrency) = workload_runner.concurrency() {
+                metrics_json["concurrency"] = serde_json::to_value(concurrency)?;
+            }
+            if let Some(report) = workload_runner.worker_balance_report() {
+                metrics_json["worker_balance"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.pool_stats_report() {
+                metrics_json["pool_stats"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.cache_warming_report() {
+                metrics_json["cache_warming"] = serde_json::to_value(report)?;
+            }
+            if let Some(summary) = &oplog_summary {
+                metrics_json["oplog"] = serde_json::to_value(summary)?;
+            }
+            if let Some(report) = workload_runner.dataset_growth_report() {
+                metrics_json["dataset_growth"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.tier_migration_report() {
+                metrics_json["tier_migration"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.checkpoint_report() {
+                metrics_json["checkpointing"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.evaluation_report() {
+                metrics_json["evaluation"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.epoch_boundary_report() {
+                metrics_json["epoch_boundary"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.failover_report() {
+                report.print_summary();
+                metrics_json["failover"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = workload_runner.list_interference_report() {
+                report.print_summary();
+                metrics_json["list_interference"] = serde_json::to_value(report)?;
+            }
+            if let Some(stripes) = &stripe_info {
+                metrics_json["striping"] = serde_json::to_value(stripes)?;
+            }
+            if let Some(report) = &repeat_report {
+                metrics_json["repeats"] = serde_json::to_value(report)?;
+            }
+            if let Some(report) = &sweep_report {
+                metrics_json["seed_sweep"] = serde_json::to_value(report)?;
+            }
+            if let Some(profile) = &compute_profile {
+                metrics_json["compute_profile"] = serde_json::to_value(profile)?;
+            }
+            if let Some(baseline_path) = au_baseline_path {
+                let au_fraction = metrics_json["metrics"]["au_fraction"].as_f64().unwrap_or(0.0);
+                metrics_json["au_baseline"] = normalize_au(baseline_path, au_fraction)?;
+            }
+            // Accelerators this rank stands for; aggregation sums them across ranks
+            metrics_json["derived"] = serde_json::json!({"accelerators": if total_ranks > 1 { layout.gpus_per_rank } else { accelerator_count }});
+            let mut derived = dl_driver_core::derived::Derived::from_results(&metrics_json);
+            if let Some(path) = baseline_path {
+                derived = derived.with_baseline(path).context(ExitStatus::ConfigError)?;
+            }
+            metrics_json["derived"] = serde_json::to_value(derived)?;
+            machine::set_results(metrics_json.clone());
+            Some(metrics_json)
         } else {
-            // Single rank mode: export to JSON file if requested
-            if let Some(results_file) = results_path {
-                let metrics_json = workload_metrics.to_json(current_rank, &dlio_config);
-                std::fs::write(results_file, serde_json::to_string_pretty(&metrics_json)?)
-                    .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
-                info!("Rank {}: Results saved to {:?}", current_rank, results_file);
+            None
+        };
+
+        // Upload results and trace in the background while this rank finishes up
+        let pending_upload = match (results_uri, &rank_results) {
+            (Some(uri), Some(metrics_json)) => {
+                use dl_driver_core::results_upload;
+                // Objects are named and compressed as the local files are
+                let json = serde_json::to_vec_pretty(metrics_json)?;
+                let mut files = vec![if compression.results() {
+                    (results_upload::rank_results_name(current_rank) + artifacts::ZSTD_SUFFIX, artifacts::compress(&json)?)
+                } else {
+                    (results_upload::rank_results_name(current_rank), json)
+                }];
+                if let Some(trace_path) = &arrival_trace {
+                    let suffix = if compression.traces() { artifacts::ZSTD_SUFFIX } else { "" };
+                    files.push((
+                        results_upload::rank_trace_name(current_rank) + suffix,
+                        std::fs::read(trace_path).with_context(|| format!("Failed to read {:?}", trace_path))?,
+                    ));
+                }
+                Some(results_upload::spawn_upload(uri, files))
             }
+            _ => None,
+        };
+
+        // Export per-rank JSON if requested (`aggregate` and `launch` combine these)
+        if let (Some(results_file), Some(metrics_json)) = (results_path, &rank_results) {
+            let written = artifacts::write(results_file, serde_json::to_string_pretty(metrics_json)?.as_bytes(), compression.results())
+                .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+            info!("Rank {}: Results saved to {:?}", current_rank, written);
+        }
+
+        if let Some(upload) = pending_upload {
+            let uploaded = upload.await.context("Results upload task failed")??;
+            info!("Rank {}: {} results objects uploaded to {}", current_rank, uploaded.len(),
+                  results_uri.unwrap_or_default());
+        }
+
+        // Data-path corruption fails the run once results are saved
+        if let Some(report) = workload_runner.content_verification_report().filter(|r| !r.passed()) {
+            return Err(anyhow::anyhow!(
+                "Content verification failed: {} mismatched samples, {} unreadable objects (first: {})",
+                report.samples_mismatched,
+                report.objects_unreadable,
+                report.mismatch_examples.first().map(String::as_str).unwrap_or("n/a")
+            ).context(ExitStatus::SloFail));
         }
     }
 
+    if let Some(path) = manifest_path {
+        let path = &artifacts::rank_path(path, current_rank, total_ranks);
+        manifest.write(path)?;
+        info!("Rank {}: Run manifest saved to {:?}", current_rank, path);
+    }
+
     println!("✅ DLIO workload completed successfully");
 
     // Output results based on mode
     if mlperf_mode {
-        // TODO: Temporarily disabled while we fix config compatibility
-        println!("MLPerf mode temporarily disabled during config system update");
-        /*
-        // Generate comprehensive MLPerf report
-        let report = dl_driver_core::mlperf::MlperfReport::from_metrics(&metrics, &dlio_config);
-        
-        let output_content = match format.to_lowercase().as_str() {
-            "json" => report.to_json()?,
-            "csv" => {
-                let mut csv_content = String::new();
-                csv_content.push_str(&format!("{}\n", dl_driver_core::mlperf::MlperfReport::to_csv_header()));
-                csv_content.push_str(&format!("{}\n", report.to_csv_row()));
-                csv_content
-            }
-            _ => return Err(anyhow::anyhow!("Unsupported format '{}'. Use 'json' or 'csv'", format)),
+        let Some(report) = mlperf_report else {
+            warn!("⚠️  --mlperf: no training phase ran (workflow.train: false), so there is no MLPerf report");
+            return Ok(());
+        };
+        let output_content = match mlperf_format.as_str() {
+            "csv" => format!("{}\n{}\n", dl_driver_core::mlperf::MlperfReport::to_csv_header(), report.to_csv_row()),
+            _ => report.to_json()?,
         };
 
         // Output to file or stdout
@@ -531,8 +1746,14 @@ async fn run_unified_dlio(
         eprintln!("  Backend: {}", report.backend_type);
         eprintln!("  Samples: {}", report.total_samples);
         eprintln!("  Throughput: {:.2} samples/sec", report.throughput_samples_per_sec);
-        eprintln!("  P99 latency: {:.3} ms", report.p99_latency_ms);
-        */
+        eprintln!("  P99 latency: {:.3} ms (I/O wait P99 {:.3} ms)", report.p99_latency_ms, report.io_p99_latency_ms);
+        if let Some(au) = report.au_percent {
+            eprintln!("  AU: {:.1}%{}", au, match report.au_pass {
+                Some(true) => " (PASS)",
+                Some(false) => " (FAIL)",
+                None => "",
+            });
+        }
     } else {
         // Basic DLIO output - using simplified metrics since WorkloadRunner handles detailed tracking
         println!("📊 DLIO workload execution completed successfully");
@@ -542,34 +1763,144 @@ async fn run_unified_dlio(
     Ok(())
 }
 
+/// Compare this run's AU against a mem:// baseline results file
+fn normalize_au(baseline_path: &std::path::Path, au_fraction: f64) -> Result<serde_json::Value> {
+    let content = artifacts::read_to_string(baseline_path)
+        .with_context(|| format!("Failed to read AU baseline: {:?}", baseline_path))?;
+    let baseline: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse AU baseline: {:?}", baseline_path))?;
+
+    let baseline_au = baseline["metrics"]["au_fraction"].as_f64()
+        .ok_or_else(|| anyhow::anyhow!("AU baseline {:?} has no metrics.au_fraction", baseline_path))?;
+    let baseline_folder = baseline["config"]["data_folder"].as_str().unwrap_or("");
+    if !baseline_folder.starts_with("mem://") {
+        warn!("⚠️  AU baseline {:?} was not a mem:// run ({}); normalized AU may not be an upper bound",
+              baseline_path, baseline_folder);
+    }
+
+    let normalized = if baseline_au > 0.0 { au_fraction / baseline_au } else { 0.0 };
+    info!("📏 AU {:.2}% vs baseline {:.2}% → {:.1}% of loader ceiling",
+          au_fraction * 100.0, baseline_au * 100.0, normalized * 100.0);
+
+    Ok(serde_json::json!({
+        "source": baseline_path.display().to_string(),
+        "data_folder": baseline_folder,
+        "baseline_au_fraction": baseline_au,
+        "normalized_au": normalized,
+    }))
+}
+
 /// Data generation phase using s3dlio (shared by both modes) - PARALLEL VERSION
-async fn run_data_generation(config: &DlioConfig) -> Result<()> {
+///
+/// With `skip_existing`, files already in the data folder at their expected size are kept.
+async fn run_data_generation(config: &DlioConfig, skip_existing: bool) -> Result<()> {
+    use dl_driver_core::multipart::{self, MultipartSettings, MultipartStats};
     use s3dlio::object_store::store_for_uri;
     use std::sync::Arc;
     
+    if dl_driver_core::backends::is_synthetic_uri(&config.dataset.data_folder) {
+        info!("{} is synthesized by its backend at load time; skipping data generation",
+              config.dataset.data_folder);
+        return Ok(());
+    }
+
+    dl_driver_core::content::check_format(config).context(ExitStatus::ConfigError)?;
     let start_time = std::time::Instant::now();
     info!("Starting PARALLEL data generation phase");
 
-    // Create object store for the configured storage backend
-    let store = Arc::new(store_for_uri(&config.dataset.data_folder)
-        .with_context(|| format!("Failed to create object store for {}", config.dataset.data_folder))?);
+    // Create object store for the configured storage backend (or a dl-driver native backend)
+    let native_backend = dl_driver_core::backends::backend_for_config(config, None)?;
+    let store = match native_backend {
+        Some(_) => None,
+        None => Some(Arc::new(store_for_uri(&config.dataset.data_folder)
+            .with_context(|| format!("Failed to create object store for {}", config.dataset.data_folder))?)),
+    };
+
+    // Resume: list what an earlier (interrupted) run already wrote
+    let existing = if skip_existing {
+        let listed = dl_driver_core::resume::ExistingObjects::list(
+            &config.dataset.data_folder,
+            native_backend.as_deref(),
+            store.as_ref().map(|s| &***s),
+        )
+        .await?;
+        Some(Arc::new(listed))
+    } else {
+        None
+    };
+
+    // Requested server-side encryption needs the SSE-capable writer
+    let sse_writer = match dl_driver_core::encryption::EncryptionSettings::from_config(config)? {
+        Some(settings) => {
+            info!("🔐 Writing with {:?}{}", settings.mode,
+                  settings.kms_key_id.as_deref().map_or(String::new(), |k| format!(" (key {})", k)));
+            Some(Arc::new(dl_driver_core::encryption::SseWriter::new(settings, config).await?))
+        }
+        None => None,
+    };
+
+    // Files over the multipart threshold are uploaded in parts
+    let multipart_settings = MultipartSettings::from_config(config);
+    let multipart_stats = Arc::new(MultipartStats::new());
 
     let num_files = config.dataset.num_files_train.unwrap_or(100);
     let samples_per_file = config.dataset.num_samples_per_file.unwrap_or(1);
     let record_size = config.dataset.record_length_bytes.unwrap_or(1024);
     
-    let file_size_mb = (samples_per_file * record_size) as f64 / 1024.0 / 1024.0;
-    let total_size_gb = (num_files as f64 * file_size_mb) / 1024.0;
+    let file_size = (samples_per_file * record_size) as u64;
 
     info!(
-        "🚀 Generating {} files with {} samples each ({:.1}MB per file, {:.2}GB total)",
-        num_files, samples_per_file, file_size_mb, total_size_gb
+        "🚀 Generating {} files with {} samples each ({} per file, {} total)",
+        num_files, samples_per_file, units::fmt_bytes(file_size), units::fmt_bytes(num_files as u64 * file_size)
     );
 
+    // record_length_bytes_stdev: each file gets its own seeded record size (sizes above are the mean)
+    let size_sampler = dl_driver_core::size_distribution::RecordSizeSampler::from_config(config)?;
+    if let Some(sampler) = &size_sampler {
+        info!("📏 Variable record sizes: {} around {} (stdev {}), drawn per file",
+              sampler.distribution().name(), units::fmt_bytes(record_size as u64),
+              units::fmt_bytes(config.dataset.record_length_bytes_stdev.unwrap_or(0) as u64));
+    }
+
     // Pre-generate synthetic data buffer to reuse across all files (memory optimization)
-    let synthetic_data = Arc::new(generate_synthetic_data(samples_per_file, record_size));
-    info!("📦 Pre-generated {:.1}MB synthetic data buffer for reuse", 
-          synthetic_data.len() as f64 / 1024.0 / 1024.0);
+    let mut synthetic_buffer = dl_driver_core::payload::generate_file_payload(config, record_size, None)?;
+    synthetic_buffer.resize(
+        dl_driver_core::directio::aligned_file_size(config, synthetic_buffer.len()),
+        0,
+    );
+    let synthetic_data = Arc::new(synthetic_buffer);
+    info!("📦 Pre-generated {} synthetic data buffer for reuse", 
+          units::fmt_bytes(synthetic_data.len() as u64));
+
+    // Verifiable content differs per file, so each file builds its own payload
+    let content_seed = dl_driver_core::content::content_seed(config);
+    if let Some(seed) = content_seed {
+        info!("🧬 Generating seeded, verifiable content (seed {})", seed);
+    }
+    let shared_config = Arc::new(config.clone());
+
+    // Multi-component datasets write one object per component for every file index
+    let component_payloads = match dl_driver_core::components::components(config) {
+        Some(comps) => {
+            let mut payloads = Vec::with_capacity(comps.len());
+            for c in comps {
+                let mut payload = dl_driver_core::components::generate_payload(config, c, samples_per_file)?;
+                payload.resize(dl_driver_core::directio::aligned_file_size(config, payload.len()), 0);
+                info!("🧩 Component {}: {} per file", dl_driver_core::components::suffix(c),
+                      units::fmt_bytes(payload.len() as u64));
+                payloads.push((dl_driver_core::components::suffix(c), Arc::new(payload)));
+            }
+            Some(Arc::new(payloads))
+        }
+        None => None,
+    };
+
+    // Object key naming (prefix depth/mode, key length); flat train_file_NNNNNN by default
+    let key_layout = Arc::new(dl_driver_core::key_layout::KeyLayout::from_config(config)?);
+    if !key_layout.is_flat() {
+        info!("🗝️  Key layout: {:?} prefixes, depth {}, key length {}", key_layout.prefix_mode,
+              key_layout.prefix_depth, key_layout.key_length.map_or("natural".to_string(), |l| l.to_string()));
+    }
 
     // Determine concurrency level - AGGRESSIVE for maximum I/O throughput
     let available_cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(8);
@@ -592,33 +1923,96 @@ async fn run_data_generation(config: &DlioConfig) -> Result<()> {
     // Spawn parallel file generation tasks
     let mut handles = Vec::new();
     for file_idx in 0..num_files {
-        let store_clone = Arc::clone(&store);
+        let store_clone = store.clone();
+        let backend_clone = native_backend.clone();
+        let sse_clone = sse_writer.clone();
         let data_clone = Arc::clone(&synthetic_data);
         let semaphore_clone = Arc::clone(&semaphore);
         let data_folder_clone = data_folder.clone();
         let format_str = format.to_string();
+        let stats_clone = Arc::clone(&multipart_stats);
+        let config_clone = Arc::clone(&shared_config);
+        let components_clone = component_payloads.clone();
+        let layout_clone = Arc::clone(&key_layout);
+        let existing_clone = existing.clone();
+        let sampler_clone = size_sampler.clone();
 
         let handle = tokio::spawn(async move {
             // Acquire semaphore permit for rate limiting
             let _permit = semaphore_clone.acquire().await.unwrap();
-            
-            // Create full URI path
-            let file_name = format!("train_file_{:06}.{}", file_idx, format_str);
-            let full_path = if data_folder_clone.ends_with('/') {
-                format!("{}{}", data_folder_clone, file_name)
-            } else {
-                format!("{}/{}", data_folder_clone, file_name)
+
+            // Seeded or size-drawn contents are per file, built only for objects that get written
+            let file_record_size = sampler_clone.as_ref().map_or(record_size, |s| s.record_size(file_idx as u64));
+            let build = || -> Result<Arc<Vec<u8>>> {
+                let seeded = content_seed.map(|seed| (seed, file_idx as u64));
+                let mut payload = dl_driver_core::payload::generate_file_payload(&config_clone, file_record_size, seeded)?;
+                payload.resize(dl_driver_core::directio::aligned_file_size(&config_clone, payload.len()), 0);
+                Ok(Arc::new(payload))
+            };
+
+            // (suffix, contents) of every object belonging to this file index; None until built
+            let objects: Vec<(String, Option<Arc<Vec<u8>>>)> = match (components_clone, content_seed, &sampler_clone) {
+                (Some(parts), _, _) => parts.iter().map(|(suffix, data)| (suffix.clone(), Some(Arc::clone(data)))).collect(),
+                (None, None, None) => vec![(format_str, Some(Arc::clone(&data_clone)))],
+                (None, _, _) => vec![(format_str, None)],
             };
 
             let write_start = std::time::Instant::now();
-            let result = store_clone
-                .put(&full_path, &*data_clone)
-                .await
-                .with_context(|| format!("Failed to write file {}", full_path));
+            let mut bytes_written = 0;
+            let mut full_path = String::new();
+            for (suffix, data) in objects {
+                // Create full URI path
+                full_path = layout_clone.uri(&data_folder_clone, file_idx, &suffix);
+                // Resume: existing objects at the expected size are skipped before anything is built
+                let data = match (data, existing_clone.as_deref()) {
+                    (Some(data), Some(existing)) if existing.skip(&full_path, data.len() as u64) => continue,
+                    (Some(data), _) => data,
+                    // Seeded content changes the bytes, not the size, of the shared buffer
+                    (None, Some(existing)) if existing.contains(&full_path) && file_record_size == record_size => {
+                        if existing.skip(&full_path, data_clone.len() as u64) {
+                            continue;
+                        }
+                        build()?
+                    }
+                    // A drawn record size is only known as a file size once built
+                    (None, Some(existing)) if existing.contains(&full_path) => {
+                        let data = build()?;
+                        if existing.skip(&full_path, data.len() as u64) {
+                            continue;
+                        }
+                        data
+                    }
+                    (None, _) => build()?,
+                };
+                let data = &data;
+
+                match (&sse_clone, &backend_clone, &store_clone) {
+                    (Some(sse), _, _) => multipart::upload(
+                        sse.as_ref(),
+                        &full_path,
+                        data,
+                        &multipart_settings,
+                        &stats_clone,
+                    )
+                    .await,
+                    (None, Some(backend), _) => backend.put(&full_path, data).await,
+                    (None, None, Some(store)) => multipart::put_object(
+                        &***store,
+                        &full_path,
+                        data,
+                        &multipart_settings,
+                        &stats_clone,
+                    )
+                    .await,
+                    (None, None, None) => unreachable!("object store is created when no native backend is used"),
+                }
+                .with_context(|| format!("Failed to write file {}", full_path))?;
+                bytes_written += data.len();
+            }
             let write_time = write_start.elapsed();
 
             // Return result with timing info
-            result.map(|_| (file_idx, full_path, data_clone.len(), write_time))
+            Ok::<_, anyhow::Error>((file_idx, full_path, bytes_written, write_time))
         });
         
         handles.push(handle);
@@ -635,15 +2029,17 @@ async fn run_data_generation(config: &DlioConfig) -> Result<()> {
             Ok((file_idx, _path, bytes, write_time)) => {
                 completed += 1;
                 total_bytes += bytes as u64;
-                fastest_write = fastest_write.min(write_time);
-                slowest_write = slowest_write.max(write_time);
+                if bytes > 0 {
+                    fastest_write = fastest_write.min(write_time);
+                    slowest_write = slowest_write.max(write_time);
+                }
                 
                 if completed % 50 == 0 || completed == num_files {
                     let progress = (completed as f64 / num_files as f64) * 100.0;
                     info!(
-                        "⏳ Progress: {}/{} files ({:.1}%) - Latest: file_{:06} ({:.1}MB in {:?})",
+                        "⏳ Progress: {}/{} files ({:.1}%) - Latest: file_{:06} ({:.1} MiB in {:?})",
                         completed, num_files, progress, file_idx,
-                        bytes as f64 / 1024.0 / 1024.0, write_time
+                        units::mib(bytes as u64), write_time
                     );
                 }
             }
@@ -655,48 +2051,60 @@ async fn run_data_generation(config: &DlioConfig) -> Result<()> {
     }
 
     let generation_time = start_time.elapsed();
-    let throughput_mbps = (total_bytes as f64 / 1024.0 / 1024.0) / generation_time.as_secs_f64();
+    let throughput = units::Throughput::new(total_bytes, generation_time.as_secs_f64());
     
     info!("✅ PARALLEL data generation completed!");
     info!("📊 Performance Summary:");
     info!("   • Files: {} generated", completed);
-    info!("   • Data: {:.2} GB written", total_bytes as f64 / 1024.0 / 1024.0 / 1024.0);
+    info!("   • Data: {} written", units::fmt_bytes(total_bytes));
     info!("   • Time: {:?}", generation_time);
-    info!("   • Throughput: {:.1} MB/s", throughput_mbps);
+    info!("   • Throughput: {}", throughput.display());
     info!("   • Write times: {:.2?} (fastest) to {:.2?} (slowest)", fastest_write, slowest_write);
     info!("   • Speedup: ~{}x faster than sequential", concurrency);
-    
-    Ok(())
-}
+    if let Some(sampler) = size_sampler.as_ref().filter(|_| component_payloads.is_none()) {
+        let sizes: Vec<usize> = (0..num_files as u64).map(|i| sampler.record_size(i)).collect();
+        let histogram = dl_driver_core::size_distribution::SizeHistogram::new(sampler.distribution(), &sizes);
+        info!("   • Record sizes: {} mean {}, stdev {}, {} to {}", histogram.distribution.name(),
+              units::fmt_bytes(histogram.mean_bytes as u64), units::fmt_bytes(histogram.stdev_bytes as u64),
+              units::fmt_bytes(histogram.min_bytes as u64), units::fmt_bytes(histogram.max_bytes as u64));
+        for line in histogram.lines() {
+            info!("       {}", line);
+        }
+    }
+    if let Some(existing) = &existing {
+        let resumed = existing.summary();
+        info!("   • Resumed: {} of {} existing objects kept ({}), {} rewritten for a size mismatch",
+              resumed.skipped, resumed.listed, units::fmt_bytes(resumed.skipped_bytes), resumed.size_mismatched);
+    }
 
-/// Generate synthetic data for testing (shared utility)
-fn generate_synthetic_data(samples: usize, record_size: usize) -> Vec<u8> {
-    let total_size = samples * record_size;
-    let mut data = vec![0u8; total_size];
-    
-    // Fill with some pattern for testing
-    for i in 0..total_size {
-        data[i] = (i % 256) as u8;
+    let mp = multipart_stats.summary();
+    if mp.multipart_objects > 0 {
+        info!("   • Multipart: {} of {} objects, {} uploads (p50 {:.2}ms, p99 {:.2}ms, max {:.2}ms), {} retries",
+              mp.multipart_objects, mp.objects, mp.uploads,
+              mp.upload_p50_ms, mp.upload_p99_ms, mp.upload_max_ms, mp.part_retries);
     }
-    
-    data
+
+    // The eval split (num_files_eval) goes to its own folder so training never lists it
+    if let Some(eval) = dl_driver_core::evaluation::eval_split_config(config) {
+        info!("🧪 Generating eval split: {} files in {}", eval.dataset.num_files_train.unwrap_or(0), eval.data_folder_uri());
+        Box::pin(run_data_generation(&eval, skip_existing)).await?;
+    }
+
+    Ok(())
 }
 
-async fn validate_dlio_config(config_path: &std::path::Path, to_json: bool) -> Result<()> {
+async fn validate_dlio_config(config_path: &std::path::Path, to_json: bool, probe: bool) -> Result<()> {
     info!("Validating DLIO config: {:?}", config_path);
 
-    // Load and parse YAML
-    let yaml_content = std::fs::read_to_string(config_path)?;
-
     if to_json {
-        // Convert YAML to JSON and print
-        let json_content = dl_driver_core::dlio_compat::yaml_to_json(&yaml_content)?;
-        println!("{}", json_content);
+        // Print the composed config (hydra defaults resolved, `workload:` unwrapped) as JSON
+        let composed = dl_driver_core::dlio_compat::hydra::compose_file(config_path).context(ExitStatus::ConfigError)?;
+        println!("{}", serde_json::to_string_pretty(&composed)?);
         return Ok(());
     }
 
     // Parse as DLIO config
-    let dlio_config = DlioConfig::from_yaml(&yaml_content)?;
+    let dlio_config = DlioConfig::from_yaml_file(config_path).context(ExitStatus::ConfigError)?;
 
     // Validate essential fields
     println!("✅ YAML parsing: SUCCESS");
@@ -740,6 +2148,10 @@ async fn validate_dlio_config(config_path: &std::path::Path, to_json: bool) -> R
     // Test RunPlan conversion (using flat RunPlan structure)
     let run_plan = dlio_config.to_run_plan()?;
     println!("✅ RunPlan conversion: SUCCESS");
+    for (phase, reader) in [("Eval", &run_plan.reader.eval), ("Checkpoint read", &run_plan.reader.checkpoint)] {
+        println!("  - {} reader: batch size {}, prefetch {}, read threads {}",
+            phase, reader.batch_size, reader.prefetch, reader.read_threads);
+    }
     
     // Display model info
     if let Some(model) = &dlio_config.model {
@@ -772,9 +2184,24 @@ async fn validate_dlio_config(config_path: &std::path::Path, to_json: bool) -> R
                        run_plan.dataset.train.num_samples_per_file;
     let total_bytes = total_samples * run_plan.dataset.train.record_length_bytes;
     
-    println!("  - Total: {} samples, {:.2} MB",
+    println!("  - Total: {} samples, {}",
         total_samples,
-        total_bytes as f64 / 1024.0 / 1024.0);
+        units::fmt_bytes(total_bytes as u64));
+
+    // Credentials and permissions, end to end, before a long job finds out
+    if probe {
+        let reports = dl_driver_core::canary::probe_all(&dlio_config).await;
+        for report in &reports {
+            for step in &report.steps {
+                println!("{} Probe {} {}: {} ({:.1} ms)",
+                    if step.ok { "✅" } else { "❌" }, report.target, step.step, step.detail, step.latency_ms);
+            }
+        }
+        let failed: Vec<&str> = reports.iter().filter(|r| !r.passed()).map(|r| r.target.as_str()).collect();
+        if !failed.is_empty() {
+            return Err(anyhow::anyhow!("Storage probe failed for {}", failed.join(", ")).context(ExitStatus::StorageError));
+        }
+    }
 
     println!("🎉 DLIO configuration is valid and ready to run!");
 
@@ -785,15 +2212,19 @@ async fn validate_dlio_config(config_path: &std::path::Path, to_json: bool) -> R
 async fn run_generate_only(
     config_path: &std::path::Path, 
     verbose: bool, 
-    skip_existing: bool
+    skip_existing: bool,
+    force_unlock: bool,
 ) -> Result<()> {
     use dl_driver_core::dlio_compat::DlioConfig;
     
     // Load DLIO config
-    let yaml_content = std::fs::read_to_string(config_path)
-        .with_context(|| format!("Failed to read config file {:?}", config_path))?;
-    let dlio_config = DlioConfig::from_yaml(&yaml_content)
-        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))?;
+    let mut dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+    dl_driver_core::directio::preflight(&mut dlio_config)
+        .context("DirectIO alignment preflight failed")?;
+    let mount_info = dl_driver_core::mount_probe::probe(&dlio_config);
+    dl_driver_core::striping::apply_hints(&dlio_config, mount_info.as_ref())?;
     
     if verbose {
         info!("Loaded DLIO config: data_folder = {}", dlio_config.dataset.data_folder);
@@ -802,24 +2233,783 @@ async fn run_generate_only(
         info!("Record size: {}B", dlio_config.dataset.record_length_bytes.unwrap_or(1024));
     }
     
-    // Check if data folder exists and handle skip_existing
-    if skip_existing {
-        // TODO: Add logic to check if folder exists and skip if it does
-        info!("Note: --skip-existing flag is set but not yet implemented");
-    }
-    
     // Run data generation phase
     info!("🚀 Starting data generation phase...");
-    run_data_generation(&dlio_config).await
-        .context("Data generation failed")?;
+    let lock = dl_driver_core::dataset_lock::DatasetLock::acquire(&dlio_config, "generate", force_unlock).await
+        .context(ExitStatus::StorageError)?;
+    let generated = run_data_generation(&dlio_config, skip_existing).await;
+    if let Some(lock) = lock {
+        lock.release().await.context(ExitStatus::StorageError)?;
+    }
+    generated
+        .context("Data generation failed")
+        .context(ExitStatus::StorageError)?;
     
     info!("✅ Data generation completed successfully");
     Ok(())
 }
 
-/// Apply sharding strategy to distribute files across ranks
-fn apply_sharding_strategy(
-    files: &[String],
+/// Overwrite workload - repeatedly rewrites a working set to measure churn behavior
+async fn run_overwrite(
+    config_path: &std::path::Path,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+
+    let runner = dl_driver_core::overwrite::OverwriteRunner::new(dlio_config);
+    let report = runner.run().await.context("Overwrite workload failed")?;
+    machine::set_results(serde_json::to_value(&report)?);
+
+    println!("\n=== Overwrite Workload Summary ===");
+    println!("Prefix: {}", report.prefix_uri);
+    println!("Working set: {} objects × {} bytes", report.working_set, report.object_size_bytes);
+    for it in &report.iterations {
+        println!("  Pass {:>4}: mean {:>8.2}ms  p99 {:>8.2}ms  max {:>8.2}ms  ({:.2}s)",
+                 it.iteration + 1, it.mean_ms, it.p99_ms, it.max_ms, it.elapsed_s);
+    }
+    match report.space_amplification {
+        Some(amp) => println!("Space amplification: {:.3}x ({} physical / {} logical bytes)",
+                              amp, report.physical_bytes.unwrap_or(0), report.logical_bytes),
+        None => println!("Space amplification: not exposed by backend"),
+    }
+    println!("==================================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Overwrite results saved to {:?}", results_file);
+    }
+
+    Ok(())
+}
+
+/// Checkpoint storm: this rank's share of synchronized checkpoint bursts
+async fn run_checkpoint_storm(
+    config_path: &std::path::Path,
+    rank: Option<u32>,
+    world_size: Option<u32>,
+    coord_id: Option<&str>,
+    coord_endpoint: Option<&str>,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    use dl_driver_core::checkpoint_storm::CheckpointStorm;
+    use dl_driver_core::coordination::RankCoordinator;
+    use dl_driver_core::net_coordination::NetworkCoordinator;
+
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+    let storm = CheckpointStorm::new(dlio_config).context(ExitStatus::ConfigError)?;
+
+    let (rank, world_size) = (rank.unwrap_or(0), world_size.unwrap_or(1));
+    if rank >= world_size {
+        return Err(anyhow::anyhow!("Rank {} must be less than world size {}", rank, world_size).context(ExitStatus::ConfigError));
+    }
+    // Every checkpoint starts at a barrier: the launcher's TCP server across hosts, shared memory on one host
+    let mut clock_offset = None;
+    let net = match coord_endpoint.filter(|_| world_size > 1) {
+        Some(endpoint) => {
+            let coord = NetworkCoordinator::new(endpoint, rank, world_size);
+            clock_offset = Some(coord.estimate_clock_offset(8).await.context("Failed to estimate clock offset from coordinator")?);
+            Some(coord)
+        }
+        None => None,
+    };
+    let shm = match (world_size > 1 && net.is_none()).then(|| {
+        let name = config_path.file_stem().and_then(|s| s.to_str()).unwrap_or("dlio");
+        let id = coord_id.map(str::to_string).unwrap_or_else(|| format!("storm_{}_{}", name, world_size));
+        RankCoordinator::new(rank, world_size, &id)
+    }) {
+        Some(coord) => {
+            let coord = coord.context("Failed to create rank coordinator")?;
+            coord.register_and_wait().await.context("Failed to register with coordination group")?;
+            Some(coord)
+        }
+        None => None,
+    };
+
+    let (net, shm) = (&net, &shm);
+    let report = storm
+        .run(rank, move |checkpoint| async move {
+            let barrier = format!("checkpoint_{}", checkpoint);
+            if let Some(coord) = net {
+                coord.barrier(&barrier, std::time::Duration::from_secs(600)).await?;
+            }
+            if let Some(coord) = shm {
+                coord.barrier(&barrier).await?;
+            }
+            Ok(())
+        })
+        .await
+        .context("Checkpoint storm failed")
+        .context(ExitStatus::StorageError)?;
+    if let Some(coord) = net {
+        coord.barrier(dl_driver_core::net_coordination::FINISH_BARRIER, std::time::Duration::from_secs(300)).await
+            .context("Failed to coordinate execution finish")?;
+    }
+    if let Some(coord) = shm {
+        coord.cleanup().ok();
+    }
+
+    let mut results = serde_json::json!({ "rank": rank, "world_size": world_size });
+    if let Some(clock) = &clock_offset {
+        results["clock_sync"] = serde_json::to_value(clock)?;
+    }
+    results["checkpoint_storm"] = serde_json::to_value(&report)?;
+    machine::set_results(results.clone());
+
+    println!("\n=== Checkpoint Storm (rank {}) ===", rank);
+    for burst in &report.bursts {
+        let seconds = burst.end - burst.start;
+        println!("  Checkpoint {:>4}: {:.3}s  {}", burst.checkpoint, seconds, units::Throughput::new(burst.bytes, seconds).display());
+    }
+    if report.late > 0 {
+        println!("Late checkpoints: {} (a checkpoint overran the interval)", report.late);
+    }
+    println!("==================================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&results)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Rank {}: Checkpoint storm results saved to {:?}", rank, results_file);
+    }
+    Ok(())
+}
+
+/// Micro-benchmarks: fio-style jobs for every size × queue depth
+async fn run_micro(
+    uri: &str,
+    ops: Vec<dl_driver_core::micro::MicroOp>,
+    sizes: Vec<usize>,
+    queue_depths: Vec<usize>,
+    duration: std::time::Duration,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    use dl_driver_core::micro::{self, MicroSettings};
+
+    let settings = MicroSettings::new(uri, ops, sizes, queue_depths, duration).context(ExitStatus::ConfigError)?;
+    let report = micro::run(&settings).await.context("Micro-benchmark failed").context(ExitStatus::StorageError)?;
+    let results = serde_json::json!({ "micro": &report });
+    machine::set_results(results.clone());
+
+    println!("\n=== Micro-benchmarks: {} ===", report.uri);
+    for job in &report.jobs {
+        println!("{}", job.fio_line());
+    }
+    println!("==================================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&results)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Micro-benchmark results saved to {:?}", results_file);
+    }
+    Ok(())
+}
+
+/// Check proxy settings and connectivity to the storage endpoint
+async fn run_doctor(config_path: &std::path::Path, results_path: Option<&std::path::Path>) -> Result<()> {
+    use dl_driver_core::network::{self, IpFamily, NetworkSettings};
+
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+    println!("✅ Config: {:?}", config_path);
+
+    let settings = NetworkSettings::from_config(&dlio_config).context(ExitStatus::ConfigError)?;
+    match &settings.proxy {
+        Some(proxy) => println!("✅ Proxy: {} (bypassed for: {})", network::redact_proxy(proxy),
+                                if settings.no_proxy.is_empty() { "none".to_string() } else { settings.no_proxy.join(",") }),
+        None => println!("✅ Proxy: none (direct connections)"),
+    }
+    println!("✅ IP family: {:?}", settings.ip_family);
+
+    let Some((host, port)) = network::endpoint_for(&dlio_config, &settings).context(ExitStatus::ConfigError)? else {
+        println!("✅ Data folder {} is local storage; no network path to check", dlio_config.data_folder_uri());
+        machine::set_results(serde_json::json!({ "network": null }));
+        return Ok(());
+    };
+    let path = network::probe(&settings, &host, port).await;
+    machine::set_results(serde_json::json!({ "network": &path }));
+
+    if path.proxy.is_none() {
+        println!("{} DNS: {} -> {} (IPv4: {}, IPv6: {})", if path.resolved.is_empty() { "❌" } else { "✅" },
+                 host, if path.resolved.is_empty() { "-".to_string() } else { path.resolved.join(", ") },
+                 if path.has_ipv4 { "yes" } else { "no" }, if path.has_ipv6 { "yes" } else { "no" });
+        if settings.ip_family == IpFamily::Auto && !path.has_ipv6 {
+            println!("⚠️  {} has no IPv6 address; it is unreachable from IPv6-only networks", host);
+        }
+    }
+    match &path.error {
+        None => println!("✅ Connect: {} via {} over {} in {:.1}ms", path.endpoint,
+                         path.proxy.as_deref().unwrap_or("direct connection"),
+                         path.connected_family.as_deref().unwrap_or("?"), path.connect_ms.unwrap_or(0.0)),
+        Some(e) => println!("❌ Connect: {}: {}", path.endpoint, e),
+    }
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&serde_json::json!({ "network": &path }))?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+    }
+    if let Some(e) = path.error {
+        return Err(anyhow::anyhow!("Storage endpoint {} is unreachable: {}", path.endpoint, e).context(ExitStatus::StorageError));
+    }
+    Ok(())
+}
+
+/// Probe the dataset and print recommended reader settings
+async fn run_advise(
+    config_path: &std::path::Path,
+    probe_objects: usize,
+    max_threads: usize,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+    dl_driver_core::advisor::AdvisorInputs::from_config(&dlio_config).context(ExitStatus::ConfigError)?;
+
+    let advice = dl_driver_core::advisor::advise(&dlio_config, probe_objects, max_threads)
+        .await
+        .context("Storage probe failed")
+        .context(ExitStatus::StorageError)?;
+    machine::set_results(serde_json::to_value(&advice)?);
+
+    println!("\n=== Reader Settings Advice ===");
+    println!("Mean object size: {:.1} KiB, target AU {:.0}%", advice.mean_object_bytes / 1024.0, advice.inputs.au_threshold * 100.0);
+    for level in &advice.probe {
+        println!("  {:>3} threads: {:>9.0} objects/s  {:>9.1} MiB/s  mean {:>8.2}ms  p99 {:>8.2}ms",
+                 level.threads, level.objects_per_s, level.mib_per_s, level.latency.mean_ms, level.latency.p99_ms);
+    }
+    for (label, s) in [("Current", &advice.current), ("Recommended", &advice.recommended)] {
+        println!("{:<12} batch_size={} read_threads={} prefetch={}: predicted AU {:.1}% ({:.1}ms I/O per batch)",
+                 label, s.batch_size, s.read_threads, s.prefetch, s.predicted_au * 100.0, s.batch_io_ms);
+    }
+    for note in &advice.notes {
+        println!("⚠️  {}", note);
+    }
+    println!("\n{}", advice.snippet());
+    println!("==============================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&advice)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Advice saved to {:?}", results_file);
+    }
+
+    Ok(())
+}
+
+/// Theoretical dedup/compression ratios of the dataset content
+async fn run_analyze_data(
+    config_path: &std::path::Path,
+    files: usize,
+    chunk_kib: usize,
+    from_storage: bool,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    use dl_driver_core::dedup_analysis::DedupAnalyzer;
+    use s3dlio::object_store::store_for_uri;
+
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+
+    let mut analyzer = DedupAnalyzer::new(chunk_kib.max(1) * 1024);
+    if from_storage {
+        let data_folder = dlio_config.data_folder_uri();
+        let store = store_for_uri(data_folder)
+            .with_context(|| format!("Failed to create object store for {}", data_folder))
+            .context(ExitStatus::StorageError)?;
+        let uris = store
+            .list(data_folder, true)
+            .await
+            .with_context(|| format!("Failed to list {}", data_folder))
+            .context(ExitStatus::StorageError)?;
+        for uri in uris.iter().take(files) {
+            let data = store.get(uri).await.with_context(|| format!("Failed to read {}", uri)).context(ExitStatus::StorageError)?;
+            analyzer.add(&data);
+        }
+    } else {
+        // The same payloads generation writes: per component, seeded per file, or one shared payload
+        let num_files = dlio_config.dataset.num_files_train.unwrap_or(100).min(files);
+        let samples_per_file = dlio_config.dataset.num_samples_per_file.unwrap_or(1);
+        let aligned = |mut payload: Vec<u8>| {
+            payload.resize(dl_driver_core::directio::aligned_file_size(&dlio_config, payload.len()), 0);
+            payload
+        };
+        let record_size = dlio_config.dataset.record_length_bytes.unwrap_or(1024);
+        let shared = aligned(dl_driver_core::payload::generate_file_payload(&dlio_config, record_size, None).context(ExitStatus::ConfigError)?);
+        let content_seed = dl_driver_core::content::content_seed(&dlio_config);
+        let size_sampler = dl_driver_core::size_distribution::RecordSizeSampler::from_config(&dlio_config)
+            .context(ExitStatus::ConfigError)?;
+        for file_idx in 0..num_files {
+            match (dl_driver_core::components::components(&dlio_config), content_seed, &size_sampler) {
+                (Some(comps), _, _) => {
+                    for c in comps {
+                        let payload = dl_driver_core::components::generate_payload(&dlio_config, c, samples_per_file)
+                            .context(ExitStatus::ConfigError)?;
+                        analyzer.add(&aligned(payload));
+                    }
+                }
+                (None, None, None) => analyzer.add(&shared),
+                (None, seed, sampler) => {
+                    let record_size = sampler.as_ref().map_or(record_size, |s| s.record_size(file_idx as u64));
+                    let payload = dl_driver_core::payload::generate_file_payload(&dlio_config, record_size, seed.map(|seed| (seed, file_idx as u64)))
+                        .context(ExitStatus::ConfigError)?;
+                    analyzer.add(&aligned(payload));
+                }
+            }
+        }
+    }
+    let report = analyzer.report();
+    machine::set_results(serde_json::to_value(&report)?);
+
+    println!("\n=== Data Reduction Analysis ===");
+    println!("Objects: {} ({} identical to an earlier one), {}",
+             report.objects, report.duplicate_objects, units::fmt_bytes(report.logical_bytes));
+    println!("Chunks:  {} at ~{} KiB average, {} unique", report.chunks, report.avg_chunk_bytes / 1024, report.unique_chunks);
+    println!("Dedup (variable block): {:.2}x", report.dedup_ratio);
+    println!("Dedup (fixed block):    {:.2}x", report.fixed_block_dedup_ratio);
+    println!("Compression (zstd):     {:.2}x", report.compression_ratio);
+    println!("Combined (estimate):    {:.2}x", report.combined_ratio);
+    println!("\n{}", report.verdict);
+    println!("===============================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Analysis saved to {:?}", results_file);
+    }
+
+    Ok(())
+}
+
+/// Time a dataset clone and the first epoch trained from it
+async fn run_clone_bench(
+    config_path: &std::path::Path,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+
+    let benchmark = dl_driver_core::clone_bench::CloneBenchmark::from_config(dlio_config)?;
+    let report = benchmark.run().await.context("Clone benchmark failed")?;
+    report.print_summary();
+    machine::set_results(serde_json::to_value(&report)?);
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Clone benchmark results saved to {:?}", results_file);
+    }
+
+    Ok(())
+}
+
+/// Replay a recorded batch arrival schedule to separate storage from consumer bottlenecks
+async fn run_replay(
+    trace_path: &std::path::Path,
+    with_compute: bool,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    let trace = dl_driver_core::arrivals::ArrivalTrace::load(trace_path)?;
+    let report = dl_driver_core::arrivals::replay(&trace, with_compute).await
+        .context("Arrival replay failed")?;
+    machine::set_results(serde_json::to_value(&report)?);
+
+    println!("\n=== Batch Arrival Replay ===");
+    println!("Trace: {:?} ({})", trace_path, trace.data_folder);
+    println!("Consumer: {}", if with_compute { "recorded compute time" } else { "no-op" });
+    for epoch in &report.epochs {
+        println!("  Epoch {:>3}: {:>6} batches  recorded {:>10.1}ms  replay {:>10.1}ms  ratio {:.2}",
+                 epoch.epoch + 1, epoch.batches, epoch.recorded_ms, epoch.replay_ms, epoch.ratio);
+    }
+    println!("Bottleneck: {} (replay/recorded = {:.2})", report.bottleneck, report.ratio);
+    println!("============================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Replay results saved to {:?}", results_file);
+    }
+
+    Ok(())
+}
+
+/// Re-issue an s3dlio op-log's GETs and PUTs, optionally against other storage
+async fn run_op_replay(
+    op_log_path: &std::path::Path,
+    options: &dl_driver_core::op_replay::ReplayOptions,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    let log = dl_driver_core::op_replay::OpLog::load(op_log_path).context(ExitStatus::ConfigError)?;
+    let report = dl_driver_core::op_replay::replay(&log, options).await
+        .context("Op-log replay failed")?;
+    machine::set_results(serde_json::to_value(&report)?);
+
+    println!("\n=== Op-Log Replay ===");
+    println!("Op-log: {:?} ({} operations, {} skipped)", op_log_path, report.ops, report.skipped);
+    if let (Some(prefix), Some(target)) = (&report.source_prefix, &report.target) {
+        println!("Target: {} -> {}", prefix, target);
+    }
+    println!("Pace: {}", options.speed.map(|s| format!("{}x recorded", s)).unwrap_or_else(|| "back to back".to_string()));
+    for (name, stats) in [("GET", &report.get), ("PUT", &report.put)] {
+        println!("  {}: {:>8} ops  {:>6} errors  {}  p50 {:.2}ms  p99 {:.2}ms{}", name, stats.count, stats.errors,
+                 stats.throughput.display(), stats.p50_ms, stats.p99_ms,
+                 stats.recorded_p50_ms.map(|ms| format!("  (logged p50 {:.2}ms)", ms)).unwrap_or_default());
+    }
+    println!("Duration: replay {:.3}s, log {:.3}s", report.replay_seconds, report.recorded_seconds);
+    if let Some(lag) = report.lag_p99_ms {
+        println!("Issue lag behind schedule: p99 {:.2}ms", lag);
+    }
+    println!("=====================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Replay results saved to {:?}", results_file);
+    }
+
+    Ok(())
+}
+
+/// Run reference DLIO and dl-driver on one config/dataset and flag metric divergences
+async fn run_crosscheck(
+    config_path: &std::path::Path,
+    dlio_bin: &std::path::Path,
+    tolerance: f64,
+    output_dir: &std::path::Path,
+    results_path: Option<&std::path::Path>,
+) -> Result<()> {
+    use dl_driver_core::crosscheck::{self, NormalizedMetrics};
+
+    let dlio_config = DlioConfig::from_yaml_file(config_path)
+        .with_context(|| format!("Failed to parse DLIO config from {:?}", config_path))
+        .context(ExitStatus::ConfigError)?;
+
+    // DLIO runs first and generates the dataset if the workflow asks for it
+    let summary = crosscheck::run_dlio(dlio_bin, config_path, &dlio_config, output_dir).await
+        .context("Reference DLIO run failed")?;
+
+    // dl-driver then trains on the same dataset without regenerating it
+    let mut train_config = dlio_config.clone();
+    if let Some(workflow) = train_config.workflow.as_mut() {
+        workflow.generate_data = Some(false);
+    }
+    info!("🦀 Running dl-driver on the same dataset");
+    let mut workload_runner = dl_driver_core::WorkloadRunner::new(train_config.clone());
+    workload_runner.run_training_phase().await
+        .context("dl-driver training workload failed")?;
+    let ours = workload_runner.get_metrics().to_json(0, &train_config);
+
+    let report = crosscheck::compare(
+        NormalizedMetrics::from_dl_driver(&ours, &dlio_config),
+        NormalizedMetrics::from_dlio_summary(&summary),
+        tolerance,
+    );
+    crosscheck::log_divergences(&report);
+    machine::set_results(serde_json::to_value(&report)?);
+
+    println!("\n=== DLIO Crosscheck (tolerance {:.1}%) ===", tolerance * 100.0);
+    println!("{:<28} {:>14} {:>14} {:>10}", "Metric", "dl-driver", "DLIO", "Diff");
+    for c in &report.comparisons {
+        let fmt = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.2}", v));
+        println!("{:<28} {:>14} {:>14} {:>9}{}",
+                 c.metric, fmt(c.dl_driver), fmt(c.dlio),
+                 c.relative_diff.map_or("-".to_string(), |d| format!("{:.1}%", d * 100.0)),
+                 if c.within_tolerance { "" } else { " ⚠️" });
+    }
+    println!("Result: {}", if report.pass { "✅ PASS" } else { "❌ DIVERGED" });
+    println!("=========================================\n");
+
+    if let Some(results_file) = results_path {
+        std::fs::write(results_file, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write results to: {:?}", results_file))?;
+        info!("Crosscheck report saved to {:?}", results_file);
+    }
+
+    if !report.pass {
+        return Err(anyhow::anyhow!("dl-driver diverges from DLIO on: {}", report.diverged.join(", "))
+            .context(ExitStatus::SloFail));
+    }
+    Ok(())
+}
+
+/// Run `dl-driver <args>` as a child process and fail on a non-zero exit
+async fn run_child(args: &[String]) -> Result<()> {
+    let exe = std::env::current_exe().context("Cannot locate the dl-driver executable")?;
+    let status = tokio::process::Command::new(exe)
+        .args(args)
+        .status()
+        .await
+        .context("Failed to start dl-driver")?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("dl-driver {} exited with {}", args.first().map_or("", String::as_str), status));
+    }
+    Ok(())
+}
+
+/// Run a benchmark suite: generate each config's data once, run its repetitions, optionally clean up
+async fn run_suite(
+    suite_path: &std::path::Path,
+    output: &std::path::Path,
+    work_dir: &std::path::Path,
+    cleanup: bool,
+    verbose: u8,
+) -> Result<()> {
+    use dl_driver_core::suite::{self, RunMetrics, SuiteManifest, SuiteReport, SuiteRun};
+
+    let manifest = SuiteManifest::load(suite_path)?;
+    let cleanup = cleanup || manifest.cleanup;
+    std::fs::create_dir_all(work_dir).with_context(|| format!("Failed to create {:?}", work_dir))?;
+    let verbosity: Vec<String> = (verbose > 0).then(|| format!("-{}", "v".repeat(verbose as usize))).into_iter().collect();
+    info!("📋 Suite of {} configs from {:?}", manifest.runs.len(), suite_path);
+
+    let mut runs = Vec::new();
+    for (index, entry) in manifest.runs.iter().enumerate() {
+        let name = entry.name();
+        let stem = suite::staged_stem(index, &name);
+        let config_str = entry.config.display().to_string();
+        let failed_run = |repetition, error: String| SuiteRun {
+            name: name.clone(),
+            config: entry.config.clone(),
+            repetition,
+            passed: false,
+            error: Some(error),
+            metrics: RunMetrics::default(),
+        };
+
+        let prepared = async {
+            let config = DlioConfig::from_yaml_file(&entry.config)
+                .with_context(|| format!("Failed to parse DLIO config from {:?}", entry.config))?;
+            if config.should_generate_data() {
+                info!("📦 [{}] Generating data", name);
+                run_child(&[verbosity.clone(), vec!["generate".into(), "--config".into(), config_str.clone()]].concat()).await?;
+            }
+            // Repetitions train on the generated dataset instead of regenerating it
+            let staged = work_dir.join(format!("{}.yaml", stem));
+            std::fs::write(&staged, suite::staged_config(&entry.config)?)
+                .with_context(|| format!("Failed to stage config {:?}", staged))?;
+            anyhow::Ok((config, staged))
+        }
+        .await;
+        let (config, staged) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                warn!("❌ [{}] Setup failed: {:#}", name, e);
+                runs.push(failed_run(0, format!("{:#}", e)));
+                continue;
+            }
+        };
+
+        for repetition in 1..=entry.repetitions.max(1) {
+            info!("▶️  [{}] Repetition {}/{}", name, repetition, entry.repetitions.max(1));
+            let results = work_dir.join(format!("{}_rep{}_results.json", stem, repetition));
+            let args = [
+                verbosity.clone(),
+                vec!["run".into(), "--config".into(), staged.display().to_string(), "--results".into(), results.display().to_string()],
+            ]
+            .concat();
+            let outcome = async {
+                run_child(&args).await?;
+                let content = artifacts::read_to_string(&results)
+                    .with_context(|| format!("Failed to read results {:?}", results))?;
+                anyhow::Ok(RunMetrics::from_results(&serde_json::from_str(&content)?))
+            }
+            .await;
+            runs.push(match outcome {
+                Ok(metrics) => SuiteRun {
+                    name: name.clone(),
+                    config: entry.config.clone(),
+                    repetition,
+                    passed: true,
+                    error: None,
+                    metrics,
+                },
+                Err(e) => {
+                    warn!("❌ [{}] Repetition {} failed: {:#}", name, repetition, e);
+                    failed_run(repetition, format!("{:#}", e))
+                }
+            });
+        }
+
+        if cleanup && config.should_generate_data() {
+            if let Err(e) = suite::cleanup_dataset(&config).await {
+                warn!("⚠️  [{}] Dataset cleanup failed: {:#}", name, e);
+            }
+        }
+    }
+
+    let report = SuiteReport::new(runs);
+    report.print_table();
+    machine::set_results(serde_json::to_value(&report)?);
+    std::fs::write(output, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("Failed to write suite report to {:?}", output))?;
+    info!("Suite report saved to {:?}", output);
+
+    if report.failed() > 0 {
+        return Err(anyhow::anyhow!("{} of {} suite runs failed", report.failed(), report.runs.len()));
+    }
+    Ok(())
+}
+
+/// Convert rank results into an external reporting format
+async fn convert_results(inputs: &str, to: &str, output: &std::path::Path) -> Result<()> {
+    let paths = glob_artifacts(inputs)?;
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!("No files found matching pattern: {}", inputs));
+    }
+
+    let mut results = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let content = artifacts::read_to_string(path)
+            .with_context(|| format!("Failed to read result file: {:?}", path))?;
+        results.push(serde_json::from_str::<serde_json::Value>(&content)
+            .with_context(|| format!("Failed to parse JSON from: {:?}", path))?);
+    }
+
+    let written = match to.to_lowercase().as_str() {
+        "mlperf-storage" => dl_driver_core::mlperf_storage::convert(&results, output)?,
+        other => return Err(anyhow::anyhow!("Unsupported report format '{}'. Use 'mlperf-storage'", other)),
+    };
+
+    println!("✅ Converted {} result files to {} format:", paths.len(), to);
+    for path in written {
+        println!("  {}", path.display());
+    }
+    Ok(())
+}
+
+/// Export results files for sharing, replacing identifying strings when redacting
+async fn export_results(
+    inputs: &str,
+    output: &std::path::Path,
+    redact: bool,
+    mapping_path: Option<&std::path::Path>,
+) -> Result<()> {
+    let results = load_rank_results(inputs).await?;
+    std::fs::create_dir_all(output)
+        .with_context(|| format!("Failed to create export directory {:?}", output))?;
+
+    // One redactor for the whole export keeps pseudonyms consistent across files
+    let mut redactor = dl_driver_core::redact::Redactor::new();
+    let mut exported = Vec::with_capacity(results.len());
+    for (name, mut data) in results {
+        if redact {
+            redactor.redact(&mut data);
+        }
+        // Exports are written as plain JSON, so a compressed input loses its .zst suffix
+        let name = name.strip_suffix(artifacts::ZSTD_SUFFIX).map_or_else(|| name.clone(), str::to_string);
+        let file_name = if redact { redactor.redact_text(&name) } else { name };
+        let path = output.join(&file_name);
+        std::fs::write(&path, serde_json::to_string_pretty(&data)?)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        exported.push(path);
+    }
+
+    if let Some(path) = mapping_path {
+        std::fs::write(path, serde_json::to_string_pretty(&redactor.mapping())?)
+            .with_context(|| format!("Failed to write pseudonym mapping to {:?}", path))?;
+        info!("Pseudonym mapping saved to {:?} (do not share)", path);
+    }
+    machine::set_results(serde_json::json!({ "redacted": redact, "files": exported }));
+
+    println!("✅ Exported {} results files{}:", exported.len(), if redact { " (redacted)" } else { "" });
+    for path in &exported {
+        println!("  {}", path.display());
+    }
+    Ok(())
+}
+
+/// Project client-count scaling and check measured multi-client results against it
+async fn project_scaling(
+    single_host: &str,
+    measured: Option<&str>,
+    options: &dl_driver_core::scaling::ProjectionOptions,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    use dl_driver_core::scaling::{ScalingPoint, ScalingReport};
+
+    let host_points = load_rank_results(single_host)
+        .await?
+        .iter()
+        .map(|(name, data)| ScalingPoint::single_host(name, data))
+        .collect::<Result<Vec<_>>>()?;
+    let measured_points = match measured {
+        Some(pattern) => load_rank_results(pattern)
+            .await?
+            .iter()
+            .map(|(name, data)| ScalingPoint::multi_client(name, data))
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+
+    let report = ScalingReport::new(host_points, &measured_points, options)?;
+    report.print_table();
+    machine::set_results(serde_json::to_value(&report)?);
+
+    if let Some(path) = output {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write scaling report to: {:?}", path))?;
+        info!("Scaling projection saved to {:?}", path);
+    }
+    Ok(())
+}
+
+/// Summarize a run against an optional base run and upload the summary for PR automation
+async fn publish_report(
+    inputs: &str,
+    base: Option<&str>,
+    to: Option<&str>,
+    label: &str,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    use dl_driver_core::publish::{PublishSummary, PUBLISH_URI_ENV};
+    use dl_driver_core::results_db::RunRecord;
+
+    let record = |results: Vec<(String, serde_json::Value)>, source: &str| {
+        let ranks: Vec<serde_json::Value> = results.into_iter().map(|(_, data)| data).collect();
+        RunRecord::from_rank_results(&ranks, source).context(ExitStatus::ConfigError)
+    };
+    let run = record(load_rank_results(inputs).await?, inputs)?;
+    let base = match base {
+        Some(pattern) => Some(record(load_rank_results(pattern).await?, pattern)?),
+        None => None,
+    };
+    let summary = PublishSummary::new(label, run, base);
+    let files = summary.files()?;
+
+    if let Some(dir) = output {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create output directory {:?}", dir))?;
+        for (name, data) in &files {
+            std::fs::write(dir.join(name), data).with_context(|| format!("Failed to write {:?}", dir.join(name)))?;
+        }
+        info!("Run summary written to {:?}", dir);
+    }
+    let prefix = to.map(str::to_string).or_else(|| std::env::var(PUBLISH_URI_ENV).ok().filter(|v| !v.is_empty()));
+    let uploaded = match &prefix {
+        Some(prefix) => dl_driver_core::results_upload::upload(prefix, files).await.context(ExitStatus::StorageError)?,
+        None => {
+            info!("No --to or ${}: summary not uploaded", PUBLISH_URI_ENV);
+            Vec::new()
+        }
+    };
+    machine::set_results(serde_json::json!({ "summary": summary, "badge": summary.badge(), "uploaded": uploaded }));
+
+    print!("{}", summary.markdown());
+    if summary.regression {
+        warn!("📉 Throughput regression of {:.1}% against the base run",
+              -summary.base.as_ref().and_then(|b| b.throughput_change_pct).unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// Apply sharding strategy to distribute files across ranks
+fn apply_sharding_strategy(
+    files: &[String],
     rank: u32,
     world_size: u32,
     strategy: &str,
@@ -853,14 +3043,10 @@ fn apply_sharding_strategy(
             files[start..end].to_vec()
         }
         "hash" => {
-            // Hash-based distribution: consistent but pseudo-random
+            // Hash-based distribution: consistent but pseudo-random, and stable across releases
             files
                 .iter()
-                .filter(|f| {
-                    let mut hasher = DefaultHasher::new();
-                    f.hash(&mut hasher);
-                    (hasher.finish() % world_size as u64) as usize == rank
-                })
+                .filter(|f| (dl_driver_core::rng::stable_hash(f.as_bytes()) % world_size as u64) as usize == rank)
                 .cloned()
                 .collect()
         }
@@ -880,32 +3066,211 @@ fn apply_sharding_strategy(
     Ok(sharded)
 }
 
+/// Launch ranks locally or over SSH, aggregate their results, and exit with the combined rank exit code
+async fn run_launch(opts: &launch::LaunchOptions, ssh: Option<&launch::SshOptions>) -> Result<()> {
+    let exits = match ssh {
+        Some(ssh) => launch::launch_ssh(opts, ssh).await?,
+        None => launch::launch_local(opts).await?,
+    };
+    finish_launch(opts, &exits).await
+}
+
+/// Aggregate the rank results of a launch or fleet run when every rank succeeded
+async fn finish_launch(opts: &launch::LaunchOptions, exits: &[launch::RankExit]) -> Result<()> {
+    let code = launch::combined_exit_code(exits);
+    let results_dir = &opts.results_dir;
+
+    if code == 0 {
+        let output = results_dir.join("aggregated_results.json");
+        aggregate_rank_results(
+            &launch::rank_results_glob(results_dir),
+            &output,
+            opts.strict_au(),
+            None,
+            Some(exits.len()),
+            std::time::Duration::ZERO,
+            None,
+            Some(&results_dir.join("aggregated_results.csv")),
+        )
+        .await
+        .context("Failed to aggregate rank results")?;
+        println!("✅ {} ranks completed; aggregated results in {:?}", exits.len(), output);
+        return Ok(());
+    }
+
+    // The launcher exits with the first failed rank's code
+    let failed: Vec<String> = exits.iter().filter(|e| e.code != 0).map(|e| e.rank.to_string()).collect();
+    Err(anyhow::anyhow!("❌ Rank(s) {} failed (exit code {}); skipping aggregation", failed.join(", "), code)
+        .context(ExitStatus::from_code(code)))
+}
+
+/// Add a run to the results database from its rank results and print its history line
+async fn history_add(db: &std::path::Path, inputs: &str) -> Result<()> {
+    use dl_driver_core::results_db;
+
+    let mut ranks = Vec::new();
+    for path in glob_artifacts(inputs)? {
+        let text = artifacts::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        ranks.push(serde_json::from_str::<serde_json::Value>(&text).with_context(|| format!("Failed to parse {:?}", path))?);
+    }
+    if ranks.is_empty() {
+        return Err(anyhow::anyhow!("No rank results match {}", inputs).context(ExitStatus::ConfigError));
+    }
+    let record = results_db::add(db, results_db::RunRecord::from_rank_results(&ranks, inputs)?)?;
+    println!("{}", record.history_line());
+    if record.regression {
+        warn!("📉 Throughput regression of {:.1}% since the previous {} run", -record.throughput_change_pct.unwrap_or_default(), record.config);
+    }
+    machine::set_results(serde_json::to_value(&record)?);
+    Ok(())
+}
+
+/// Print the results database, oldest run first
+async fn history_show(db: &std::path::Path, config: Option<&str>, last: Option<usize>, json: bool) -> Result<()> {
+    let mut records = dl_driver_core::results_db::load(db)?;
+    records.retain(|r| config.is_none_or(|c| r.config == c));
+    records.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    if let Some(last) = last {
+        records.drain(..records.len().saturating_sub(last));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&records)?);
+    } else if records.is_empty() {
+        println!("No runs in {:?}", db);
+    } else {
+        for record in &records {
+            println!("{}", record.history_line());
+        }
+    }
+    machine::set_results(serde_json::to_value(&records)?);
+    Ok(())
+}
+
+/// Agent token from the environment, for `serve` and `fleet run` without --token
+fn serve_token() -> Option<String> {
+    std::env::var(serve::TOKEN_ENV).ok().filter(|t| !t.is_empty())
+}
+
+/// How often `aggregate --expect-ranks` re-checks its inputs
+const AGGREGATE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How long ranks that finished generating wait for the slowest one
+const GENERATION_BARRIER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(24 * 3600);
+
+/// Parse a wait duration such as "90", "90s", "10m" or "1h"
+fn parse_wait_duration(value: &str) -> Result<std::time::Duration, String> {
+    let value = value.trim();
+    let (number, unit_secs) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 3600),
+        _ => (value, 1),
+    };
+    let n = number
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| format!("invalid duration '{}' (expected e.g. 90s, 10m, 1h)", value))?;
+    n.checked_mul(unit_secs)
+        .map(std::time::Duration::from_secs)
+        .ok_or_else(|| format!("duration '{}' is too large", value))
+}
+
+/// Local files matching a glob; a compressed artifact (`<name>.zst`) matches as its uncompressed name would
+fn glob_artifacts(pattern: &str) -> Result<Vec<std::path::PathBuf>> {
+    let mut paths = Vec::new();
+    for pattern in [pattern.to_string(), format!("{}{}", pattern, artifacts::ZSTD_SUFFIX)] {
+        for path in glob::glob(&pattern).with_context(|| format!("Failed to glob pattern: {}", pattern))? {
+            paths.push(path?);
+        }
+    }
+    // Sorted, a compressed artifact follows its uncompressed name; keep one of the two
+    paths.sort();
+    paths.dedup_by(|later, earlier| *later == artifacts::output_path(earlier, true));
+    Ok(paths)
+}
+
+/// Load and parse every rank result as (name, JSON), from a results URI or a local glob
+async fn load_rank_results(inputs: &str) -> Result<Vec<(String, serde_json::Value)>> {
+    let sources: Vec<(String, String)> = if dl_driver_core::results_upload::is_results_uri(inputs) {
+        dl_driver_core::results_upload::fetch_rank_results(inputs).await?
+            .into_iter()
+            .map(|(uri, content)| (uri.rsplit('/').next().unwrap_or(&uri).to_string(), content))
+            .collect()
+    } else {
+        glob_artifacts(inputs)?
+            .iter()
+            .map(|path| {
+                let content = artifacts::read_to_string(path)
+                    .with_context(|| format!("Failed to read result file: {:?}", path))?;
+                Ok((path.file_name().unwrap_or_default().to_string_lossy().to_string(), content))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    if sources.is_empty() {
+        return Err(anyhow::anyhow!("No files found matching pattern: {}", inputs));
+    }
+
+    sources
+        .into_iter()
+        .map(|(name, content)| {
+            let data = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse JSON from: {}", name))?;
+            Ok((name, data))
+        })
+        .collect()
+}
+
 /// Aggregate results from multiple rank JSON files
 async fn aggregate_rank_results(
     inputs: &str,
     output: &std::path::Path,
     strict_au: bool,
     au_threshold: Option<f64>,
+    expect_ranks: Option<usize>,
+    wait_timeout: std::time::Duration,
+    baseline_path: Option<&std::path::Path>,
+    csv_path: Option<&std::path::Path>,
 ) -> Result<()> {
-    use glob::glob;
     use serde_json::Value;
     
     info!("Aggregating results from pattern: {}", inputs);
-    
-    // Find all matching files
-    let paths: Vec<_> = glob(inputs)
-        .with_context(|| format!("Failed to glob pattern: {}", inputs))?
-        .collect::<Result<Vec<_>, _>>()?;
-        
-    if paths.is_empty() {
-        return Err(anyhow::anyhow!("No files found matching pattern: {}", inputs));
-    }
-    
-    info!("Found {} result files to aggregate", paths.len());
-    
+
+    // With --expect-ranks, poll until every rank's results have landed (and parse)
+    let deadline = std::time::Instant::now() + wait_timeout;
+    let ranks = loop {
+        let loaded = load_rank_results(inputs).await;
+        let Some(expected) = expect_ranks else {
+            break loaded?;
+        };
+        let found = loaded.as_ref().map_or(0, |ranks| ranks.len());
+        if found >= expected {
+            if found > expected {
+                warn!("⚠️  Found {} rank results, more than the {} expected", found, expected);
+            }
+            break loaded?;
+        }
+        if let Err(e) = &loaded {
+            debug!("Rank results not ready: {:#}", e);
+        }
+        if std::time::Instant::now() >= deadline {
+            return Err(anyhow::anyhow!(
+                "Timed out after {:?} waiting for {} rank results in {} ({} present)",
+                wait_timeout, expected, inputs, found
+            ));
+        }
+        info!("⏳ {}/{} rank results present in {}, waiting...", found, expected, inputs);
+        tokio::time::sleep(AGGREGATE_POLL_INTERVAL.min(deadline.saturating_duration_since(std::time::Instant::now()))).await;
+    };
+    info!("Found {} result files to aggregate", ranks.len());
+    // Ranks of one run share one config file
+    let config_sha256 = dl_driver_core::manifest::verify_config_hashes(&ranks).context(ExitStatus::ConfigError)?;
+
     let mut aggregated = serde_json::json!({
         "aggregated_results": {
-            "total_ranks": paths.len(),
+            "total_ranks": ranks.len(),
+            "config_sha256": config_sha256,
             "global_metrics": {},
             "rank_details": []
         }
@@ -916,14 +3281,51 @@ async fn aggregate_rank_results(
     let mut total_bytes_read = 0u64;
     let mut min_start_time = f64::MAX;
     let mut max_end_time = 0.0_f64;
+
+    // Per-rank clock offsets, re-referenced to rank 0's clock
+    use dl_driver_core::net_coordination::ClockOffset;
+    let offsets: Vec<Option<ClockOffset>> = ranks
+        .iter()
+        .map(|(_, data)| data.get("clock_sync").and_then(|v| serde_json::from_value(v.clone()).ok()))
+        .collect();
+    let reference = ranks
+        .iter()
+        .position(|(_, data)| data.get("rank").and_then(|v| v.as_u64()) == Some(0))
+        .and_then(|i| offsets[i])
+        .unwrap_or_default();
+    let clock_corrected = offsets.iter().all(Option::is_some);
+
+    // `bench checkpoint` rank results aggregate into the storm view
+    if ranks.iter().all(|(_, data)| data.get("checkpoint_storm").is_some()) {
+        use dl_driver_core::checkpoint_storm::{StormRankReport, StormReport};
+        let storm_ranks = ranks
+            .iter()
+            .zip(&offsets)
+            .map(|((name, data), offset)| {
+                let report: StormRankReport = serde_json::from_value(data["checkpoint_storm"].clone())
+                    .with_context(|| format!("Invalid checkpoint_storm results in {}", name))?;
+                Ok((offset.map(|o| o.relative_to(&reference)).unwrap_or_default(), report))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let aggregated = serde_json::json!({
+            "aggregated_results": {
+                "total_ranks": ranks.len(),
+                "checkpoint_storm": StormReport::aggregate(&storm_ranks, clock_corrected),
+            }
+        });
+        machine::set_results(aggregated.clone());
+        std::fs::write(output, serde_json::to_string_pretty(&aggregated)?)
+            .with_context(|| format!("Failed to write aggregated results to: {:?}", output))?;
+        info!("✅ Aggregated checkpoint storm results written to: {:?}", output);
+        return Ok(());
+    }
+    let mut clock_uncertainty_ns = 0u64;
     
     // Process each rank result file
-    for (rank_idx, path) in paths.iter().enumerate() {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read result file: {:?}", path))?;
-        let rank_data: Value = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse JSON from: {:?}", path))?;
-            
+    for (rank_idx, (name, rank_data)) in ranks.iter().enumerate() {
+        let clock = offsets[rank_idx].map(|o| o.relative_to(&reference)).unwrap_or_default();
+        clock_uncertainty_ns = clock_uncertainty_ns.max(clock.uncertainty_ns);
+
         // Extract metrics from rank data
         if let Some(metrics) = rank_data.get("metrics") {
             if let Some(throughput) = metrics.get("storage_throughput_gib_s").and_then(|v| v.as_f64()) {
@@ -939,80 +3341,100 @@ async fn aggregate_rank_results(
         
         // Track timing for global AU calculation
         if let Some(start) = rank_data.get("start_time").and_then(|v| v.as_f64()) {
-            min_start_time = min_start_time.min(start);
+            min_start_time = min_start_time.min(clock.correct(start));
         }
         if let Some(end) = rank_data.get("end_time").and_then(|v| v.as_f64()) {
-            max_end_time = max_end_time.max(end);
+            max_end_time = max_end_time.max(clock.correct(end));
         }
         
         // Add rank details to aggregated results
         aggregated["aggregated_results"]["rank_details"].as_array_mut().unwrap()
             .push(serde_json::json!({
                 "rank": rank_idx,
-                "file": path.file_name().unwrap_or_default().to_string_lossy(),
-                "metrics": rank_data.get("metrics").cloned().unwrap_or(Value::Null)
+                "file": name,
+                "hostname": rank_data.get("hostname").cloned().unwrap_or(Value::Null),
+                "clock_offset_seconds": clock.offset_ns as f64 / 1e9,
+                "metrics": rank_data.get("metrics").cloned().unwrap_or(Value::Null),
+                "derived": rank_data.get("derived").cloned().unwrap_or(Value::Null),
+                "startup": rank_data.get("startup").cloned().unwrap_or(Value::Null),
+                "compute_profile": rank_data.get("compute_profile").cloned().unwrap_or(Value::Null)
             }));
     }
     
     // Calculate global metrics
     let global_runtime = max_end_time - min_start_time;
     
-    // Plan A1: Multi-GPU AU aggregation - sum compute times and wall clock times across all GPUs
-    let mut total_compute_time = 0.0;
-    let mut total_wall_clock_time = 0.0;
-    let mut gpu_count = 0u32;
-    
-    // Re-read rank files to aggregate AU calculation data
-    for path in &paths {
-        if let Ok(content) = std::fs::read_to_string(path) {
-            if let Ok(rank_data) = serde_json::from_str::<Value>(&content) {
-                if let Some(metrics) = rank_data.get("metrics") {
-                    // Sum total compute time from all GPUs
-                    if let Some(compute_ms) = metrics.get("total_compute_time_ms").and_then(|v| v.as_f64()) {
-                        total_compute_time += compute_ms / 1000.0; // Convert to seconds
-                    }
-                    // Sum wall clock time from all GPUs
-                    if let Some(wall_ms) = metrics.get("wall_clock_time_ms").and_then(|v| v.as_f64()) {
-                        total_wall_clock_time += wall_ms / 1000.0; // Convert to seconds
-                    }
-                    gpu_count += 1;
-                }
-            }
-        }
-    }
-    
-    // Plan A1: Global AU = Total GPU compute time / (Total wall clock time across all GPUs)
-    let global_au = if total_wall_clock_time > 0.0 && gpu_count > 0 {
-        // Multi-GPU AU: aggregate utilization across all GPUs
-        let average_wall_clock = total_wall_clock_time / gpu_count as f64;
-        (total_compute_time / average_wall_clock).min(1.0) // Cap at 100%
-    } else {
-        0.0
-    };
+    // Plan A1: Multi-GPU AU aggregation - every accelerator's AU, each rank weighted by the
+    // accelerators it stands for (derived.accelerators, 1 for results without it)
+    let rank_au: Vec<(u64, f64)> = ranks
+        .iter()
+        .filter_map(|(_, rank_data)| {
+            let metrics = rank_data.get("metrics")?;
+            let au = metrics.get("au_fraction").and_then(|v| v.as_f64()).or_else(|| {
+                let compute_ms = metrics.get("total_compute_time_ms").and_then(|v| v.as_f64())?;
+                let wall_ms = metrics.get("wall_clock_time_ms").and_then(|v| v.as_f64()).filter(|&w| w > 0.0)?;
+                Some(compute_ms / wall_ms)
+            })?;
+            Some((rank_data["derived"]["accelerators"].as_u64().unwrap_or(1), au.min(1.0)))
+        })
+        .collect();
+    let gpu_count: u64 = rank_au.iter().map(|&(a, _)| a).sum();
+    let global_au = dl_driver_core::accelerators::weighted_au(&rank_au);
     
-    info!("Plan A1 Multi-GPU AU: {:.1}% across {} GPUs (total_compute={:.3}s, avg_wall_clock={:.3}s)", 
-          global_au * 100.0, gpu_count, total_compute_time, total_wall_clock_time / gpu_count.max(1) as f64);
+    info!("Plan A1 Multi-GPU AU: {:.1}% across {} GPUs on {} ranks", global_au * 100.0, gpu_count, rank_au.len());
     
+    // The job trains once its slowest rank has its first batch
+    let time_to_first_batch = ranks
+        .iter()
+        .filter_map(|(_, data)| data["startup"]["time_to_first_batch_s"].as_f64())
+        .reduce(f64::max);
+
     aggregated["aggregated_results"]["global_metrics"] = serde_json::json!({
         "total_throughput_gib_s": total_throughput,
+        "total_throughput": units::Throughput::from_gib_s(total_throughput),
         "total_files_processed": total_files_processed,
         "total_bytes_read": total_bytes_read,
         "global_runtime_seconds": global_runtime,
+        // Start and end may come from different ranks, each off by up to the clock uncertainty
+        "global_runtime_uncertainty_seconds": 2.0 * clock_uncertainty_ns as f64 / 1e9,
+        "clock_corrected": clock_corrected,
         "global_au": global_au,
+        "time_to_first_batch_s": time_to_first_batch,
         "pass": !strict_au || global_au >= au_threshold.unwrap_or(0.9)
     });
+    let mut derived = dl_driver_core::derived::Derived::from_results(&aggregated);
+    if let Some(path) = baseline_path {
+        derived = derived.with_baseline(path).context(ExitStatus::ConfigError)?;
+    }
+    aggregated["aggregated_results"]["derived"] = serde_json::to_value(derived)?;
+
+    // Mixed fleets: each compute profile's share of the global AU
+    let profiles = dl_driver_core::compute_profiles::summarize(&ranks.iter().map(|(_, data)| data).collect::<Vec<_>>());
+    for profile in &profiles {
+        info!("🧮 Profile '{}': {} ranks, {} GPUs, {:.3}s per step, AU {:.1}%, {:.2} GiB/s",
+              profile.name, profile.ranks.len(), profile.accelerators, profile.computation_time, profile.au * 100.0, profile.throughput_gib_s);
+    }
+    if !profiles.is_empty() {
+        aggregated["aggregated_results"]["compute_profiles"] = serde_json::to_value(&profiles)?;
+    }
     
     // Write aggregated results
+    machine::set_results(aggregated.clone());
     std::fs::write(output, serde_json::to_string_pretty(&aggregated)?)
         .with_context(|| format!("Failed to write aggregated results to: {:?}", output))?;
         
     info!("✅ Aggregated results written to: {:?}", output);
-    info!("Global metrics: {:.2} GiB/s throughput, {} files, {:.2}s runtime", 
-          total_throughput, total_files_processed, global_runtime);
+    if let Some(path) = csv_path {
+        std::fs::write(path, dl_driver_core::results_csv::aggregated_to_csv(&aggregated))
+            .with_context(|| format!("Failed to write aggregated CSV to: {:?}", path))?;
+        info!("📄 Aggregated CSV written to: {:?}", path);
+    }
+    info!("Global metrics: {} throughput, {} files, {:.2}s runtime", 
+          units::Throughput::from_gib_s(total_throughput).display(), total_files_processed, global_runtime);
     
     if strict_au && global_au < au_threshold.unwrap_or(0.9) {
         return Err(anyhow::anyhow!("Global AU {:.3} below threshold {:.3}", 
-                                  global_au, au_threshold.unwrap_or(0.9)));
+                                  global_au, au_threshold.unwrap_or(0.9)).context(ExitStatus::AuFail));
     }
     
     Ok(())
@@ -0,0 +1,887 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/cli/src/serve.rs
+//
+// Benchmark agent: `dl-driver serve --listen 0.0.0.0:8470`
+//
+// A small REST API so a central dashboard can drive dl-driver on many client
+// nodes without SSH. Every run is a `dl-driver run` child process with its own
+// directory under --state-dir (config.yaml, results.json, control.sock):
+//
+//   GET  /health                  agent version and the active run, if any
+//   POST /runs[?start=true]       submit a DLIO config (YAML or JSON body) → 201 {"id": ...}
+//   GET  /runs                    every run this agent has seen since it started
+//   GET  /runs/{id}               state, exit code and exit status of one run
+//   POST /runs/{id}/start         start a submitted run (409 while another run is active)
+//   POST /runs/{id}/stop[?force]  `stop` over the control socket, SIGTERM after the grace period
+//   GET  /runs/{id}/events        NDJSON stream: log lines, periodic metrics, the final exit
+//   GET  /runs/{id}/results       the run's results JSON once it has exited
+//
+// One run is active at a time, since concurrent runs on one node measure each
+// other; only ranks of one `fleet run` (same group, see fleet.rs) run together.
+// With --token (or DL_DRIVER_SERVE_TOKEN), every request except /health needs
+// `Authorization: Bearer <token>`. The API is plain HTTP/1.1, one request per
+// connection; put it behind a TLS proxy when the network is untrusted.
+// Configs with shell hooks (clone_benchmark/tier_migration `command`) are
+// refused with 403 unless the agent runs with --allow-shell-hooks.
+// With --grpc-listen, the same runs are also served over gRPC (see fleet.rs).
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UnixStream};
+use tokio::process::Command;
+use tokio::sync::{broadcast, oneshot};
+use tracing::{info, warn};
+
+use dl_driver_core::exit_status::ExitStatus;
+use dl_driver_core::DlioConfig;
+
+/// Default listen address of `serve`
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8470";
+/// Environment variable holding the bearer token when --token is not given
+pub const TOKEN_ENV: &str = "DL_DRIVER_SERVE_TOKEN";
+
+/// Largest request head (request line and headers)
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// Largest request body (a config)
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Events kept per run for clients that connect late
+const EVENT_BACKLOG: usize = 10_000;
+/// Time a run gets to finish after `stop` before it is sent SIGTERM
+const STOP_GRACE: Duration = Duration::from_secs(60);
+/// Time a run gets after SIGTERM before it is killed
+const KILL_GRACE: Duration = Duration::from_secs(10);
+
+/// `run` flags a remote caller may pass in `run_args`, and whether each takes a value.
+/// Flags naming local files (--results, --output, --record-oplog, --control-socket, ...)
+/// are set by the agent or not at all.
+const REMOTE_RUN_FLAGS: &[(&str, bool)] = &[
+    ("--rank", true),
+    ("--world-size", true),
+    ("--coord-endpoint", true),
+    ("--coord-id", true),
+    ("--coord", true),
+    ("--start-at-epoch", true),
+    ("--start-in", true),
+    ("--max-epochs", true),
+    ("--max-steps", true),
+    ("--pool-size", true),
+    ("--readahead", true),
+    ("--max-inflight", true),
+    ("--timeout", true),
+    ("--accelerators", true),
+    ("--gpus", true),
+    ("--gpus-per-rank", true),
+    ("--repeats", true),
+    ("--aggregate", true),
+    ("--seed-sweep", true),
+    ("--shard-strategy", true),
+    ("--format", true),
+    ("--compress", true),
+    ("--timeline", true),
+    ("--mlperf", false),
+    ("--strict-au", false),
+    ("--discard-outliers", false),
+    ("--hdf5-as-npz", false),
+    ("--use-real-gpus", false),
+];
+
+/// Check remotely supplied `run` arguments against REMOTE_RUN_FLAGS
+pub fn check_run_args(run_args: &[String]) -> Result<(), String> {
+    let mut args = run_args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        let Some(&(_, takes_value)) = REMOTE_RUN_FLAGS.iter().find(|(name, _)| *name == flag) else {
+            return Err(format!("Run argument '{}' is not allowed from a remote caller", arg));
+        };
+        match (takes_value, inline_value) {
+            (false, Some(_)) => return Err(format!("{} takes no value", flag)),
+            (false, None) | (true, Some(_)) => {}
+            (true, None) => match args.next() {
+                // A value that looks like a flag would be parsed as one
+                Some(value) if !value.starts_with('-') => {}
+                _ => return Err(format!("{} needs a value", flag)),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// `serve` settings
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub listen: String,
+    pub state_dir: PathBuf,
+    pub token: Option<String>,
+    /// Also serve the gRPC agent protocol (see fleet.rs) on this address
+    pub grpc_listen: Option<String>,
+    /// Seconds between metrics snapshots on the event stream (0 = none)
+    pub metrics_interval: u64,
+    /// `-v` count passed on to runs, so their progress logs reach the event stream
+    pub verbose: u8,
+    /// Accept configs with shell hooks (clone_benchmark.command, tier_migration.command)
+    pub allow_shell_hooks: bool,
+}
+
+/// One parsed HTTP request
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Header value by case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    /// Whether a query flag is set (`?force`, `?force=true`, `?force=1`)
+    pub fn flag(&self, name: &str) -> bool {
+        self.query.iter().any(|(k, v)| k == name && matches!(v.as_str(), "" | "true" | "1" | "yes"))
+    }
+}
+
+/// Read one request; None when the client closed the connection before sending one
+pub async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Request>> {
+    let mut line = String::new();
+    let mut head_bytes = 0;
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    head_bytes += line.len();
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next()) else {
+        anyhow::bail!("Malformed request line '{}'", line.trim());
+    };
+    if !version.starts_with("HTTP/1.") {
+        anyhow::bail!("Unsupported protocol '{}'", version);
+    }
+    let method = method.to_string();
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target.to_string(), Vec::new()),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            anyhow::bail!("Connection closed inside the request head");
+        }
+        head_bytes += line.len();
+        if head_bytes > MAX_HEAD_BYTES {
+            anyhow::bail!("Request head exceeds {} bytes", MAX_HEAD_BYTES);
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').with_context(|| format!("Malformed header '{}'", header))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let mut request = Request { method, path, query, headers, body: Vec::new() };
+    if let Some(length) = request.header("content-length") {
+        let length: usize = length.parse().with_context(|| format!("Invalid Content-Length '{}'", length))?;
+        if length > MAX_BODY_BYTES {
+            anyhow::bail!("Request body of {} bytes exceeds {} bytes", length, MAX_BODY_BYTES);
+        }
+        request.body = vec![0; length];
+        reader.read_exact(&mut request.body).await.context("Connection closed inside the request body")?;
+    } else if request.header("transfer-encoding").is_some() {
+        anyhow::bail!("Chunked request bodies are not supported; send Content-Length");
+    }
+    Ok(Some(request))
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((k, v)) => (k.to_string(), v.to_string()),
+            None => (pair.to_string(), String::new()),
+        })
+        .collect()
+}
+
+/// API endpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+    Health,
+    ListRuns,
+    CreateRun,
+    GetRun(String),
+    StartRun(String),
+    StopRun(String),
+    Events(String),
+    Results(String),
+    NotFound,
+    MethodNotAllowed,
+}
+
+pub fn route(method: &str, path: &str) -> Route {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let get = method == "GET";
+    let post = method == "POST";
+    match segments.as_slice() {
+        ["health"] if get => Route::Health,
+        ["runs"] if get => Route::ListRuns,
+        ["runs"] if post => Route::CreateRun,
+        ["runs", id] if get => Route::GetRun(id.to_string()),
+        ["runs", id, "start"] if post => Route::StartRun(id.to_string()),
+        ["runs", id, "stop"] if post => Route::StopRun(id.to_string()),
+        ["runs", id, "events"] if get => Route::Events(id.to_string()),
+        ["runs", id, "results"] if get => Route::Results(id.to_string()),
+        ["health"] | ["runs"] | ["runs", _] | ["runs", _, "start" | "stop" | "events" | "results"] => Route::MethodNotAllowed,
+        _ => Route::NotFound,
+    }
+}
+
+/// Whether the request carries the agent's bearer token (always true without one)
+pub fn authorized(token: Option<&str>, request: &Request) -> bool {
+    match token {
+        None => true,
+        Some(token) => request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|presented| presented.trim() == token),
+    }
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64())
+}
+
+/// Lifecycle of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunState {
+    Created,
+    Running,
+    Exited,
+}
+
+/// What `GET /runs/{id}` reports
+#[derive(Debug, Clone, Serialize)]
+pub struct RunStatus {
+    pub id: String,
+    pub state: RunState,
+    pub created_unix_s: f64,
+    pub started_unix_s: Option<f64>,
+    pub finished_unix_s: Option<f64>,
+    pub exit_code: Option<i32>,
+    /// ExitStatus name of the exit code ("success", "au_fail", ...)
+    pub exit_status: Option<&'static str>,
+    pub stop_requested: bool,
+    pub dir: PathBuf,
+}
+
+/// A run and its event history
+pub struct Run {
+    status: Mutex<RunStatus>,
+    /// `run` arguments beyond the config, results and control socket (rank, world size, ...)
+    run_args: Vec<String>,
+    /// Fleet run this run is a rank of; runs of one group may be active together
+    group: Option<String>,
+    events: Mutex<VecDeque<Value>>,
+    live: broadcast::Sender<Value>,
+    terminate: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl Run {
+    pub fn new(id: String, dir: PathBuf, run_args: Vec<String>, group: Option<String>) -> Self {
+        Self {
+            status: Mutex::new(RunStatus {
+                id,
+                state: RunState::Created,
+                created_unix_s: unix_now(),
+                started_unix_s: None,
+                finished_unix_s: None,
+                exit_code: None,
+                exit_status: None,
+                stop_requested: false,
+                dir,
+            }),
+            run_args,
+            group,
+            events: Mutex::new(VecDeque::new()),
+            live: broadcast::channel(1024).0,
+            terminate: Mutex::new(None),
+        }
+    }
+
+    pub fn status(&self) -> RunStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Record an event and send it to connected streams
+    fn publish(&self, mut event: Value) {
+        event["time_unix_s"] = json!(unix_now());
+        let mut events = self.events.lock().unwrap();
+        if events.len() == EVENT_BACKLOG {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+        // No receivers is fine: nobody is streaming
+        let _ = self.live.send(event);
+    }
+
+    /// Past events and a receiver for the ones after them, without gaps or repeats
+    pub fn subscribe(&self) -> (Vec<Value>, broadcast::Receiver<Value>) {
+        let events = self.events.lock().unwrap();
+        (events.iter().cloned().collect(), self.live.subscribe())
+    }
+
+    pub fn results_path(&self) -> PathBuf {
+        self.status.lock().unwrap().dir.join("results.json")
+    }
+
+    fn control_socket(&self) -> PathBuf {
+        self.status.lock().unwrap().dir.join("control.sock")
+    }
+}
+
+/// Runs of this agent, shared by the REST and gRPC APIs
+pub struct Agent {
+    options: ServeOptions,
+    runs: Mutex<BTreeMap<String, Arc<Run>>>,
+    next_id: std::sync::atomic::AtomicU64,
+}
+
+impl Agent {
+    pub fn new(options: ServeOptions) -> Self {
+        Self { options, runs: Mutex::new(BTreeMap::new()), next_id: Default::default() }
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.options.token.as_deref()
+    }
+
+    pub fn run(&self, id: &str) -> Option<Arc<Run>> {
+        self.runs.lock().unwrap().get(id).cloned()
+    }
+
+    pub fn active_run(&self) -> Option<String> {
+        let runs = self.runs.lock().unwrap();
+        runs.values().map(|run| run.status()).find(|s| s.state == RunState::Running).map(|s| s.id)
+    }
+
+    /// Validate and store a config as a new run (400 for an invalid config)
+    pub fn submit(&self, yaml: &str, run_args: Vec<String>, group: Option<String>) -> Result<Arc<Run>, Response> {
+        if let Err(message) = check_run_args(&run_args) {
+            return Err(error(400, message));
+        }
+        // YAML is a superset of JSON, so either body parses here
+        let config = match DlioConfig::from_yaml(yaml) {
+            Ok(config) => config,
+            Err(e) => return Err(error(400, format!("Invalid config: {:#}", e))),
+        };
+        if !self.options.allow_shell_hooks {
+            if let Some(hook) = shell_hook(&config) {
+                return Err(error(403, format!("{} runs a shell command; start the agent with --allow-shell-hooks to accept it", hook)));
+            }
+        }
+
+        let seq = self.next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let id = format!("run_{}_{:03}", unix_now() as u64, seq);
+        let dir = self.options.state_dir.join("runs").join(&id);
+        if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(dir.join("config.yaml"), yaml)) {
+            return Err(error(500, format!("Failed to store config in {:?}: {}", dir, e)));
+        }
+        let run = Arc::new(Run::new(id.clone(), dir, run_args, group));
+        self.runs.lock().unwrap().insert(id.clone(), Arc::clone(&run));
+        info!("📥 Run {} submitted", id);
+        Ok(run)
+    }
+}
+
+/// The first config setting that makes a run execute a shell command, if any
+pub fn shell_hook(config: &DlioConfig) -> Option<&'static str> {
+    let shell = |hook: Option<&String>, command: Option<&String>| command.is_some() || hook.is_some_and(|h| h == "shell");
+    if let Some(clone) = &config.clone_benchmark {
+        if shell(clone.hook.as_ref(), clone.command.as_ref()) {
+            return Some("clone_benchmark.command");
+        }
+    }
+    if let Some(migration) = &config.tier_migration {
+        if shell(migration.hook.as_ref(), migration.command.as_ref()) {
+            return Some("tier_migration.command");
+        }
+    }
+    None
+}
+
+/// A response with an HTTP status code and a JSON body
+#[derive(Debug)]
+pub struct Response {
+    pub code: u16,
+    pub body: Value,
+}
+
+fn respond(code: u16, body: Value) -> Response {
+    Response { code, body }
+}
+
+fn error(code: u16, message: impl Into<String>) -> Response {
+    respond(code, json!({"error": message.into()}))
+}
+
+fn reason(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, response: &Response) -> Result<()> {
+    let body = serde_json::to_vec_pretty(&response.body)?;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.code,
+        reason(response.code),
+        body.len() + 1
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.write_all(b"\n").await?;
+    Ok(())
+}
+
+/// Serve the API until the process is interrupted
+pub async fn serve(options: ServeOptions) -> Result<()> {
+    std::fs::create_dir_all(options.state_dir.join("runs"))
+        .with_context(|| format!("Failed to create state directory {:?}", options.state_dir))?;
+    let listener = TcpListener::bind(&options.listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", options.listen))?;
+    info!(
+        "🛰️  dl-driver agent listening on http://{} (state in {:?}{})",
+        listener.local_addr()?,
+        options.state_dir,
+        if options.token.is_some() { ", token required" } else { "" }
+    );
+    if options.token.is_none() {
+        let mut exposed = vec![listener.local_addr()?];
+        if let Some(grpc_listen) = &options.grpc_listen {
+            exposed.extend(grpc_listen.to_socket_addrs().into_iter().flatten());
+        }
+        for addr in exposed.iter().filter(|addr| !addr.ip().is_loopback()) {
+            warn!("⚠️  Listening on {} without --token: anyone who can reach this port can start runs", addr);
+        }
+    }
+
+    let grpc_listen = options.grpc_listen.clone();
+    let agent = Arc::new(Agent::new(options));
+    if let Some(grpc_listen) = grpc_listen {
+        let grpc = crate::fleet::serve_grpc(grpc_listen, Arc::clone(&agent));
+        tokio::spawn(async move {
+            if let Err(e) = grpc.await {
+                warn!("❌ gRPC agent service stopped: {:#}", e);
+            }
+        });
+    }
+    loop {
+        let (stream, peer) = listener.accept().await.context("Failed to accept a connection")?;
+        let agent = Arc::clone(&agent);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(agent, stream).await {
+                warn!("Request from {} failed: {:#}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(agent: Arc<Agent>, mut stream: TcpStream) -> Result<()> {
+    let request = {
+        let mut reader = BufReader::new(&mut stream);
+        read_request(&mut reader).await
+    };
+    let request = match request {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            let code = if e.to_string().contains("exceeds") { 413 } else { 400 };
+            return write_response(&mut stream, &error(code, format!("{:#}", e))).await;
+        }
+    };
+
+    let route = route(&request.method, &request.path);
+    if route != Route::Health && !authorized(agent.options.token.as_deref(), &request) {
+        return write_response(&mut stream, &error(401, "Missing or wrong bearer token")).await;
+    }
+    let response = match route {
+        Route::Health => respond(200, json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+            "active_run": agent.active_run(),
+        })),
+        Route::ListRuns => {
+            let runs: Vec<RunStatus> = agent.runs.lock().unwrap().values().map(|run| run.status()).collect();
+            respond(200, json!({"runs": runs}))
+        }
+        Route::CreateRun => create_run(&agent, &request).await,
+        Route::GetRun(id) => match agent.run(&id) {
+            Some(run) => respond(200, json!(run.status())),
+            None => error(404, format!("No run '{}'", id)),
+        },
+        Route::StartRun(id) => match agent.run(&id) {
+            Some(run) => start_run(&agent, &run),
+            None => error(404, format!("No run '{}'", id)),
+        },
+        Route::StopRun(id) => match agent.run(&id) {
+            Some(run) => stop_run(&run, request.flag("force")).await,
+            None => error(404, format!("No run '{}'", id)),
+        },
+        Route::Events(id) => match agent.run(&id) {
+            Some(run) => return stream_events(&mut stream, &run).await,
+            None => error(404, format!("No run '{}'", id)),
+        },
+        Route::Results(id) => match agent.run(&id) {
+            Some(run) => results(&run),
+            None => error(404, format!("No run '{}'", id)),
+        },
+        Route::NotFound => error(404, format!("No endpoint {}", request.path)),
+        Route::MethodNotAllowed => error(405, format!("{} is not allowed on {}", request.method, request.path)),
+    };
+    write_response(&mut stream, &response).await
+}
+
+async fn create_run(agent: &Arc<Agent>, request: &Request) -> Response {
+    let Ok(yaml) = std::str::from_utf8(&request.body) else {
+        return error(400, "Config body is not UTF-8");
+    };
+    let run = match agent.submit(yaml, Vec::new(), None) {
+        Ok(run) => run,
+        Err(response) => return response,
+    };
+    if request.flag("start") {
+        let started = start_run(agent, &run);
+        if started.code != 202 {
+            return started;
+        }
+    }
+    respond(201, json!(run.status()))
+}
+
+/// Start a submitted run (202; 409 while a run outside its group is active or when it already ran)
+pub fn start_run(agent: &Arc<Agent>, run: &Arc<Run>) -> Response {
+    // Checking and claiming under the runs lock keeps two starts from racing
+    let runs = agent.runs.lock().unwrap();
+    let same_group = |other: &Run| run.group.is_some() && other.group == run.group;
+    if let Some(active) = runs.values().find(|r| r.status().state == RunState::Running && !same_group(r)) {
+        return error(409, format!("Run {} is active; stop it or wait for it to exit", active.status().id));
+    }
+    let mut status = run.status.lock().unwrap();
+    if status.state != RunState::Created {
+        return error(409, format!("Run {} was already started", status.id));
+    }
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return error(500, format!("Cannot locate the dl-driver executable: {}", e)),
+    };
+    let mut command = Command::new(exe);
+    if agent.options.verbose > 0 {
+        command.arg(format!("-{}", "v".repeat(agent.options.verbose as usize)));
+    }
+    command
+        .arg("run")
+        .arg("--config")
+        .arg(status.dir.join("config.yaml"))
+        .arg("--results")
+        .arg(status.dir.join("results.json"))
+        .arg("--control-socket")
+        .arg(status.dir.join("control.sock"))
+        .args(&run.run_args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return error(500, format!("Failed to start run {}: {}", status.id, e)),
+    };
+
+    status.state = RunState::Running;
+    status.started_unix_s = Some(unix_now());
+    let (terminate, terminated) = oneshot::channel();
+    *run.terminate.lock().unwrap() = Some(terminate);
+    info!("🚀 Run {} started", status.id);
+    let response = respond(202, json!(*status));
+    drop(status);
+    drop(runs);
+
+    tokio::spawn(supervise(Arc::clone(run), child, terminated));
+    if agent.options.metrics_interval > 0 {
+        tokio::spawn(poll_metrics(Arc::clone(run), Duration::from_secs(agent.options.metrics_interval)));
+    }
+    response
+}
+
+/// Forward a child's output lines as log events
+async fn forward_events<R: AsyncRead + Unpin>(reader: R, run: Arc<Run>, stream: &'static str) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        run.publish(json!({"type": "log", "stream": stream, "line": line}));
+    }
+}
+
+/// Wait for a run to exit, terminating it on request, and publish the exit
+async fn supervise(run: Arc<Run>, mut child: tokio::process::Child, terminated: oneshot::Receiver<()>) {
+    let stdout = tokio::spawn(forward_events(child.stdout.take().expect("piped stdout"), Arc::clone(&run), "stdout"));
+    let stderr = tokio::spawn(forward_events(child.stderr.take().expect("piped stderr"), Arc::clone(&run), "stderr"));
+
+    let exit = tokio::select! {
+        exit = child.wait() => exit,
+        Ok(()) = terminated => {
+            if let Some(pid) = child.id() {
+                // SIGTERM lets the run exit as interrupted; SIGKILL only if it hangs
+                unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+            }
+            match tokio::time::timeout(KILL_GRACE, child.wait()).await {
+                Ok(exit) => exit,
+                Err(_) => {
+                    let _ = child.kill().await;
+                    child.wait().await
+                }
+            }
+        }
+    };
+    let _ = tokio::join!(stdout, stderr);
+
+    // A signal death has no code; it counts as an interrupted run
+    let code = match exit {
+        Ok(exit) => exit.code().unwrap_or(ExitStatus::Interrupted.code()),
+        Err(_) => ExitStatus::Error.code(),
+    };
+    let exit_status = ExitStatus::from_code(code).name();
+    let id = {
+        let mut status = run.status.lock().unwrap();
+        status.state = RunState::Exited;
+        status.finished_unix_s = Some(unix_now());
+        status.exit_code = Some(code);
+        status.exit_status = Some(exit_status);
+        status.id.clone()
+    };
+    run.terminate.lock().unwrap().take();
+    info!("🏁 Run {} exited with {} ({})", id, code, exit_status);
+    run.publish(json!({"type": "exit", "exit_code": code, "exit_status": exit_status}));
+}
+
+/// Send one command to a run's control socket and read its reply
+async fn control_command(socket: &Path, command: &str) -> Result<Value> {
+    let mut stream = UnixStream::connect(socket)
+        .await
+        .with_context(|| format!("Cannot connect to control socket {:?}", socket))?;
+    stream.write_all(format!("{}\n", command).as_bytes()).await?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).await?;
+    serde_json::from_str(&reply).with_context(|| format!("Malformed control reply '{}'", reply.trim()))
+}
+
+/// Publish a metrics snapshot every `interval` while the run is active
+async fn poll_metrics(run: Arc<Run>, interval: Duration) {
+    let socket = run.control_socket();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        if run.status().state != RunState::Running {
+            break;
+        }
+        // The socket only exists once training has started
+        if let Ok(reply) = control_command(&socket, "dump-metrics-now").await {
+            run.publish(json!({"type": "metrics", "metrics": reply["metrics"]}));
+        }
+    }
+}
+
+/// Stop a running run: gracefully over its control socket unless `force`, then terminate it
+pub async fn stop_run(run: &Arc<Run>, force: bool) -> Response {
+    let status = run.status();
+    if status.state != RunState::Running {
+        return error(409, format!("Run {} is not running", status.id));
+    }
+    run.status.lock().unwrap().stop_requested = true;
+
+    let graceful = !force && control_command(&run.control_socket(), "stop").await.is_ok_and(|reply| reply["ok"] == true);
+    let grace = if graceful { STOP_GRACE } else { Duration::ZERO };
+    if graceful {
+        info!("🛑 Run {} asked to stop after its current step", status.id);
+    } else {
+        info!("🛑 Terminating run {}", status.id);
+    }
+    let run = Arc::clone(run);
+    tokio::spawn(async move {
+        tokio::time::sleep(grace).await;
+        if let Some(terminate) = run.terminate.lock().unwrap().take() {
+            let _ = terminate.send(());
+        }
+    });
+    respond(202, json!({"id": status.id, "graceful": graceful, "terminate_after_s": grace.as_secs()}))
+}
+
+/// Stream a run's events as NDJSON until it exits or the client goes away
+async fn stream_events(stream: &mut TcpStream, run: &Arc<Run>) -> Result<()> {
+    let (past, mut live) = run.subscribe();
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n")
+        .await?;
+
+    let is_exit = |event: &Value| event["type"] == "exit";
+    for event in &past {
+        write_event(stream, event).await?;
+        if is_exit(event) {
+            return Ok(());
+        }
+    }
+    loop {
+        match live.recv().await {
+            Ok(event) => {
+                write_event(stream, &event).await?;
+                if is_exit(&event) {
+                    return Ok(());
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                write_event(stream, &json!({"type": "lagged", "skipped": skipped})).await?;
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn write_event(stream: &mut TcpStream, event: &Value) -> Result<()> {
+    let mut line = serde_json::to_vec(event)?;
+    line.push(b'\n');
+    stream.write_all(&line).await?;
+    Ok(())
+}
+
+fn results(run: &Arc<Run>) -> Response {
+    let status = run.status();
+    if status.state != RunState::Exited {
+        return error(409, format!("Run {} has not exited yet", status.id));
+    }
+    let path = run.results_path();
+    match dl_driver_core::artifacts::read_to_string(&path) {
+        Ok(text) => match serde_json::from_str(&text) {
+            Ok(results) => respond(200, results),
+            Err(e) => error(500, format!("Results of run {} are not valid JSON: {}", status.id, e)),
+        },
+        Err(_) => error(404, format!("Run {} wrote no results (exit status {})", status.id, status.exit_status.unwrap_or("unknown"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_parsing_routing_and_auth() {
+        let raw = b"POST /runs?start=true HTTP/1.1\r\nHost: agent\r\nAuthorization: Bearer s3cret\r\nContent-Length: 11\r\n\r\nreader: {}\nextra";
+        let mut reader = BufReader::new(&raw[..]);
+        let request = read_request(&mut reader).await.unwrap().unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/runs"));
+        assert!(request.flag("start") && !request.flag("force"));
+        assert_eq!(request.header("content-length"), Some("11"));
+        assert_eq!(request.body, b"reader: {}\n");
+        assert!(authorized(Some("s3cret"), &request));
+        assert!(!authorized(Some("other"), &request));
+        assert!(authorized(None, &request));
+
+        let oversized = format!("POST /runs HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_BYTES + 1);
+        assert!(read_request(&mut BufReader::new(oversized.as_bytes())).await.is_err());
+        assert!(read_request(&mut BufReader::new(&b"garbage\r\n\r\n"[..])).await.is_err());
+        assert!(read_request(&mut BufReader::new(&b""[..])).await.unwrap().is_none());
+
+        assert_eq!(route("GET", "/health"), Route::Health);
+        assert_eq!(route("POST", "/runs/"), Route::CreateRun);
+        assert_eq!(route("GET", "/runs/run_1_000"), Route::GetRun("run_1_000".to_string()));
+        assert_eq!(route("POST", "/runs/r/stop"), Route::StopRun("r".to_string()));
+        assert_eq!(route("GET", "/runs/r/events"), Route::Events("r".to_string()));
+        assert_eq!(route("DELETE", "/runs/r"), Route::MethodNotAllowed);
+        assert_eq!(route("GET", "/runs/r/start"), Route::MethodNotAllowed);
+        assert_eq!(route("GET", "/metrics"), Route::NotFound);
+    }
+
+    #[test]
+    fn test_remote_run_args_allowlist() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(check_run_args(&args(&["--rank", "1", "--world-size=4", "--coord-endpoint", "head:7000", "--strict-au"])).is_ok());
+        assert!(check_run_args(&[]).is_ok());
+        assert!(check_run_args(&args(&["--results", "/etc/cron.d/x"])).is_err());
+        assert!(check_run_args(&args(&["--record-oplog=/tmp/ops.jsonl"])).is_err());
+        assert!(check_run_args(&args(&["--rank", "--control-socket", "/tmp/s"])).is_err());
+        assert!(check_run_args(&args(&["--rank"])).is_err());
+        assert!(check_run_args(&args(&["--strict-au=yes"])).is_err());
+        assert!(check_run_args(&args(&["positional"])).is_err());
+    }
+
+    #[test]
+    fn test_shell_hooks_need_opt_in() {
+        let base = "dataset:\n  data_folder: file:///tmp/data\nreader: {}\n";
+        let plain = DlioConfig::from_yaml(base).unwrap();
+        assert_eq!(shell_hook(&plain), None);
+        let copy = DlioConfig::from_yaml(&format!("{}clone_benchmark:\n  hook: copy\n", base)).unwrap();
+        assert_eq!(shell_hook(&copy), None);
+        let clone = DlioConfig::from_yaml(&format!("{}clone_benchmark:\n  command: cp -r {{source_path}} {{target_path}}\n", base)).unwrap();
+        assert_eq!(shell_hook(&clone), Some("clone_benchmark.command"));
+        let migration = DlioConfig::from_yaml(&format!("{}tier_migration:\n  hook: shell\n", base)).unwrap();
+        assert_eq!(shell_hook(&migration), Some("tier_migration.command"));
+
+        let state_dir = tempfile::tempdir().unwrap();
+        let options = |allow_shell_hooks| ServeOptions {
+            listen: DEFAULT_LISTEN.to_string(),
+            state_dir: state_dir.path().to_path_buf(),
+            token: None,
+            grpc_listen: None,
+            metrics_interval: 0,
+            verbose: 0,
+            allow_shell_hooks,
+        };
+        let yaml = format!("{}clone_benchmark:\n  command: 'true'\n", base);
+        let refused = Agent::new(options(false)).submit(&yaml, Vec::new(), None).err().unwrap();
+        assert_eq!(refused.code, 403);
+        assert!(Agent::new(options(true)).submit(&yaml, Vec::new(), None).is_ok());
+    }
+
+    #[test]
+    fn test_event_history_and_subscription() {
+        let run = Run::new("r".to_string(), PathBuf::from("/tmp/r"), Vec::new(), None);
+        run.publish(json!({"type": "log", "line": "one"}));
+        let (past, mut live) = run.subscribe();
+        run.publish(json!({"type": "exit", "exit_code": 0}));
+        assert_eq!(past.len(), 1);
+        assert_eq!(past[0]["line"], "one");
+        assert_eq!(live.try_recv().unwrap()["type"], "exit");
+        assert!(live.try_recv().is_err());
+        assert_eq!(run.status().state, RunState::Created);
+    }
+}
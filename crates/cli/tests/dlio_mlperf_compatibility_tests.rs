@@ -505,6 +505,14 @@ fn validate_deterministic_access_order(report1: &Value, report2: &Value) -> Resu
             access_order1, access_order2
         );
     }
+
+    // Full per-epoch access order, beyond the first items
+    if report1.get("access_order_digests") != report2.get("access_order_digests") {
+        anyhow::bail!(
+            "Access order digests differ between runs:\nRun 1: {:?}\nRun 2: {:?}",
+            report1.get("access_order_digests"), report2.get("access_order_digests")
+        );
+    }
     
     Ok(())
 }
@@ -194,13 +194,13 @@ async fn run_large_scale_test() -> Result<()> {
     let min_batch_time = batch_times.iter().min().unwrap();
     let max_batch_time = batch_times.iter().max().unwrap();
     let files_per_second = total_files_processed as f64 / total_processing_time.as_secs_f64();
-    let mb_per_second =
+    let mib_per_second =
         (total_bytes_processed as f64 / 1024.0 / 1024.0) / total_processing_time.as_secs_f64();
 
     println!("\n🎯 LARGE-SCALE PERFORMANCE RESULTS:");
     println!("   ✅ Total files processed: {}", total_files_processed);
     println!(
-        "   ✅ Total data processed: {:.2} MB",
+        "   ✅ Total data processed: {:.2} MiB",
         total_bytes_processed as f64 / 1024.0 / 1024.0
     );
     println!("   ✅ Total batches: {}", total_batches);
@@ -209,7 +209,7 @@ async fn run_large_scale_test() -> Result<()> {
     println!("   ✅ Total time: {:?}", total_time);
     println!("\n📊 THROUGHPUT METRICS:");
     println!("   ⚡ Files per second: {:.2}", files_per_second);
-    println!("   ⚡ MB per second: {:.2}", mb_per_second);
+    println!("   ⚡ MiB per second: {:.2}", mib_per_second);
     println!("   ⚡ Average batch time: {:?}", avg_batch_time);
     println!("   ⚡ Min batch time: {:?}", min_batch_time);
     println!("   ⚡ Max batch time: {:?}", max_batch_time);
@@ -239,9 +239,9 @@ async fn run_large_scale_test() -> Result<()> {
         files_per_second
     );
     assert!(
-        mb_per_second > 1.0,
-        "Should achieve reasonable data throughput (got {:.2} MB/sec)",
-        mb_per_second
+        mib_per_second > 1.0,
+        "Should achieve reasonable data throughput (got {:.2} MiB/s)",
+        mib_per_second
     );
     assert!(
         total_processing_time < Duration::from_secs(10),
@@ -256,7 +256,7 @@ async fn run_large_scale_test() -> Result<()> {
 
     println!("\n✅ LARGE-SCALE test completed successfully!");
     println!(
-        "✅ Processed {} files ({:.2} MB) in {:?} with {:.2} files/sec throughput",
+        "✅ Processed {} files ({:.2} MiB) in {:?} with {:.2} files/sec throughput",
         total_files_processed,
         total_bytes_processed as f64 / 1024.0 / 1024.0,
         total_processing_time,
@@ -6,7 +6,7 @@
 use anyhow::Result;
 use tempfile::TempDir;
 
-use dl_driver_core::dlio_compat::{yaml_to_json, DlioConfig};
+use dl_driver_core::dlio_compat::{yaml_to_json, DlioConfig, LoaderConfig};
 use s3dlio::api::advanced::{AsyncPoolDataLoader, MultiBackendDataset};
 use s3dlio::object_store::store_for_uri;
 
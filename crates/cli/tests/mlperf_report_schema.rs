@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// `run --mlperf` report schema: a short mem:// training run, reported as JSON and CSV
+use anyhow::Result;
+use dl_driver_core::testing::ConfigBuilder;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+const NUMBER_FIELDS: &[&str] = &[
+    "throughput_samples_per_sec",
+    "p50_latency_ms",
+    "p95_latency_ms",
+    "p99_latency_ms",
+    "io_p50_latency_ms",
+    "io_p95_latency_ms",
+    "io_p99_latency_ms",
+    "decode_p50_latency_ms",
+    "decode_p95_latency_ms",
+    "decode_p99_latency_ms",
+    "h2d_p50_latency_ms",
+    "h2d_p95_latency_ms",
+    "h2d_p99_latency_ms",
+    "total_execution_time_secs",
+    "au_percent",
+];
+const STRING_FIELDS: &[&str] = &["benchmark_name", "backend_type", "data_folder", "format", "dl_driver_version", "s3dlio_version"];
+
+fn dl_driver_binary() -> Result<PathBuf> {
+    if let Ok(binary_path) = std::env::var("CARGO_BIN_EXE_dl-driver") {
+        return Ok(PathBuf::from(binary_path));
+    }
+    let workspace_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    ["target/debug/dl-driver", "target/release/dl-driver"]
+        .iter()
+        .map(|p| workspace_root.join(p))
+        .find(|p| p.exists())
+        .ok_or_else(|| anyhow::anyhow!("dl-driver binary not found; run 'cargo build' first"))
+}
+
+/// Runs `run --mlperf --format <format>` and returns the report file's contents
+fn mlperf_run(dir: &TempDir, format: &str) -> Result<String> {
+    let config = dir.path().join("mlperf.yaml");
+    let yaml = ConfigBuilder::new("mem://mlperf")
+        .epochs(2)
+        .computation_time(0.001)
+        .yaml("metric:\n  au: 0.01\n")
+        .to_yaml();
+    std::fs::write(&config, yaml)?;
+    let output = dir.path().join(format!("mlperf.{}", format));
+
+    let status = Command::new(dl_driver_binary()?)
+        .args(["run", "--config"])
+        .arg(&config)
+        .args(["--mlperf", "--format", format, "--output"])
+        .arg(&output)
+        .status()?;
+    anyhow::ensure!(status.success(), "dl-driver run --mlperf --format {} failed: {}", format, status);
+    Ok(std::fs::read_to_string(&output)?)
+}
+
+#[test]
+fn test_mlperf_json_and_csv_reports_match_schema() -> Result<()> {
+    let dir = TempDir::new()?;
+
+    let report: Value = serde_json::from_str(&mlperf_run(&dir, "json")?)?;
+    for field in NUMBER_FIELDS {
+        assert!(report[field].is_number(), "{} should be a number: {}", field, report[field]);
+    }
+    for field in STRING_FIELDS {
+        assert!(report[field].is_string(), "{} should be a string: {}", field, report[field]);
+    }
+    assert_eq!(report["total_samples"].as_u64(), Some(32), "8 files × 2 samples × 2 epochs");
+    assert!(report["total_bytes"].as_u64().unwrap_or(0) > 0);
+    assert_eq!((report["batch_size"].as_u64(), report["read_threads"].as_u64()), (Some(4), Some(2)));
+    // Unshuffled and unseeded: the seed drawn for the run is still reported
+    assert_eq!(report["shuffle"].as_bool(), Some(false));
+    assert!(report["seed"].is_u64());
+    assert!(report["au_pass"].is_boolean());
+    assert_eq!(report["data_folder"], "mem://mlperf");
+    assert!(report["p99_latency_ms"].as_f64() >= report["p50_latency_ms"].as_f64());
+    // One access-order digest per epoch
+    assert_eq!(report["access_order_digests"].as_array().map(Vec::len), Some(2));
+
+    let csv = mlperf_run(&dir, "csv")?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2, "header and one row: {}", csv);
+    let header: Vec<&str> = lines[0].split(',').collect();
+    let row: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!(header.len(), row.len());
+    let column = |name: &str| header.iter().position(|h| *h == name).map(|i| row[i]);
+    assert_eq!(column("total_samples"), Some("32"));
+    assert_eq!(column("shuffle"), Some("false"));
+    assert!(column("au_percent").and_then(|au| au.parse::<f64>().ok()).is_some());
+    for field in NUMBER_FIELDS.iter().filter(|f| **f != "total_execution_time_secs") {
+        let value = column(field).unwrap_or_else(|| panic!("CSV has no {} column", field));
+        assert!(value.parse::<f64>().is_ok(), "{} = {:?}", field, value);
+    }
+    Ok(())
+}
@@ -0,0 +1,1531 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/dlio_compat.rs
+//
+// DLIO-compatible configuration parsing for MLCommons benchmarks
+//
+// Config structs, YAML/JSON parsing and RunPlan computation only; s3dlio
+// loader options are derived in dl_driver_core (`LoaderConfig`).
+//
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Helper function to deserialize AU values that can be either fraction (0.90) or percentage (90)
+fn de_frac_or_pct<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f64>, D::Error> {
+    let v: Option<f64> = Option::<f64>::deserialize(d)?;
+    Ok(v.map(|x| if x > 1.0 { x / 100.0 } else { x }))
+}
+
+/// A rank list written as text ("0-3,8") or as a bare rank number
+fn de_rank_list<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RankList {
+        Text(String),
+        Rank(u64),
+    }
+    Ok(match RankList::deserialize(d)? {
+        RankList::Text(ranks) => ranks,
+        RankList::Rank(rank) => rank.to_string(),
+    })
+}
+
+/// A sampling weight: finite and positive, or absent for the default
+fn de_weight<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f64>, D::Error> {
+    let v: Option<f64> = Option::<f64>::deserialize(d)?;
+    match v {
+        Some(w) if !w.is_finite() || w <= 0.0 => {
+            Err(serde::de::Error::custom(format!("weight must be a finite positive number, got {}", w)))
+        }
+        _ => Ok(v),
+    }
+}
+
+/// Reject `.inf` / `.nan` in a YAML config: the JSON round trip would silently turn them into null
+fn reject_non_finite(value: &serde_yaml::Value, path: &str) -> Result<()> {
+    match value {
+        serde_yaml::Value::Number(n) if n.as_f64().is_some_and(|x| !x.is_finite()) => {
+            anyhow::bail!("Config value {} is not a finite number ({})", if path.is_empty() { "<root>" } else { path }, n)
+        }
+        serde_yaml::Value::Mapping(map) => map.iter().try_for_each(|(key, v)| {
+            let key = key.as_str().map_or_else(|| format!("{:?}", key), str::to_string);
+            reject_non_finite(v, &if path.is_empty() { key } else { format!("{}.{}", path, key) })
+        }),
+        serde_yaml::Value::Sequence(items) => {
+            items.iter().enumerate().try_for_each(|(i, v)| reject_non_finite(v, &format!("{}[{}]", path, i)))
+        }
+        serde_yaml::Value::Tagged(tagged) => reject_non_finite(&tagged.value, path),
+        _ => Ok(()),
+    }
+}
+
+/// Unified execution plan derived from DLIO config
+/// This normalizes and validates all DLIO configuration into an actionable plan
+#[derive(Debug, Clone, Serialize)]
+pub struct RunPlan {
+    /// Model configuration
+    pub model: ModelPlan,
+
+    /// Workflow phases to execute
+    pub workflow: WorkflowPlan,
+
+    /// Dataset configuration and paths
+    pub dataset: DatasetPlan,
+
+    /// Reader/loader configuration
+    pub reader: ReaderPlan,
+
+    /// Training configuration
+    pub train: TrainPlan,
+
+    /// Metric configuration for pass/fail
+    pub metric: Option<MetricPlan>,
+
+    /// Checkpointing configuration
+    pub checkpointing: Option<CheckpointingPlan>,
+
+    /// Profiling configuration
+    pub profiling: Option<ProfilingPlan>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelPlan {
+    pub name: String,
+    pub model_size_bytes: Option<u64>,
+    pub framework: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowPlan {
+    pub generate_data: bool,
+    pub train: bool,
+    pub checkpoint: bool,
+    pub evaluation: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainPlan {
+    pub epochs: u32,
+    pub computation_time: f64,
+    pub computation_time_stdev: Option<f64>,
+    pub total_training_steps: Option<i64>,
+    /// Accelerators each rank stands for
+    pub gpus_per_rank: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricPlan {
+    pub au_threshold: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetPlan {
+    /// Normalized data folder URI (file://, s3://, az://, direct://)
+    pub data_folder_uri: String,
+
+    /// Data format (npz, hdf5, tfrecord, csv, jpeg, png, synthetic)
+    pub format: String,
+
+    /// Training dataset configuration
+    pub train: DatasetSplit,
+
+    /// Evaluation dataset configuration  
+    pub eval: Option<DatasetSplit>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetSplit {
+    pub num_files: usize,
+    pub num_samples_per_file: usize,
+    pub record_length_bytes: usize,
+    pub total_samples: usize,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReaderPlan {
+    pub batch_size: usize,
+    pub prefetch: usize,
+    pub shuffle: bool,
+    pub read_threads: usize,
+    pub seed: Option<u64>,
+    /// Evaluation settings (training settings where not overridden)
+    pub eval: PhaseReaderPlan,
+    /// Checkpoint-read settings (training settings where not overridden)
+    pub checkpoint: PhaseReaderPlan,
+}
+
+/// Reader settings of a non-training phase
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PhaseReaderPlan {
+    pub batch_size: usize,
+    pub prefetch: usize,
+    pub read_threads: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointingPlan {
+    pub enabled: bool,
+    pub checkpoint_folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfilingPlan {
+    pub enabled: bool,
+    pub profiler_type: String,
+}
+
+/// Training configuration for DLIO workload execution
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrainConfig {
+    /// Number of epochs to train for
+    pub epochs: Option<u32>,
+    /// Emulated computation time per step in seconds
+    pub computation_time: Option<f64>,
+    /// Standard deviation for computation time (for realistic variation)
+    pub computation_time_stdev: Option<f64>,
+    /// How computation time is spent: sleep (default), busy_loop or matmul
+    pub computation_model: Option<String>,
+    /// Also load a real GPU during each compute window (`cuda` feature)
+    pub gpu_burn: Option<GpuBurnConfig>,
+    /// Total training steps (alternative to epochs-based termination)
+    pub total_training_steps: Option<i64>,
+    /// Accelerators each rank stands for; a step loads one batch for each (dl-driver extension, default: 1)
+    pub gpus_per_rank: Option<u32>,
+    /// Per-rank computation times for mixed accelerator fleets (dl-driver extension, default: none)
+    pub compute_profiles: Option<Vec<ComputeProfileConfig>>,
+}
+
+/// Computation time of a group of ranks (`train.compute_profiles`), e.g. one GPU model of a mixed fleet
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ComputeProfileConfig {
+    /// Name reported in results, e.g. "A100"
+    pub name: String,
+    /// Ranks with this profile: "0-3", "4,6", "8-11,16" or a single rank number, each below the world size
+    #[serde(deserialize_with = "de_rank_list")]
+    pub ranks: String,
+    /// Emulated computation time per step of these ranks in seconds
+    pub computation_time: f64,
+    /// Standard deviation of their computation time (default: train.computation_time_stdev)
+    pub computation_time_stdev: Option<f64>,
+}
+
+/// Evaluation settings (DLIO `evaluation:`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EvaluationConfig {
+    /// Emulated computation time per evaluation step in seconds
+    pub eval_time: Option<f64>,
+    /// Standard deviation for eval_time
+    pub eval_time_stdev: Option<f64>,
+    /// Evaluate after every N training epochs (default 1)
+    pub epochs_between_evals: Option<usize>,
+}
+
+/// GPU burn kernel settings (`train.gpu_burn`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GpuBurnConfig {
+    /// Fraction of the GPU's SMs kept busy, (0, 1] (default 1.0)
+    pub occupancy: Option<f64>,
+    /// CUDA device ordinal (default 0)
+    pub device: Option<usize>,
+}
+
+/// Metric configuration for pass/fail determination
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MetricConfig {
+    /// Accelerator Utilization threshold for pass/fail (accepts 0.90 or 90)
+    #[serde(default, deserialize_with = "de_frac_or_pct")]
+    pub au: Option<f64>,
+}
+
+/// DLIO-compatible JSON configuration structure
+/// Based on MLCommons DLIO YAML schema translated to JSON
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DlioConfig {
+    pub model: Option<ModelConfig>,
+    pub framework: Option<String>,
+    pub workflow: Option<WorkflowConfig>,
+    pub dataset: DatasetConfig,
+    pub reader: ReaderConfig,
+    pub train: Option<TrainConfig>,
+    pub evaluation: Option<EvaluationConfig>,
+    pub metric: Option<MetricConfig>,
+    pub checkpointing: Option<CheckpointingConfig>,
+    pub profiling: Option<ProfilingConfig>,
+
+    // Framework-specific configurations for M4 integration
+    pub pytorch_config: Option<PyTorchFrameworkConfig>,
+    pub tensorflow_config: Option<TensorFlowFrameworkConfig>,
+    pub jax_config: Option<JaxFrameworkConfig>,
+
+    // Alternative nested framework configuration
+    pub framework_profiles: Option<FrameworkProfiles>,
+
+    // dl-driver storage tuning extensions (not part of the DLIO schema)
+    pub storage: Option<StorageConfig>,
+
+    // dl-driver overwrite/churn workload (not part of the DLIO schema)
+    pub overwrite: Option<OverwriteConfig>,
+
+    // dl-driver data-path validation while reading (not part of the DLIO schema)
+    pub validation: Option<ValidationConfig>,
+
+    // dl-driver timed actions within the run (not part of the DLIO schema)
+    pub schedule: Option<Vec<ScheduleEntry>>,
+
+    // dl-driver storage failover resilience test (not part of the DLIO schema)
+    pub failover: Option<FailoverConfig>,
+
+    // dl-driver dataset snapshot/clone benchmark (not part of the DLIO schema)
+    pub clone_benchmark: Option<CloneBenchmarkConfig>,
+
+    // dl-driver LIST-while-training interference test (not part of the DLIO schema)
+    pub list_interference: Option<ListInterferenceConfig>,
+
+    // dl-driver client CPU/memory footprint limits (not part of the DLIO schema)
+    pub client_limits: Option<ClientLimitsConfig>,
+
+    // dl-driver growing-dataset simulation between epochs (not part of the DLIO schema)
+    pub dataset_growth: Option<DatasetGrowthConfig>,
+
+    // dl-driver data-aging / tier-migration simulation between epochs (not part of the DLIO schema)
+    pub tier_migration: Option<TierMigrationConfig>,
+
+    // dl-driver standalone checkpoint burst-write benchmark (not part of the DLIO schema)
+    pub checkpoint_storm: Option<CheckpointStormConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ModelConfig {
+    pub name: Option<String>,
+    pub model_size: Option<u64>,
+    pub framework: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WorkflowConfig {
+    pub generate_data: Option<bool>,
+    pub train: Option<bool>,
+    pub checkpoint: Option<bool>,
+    pub evaluation: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DatasetConfig {
+    pub data_folder: String,
+    pub format: Option<String>,
+    pub num_files_train: Option<usize>,
+    pub num_files_eval: Option<usize>,
+    pub record_length_bytes: Option<usize>,
+    /// Spread of generated record sizes around record_length_bytes (DLIO `record_length_bytes_stdev`)
+    pub record_length_bytes_stdev: Option<usize>,
+    /// "normal" or "lognormal" record sizes when record_length_bytes_stdev is set (dl-driver extension, default: normal)
+    pub record_length_distribution: Option<String>,
+    pub num_samples_per_file: Option<usize>,
+    pub compression: Option<String>,
+    /// Per-record array dimensions (DLIO `record_dims`)
+    pub record_dims: Option<Vec<usize>>,
+    /// Per-record numpy element type, e.g. "uint8" (DLIO `record_element_type`)
+    pub record_element_type: Option<String>,
+    /// Per-sample components stored as separate objects (dl-driver extension)
+    pub components: Option<Vec<DatasetComponent>>,
+    /// Read from several data folders with sampling weights (dl-driver extension)
+    pub data_folders: Option<Vec<DataFolderSource>>,
+    /// Prefix depth and key length of generated object keys (dl-driver extension)
+    pub key_layout: Option<KeyLayoutConfig>,
+    /// Lock object held in the data folder while generating or cleaning up (dl-driver extension)
+    pub lock: Option<DatasetLockConfig>,
+    /// Where the num_files_eval split lives (dl-driver extension, default: `<data_folder>_eval`)
+    pub eval_folder: Option<String>,
+}
+
+/// Guards a dataset against two runs writing it at the same time
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DatasetLockConfig {
+    /// Take the lock (default true when the block is present)
+    pub enabled: Option<bool>,
+    /// Seconds after which a lock left by a crashed run may be taken over (default 3600)
+    pub ttl_s: Option<u64>,
+}
+
+/// Generated object key naming, e.g. deep or hashed prefixes and long keys
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct KeyLayoutConfig {
+    /// Prefix levels above each object (default 0)
+    pub prefix_depth: Option<usize>,
+    /// "sequential" (default) or "random" (hashed) prefixes
+    pub prefix_mode: Option<String>,
+    /// Sequential prefixes per level and files per leaf prefix (default 16)
+    pub fanout: Option<usize>,
+    /// Hex characters per random prefix level (default 4)
+    pub prefix_width: Option<usize>,
+    /// Pad object keys to this length, relative to the data folder
+    pub key_length: Option<usize>,
+    /// Seed for random prefixes (default: reader.seed, else 0)
+    pub seed: Option<u64>,
+}
+
+/// One weighted read source of a tiered dataset, e.g. s3://hot with weight 0.8
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DataFolderSource {
+    pub uri: String,
+    /// Relative share of samples read from this source (default 1.0)
+    #[serde(default, deserialize_with = "de_weight")]
+    pub weight: Option<f64>,
+}
+
+/// One component of a multi-component sample, e.g. an npz image plus a json sidecar
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DatasetComponent {
+    pub name: String,
+    pub format: String,
+    /// Per-record size of this component (default: dataset.record_length_bytes)
+    pub record_length_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReaderConfig {
+    pub data_loader: Option<String>,
+    pub batch_size: Option<usize>,
+    /// Batch size in bytes instead of samples; samples per batch follow the record size (dl-driver extension)
+    pub batch_size_bytes: Option<usize>,
+    pub prefetch: Option<usize>,
+    pub shuffle: Option<bool>,
+    pub read_threads: Option<usize>,
+    pub compute_threads: Option<usize>,
+    pub transfer_size: Option<usize>,
+    pub file_access_type: Option<String>,
+    pub seed: Option<u64>,
+    /// Drop the trailing partial batch of each epoch
+    pub drop_last: Option<bool>,
+    /// "permutation" (default), "buffer" or "sample" when `shuffle` is set (dl-driver extension)
+    pub shuffle_strategy: Option<String>,
+    /// DLIO's sample shuffle: "seed" or "random" shuffles samples across files when `shuffle` is set
+    pub sample_shuffle: Option<String>,
+    /// Shuffle buffer size in samples for the "buffer" strategy
+    pub shuffle_size: Option<usize>,
+    /// What read_threads means: "native" (default) or "dlio" (dl-driver extension)
+    pub thread_model: Option<String>,
+    /// Batch size for evaluation (default: batch_size)
+    pub batch_size_eval: Option<usize>,
+    /// Prefetch depth for evaluation (dl-driver extension, default: prefetch)
+    pub prefetch_eval: Option<usize>,
+    /// Reader threads for evaluation (dl-driver extension, default: read_threads)
+    pub read_threads_eval: Option<usize>,
+    /// Batch size when reading checkpoints back (dl-driver extension, default: batch_size)
+    pub batch_size_checkpoint: Option<usize>,
+    /// Prefetch depth when reading checkpoints back (dl-driver extension, default: prefetch)
+    pub prefetch_checkpoint: Option<usize>,
+    /// Reader threads when reading checkpoints back (dl-driver extension, default: read_threads)
+    pub read_threads_checkpoint: Option<usize>,
+    /// Prefetched batches at epoch boundaries: "drain", "discard" or "carry_over" (dl-driver extension, default: drain)
+    pub epoch_boundary: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CheckpointingConfig {
+    pub checkpoint_folder: Option<String>,
+    pub checkpoint_after_epoch: Option<usize>,
+    pub epochs_between_checkpoints: Option<usize>,
+    pub steps_between_checkpoints: Option<usize>,
+    /// Bytes per checkpoint, split across ranks (dl-driver extension, default: model.model_size)
+    pub checkpoint_size_bytes: Option<u64>,
+    /// Hold training reads while a checkpoint is written (dl-driver extension, default: false, loaders keep prefetching);
+    /// the s3dlio loader's pool keeps reading until its readahead is full
+    pub pause_loading: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProfilingConfig {
+    pub profiler: Option<String>,
+    pub profile_folder: Option<String>,
+    pub iostat: Option<bool>,
+}
+
+/// Storage backend tuning options (dl-driver extension, ignored by DLIO)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StorageConfig {
+    /// Multipart GET/PUT tuning for large-object workloads
+    pub multipart: Option<MultipartConfig>,
+
+    /// Alignment constraints and misalignment policy for direct:// targets
+    pub directio: Option<DirectIoConfig>,
+
+    /// Striping hints applied to the data folder on parallel filesystems
+    pub striping: Option<StripingConfig>,
+
+    /// Latency/bandwidth model for the null:// and sim:// backends
+    pub latency_model: Option<LatencyModelConfig>,
+
+    /// Replicas of the dataset at several endpoints/regions, read under a distribution policy
+    pub endpoints: Option<EndpointsConfig>,
+
+    /// Record per-request GET timings (TTFB, transfer time, retries) in the results
+    pub request_timing: Option<bool>,
+
+    /// Sample loader pool stats (reads in flight, queue depth, retries) every N ms into the results (dl-driver extension, default: off)
+    pub pool_stats_interval_ms: Option<u64>,
+
+    /// Attribute each epoch's bytes to cache or backend, as a cache warming curve in the results
+    pub cache_analysis: Option<CacheAnalysisConfig>,
+
+    /// Proxy and IP family settings for cloud/HTTP backends
+    pub network: Option<NetworkConfig>,
+
+    /// Server-side encryption requested on S3 writes
+    pub encryption: Option<EncryptionConfig>,
+
+    /// Storage class for writes, e.g. STANDARD or INTELLIGENT_TIERING: recorded in the results only,
+    /// not applied (s3dlio writes use the bucket's default class)
+    pub storage_class: Option<String>,
+
+    /// Probe range GET, multipart, consistency and listing support at run start, writing
+    /// probe objects under the data folder (default: false)
+    pub capability_probe: Option<bool>,
+}
+
+/// Per-epoch cache vs backend byte attribution (`storage.cache_analysis`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CacheAnalysisConfig {
+    /// Remote reads at or under this latency count as cache hits (default: a quarter of the first epoch's median read)
+    pub hit_latency_ms: Option<f64>,
+}
+
+/// S3 server-side encryption (`storage.encryption`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EncryptionConfig {
+    /// "sse-s3" (default), "sse-kms" or "none"
+    pub mode: Option<String>,
+
+    /// KMS key id or ARN for sse-kms (default: the bucket's KMS key)
+    pub kms_key_id: Option<String>,
+}
+
+/// Network path settings (`storage.network`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NetworkConfig {
+    /// Proxy URL, e.g. http://proxy.lab:3128 (default: HTTPS_PROXY from the environment)
+    pub proxy: Option<String>,
+
+    /// Hosts or domain suffixes that bypass the proxy (default: NO_PROXY)
+    pub no_proxy: Option<Vec<String>>,
+
+    /// "auto" (default), "ipv4" or "ipv6": the family the endpoint must be reachable over
+    pub ip_family: Option<String>,
+
+    /// Endpoint to probe (host:port or URL) when the data folder URI does not name it
+    pub endpoint: Option<String>,
+}
+
+/// Multi-endpoint read distribution (`storage.endpoints`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EndpointsConfig {
+    /// The same dataset at each endpoint, e.g. s3://train-us-east/data/ and s3://train-eu-west/data/
+    pub replicas: Vec<EndpointReplica>,
+
+    /// "round_robin" (default) or "latency_aware"
+    pub policy: Option<String>,
+
+    /// Retry a failed read on the next endpoint (default: true)
+    pub failover: Option<bool>,
+}
+
+/// One endpoint's copy of the dataset
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EndpointReplica {
+    pub uri: String,
+    /// Label for reports (default: the URI)
+    pub name: Option<String>,
+}
+
+/// Synthetic storage service model: per-request latency plus a shared bandwidth cap
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LatencyModelConfig {
+    /// Read latency in milliseconds (the median for lognormal)
+    pub read_latency_ms: Option<f64>,
+
+    /// Write latency in milliseconds (the median for lognormal)
+    pub write_latency_ms: Option<f64>,
+
+    /// Latency distribution: "fixed" (default) or "lognormal"
+    pub distribution: Option<String>,
+
+    /// Target read p99 in milliseconds; sets the lognormal spread
+    pub p99_ms: Option<f64>,
+
+    /// Uniform jitter of up to ± this many milliseconds added to each request
+    pub jitter_ms: Option<f64>,
+
+    /// Aggregate bandwidth cap shared by all requests, in MiB/s (unlimited if unset)
+    pub bandwidth_mib_s: Option<f64>,
+}
+
+/// Parallel filesystem striping hints (applied on Lustre, recorded on Lustre/GPFS)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StripingConfig {
+    /// Number of OSTs to stripe each file across (-1 for all)
+    pub stripe_count: Option<i64>,
+
+    /// Stripe size in bytes
+    pub stripe_size_bytes: Option<u64>,
+}
+
+/// DirectIO alignment configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DirectIoConfig {
+    /// Required alignment for O_DIRECT transfers in bytes (default: 4096)
+    pub alignment_bytes: Option<usize>,
+
+    /// What to do when file sizes are misaligned: "pad" (default), "fallback" to file://, or "error"
+    pub on_misaligned: Option<String>,
+}
+
+/// Multipart transfer tuning for large objects (e.g. 1GB+ shards)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct MultipartConfig {
+    /// Objects at or above this size use multipart PUT / ranged GET (default: 64 MiB)
+    pub threshold_bytes: Option<u64>,
+
+    /// Size of each part in bytes (default: 16 MiB)
+    pub part_size_bytes: Option<u64>,
+
+    /// Parts transferred concurrently per object for ranged GETs (default: 8)
+    pub concurrency: Option<usize>,
+
+    /// Retries per part before the transfer fails (default: 3)
+    pub max_retries: Option<u32>,
+}
+
+/// Overwrite (checkpoint-style churn) workload configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OverwriteConfig {
+    /// Number of objects in the working set that is repeatedly overwritten (default: 16)
+    pub working_set: Option<usize>,
+
+    /// Size of each object in bytes (default: dataset record size × samples per file)
+    pub object_size_bytes: Option<usize>,
+
+    /// Number of full passes over the working set (default: 10)
+    pub iterations: Option<u32>,
+
+    /// Sub-prefix under data_folder used for the working set (default: "overwrite")
+    pub prefix: Option<String>,
+}
+
+/// Checkpoint storm: synchronized multi-rank checkpoint writes at a fixed cadence (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CheckpointStormConfig {
+    /// Checkpoint bytes written by each rank (default: 256 MiB)
+    pub size_bytes: Option<usize>,
+
+    /// Objects each rank splits its checkpoint into, written concurrently (default: 1)
+    pub shards: Option<usize>,
+
+    /// Number of checkpoints (default: 10)
+    pub count: Option<u32>,
+
+    /// Seconds between checkpoint starts (default: 30)
+    pub interval_s: Option<f64>,
+
+    /// fsync each file before the write counts as complete, for local paths (default: true)
+    pub fsync: Option<bool>,
+
+    /// Destination prefix (default: checkpointing.checkpoint_folder, else data_folder/checkpoint_storm)
+    pub folder: Option<String>,
+}
+
+/// One scheduled action (`schedule:` list, see dl_driver_core::schedule)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ScheduleEntry {
+    /// Offset from the start of training, e.g. "90s", "10m", "1h", "500ms"
+    pub at: String,
+    pub action: String,
+    /// checkpoint_storm: objects written (default 16)
+    pub objects: Option<usize>,
+    /// checkpoint_storm: bytes per object (default: one dataset file)
+    pub size_bytes: Option<usize>,
+    /// inject_latency: delay per step
+    pub latency_ms: Option<u64>,
+    /// set_target_throughput: MiB/s (0 = unlimited)
+    pub throughput_mib_s: Option<f64>,
+    /// Free-form annotation for the timeline
+    pub label: Option<String>,
+}
+
+/// Storage failover resilience test configuration (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FailoverConfig {
+    /// Width of the throughput buckets used to detect recovery (default: 1000 ms)
+    pub bucket_ms: Option<u64>,
+
+    /// Fraction of pre-failover throughput that counts as recovered (default: 0.9)
+    pub recovery_fraction: Option<f64>,
+
+    /// Delay before a failed read is retried (default: 100 ms)
+    pub retry_delay_ms: Option<u64>,
+
+    /// Longest a read keeps being retried before it fails the run (default: 300 s)
+    pub max_retry_s: Option<u64>,
+}
+
+/// Background LIST interference test configuration (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ListInterferenceConfig {
+    /// LIST operations per second over the prefix (default: 1)
+    pub rate_per_s: Option<f64>,
+
+    /// Seconds of training before listing starts, the baseline (default: 10)
+    pub start_after_s: Option<f64>,
+
+    /// Seconds to keep listing (default: until training ends)
+    pub duration_s: Option<f64>,
+
+    /// Prefix to list (default: data_folder)
+    pub prefix: Option<String>,
+}
+
+/// Dataset growth between epochs, simulating continuous ingest (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DatasetGrowthConfig {
+    /// Files each rank appends to the dataset before every epoch after the first (default: 10)
+    pub files_per_epoch: Option<usize>,
+}
+
+/// Data aging between epochs: migrate a share of files to a cold tier (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TierMigrationConfig {
+    /// Share of the not-yet-migrated files migrated before every epoch after the first (default: 0.1)
+    pub fraction: Option<f64>,
+
+    /// Migration hook: "copy" copies objects to `target`, "shell" runs `command` (default: shell if `command` is set)
+    pub hook: Option<String>,
+
+    /// Shell command migrating the files listed in `{list}` (one URI per line); `{target}`, `{target_path}` and `{epoch}` are substituted
+    pub command: Option<String>,
+
+    /// Cold-tier prefix migrated files are read from (default: data_folder with a "_cold" suffix; in place for shell hooks)
+    pub target: Option<String>,
+}
+
+/// Client footprint limits (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ClientLimitsConfig {
+    /// Pin the process to this many cores (ranks on one host get disjoint sets)
+    pub cpus: Option<usize>,
+
+    /// Pin the process to exactly these cores instead
+    pub cpu_list: Option<Vec<usize>>,
+
+    /// Memory limit in bytes, applied through a cgroup v2 child cgroup
+    pub memory_bytes: Option<u64>,
+}
+
+/// Dataset snapshot/clone benchmark configuration (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CloneBenchmarkConfig {
+    /// Clone hook: "shell" runs `command`, "copy" copies object by object (default: shell if `command` is set)
+    pub hook: Option<String>,
+
+    /// Shell command performing the clone; `{source}`, `{target}`, `{source_path}` and `{target_path}` are substituted
+    pub command: Option<String>,
+
+    /// Clone destination URI (default: data_folder with a "_clone" suffix)
+    pub target: Option<String>,
+
+    /// Also measure a first epoch from the source prefix for comparison (default: false)
+    pub compare_source: Option<bool>,
+
+    /// Delete the clone after the benchmark (default: false)
+    pub cleanup: Option<bool>,
+}
+
+/// Read-side data validation (dl-driver extension)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ValidationConfig {
+    /// Fraction of batches whose samples are fully decoded (0.01, or a percentage such as 5)
+    #[serde(default, deserialize_with = "de_frac_or_pct")]
+    pub decode_sample_rate: Option<f64>,
+
+    /// Number of failing samples kept as examples in the report (default: 5)
+    pub max_failure_examples: Option<usize>,
+
+    /// Generate seeded, self-identifying samples and verify every sample read against them;
+    /// the expected content is that of the file index in each object's key
+    pub verify_content: Option<bool>,
+
+    /// Seed for verifiable content (default: reader.seed, then 0)
+    pub content_seed: Option<u64>,
+}
+
+/// Framework-specific configuration structures for M4 integration
+/// PyTorch DataLoader configuration within DLIO config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PyTorchFrameworkConfig {
+    /// Batch size for PyTorch DataLoader
+    pub batch_size: Option<usize>,
+
+    /// Number of worker processes for data loading  
+    pub num_workers: Option<usize>,
+
+    /// Whether to shuffle data
+    pub shuffle: Option<bool>,
+
+    /// Random seed for reproducibility
+    pub seed: Option<u64>,
+
+    /// Pin memory for CUDA acceleration
+    pub pin_memory: Option<bool>,
+
+    /// Drop last incomplete batch
+    pub drop_last: Option<bool>,
+
+    /// Prefetch factor for data loading
+    pub prefetch_factor: Option<usize>,
+
+    /// Enable persistent workers
+    pub persistent_workers: Option<bool>,
+
+    /// Return type: "tensor", "bytes", or "reader"
+    pub return_type: Option<String>,
+
+    /// Enable distributed training support
+    pub distributed: Option<bool>,
+}
+
+/// TensorFlow tf.data.Dataset configuration within DLIO config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TensorFlowFrameworkConfig {
+    /// Batch size for tf.data.Dataset
+    pub batch_size: Option<usize>,
+
+    /// Buffer size for shuffling
+    pub shuffle_buffer_size: Option<usize>,
+
+    /// Random seed for reproducibility
+    pub seed: Option<u64>,
+
+    /// Number of parallel calls for map operations
+    pub num_parallel_calls: Option<i32>, // -1 for AUTOTUNE
+
+    /// Prefetch buffer size (-1 for AUTOTUNE)
+    pub prefetch_buffer_size: Option<i32>,
+
+    /// Enable deterministic operations
+    pub deterministic: Option<bool>,
+
+    /// Drop remainder for batching
+    pub drop_remainder: Option<bool>,
+
+    /// Reshuffle each iteration
+    pub reshuffle_each_iteration: Option<bool>,
+
+    /// Enable writable NumPy arrays (extra copy)
+    pub writable: Option<bool>,
+}
+
+/// JAX configuration within DLIO config
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JaxFrameworkConfig {
+    /// Random seed for reproducibility
+    pub seed: Option<u64>,
+
+    /// Enable writable NumPy arrays (extra copy)
+    pub writable: Option<bool>,
+
+    /// Batch size for grouping
+    pub batch_size: Option<usize>,
+
+    /// Buffer size for prefetching
+    pub prefetch_buffer_size: Option<usize>,
+}
+
+/// Nested framework profiles structure (alternative organization)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FrameworkProfiles {
+    pub pytorch: Option<PyTorchFrameworkConfig>,
+    pub tensorflow: Option<TensorFlowFrameworkConfig>,
+    pub jax: Option<JaxFrameworkConfig>,
+}
+
+impl DlioConfig {
+    /// Parse DLIO config from JSON string
+    pub fn from_json(json_str: &str) -> Result<Self> {
+        serde_json::from_str(json_str).with_context(|| "Failed to parse DLIO JSON config")
+    }
+
+    /// Parse DLIO config from YAML string by converting to JSON first
+    pub fn from_yaml(yaml_str: &str) -> Result<Self> {
+        // Parse YAML to generic Value first
+        let yaml_value: serde_yaml::Value =
+            serde_yaml::from_str(yaml_str).with_context(|| "Failed to parse YAML")?;
+
+        // Accept the MLCommons layout, nested under `workload:`
+        let yaml_value = crate::hydra::unwrap_workload(yaml_value);
+        reject_non_finite(&yaml_value, "")?;
+
+        // Convert to JSON string
+        let json_str =
+            serde_json::to_string(&yaml_value).with_context(|| "Failed to convert YAML to JSON")?;
+
+        // Parse as DLIO config
+        Self::from_json(&json_str)
+    }
+
+    /// Load a config file, resolving hydra `defaults:` includes relative to it
+    pub fn from_yaml_file(path: &std::path::Path) -> Result<Self> {
+        let composed = crate::hydra::compose_file(path)?;
+        reject_non_finite(&composed, "")?;
+        let json_str = serde_json::to_string(&composed).with_context(|| "Failed to convert YAML to JSON")?;
+        Self::from_json(&json_str)
+    }
+
+    /// Get the data folder URI for object store creation
+    pub fn data_folder_uri(&self) -> &str {
+        &self.dataset.data_folder
+    }
+
+    /// Detect storage backend from data_folder URI
+    pub fn detect_storage_backend(&self) -> &str {
+        let uri = &self.dataset.data_folder;
+
+        if uri.starts_with("s3://") {
+            "s3"
+        } else if uri.starts_with("az://") {
+            "azure"
+        } else if uri.starts_with("direct://") {
+            "direct"
+        } else if uri.starts_with("http://") || uri.starts_with("https://") {
+            "http"
+        } else if uri.starts_with("mem://") {
+            "mem"
+        } else if uri.starts_with("null://") {
+            "null"
+        } else if uri.starts_with("sim://") {
+            "sim"
+        } else if uri.starts_with("file://") || !uri.contains("://") {
+            "file"
+        } else {
+            "unknown"
+        }
+    }
+
+    /// Convert this DLIO config to a comprehensive RunPlan
+    pub fn to_run_plan(&self) -> Result<RunPlan> {
+        // Normalize data folder URI
+        let data_folder_uri = self.normalize_data_folder_uri(&self.dataset.data_folder)?;
+
+        // Calculate dataset splits
+        let train_split = self.calculate_dataset_split(
+            self.dataset.num_files_train.unwrap_or(1),
+            self.dataset.num_samples_per_file.unwrap_or(1),
+            self.dataset.record_length_bytes.unwrap_or(1024),
+        );
+
+        let eval_split = self.dataset.num_files_eval.map(|num_files_eval| {
+            self.calculate_dataset_split(
+                num_files_eval,
+                self.dataset.num_samples_per_file.unwrap_or(1),
+                self.dataset.record_length_bytes.unwrap_or(1024),
+            )
+        });
+
+        // Build the comprehensive plan
+        Ok(RunPlan {
+            model: ModelPlan {
+                name: self
+                    .model
+                    .as_ref()
+                    .and_then(|m| m.name.clone())
+                    .unwrap_or_else(|| "dlio_workload".to_string()),
+                model_size_bytes: self.model.as_ref().and_then(|m| m.model_size),
+                framework: self
+                    .framework
+                    .clone()
+                    .or_else(|| self.model.as_ref().and_then(|m| m.framework.clone()))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            },
+
+            workflow: WorkflowPlan {
+                generate_data: self.should_generate_data(),
+                train: self.should_train(),
+                checkpoint: self.should_checkpoint(),
+                evaluation: self.should_evaluate(),
+            },
+
+            dataset: DatasetPlan {
+                data_folder_uri,
+                format: self
+                    .dataset
+                    .format
+                    .clone()
+                    .unwrap_or_else(|| "npz".to_string()),
+                train: train_split,
+                eval: eval_split,
+            },
+
+            reader: {
+                let r = &self.reader;
+                let (batch_size, prefetch, read_threads) =
+                    (r.batch_size.unwrap_or(1), r.prefetch.unwrap_or(4), r.read_threads.unwrap_or(1));
+                ReaderPlan {
+                    batch_size,
+                    prefetch,
+                    shuffle: r.shuffle.unwrap_or(false),
+                    read_threads,
+                    seed: r.seed,
+                    eval: PhaseReaderPlan {
+                        batch_size: r.batch_size_eval.unwrap_or(batch_size),
+                        prefetch: r.prefetch_eval.unwrap_or(prefetch),
+                        read_threads: r.read_threads_eval.unwrap_or(read_threads),
+                    },
+                    checkpoint: PhaseReaderPlan {
+                        batch_size: r.batch_size_checkpoint.unwrap_or(batch_size),
+                        prefetch: r.prefetch_checkpoint.unwrap_or(prefetch),
+                        read_threads: r.read_threads_checkpoint.unwrap_or(read_threads),
+                    },
+                }
+            },
+
+            train: TrainPlan {
+                epochs: self.train.as_ref().and_then(|t| t.epochs).unwrap_or(1),
+                computation_time: self.train.as_ref().and_then(|t| t.computation_time).unwrap_or(0.0),
+                computation_time_stdev: self.train.as_ref().and_then(|t| t.computation_time_stdev),
+                total_training_steps: self.train.as_ref().and_then(|t| t.total_training_steps),
+                gpus_per_rank: self.train.as_ref().and_then(|t| t.gpus_per_rank).unwrap_or(1),
+            },
+
+            metric: self.metric.as_ref().map(|m| MetricPlan {
+                au_threshold: m.au.unwrap_or(0.9), // Default to 90% AU threshold
+            }),
+
+            checkpointing: self.checkpointing.as_ref().map(|c| CheckpointingPlan {
+                enabled: c.checkpoint_after_epoch.unwrap_or(0) > 0,
+                checkpoint_folder: c.checkpoint_folder.clone(),
+            }),
+
+            profiling: self.profiling.as_ref().map(|p| ProfilingPlan {
+                enabled: true,
+                profiler_type: p.profiler.clone().unwrap_or_else(|| "none".to_string()),
+            }),
+        })
+    }
+
+    /// Normalize data folder URI to ensure proper scheme
+    fn normalize_data_folder_uri(&self, data_folder: &str) -> Result<String> {
+        // If already has scheme, validate it
+        if data_folder.contains("://") {
+            let scheme = data_folder.split("://").next().unwrap_or("");
+            match scheme {
+                "file" | "s3" | "az" | "direct" | "http" | "https" | "mem" | "null" | "sim" => Ok(data_folder.to_string()),
+                _ => Err(anyhow::anyhow!("Unsupported URI scheme: {}", scheme)),
+            }
+        } else {
+            // Add file:// scheme to relative/absolute paths
+            if data_folder.starts_with('/') {
+                Ok(format!("file://{}", data_folder))
+            } else {
+                // Convert relative path to absolute (kept relative where there is no
+                // working directory, e.g. in the browser)
+                let Ok(cwd) = std::env::current_dir() else {
+                    return Ok(format!("file://{}", data_folder));
+                };
+                Ok(anchor_local_uri(data_folder, &cwd).unwrap_or_else(|| format!("file://{}", data_folder)))
+            }
+        }
+    }
+
+    /// Absolute data folder URI; relative paths resolve against the current directory
+    pub fn resolved_data_folder_uri(&self) -> Result<String> {
+        self.normalize_data_folder_uri(&self.dataset.data_folder)
+    }
+
+    /// Resolve relative data and checkpoint folders against `workdir` instead of
+    /// the current directory, which launchers may set differently per rank
+    pub fn anchor_relative_paths(&mut self, workdir: &std::path::Path) -> Result<()> {
+        let workdir = workdir
+            .canonicalize()
+            .with_context(|| format!("Cannot resolve working directory {:?}", workdir))?;
+        if let Some(uri) = anchor_local_uri(&self.dataset.data_folder, &workdir) {
+            self.dataset.data_folder = uri;
+        }
+        if let Some(folder) = self.checkpointing.as_mut().and_then(|c| c.checkpoint_folder.as_mut()) {
+            if let Some(uri) = anchor_local_uri(folder, &workdir) {
+                *folder = uri;
+            }
+        }
+        if let Some(folder) = self.dataset.eval_folder.as_mut() {
+            if let Some(uri) = anchor_local_uri(folder, &workdir) {
+                *folder = uri;
+            }
+        }
+        Ok(())
+    }
+
+    /// Folder of the num_files_eval split: `dataset.eval_folder`, else `<data_folder>_eval`
+    /// (a sibling, so training listings of the data folder never pick up eval files)
+    pub fn eval_folder_uri(&self) -> String {
+        match &self.dataset.eval_folder {
+            Some(folder) => folder.clone(),
+            None => format!("{}_eval", self.dataset.data_folder.trim_end_matches('/')),
+        }
+    }
+
+    /// Calculate dataset split configuration with size calculations
+    fn calculate_dataset_split(
+        &self,
+        num_files: usize,
+        samples_per_file: usize,
+        record_bytes: usize,
+    ) -> DatasetSplit {
+        let total_samples = num_files * samples_per_file;
+        let total_bytes = (total_samples * record_bytes) as u64;
+
+        DatasetSplit {
+            num_files,
+            num_samples_per_file: samples_per_file,
+            record_length_bytes: record_bytes,
+            total_samples,
+            total_bytes,
+        }
+    }
+
+    /// Check if data generation phase should run
+    pub fn should_generate_data(&self) -> bool {
+        self.workflow
+            .as_ref()
+            .and_then(|w| w.generate_data)
+            .unwrap_or(false)
+    }
+
+    /// Check if training phase should run
+    pub fn should_train(&self) -> bool {
+        self.workflow.as_ref().and_then(|w| w.train).unwrap_or(true) // Default to true for DLIO compatibility
+    }
+
+    /// Check if checkpointing phase should run
+    pub fn should_checkpoint(&self) -> bool {
+        self.workflow
+            .as_ref()
+            .and_then(|w| w.checkpoint)
+            .unwrap_or(false)
+    }
+
+    /// M4 Framework Integration Methods
+    /// Get PyTorch framework configuration
+    pub fn get_pytorch_config(&self) -> Option<&PyTorchFrameworkConfig> {
+        // Check direct pytorch_config first
+        if let Some(ref config) = self.pytorch_config {
+            return Some(config);
+        }
+
+        // Check nested framework_profiles
+        self.framework_profiles
+            .as_ref()
+            .and_then(|fp| fp.pytorch.as_ref())
+    }
+
+    /// Get TensorFlow framework configuration
+    pub fn get_tensorflow_config(&self) -> Option<&TensorFlowFrameworkConfig> {
+        // Check direct tensorflow_config first
+        if let Some(ref config) = self.tensorflow_config {
+            return Some(config);
+        }
+
+        // Check nested framework_profiles
+        self.framework_profiles
+            .as_ref()
+            .and_then(|fp| fp.tensorflow.as_ref())
+    }
+
+    /// Get JAX framework configuration
+    pub fn get_jax_config(&self) -> Option<&JaxFrameworkConfig> {
+        // Check direct jax_config first
+        if let Some(ref config) = self.jax_config {
+            return Some(config);
+        }
+
+        // Check nested framework_profiles
+        self.framework_profiles
+            .as_ref()
+            .and_then(|fp| fp.jax.as_ref())
+    }
+
+    /// Detect which framework is configured
+    pub fn detect_framework(&self) -> Option<String> {
+        // Check explicit framework field first
+        if let Some(ref fw) = self.framework {
+            return Some(fw.clone());
+        }
+
+        // Check which framework configs are present
+        if self.get_pytorch_config().is_some() {
+            return Some("pytorch".to_string());
+        }
+
+        if self.get_tensorflow_config().is_some() {
+            return Some("tensorflow".to_string());
+        }
+
+        if self.get_jax_config().is_some() {
+            return Some("jax".to_string());
+        }
+
+        None
+    }
+
+    /// Check if evaluation phase should run
+    pub fn should_evaluate(&self) -> bool {
+        self.workflow
+            .as_ref()
+            .and_then(|w| w.evaluation)
+            .unwrap_or(false)
+    }
+}
+
+/// Convert YAML string to JSON string (utility function)
+pub fn yaml_to_json(yaml_str: &str) -> Result<String> {
+    let yaml_value: serde_yaml::Value =
+        serde_yaml::from_str(yaml_str).with_context(|| "Failed to parse YAML")?;
+
+    serde_json::to_string_pretty(&yaml_value).with_context(|| "Failed to convert to JSON")
+}
+
+/// Resolve a relative local path (bare, file:// or direct://) against `base`
+/// Returns None for absolute paths and object storage URIs
+pub fn anchor_local_uri(uri: &str, base: &std::path::Path) -> Option<String> {
+    let (scheme, path) = match uri.split_once("://") {
+        Some((scheme @ ("file" | "direct" | "directio"), path)) => (scheme, path),
+        Some(_) => return None,
+        None => ("file", uri),
+    };
+    if std::path::Path::new(path).is_absolute() {
+        return None;
+    }
+    let joined = base.join(path);
+    let absolute = joined.canonicalize().unwrap_or(joined);
+    Some(format!("{}://{}", scheme, absolute.display()))
+}
+
+/// Map a file:// or direct:// URI (or bare path) to a local filesystem path
+/// Returns None for object storage URIs (s3://, az://, ...)
+pub fn local_path_for_uri(uri: &str) -> Option<std::path::PathBuf> {
+    if let Some(path) = uri.strip_prefix("file://") {
+        Some(std::path::PathBuf::from(path))
+    } else if let Some(path) = uri.strip_prefix("direct://") {
+        Some(std::path::PathBuf::from(path))
+    } else if let Some(path) = uri.strip_prefix("directio://") {
+        Some(std::path::PathBuf::from(path))
+    } else if !uri.contains("://") {
+        Some(std::path::PathBuf::from(uri))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_minimal_dlio_config() {
+        let json = r#"
+        {
+            "model": {
+                "name": "my_workload"
+            },
+            "framework": "pytorch",
+            "workflow": {
+                "generate_data": true,
+                "train": true,
+                "checkpoint": false
+            },
+            "dataset": {
+                "data_folder": "/path/to/data",
+                "format": "npz",
+                "num_files_train": 100,
+                "record_length_bytes": 1048576
+            },
+            "reader": {
+                "data_loader": "pytorch",
+                "batch_size": 16,
+                "read_threads": 4,
+                "compute_threads": 2,
+                "prefetch": 8,
+                "shuffle": true
+            }
+        }
+        "#;
+
+        let config = DlioConfig::from_json(json).expect("Should parse minimal config");
+
+        assert_eq!(config.framework.as_deref(), Some("pytorch"));
+        assert_eq!(config.dataset.data_folder, "/path/to/data");
+        assert_eq!(config.reader.batch_size, Some(16));
+        assert_eq!(config.reader.read_threads, Some(4));
+        assert!(config.should_generate_data());
+        assert!(config.should_train());
+        assert!(!config.should_checkpoint());
+    }
+
+    #[test]
+    fn test_parse_unet3d_config() {
+        let json = r#"
+        {
+            "model": {
+                "name": "unet3d_workload",
+                "model_size": 499153191
+            },
+            "framework": "pytorch",
+            "workflow": {
+                "generate_data": true,
+                "train": true,
+                "checkpoint": false
+            },
+            "dataset": {
+                "data_folder": "/path/to/unet3d/data",
+                "format": "npz",
+                "num_files_train": 100,
+                "record_length_bytes": 1048576
+            },
+            "reader": {
+                "data_loader": "pytorch",
+                "batch_size": 4,
+                "prefetch": 4,
+                "shuffle": false,
+                "read_threads": 2,
+                "compute_threads": 2
+            }
+        }
+        "#;
+
+        let config = DlioConfig::from_json(json).expect("Should parse UNet3D config");
+
+        assert_eq!(
+            config.model.as_ref().unwrap().name.as_deref(),
+            Some("unet3d_workload")
+        );
+        assert_eq!(config.model.as_ref().unwrap().model_size, Some(499153191));
+        assert_eq!(config.reader.batch_size, Some(4));
+        assert_eq!(config.reader.shuffle, Some(false));
+    }
+
+    #[test]
+    fn test_yaml_to_json_conversion() {
+        let yaml = r#"
+model:
+  name: test_workload
+framework: pytorch
+dataset:
+  data_folder: /test/path
+  format: npz
+reader:
+  batch_size: 32
+  shuffle: true
+        "#;
+
+        let config = DlioConfig::from_yaml(yaml).expect("Should parse YAML");
+        assert_eq!(
+            config.model.as_ref().unwrap().name.as_deref(),
+            Some("test_workload")
+        );
+        assert_eq!(config.reader.batch_size, Some(32));
+    }
+
+    /// Test backend detection from data_folder URIs
+    #[test]
+    fn test_backend_detection() {
+        let test_cases = [
+            ("file:///tmp/data", "file"),
+            ("s3://bucket/path", "s3"),
+            ("az://account/container/path", "azure"),
+            ("direct:///mnt/nvme/data", "direct"),
+            ("/local/path", "file"), // implicit file
+        ];
+
+        for (uri, expected_backend) in test_cases {
+            let json = format!(
+                r#"{{
+                "dataset": {{
+                    "data_folder": "{}"
+                }},
+                "reader": {{}}
+            }}"#,
+                uri
+            );
+
+            let config = DlioConfig::from_json(&json).expect("Should parse config with valid URI");
+
+            let backend = config.detect_storage_backend();
+            assert_eq!(backend, expected_backend, "Failed for URI: {}", uri);
+        }
+    }
+
+    /// Test framework profile integration
+    #[test]
+    fn test_framework_profiles() {
+        let json = r#"{
+            "framework": "pytorch",
+            "dataset": {
+                "data_folder": "/test"
+            },
+            "reader": {},
+            "framework_profiles": {
+                "pytorch": {
+                    "num_workers": 4,
+                    "pin_memory": true,
+                    "persistent_workers": true,
+                    "prefetch_factor": 3,
+                    "drop_last": false
+                }
+            }
+        }"#;
+
+        let config =
+            DlioConfig::from_json(json).expect("Should parse config with framework profiles");
+
+        assert_eq!(config.framework.as_deref(), Some("pytorch"));
+
+        let pytorch_config = config.get_pytorch_config();
+        assert!(pytorch_config.is_some());
+
+        let pytorch = pytorch_config.unwrap();
+        assert_eq!(pytorch.num_workers, Some(4));
+        assert_eq!(pytorch.pin_memory, Some(true));
+        assert_eq!(pytorch.persistent_workers, Some(true));
+        assert_eq!(pytorch.prefetch_factor, Some(3));
+        assert_eq!(pytorch.drop_last, Some(false));
+    }
+
+    /// Test RunPlan conversion with comprehensive fields
+    #[test]
+    fn test_run_plan_conversion() {
+        let json = r#"{
+            "model": {
+                "name": "test_model",
+                "model_size": 500000000
+            },
+            "framework": "pytorch",
+            "workflow": {
+                "generate_data": true,
+                "train": true,
+                "checkpoint": true
+            },
+            "dataset": {
+                "data_folder": "file:///mnt/vast1/test_data",
+                "format": "npz",
+                "num_files_train": 100,
+                "num_samples_per_file": 64,
+                "record_length_bytes": 8192
+            },
+            "reader": {
+                "batch_size": 16,
+                "prefetch": 4,
+                "shuffle": true,
+                "read_threads": 8
+            }
+        }"#;
+
+        let config = DlioConfig::from_json(json).expect("Should parse config for RunPlan");
+
+        let run_plan = config.to_run_plan().expect("Should convert to RunPlan");
+
+        // Verify model plan
+        assert_eq!(run_plan.model.name, "test_model");
+        assert_eq!(run_plan.model.framework, "pytorch");
+
+        // Verify workflow plan
+        assert!(run_plan.workflow.generate_data);
+        assert!(run_plan.workflow.train);
+        assert!(run_plan.workflow.checkpoint);
+
+        // Verify dataset plan
+        assert_eq!(
+            run_plan.dataset.data_folder_uri,
+            "file:///mnt/vast1/test_data"
+        );
+        assert_eq!(run_plan.dataset.format, "npz");
+        assert_eq!(run_plan.dataset.train.num_files, 100);
+        assert_eq!(run_plan.dataset.train.num_samples_per_file, 64);
+
+        // Verify reader plan
+        assert_eq!(run_plan.reader.batch_size, 16);
+        assert_eq!(run_plan.reader.prefetch, 4);
+        assert!(run_plan.reader.shuffle);
+    }
+
+    #[test]
+    fn test_eval_and_checkpoint_reader_fall_back_to_training() {
+        let yaml = "dataset:\n  data_folder: s3://bucket/train\nreader:\n  batch_size: 16\n  prefetch: 4\n  read_threads: 8\n  batch_size_eval: 64\n  read_threads_eval: 2\n  prefetch_checkpoint: 1\n";
+        let plan = DlioConfig::from_yaml(yaml).unwrap().to_run_plan().unwrap();
+        assert_eq!(plan.reader.eval, PhaseReaderPlan { batch_size: 64, prefetch: 4, read_threads: 2 });
+        assert_eq!(plan.reader.checkpoint, PhaseReaderPlan { batch_size: 16, prefetch: 1, read_threads: 8 });
+    }
+
+    #[test]
+    fn test_data_folder_weights_must_be_finite_and_positive() {
+        let yaml = |weight: &str| format!("dataset:\n  data_folder: s3://hot\n  data_folders:\n    - uri: s3://hot\n      weight: {}\n    - uri: s3://cold\n", weight);
+        let config = DlioConfig::from_yaml(&yaml("0.8")).unwrap();
+        let folders = config.dataset.data_folders.unwrap();
+        assert_eq!((folders[0].weight, folders[1].weight), (Some(0.8), None));
+
+        let err = DlioConfig::from_yaml(&yaml(".inf")).unwrap_err();
+        assert!(format!("{:#}", err).contains("dataset.data_folders[0].weight"), "{:#}", err);
+        assert!(DlioConfig::from_yaml(&yaml(".nan")).is_err());
+        assert!(DlioConfig::from_yaml(&yaml("-1")).is_err());
+        assert!(DlioConfig::from_yaml(&yaml("0")).is_err());
+    }
+
+    /// Test error handling for invalid configurations
+    #[test]
+    fn test_error_handling_invalid_json() {
+        let invalid_json = r#"{ "invalid": json syntax }"#;
+        let result = DlioConfig::from_json(invalid_json);
+        assert!(result.is_err(), "Should fail on invalid JSON");
+    }
+
+    /// Test data_folder URI normalization
+    #[test]
+    fn test_data_folder_uri_normalization() {
+        let test_cases = [
+            ("file:///tmp/data", "file:///tmp/data"),
+            ("s3://bucket/key", "s3://bucket/key"),
+            ("az://account/container", "az://account/container"),
+        ];
+
+        for (input, expected) in test_cases {
+            let json = format!(
+                r#"{{
+                "dataset": {{
+                    "data_folder": "{}"
+                }},
+                "reader": {{}}
+            }}"#,
+                input
+            );
+
+            let config = DlioConfig::from_json(&json).expect("Should parse config");
+
+            let normalized_uri = config.data_folder_uri();
+            assert_eq!(normalized_uri, expected, "Failed to normalize: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_relative_paths_anchor_to_workdir() {
+        let root = std::env::temp_dir().canonicalize().unwrap();
+        let yaml = "dataset:\n  data_folder: data/train\nreader: {}\ncheckpointing: {checkpoint_folder: 'direct://ckpt'}\n";
+        let mut config = DlioConfig::from_yaml(yaml).unwrap();
+        config.anchor_relative_paths(&std::env::temp_dir()).unwrap();
+        assert_eq!(config.data_folder_uri(), format!("file://{}/data/train", root.display()));
+        assert_eq!(config.resolved_data_folder_uri().unwrap(), config.data_folder_uri());
+        assert_eq!(
+            config.checkpointing.as_ref().unwrap().checkpoint_folder.as_deref(),
+            Some(format!("direct://{}/ckpt", root.display()).as_str())
+        );
+
+        assert_eq!(anchor_local_uri("s3://bucket/train", &root), None);
+        assert_eq!(anchor_local_uri("/abs/train", &root), None);
+        assert!(config.anchor_relative_paths(&root.join("dl-driver-missing-workdir")).is_err());
+    }
+}
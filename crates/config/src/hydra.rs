@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/hydra.rs
+//
+// Stock DLIO/MLCommons config layouts
+//
+// MLCommons configs nest the workload under a `workload:` key and pull parts
+// in through hydra `defaults:` lists. This resolves the simple cases so those
+// YAMLs run unmodified next to flat dl-driver configs:
+//
+// - `- name` merges `<root>/name.yaml` at the top level
+// - `- group: option` merges `<root>/group/option.yaml` under `group:`, or at
+//   the top level when that file starts with `# @package _global_`
+// - `- _self_` marks where the file's own content is merged (last when absent)
+// - `override ...`, `hydra/...` and null entries are skipped
+//
+// `<root>` is the directory of the top-level config; included files may have
+// their own defaults. Later entries override earlier ones, mappings merging
+// key by key. Finally a `workload:` mapping is unwrapped, with any sibling
+// sections (e.g. dl-driver extensions) merged over it.
+//
+use anyhow::{Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Includes deeper than this are assumed to be a cycle
+const MAX_DEPTH: usize = 16;
+
+/// Read `path` and compose it: resolve `defaults:` includes, then unwrap `workload:`
+pub fn compose_file(path: &Path) -> Result<Value> {
+    let root = path.parent().unwrap_or(Path::new("."));
+    let composed = load(path, root, 0)?;
+    Ok(unwrap_workload(composed))
+}
+
+/// Unwrap a `workload:` mapping; other documents are returned unchanged
+pub fn unwrap_workload(value: Value) -> Value {
+    let Value::Mapping(mut top) = value else {
+        return value;
+    };
+    top.remove("defaults");
+    match top.remove("workload") {
+        Some(workload @ Value::Mapping(_)) => {
+            top.remove("hydra");
+            let mut unwrapped = workload;
+            merge(&mut unwrapped, Value::Mapping(top));
+            unwrapped
+        }
+        Some(other) => {
+            top.insert(Value::from("workload"), other);
+            Value::Mapping(top)
+        }
+        None => Value::Mapping(top),
+    }
+}
+
+fn load(path: &Path, root: &Path, depth: usize) -> Result<Value> {
+    anyhow::ensure!(depth <= MAX_DEPTH, "Config includes nested deeper than {} levels at {:?}", MAX_DEPTH, path);
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read config file {:?}", path))?;
+    let mut own: Value = serde_yaml::from_str(&text).with_context(|| format!("Failed to parse YAML in {:?}", path))?;
+    let defaults = match own.as_mapping_mut().and_then(|m| m.remove("defaults")) {
+        Some(Value::Sequence(defaults)) => defaults,
+        Some(Value::Null) | None => return Ok(own),
+        Some(_) => anyhow::bail!("`defaults` in {:?} must be a list", path),
+    };
+
+    let mut composed = Value::Mapping(Mapping::new());
+    let mut own = Some(own);
+    for entry in defaults {
+        match entry {
+            Value::String(name) if name == "_self_" => {
+                if let Some(own) = own.take() {
+                    merge(&mut composed, own);
+                }
+            }
+            Value::String(name) => {
+                let included = load(&root.join(format!("{}.yaml", name)), root, depth + 1)?;
+                merge(&mut composed, included);
+            }
+            Value::Mapping(entry) => {
+                for (group, option) in entry {
+                    let (Some(group), Some(option)) = (group.as_str(), option.as_str()) else {
+                        continue;
+                    };
+                    if group.starts_with("override ") || group.starts_with("hydra/") {
+                        continue;
+                    }
+                    let file = root.join(group).join(format!("{}.yaml", option));
+                    let included = load(&file, root, depth + 1)?;
+                    if is_global_package(&file) {
+                        merge(&mut composed, included);
+                    } else {
+                        let mut wrapped = Mapping::new();
+                        wrapped.insert(Value::from(group), included);
+                        merge(&mut composed, Value::Mapping(wrapped));
+                    }
+                }
+            }
+            other => anyhow::bail!("Unsupported `defaults` entry in {:?}: {:?}", path, other),
+        }
+    }
+    if let Some(own) = own {
+        merge(&mut composed, own);
+    }
+    Ok(composed)
+}
+
+/// Hydra's `# @package _global_` header: the group file belongs at the top level
+fn is_global_package(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|text| {
+        text.lines()
+            .take_while(|line| line.trim_start().starts_with('#') || line.trim().is_empty())
+            .any(|line| line.trim_start_matches('#').trim() == "@package _global_")
+    })
+}
+
+/// Merge `overlay` into `base`: mappings key by key, anything else replaced
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, Value::Null) if !base.is_null() => {}
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DlioConfig;
+
+    #[test]
+    fn test_hydra_defaults_and_workload_wrapper() {
+        let dir = std::env::temp_dir().join(format!("dl_driver_hydra_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("workload")).unwrap();
+        std::fs::write(
+            dir.join("workload/unet3d.yaml"),
+            "model:\n  name: unet3d\ndataset:\n  data_folder: data/unet3d/\n  num_files_train: 168\nreader:\n  batch_size: 4\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.join("storage")).unwrap();
+        std::fs::write(dir.join("storage/s3.yaml"), "# @package _global_\nworkload:\n  dataset:\n    data_folder: s3://bucket/unet3d/\n").unwrap();
+        std::fs::write(dir.join("common.yaml"), "workload:\n  reader:\n    batch_size: 2\n    read_threads: 8\n").unwrap();
+        std::fs::write(
+            dir.join("config.yaml"),
+            "defaults:\n  - _self_\n  - workload: unet3d\n  - storage: s3\n  - common\n  - override hydra/job_logging: disabled\nworkload:\n  reader:\n    batch_size: 1\n",
+        )
+        .unwrap();
+
+        // _self_ first: later includes override the config's own batch_size; storage/s3 is global
+        let config: DlioConfig = serde_yaml::from_value(compose_file(&dir.join("config.yaml")).unwrap()).unwrap();
+        assert_eq!(config.model.as_ref().unwrap().name.as_deref(), Some("unet3d"));
+        assert_eq!(config.dataset.data_folder, "s3://bucket/unet3d/");
+        assert_eq!(config.dataset.num_files_train, Some(168));
+        assert_eq!((config.reader.batch_size, config.reader.read_threads), (Some(2), Some(8)));
+
+        // Already-composed (hydra output) layout, with a dl-driver section beside the workload
+        let wrapped = "workload:\n  dataset:\n    data_folder: /data\n  reader: {}\nhydra:\n  run: {dir: out}\nstorage:\n  request_timing: true\n";
+        let config = DlioConfig::from_yaml(wrapped).unwrap();
+        assert_eq!(config.dataset.data_folder, "/data");
+        assert!(config.storage.is_some());
+
+        std::fs::write(dir.join("loop.yaml"), "defaults:\n  - loop\n").unwrap();
+        assert!(compose_file(&dir.join("loop.yaml")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
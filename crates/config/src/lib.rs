@@ -0,0 +1,22 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! dl-driver configuration: DLIO YAML/JSON parsing and RunPlan computation.
+//!
+//! Depends only on serde and anyhow, so it builds for `wasm32-unknown-unknown`
+//! and a browser can validate configs and estimate run plans without tokio or
+//! s3dlio. dl_driver_core re-exports everything here as `dl_driver_core::dlio_compat`.
+
+// DLIO config structs, parsing and RunPlan
+pub mod dlio_compat;
+// MLCommons layouts: `workload:` wrapper and hydra `defaults:` includes
+pub mod hydra;
+// reader.read_threads semantics: DLIO workers vs dl-driver pool
+pub mod thread_model;
+
+pub use dlio_compat::{DlioConfig, RunPlan};
+
+/// Parse a YAML config and compute its RunPlan (validate + estimate in one call)
+pub fn plan_yaml(yaml: &str) -> anyhow::Result<RunPlan> {
+    DlioConfig::from_yaml(yaml)?.to_run_plan()
+}
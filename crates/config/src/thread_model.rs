@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/thread_model.rs
+//
+// What `reader.read_threads` means (`reader.thread_model`)
+//
+// DLIO's read_threads is the number of DataLoader worker processes per rank
+// (PyTorch num_workers); each worker reads one sample at a time, so at most
+// read_threads requests are outstanding, and 0 reads on the main process.
+// dl-driver's own loaders historically treated it as a pool size and let the
+// s3dlio pool keep four requests per worker in flight. Comparing the two at
+// the same read_threads is apples to oranges, so the mapping is explicit:
+//
+//   model    default  workers          max in flight
+//   native   8        read_threads     4 x workers (s3dlio pool), workers (native backends)
+//   dlio     1        read_threads|1   workers
+//
+// The effective model is recorded in the results under "concurrency".
+//
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::dlio_compat::DlioConfig;
+
+/// How `reader.read_threads` maps onto loader concurrency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadModel {
+    /// dl-driver pool semantics (default)
+    Native,
+    /// DLIO/PyTorch worker semantics: one outstanding read per worker
+    Dlio,
+}
+
+/// Effective loader concurrency of a run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Concurrency {
+    pub thread_model: String,
+    /// `reader.read_threads` as configured
+    pub read_threads: Option<usize>,
+    /// Loader workers (pool slots)
+    pub workers: usize,
+    /// Storage requests outstanding at once
+    pub max_inflight: usize,
+    /// "s3dlio" or the native backend serving the reads
+    pub loader: String,
+}
+
+impl ThreadModel {
+    /// From `reader.thread_model` ("native" or "dlio")
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        match config.reader.thread_model.as_deref().unwrap_or("native") {
+            "native" => Ok(Self::Native),
+            "dlio" | "dlio-compatible" => Ok(Self::Dlio),
+            other => anyhow::bail!("Unknown reader.thread_model '{}' (expected 'native' or 'dlio')", other),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::Dlio => "dlio",
+        }
+    }
+
+    /// Workers and in-flight requests for `read_threads` on the given loader
+    /// (`native_loader`: a dl-driver backend rather than the s3dlio pool)
+    pub fn concurrency(&self, read_threads: Option<usize>, loader: &str, native_loader: bool) -> Concurrency {
+        let (workers, max_inflight) = match self {
+            Self::Native => {
+                let workers = read_threads.unwrap_or(8).max(1);
+                (workers, if native_loader { workers } else { workers * 4 })
+            }
+            Self::Dlio => {
+                let workers = read_threads.unwrap_or(1).max(1);
+                (workers, workers)
+            }
+        };
+        Concurrency {
+            thread_model: self.name().to_string(),
+            read_threads,
+            workers,
+            max_inflight,
+            loader: loader.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_models_map_read_threads() {
+        let native = ThreadModel::Native;
+        assert_eq!(native.concurrency(Some(4), "s3dlio", false).max_inflight, 16);
+        assert_eq!(native.concurrency(Some(4), "http", true).max_inflight, 4);
+        assert_eq!(native.concurrency(None, "s3dlio", false).workers, 8);
+
+        // DLIO: one outstanding read per worker, 0 workers reads on the main process
+        let dlio = ThreadModel::Dlio;
+        let c = dlio.concurrency(Some(4), "s3dlio", false);
+        assert_eq!((c.workers, c.max_inflight, c.thread_model.as_str()), (4, 4, "dlio"));
+        assert_eq!(dlio.concurrency(Some(0), "s3dlio", false).max_inflight, 1);
+        assert_eq!(dlio.concurrency(None, "s3dlio", false).workers, 1);
+
+        let mut config = DlioConfig::from_yaml("dataset: {data_folder: /tmp/d}\nreader: {thread_model: dlio}\n").unwrap();
+        assert_eq!(ThreadModel::from_config(&config).unwrap(), ThreadModel::Dlio);
+        config.reader.thread_model = Some("threads".to_string());
+        assert!(ThreadModel::from_config(&config).is_err());
+    }
+}
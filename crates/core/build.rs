@@ -0,0 +1,28 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/build.rs
+//
+// Records the s3dlio version this build resolved (S3DLIO_VERSION, from the
+// workspace Cargo.lock) for results, manifests and MLPerf reports
+
+fn main() {
+    let lockfile = "../../Cargo.lock";
+    println!("cargo:rerun-if-changed={}", lockfile);
+    let version = std::fs::read_to_string(lockfile)
+        .ok()
+        .and_then(|lock| locked_version(&lock, "s3dlio"))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=S3DLIO_VERSION={}", version);
+}
+
+/// Version of `package` in a Cargo.lock's `[[package]]` entries
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+    let name = format!("name = \"{}\"", package);
+    lock.split("[[package]]").find_map(|entry| {
+        let mut lines = entry.lines().map(str::trim);
+        lines.any(|line| line == name).then_some(())?;
+        let version = lines.find_map(|line| line.strip_prefix("version = "))?;
+        Some(version.trim_matches('"').to_string())
+    })
+}
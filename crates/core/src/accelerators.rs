@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/accelerators.rs
+//
+// Accelerators per rank (`--gpus-per-rank`, `train.gpus_per_rank`)
+//
+// By default every rank of a multi-rank run stands for one accelerator. A
+// rank can stand for several instead, e.g. one rank per 8-GPU node: each
+// step it loads one batch for every accelerator it feeds (reader.batch_size
+// × gpus_per_rank samples) while they compute in parallel for one
+// computation_time. The rank's AU is then the AU of each of its
+// accelerators, so:
+//
+// - run-level AU weights each rank's AU by the accelerators it stands for
+// - accelerator totals (derived per-accelerator rates, MLPerf
+//   num_accelerators) sum the per-rank counts recorded under
+//   "derived.accelerators"
+//
+use anyhow::Result;
+
+use crate::dlio_compat::DlioConfig;
+
+/// Accelerators of a run, by rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceleratorLayout {
+    pub ranks: u32,
+    pub gpus_per_rank: u32,
+}
+
+impl AcceleratorLayout {
+    /// `--gpus-per-rank` if given, else `train.gpus_per_rank`, else 1
+    pub fn from_config(config: &DlioConfig, gpus_per_rank: Option<u32>, ranks: u32) -> Result<Self> {
+        let gpus_per_rank = gpus_per_rank
+            .or_else(|| config.train.as_ref().and_then(|t| t.gpus_per_rank))
+            .unwrap_or(1);
+        if gpus_per_rank == 0 {
+            anyhow::bail!("gpus_per_rank must be at least 1");
+        }
+        Ok(Self { ranks: ranks.max(1), gpus_per_rank })
+    }
+
+    pub fn total(&self) -> u32 {
+        self.ranks * self.gpus_per_rank
+    }
+
+    /// The config a rank trains with: per-step batch sizes cover all of its accelerators
+    pub fn step_config(&self, config: &DlioConfig) -> DlioConfig {
+        let mut config = config.clone();
+        if self.gpus_per_rank > 1 {
+            let n = self.gpus_per_rank as usize;
+            let reader = &mut config.reader;
+            for batch in [&mut reader.batch_size, &mut reader.batch_size_bytes, &mut reader.batch_size_eval] {
+                *batch = batch.map(|b| b * n);
+            }
+            // Unset batch_size is DLIO's 1 sample per accelerator
+            reader.batch_size.get_or_insert(n);
+        }
+        config
+    }
+}
+
+/// Run-level AU: each rank's AU fraction weighted by its accelerators, as (accelerators, au_fraction)
+pub fn weighted_au(ranks: &[(u64, f64)]) -> f64 {
+    let accelerators: u64 = ranks.iter().map(|&(a, _)| a).sum();
+    if accelerators == 0 {
+        return 0.0;
+    }
+    ranks.iter().map(|&(a, au)| a as f64 * au).sum::<f64>() / accelerators as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpus_per_rank_scales_steps_and_weights_au() {
+        let config = DlioConfig::from_yaml(
+            "dataset:\n  data_folder: /data\nreader:\n  batch_size: 32\ntrain:\n  computation_time: 0.1\n  gpus_per_rank: 4\n",
+        )
+        .unwrap();
+
+        let layout = AcceleratorLayout::from_config(&config, None, 2).unwrap();
+        assert_eq!((layout.gpus_per_rank, layout.total()), (4, 8));
+        assert_eq!(AcceleratorLayout::from_config(&config, Some(8), 1).unwrap().total(), 8);
+        assert!(AcceleratorLayout::from_config(&config, Some(0), 1).is_err());
+
+        let step = layout.step_config(&config);
+        assert_eq!((step.reader.batch_size, step.reader.batch_size_bytes), (Some(128), None));
+        assert_eq!(step.train.as_ref().and_then(|t| t.computation_time), Some(0.1));
+        let single = AcceleratorLayout { ranks: 1, gpus_per_rank: 1 }.step_config(&config);
+        assert_eq!(single.reader.batch_size, Some(32));
+
+        // One 8-GPU rank at 90% and one 1-GPU rank at 0%: the run's accelerators are 80% utilized
+        assert!((weighted_au(&[(8, 0.9), (1, 0.0)]) - 0.8).abs() < 1e-12);
+        assert!((weighted_au(&[(1, 0.5), (1, 0.7)]) - 0.6).abs() < 1e-12);
+        assert_eq!(weighted_au(&[]), 0.0);
+    }
+}
@@ -0,0 +1,306 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/advisor.rs
+//
+// Reader settings advisor (`advise`)
+//
+// A short probe GETs dataset objects at increasing concurrency and measures
+// object rate and latency per level. A steady-state loader model then predicts
+// AU for candidate reader settings:
+//
+//   in-flight   = min(read_threads, objects_per_batch × (prefetch + 1))
+//   io/batch    = objects_per_batch / rate(in-flight)      (rate interpolated)
+//   compute     = computation_time × batch_size / configured batch_size
+//   AU          = min(1, compute / io)
+//
+// and prefetch is sized so the queue covers one batch load plus the latency
+// tail (p99 - mean) of a GET. The advisor keeps the configured batch_size and
+// picks the fewest read_threads meeting `metric.au`; only if no probed thread
+// count suffices are larger batches (2x, 4x) tried, since batch size is a
+// training hyperparameter. The result is printed as a `reader:` snippet.
+//
+// Later probe levels may be served from caches warmed by earlier ones; each
+// level reads the next slice of the listing to limit this.
+//
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+use crate::backends::{self, DataBackend};
+use crate::components;
+use crate::dlio_compat::DlioConfig;
+use crate::list_interference::LatencySummary;
+
+/// Larger batch sizes tried when the configured one cannot meet the AU target
+const BATCH_MULTIPLIERS: &[usize] = &[1, 2, 4];
+
+/// Largest prefetch depth the advisor suggests
+const MAX_PREFETCH: usize = 64;
+
+/// Measured GET performance at one concurrency
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeLevel {
+    pub threads: usize,
+    pub objects_per_s: f64,
+    pub mib_per_s: f64,
+    pub latency: LatencySummary,
+}
+
+/// Workload figures the model needs, from the config
+#[derive(Debug, Clone, Serialize)]
+pub struct AdvisorInputs {
+    /// Per-step compute time at the configured batch size, in seconds
+    pub computation_time_s: f64,
+    pub batch_size: usize,
+    pub samples_per_file: usize,
+    /// Objects per file (multi-component samples)
+    pub objects_per_file: usize,
+    pub au_threshold: f64,
+    pub read_threads: usize,
+    pub prefetch: usize,
+}
+
+impl AdvisorInputs {
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        let computation_time_s = config.train.as_ref().and_then(|t| t.computation_time).unwrap_or(0.0);
+        if computation_time_s <= 0.0 {
+            anyhow::bail!("The advisor needs train.computation_time > 0 to size the reader against");
+        }
+        Ok(Self {
+            computation_time_s,
+            batch_size: config.reader.batch_size.unwrap_or(16).max(1),
+            samples_per_file: config.dataset.num_samples_per_file.unwrap_or(1).max(1),
+            objects_per_file: components::components(config).map_or(1, |c| c.len()),
+            au_threshold: config.metric.as_ref().and_then(|m| m.au).unwrap_or(0.9),
+            read_threads: config.reader.read_threads.unwrap_or(8),
+            prefetch: config.reader.prefetch.unwrap_or(4),
+        })
+    }
+
+    fn objects_per_batch(&self, batch_size: usize) -> usize {
+        batch_size.div_ceil(self.samples_per_file) * self.objects_per_file
+    }
+}
+
+/// Reader settings with their predicted outcome
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ReaderSuggestion {
+    pub batch_size: usize,
+    pub read_threads: usize,
+    pub prefetch: usize,
+    pub predicted_au: f64,
+    /// Predicted time to load one batch, in ms
+    pub batch_io_ms: f64,
+}
+
+/// Advisor output
+#[derive(Debug, Clone, Serialize)]
+pub struct Advice {
+    pub inputs: AdvisorInputs,
+    pub probe: Vec<ProbeLevel>,
+    pub mean_object_bytes: f64,
+    pub current: ReaderSuggestion,
+    pub recommended: ReaderSuggestion,
+    /// Whether the recommendation meets the AU target
+    pub meets_target: bool,
+    pub notes: Vec<String>,
+}
+
+impl Advice {
+    /// Pick reader settings from probe results
+    pub fn compute(inputs: AdvisorInputs, mut probe: Vec<ProbeLevel>, mean_object_bytes: f64) -> Result<Self> {
+        probe.retain(|l| l.objects_per_s > 0.0);
+        probe.sort_by_key(|l| l.threads);
+        if probe.is_empty() {
+            anyhow::bail!("Probe produced no successful reads");
+        }
+
+        let current = predict(&inputs, &probe, inputs.batch_size, inputs.read_threads, inputs.prefetch);
+        let mut candidates = Vec::new();
+        for multiplier in BATCH_MULTIPLIERS {
+            let batch_size = inputs.batch_size * multiplier;
+            for level in &probe {
+                let prefetch = prefetch_for(&inputs, &probe, batch_size, level.threads);
+                candidates.push(predict(&inputs, &probe, batch_size, level.threads, prefetch));
+            }
+        }
+        // First candidate meeting the target: smallest batch, then fewest threads
+        let (recommended, meets_target) = match candidates.iter().find(|c| c.predicted_au >= inputs.au_threshold) {
+            Some(c) => (c.clone(), true),
+            None => {
+                let best = candidates.iter().fold(&candidates[0], |best, c| if c.predicted_au > best.predicted_au { c } else { best });
+                (best.clone(), false)
+            }
+        };
+
+        let mut notes = Vec::new();
+        if !meets_target {
+            notes.push(format!(
+                "No probed setting reaches AU {:.0}%: storage delivers at most {:.0} objects/s; the best predicted is {:.1}%",
+                inputs.au_threshold * 100.0,
+                probe.iter().map(|l| l.objects_per_s).fold(0.0, f64::max),
+                recommended.predicted_au * 100.0
+            ));
+        }
+        if recommended.batch_size != inputs.batch_size {
+            notes.push(format!(
+                "batch_size {} -> {} changes the training hyperparameters; use it only if the workload allows",
+                inputs.batch_size, recommended.batch_size
+            ));
+        }
+        if recommended.read_threads == probe.last().unwrap().threads {
+            notes.push("The largest probed concurrency was chosen; probing higher (--max-threads) may do better".to_string());
+        }
+        Ok(Self { inputs, probe, mean_object_bytes, current, recommended, meets_target, notes })
+    }
+
+    /// The recommendation as a config fragment
+    pub fn snippet(&self) -> String {
+        format!(
+            "reader:\n  batch_size: {}\n  read_threads: {}\n  prefetch: {}\n",
+            self.recommended.batch_size, self.recommended.read_threads, self.recommended.prefetch
+        )
+    }
+}
+
+/// Object rate at `threads` in flight, interpolated between probe levels
+fn rate_at(probe: &[ProbeLevel], threads: f64) -> f64 {
+    let first = &probe[0];
+    if threads <= first.threads as f64 {
+        // Below the first level, rate scales with concurrency
+        return first.objects_per_s * threads / first.threads as f64;
+    }
+    for pair in probe.windows(2) {
+        let (lo, hi) = (&pair[0], &pair[1]);
+        if threads <= hi.threads as f64 {
+            let f = (threads - lo.threads as f64) / (hi.threads - lo.threads) as f64;
+            return lo.objects_per_s + f * (hi.objects_per_s - lo.objects_per_s);
+        }
+    }
+    probe.last().unwrap().objects_per_s
+}
+
+/// Latency tail (p99 - mean) of the probe level closest to `threads`, in seconds
+fn latency_tail_s(probe: &[ProbeLevel], threads: usize) -> f64 {
+    let level = probe.iter().min_by_key(|l| l.threads.abs_diff(threads)).unwrap();
+    (level.latency.p99_ms - level.latency.mean_ms).max(0.0) / 1000.0
+}
+
+fn predict(inputs: &AdvisorInputs, probe: &[ProbeLevel], batch_size: usize, read_threads: usize, prefetch: usize) -> ReaderSuggestion {
+    let objects = inputs.objects_per_batch(batch_size);
+    let in_flight = read_threads.min(objects * (prefetch + 1)).max(1);
+    let io_s = objects as f64 / rate_at(probe, in_flight as f64);
+    let compute_s = inputs.computation_time_s * batch_size as f64 / inputs.batch_size as f64;
+    ReaderSuggestion {
+        batch_size,
+        read_threads,
+        prefetch,
+        predicted_au: (compute_s / io_s).min(1.0),
+        batch_io_ms: io_s * 1000.0,
+    }
+}
+
+/// Prefetch depth covering one batch load plus the GET latency tail
+fn prefetch_for(inputs: &AdvisorInputs, probe: &[ProbeLevel], batch_size: usize, read_threads: usize) -> usize {
+    let compute_s = inputs.computation_time_s * batch_size as f64 / inputs.batch_size as f64;
+    let io_s = predict(inputs, probe, batch_size, read_threads, MAX_PREFETCH).batch_io_ms / 1000.0;
+    (((io_s + latency_tail_s(probe, read_threads)) / compute_s).ceil() as usize + 1).clamp(2, MAX_PREFETCH)
+}
+
+/// Probe the dataset and advise reader settings
+pub async fn advise(config: &DlioConfig, probe_objects: usize, max_threads: usize) -> Result<Advice> {
+    let inputs = AdvisorInputs::from_config(config)?;
+    let data_folder = config.data_folder_uri();
+    let backend: Arc<dyn DataBackend> = match backends::backend_for_config(config, None)? {
+        Some(backend) => backend,
+        None => Arc::new(backends::ObjectStoreBackend::new(data_folder)?),
+    };
+    let uris = backend.list().await.with_context(|| format!("Failed to list {}", data_folder))?;
+    if uris.is_empty() {
+        anyhow::bail!("No objects under {}; generate the dataset first", data_folder);
+    }
+
+    let mut probe = Vec::new();
+    let mut total_bytes = 0u64;
+    let mut total_gets = 0usize;
+    let mut threads = 1;
+    while threads <= max_threads.max(1) {
+        // Each level reads the next slice of the listing (wrapping on small datasets)
+        let start = total_gets % uris.len();
+        let batch: Vec<&String> = uris.iter().cycle().skip(start).take(probe_objects.max(threads)).collect();
+        let level_start = Instant::now();
+        let results: Vec<(f64, usize)> = stream::iter(batch)
+            .map(|uri| {
+                let backend = Arc::clone(&backend);
+                async move {
+                    let get_start = Instant::now();
+                    let data = backend.get(uri).await.with_context(|| format!("Probe read of {} failed", uri))?;
+                    Ok::<_, anyhow::Error>((get_start.elapsed().as_secs_f64() * 1000.0, data.len()))
+                }
+            })
+            .buffer_unordered(threads)
+            .try_collect()
+            .await?;
+        let elapsed = level_start.elapsed().as_secs_f64().max(1e-9);
+        let bytes: usize = results.iter().map(|&(_, b)| b).sum();
+        let latencies: Vec<f64> = results.iter().map(|&(ms, _)| ms).collect();
+        let level = ProbeLevel {
+            threads,
+            objects_per_s: results.len() as f64 / elapsed,
+            mib_per_s: crate::units::mib(bytes as u64) / elapsed,
+            latency: LatencySummary::from_ms(&latencies),
+        };
+        info!(
+            "🧭 Probe {:>3} threads: {:.0} objects/s, {:.1} MiB/s, mean {:.1}ms, p99 {:.1}ms",
+            threads, level.objects_per_s, level.mib_per_s, level.latency.mean_ms, level.latency.p99_ms
+        );
+        probe.push(level);
+        total_bytes += bytes as u64;
+        total_gets += results.len();
+        threads *= 2;
+    }
+
+    Advice::compute(inputs, probe, total_bytes as f64 / total_gets.max(1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(threads: usize, objects_per_s: f64, mean_ms: f64, p99_ms: f64) -> ProbeLevel {
+        ProbeLevel { threads, objects_per_s, mib_per_s: objects_per_s, latency: LatencySummary { count: 64, mean_ms, p99_ms } }
+    }
+
+    #[test]
+    fn test_advice_picks_fewest_threads_meeting_target() {
+        // 20 ms GETs scaling linearly to 16 threads (800 objects/s), flat after
+        let probe = vec![level(1, 50.0, 20.0, 30.0), level(4, 200.0, 20.0, 35.0), level(16, 800.0, 20.0, 60.0), level(64, 800.0, 80.0, 200.0)];
+        let inputs = AdvisorInputs {
+            computation_time_s: 0.1,
+            batch_size: 32,
+            samples_per_file: 1,
+            objects_per_file: 1,
+            au_threshold: 0.9,
+            read_threads: 4,
+            prefetch: 2,
+        };
+
+        // Demand is 320 objects/s: 4 threads (200/s) fall short, 16 suffice
+        let advice = Advice::compute(inputs.clone(), probe.clone(), crate::units::MIB).unwrap();
+        assert!((advice.current.predicted_au - 0.625).abs() < 1e-9, "{:?}", advice.current);
+        assert!(advice.meets_target);
+        assert_eq!((advice.recommended.batch_size, advice.recommended.read_threads), (32, 16));
+        assert!(advice.recommended.prefetch >= 2);
+        assert!(advice.snippet().contains("read_threads: 16"));
+
+        // 10x faster compute cannot be fed by any setting
+        let advice = Advice::compute(AdvisorInputs { computation_time_s: 0.01, ..inputs }, probe, crate::units::MIB).unwrap();
+        assert!(!advice.meets_target);
+        assert!(advice.recommended.predicted_au < 0.9);
+        assert!(!advice.notes.is_empty());
+    }
+}
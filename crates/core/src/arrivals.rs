@@ -0,0 +1,247 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/arrivals.rs
+//
+// Batch arrival recording and replay
+//
+// A recorded run captures when each batch became ready (left the loader) and
+// when the consumer picked it up. Replay re-delivers batches on the recorded
+// ready schedule to a no-op consumer: if replay finishes much sooner than the
+// original epoch, the consumer side (framework code), not storage, was the
+// bottleneck.
+//
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// Timing for one batch, relative to the start of its epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchArrival {
+    /// When the loader finished producing the batch
+    pub ready_ms: f64,
+    /// When the training loop received the batch
+    pub consumed_ms: f64,
+    pub items: usize,
+    pub bytes: usize,
+    pub compute_ms: f64,
+}
+
+/// Arrivals for one epoch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EpochArrivals {
+    pub epoch: u32,
+    pub duration_ms: f64,
+    pub batches: Vec<BatchArrival>,
+}
+
+/// Serialized arrival trace for a whole run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArrivalTrace {
+    pub data_folder: String,
+    pub batch_size: usize,
+    pub prefetch: usize,
+    pub epochs: Vec<EpochArrivals>,
+}
+
+impl ArrivalTrace {
+    /// Save to `path`, or zstd-compressed to `<path>.zst`; returns the path written
+    pub fn save(&self, path: &Path, compress: bool) -> Result<PathBuf> {
+        crate::artifacts::write(path, serde_json::to_string_pretty(self)?.as_bytes(), compress)
+            .with_context(|| format!("Failed to write arrival trace: {:?}", path))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = crate::artifacts::read_to_string(path)
+            .with_context(|| format!("Failed to read arrival trace: {:?}", path))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse arrival trace: {:?}", path))
+    }
+}
+
+/// Collects batch arrivals during a training run
+#[derive(Debug, Default)]
+pub struct ArrivalRecorder {
+    data: Mutex<ArrivalRecorderData>,
+}
+
+#[derive(Debug, Default)]
+struct ArrivalRecorderData {
+    epochs: Vec<EpochArrivals>,
+    epoch_start: Option<Instant>,
+}
+
+impl ArrivalRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start_epoch(&self, epoch: u32, start: Instant) {
+        let mut data = self.data.lock().unwrap();
+        data.epoch_start = Some(start);
+        data.epochs.push(EpochArrivals { epoch, ..Default::default() });
+    }
+
+    pub fn record_batch(&self, ready: Instant, consumed: Instant, items: usize, bytes: usize, compute: Duration) {
+        let mut data = self.data.lock().unwrap();
+        let Some(start) = data.epoch_start else { return };
+        let ms = |t: Instant| t.saturating_duration_since(start).as_secs_f64() * 1000.0;
+        let arrival = BatchArrival {
+            ready_ms: ms(ready),
+            consumed_ms: ms(consumed),
+            items,
+            bytes,
+            compute_ms: compute.as_secs_f64() * 1000.0,
+        };
+        if let Some(epoch) = data.epochs.last_mut() {
+            epoch.batches.push(arrival);
+        }
+    }
+
+    pub fn end_epoch(&self, duration: Duration) {
+        let mut data = self.data.lock().unwrap();
+        if let Some(epoch) = data.epochs.last_mut() {
+            epoch.duration_ms = duration.as_secs_f64() * 1000.0;
+        }
+        data.epoch_start = None;
+    }
+
+    pub fn trace(&self, data_folder: &str, batch_size: usize, prefetch: usize) -> ArrivalTrace {
+        ArrivalTrace {
+            data_folder: data_folder.to_string(),
+            batch_size,
+            prefetch,
+            epochs: self.data.lock().unwrap().epochs.clone(),
+        }
+    }
+}
+
+/// Replay outcome for one epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochReplay {
+    pub epoch: u32,
+    pub batches: usize,
+    pub recorded_ms: f64,
+    pub replay_ms: f64,
+    /// replay_ms / recorded_ms; well below 1.0 means the consumer was the bottleneck
+    pub ratio: f64,
+}
+
+/// Replay results for a whole trace
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub with_compute: bool,
+    pub epochs: Vec<EpochReplay>,
+    pub recorded_ms: f64,
+    pub replay_ms: f64,
+    pub ratio: f64,
+    pub bottleneck: String,
+}
+
+/// Ratio below which the original run is attributed to downstream code
+const DOWNSTREAM_RATIO: f64 = 0.9;
+
+/// Re-deliver a trace's batches on their recorded ready schedule
+///
+/// The consumer does nothing, unless `with_compute` is set, in which case it
+/// sleeps for each batch's recorded compute time.
+pub async fn replay(trace: &ArrivalTrace, with_compute: bool) -> Result<ReplayReport> {
+    let mut epochs = Vec::with_capacity(trace.epochs.len());
+
+    for recorded in &trace.epochs {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Duration>(trace.prefetch.max(1) * 2);
+        let schedule: Vec<(Duration, Duration)> = recorded
+            .batches
+            .iter()
+            .map(|b| (ms_duration(b.ready_ms), ms_duration(b.compute_ms)))
+            .collect();
+
+        let start = tokio::time::Instant::now();
+        let producer = tokio::spawn(async move {
+            for (ready, compute) in schedule {
+                tokio::time::sleep_until(start + ready).await;
+                if tx.send(compute).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut batches = 0;
+        while let Some(compute) = rx.recv().await {
+            if with_compute {
+                tokio::time::sleep(compute).await;
+            }
+            batches += 1;
+        }
+        producer.await.context("Replay producer task failed")?;
+
+        let replay_ms = start.elapsed().as_secs_f64() * 1000.0;
+        let recorded_ms = recorded.duration_ms;
+        debug!("Replay epoch {}: {} batches, recorded {:.1}ms, replay {:.1}ms",
+               recorded.epoch, batches, recorded_ms, replay_ms);
+        epochs.push(EpochReplay {
+            epoch: recorded.epoch,
+            batches,
+            recorded_ms,
+            replay_ms,
+            ratio: if recorded_ms > 0.0 { replay_ms / recorded_ms } else { 0.0 },
+        });
+    }
+
+    let recorded_ms: f64 = epochs.iter().map(|e| e.recorded_ms).sum();
+    let replay_ms: f64 = epochs.iter().map(|e| e.replay_ms).sum();
+    let ratio = if recorded_ms > 0.0 { replay_ms / recorded_ms } else { 0.0 };
+    let bottleneck = if ratio < DOWNSTREAM_RATIO { "downstream" } else { "storage" }.to_string();
+    info!("🔁 Replay: recorded {:.1}ms, replay {:.1}ms (ratio {:.2}) → {} bound",
+          recorded_ms, replay_ms, ratio, bottleneck);
+
+    Ok(ReplayReport { with_compute, epochs, recorded_ms, replay_ms, ratio, bottleneck })
+}
+
+fn ms_duration(ms: f64) -> Duration {
+    Duration::from_secs_f64(ms.max(0.0) / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_relative_times() {
+        let recorder = ArrivalRecorder::new();
+        let start = Instant::now();
+        recorder.start_epoch(0, start);
+        recorder.record_batch(start + Duration::from_millis(5), start + Duration::from_millis(8), 4, 4096,
+                              Duration::from_millis(2));
+        recorder.end_epoch(Duration::from_millis(10));
+
+        let trace = recorder.trace("mem://baseline", 4, 2);
+        assert_eq!(trace.epochs.len(), 1);
+        let batch = &trace.epochs[0].batches[0];
+        assert!((batch.ready_ms - 5.0).abs() < 1e-6);
+        assert!((batch.consumed_ms - 8.0).abs() < 1e-6);
+        assert!((trace.epochs[0].duration_ms - 10.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_replay_flags_downstream_bottleneck() {
+        // Batches were ready within 2ms but the epoch took 200ms: consumer-bound
+        let trace = ArrivalTrace {
+            data_folder: "mem://baseline".into(),
+            batch_size: 1,
+            prefetch: 2,
+            epochs: vec![EpochArrivals {
+                epoch: 0,
+                duration_ms: 200.0,
+                batches: (0..3)
+                    .map(|i| BatchArrival { ready_ms: i as f64, consumed_ms: 0.0, items: 1, bytes: 1, compute_ms: 50.0 })
+                    .collect(),
+            }],
+        };
+        let report = replay(&trace, false).await.unwrap();
+        assert_eq!(report.epochs[0].batches, 3);
+        assert_eq!(report.bottleneck, "downstream");
+    }
+}
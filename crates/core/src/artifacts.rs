@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/artifacts.rs
+//
+// zstd-compressed run artifacts (`run --compress`)
+//
+// Arrival traces and op logs grow with every batch, and results with a step
+// timeline are not far behind. `run --compress` picks what is written with
+// zstd:
+//
+//   traces  arrival traces and op logs (default)
+//   all     traces, rank results and their uploads
+//   none    nothing; an explicit `.zst` op-log name still compresses
+//
+// A compressed artifact gets `.zst` appended to the requested path. Readers
+// (`aggregate`, `report`, `history`, `replay`, baselines) go through `read`,
+// which detects zstd by its frame magic rather than the name and falls back
+// to `<path>.zst` when only the compressed artifact exists, so existing
+// scripts and patterns keep working whatever a run wrote.
+//
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Suffix of compressed artifacts
+pub const ZSTD_SUFFIX: &str = ".zst";
+
+/// zstd frame magic number (little-endian 0xFD2FB528)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// zstd level: fast, and JSON still shrinks several-fold
+const LEVEL: i32 = 3;
+
+/// Which run outputs are written zstd-compressed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    #[default]
+    Traces,
+    All,
+    None,
+}
+
+impl Compression {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "traces" => Ok(Self::Traces),
+            "all" => Ok(Self::All),
+            "none" | "off" => Ok(Self::None),
+            other => Err(anyhow::anyhow!("Unknown --compress '{}': use traces, all or none", other)),
+        }
+    }
+
+    /// Arrival traces and op logs
+    pub fn traces(self) -> bool {
+        self != Self::None
+    }
+
+    /// Rank results JSON
+    pub fn results(self) -> bool {
+        self == Self::All
+    }
+}
+
+/// Whether `data` starts with a zstd frame
+pub fn is_zstd(data: &[u8]) -> bool {
+    data.starts_with(&ZSTD_MAGIC)
+}
+
+/// The path an artifact is written to: `.zst` appended when compressing
+pub fn output_path(path: &Path, compress: bool) -> PathBuf {
+    let name = path.as_os_str().to_string_lossy();
+    if compress && !name.ends_with(ZSTD_SUFFIX) {
+        PathBuf::from(format!("{}{}", name, ZSTD_SUFFIX))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    zstd::encode_all(data, LEVEL).context("zstd compression failed")
+}
+
+/// `data` decompressed if it is zstd, otherwise unchanged
+pub fn decode(data: Vec<u8>) -> Result<Vec<u8>> {
+    if is_zstd(&data) {
+        zstd::decode_all(data.as_slice()).context("zstd decompression failed")
+    } else {
+        Ok(data)
+    }
+}
+
+/// Write an artifact, compressed to `<path>.zst` when `compress`; returns the path written
+pub fn write(path: &Path, data: &[u8], compress: bool) -> Result<PathBuf> {
+    let written = output_path(path, compress);
+    let data = if compress { self::compress(data)? } else { data.to_vec() };
+    std::fs::write(&written, data).with_context(|| format!("Failed to write {:?}", written))?;
+    Ok(written)
+}
+
+/// This rank's copy of a per-rank artifact path
+///
+/// A `{rank}` placeholder in the file name is replaced by the rank. Otherwise,
+/// with more than one rank, `_rank_NNNNN` is inserted before the extensions
+/// (`ops.jsonl.zst` -> `ops_rank_00001.jsonl.zst`) so ranks sharing a
+/// filesystem do not overwrite each other's file.
+pub fn rank_path(path: &Path, rank: u32, world_size: u32) -> PathBuf {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+        return path.to_path_buf();
+    };
+    let name = if name.contains("{rank}") {
+        name.replace("{rank}", &rank.to_string())
+    } else if world_size > 1 {
+        // The first '.' after the first character, so dotfiles keep their leading dot
+        match name.char_indices().skip(1).find(|&(_, c)| c == '.') {
+            Some((dot, _)) => format!("{}_rank_{:05}{}", &name[..dot], rank, &name[dot..]),
+            None => format!("{}_rank_{:05}", name, rank),
+        }
+    } else {
+        return path.to_path_buf();
+    };
+    path.with_file_name(name)
+}
+
+/// `path`, or `<path>.zst` when only the compressed artifact exists
+pub fn resolve(path: &Path) -> PathBuf {
+    let compressed = output_path(path, true);
+    if !path.exists() && compressed.exists() { compressed } else { path.to_path_buf() }
+}
+
+/// Read an artifact, decompressing zstd
+pub fn read(path: &Path) -> Result<Vec<u8>> {
+    let path = resolve(path);
+    let data = std::fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    decode(data).with_context(|| format!("Failed to decompress {:?}", path))
+}
+
+pub fn read_to_string(path: &Path) -> Result<String> {
+    String::from_utf8(read(path)?).with_context(|| format!("{:?} is not UTF-8 text", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_artifacts_read_back_transparently() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = br#"{"metrics": {"au_fraction": 0.97}}"#.repeat(64);
+
+        let plain = write(&dir.path().join("plain.json"), &json, false).unwrap();
+        let packed = write(&dir.path().join("rank_0000_results.json"), &json, true).unwrap();
+        assert_eq!(packed, dir.path().join("rank_0000_results.json.zst"));
+        let on_disk = std::fs::read(&packed).unwrap();
+        assert!(is_zstd(&on_disk) && on_disk.len() < json.len());
+
+        // By content and by the uncompressed name alike
+        assert_eq!(read(&plain).unwrap(), json);
+        assert_eq!(read(&packed).unwrap(), json);
+        assert_eq!(read(&dir.path().join("rank_0000_results.json")).unwrap(), json);
+        assert_eq!(output_path(&packed, true), packed);
+
+        assert_eq!(Compression::default(), Compression::Traces);
+        assert!(Compression::parse("all").unwrap().results() && !Compression::parse("none").unwrap().traces());
+        assert!(Compression::parse("gzip").is_err());
+    }
+
+    #[test]
+    fn test_rank_paths() {
+        let ops = Path::new("/out/ops.jsonl.zst");
+        assert_eq!(rank_path(ops, 0, 1), ops);
+        assert_eq!(rank_path(ops, 3, 4), Path::new("/out/ops_rank_00003.jsonl.zst"));
+        assert_eq!(rank_path(Path::new("/out/ops-{rank}.tsv"), 3, 4), Path::new("/out/ops-3.tsv"));
+        assert_eq!(rank_path(Path::new("oplog"), 2, 4), Path::new("oplog_rank_00002"));
+    }
+}
@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/http.rs
+//
+// Read-only HTTP(S) backend for datasets served from a web endpoint
+//
+// HTTP has no listing, so object names come from `--filelist` or from a
+// `manifest.txt` under the data folder. Entries are either paths relative to
+// the data folder or complete URLs (e.g. pre-signed URLs with query strings).
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+use super::DataBackend;
+use crate::dlio_compat::DlioConfig;
+use crate::multipart::{MultipartSettings, MultipartStats};
+use crate::request_timing;
+
+const MANIFEST_NAME: &str = "manifest.txt";
+
+/// Read-only backend for http:// and https:// data folders
+pub struct HttpBackend {
+    client: reqwest::Client,
+    base_url: String,
+    file_list: Option<Vec<String>>,
+    settings: MultipartSettings,
+    stats: MultipartStats,
+}
+
+impl HttpBackend {
+    pub fn new(config: &DlioConfig, file_list: Option<&[String]>) -> Result<Self> {
+        let settings = MultipartSettings::from_config(config);
+        let read_threads = config.reader.read_threads.unwrap_or(8) as usize;
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(read_threads * settings.concurrency)
+            .build()
+            .context("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url: format!("{}/", config.data_folder_uri().trim_end_matches('/')),
+            file_list: file_list.map(|f| f.to_vec()),
+            settings,
+            stats: MultipartStats::new(),
+        })
+    }
+
+    /// Per-range latency and retry statistics
+    pub fn stats(&self) -> &MultipartStats {
+        &self.stats
+    }
+
+    fn resolve(&self, entry: &str) -> String {
+        resolve_url(&self.base_url, entry)
+    }
+
+    /// Fetch one byte range, retrying transient failures
+    ///
+    /// Returns the body and the total object size from Content-Range, or None
+    /// when the server ignored the Range header and sent the whole object.
+    async fn get_range(&self, url: &str, offset: u64, len: u64) -> Result<(Vec<u8>, Option<u64>)> {
+        let mut attempt = 0;
+        loop {
+            let start = Instant::now();
+            let result = self
+                .client
+                .get(url)
+                .header(RANGE, format!("bytes={}-{}", offset, offset + len - 1))
+                .send()
+                .await
+                .and_then(|r| r.error_for_status());
+
+            let result = match result {
+                Ok(response) => {
+                    request_timing::mark_first_byte();
+                    let total = if response.status() == StatusCode::PARTIAL_CONTENT {
+                        response
+                            .headers()
+                            .get(CONTENT_RANGE)
+                            .and_then(|v| v.to_str().ok())
+                            .and_then(parse_content_range_total)
+                    } else {
+                        None
+                    };
+                    response.bytes().await.map(|body| (body.to_vec(), total))
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(part) => {
+                    self.stats.record_part(start.elapsed(), attempt);
+                    return Ok(part);
+                }
+                Err(e) if attempt < self.settings.max_retries => {
+                    attempt += 1;
+                    request_timing::note_retry();
+                    warn!("GET {} range {}+{} failed (attempt {}/{}): {}",
+                          url, offset, len, attempt, self.settings.max_retries, e);
+                }
+                Err(e) => {
+                    self.stats.record_failed_part(attempt);
+                    return Err(e).with_context(|| format!("Failed to read {} range {}+{}", url, offset, len));
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DataBackend for HttpBackend {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        if let Some(files) = &self.file_list {
+            return Ok(files.iter().map(|f| self.resolve(f)).collect());
+        }
+
+        let manifest_url = self.resolve(MANIFEST_NAME);
+        info!("Reading HTTP dataset manifest: {}", manifest_url);
+        let manifest = self
+            .client
+            .get(&manifest_url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("HTTP datasets need --filelist or a {} (tried {})", MANIFEST_NAME, manifest_url))?
+            .text()
+            .await
+            .context("Failed to read HTTP dataset manifest")?;
+
+        let urls: Vec<String> = parse_manifest(&manifest).map(|e| self.resolve(e)).collect();
+        if urls.is_empty() {
+            return Err(anyhow::anyhow!("HTTP dataset manifest {} lists no files", manifest_url));
+        }
+        Ok(urls)
+    }
+
+    /// Read an object with parallel ranged GETs
+    ///
+    /// The first range also discovers the object size, so no HEAD request is
+    /// needed (pre-signed URLs are usually only valid for GET).
+    async fn get(&self, url: &str) -> Result<Vec<u8>> {
+        let part_size = self.settings.part_size_bytes;
+        let (first, total) = self.get_range(url, 0, part_size).await?;
+
+        let Some(total) = total.filter(|&t| t > first.len() as u64) else {
+            self.stats.record_object(false);
+            return Ok(first);
+        };
+        self.stats.record_object(true);
+
+        let ranges: Vec<(u64, u64)> = self
+            .settings
+            .part_ranges(total)
+            .into_iter()
+            .skip_while(|&(offset, _)| offset < first.len() as u64)
+            .collect();
+        debug!("Ranged GET {}: {} bytes, {} more parts, concurrency {}",
+               url, total, ranges.len(), self.settings.concurrency);
+
+        let parts: Vec<Vec<u8>> = stream::iter(ranges)
+            .map(|(offset, len)| async move { self.get_range(url, offset, len).await.map(|(body, _)| body) })
+            .buffered(self.settings.concurrency)
+            .try_collect()
+            .await?;
+
+        let mut data = Vec::with_capacity(total as usize);
+        data.extend_from_slice(&first);
+        for part in parts {
+            data.extend_from_slice(&part);
+        }
+        Ok(data)
+    }
+
+    async fn head(&self, url: &str, len: usize) -> Result<Vec<u8>> {
+        let (mut data, _) = self.get_range(url, 0, len.max(1) as u64).await?;
+        // Servers that ignore Range send the whole object
+        data.truncate(len);
+        Ok(data)
+    }
+}
+
+/// Join a manifest/file list entry onto the base URL unless it is already absolute
+fn resolve_url(base_url: &str, entry: &str) -> String {
+    if entry.contains("://") {
+        entry.to_string()
+    } else {
+        format!("{}{}", base_url, entry.trim_start_matches('/'))
+    }
+}
+
+/// Manifest entries: one per line, blank lines and `#` comments ignored
+fn parse_manifest(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+}
+
+/// Total size from a `Content-Range: bytes 0-1023/4096` header
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/').and_then(|(_, total)| total.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_and_manifest() {
+        let base = "https://example.com/datasets/unet3d/";
+        assert_eq!(resolve_url(base, "train/img_0001.npz"),
+                   "https://example.com/datasets/unet3d/train/img_0001.npz");
+        assert_eq!(resolve_url(base, "https://cdn.example.com/a.npz?X-Amz-Signature=abc"),
+                   "https://cdn.example.com/a.npz?X-Amz-Signature=abc");
+
+        let manifest = "# unet3d train split\n\ntrain/a.npz\n  train/b.npz  \n";
+        assert_eq!(parse_manifest(manifest).collect::<Vec<_>>(), vec!["train/a.npz", "train/b.npz"]);
+    }
+
+    #[test]
+    fn test_content_range_total() {
+        assert_eq!(parse_content_range_total("bytes 0-1023/4096"), Some(4096));
+        assert_eq!(parse_content_range_total("bytes 0-1023/*"), None);
+    }
+}
@@ -0,0 +1,213 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/latency.rs
+//
+// Storage service-time model used by synthetic and simulated backends
+//
+use anyhow::Result;
+use rand::Rng;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::dlio_compat::{DlioConfig, LatencyModelConfig};
+use crate::request_timing;
+
+/// z-score of the 99th percentile of a standard normal distribution
+const Z_P99: f64 = 2.326_347_874;
+
+/// Request direction, since reads and writes may have different latency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Read,
+    Write,
+}
+
+/// Shape of the per-request latency distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyDistribution {
+    /// Every request sees the configured latency
+    Fixed,
+    /// Configured latency is the median; `sigma` is the spread of ln(latency)
+    LogNormal { sigma: f64 },
+}
+
+/// Per-request latency plus a bandwidth cap shared by all in-flight requests
+///
+/// Transfers are serialized on a single virtual link: each request reserves
+/// `bytes / bandwidth` on the link's timeline, then completes after its latency.
+#[derive(Debug)]
+pub struct LatencyModel {
+    read_latency: Duration,
+    write_latency: Duration,
+    distribution: LatencyDistribution,
+    jitter: Duration,
+    bytes_per_sec: Option<f64>,
+    link_free_at: Mutex<Option<Instant>>,
+}
+
+impl LatencyModel {
+    pub fn new(cfg: &LatencyModelConfig) -> Result<Self> {
+        let ms = |v: Option<f64>| Duration::from_secs_f64(v.unwrap_or(0.0).max(0.0) / 1000.0);
+        let read_latency = ms(cfg.read_latency_ms);
+
+        let distribution = match cfg.distribution.as_deref().unwrap_or("fixed").to_lowercase().as_str() {
+            "fixed" => LatencyDistribution::Fixed,
+            "lognormal" => {
+                let median_ms = read_latency.as_secs_f64() * 1000.0;
+                let p99_ms = cfg.p99_ms.ok_or_else(|| {
+                    anyhow::anyhow!("storage.latency_model.p99_ms is required for the lognormal distribution")
+                })?;
+                if median_ms <= 0.0 || p99_ms < median_ms {
+                    return Err(anyhow::anyhow!(
+                        "Lognormal latency needs 0 < read_latency_ms ({}) <= p99_ms ({})",
+                        median_ms, p99_ms
+                    ));
+                }
+                LatencyDistribution::LogNormal { sigma: (p99_ms / median_ms).ln() / Z_P99 }
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown storage.latency_model.distribution '{}'. Valid options: fixed, lognormal",
+                    other
+                ))
+            }
+        };
+
+        Ok(Self {
+            read_latency,
+            write_latency: ms(cfg.write_latency_ms),
+            distribution,
+            jitter: ms(cfg.jitter_ms),
+            bytes_per_sec: cfg
+                .bandwidth_mib_s
+                .filter(|&bw| bw > 0.0)
+                .map(|bw| bw * crate::units::MIB),
+            link_free_at: Mutex::new(None),
+        })
+    }
+
+    /// Build from `storage.latency_model`; a missing section means zero cost
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        Self::new(
+            &config
+                .storage
+                .as_ref()
+                .and_then(|s| s.latency_model.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// A model that adds no delay
+    pub fn zero() -> Self {
+        Self::new(&LatencyModelConfig::default()).expect("default latency model is valid")
+    }
+
+    /// Time to move `bytes` over the link, ignoring contention
+    pub fn transfer_time(&self, bytes: usize) -> Duration {
+        self.bytes_per_sec
+            .map_or(Duration::ZERO, |bps| Duration::from_secs_f64(bytes as f64 / bps))
+    }
+
+    fn base_latency(&self, op: Op) -> Duration {
+        match op {
+            Op::Read => self.read_latency,
+            Op::Write => self.write_latency,
+        }
+    }
+
+    /// Draw one request latency from the configured distribution
+    fn sample_latency(&self, op: Op) -> Duration {
+        let base = self.base_latency(op).as_secs_f64();
+        let mut rng = rand::rng();
+
+        let mut secs = match self.distribution {
+            LatencyDistribution::Fixed => base,
+            LatencyDistribution::LogNormal { sigma } => {
+                let z = crate::rng::standard_normal(|| rng.random::<f64>());
+                base * (sigma * z).exp()
+            }
+        };
+        if !self.jitter.is_zero() {
+            secs += self.jitter.as_secs_f64() * (2.0 * rng.random::<f64>() - 1.0);
+        }
+        Duration::from_secs_f64(secs.max(0.0))
+    }
+
+    /// Reserve link time for a transfer and return when it completes
+    fn reserve(&self, bytes: usize, now: Instant) -> Instant {
+        let transfer = self.transfer_time(bytes);
+        if transfer.is_zero() {
+            return now;
+        }
+        let mut free_at = self.link_free_at.lock().unwrap();
+        let start = free_at.map_or(now, |t| t.max(now));
+        let done = start + transfer;
+        *free_at = Some(done);
+        done
+    }
+
+    /// Wait for the modeled service time of one request
+    ///
+    /// The first byte arrives once the transfer starts, `transfer_time` before the end.
+    pub async fn wait(&self, op: Op, bytes: usize) {
+        let now = Instant::now();
+        let done = self.reserve(bytes, now) + self.sample_latency(op);
+        let first_byte = done.checked_sub(self.transfer_time(bytes)).unwrap_or(now).max(now);
+        if first_byte > now {
+            tokio::time::sleep_until(first_byte).await;
+        }
+        request_timing::mark_first_byte();
+        if done > first_byte {
+            tokio::time::sleep_until(done).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_bandwidth_serializes_transfers() {
+        let model = LatencyModel::new(&LatencyModelConfig {
+            read_latency_ms: Some(2.0),
+            bandwidth_mib_s: Some(1.0),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(model.sample_latency(Op::Read), Duration::from_millis(2));
+        assert_eq!(model.sample_latency(Op::Write), Duration::ZERO);
+
+        // Two 512 KiB transfers on a 1 MiB/s link finish 0.5s apart
+        let now = Instant::now();
+        let first = model.reserve(512 * 1024, now);
+        let second = model.reserve(512 * 1024, now);
+        assert_eq!(first - now, Duration::from_millis(500));
+        assert_eq!(second - now, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_lognormal_matches_target_p99() {
+        let model = LatencyModel::new(&LatencyModelConfig {
+            read_latency_ms: Some(5.0),
+            distribution: Some("lognormal".into()),
+            p99_ms: Some(50.0),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut samples: Vec<f64> = (0..20_000)
+            .map(|_| model.sample_latency(Op::Read).as_secs_f64() * 1000.0)
+            .collect();
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let p50 = samples[samples.len() / 2];
+        let p99 = samples[samples.len() * 99 / 100];
+        assert!((p50 - 5.0).abs() < 0.5, "p50 was {}", p50);
+        assert!((p99 - 50.0).abs() < 10.0, "p99 was {}", p99);
+
+        let bad = LatencyModelConfig { distribution: Some("lognormal".into()), ..Default::default() };
+        assert!(LatencyModel::new(&bad).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/mod.rs
+//
+// dl-driver native backends for URI schemes that s3dlio does not serve.
+// The training phase streams batches from these through the same prefetch
+// channel as the s3dlio AsyncPoolDataLoader path.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info};
+
+use crate::batch_channel::LoaderHandle;
+use crate::components;
+use crate::dlio_compat::DlioConfig;
+use crate::worker_balance::WorkerBalance;
+
+pub mod http;
+pub mod latency;
+pub mod mem;
+pub mod multi_endpoint;
+pub mod null;
+pub mod store;
+pub mod sim;
+pub mod stats;
+pub mod tiered;
+
+pub use http::HttpBackend;
+pub use latency::LatencyModel;
+pub use mem::MemBackend;
+pub use multi_endpoint::MultiEndpointBackend;
+pub use null::NullBackend;
+pub use store::ObjectStoreBackend;
+pub use sim::SimBackend;
+pub use tiered::TieredBackend;
+
+/// Storage backend implemented inside dl-driver rather than s3dlio
+#[async_trait]
+pub trait DataBackend: Send + Sync {
+    /// Short backend name for logs and reports
+    fn name(&self) -> &'static str;
+
+    /// List the object URIs that make up the dataset
+    async fn list(&self) -> Result<Vec<String>>;
+
+    /// Read an entire object
+    async fn get(&self, uri: &str) -> Result<Vec<u8>>;
+
+    /// Read up to the first `len` bytes of an object
+    async fn head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        let mut data = self.get(uri).await?;
+        data.truncate(len);
+        Ok(data)
+    }
+
+    /// Size of an object in bytes; backends that can look it up cheaply override this
+    async fn size(&self, uri: &str) -> Result<u64> {
+        Ok(self.get(uri).await?.len() as u64)
+    }
+
+    /// Write an object; read-only backends reject writes
+    async fn put(&self, uri: &str, _data: &[u8]) -> Result<()> {
+        Err(anyhow::anyhow!("{} backend is read-only, cannot write {}", self.name(), uri))
+    }
+
+    /// Backend-specific read statistics for the results, if any
+    fn report(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+/// URI schemes served by dl-driver native backends
+pub const NATIVE_SCHEMES: &[&str] = &["http", "https", "mem", "null", "sim"];
+
+/// Native schemes that cannot be written (datasets are staged externally)
+const READ_ONLY_SCHEMES: &[&str] = &["http", "https"];
+
+/// Native schemes whose dataset is synthesized by the backend itself
+const SYNTHETIC_SCHEMES: &[&str] = &["mem"];
+
+fn scheme(uri: &str) -> Option<&str> {
+    uri.split_once("://").map(|(scheme, _)| scheme)
+}
+
+/// Whether a data folder URI is served by a dl-driver native backend
+pub fn is_native_uri(uri: &str) -> bool {
+    scheme(uri).map_or(false, |s| NATIVE_SCHEMES.contains(&s))
+}
+
+/// Whether a data folder URI refers to a read-only native backend
+pub fn is_read_only_uri(uri: &str) -> bool {
+    scheme(uri).map_or(false, |s| READ_ONLY_SCHEMES.contains(&s))
+}
+
+/// Whether the backend synthesizes its dataset, making the generation phase a no-op
+pub fn is_synthetic_uri(uri: &str) -> bool {
+    scheme(uri).map_or(false, |s| SYNTHETIC_SCHEMES.contains(&s))
+}
+
+/// Object URIs for the configured training dataset, matching generation naming
+pub fn synthetic_object_uris(config: &DlioConfig) -> Vec<String> {
+    let base = config.data_folder_uri().trim_end_matches('/');
+    let suffixes: Vec<String> = match components::components(config) {
+        Some(comps) => comps.iter().map(components::suffix).collect(),
+        None => vec![config.dataset.format.as_deref().unwrap_or("npz").to_string()],
+    };
+    // Synthetic datasets have nothing to validate the layout against; fall back to flat names
+    let layout = crate::key_layout::KeyLayout::from_config(config).unwrap_or_default();
+    (0..config.dataset.num_files_train.unwrap_or(100))
+        .flat_map(|i| suffixes.iter().map(move |s| (i, s)))
+        .map(|(i, s)| layout.uri(base, i, s))
+        .collect()
+}
+
+/// Create the native backend for the config's data folder, if it uses one
+///
+/// `file_list` (from `--filelist`) provides the object list for backends that
+/// cannot enumerate their contents.
+pub fn backend_for_config(
+    config: &DlioConfig,
+    file_list: Option<&[String]>,
+) -> Result<Option<Arc<dyn DataBackend>>> {
+    if config.dataset.data_folders.is_some() {
+        let backend = TieredBackend::new(config, file_list)?;
+        info!("Using weighted reads across {} data folders", backend.source_reports().len());
+        return Ok(Some(Arc::new(backend)));
+    }
+    if config.storage.as_ref().is_some_and(|s| s.endpoints.is_some()) {
+        let backend = MultiEndpointBackend::new(config)?;
+        info!("Distributing reads over {} endpoints", backend.endpoint_reports().len());
+        return Ok(Some(Arc::new(backend)));
+    }
+
+    let uri = config.data_folder_uri();
+    let Some(scheme) = scheme(uri) else {
+        return Ok(None);
+    };
+
+    let backend: Arc<dyn DataBackend> = match scheme {
+        "http" | "https" => Arc::new(HttpBackend::new(config, file_list)?),
+        "mem" => Arc::new(MemBackend::new(config)?),
+        "null" => Arc::new(NullBackend::new(config)?),
+        "sim" => Arc::new(SimBackend::new(config, file_list)?),
+        _ => return Ok(None),
+    };
+    info!("Using dl-driver native {} backend for {}", backend.name(), uri);
+    Ok(Some(backend))
+}
+
+/// One object per sample, for datasets without components
+pub fn single_object_groups(uris: Vec<String>) -> Vec<Vec<String>> {
+    uris.into_iter().map(|uri| vec![uri]).collect()
+}
+
+/// Stream batches from a native backend into the training prefetch channel
+///
+/// Each group holds the objects of one sample (one per dataset component).
+/// Groups are fetched `read_threads` at a time, in listing order, each on a
+/// `workers` slot for the worker-balance report, and packed into batches of
+/// as many groups as `batch_sizes` yields for each (the last size repeats),
+/// each stamped with the time it became ready. A trailing partial batch is
+/// sent unless `drop_last` is set. At most `capacity` batches are queued; the
+/// loader stops when shut down.
+pub fn spawn_batch_loader(
+    backend: Arc<dyn DataBackend>,
+    groups: Vec<Vec<String>>,
+    batch_sizes: impl Iterator<Item = usize> + Send + 'static,
+    read_threads: usize,
+    drop_last: bool,
+    workers: Arc<WorkerBalance>,
+    capacity: usize,
+) -> LoaderHandle {
+    LoaderHandle::spawn(capacity, move |tx| async move {
+        let mut batch_sizes = batch_sizes.map(|size| size.max(1));
+        let mut batch_size = batch_sizes.next().unwrap_or(1);
+        let mut items = stream::iter(groups)
+            .map(|group| {
+                let backend = Arc::clone(&backend);
+                let workers = Arc::clone(&workers);
+                let tx = tx.clone();
+                async move {
+                    // A paused loader (checkpoint write) starts no new reads
+                    tx.resumed().await;
+                    // Each in-flight sample read occupies one worker slot
+                    let slot = workers.acquire();
+                    try_join_all(group.iter().map(|uri| {
+                        let (backend, slot) = (&backend, &slot);
+                        async move {
+                            let start = Instant::now();
+                            let result = backend.get(uri).await;
+                            match &result {
+                                Ok(data) => slot.record(start, data.len()),
+                                Err(_) => slot.record_error(),
+                            }
+                            result
+                        }
+                    }))
+                    .await
+                }
+            })
+            .buffered(read_threads.max(1));
+
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut groups_in_batch = 0usize;
+        let mut batches = 0usize;
+        loop {
+            let item = tokio::select! {
+                _ = tx.cancelled() => {
+                    debug!("Native loader cancelled at batch {}", batches);
+                    return;
+                }
+                item = items.next() => item,
+            };
+            match item {
+                None => break,
+                Some(Ok(objects)) => batch.extend(objects),
+                Some(Err(e)) => {
+                    tx.send(Err(e)).await;
+                    return;
+                }
+            }
+            groups_in_batch += 1;
+            if groups_in_batch == batch_size {
+                batches += 1;
+                groups_in_batch = 0;
+                batch_size = batch_sizes.next().unwrap_or(batch_size);
+                let full = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                if !tx.send(Ok((Instant::now(), full))).await {
+                    debug!("Consumer finished, stopping native loader at batch {}", batches);
+                    return;
+                }
+            }
+        }
+        if !batch.is_empty() && !drop_last {
+            batches += 1;
+            tx.send(Ok((Instant::now(), batch))).await;
+        }
+        info!("🛑 Native {} loader completed: {} batches loaded", backend.name(), batches);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_scheme_detection() {
+        assert!(is_native_uri("https://example.com/data/"));
+        assert!(is_native_uri("http://localhost:8000/data"));
+        assert!(!is_native_uri("s3://bucket/data"));
+        assert!(!is_native_uri("file:///tmp/data"));
+        assert!(!is_native_uri("/tmp/data"));
+        assert!(is_read_only_uri("https://example.com/data/"));
+        assert!(!is_read_only_uri("mem://baseline"));
+        assert!(is_synthetic_uri("mem://baseline"));
+        assert!(is_native_uri("sim://s3://bucket/data"));
+        assert!(!is_read_only_uri("sim://https://example.com/data"));
+    }
+}
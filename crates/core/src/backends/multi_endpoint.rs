@@ -0,0 +1,276 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/multi_endpoint.rs
+//
+// One logical dataset read from replicas at several endpoints/regions
+// (`storage.endpoints`), to evaluate multi-site read architectures:
+//
+//   storage:
+//     endpoints:
+//       policy: latency_aware          # or round_robin (default)
+//       replicas:
+//         - { uri: s3://train-us-east/data/, name: us-east }
+//         - { uri: s3://train-eu-west/data/, name: eu-west }
+//
+// Object keys are the same under every replica; the dataset is listed from
+// the first replica that answers. `latency_aware` sends each read to the
+// replica with the lowest smoothed latency, sampling the others every
+// EXPLORE_EVERY reads so estimates stay current. Failed reads move on to the
+// next replica unless `failover: false`. Per-endpoint statistics are reported.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{debug, warn};
+
+use super::stats::{ReadStats, ReadSummary, ReadWindow};
+use super::{backend_for_config, DataBackend, ObjectStoreBackend};
+use crate::dlio_compat::DlioConfig;
+
+/// Every Nth latency-aware read goes round-robin to refresh the other estimates
+const EXPLORE_EVERY: usize = 16;
+
+/// Weight of the newest sample in the smoothed latency
+const EWMA_ALPHA: f64 = 0.2;
+
+/// How reads are spread over endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointPolicy {
+    RoundRobin,
+    LatencyAware,
+}
+
+impl EndpointPolicy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "round_robin" | "round-robin" => Ok(Self::RoundRobin),
+            "latency_aware" | "latency-aware" => Ok(Self::LatencyAware),
+            other => anyhow::bail!("Unknown storage.endpoints.policy '{}' (expected round_robin or latency_aware)", other),
+        }
+    }
+}
+
+/// Picks the endpoint for each read
+pub struct EndpointChooser {
+    policy: EndpointPolicy,
+    next: AtomicUsize,
+    smoothed_ms: Mutex<Vec<Option<f64>>>,
+}
+
+impl EndpointChooser {
+    pub fn new(policy: EndpointPolicy, endpoints: usize) -> Self {
+        Self { policy, next: AtomicUsize::new(0), smoothed_ms: Mutex::new(vec![None; endpoints]) }
+    }
+
+    pub fn pick(&self) -> usize {
+        let turn = self.next.fetch_add(1, Ordering::Relaxed);
+        let smoothed = self.smoothed_ms.lock().unwrap();
+        let round_robin = turn % smoothed.len();
+        if self.policy == EndpointPolicy::RoundRobin {
+            return round_robin;
+        }
+        // Exploration reads cycle through the endpoints
+        if turn % EXPLORE_EVERY == EXPLORE_EVERY - 1 {
+            return (turn / EXPLORE_EVERY) % smoothed.len();
+        }
+        // Unmeasured endpoints first, then the fastest
+        smoothed
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or_else(|| {
+                (0..smoothed.len())
+                    .min_by(|&a, &b| smoothed[a].partial_cmp(&smoothed[b]).unwrap())
+                    .unwrap_or(round_robin)
+            })
+    }
+
+    pub fn observe(&self, endpoint: usize, latency_ms: f64) {
+        let mut smoothed = self.smoothed_ms.lock().unwrap();
+        let current = &mut smoothed[endpoint];
+        *current = Some(current.map_or(latency_ms, |s| s + EWMA_ALPHA * (latency_ms - s)));
+    }
+}
+
+struct Endpoint {
+    name: String,
+    uri: String,
+    backend: Arc<dyn DataBackend>,
+    stats: ReadStats,
+}
+
+/// Read statistics of one endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointReport {
+    pub name: String,
+    pub uri: String,
+    #[serde(flatten)]
+    pub stats: ReadSummary,
+}
+
+/// Backend distributing reads of one dataset over replica endpoints
+pub struct MultiEndpointBackend {
+    /// Logical dataset prefix (dataset.data_folder) that object URIs are reported under
+    logical: String,
+    endpoints: Vec<Endpoint>,
+    chooser: EndpointChooser,
+    failover: bool,
+    window: ReadWindow,
+}
+
+impl MultiEndpointBackend {
+    pub fn new(config: &DlioConfig) -> Result<Self> {
+        let settings = config
+            .storage
+            .as_ref()
+            .and_then(|s| s.endpoints.as_ref())
+            .ok_or_else(|| anyhow::anyhow!("storage.endpoints is not configured"))?;
+        if settings.replicas.is_empty() {
+            anyhow::bail!("storage.endpoints.replicas is empty");
+        }
+        let policy = EndpointPolicy::parse(settings.policy.as_deref().unwrap_or("round_robin"))?;
+
+        let endpoints = settings
+            .replicas
+            .iter()
+            .map(|replica| {
+                // Each replica gets the backend a single-endpoint run would use
+                let mut replica_config = config.clone();
+                replica_config.dataset.data_folder = replica.uri.clone();
+                if let Some(storage) = replica_config.storage.as_mut() {
+                    storage.endpoints = None;
+                }
+                let backend = match backend_for_config(&replica_config, None)? {
+                    Some(backend) => backend,
+                    None => Arc::new(ObjectStoreBackend::new(&replica.uri)?),
+                };
+                Ok(Endpoint {
+                    name: replica.name.clone().unwrap_or_else(|| replica.uri.clone()),
+                    uri: replica.uri.trim_end_matches('/').to_string(),
+                    backend,
+                    stats: ReadStats::default(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            logical: config.data_folder_uri().trim_end_matches('/').to_string(),
+            chooser: EndpointChooser::new(policy, endpoints.len()),
+            endpoints,
+            failover: settings.failover.unwrap_or(true),
+            window: ReadWindow::default(),
+        })
+    }
+
+    /// Object key relative to the dataset root, from a logical or replica URI
+    fn relative<'a>(&self, uri: &'a str) -> Result<&'a str> {
+        std::iter::once(self.logical.as_str())
+            .chain(self.endpoints.iter().map(|e| e.uri.as_str()))
+            .find_map(|prefix| uri.strip_prefix(prefix).filter(|rest| rest.is_empty() || rest.starts_with('/')))
+            .ok_or_else(|| anyhow::anyhow!("{} is not under the dataset {} or any endpoint replica", uri, self.logical))
+    }
+
+    pub fn endpoint_reports(&self) -> Vec<EndpointReport> {
+        let total_reads: u64 = self.endpoints.iter().map(|e| e.stats.reads()).sum();
+        self.endpoints
+            .iter()
+            .map(|endpoint| EndpointReport {
+                name: endpoint.name.clone(),
+                uri: endpoint.uri.clone(),
+                stats: endpoint.stats.summary(self.window.seconds(), total_reads),
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DataBackend for MultiEndpointBackend {
+    fn name(&self) -> &'static str {
+        "multi-endpoint"
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let mut last_error = None;
+        for endpoint in &self.endpoints {
+            match endpoint.backend.list().await {
+                Ok(uris) if !uris.is_empty() => {
+                    return uris
+                        .iter()
+                        .map(|uri| Ok(format!("{}{}", self.logical, self.relative(uri)?)))
+                        .collect();
+                }
+                Ok(_) => warn!("Endpoint {} lists no dataset objects", endpoint.name),
+                Err(e) => {
+                    warn!("Listing endpoint {} failed: {:#}", endpoint.name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        last_error.map_or(Ok(Vec::new()), Err)
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let rel = self.relative(uri)?;
+        let first = self.chooser.pick();
+        let attempts = if self.failover { self.endpoints.len() } else { 1 };
+        let mut last_error = None;
+        for i in (0..attempts).map(|k| (first + k) % self.endpoints.len()) {
+            let endpoint = &self.endpoints[i];
+            let start = Instant::now();
+            match endpoint.backend.get(&format!("{}{}", endpoint.uri, rel)).await {
+                Ok(data) => {
+                    let end = Instant::now();
+                    endpoint.stats.record(start, end, data.len());
+                    self.chooser.observe(i, (end - start).as_secs_f64() * 1000.0);
+                    self.window.extend(start, end);
+                    return Ok(data);
+                }
+                Err(e) => {
+                    endpoint.stats.record_error();
+                    debug!("Read of {} from endpoint {} failed: {:#}", rel, endpoint.name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No endpoints to read {}", uri)))
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self.endpoint_reports()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_endpoint_policies_and_failover() {
+        let chooser = EndpointChooser::new(EndpointPolicy::LatencyAware, 2);
+        assert_eq!(chooser.pick(), 0); // nothing measured yet
+        chooser.observe(0, 50.0);
+        assert_eq!(chooser.pick(), 1);
+        chooser.observe(1, 5.0);
+        let picks: Vec<usize> = (0..EXPLORE_EVERY).map(|_| chooser.pick()).collect();
+        assert_eq!(picks.iter().filter(|&&i| i == 0).count(), 1, "only the exploration read goes to the slow endpoint");
+
+        // The first replica cannot serve reads: every read fails over to the second
+        let yaml = "dataset:\n  data_folder: mem://b\n  num_files_train: 6\n  record_length_bytes: 32\nreader: {}\n\
+                    storage:\n  endpoints:\n    replicas:\n      - {uri: 'file:///nonexistent-dl-driver-replica', name: down}\n      \
+                    - {uri: 'mem://b', name: up}\n";
+        let backend = MultiEndpointBackend::new(&DlioConfig::from_yaml(yaml).unwrap()).unwrap();
+        let uris = backend.list().await.unwrap();
+        assert_eq!(uris.len(), 6);
+        for uri in &uris {
+            assert_eq!(backend.get(uri).await.unwrap().len(), 32);
+        }
+        let report = backend.endpoint_reports();
+        assert_eq!((report[0].name.as_str(), report[0].stats.reads), ("down", 0));
+        assert_eq!(report[0].stats.errors, 3); // round-robin sent every other read there first
+        assert_eq!((report[1].stats.reads, report[1].stats.read_share), (6, 1.0));
+        assert!(EndpointPolicy::parse("random").is_err());
+    }
+}
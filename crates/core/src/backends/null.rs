@@ -0,0 +1,77 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/null.rs
+//
+// Sink backend: writes are discarded, reads are synthesized
+//
+// Service time follows `storage.latency_model`, so dl-driver's own pipeline
+// can be measured (or configs tested) without consuming any capacity.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::debug;
+
+use super::latency::{LatencyModel, Op};
+use super::{synthetic_object_uris, DataBackend};
+use crate::dlio_compat::DlioConfig;
+
+/// null:// backend backed by a latency/bandwidth model instead of storage
+pub struct NullBackend {
+    uris: Vec<String>,
+    template: Arc<Vec<u8>>,
+    model: LatencyModel,
+    bytes_discarded: AtomicU64,
+}
+
+impl NullBackend {
+    pub fn new(config: &DlioConfig) -> Result<Self> {
+        Ok(Self::with_model(config, LatencyModel::from_config(config)?))
+    }
+
+    /// Synthetic dataset for `config` served with an explicit service-time model
+    pub fn with_model(config: &DlioConfig, model: LatencyModel) -> Self {
+        let file_size = config.dataset.record_length_bytes.unwrap_or(1024)
+            * config.dataset.num_samples_per_file.unwrap_or(1);
+        Self {
+            uris: synthetic_object_uris(config),
+            template: Arc::new(s3dlio::generate_controlled_data(file_size, 0, 0)),
+            model,
+            bytes_discarded: AtomicU64::new(0),
+        }
+    }
+
+    /// Total bytes accepted and dropped by writes
+    pub fn bytes_discarded(&self) -> u64 {
+        self.bytes_discarded.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl DataBackend for NullBackend {
+    fn name(&self) -> &'static str {
+        "null"
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.uris.clone())
+    }
+
+    async fn get(&self, _uri: &str) -> Result<Vec<u8>> {
+        self.model.wait(Op::Read, self.template.len()).await;
+        Ok(self.template.as_ref().clone())
+    }
+
+    async fn size(&self, _uri: &str) -> Result<u64> {
+        Ok(self.template.len() as u64)
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.model.wait(Op::Write, data.len()).await;
+        self.bytes_discarded.fetch_add(data.len() as u64, Ordering::Relaxed);
+        debug!("null:// discarded {} bytes for {}", data.len(), uri);
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/sim.rs
+//
+// Simulated storage for what-if analysis ("what AU would we get at p99 = X ms?")
+//
+// `sim://<inner-uri>` wraps a real backend and adds the modeled service time on
+// top of the real one; `sim://<name>` (no inner scheme) serves synthetic data
+// so the model alone determines service time.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::info;
+
+use super::latency::{LatencyModel, Op};
+use super::{DataBackend, NullBackend, ObjectStoreBackend};
+use crate::dlio_compat::DlioConfig;
+
+/// sim:// backend applying `storage.latency_model` to an inner backend
+pub struct SimBackend {
+    /// Whether URIs are `sim://<inner-uri>` (false for synthetic data)
+    wraps_inner: bool,
+    inner: Arc<dyn DataBackend>,
+    model: LatencyModel,
+}
+
+impl SimBackend {
+    pub fn new(config: &DlioConfig, file_list: Option<&[String]>) -> Result<Self> {
+        let model = LatencyModel::from_config(config)?;
+        let rest = config
+            .data_folder_uri()
+            .strip_prefix("sim://")
+            .ok_or_else(|| anyhow::anyhow!("Not a sim:// URI: {}", config.data_folder_uri()))?;
+
+        let (wraps_inner, inner): (bool, Arc<dyn DataBackend>) = if rest.contains("://") {
+            if rest.starts_with("sim://") {
+                return Err(anyhow::anyhow!("sim:// backends cannot be nested: {}", config.data_folder_uri()));
+            }
+            let mut inner_config = config.clone();
+            inner_config.dataset.data_folder = rest.to_string();
+            let inner = match super::backend_for_config(&inner_config, file_list)? {
+                Some(native) => native,
+                None => Arc::new(ObjectStoreBackend::new(rest)?),
+            };
+            (true, inner)
+        } else {
+            (false, Arc::new(NullBackend::with_model(config, LatencyModel::zero())))
+        };
+
+        info!("🧪 Simulated storage over {} backend: {:?}",
+              inner.name(), config.storage.as_ref().and_then(|s| s.latency_model.as_ref()));
+        Ok(Self { wraps_inner, inner, model })
+    }
+
+    /// Map an outer URI onto the inner backend's namespace
+    fn inner_uri<'a>(&self, uri: &'a str) -> &'a str {
+        if self.wraps_inner {
+            uri.strip_prefix("sim://").unwrap_or(uri)
+        } else {
+            uri
+        }
+    }
+
+    /// Map an inner URI back into the sim:// namespace
+    fn outer_uri(&self, uri: String) -> String {
+        if self.wraps_inner {
+            format!("sim://{}", uri)
+        } else {
+            uri
+        }
+    }
+}
+
+#[async_trait]
+impl DataBackend for SimBackend {
+    fn name(&self) -> &'static str {
+        "sim"
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.inner.list().await?.into_iter().map(|u| self.outer_uri(u)).collect())
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let data = self.inner.get(self.inner_uri(uri)).await?;
+        self.model.wait(Op::Read, data.len()).await;
+        Ok(data)
+    }
+
+    async fn size(&self, uri: &str) -> Result<u64> {
+        self.inner.size(self.inner_uri(uri)).await
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.model.wait(Op::Write, data.len()).await;
+        self.inner.put(self.inner_uri(uri), data).await
+    }
+}
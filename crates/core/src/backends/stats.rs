@@ -0,0 +1,95 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/stats.rs
+//
+// Per-source read statistics for backends that spread reads over several
+// stores (weighted data folders, multi-endpoint replicas)
+//
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+#[derive(Default)]
+struct Counts {
+    reads: u64,
+    bytes: u64,
+    errors: u64,
+    latencies_ms: Vec<f64>,
+}
+
+/// Reads, bytes, errors and latencies of one source
+#[derive(Default)]
+pub struct ReadStats {
+    counts: Mutex<Counts>,
+}
+
+/// Summary of one source's reads
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReadSummary {
+    pub reads: u64,
+    /// Share of all reads served by this source
+    pub read_share: f64,
+    pub bytes: u64,
+    pub errors: u64,
+    pub throughput_mib_s: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl ReadStats {
+    pub fn record(&self, start: Instant, end: Instant, bytes: usize) {
+        let mut counts = self.counts.lock().unwrap();
+        counts.reads += 1;
+        counts.bytes += bytes as u64;
+        counts.latencies_ms.push((end - start).as_secs_f64() * 1000.0);
+    }
+
+    pub fn record_error(&self) {
+        self.counts.lock().unwrap().errors += 1;
+    }
+
+    pub fn reads(&self) -> u64 {
+        self.counts.lock().unwrap().reads
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.counts.lock().unwrap().errors
+    }
+
+    /// Summary over a read window of `window_s` seconds, out of `total_reads` across all sources
+    pub fn summary(&self, window_s: f64, total_reads: u64) -> ReadSummary {
+        let counts = self.counts.lock().unwrap();
+        let mut ms = counts.latencies_ms.clone();
+        ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let pct = |p: f64| if ms.is_empty() { 0.0 } else { ms[((p * (ms.len() - 1) as f64) as usize).min(ms.len() - 1)] };
+        ReadSummary {
+            reads: counts.reads,
+            read_share: if total_reads > 0 { counts.reads as f64 / total_reads as f64 } else { 0.0 },
+            bytes: counts.bytes,
+            errors: counts.errors,
+            throughput_mib_s: if window_s > 0.0 { crate::units::mib(counts.bytes) / window_s } else { 0.0 },
+            mean_ms: if ms.is_empty() { 0.0 } else { ms.iter().sum::<f64>() / ms.len() as f64 },
+            p50_ms: pct(0.50),
+            p99_ms: pct(0.99),
+        }
+    }
+}
+
+/// First read start to last read end across all sources, for per-source throughput
+#[derive(Default)]
+pub struct ReadWindow {
+    span: Mutex<Option<(Instant, Instant)>>,
+}
+
+impl ReadWindow {
+    pub fn extend(&self, start: Instant, end: Instant) {
+        let mut span = self.span.lock().unwrap();
+        *span = Some(span.map_or((start, end), |(first, last)| (first.min(start), last.max(end))));
+    }
+
+    pub fn seconds(&self) -> f64 {
+        self.span.lock().unwrap().map_or(0.0, |(start, end)| (end - start).as_secs_f64())
+    }
+}
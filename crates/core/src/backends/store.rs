@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/backends/store.rs
+//
+// Adapter exposing an s3dlio ObjectStore through the DataBackend trait
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::DataBackend;
+use crate::multipart::{self, MultipartSettings, MultipartStats};
+use s3dlio::object_store::{store_for_uri, ObjectStore};
+
+/// s3dlio-backed storage (file://, direct://, s3://, az://) as a DataBackend
+pub struct ObjectStoreBackend {
+    prefix: String,
+    store: Box<dyn ObjectStore>,
+    multipart: Option<(MultipartSettings, MultipartStats)>,
+}
+
+impl ObjectStoreBackend {
+    pub fn new(prefix: &str) -> Result<Self> {
+        let store = store_for_uri(prefix)
+            .with_context(|| format!("Failed to create object store for {}", prefix))?;
+        Ok(Self { prefix: prefix.to_string(), store, multipart: None })
+    }
+
+    /// Read objects over the threshold with concurrent ranged GETs, and write them
+    /// in parts, reporting the transfers in `report()`
+    ///
+    /// Each read first stats the object for its size.
+    pub fn with_multipart(mut self, settings: MultipartSettings) -> Self {
+        self.multipart = Some((settings, MultipartStats::new()));
+        self
+    }
+}
+
+#[async_trait]
+impl DataBackend for ObjectStoreBackend {
+    fn name(&self) -> &'static str {
+        "s3dlio"
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let mut uris = self
+            .store
+            .list(&self.prefix, true)
+            .await
+            .with_context(|| format!("Failed to list {}", self.prefix))?;
+        uris.sort();
+        Ok(uris)
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        if let Some((settings, stats)) = &self.multipart {
+            let size = self.store.stat(uri).await.with_context(|| format!("Failed to stat {}", uri))?.size;
+            return multipart::get_object(self.store.as_ref(), uri, size, settings, stats).await;
+        }
+        let data = self.store.get(uri).await.with_context(|| format!("Failed to read {}", uri))?;
+        Ok(data.to_vec())
+    }
+
+    async fn head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        let data = self
+            .store
+            .get_range(uri, 0, Some(len as u64))
+            .await
+            .with_context(|| format!("Failed to read head of {}", uri))?;
+        Ok(data.to_vec())
+    }
+
+    async fn size(&self, uri: &str) -> Result<u64> {
+        Ok(self.store.stat(uri).await.with_context(|| format!("Failed to stat {}", uri))?.size)
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        if let Some((settings, stats)) = &self.multipart {
+            return multipart::put_object(self.store.as_ref(), uri, data, settings, stats).await;
+        }
+        self.store.put(uri, data).await.with_context(|| format!("Failed to write {}", uri))
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.multipart.as_ref().map(|(_, stats)| serde_json::json!({ "multipart": stats.to_json() }))
+    }
+}
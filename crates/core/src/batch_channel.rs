@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/batch_channel.rs
+//
+// Bounded batch channel between a background loader and the training loop
+//
+// A loader fills a bounded queue (`reader.prefetch` × 2 batches) and waits
+// while it is full, so memory stays bounded however far I/O runs ahead of
+// compute. Shutdown is explicit:
+//
+// - the training loop ends a loader with `LoaderHandle::shutdown`, which
+//   cancels it, throws away (and counts) what is still queued and waits for
+//   the task to exit
+// - a cancelled loader stops at its next send or read, so reads in flight
+//   are dropped rather than completed for nobody
+// - dropping a `LoaderHandle` without shutting it down (an error or early
+//   return in the training loop) cancels the loader, so no task is left
+//   blocked on a full queue or reading in the background
+// - a loader error is delivered after the batches queued before it; the
+//   loader then stops
+//
+// The training loop can also pause a loader (`LoaderHandle::pause`, e.g. for
+// the length of a checkpoint write): loaders check `BatchSender::resumed`
+// before starting reads, so reads in flight finish but no new ones start
+// until `resume`.
+//
+// `LoaderHandle::queue_probe` lets another task (the pool stats sampler)
+// read the queue depth while the training loop owns the handle.
+//
+use anyhow::Result;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+/// One loaded batch (object contents, stamped when ready) or the loader's error
+pub type BatchItem = Result<(Instant, Vec<Vec<u8>>)>;
+
+/// Loader side of the channel
+#[derive(Clone)]
+pub struct BatchSender {
+    tx: mpsc::Sender<BatchItem>,
+    cancel: CancellationToken,
+    paused: watch::Receiver<bool>,
+    depth: Arc<AtomicUsize>,
+}
+
+impl BatchSender {
+    /// Queue a batch, waiting while the queue is full; false once the loader should stop
+    pub async fn send(&self, item: BatchItem) -> bool {
+        // Counted before it can be received, so the depth never underflows
+        self.depth.fetch_add(1, Ordering::Relaxed);
+        let sent = tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => false,
+            sent = self.tx.send(item) => sent.is_ok(),
+        };
+        if !sent {
+            self.depth.fetch_sub(1, Ordering::Relaxed);
+        }
+        sent
+    }
+
+    /// Resolves once the loader is cancelled or the training loop is gone
+    pub async fn cancelled(&self) {
+        tokio::select! {
+            _ = self.cancel.cancelled() => {}
+            _ = self.tx.closed() => {}
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled() || self.tx.is_closed()
+    }
+
+    /// Resolves once the loader is not paused (or should stop); call before starting reads
+    pub async fn resumed(&self) {
+        let mut paused = self.paused.clone();
+        tokio::select! {
+            _ = self.cancelled() => {}
+            _ = paused.wait_for(|&p| !p) => {}
+        }
+    }
+}
+
+/// Queue depth of one loader, readable without its LoaderHandle
+#[derive(Debug, Clone)]
+pub struct QueueProbe {
+    depth: Arc<AtomicUsize>,
+    capacity: usize,
+}
+
+impl QueueProbe {
+    /// Batches loaded and waiting (a send blocked on a full queue is not counted)
+    pub fn queued(&self) -> usize {
+        self.depth.load(Ordering::Relaxed).min(self.capacity)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Training-loop side: the queue and the loader task filling it
+pub struct LoaderHandle {
+    rx: mpsc::Receiver<BatchItem>,
+    task: Option<JoinHandle<()>>,
+    cancel: CancellationToken,
+    pause: watch::Sender<bool>,
+    probe: QueueProbe,
+}
+
+impl LoaderHandle {
+    /// Spawn `loader` with a queue of `capacity` batches
+    pub fn spawn<F, Fut>(capacity: usize, loader: F) -> Self
+    where
+        F: FnOnce(BatchSender) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let cancel = CancellationToken::new();
+        let (pause, paused) = watch::channel(false);
+        let probe = QueueProbe { depth: Arc::new(AtomicUsize::new(0)), capacity: capacity.max(1) };
+        let task = tokio::spawn(loader(BatchSender { tx, cancel: cancel.clone(), paused, depth: Arc::clone(&probe.depth) }));
+        Self { rx, task: Some(task), cancel, pause, probe }
+    }
+
+    /// Hold the loader's new reads until `resume`
+    pub fn pause(&self) {
+        self.pause.send_replace(true);
+    }
+
+    pub fn resume(&self) {
+        self.pause.send_replace(false);
+    }
+
+    /// Next batch; None once the loader has finished and the queue is empty
+    pub async fn recv(&mut self) -> Option<BatchItem> {
+        let item = self.rx.recv().await;
+        if item.is_some() {
+            self.probe.depth.fetch_sub(1, Ordering::Relaxed);
+        }
+        item
+    }
+
+    /// Batches loaded and waiting
+    pub fn queued(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// The queue depth, for reading from another task
+    pub fn queue_probe(&self) -> QueueProbe {
+        self.probe.clone()
+    }
+
+    /// The loader task has exited (its last batches may still be queued)
+    pub fn is_finished(&self) -> bool {
+        self.task.as_ref().is_none_or(|t| t.is_finished())
+    }
+
+    /// Stop the loader, discard what it queued and wait for it to exit; returns (batches, bytes) discarded
+    pub async fn shutdown(mut self) -> (u64, u64) {
+        self.cancel.cancel();
+        self.rx.close();
+        let (mut batches, mut bytes) = (0, 0);
+        while let Ok(item) = self.rx.try_recv() {
+            if let Ok((_, files)) = item {
+                batches += 1;
+                bytes += files.iter().map(|f| f.len() as u64).sum::<u64>();
+            }
+        }
+        if let Some(task) = self.task.take() {
+            if let Err(e) = task.await {
+                warn!("Background I/O task error: {:?}", e);
+            }
+        }
+        (batches, bytes)
+    }
+}
+
+impl Drop for LoaderHandle {
+    /// The loader notices at its next send or read and exits on its own
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Sends batches of `size` bytes until stopped, then counts its exit
+    async fn endless(tx: BatchSender, size: usize, exited: Arc<AtomicUsize>) {
+        while tx.send(Ok((Instant::now(), vec![vec![0u8; size]]))).await {}
+        exited.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn within<F: Future>(what: &str, f: F) -> F::Output {
+        tokio::time::timeout(Duration::from_secs(5), f).await.unwrap_or_else(|_| panic!("{}", what))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_early_exit_error_and_drop_never_leak_loaders() {
+        let exited = Arc::new(AtomicUsize::new(0));
+
+        // Early exit (a step limit or stop request) with the queue full: bounded, and every loader exits
+        for _ in 0..200 {
+            let mut loader = LoaderHandle::spawn(4, |tx| endless(tx, 16, exited.clone()));
+            for _ in 0..3 {
+                assert!(loader.recv().await.unwrap().is_ok());
+            }
+            let (batches, bytes) = within("shutdown hung", loader.shutdown()).await;
+            assert!(batches <= 4 && bytes == batches * 16);
+        }
+        assert_eq!(exited.load(Ordering::SeqCst), 200);
+
+        // Dropped without shutdown (an error path): the blocked loader is cancelled
+        let loader = LoaderHandle::spawn(1, |tx| endless(tx, 16, exited.clone()));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(loader);
+        within("dropped loader kept running", async {
+            while exited.load(Ordering::SeqCst) < 201 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await;
+
+        // A loader error arrives after the batches before it, then the loader stops
+        let mut loader = LoaderHandle::spawn(8, |tx| async move {
+            tx.send(Ok((Instant::now(), vec![vec![1u8; 4]]))).await;
+            tx.send(Err(anyhow::anyhow!("read failed"))).await;
+        });
+        let probe = loader.queue_probe();
+        within("queued batches were not counted", async {
+            while probe.queued() < 2 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await;
+        assert!(loader.recv().await.unwrap().is_ok());
+        assert_eq!(probe.queued(), 1);
+        assert!(loader.recv().await.unwrap().is_err());
+        assert!(loader.recv().await.is_none());
+        assert_eq!(loader.shutdown().await, (0, 0));
+
+        // A read in flight is abandoned on shutdown instead of completing
+        let loader = LoaderHandle::spawn(2, |tx| async move {
+            tokio::select! {
+                _ = tx.cancelled() => {}
+                _ = tokio::time::sleep(Duration::from_secs(3600)) => panic!("read was not cancelled"),
+            }
+        });
+        within("in-flight read blocked shutdown", loader.shutdown()).await;
+
+        // Paused: no new reads start until resumed, and a paused loader still shuts down
+        let reads = Arc::new(AtomicUsize::new(0));
+        let counted = reads.clone();
+        let mut loader = LoaderHandle::spawn(64, |tx| async move {
+            loop {
+                tx.resumed().await;
+                if tx.is_cancelled() {
+                    break;
+                }
+                counted.fetch_add(1, Ordering::SeqCst);
+                if !tx.send(Ok((Instant::now(), vec![vec![0u8; 4]]))).await {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+        assert!(loader.recv().await.unwrap().is_ok());
+        loader.pause();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let held = reads.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(reads.load(Ordering::SeqCst), held);
+        loader.resume();
+        within("resumed loader stayed paused", async {
+            while reads.load(Ordering::SeqCst) == held {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await;
+        loader.pause();
+        within("paused loader blocked shutdown", loader.shutdown()).await;
+    }
+}
@@ -0,0 +1,409 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/batching.rs
+//
+// Sample-based batch construction
+//
+// Loaders deliver whole files, but DLIO defines `batch_size` in samples. The
+// SampleBatcher splits each file into its `num_samples_per_file` samples and
+// packs exactly `batch_size` samples per training step, so a file may span
+// several batches and a batch may draw on several files.
+//
+// With `reader.batch_size_bytes` a batch closes once it holds that many bytes
+// instead, so its sample count varies with the actual sample sizes. The
+// nominal count (bytes over the record size) sizes loader requests.
+//
+// Where the dataset format delimits its samples (TFRecord records, CSV rows,
+// rows of a stored NPZ data array or of dl-driver's SHD5 dataset) a file is
+// split at those boundaries, so each sample carries its own bytes; headers
+// count toward the first sample and trailers toward the last. Files whose
+// sample count differs from `num_samples_per_file`, multi-component samples
+// and other formats are split evenly.
+//
+// Under sample shuffling the epoch follows a plan (`shuffle::plan_sample_batches`):
+// each loader batch holds the files of one planned batch and yields exactly
+// its samples, in planned order.
+//
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use real_dlio_formats::sample_extents;
+use tracing::debug;
+
+use crate::components;
+use crate::dlio_compat::DlioConfig;
+use crate::shuffle::{permute, OrderDigest, PlannedBatch, ShuffleBuffer, ShuffleStrategy};
+
+/// One training step's worth of samples
+#[derive(Debug)]
+pub struct SampleBatch {
+    /// When the last file contributing to this batch became ready
+    pub ready_at: Instant,
+    pub samples: usize,
+    /// Bytes of the samples in this batch
+    pub bytes: usize,
+    /// Bytes read from storage for this batch: whole files, so under a
+    /// sample-shuffle plan up to `num_samples_per_file` times `bytes`
+    pub fetched: usize,
+    /// Objects whose first sample falls in this batch, for whole-object validation
+    pub objects: Vec<Vec<u8>>,
+}
+
+/// One sample on its way from a loaded file into a batch
+struct SampleUnit {
+    ready_at: Instant,
+    bytes: usize,
+    /// The file's objects travel with its first sample
+    objects: Vec<Vec<u8>>,
+    file_position: u64,
+    sample: u64,
+}
+
+/// Repacks file batches from a loader into batches of `batch_size` samples
+pub struct SampleBatcher {
+    batch_size: usize,
+    batch_bytes: Option<usize>,
+    samples_per_file: usize,
+    objects_per_file: usize,
+    drop_last: bool,
+    strategy: ShuffleStrategy,
+    seed: u64,
+    buffer: Option<ShuffleBuffer<SampleUnit>>,
+    /// Dataset format, for locating sample boundaries
+    format: Option<String>,
+    plan: Option<VecDeque<PlannedBatch>>,
+    files_seen: u64,
+    bytes_fetched: u64,
+    digest: OrderDigest,
+    current: Option<SampleBatch>,
+}
+
+impl SampleBatcher {
+    pub fn new(batch_size: usize, samples_per_file: usize, objects_per_file: usize, drop_last: bool) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            batch_bytes: None,
+            samples_per_file: samples_per_file.max(1),
+            objects_per_file: objects_per_file.max(1),
+            drop_last,
+            strategy: ShuffleStrategy::None,
+            seed: 0,
+            buffer: None,
+            format: None,
+            plan: None,
+            files_seen: 0,
+            bytes_fetched: 0,
+            digest: OrderDigest::default(),
+            current: None,
+        }
+    }
+
+    /// Batcher for the configured reader and dataset
+    pub fn from_config(config: &DlioConfig) -> Self {
+        let mut batcher = Self::new(
+            config.reader.batch_size.unwrap_or(16),
+            config.dataset.num_samples_per_file.unwrap_or(1),
+            components::components(config).map_or(1, |c| c.len()),
+            config.reader.drop_last.unwrap_or(false),
+        );
+        if let Some(format) = config.dataset.format.as_deref().filter(|_| batcher.objects_per_file == 1) {
+            batcher = batcher.with_format(format);
+        }
+        match config.reader.batch_size_bytes {
+            Some(bytes) => batcher.with_batch_bytes(bytes, components::sample_bytes(config)),
+            None => batcher,
+        }
+    }
+
+    /// Close batches at `bytes` rather than a sample count; `sample_bytes`
+    /// (the record size) gives the nominal samples per batch
+    pub fn with_batch_bytes(mut self, bytes: usize, sample_bytes: usize) -> Self {
+        let bytes = bytes.max(1);
+        self.batch_size = bytes.div_ceil(sample_bytes.max(1));
+        self.batch_bytes = Some(bytes);
+        self
+    }
+
+    /// Split files at the sample boundaries of `format` where it has them
+    pub fn with_format(mut self, format: &str) -> Self {
+        self.format = Some(format.to_string());
+        self
+    }
+
+    /// Follow a sample-shuffle plan: each pushed loader batch holds the files of the next planned batch
+    pub fn with_plan(mut self, plan: Vec<PlannedBatch>) -> Self {
+        self.plan = Some(plan.into());
+        self
+    }
+
+    /// Samples per batch (nominal when batches are sized in bytes)
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Bytes per batch, when batches are sized in bytes
+    pub fn batch_bytes(&self) -> Option<usize> {
+        self.batch_bytes
+    }
+
+    /// Shuffle samples with `strategy`, seeded for this epoch
+    ///
+    /// Permutation shuffles samples within each file (file order is permuted by
+    /// the loader); buffer passes every sample through a shuffle buffer.
+    pub fn with_shuffle(mut self, strategy: ShuffleStrategy, seed: u64) -> Self {
+        self.strategy = strategy;
+        self.seed = seed;
+        self.buffer = match strategy {
+            ShuffleStrategy::Buffer { size } => Some(ShuffleBuffer::new(size, seed)),
+            _ => None,
+        };
+        self
+    }
+
+    /// Files a loader should fetch per batch to keep one sample batch in flight
+    pub fn files_per_batch(&self) -> usize {
+        self.batch_size.div_ceil(self.samples_per_file)
+    }
+
+    /// Samples in a dataset of `files` files
+    pub fn total_samples(&self, files: usize) -> usize {
+        files * self.samples_per_file
+    }
+
+    /// Digest of the (file position, sample) order delivered so far
+    /// (dataset file index under a sample-shuffle plan)
+    pub fn sample_order_digest(&self) -> String {
+        self.digest.hex()
+    }
+
+    /// File reads and bytes fetched so far, under a sample-shuffle plan
+    pub fn fetched(&self) -> Option<(u64, u64)> {
+        self.plan.as_ref().map(|_| (self.files_seen, self.bytes_fetched))
+    }
+
+    /// Bytes of each of a file's samples: split at the format's sample
+    /// boundaries when they match `samples_per_file`, evenly otherwise
+    fn sample_bytes(&self, file: &[Vec<u8>]) -> Vec<usize> {
+        let file_bytes: usize = file.iter().map(|o| o.len()).sum();
+        let extents = match (&self.format, file) {
+            (Some(format), [object]) if self.samples_per_file > 1 => match sample_extents(format, object) {
+                Ok(extents) => extents.filter(|e| e.len() == self.samples_per_file),
+                Err(e) => {
+                    debug!("No sample boundaries in {} object: {}", format, e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        match extents {
+            Some(extents) => {
+                let mut starts: Vec<usize> = extents.iter().map(|e| e.start).collect();
+                starts[0] = 0;
+                starts.push(file_bytes);
+                starts.windows(2).map(|w| w[1] - w[0]).collect()
+            }
+            // Byte share of one sample, remainder spread by rounding
+            None => (0..self.samples_per_file)
+                .map(|s| file_bytes * (s + 1) / self.samples_per_file - file_bytes * s / self.samples_per_file)
+                .collect(),
+        }
+    }
+
+    /// Add a loader batch of files; returns every sample batch it completes
+    pub fn push(&mut self, ready_at: Instant, objects: Vec<Vec<u8>>) -> Vec<SampleBatch> {
+        if self.plan.is_some() {
+            return self.push_planned(ready_at, objects).into_iter().collect();
+        }
+        let mut full = Vec::new();
+        let mut objects = objects.into_iter().peekable();
+        while objects.peek().is_some() {
+            let mut file: Vec<Vec<u8>> = objects.by_ref().take(self.objects_per_file).collect();
+            let sizes = self.sample_bytes(&file);
+            let file_position = self.files_seen;
+            self.files_seen += 1;
+
+            let mut order: Vec<usize> = (0..self.samples_per_file).collect();
+            if self.strategy == ShuffleStrategy::Permutation {
+                permute(&mut order, crate::rng::file_seed(self.seed, file_position));
+            }
+            for sample in order {
+                let unit = SampleUnit {
+                    ready_at,
+                    bytes: sizes[sample],
+                    objects: std::mem::take(&mut file),
+                    file_position,
+                    sample: sample as u64,
+                };
+                let unit = match self.buffer.as_mut() {
+                    Some(buffer) => buffer.push(unit),
+                    None => Some(unit),
+                };
+                if let Some(unit) = unit {
+                    full.extend(self.add(unit));
+                }
+            }
+        }
+        full
+    }
+
+    /// The next planned batch, from a loader batch holding its files in order
+    fn push_planned(&mut self, ready_at: Instant, objects: Vec<Vec<u8>>) -> Option<SampleBatch> {
+        let planned = self.plan.as_mut()?.pop_front()?;
+        let mut files: Vec<Vec<Vec<u8>>> = Vec::with_capacity(planned.files.len());
+        let mut objects = objects.into_iter().peekable();
+        while objects.peek().is_some() {
+            files.push(objects.by_ref().take(self.objects_per_file).collect());
+        }
+        let sizes: Vec<Vec<usize>> = files.iter().map(|file| self.sample_bytes(file)).collect();
+        self.files_seen += files.len() as u64;
+        self.bytes_fetched += sizes.iter().flatten().sum::<usize>() as u64;
+
+        let fetched: usize = sizes.iter().flatten().sum();
+        let mut batch = SampleBatch { ready_at, samples: 0, bytes: 0, fetched, objects: Vec::new() };
+        for (file, sample) in planned.samples {
+            self.digest.add(file as u64);
+            self.digest.add(sample as u64);
+            let Some(slot) = planned.files.iter().position(|&f| f == file).filter(|&slot| slot < files.len()) else {
+                continue;
+            };
+            batch.samples += 1;
+            batch.bytes += sizes[slot][sample.min(self.samples_per_file - 1)];
+            // A file's objects travel with its first sample, once per epoch
+            if sample == 0 {
+                batch.objects.append(&mut files[slot]);
+            }
+        }
+        Some(batch)
+    }
+
+    /// Flush buffered samples and the trailing partial batch at the end of an epoch
+    ///
+    /// The partial batch is dropped when `drop_last` is set.
+    pub fn finish(&mut self) -> Vec<SampleBatch> {
+        let mut full = Vec::new();
+        let drained = self.buffer.as_mut().map(|b| b.drain()).unwrap_or_default();
+        for unit in drained {
+            full.extend(self.add(unit));
+        }
+        full.extend(self.current.take().filter(|_| !self.drop_last));
+        full
+    }
+
+    fn add(&mut self, unit: SampleUnit) -> Option<SampleBatch> {
+        self.digest.add(unit.file_position);
+        self.digest.add(unit.sample);
+
+        let batch = self.current.get_or_insert_with(|| SampleBatch {
+            ready_at: unit.ready_at,
+            samples: 0,
+            bytes: 0,
+            fetched: 0,
+            objects: Vec::new(),
+        });
+        batch.ready_at = batch.ready_at.max(unit.ready_at);
+        batch.samples += 1;
+        batch.bytes += unit.bytes;
+        // Without a plan every file is read once, its bytes spread over its samples
+        batch.fetched += unit.bytes;
+        batch.objects.extend(unit.objects);
+
+        let full = match self.batch_bytes {
+            Some(bytes) => batch.bytes >= bytes,
+            None => batch.samples == self.batch_size,
+        };
+        if full {
+            self.current.take()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_split_across_sample_batches() {
+        // 3 files x 4 samples of 100 bytes, batch_size 5 → 5 + 5 + 2 samples
+        let mut batcher = SampleBatcher::new(5, 4, 1, false);
+        assert_eq!(batcher.files_per_batch(), 2);
+
+        let now = Instant::now();
+        let mut batches = batcher.push(now, vec![vec![0u8; 400], vec![1u8; 400]]);
+        batches.extend(batcher.push(now, vec![vec![2u8; 400]]));
+        batches.extend(batcher.finish());
+
+        let samples: Vec<usize> = batches.iter().map(|b| b.samples).collect();
+        assert_eq!(samples, vec![5, 5, 2]);
+        let bytes: Vec<usize> = batches.iter().map(|b| b.bytes).collect();
+        assert_eq!(bytes, vec![500, 500, 200]);
+        let objects: Vec<usize> = batches.iter().map(|b| b.objects.len()).collect();
+        assert_eq!(objects, vec![2, 1, 0]);
+
+        let mut dropping = SampleBatcher::new(5, 4, 1, true);
+        assert_eq!(dropping.push(now, vec![vec![0u8; 400]]).len(), 0);
+        assert!(dropping.finish().is_empty());
+    }
+
+    #[test]
+    fn test_buffer_shuffle_keeps_every_sample() {
+        let now = Instant::now();
+        let run = |seed| {
+            let mut batcher = SampleBatcher::new(3, 4, 1, false).with_shuffle(ShuffleStrategy::Buffer { size: 6 }, seed);
+            let mut batches = batcher.push(now, (0..5).map(|i| vec![i as u8; 40]).collect());
+            batches.extend(batcher.finish());
+            (batches, batcher.sample_order_digest())
+        };
+        let (batches, digest) = run(1);
+        assert_eq!(batches.iter().map(|b| b.samples).sum::<usize>(), 20);
+        assert_eq!(batches.iter().map(|b| b.bytes).sum::<usize>(), 200);
+        assert_eq!(batches.iter().map(|b| b.objects.len()).sum::<usize>(), 5);
+        assert_eq!(digest, run(1).1);
+        assert_ne!(digest, run(2).1);
+    }
+
+    #[test]
+    fn test_batch_size_bytes_varies_samples_per_batch() {
+        // 1000-byte batches of 100-byte records: nominally 10 samples, 3 files per load
+        let mut batcher = SampleBatcher::new(1, 4, 1, false).with_batch_bytes(1000, 100);
+        assert_eq!((batcher.batch_size(), batcher.files_per_batch()), (10, 3));
+
+        // Samples of 100, then 250 bytes: batches close at the byte limit, not a count
+        let now = Instant::now();
+        let mut batches = batcher.push(now, vec![vec![0u8; 400], vec![0u8; 400], vec![0u8; 1000], vec![0u8; 1000]]);
+        batches.extend(batcher.finish());
+        let samples: Vec<usize> = batches.iter().map(|b| b.samples).collect();
+        assert_eq!(samples, vec![9, 4, 3]);
+        let bytes: Vec<usize> = batches.iter().map(|b| b.bytes).collect();
+        assert_eq!(bytes, vec![1050, 1000, 750]);
+    }
+
+    #[test]
+    fn test_samples_follow_format_boundaries_and_sample_plans() {
+        // CSV rows of 4, 8 and 12 bytes after a 6-byte header
+        let csv = b"id,vv\n1,a\n2,bbbbb\n3,ccccccccc\n".to_vec();
+        let now = Instant::now();
+        let mut batcher = SampleBatcher::new(1, 3, 1, false).with_format("csv");
+        let bytes: Vec<usize> = batcher.push(now, vec![csv.clone()]).iter().map(|b| b.bytes).collect();
+        assert_eq!(bytes, vec![10, 8, 12]);
+        // A file with another sample count is split evenly
+        let mut even = SampleBatcher::new(1, 2, 1, false).with_format("csv");
+        assert_eq!(even.push(now, vec![csv.clone()]).iter().map(|b| b.bytes).collect::<Vec<_>>(), vec![15, 15]);
+
+        // Planned batches of samples drawn across two files, each loaded per batch it needs
+        let plan = vec![
+            PlannedBatch { files: vec![1, 0], samples: vec![(1, 2), (0, 0)] },
+            PlannedBatch { files: vec![0, 1], samples: vec![(0, 1), (1, 0), (0, 2), (1, 1)] },
+        ];
+        let mut planned = SampleBatcher::new(4, 3, 1, false).with_format("csv").with_plan(plan);
+        let mut batches = planned.push(now, vec![csv.clone(), csv.clone()]);
+        batches.extend(planned.push(now, vec![csv.clone(), csv.clone()]));
+        batches.extend(planned.finish());
+        let shape: Vec<(usize, usize, usize, usize)> = batches.iter().map(|b| (b.samples, b.bytes, b.fetched, b.objects.len())).collect();
+        assert_eq!(shape, vec![(2, 22, 60, 1), (4, 38, 60, 1)]);
+        assert_eq!(planned.fetched(), Some((4, 120)));
+        assert_eq!(batcher.fetched(), None);
+    }
+}
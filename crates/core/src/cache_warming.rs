@@ -0,0 +1,196 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/cache_warming.rs
+//
+// Cache warming curve (`storage.cache_analysis`)
+//
+// With a page cache or a cache tier in front of storage, later epochs read
+// less from the backend than the first. Each epoch's bytes are attributed to
+// cache or backend, so the curve shows how fast the cache warms and how much
+// of the dataset it ends up serving, the evidence for sizing a local NVMe
+// cache:
+//
+//   page_cache  local data folders: bytes the process fetched from the block
+//               layer (`read_bytes` in /proc/self/io) came from the backend,
+//               the rest of the epoch's bytes from the page cache
+//   latency     remote data folders on a native loader: reads at or under
+//               `hit_latency_ms` count as cache hits (default: a quarter of
+//               the first epoch's median read, taking that epoch as cold)
+//
+// Remote runs on the s3dlio loader expose no per-read timings, so they have
+// no section. Reads prefetched across an epoch boundary count toward the
+// epoch in which they complete.
+//
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+use crate::units::Throughput;
+
+/// Share of an epoch's bytes from cache at which the cache counts as warm
+pub const WARM_FRACTION: f64 = 0.9;
+
+/// Default hit threshold, as a fraction of the first epoch's median read latency
+const DEFAULT_HIT_FRACTION: f64 = 0.25;
+
+/// How bytes are attributed to cache or backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Attribution {
+    PageCache,
+    Latency,
+}
+
+/// One epoch's bytes by source
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochCacheSource {
+    pub epoch: u32,
+    pub bytes: u64,
+    pub cache_bytes: u64,
+    pub backend_bytes: u64,
+    pub cache_fraction: f64,
+    pub throughput: Throughput,
+    /// Throughput over the first epoch's
+    pub speedup: f64,
+}
+
+/// `cache_warming` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheWarmingReport {
+    pub method: Attribution,
+    /// Latency attribution threshold in use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_latency_ms: Option<f64>,
+    /// First epoch serving at least WARM_FRACTION of its bytes from cache
+    pub warm_epoch: Option<u32>,
+    /// Last epoch's cache fraction: the share of the dataset the cache ends up serving
+    pub final_cache_fraction: f64,
+    pub epochs: Vec<EpochCacheSource>,
+}
+
+/// Bytes this process fetched from the storage layer (`read_bytes` in /proc/self/io; Linux only)
+fn storage_read_bytes() -> Option<u64> {
+    let io = std::fs::read_to_string("/proc/self/io").ok()?;
+    io.lines().find_map(|line| line.strip_prefix("read_bytes:")).and_then(|v| v.trim().parse().ok())
+}
+
+fn median_ms(reads: &[(f64, u64)]) -> f64 {
+    let mut ms: Vec<f64> = reads.iter().map(|r| r.0).collect();
+    ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ms.get(ms.len() / 2).copied().unwrap_or(0.0)
+}
+
+/// Per-epoch cache attribution for one training run
+pub struct CacheWarming {
+    method: Attribution,
+    hit_latency_ms: Option<f64>,
+    io_at_start: u64,
+    epochs: Vec<EpochCacheSource>,
+}
+
+impl CacheWarming {
+    /// None when `storage.cache_analysis` is unset or the run's bytes cannot be attributed
+    pub fn from_config(config: &DlioConfig, native_loader: bool) -> Option<Self> {
+        let analysis = config.storage.as_ref()?.cache_analysis.as_ref()?;
+        let method = if local_path_for_uri(&config.dataset.data_folder).is_some() && storage_read_bytes().is_some() {
+            Attribution::PageCache
+        } else if native_loader {
+            Attribution::Latency
+        } else {
+            warn!("⚠️  storage.cache_analysis: the s3dlio loader exposes no per-read timings for {}; no cache warming curve",
+                  config.dataset.data_folder);
+            return None;
+        };
+        Some(Self { method, hit_latency_ms: analysis.hit_latency_ms, io_at_start: 0, epochs: Vec::new() })
+    }
+
+    /// Whether attribution needs the loader's per-read latencies (`WorkerBalance::with_read_log`)
+    pub fn needs_read_log(&self) -> bool {
+        self.method == Attribution::Latency
+    }
+
+    pub fn start_epoch(&mut self) {
+        self.io_at_start = storage_read_bytes().unwrap_or(0);
+    }
+
+    /// Attribute an epoch's `bytes`; `reads` are its (latency ms, bytes) reads for latency attribution
+    pub fn end_epoch(&mut self, epoch: u32, bytes: u64, elapsed: Duration, reads: &[(f64, u64)]) {
+        let backend_bytes = match self.method {
+            Attribution::PageCache => storage_read_bytes().unwrap_or(0).saturating_sub(self.io_at_start).min(bytes),
+            Attribution::Latency => {
+                if self.hit_latency_ms.is_none() && !reads.is_empty() {
+                    self.hit_latency_ms = Some(median_ms(reads) * DEFAULT_HIT_FRACTION);
+                }
+                let threshold = self.hit_latency_ms.unwrap_or(0.0);
+                let read: u64 = reads.iter().map(|r| r.1).sum();
+                let missed: u64 = reads.iter().filter(|r| r.0 > threshold).map(|r| r.1).sum();
+                // Reads are whole objects; scale their split to the bytes the epoch delivered
+                if read > 0 { (bytes as f64 * missed as f64 / read as f64).round() as u64 } else { bytes }
+            }
+        };
+        let throughput = Throughput::new(bytes, elapsed.as_secs_f64());
+        let first = self.epochs.first().map_or(throughput.bytes_per_s, |e| e.throughput.bytes_per_s);
+        let source = EpochCacheSource {
+            epoch,
+            bytes,
+            cache_bytes: bytes - backend_bytes,
+            backend_bytes,
+            cache_fraction: if bytes > 0 { (bytes - backend_bytes) as f64 / bytes as f64 } else { 0.0 },
+            throughput,
+            speedup: if first > 0.0 { throughput.bytes_per_s / first } else { 0.0 },
+        };
+        info!("🌡️  Epoch {}: {:.1}% of {} from cache ({}, {:.2}x epoch 1)", epoch, source.cache_fraction * 100.0,
+              crate::units::fmt_bytes(bytes), throughput.display(), source.speedup);
+        self.epochs.push(source);
+    }
+
+    pub fn report(&self) -> CacheWarmingReport {
+        CacheWarmingReport {
+            method: self.method,
+            hit_latency_ms: self.hit_latency_ms.filter(|_| self.method == Attribution::Latency),
+            warm_epoch: self.epochs.iter().find(|e| e.cache_fraction >= WARM_FRACTION).map(|e| e.epoch),
+            final_cache_fraction: self.epochs.last().map_or(0.0, |e| e.cache_fraction),
+            epochs: self.epochs.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigBuilder;
+
+    #[test]
+    fn test_cache_warming_curve_from_read_latency() {
+        let config = |folder: &str| ConfigBuilder::new(folder).yaml("storage:\n  cache_analysis: {}\n").build().unwrap();
+        assert!(CacheWarming::from_config(&config("s3://bench/train"), false).is_none());
+        let mut off = config("s3://bench/train");
+        off.storage = None;
+        assert!(CacheWarming::from_config(&off, true).is_none());
+
+        // Cold first epoch at 8ms per read, then half and all of the reads at 0.5ms
+        let mut cache = CacheWarming::from_config(&config("s3://bench/train"), true).unwrap();
+        assert!(cache.needs_read_log());
+        let reads = |fast: usize| -> Vec<(f64, u64)> { (0..10).map(|i| (if i < fast { 0.5 } else { 8.0 }, 100)).collect() };
+        for (epoch, fast, secs) in [(1, 0, 4), (2, 5, 2), (3, 10, 1)] {
+            cache.start_epoch();
+            cache.end_epoch(epoch, 1000, Duration::from_secs(secs), &reads(fast));
+        }
+        let report = cache.report();
+        assert_eq!((report.method, report.hit_latency_ms, report.warm_epoch), (Attribution::Latency, Some(2.0), Some(3)));
+        let curve: Vec<(u64, f64)> = report.epochs.iter().map(|e| (e.cache_bytes, e.speedup)).collect();
+        assert_eq!(curve, [(0, 1.0), (500, 2.0), (1000, 4.0)]);
+        assert_eq!(report.final_cache_fraction, 1.0);
+
+        // Local folders use the kernel's block-layer read counter where there is one
+        if let Some(mut cache) = CacheWarming::from_config(&config("file:///tmp/bench"), false) {
+            assert!(!cache.needs_read_log());
+            cache.start_epoch();
+            cache.end_epoch(1, 4096, Duration::from_secs(1), &[]);
+            let epoch = &cache.report().epochs[0];
+            assert_eq!(epoch.cache_bytes + epoch.backend_bytes, 4096);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/canary.rs
+//
+// Reachability and permission smoke test (`validate --probe`)
+//
+// Expired credentials, a missing bucket or a read-only mount otherwise show
+// up minutes or hours into a job. The probe writes a small canary object into
+// the data folder (and the checkpoint folder, when configured), reads it back,
+// and deletes it, reporting each step separately so a write-only or
+// no-delete policy is easy to spot. Local folders (file://, direct:// and
+// bare paths) go through the filesystem; everything else through the
+// object store for the URI.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+use crate::dlio_compat::{anchor_local_uri, local_path_for_uri, DlioConfig};
+use s3dlio::object_store::{store_for_uri, ObjectStore};
+
+/// Size of the canary object
+const CANARY_BYTES: usize = 4096;
+
+/// Outcome of one step (write, read, delete)
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStep {
+    pub step: String,
+    pub ok: bool,
+    pub latency_ms: f64,
+    pub detail: String,
+}
+
+/// Probe of one folder
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryReport {
+    /// "data_folder" or "checkpoint_folder"
+    pub target: String,
+    /// Canary object written
+    pub uri: String,
+    pub steps: Vec<CanaryStep>,
+}
+
+impl CanaryReport {
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|s| s.ok)
+    }
+}
+
+/// Folders to probe: the data folder, plus the checkpoint folder when configured
+pub fn targets(config: &DlioConfig) -> Vec<(&'static str, String)> {
+    let data_folder = config.resolved_data_folder_uri().unwrap_or_else(|_| config.data_folder_uri().to_string());
+    let mut targets = vec![("data_folder", data_folder)];
+    if let Some(folder) = config.checkpointing.as_ref().and_then(|c| c.checkpoint_folder.as_deref()) {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        targets.push(("checkpoint_folder", anchor_local_uri(folder, &cwd).unwrap_or_else(|| folder.to_string())));
+    }
+    targets
+}
+
+/// Probe every target folder of `config`
+pub async fn probe_all(config: &DlioConfig) -> Vec<CanaryReport> {
+    let mut reports = Vec::new();
+    for (target, folder) in targets(config) {
+        reports.push(probe(target, &folder).await);
+    }
+    reports
+}
+
+/// Write, read back and delete a canary object in `folder`
+pub async fn probe(target: &str, folder: &str) -> CanaryReport {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let uri = format!("{}/.dl_driver_canary_{}_{}", folder.trim_end_matches('/'), std::process::id(), nanos);
+    let access = Access::for_uri(&uri);
+    let data = s3dlio::generate_controlled_data(CANARY_BYTES, 0, 0);
+
+    let mut steps = Vec::new();
+    match access {
+        Err(e) => steps.push(step("write", Instant::now(), Err(e))),
+        Ok(access) => {
+            let start = Instant::now();
+            let written = access.put(&uri, &data).await.map(|()| format!("{} bytes", data.len()));
+            let write_ok = written.is_ok();
+            steps.push(step("write", start, written));
+            if write_ok {
+                let start = Instant::now();
+                let read = access.get(&uri).await.and_then(|read| {
+                    anyhow::ensure!(read == data, "read {} bytes that differ from the {} written", read.len(), data.len());
+                    Ok(format!("{} bytes verified", read.len()))
+                });
+                steps.push(step("read", start, read));
+                let start = Instant::now();
+                let deleted = access.delete(&uri).await.map(|()| "removed".to_string());
+                steps.push(step("delete", start, deleted));
+            }
+        }
+    }
+
+    let report = CanaryReport { target: target.to_string(), uri, steps };
+    let line: Vec<String> = report.steps.iter().map(|s| format!("{} {}", s.step, if s.ok { "✅" } else { "❌" })).collect();
+    info!("🐤 Canary in {} ({}): {}", target, folder, line.join(" | "));
+    report
+}
+
+fn step(name: &str, start: Instant, result: Result<String>) -> CanaryStep {
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let (ok, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(e) => (false, format!("{:#}", e)),
+    };
+    CanaryStep { step: name.to_string(), ok, latency_ms, detail }
+}
+
+/// Local folders through the filesystem, the rest through an object store
+pub(crate) enum Access {
+    Local(PathBuf),
+    Store(Box<dyn ObjectStore>),
+}
+
+impl Access {
+    pub(crate) fn for_uri(uri: &str) -> Result<Self> {
+        match local_path_for_uri(uri) {
+            Some(path) => Ok(Self::Local(path)),
+            None => store_for_uri(uri).with_context(|| format!("No object store for {}", uri)).map(Self::Store),
+        }
+    }
+
+    pub(crate) async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        match self {
+            Self::Local(path) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await.with_context(|| format!("Failed to create {:?}", parent))?;
+                }
+                tokio::fs::write(path, data).await.with_context(|| format!("Failed to write {:?}", path))
+            }
+            Self::Store(store) => store.put(uri, data).await.with_context(|| format!("PUT {} failed", uri)),
+        }
+    }
+
+    pub(crate) async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Local(path) => tokio::fs::read(path).await.with_context(|| format!("Failed to read {:?}", path)),
+            Self::Store(store) => Ok(store.get(uri).await.with_context(|| format!("GET {} failed", uri))?.to_vec()),
+        }
+    }
+
+    /// GET that tells a missing object (None) apart from a failed request (Err)
+    pub(crate) async fn get_if_exists(&self, uri: &str) -> Result<Option<Vec<u8>>> {
+        match self {
+            Self::Local(path) => match tokio::fs::read(path).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).with_context(|| format!("Failed to read {:?}", path)),
+            },
+            Self::Store(store) => match store.get(uri).await {
+                Ok(bytes) => Ok(Some(bytes.to_vec())),
+                // Store errors do not carry a status, so a listing settles whether the object is there
+                Err(e) => {
+                    let listed = store.list(uri, false).await.with_context(|| format!("Failed to list {}", uri))?;
+                    if listed.iter().any(|listed| listed == uri) {
+                        Err(e).with_context(|| format!("GET {} failed", uri))
+                    } else {
+                        Ok(None)
+                    }
+                }
+            },
+        }
+    }
+
+    pub(crate) async fn delete(&self, uri: &str) -> Result<()> {
+        match self {
+            Self::Local(path) => tokio::fs::remove_file(path).await.with_context(|| format!("Failed to delete {:?}", path)),
+            Self::Store(store) => store.delete(uri).await.with_context(|| format!("DELETE {} failed", uri)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_canary_writes_reads_and_deletes() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = format!(
+            "dataset:\n  data_folder: {}\nreader: {{}}\ncheckpointing:\n  checkpoint_folder: {}/ckpt\n",
+            dir.path().display(),
+            dir.path().display()
+        );
+        let config = DlioConfig::from_yaml(&yaml).unwrap();
+        let reports = probe_all(&config).await;
+        assert_eq!(reports.iter().map(|r| r.target.as_str()).collect::<Vec<_>>(), ["data_folder", "checkpoint_folder"]);
+        assert!(reports.iter().all(|r| r.passed() && r.steps.len() == 3), "{:?}", reports);
+        // Canaries are removed; the checkpoint folder was created for its canary
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(std::fs::read_dir(dir.path().join("ckpt")).unwrap().count(), 0);
+
+        // A folder that cannot be created fails the write and skips the rest
+        let blocked = dir.path().join("file");
+        std::fs::write(&blocked, b"not a directory").unwrap();
+        let report = probe("data_folder", &format!("file://{}/sub", blocked.display())).await;
+        assert!(!report.passed());
+        assert_eq!(report.steps.len(), 1);
+        assert!(report.steps[0].detail.contains("Failed to create"), "{}", report.steps[0].detail);
+    }
+}
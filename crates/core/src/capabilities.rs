@@ -0,0 +1,236 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/capabilities.rs
+//
+// Backend capability probe ("capabilities" in the results)
+//
+// A missing storage feature usually surfaces as an anomaly far from its cause:
+// whole-object reads where ranged GETs were expected, slow uploads without
+// multipart, stale reads after an overwrite. At run start, when
+// `storage.capability_probe: true`, each rank writes a few objects under
+// `<data_folder>/_dl_driver_probe/rank_<r>/` and checks:
+//
+//   read_after_write   a new object reads back immediately
+//   range_get          a ranged GET returns exactly the requested bytes
+//   write_after_write  an overwrite is visible to the next read
+//   multipart_put      a two-part multipart upload reads back intact
+//   list_after_write   new objects are listed immediately
+//
+// alongside the backend's documented LIST page size. Probe objects are
+// deleted afterwards. It is opt-in because those writes land in the dataset
+// itself, where a concurrent run listing the folder would see them, and a
+// crashed run leaves them behind. The probe never fails a run: when the probe object
+// cannot be written (e.g. a read-only bucket) every check is "unknown".
+// Native backends (mem://, null://, sim://, http(s)://) are not probed.
+//
+use anyhow::Result;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::backends;
+use crate::dlio_compat::DlioConfig;
+use s3dlio::object_store::{store_for_uri, ObjectStore};
+
+/// Size of the small probe objects
+const PROBE_OBJECT_BYTES: usize = 64 << 10;
+
+/// Part size of the multipart probe: S3's minimum, so the upload has two parts
+const MULTIPART_PART_BYTES: usize = 5 << 20;
+
+/// Objects written for the listing check
+const LIST_OBJECTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CapabilityStatus {
+    Supported,
+    Unsupported,
+    Unknown,
+}
+
+/// One row of the capability matrix
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityCheck {
+    pub capability: String,
+    pub status: CapabilityStatus,
+    pub detail: String,
+}
+
+/// Capabilities of the data folder's backend
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityMatrix {
+    pub uri: String,
+    pub scheme: String,
+    /// Documented maximum keys per LIST page (None: not paginated)
+    pub list_page_size: Option<u32>,
+    pub checks: Vec<CapabilityCheck>,
+}
+
+impl CapabilityMatrix {
+    pub fn status(&self, capability: &str) -> Option<CapabilityStatus> {
+        self.checks.iter().find(|c| c.capability == capability).map(|c| c.status)
+    }
+
+    /// Capabilities the probe found missing
+    pub fn unsupported(&self) -> Vec<&str> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CapabilityStatus::Unsupported)
+            .map(|c| c.capability.as_str())
+            .collect()
+    }
+}
+
+fn list_page_size(scheme: &str) -> Option<u32> {
+    match scheme {
+        "s3" | "gs" | "gcs" => Some(1000),
+        "az" | "azure" => Some(5000),
+        _ => None,
+    }
+}
+
+/// Probe the data folder's backend; None when disabled or served by a native backend
+pub async fn probe(config: &DlioConfig, rank: u32) -> Option<CapabilityMatrix> {
+    let enabled = config.storage.as_ref().and_then(|s| s.capability_probe).unwrap_or(false);
+    let uri = config.data_folder_uri();
+    if !enabled || backends::is_native_uri(uri) {
+        return None;
+    }
+    let store = match store_for_uri(uri) {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("⚠️  Capability probe skipped: no object store for {}: {:#}", uri, e);
+            return None;
+        }
+    };
+    let prefix = format!("{}/_dl_driver_probe/rank_{:05}/", uri.trim_end_matches('/'), rank);
+    Some(run_checks(store.as_ref(), uri, &prefix).await)
+}
+
+/// Run every check under `prefix`, then delete it
+pub async fn run_checks(store: &dyn ObjectStore, uri: &str, prefix: &str) -> CapabilityMatrix {
+    let scheme = uri.split_once("://").map_or("file", |(scheme, _)| scheme).to_string();
+    let object = format!("{}object", prefix);
+    let data = s3dlio::generate_controlled_data(PROBE_OBJECT_BYTES, 0, 0);
+
+    let checks: Vec<(&str, Result<(bool, String)>)> = match store.put(&object, &data).await {
+        Err(e) => {
+            warn!("⚠️  Capability probe could not write {}: {:#}", object, e);
+            let names = ["read_after_write", "range_get", "write_after_write", "multipart_put", "list_after_write"];
+            names.iter().map(|&name| (name, Err(anyhow::anyhow!("probe write failed: {:#}", e)))).collect()
+        }
+        Ok(()) => vec![
+            ("read_after_write", read_after_write(store, &object, &data).await),
+            ("range_get", range_get(store, &object, &data).await),
+            ("write_after_write", write_after_write(store, &object, &data).await),
+            ("multipart_put", multipart_put(store, prefix).await),
+            ("list_after_write", list_after_write(store, prefix).await),
+        ],
+    };
+    if let Err(e) = store.delete_prefix(prefix).await {
+        warn!("⚠️  Failed to delete capability probe objects under {}: {:#}", prefix, e);
+    }
+
+    let checks: Vec<CapabilityCheck> = checks
+        .into_iter()
+        .map(|(capability, result)| {
+            let (status, detail) = match result {
+                Ok((true, detail)) => (CapabilityStatus::Supported, detail),
+                Ok((false, detail)) => (CapabilityStatus::Unsupported, detail),
+                Err(e) => (CapabilityStatus::Unknown, format!("{:#}", e)),
+            };
+            CapabilityCheck { capability: capability.to_string(), status, detail }
+        })
+        .collect();
+    let matrix = CapabilityMatrix { list_page_size: list_page_size(&scheme), uri: uri.to_string(), scheme, checks };
+    let line: Vec<String> = matrix
+        .checks
+        .iter()
+        .map(|c| format!("{} {}", c.capability, match c.status {
+            CapabilityStatus::Supported => "✅",
+            CapabilityStatus::Unsupported => "❌",
+            CapabilityStatus::Unknown => "❔",
+        }))
+        .collect();
+    info!("🧭 Capabilities of {}: {}", uri, line.join(" | "));
+    matrix
+}
+
+async fn read_after_write(store: &dyn ObjectStore, object: &str, data: &[u8]) -> Result<(bool, String)> {
+    Ok(match store.get(object).await {
+        Ok(read) if read[..] == *data => (true, "new object readable immediately".to_string()),
+        Ok(read) => (false, format!("read {} bytes that differ from the {} written", read.len(), data.len())),
+        Err(e) => (false, format!("GET right after PUT failed: {:#}", e)),
+    })
+}
+
+async fn range_get(store: &dyn ObjectStore, object: &str, data: &[u8]) -> Result<(bool, String)> {
+    let (offset, len) = (1000, 100);
+    Ok(match store.get_range(object, offset as u64, Some(len as u64)).await {
+        Ok(read) if read[..] == data[offset..offset + len] => (true, format!("{} bytes at offset {}", len, offset)),
+        Ok(read) if read.len() == data.len() => (false, "range ignored: whole object returned".to_string()),
+        Ok(read) => (false, format!("requested {} bytes at offset {}, got {} other bytes", len, offset, read.len())),
+        Err(e) => (false, format!("ranged GET failed: {:#}", e)),
+    })
+}
+
+async fn write_after_write(store: &dyn ObjectStore, object: &str, data: &[u8]) -> Result<(bool, String)> {
+    let updated: Vec<u8> = data.iter().map(|b| !b).collect();
+    store.put(object, &updated).await?;
+    Ok(match store.get(object).await {
+        Ok(read) if read[..] == updated[..] => (true, "overwrite visible to the next read".to_string()),
+        Ok(read) if read[..] == *data => (false, "stale read: the previous version was returned".to_string()),
+        Ok(read) => (false, format!("read {} bytes matching neither version", read.len())),
+        Err(e) => (false, format!("GET after overwrite failed: {:#}", e)),
+    })
+}
+
+async fn multipart_put(store: &dyn ObjectStore, prefix: &str) -> Result<(bool, String)> {
+    let object = format!("{}multipart", prefix);
+    let data = s3dlio::generate_controlled_data(MULTIPART_PART_BYTES + PROBE_OBJECT_BYTES, 0, 0);
+    if let Err(e) = store.put_multipart(&object, &data, Some(MULTIPART_PART_BYTES)).await {
+        return Ok((false, format!("multipart upload failed: {:#}", e)));
+    }
+    Ok(match store.get(&object).await {
+        Ok(read) if read[..] == data[..] => (true, format!("2 parts of up to {} bytes", MULTIPART_PART_BYTES)),
+        Ok(read) => (false, format!("read back {} bytes of {} uploaded, content differs", read.len(), data.len())),
+        Err(e) => (false, format!("GET after multipart upload failed: {:#}", e)),
+    })
+}
+
+async fn list_after_write(store: &dyn ObjectStore, prefix: &str) -> Result<(bool, String)> {
+    let dir = format!("{}list/", prefix);
+    let objects: Vec<String> = (0..LIST_OBJECTS).map(|i| format!("{}object_{}", dir, i)).collect();
+    for object in &objects {
+        store.put(object, b"probe").await?;
+    }
+    let listed = store.list(&dir, true).await?;
+    let found = objects.iter().filter(|o| listed.contains(o)).count();
+    Ok((found == objects.len(), format!("{}/{} new objects listed", found, objects.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_lookup_and_page_sizes() {
+        let check = |capability: &str, status| CapabilityCheck { capability: capability.to_string(), status, detail: String::new() };
+        let matrix = CapabilityMatrix {
+            uri: "s3://bucket/train".to_string(),
+            scheme: "s3".to_string(),
+            list_page_size: list_page_size("s3"),
+            checks: vec![
+                check("range_get", CapabilityStatus::Supported),
+                check("multipart_put", CapabilityStatus::Unsupported),
+                check("list_after_write", CapabilityStatus::Unknown),
+            ],
+        };
+        assert_eq!(matrix.status("range_get"), Some(CapabilityStatus::Supported));
+        assert_eq!(matrix.status("missing"), None);
+        assert_eq!(matrix.unsupported(), ["multipart_put"]);
+        assert_eq!((matrix.list_page_size, list_page_size("az"), list_page_size("file")), (Some(1000), Some(5000), None));
+        assert_eq!(serde_json::to_value(&matrix).unwrap()["checks"][1]["status"], "unsupported");
+    }
+}
@@ -0,0 +1,230 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/checkpoint.rs
+//
+// Checkpoint writes during training (`workflow.checkpoint`, `checkpointing:`)
+//
+// With checkpointing enabled, training stops to write a checkpoint through
+// s3dlio's CheckpointStore, to any backend URI, on DLIO's schedule: every
+// `steps_between_checkpoints` steps when set, otherwise every
+// `epochs_between_checkpoints` epochs from `checkpoint_after_epoch` on. The
+// checkpoint is `checkpointing.checkpoint_size_bytes` (default
+// `model.model_size`) split evenly across ranks, each rank writing its shard
+// under `<checkpoint_folder>/rank_<r>`.
+//
+// Checkpoint stalls are timed separately: the training loop subtracts them
+// from epoch wall-clock time, so AU measures training I/O only, and the
+// writes are reported on their own (count, bytes, time, write throughput).
+//
+// Frameworks differ in what the data loader does meanwhile: some keep
+// prefetching (checkpoint writes and training reads interleave on storage),
+// others stop. By default loaders keep prefetching until their queue is full;
+// `checkpointing.pause_loading: true` holds new training reads for the
+// length of each write. Each write's window lands in the results timeseries.
+// On the s3dlio loader only dl-driver's pull from s3dlio's pool is held: the
+// pool itself cannot be paused and keeps reading until its readahead is full.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::dlio_compat::{anchor_local_uri, DlioConfig};
+use crate::metrics::Metrics;
+use crate::units::Throughput;
+use s3dlio::checkpoint::CheckpointStore;
+
+/// Checkpoint size when neither checkpoint_size_bytes nor model.model_size is set
+const DEFAULT_CHECKPOINT_BYTES: u64 = 64 << 20;
+
+/// When checkpoints are due, following DLIO's checkpointing options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckpointSchedule {
+    /// First epoch (1-based) that ends with a checkpoint
+    pub after_epoch: usize,
+    pub epochs_between: usize,
+    /// Step interval; replaces the epoch schedule when set
+    pub steps_between: Option<usize>,
+}
+
+impl CheckpointSchedule {
+    pub fn from_config(config: &DlioConfig) -> Self {
+        let checkpointing = config.checkpointing.as_ref();
+        Self {
+            after_epoch: checkpointing.and_then(|c| c.checkpoint_after_epoch).unwrap_or(1).max(1),
+            epochs_between: checkpointing.and_then(|c| c.epochs_between_checkpoints).unwrap_or(1).max(1),
+            steps_between: checkpointing.and_then(|c| c.steps_between_checkpoints).filter(|&s| s > 0),
+        }
+    }
+
+    /// A checkpoint is due after global training step `step` (1-based)
+    pub fn due_after_step(&self, step: usize) -> bool {
+        self.steps_between.is_some_and(|every| step.is_multiple_of(every))
+    }
+
+    /// A checkpoint is due at the end of epoch `epoch` (1-based)
+    pub fn due_after_epoch(&self, epoch: u32) -> bool {
+        let epoch = epoch as usize;
+        self.steps_between.is_none()
+            && epoch >= self.after_epoch
+            && (epoch - self.after_epoch).is_multiple_of(self.epochs_between)
+    }
+}
+
+/// Resolved checkpoint settings for one rank
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointSettings {
+    pub schedule: CheckpointSchedule,
+    /// This rank's checkpoint folder URI
+    pub folder: String,
+    /// Bytes this rank writes per checkpoint
+    pub shard_bytes: u64,
+    /// Training reads are held while a checkpoint is written
+    pub pause_loading: bool,
+}
+
+impl CheckpointSettings {
+    /// None unless `workflow.checkpoint` is on
+    pub fn from_config(config: &DlioConfig, rank: u32, world_size: u32) -> Result<Option<Self>> {
+        if !config.should_checkpoint() {
+            return Ok(None);
+        }
+        let checkpointing = config.checkpointing.as_ref();
+        let size = checkpointing
+            .and_then(|c| c.checkpoint_size_bytes)
+            .or_else(|| config.model.as_ref().and_then(|m| m.model_size))
+            .unwrap_or(DEFAULT_CHECKPOINT_BYTES);
+        if size == 0 {
+            anyhow::bail!("checkpointing.checkpoint_size_bytes must be greater than zero");
+        }
+        let folder = checkpointing.and_then(|c| c.checkpoint_folder.as_deref()).unwrap_or("checkpoints");
+        let cwd = std::env::current_dir().unwrap_or_default();
+        // CheckpointStore takes URIs: bare paths become file://, relative ones anchored to the cwd
+        let folder = anchor_local_uri(folder, &cwd).unwrap_or_else(|| {
+            if folder.contains("://") { folder.to_string() } else { format!("file://{}", folder) }
+        });
+        Ok(Some(Self {
+            schedule: CheckpointSchedule::from_config(config),
+            folder: format!("{}/rank_{:05}", folder.trim_end_matches('/'), rank),
+            shard_bytes: size.div_ceil(world_size.max(1) as u64),
+            pause_loading: checkpointing.and_then(|c| c.pause_loading).unwrap_or(false),
+        }))
+    }
+}
+
+/// One checkpoint written by this rank
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointWrite {
+    pub step: usize,
+    pub epoch: u32,
+    pub seconds: f64,
+    pub throughput: Throughput,
+}
+
+/// Checkpoints written during the last training run
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointReport {
+    pub folder: String,
+    pub shard_bytes: u64,
+    pub pause_loading: bool,
+    pub checkpoints: Vec<CheckpointWrite>,
+    pub total_write_s: f64,
+    pub throughput: Throughput,
+}
+
+/// Writes this rank's checkpoints as training reaches them
+pub struct Checkpointer {
+    settings: CheckpointSettings,
+    store: CheckpointStore,
+    data: Vec<u8>,
+    writes: Vec<CheckpointWrite>,
+}
+
+impl Checkpointer {
+    pub fn new(settings: CheckpointSettings) -> Result<Self> {
+        let store = CheckpointStore::open(&settings.folder)
+            .with_context(|| format!("Failed to open checkpoint store {}", settings.folder))?;
+        let data = s3dlio::generate_controlled_data(settings.shard_bytes as usize, 0, 0);
+        info!("💾 Checkpointing {} per rank to {} (data loading {} during writes)", crate::units::fmt_bytes(settings.shard_bytes),
+              settings.folder, if settings.pause_loading { "paused" } else { "continues" });
+        Ok(Self { settings, store, data, writes: Vec::new() })
+    }
+
+    pub fn schedule(&self) -> &CheckpointSchedule {
+        &self.settings.schedule
+    }
+
+    pub fn pauses_loading(&self) -> bool {
+        self.settings.pause_loading
+    }
+
+    /// Write one checkpoint; returns the time training was stalled
+    pub async fn write(&mut self, step: usize, epoch: u32, metrics: &Metrics) -> Result<Duration> {
+        let start = Instant::now();
+        self.store
+            .save(step as u64, epoch as u64, "dl-driver", &self.data, None)
+            .await
+            .with_context(|| format!("Checkpoint at step {} failed in {}", step, self.settings.folder))?;
+        let elapsed = start.elapsed();
+        metrics.record_checkpoint_write(self.settings.shard_bytes, elapsed);
+        let throughput = Throughput::new(self.settings.shard_bytes, elapsed.as_secs_f64());
+        info!("💾 Checkpoint at step {} (epoch {}) written in {:?} ({})", step, epoch, elapsed, throughput.display());
+        self.writes.push(CheckpointWrite { step, epoch, seconds: elapsed.as_secs_f64(), throughput });
+        Ok(elapsed)
+    }
+
+    pub fn report(&self) -> CheckpointReport {
+        let total_write_s: f64 = self.writes.iter().map(|w| w.seconds).sum();
+        CheckpointReport {
+            folder: self.settings.folder.clone(),
+            shard_bytes: self.settings.shard_bytes,
+            pause_loading: self.settings.pause_loading,
+            checkpoints: self.writes.clone(),
+            total_write_s,
+            throughput: Throughput::new(self.settings.shard_bytes * self.writes.len() as u64, total_write_s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_schedule_and_sizing() {
+        let yaml = |extra: &str| {
+            DlioConfig::from_yaml(&format!(
+                "dataset:\n  data_folder: /tmp/data\nreader: {{}}\nworkflow:\n  checkpoint: true\nmodel:\n  model_size: 1000\n{}",
+                extra
+            ))
+            .unwrap()
+        };
+
+        // Epoch schedule: first at epoch 2, then every 3 epochs
+        let config = yaml("checkpointing:\n  checkpoint_folder: s3://bucket/ckpt/\n  checkpoint_after_epoch: 2\n  epochs_between_checkpoints: 3\n");
+        let settings = CheckpointSettings::from_config(&config, 1, 3).unwrap().unwrap();
+        assert_eq!(settings.folder, "s3://bucket/ckpt/rank_00001");
+        assert_eq!(settings.shard_bytes, 334);
+        assert!(!settings.pause_loading);
+        let due: Vec<u32> = (1..=9).filter(|&e| settings.schedule.due_after_epoch(e)).collect();
+        assert_eq!(due, [2, 5, 8]);
+        assert!(!settings.schedule.due_after_step(4));
+
+        // A step interval replaces the epoch schedule; the size override wins over model_size
+        let config = yaml("checkpointing:\n  steps_between_checkpoints: 4\n  checkpoint_size_bytes: 64\n  pause_loading: true\n");
+        let settings = CheckpointSettings::from_config(&config, 0, 1).unwrap().unwrap();
+        assert!(settings.pause_loading);
+        assert!(settings.folder.starts_with("file:///") && settings.folder.ends_with("/checkpoints/rank_00000"));
+        assert_eq!(settings.shard_bytes, 64);
+        let due: Vec<usize> = (1..=12).filter(|&s| settings.schedule.due_after_step(s)).collect();
+        assert_eq!(due, [4, 8, 12]);
+        assert!(!settings.schedule.due_after_epoch(1));
+
+        let config = yaml("checkpointing:\n  checkpoint_folder: /mnt/ckpt\n");
+        assert_eq!(CheckpointSettings::from_config(&config, 0, 1).unwrap().unwrap().folder, "file:///mnt/ckpt/rank_00000");
+
+        let off = DlioConfig::from_yaml("dataset:\n  data_folder: /tmp/data\nreader: {}\n").unwrap();
+        assert!(CheckpointSettings::from_config(&off, 0, 1).unwrap().is_none());
+    }
+}
@@ -0,0 +1,346 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/checkpoint_storm.rs
+//
+// Checkpoint storm (`bench checkpoint`, `checkpoint_storm:` config block)
+//
+// Burst-write qualification without training I/O: every `interval_s` all
+// ranks meet at a barrier and write a `size_bytes` checkpoint each, split
+// into `shards` objects written concurrently, under
+// `<folder>/ckpt_<n>/rank_<r>_shard_<s>.bin`. Local files are fsynced before
+// a write counts as complete (`fsync`, default on); object PUTs complete when
+// the store acknowledges them. A checkpoint that overruns its slot delays
+// the next one, which is counted as late.
+//
+// Each rank reports its bursts with UNIX start/end times and per-object write
+// latencies. `aggregate` combines rank results into the storm view: burst
+// bandwidth (all ranks' bytes over first start to last end), write tail
+// latency across ranks, and inter-rank skew (last minus first rank to finish),
+// with rank clocks corrected when ranks measured their offset.
+//
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::content;
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+use crate::multipart::{self, MultipartSettings, MultipartStats};
+use crate::net_coordination::ClockOffset;
+use crate::units::Throughput;
+use s3dlio::object_store::store_for_uri;
+
+/// Resolved `checkpoint_storm:` settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct StormSettings {
+    pub size_bytes: usize,
+    pub shards: usize,
+    pub count: u32,
+    pub interval: Duration,
+    pub fsync: bool,
+    pub folder: String,
+}
+
+impl StormSettings {
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        let storm = config.checkpoint_storm.clone().unwrap_or_default();
+        let interval_s = storm.interval_s.unwrap_or(30.0);
+        let interval = Duration::try_from_secs_f64(interval_s)
+            .with_context(|| format!("checkpoint_storm.interval_s must be a non-negative number of seconds, got {}", interval_s))?;
+        let folder = storm
+            .folder
+            .or_else(|| config.checkpointing.as_ref().and_then(|c| c.checkpoint_folder.clone()))
+            .unwrap_or_else(|| format!("{}/checkpoint_storm", config.data_folder_uri().trim_end_matches('/')));
+        Ok(Self {
+            size_bytes: storm.size_bytes.unwrap_or(256 << 20).max(1),
+            shards: storm.shards.unwrap_or(1).max(1),
+            count: storm.count.unwrap_or(10).max(1),
+            interval,
+            fsync: storm.fsync.unwrap_or(true),
+            folder: folder.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn shard_uri(&self, checkpoint: u32, rank: u32, shard: usize) -> String {
+        format!("{}/ckpt_{:04}/rank_{:05}_shard_{:03}.bin", self.folder, checkpoint, rank, shard)
+    }
+}
+
+/// One rank's write of one checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StormBurst {
+    pub checkpoint: u32,
+    /// UNIX seconds on this rank's clock
+    pub start: f64,
+    pub end: f64,
+    pub bytes: u64,
+    pub write_ms: Vec<f64>,
+}
+
+/// One rank's `checkpoint_storm` results section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StormRankReport {
+    pub rank: u32,
+    pub folder: String,
+    pub size_bytes: usize,
+    pub shards: usize,
+    pub interval_s: f64,
+    /// Whether writes were fsynced (local paths with `fsync`)
+    pub fsynced: bool,
+    /// Checkpoints started after their slot because the previous one overran
+    pub late: u32,
+    pub bursts: Vec<StormBurst>,
+    /// Object-store upload statistics (`MultipartStats`); absent for local folders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multipart: Option<serde_json::Value>,
+}
+
+/// One checkpoint across all ranks
+#[derive(Debug, Clone, Serialize)]
+pub struct StormCheckpoint {
+    pub checkpoint: u32,
+    pub bytes: u64,
+    /// First rank start to last rank end
+    pub seconds: f64,
+    pub throughput: Throughput,
+    /// Last rank to finish minus first
+    pub skew_seconds: f64,
+}
+
+/// Storm view over all ranks (`aggregate` of checkpoint-storm rank results)
+#[derive(Debug, Clone, Serialize)]
+pub struct StormReport {
+    pub ranks: usize,
+    pub checkpoints: usize,
+    pub bytes_per_checkpoint: u64,
+    pub burst_throughput_mean: Throughput,
+    pub burst_throughput_min: Throughput,
+    pub write_p50_ms: f64,
+    pub write_p99_ms: f64,
+    pub write_max_ms: f64,
+    pub skew_seconds_mean: f64,
+    pub skew_seconds_max: f64,
+    pub late_checkpoints: u32,
+    pub clock_corrected: bool,
+    pub per_checkpoint: Vec<StormCheckpoint>,
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        0.0
+    } else {
+        sorted[((p * (sorted.len() - 1) as f64) as usize).min(sorted.len() - 1)]
+    }
+}
+
+/// Write one file and (optionally) fsync it before returning
+fn write_local(path: std::path::PathBuf, data: &[u8], fsync: bool) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+    file.write_all(data)?;
+    if fsync {
+        file.sync_all().with_context(|| format!("Failed to fsync {:?}", path))?;
+    }
+    Ok(())
+}
+
+/// Checkpoint writer for one rank
+pub struct CheckpointStorm {
+    config: DlioConfig,
+    settings: StormSettings,
+}
+
+impl CheckpointStorm {
+    pub fn new(config: DlioConfig) -> Result<Self> {
+        let settings = StormSettings::from_config(&config)?;
+        Ok(Self { config, settings })
+    }
+
+    pub fn settings(&self) -> &StormSettings {
+        &self.settings
+    }
+
+    /// Write every checkpoint; `barrier(n)` returns once all ranks are ready for checkpoint n
+    pub async fn run<B, F>(&self, rank: u32, mut barrier: B) -> Result<StormRankReport>
+    where
+        B: FnMut(u32) -> F,
+        F: Future<Output = Result<()>>,
+    {
+        let s = &self.settings;
+        info!("🌩️  Checkpoint storm: {} checkpoints of {} ({} shards) every {:?} under {}",
+              s.count, crate::units::fmt_bytes(s.size_bytes as u64), s.shards, s.interval, s.folder);
+        let local = local_path_for_uri(&s.folder).is_some();
+        let store = if local {
+            None
+        } else {
+            Some(store_for_uri(&s.folder).with_context(|| format!("Failed to create object store for {}", s.folder))?)
+        };
+        let mp_settings = MultipartSettings::from_config(&self.config);
+        let mp_stats = MultipartStats::new();
+        let shard_bytes = s.size_bytes.div_ceil(s.shards);
+
+        let origin = Instant::now();
+        let mut late = 0;
+        let mut bursts = Vec::with_capacity(s.count as usize);
+        for checkpoint in 0..s.count {
+            let slot = origin + s.interval * checkpoint;
+            if checkpoint > 0 && !s.interval.is_zero() && Instant::now() > slot {
+                late += 1;
+                warn!("⚠️  Checkpoint {} starts {:?} late: the previous checkpoint overran the interval",
+                      checkpoint + 1, Instant::now() - slot);
+            }
+            tokio::time::sleep_until(slot.into()).await;
+            // Distinct content per checkpoint, rank and shard so dedup-capable backends store every
+            // byte; built before the barrier, like model state already in memory at a checkpoint
+            let shard_data: Vec<Arc<Vec<u8>>> = (0..s.shards)
+                .map(|shard| {
+                    let mut data = vec![0u8; shard_bytes];
+                    content::fill_sample(checkpoint as u64, rank as u64, shard as u64, &mut data);
+                    Arc::new(data)
+                })
+                .collect();
+            barrier(checkpoint).await.with_context(|| format!("Failed to synchronize before checkpoint {}", checkpoint + 1))?;
+
+            let start = unix_now();
+            let write_ms: Vec<f64> = stream::iter(0..s.shards)
+                .map(|shard| {
+                    let (uri, data, store) = (s.shard_uri(checkpoint, rank, shard), &shard_data[shard], &store);
+                    let (mp_settings, mp_stats) = (&mp_settings, &mp_stats);
+                    async move {
+                        let write_start = Instant::now();
+                        match (store, local_path_for_uri(&uri)) {
+                            (Some(store), _) => multipart::put_object(store.as_ref(), &uri, data, mp_settings, mp_stats).await,
+                            (None, Some(path)) => {
+                                // A handle, not a copy: the shard bytes are shared with the blocking task
+                                let (data, fsync) = (Arc::clone(data), s.fsync);
+                                tokio::task::spawn_blocking(move || write_local(path, &data, fsync)).await?
+                            }
+                            (None, None) => unreachable!("local folder has local shard paths"),
+                        }
+                        .with_context(|| format!("Checkpoint write of {} failed", uri))?;
+                        Ok::<_, anyhow::Error>(write_start.elapsed().as_secs_f64() * 1000.0)
+                    }
+                })
+                .buffer_unordered(s.shards)
+                .try_collect()
+                .await?;
+            let burst = StormBurst { checkpoint: checkpoint + 1, start, end: unix_now(), bytes: (shard_bytes * s.shards) as u64, write_ms };
+            info!("💾 Checkpoint {}/{}: {} in {:.3}s ({})", checkpoint + 1, s.count, crate::units::fmt_bytes(burst.bytes),
+                  burst.end - burst.start, Throughput::new(burst.bytes, burst.end - burst.start).display());
+            bursts.push(burst);
+        }
+
+        Ok(StormRankReport {
+            rank,
+            folder: s.folder.clone(),
+            size_bytes: s.size_bytes,
+            shards: s.shards,
+            interval_s: s.interval.as_secs_f64(),
+            fsynced: local && s.fsync,
+            late,
+            bursts,
+            multipart: store.is_some().then(|| mp_stats.to_json()),
+        })
+    }
+}
+
+impl StormReport {
+    /// Combine rank reports, each with its clock offset relative to the reference rank
+    pub fn aggregate(ranks: &[(ClockOffset, StormRankReport)], clock_corrected: bool) -> Self {
+        let checkpoints = ranks.iter().map(|(_, r)| r.bursts.len()).min().unwrap_or(0);
+        let per_checkpoint: Vec<StormCheckpoint> = (0..checkpoints)
+            .map(|i| {
+                let bursts: Vec<(f64, f64, u64)> = ranks
+                    .iter()
+                    .map(|(clock, r)| (clock.correct(r.bursts[i].start), clock.correct(r.bursts[i].end), r.bursts[i].bytes))
+                    .collect();
+                let first_start = bursts.iter().map(|b| b.0).fold(f64::INFINITY, f64::min);
+                let first_end = bursts.iter().map(|b| b.1).fold(f64::INFINITY, f64::min);
+                let last_end = bursts.iter().map(|b| b.1).fold(0.0, f64::max);
+                let bytes = bursts.iter().map(|b| b.2).sum();
+                StormCheckpoint {
+                    checkpoint: i as u32 + 1,
+                    bytes,
+                    seconds: last_end - first_start,
+                    throughput: Throughput::new(bytes, last_end - first_start),
+                    skew_seconds: last_end - first_end,
+                }
+            })
+            .collect();
+
+        let mut write_ms: Vec<f64> = ranks.iter().flat_map(|(_, r)| r.bursts.iter().flat_map(|b| b.write_ms.iter().copied())).collect();
+        write_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = per_checkpoint.len().max(1) as f64;
+        let rates: Vec<f64> = per_checkpoint.iter().map(|c| c.throughput.bytes_per_s).collect();
+        let report = Self {
+            ranks: ranks.len(),
+            checkpoints,
+            bytes_per_checkpoint: per_checkpoint.first().map_or(0, |c| c.bytes),
+            burst_throughput_mean: Throughput::from_bytes_per_s(rates.iter().sum::<f64>() / n),
+            burst_throughput_min: Throughput::from_bytes_per_s(rates.iter().copied().reduce(f64::min).unwrap_or(0.0)),
+            write_p50_ms: percentile(&write_ms, 0.50),
+            write_p99_ms: percentile(&write_ms, 0.99),
+            write_max_ms: write_ms.last().copied().unwrap_or(0.0),
+            skew_seconds_mean: per_checkpoint.iter().map(|c| c.skew_seconds).sum::<f64>() / n,
+            skew_seconds_max: per_checkpoint.iter().map(|c| c.skew_seconds).fold(0.0, f64::max),
+            late_checkpoints: ranks.iter().map(|(_, r)| r.late).max().unwrap_or(0),
+            clock_corrected,
+            per_checkpoint,
+        };
+        info!("🌩️  Checkpoint storm: {} ranks × {} checkpoints, burst {} mean ({} min), write p99 {:.1}ms, skew max {:.3}s",
+              report.ranks, report.checkpoints, report.burst_throughput_mean.display(),
+              report.burst_throughput_min.display(), report.write_p99_ms, report.skew_seconds_max);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_storm_writes_bursts_and_aggregates_skew() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = format!(
+            "dataset:\n  data_folder: {}\nreader: {{}}\ncheckpoint_storm: {{size_bytes: 4096, shards: 2, count: 2, interval_s: 0}}\n",
+            dir.path().display()
+        );
+        let too_long = DlioConfig::from_yaml(&yaml.replace("interval_s: 0", "interval_s: 1e300")).unwrap();
+        assert!(StormSettings::from_config(&too_long).is_err());
+        let storm = CheckpointStorm::new(DlioConfig::from_yaml(&yaml).unwrap()).unwrap();
+        let mut barriers = Vec::new();
+        let report = storm.run(3, |n| { barriers.push(n); async { Ok(()) } }).await.unwrap();
+        assert_eq!(barriers, [0, 1]);
+        assert!(report.fsynced);
+        assert_eq!(report.bursts.len(), 2);
+        assert!(report.bursts.iter().all(|b| b.bytes == 4096 && b.write_ms.len() == 2));
+        let shard = |checkpoint, shard| std::fs::read(dir.path().join(format!("checkpoint_storm/ckpt_{:04}/rank_00003_shard_{:03}.bin", checkpoint, shard))).unwrap();
+        assert_eq!(shard(1, 1).len(), 2048);
+        // No two shards, in one checkpoint or across checkpoints, share content
+        assert_ne!(shard(1, 0), shard(1, 1));
+        assert_ne!(shard(0, 0), shard(1, 0));
+
+        // Two ranks: the second finishes a second later, and its clock runs 0.5s ahead
+        let burst = |start: f64, end: f64| StormBurst { checkpoint: 1, start, end, bytes: 1 << 30, write_ms: vec![end - start] };
+        let rank = |rank, bursts| StormRankReport { rank, bursts, ..report.clone() };
+        let ahead = ClockOffset { offset_ns: -500_000_000, uncertainty_ns: 0 };
+        let storm = StormReport::aggregate(
+            &[(ClockOffset::default(), rank(0, vec![burst(100.0, 101.0)])), (ahead, rank(1, vec![burst(100.5, 102.5)]))],
+            true,
+        );
+        assert_eq!((storm.ranks, storm.checkpoints, storm.bytes_per_checkpoint), (2, 1, 2 << 30));
+        assert_eq!(storm.per_checkpoint[0].skew_seconds, 1.0);
+        assert_eq!(storm.burst_throughput_mean.gib_s, 1.0);
+    }
+}
@@ -0,0 +1,205 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/client_limits.rs
+//
+// Client footprint limits (`client_limits:` config block), to emulate
+// constrained clients on a large benchmark host
+//
+// - CPU: every thread of the process is pinned to `cpus` cores (or the explicit
+//   `cpu_list`). Ranks sharing a host get consecutive, disjoint core sets
+//   (rank r starts at core r × cpus of the allowed set, wrapping around).
+//   Threads started later inherit the affinity.
+// - Memory: with cgroup v2, the process moves into a child cgroup
+//   `dl-driver-<pid>` of its own cgroup, and `memory.max` is set there. This
+//   needs a writable (delegated) cgroup, e.g. under `systemd-run --user --scope`;
+//   otherwise the limit is reported as not applied rather than failing the run.
+//
+// What was actually applied is recorded under "client_limits" in the results.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::dlio_compat::DlioConfig;
+
+/// cgroup v2 unified hierarchy mount point
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Requested limits for this rank
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientLimits {
+    /// Cores to pin to
+    pub cpu_list: Option<Vec<usize>>,
+    pub memory_bytes: Option<u64>,
+}
+
+/// Limits in effect after `apply`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AppliedLimits {
+    pub cpu_list: Option<Vec<usize>>,
+    /// Threads pinned (all threads of the process when applied)
+    pub pinned_threads: usize,
+    pub memory_limit_bytes: Option<u64>,
+    /// cgroup holding the memory limit
+    pub cgroup: Option<String>,
+    /// Requested limits that could not be applied, and why
+    pub not_applied: Vec<String>,
+}
+
+impl ClientLimits {
+    /// Limits for `rank` from the config's `client_limits:` block; None when absent
+    pub fn from_config(config: &DlioConfig, rank: u32) -> Result<Option<Self>> {
+        let Some(settings) = &config.client_limits else {
+            return Ok(None);
+        };
+        let allowed = current_affinity().context("Cannot read the process CPU affinity")?;
+        let cpu_list = match (&settings.cpu_list, settings.cpus) {
+            (Some(_), Some(_)) => anyhow::bail!("client_limits: set either cpus or cpu_list, not both"),
+            (Some(list), None) => {
+                if let Some(cpu) = list.iter().find(|c| !allowed.contains(c)) {
+                    anyhow::bail!("client_limits.cpu_list: CPU {} is not available to this process (allowed: {:?})", cpu, allowed);
+                }
+                Some(list.clone())
+            }
+            (None, Some(count)) => Some(select_cpus(&allowed, count, rank)?),
+            (None, None) => None,
+        };
+        if settings.memory_bytes == Some(0) {
+            anyhow::bail!("client_limits.memory_bytes must be positive");
+        }
+        Ok(Some(Self { cpu_list, memory_bytes: settings.memory_bytes }))
+    }
+
+    /// Apply the limits to the whole process
+    pub fn apply(&self) -> AppliedLimits {
+        let mut applied = AppliedLimits::default();
+        if let Some(cpus) = &self.cpu_list {
+            match pin_process(cpus) {
+                Ok(threads) => {
+                    info!("📌 Client limited to CPUs {:?} ({} threads pinned)", cpus, threads);
+                    applied.cpu_list = Some(cpus.clone());
+                    applied.pinned_threads = threads;
+                }
+                Err(e) => {
+                    warn!("⚠️  CPU affinity not applied: {:#}", e);
+                    applied.not_applied.push(format!("cpu affinity: {:#}", e));
+                }
+            }
+        }
+        if let Some(bytes) = self.memory_bytes {
+            match limit_memory(bytes) {
+                Ok(cgroup) => {
+                    info!("📌 Client memory limited to {} bytes (cgroup {})", bytes, cgroup.display());
+                    applied.memory_limit_bytes = Some(bytes);
+                    applied.cgroup = Some(cgroup.display().to_string());
+                }
+                Err(e) => {
+                    warn!("⚠️  Memory limit not applied: {:#}", e);
+                    applied.not_applied.push(format!("memory limit: {:#}", e));
+                }
+            }
+        }
+        applied
+    }
+}
+
+/// `count` consecutive CPUs of `allowed` for `rank`, wrapping around
+pub fn select_cpus(allowed: &[usize], count: usize, rank: u32) -> Result<Vec<usize>> {
+    if count == 0 || count > allowed.len() {
+        anyhow::bail!("client_limits.cpus must be 1..={} (CPUs available to this process)", allowed.len());
+    }
+    let start = rank as usize * count;
+    Ok((0..count).map(|i| allowed[(start + i) % allowed.len()]).collect())
+}
+
+fn current_affinity() -> Result<Vec<usize>> {
+    // SAFETY: cpu_set_t is plain data; sched_getaffinity fills it for this thread
+    let set = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        set
+    };
+    // SAFETY: CPU_ISSET only reads the set
+    Ok((0..libc::CPU_SETSIZE as usize).filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) }).collect())
+}
+
+/// Pin every thread of the process to `cpus`; returns the number of threads pinned
+fn pin_process(cpus: &[usize]) -> Result<usize> {
+    // SAFETY: cpu_set_t is plain data; CPU_SET writes within it (cpus < CPU_SETSIZE checked)
+    let set = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for &cpu in cpus {
+            anyhow::ensure!(cpu < libc::CPU_SETSIZE as usize, "CPU {} out of range", cpu);
+            libc::CPU_SET(cpu, &mut set);
+        }
+        set
+    };
+    let mut pinned = 0;
+    for entry in std::fs::read_dir("/proc/self/task").context("Cannot list process threads")? {
+        let Some(tid) = entry?.file_name().to_str().and_then(|s| s.parse::<libc::pid_t>().ok()) else {
+            continue;
+        };
+        // SAFETY: the set is initialized; a thread that exited meanwhile just fails with ESRCH
+        if unsafe { libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) } == 0 {
+            pinned += 1;
+        } else {
+            let err = std::io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::ESRCH) {
+                return Err(err).with_context(|| format!("sched_setaffinity failed for thread {}", tid));
+            }
+        }
+    }
+    Ok(pinned)
+}
+
+/// Move the process into a child cgroup with `memory.max` = `bytes`
+fn limit_memory(bytes: u64) -> Result<PathBuf> {
+    let membership = std::fs::read_to_string("/proc/self/cgroup").context("Cannot read /proc/self/cgroup")?;
+    let own = membership
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .context("cgroup v2 is not in use")?;
+    let parent = Path::new(CGROUP_ROOT).join(own.trim_start_matches('/'));
+    let child = parent.join(format!("dl-driver-{}", std::process::id()));
+
+    let write = |path: PathBuf, value: &str| {
+        std::fs::write(&path, value).with_context(|| format!("Cannot write {:?} to {}", value, path.display()))
+    };
+    std::fs::create_dir_all(&child).with_context(|| format!("Cannot create cgroup {} (not delegated?)", child.display()))?;
+    write(child.join("cgroup.procs"), &std::process::id().to_string())?;
+    // With the process out of the parent, the parent may delegate the memory controller
+    let controllers = std::fs::read_to_string(child.join("cgroup.controllers")).unwrap_or_default();
+    if !controllers.split_whitespace().any(|c| c == "memory") {
+        write(parent.join("cgroup.subtree_control"), "+memory")?;
+    }
+    write(child.join("memory.max"), &bytes.to_string())?;
+    // Keep the limit from being met by swapping; not every host allows it
+    let _ = std::fs::write(child.join("memory.swap.max"), "0");
+    Ok(child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_selection_per_rank() {
+        let allowed = vec![0, 1, 2, 3, 4, 5, 8, 9];
+        assert_eq!(select_cpus(&allowed, 2, 0).unwrap(), vec![0, 1]);
+        assert_eq!(select_cpus(&allowed, 2, 3).unwrap(), vec![8, 9]);
+        assert_eq!(select_cpus(&allowed, 3, 2).unwrap(), vec![8, 9, 0], "wraps around");
+        assert!(select_cpus(&allowed, 0, 0).is_err());
+        assert!(select_cpus(&allowed, 9, 0).is_err());
+
+        let yaml = "dataset:\n  data_folder: file:///tmp/d\nreader: {}\nclient_limits: {cpus: 1, cpu_list: [0]}\n";
+        assert!(ClientLimits::from_config(&DlioConfig::from_yaml(yaml).unwrap(), 0).is_err());
+        let yaml = "dataset:\n  data_folder: file:///tmp/d\nreader: {}\nclient_limits: {cpus: 1, memory_bytes: 1073741824}\n";
+        let limits = ClientLimits::from_config(&DlioConfig::from_yaml(yaml).unwrap(), 0).unwrap().unwrap();
+        assert_eq!(limits.cpu_list.map(|c| c.len()), Some(1));
+        assert_eq!(limits.memory_bytes, Some(1 << 30));
+    }
+}
@@ -0,0 +1,298 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/clone_bench.rs
+//
+// Dataset snapshot/clone benchmark (`clone_benchmark:` config block)
+//
+// Many arrays can clone a bucket or directory near-instantly. The benchmark
+// times the clone through a pluggable CloneHook, checks the clone holds every
+// source object, then trains one epoch from the cloned prefix:
+//
+//   clone_benchmark:
+//     command: "vastcli clone create --src {source_path} --dst {target_path}"
+//     target: s3://bucket/train_clone/
+//     compare_source: true      # also time a first epoch from the source
+//     cleanup: true             # delete the clone afterwards
+//
+// Without `command`, the "copy" hook copies object by object, a baseline for
+// arrays without a clone feature. Library users can supply their own hook
+// (e.g. a storage vendor API) with CloneBenchmark::with_hook.
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::time::Instant;
+use tracing::info;
+
+use crate::dlio_compat::{local_path_for_uri, CloneBenchmarkConfig, DlioConfig};
+use crate::suite::{cleanup_dataset, RunMetrics};
+use crate::workload::WorkloadRunner;
+use s3dlio::object_store::store_for_uri;
+
+/// Objects copied concurrently by the copy hook
+const COPY_CONCURRENCY: usize = 16;
+
+/// Creates a clone of a dataset prefix
+#[async_trait]
+pub trait CloneHook: Send + Sync {
+    /// Short hook name for logs and reports
+    fn name(&self) -> String;
+
+    /// Clone everything under `source` to `target`, returning once the clone is readable
+    async fn clone_prefix(&self, source: &str, target: &str) -> Result<()>;
+}
+
+/// Path form of a URI for shell commands: local path, or the part after the scheme
+pub(crate) fn uri_path(uri: &str) -> String {
+    match local_path_for_uri(uri) {
+        Some(path) => path.display().to_string(),
+        None => uri.split_once("://").map_or(uri, |(_, rest)| rest).to_string(),
+    }
+}
+
+/// Run an operator-supplied hook command through `sh -c`, failing with its stderr
+/// when it exits non-zero. `kind` names the hook in errors ("Clone", "Migration").
+pub(crate) async fn run_shell(kind: &str, command: &str) -> Result<()> {
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .await
+        .with_context(|| format!("Failed to launch {} command: {}", kind.to_lowercase(), command))?;
+    if !output.status.success() {
+        anyhow::bail!("{} command exited with {}: {}", kind, output.status, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Runs an operator-supplied shell command
+pub struct ShellCloneHook {
+    command: String,
+}
+
+impl ShellCloneHook {
+    pub fn new(command: &str) -> Self {
+        Self { command: command.to_string() }
+    }
+
+    fn render(&self, source: &str, target: &str) -> String {
+        self.command
+            .replace("{source_path}", &uri_path(source))
+            .replace("{target_path}", &uri_path(target))
+            .replace("{source}", source)
+            .replace("{target}", target)
+    }
+}
+
+#[async_trait]
+impl CloneHook for ShellCloneHook {
+    fn name(&self) -> String {
+        "shell".to_string()
+    }
+
+    async fn clone_prefix(&self, source: &str, target: &str) -> Result<()> {
+        let command = self.render(source, target);
+        info!("🐑 Clone command: {}", command);
+        run_shell("Clone", &command).await
+    }
+}
+
+/// Object-by-object copy, the baseline a fast clone is compared against
+pub struct CopyCloneHook;
+
+#[async_trait]
+impl CloneHook for CopyCloneHook {
+    fn name(&self) -> String {
+        "copy".to_string()
+    }
+
+    async fn clone_prefix(&self, source: &str, target: &str) -> Result<()> {
+        // Listed as a directory: a bare prefix would also match "train_old/..." next to "train/"
+        let source = format!("{}/", source.trim_end_matches('/'));
+        let target = format!("{}/", target.trim_end_matches('/'));
+        let (source, target) = (source.as_str(), target.as_str());
+        let src_store = store_for_uri(source).with_context(|| format!("Failed to create object store for {}", source))?;
+        let dst_store = store_for_uri(target).with_context(|| format!("Failed to create object store for {}", target))?;
+        let uris = src_store.list(source, true).await.with_context(|| format!("Failed to list {}", source))?;
+
+        stream::iter(uris)
+            .map(|uri| {
+                let (src_store, dst_store) = (&src_store, &dst_store);
+                async move {
+                    let rel = uri.strip_prefix(source).with_context(|| format!("{} is not under {}", uri, source))?;
+                    let data = src_store.get(&uri).await.with_context(|| format!("Failed to read {}", uri))?;
+                    let dest = format!("{}{}", target, rel);
+                    dst_store.put(&dest, &data).await.with_context(|| format!("Failed to write {}", dest))
+                }
+            })
+            .buffer_unordered(COPY_CONCURRENCY)
+            .try_collect::<Vec<()>>()
+            .await?;
+        Ok(())
+    }
+}
+
+/// Results of a clone benchmark
+#[derive(Debug, Clone, Serialize)]
+pub struct CloneReport {
+    pub hook: String,
+    pub source: String,
+    pub target: String,
+    pub clone_latency_s: f64,
+    pub source_objects: usize,
+    pub clone_objects: usize,
+    /// First epoch trained from the clone
+    pub clone_epoch: RunMetrics,
+    /// First epoch trained from the source, with `compare_source`
+    pub source_epoch: Option<RunMetrics>,
+    pub cleaned_up: bool,
+}
+
+impl CloneReport {
+    pub fn print_summary(&self) {
+        println!("\n=== Clone Benchmark Summary ===");
+        println!("Hook: {}  {} -> {}", self.hook, self.source, self.target);
+        println!("Clone latency: {:.3}s ({} objects)", self.clone_latency_s, self.clone_objects);
+        let line = |label: &str, m: &RunMetrics| {
+            println!(
+                "{:<14} {:>9.3} GiB/s  {:>10.1} samples/s  epoch {:>8.2}s",
+                label,
+                m.throughput_gib_s.unwrap_or(0.0),
+                m.samples_per_second.unwrap_or(0.0),
+                m.wall_clock_s.unwrap_or(0.0)
+            );
+        };
+        line("From clone:", &self.clone_epoch);
+        if let Some(source) = &self.source_epoch {
+            line("From source:", source);
+        }
+        println!("===============================\n");
+    }
+}
+
+/// Clone the dataset, validate the clone and train one epoch from it
+pub struct CloneBenchmark {
+    config: DlioConfig,
+    settings: CloneBenchmarkConfig,
+    hook: Box<dyn CloneHook>,
+}
+
+impl CloneBenchmark {
+    /// From the config's `clone_benchmark:` block
+    pub fn from_config(config: DlioConfig) -> Result<Self> {
+        let settings = config
+            .clone_benchmark
+            .clone()
+            .context("Config has no clone_benchmark section")?;
+        let hook: Box<dyn CloneHook> = match (settings.hook.as_deref(), settings.command.as_deref()) {
+            (Some("shell") | None, Some(command)) => Box::new(ShellCloneHook::new(command)),
+            (Some("shell"), None) => anyhow::bail!("clone_benchmark.hook 'shell' needs a command"),
+            (Some("copy") | None, _) => Box::new(CopyCloneHook),
+            (Some(other), _) => anyhow::bail!("Unknown clone_benchmark.hook '{}' (expected shell or copy)", other),
+        };
+        Ok(Self { config, settings, hook })
+    }
+
+    /// Replace the configured hook, e.g. with a storage vendor API client
+    pub fn with_hook(mut self, hook: Box<dyn CloneHook>) -> Self {
+        self.hook = hook;
+        self
+    }
+
+    pub fn target(&self) -> String {
+        self.settings
+            .target
+            .clone()
+            .unwrap_or_else(|| format!("{}_clone/", self.config.data_folder_uri().trim_end_matches('/')))
+    }
+
+    /// One training epoch with the dataset read from `data_folder`
+    async fn first_epoch(&self, data_folder: &str) -> Result<RunMetrics> {
+        let mut config = self.config.clone();
+        config.dataset.data_folder = data_folder.to_string();
+        if let Some(train) = config.train.as_mut() {
+            train.epochs = Some(1);
+        }
+        config.schedule = None;
+        config.failover = None;
+        let mut runner = WorkloadRunner::new(config.clone());
+        runner.run_training_phase().await.with_context(|| format!("Training from {} failed", data_folder))?;
+        Ok(RunMetrics::from_results(&runner.get_metrics().to_json(0, &config)))
+    }
+
+    pub async fn run(&self) -> Result<CloneReport> {
+        let source = self.config.data_folder_uri().to_string();
+        let target = self.target();
+        let count = |uri: String| async move {
+            let store = store_for_uri(&uri).with_context(|| format!("Failed to create object store for {}", uri))?;
+            let objects = store.list(&uri, true).await.with_context(|| format!("Failed to list {}", uri))?;
+            anyhow::Ok(objects.len())
+        };
+        let source_objects = count(source.clone()).await?;
+        if source_objects == 0 {
+            anyhow::bail!("No dataset objects under {}; generate the dataset first", source);
+        }
+
+        info!("🐑 Cloning {} -> {} with the {} hook", source, target, self.hook.name());
+        let start = Instant::now();
+        self.hook.clone_prefix(&source, &target).await.context("Clone hook failed")?;
+        let clone_latency_s = start.elapsed().as_secs_f64();
+        info!("🐑 Clone ready in {:.3}s", clone_latency_s);
+
+        let clone_objects = count(target.clone()).await?;
+        if clone_objects < source_objects {
+            anyhow::bail!("Clone at {} holds {} of {} source objects", target, clone_objects, source_objects);
+        }
+
+        let clone_epoch = self.first_epoch(&target).await?;
+        let source_epoch = if self.settings.compare_source.unwrap_or(false) {
+            Some(self.first_epoch(&source).await?)
+        } else {
+            None
+        };
+
+        let cleaned_up = self.settings.cleanup.unwrap_or(false);
+        if cleaned_up {
+            let mut clone_config = self.config.clone();
+            clone_config.dataset.data_folder = target.clone();
+            cleanup_dataset(&clone_config).await?;
+        }
+
+        Ok(CloneReport {
+            hook: self.hook.name(),
+            source,
+            target,
+            clone_latency_s,
+            source_objects,
+            clone_objects,
+            clone_epoch,
+            source_epoch,
+            cleaned_up,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_hook_selection_and_rendering() {
+        let hook = ShellCloneHook::new("cp -r {source_path} {target_path} # {source} {target}");
+        assert_eq!(
+            hook.render("file:///data/train/", "s3://bucket/clone/"),
+            "cp -r /data/train/ bucket/clone/ # file:///data/train/ s3://bucket/clone/"
+        );
+
+        let yaml = |block: &str| format!("dataset:\n  data_folder: file:///data/train/\nreader: {{}}\nclone_benchmark: {}\n", block);
+        let bench = CloneBenchmark::from_config(DlioConfig::from_yaml(&yaml("{command: 'true'}")).unwrap()).unwrap();
+        assert_eq!(bench.hook.name(), "shell");
+        assert_eq!(bench.target(), "file:///data/train_clone/");
+        let bench = CloneBenchmark::from_config(DlioConfig::from_yaml(&yaml("{target: 'file:///c/'}")).unwrap()).unwrap();
+        assert_eq!((bench.hook.name(), bench.target()), ("copy".to_string(), "file:///c/".to_string()));
+        assert!(CloneBenchmark::from_config(DlioConfig::from_yaml(&yaml("{hook: shell}")).unwrap()).is_err());
+        assert!(CloneBenchmark::from_config(DlioConfig::from_yaml(&yaml("{hook: magic}")).unwrap()).is_err());
+    }
+}
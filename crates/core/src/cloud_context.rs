@@ -0,0 +1,166 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/cloud_context.rs
+//
+// Resolved cloud context of a run ("cloud_context" in the results)
+//
+// Differently configured clients are the biggest source of non-reproducible
+// cloud results: a custom endpoint vs the regional one, another region,
+// path-style vs virtual-hosted addressing, plain HTTP vs TLS. This records
+// what the run actually resolved, from the config and the same environment
+// the storage clients read:
+//
+//   s3://      AWS_ENDPOINT_URL (path-style addressing, as s3dlio does), else
+//              the regional AWS endpoint from AWS_REGION / AWS_DEFAULT_REGION;
+//              AWS_CA_BUNDLE when set
+//   az://      the AZURE_BLOB_ACCOUNT blob endpoint
+//   gs://      storage.googleapis.com
+//   http(s)    the URL's own endpoint
+//
+// `storage.network.endpoint` overrides the endpoint, as it does for the
+// reachability probe. Local backends have no cloud context.
+//
+// `storage.storage_class` is recorded as requested but not applied: s3dlio's
+// PUTs take no storage class, so generated data and checkpoints land in the
+// bucket's default class. `storage_class_applied` says so in the results.
+//
+use serde::Serialize;
+
+use crate::dlio_compat::DlioConfig;
+use crate::network::redact_proxy;
+
+/// S3 region when neither AWS_REGION nor AWS_DEFAULT_REGION is set
+const DEFAULT_S3_REGION: &str = "us-east-1";
+
+/// Endpoint, region, addressing and TLS a cloud run resolved
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CloudContext {
+    /// "s3", "azure", "gcs" or "http"
+    pub backend: String,
+    /// Endpoint URL (credentials removed); None when it cannot be resolved
+    pub endpoint_url: Option<String>,
+    /// Where the endpoint came from: "config", "env", "uri" or "default"
+    pub endpoint_source: String,
+    pub region: Option<String>,
+    /// `storage.storage_class`, when requested
+    pub storage_class: Option<String>,
+    /// Whether writes used `storage_class`; always false, as s3dlio PUTs take none
+    pub storage_class_applied: bool,
+    /// "path" or "virtual" (S3 only)
+    pub addressing_style: Option<String>,
+    pub tls: bool,
+    /// Custom CA bundle in use (S3 only)
+    pub ca_bundle: Option<String>,
+}
+
+impl CloudContext {
+    /// Context of the data folder's backend; None for local storage
+    pub fn from_config(config: &DlioConfig) -> Option<Self> {
+        Self::resolve(config, |name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    /// As `from_config`, reading environment variables through `env`
+    pub fn resolve(config: &DlioConfig, env: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let uri = config.data_folder_uri();
+        let scheme = uri.split_once("://").map(|(s, _)| s)?;
+        let configured = config.storage.as_ref().and_then(|s| s.network.as_ref()).and_then(|n| n.endpoint.clone());
+        let storage_class = config.storage.as_ref().and_then(|s| s.storage_class.clone());
+
+        let (backend, default_endpoint, region, addressing_style, ca_bundle) = match scheme {
+            "s3" => {
+                let region = env("AWS_REGION").or_else(|| env("AWS_DEFAULT_REGION"));
+                let custom = env("AWS_ENDPOINT_URL");
+                let default = match &custom {
+                    Some(url) => (Some(url.clone()), "env"),
+                    None => {
+                        let region = region.as_deref().unwrap_or(DEFAULT_S3_REGION);
+                        (Some(format!("https://s3.{}.amazonaws.com", region)), "default")
+                    }
+                };
+                let addressing = if custom.is_some() { "path" } else { "virtual" };
+                let region = region.unwrap_or_else(|| DEFAULT_S3_REGION.to_string());
+                ("s3", default, Some(region), Some(addressing.to_string()), env("AWS_CA_BUNDLE"))
+            }
+            "az" => {
+                let endpoint = env("AZURE_BLOB_ACCOUNT").map(|account| format!("https://{}.blob.core.windows.net", account));
+                ("azure", (endpoint, "env"), None, None, None)
+            }
+            "gs" | "gcs" => ("gcs", (Some("https://storage.googleapis.com".to_string()), "default"), None, None, None),
+            "http" | "https" => {
+                let authority = uri.split_once("://").map_or("", |(_, rest)| rest.split('/').next().unwrap_or_default());
+                ("http", (Some(format!("{}://{}", scheme, authority)), "uri"), None, None, None)
+            }
+            _ => return None,
+        };
+
+        let (endpoint_url, endpoint_source) = match configured {
+            Some(endpoint) if endpoint.contains("://") => (Some(endpoint), "config"),
+            Some(endpoint) => (Some(format!("https://{}", endpoint)), "config"),
+            None => default_endpoint,
+        };
+        Some(Self {
+            backend: backend.to_string(),
+            tls: !endpoint_url.as_deref().is_some_and(|url| url.starts_with("http://")),
+            endpoint_url: endpoint_url.map(|url| redact_proxy(url.trim_end_matches('/'))),
+            endpoint_source: endpoint_source.to_string(),
+            region,
+            storage_class,
+            storage_class_applied: false,
+            addressing_style,
+            ca_bundle,
+        })
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {} ({}{}{}, {})",
+            self.backend,
+            self.endpoint_url.as_deref().unwrap_or("(endpoint unknown)"),
+            self.region.as_deref().map(|r| format!("region {}, ", r)).unwrap_or_default(),
+            self.addressing_style.as_deref().map(|a| format!("{}-style, ", a)).unwrap_or_default(),
+            if self.tls { "TLS" } else { "no TLS" },
+            self.storage_class.as_deref().map_or("default storage class".to_string(), |c| format!("{} requested, not applied", c)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_context_resolution() {
+        let config = |folder: &str, storage: &str| {
+            DlioConfig::from_yaml(&format!("dataset:\n  data_folder: {}\nreader: {{}}\n{}", folder, storage)).unwrap()
+        };
+        let no_env = |_: &str| None;
+        assert_eq!(CloudContext::resolve(&config("/local/data", ""), no_env), None);
+
+        // Regional AWS endpoint, virtual-hosted, requested storage class
+        let aws = CloudContext::resolve(&config("s3://bucket/train", "storage:\n  storage_class: INTELLIGENT_TIERING\n"),
+                                        |name| (name == "AWS_DEFAULT_REGION").then(|| "eu-west-1".to_string()))
+            .unwrap();
+        assert_eq!(aws.endpoint_url.as_deref(), Some("https://s3.eu-west-1.amazonaws.com"));
+        assert_eq!((aws.endpoint_source.as_str(), aws.region.as_deref()), ("default", Some("eu-west-1")));
+        assert_eq!((aws.addressing_style.as_deref(), aws.tls), (Some("virtual"), true));
+        assert_eq!(aws.storage_class.as_deref(), Some("INTELLIGENT_TIERING"));
+        assert!(!aws.storage_class_applied);
+
+        // S3-compatible endpoint over plain HTTP is path-style
+        let minio = CloudContext::resolve(&config("s3://bucket/train", ""), |name| match name {
+            "AWS_ENDPOINT_URL" => Some("http://minio.lab:9000/".to_string()),
+            "AWS_CA_BUNDLE" => Some("/etc/ca.pem".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!((minio.endpoint_url.as_deref(), minio.endpoint_source.as_str()), (Some("http://minio.lab:9000"), "env"));
+        assert_eq!((minio.addressing_style.as_deref(), minio.tls, minio.region.as_deref()), (Some("path"), false, Some("us-east-1")));
+        assert_eq!(minio.ca_bundle.as_deref(), Some("/etc/ca.pem"));
+
+        // storage.network.endpoint wins
+        let pinned = CloudContext::resolve(&config("https://data.lab:8443/train/", "storage:\n  network: {endpoint: 'cdn.lab:443'}\n"), no_env)
+            .unwrap();
+        assert_eq!((pinned.backend.as_str(), pinned.endpoint_url.as_deref(), pinned.endpoint_source.as_str()), ("http", Some("https://cdn.lab:443"), "config"));
+    }
+}
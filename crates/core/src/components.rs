@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/components.rs
+//
+// Multi-component (mixed-format) datasets
+//
+// With `dataset.components`, every file index is stored as one object per
+// component, e.g. `train_file_000042.image.npz` + `train_file_000042.meta.json`.
+// Components sharing a file index are generated together and always land in
+// the same training batch.
+//
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use crate::dlio_compat::{DatasetComponent, DlioConfig};
+use real_dlio_formats::{NpyDtype, NpzStreamingFormat, StreamingFormat, TfRecordFormat};
+
+/// Configured components, if this is a multi-component dataset
+pub fn components(config: &DlioConfig) -> Option<&[DatasetComponent]> {
+    config.dataset.components.as_deref().filter(|c| !c.is_empty())
+}
+
+/// Object name suffix for a component: `<name>.<format>`
+pub fn suffix(component: &DatasetComponent) -> String {
+    format!("{}.{}", component.name, component.format.to_lowercase())
+}
+
+/// Per-record size of a component (falls back to `dataset.record_length_bytes`)
+pub fn record_length(config: &DlioConfig, component: &DatasetComponent) -> usize {
+    component
+        .record_length_bytes
+        .or(config.dataset.record_length_bytes)
+        .unwrap_or(1024)
+}
+
+/// Bytes of one sample: its components' records together, else `dataset.record_length_bytes`
+pub fn sample_bytes(config: &DlioConfig) -> usize {
+    match components(config) {
+        Some(comps) => comps.iter().map(|c| record_length(config, c)).sum(),
+        None => config.dataset.record_length_bytes.unwrap_or(1024),
+    }
+}
+
+/// Build one component object holding `samples` records
+pub fn generate_payload(config: &DlioConfig, component: &DatasetComponent, samples: usize) -> Result<Vec<u8>> {
+    let record = record_length(config, component).max(1);
+    match component.format.to_lowercase().as_str() {
+        "npz" => NpzStreamingFormat::new(vec![samples, record], 2)
+            .with_dtype(NpyDtype::U8)
+            .generate_bytes(""),
+        "tfrecord" => TfRecordFormat::new(samples, record).generate_bytes(""),
+        "json" => Ok(json_sidecar(&component.name, samples, record)),
+        _ => Ok(s3dlio::generate_controlled_data(samples * record, 0, 0)[..samples * record].to_vec()),
+    }
+}
+
+/// JSON array with one metadata object per sample, each padded to about `record` bytes
+fn json_sidecar(name: &str, samples: usize, record: usize) -> Vec<u8> {
+    let entries: Vec<String> = (0..samples)
+        .map(|i| {
+            let head = format!("{{\"sample\":{},\"component\":\"{}\",\"label\":{},\"pad\":\"", i, name, i % 10);
+            let pad = record.saturating_sub(head.len() + 2);
+            format!("{}{}\"}}", head, "x".repeat(pad))
+        })
+        .collect();
+    format!("[{}]", entries.join(",")).into_bytes()
+}
+
+/// Group object URIs into samples: one URI per component, in component order
+///
+/// Objects are keyed by their file name up to the first '.'; a key missing any
+/// component is an error, and objects of unknown components are ignored.
+pub fn group_by_sample(uris: Vec<String>, components: &[DatasetComponent]) -> Result<Vec<Vec<String>>> {
+    let suffixes: Vec<String> = components.iter().map(suffix).collect();
+    let mut groups: BTreeMap<String, Vec<Option<String>>> = BTreeMap::new();
+
+    for uri in uris {
+        let name = uri.rsplit('/').next().unwrap_or(&uri);
+        let Some((key, rest)) = name.split_once('.') else {
+            continue;
+        };
+        if let Some(slot) = suffixes.iter().position(|s| s == rest) {
+            let key = uri[..uri.len() - name.len()].to_string() + key;
+            groups.entry(key).or_insert_with(|| vec![None; suffixes.len()])[slot] = Some(uri);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, slots)| {
+            slots
+                .into_iter()
+                .zip(&suffixes)
+                .map(|(uri, suffix)| uri.ok_or_else(|| anyhow::anyhow!("Sample {} has no {} component", key, suffix)))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(name: &str, format: &str, size: usize) -> DatasetComponent {
+        DatasetComponent { name: name.into(), format: format.into(), record_length_bytes: Some(size) }
+    }
+
+    #[test]
+    fn test_group_by_sample() {
+        let comps = [component("image", "npz", 1024), component("meta", "json", 128)];
+        let uris = vec![
+            "s3://b/d/train_file_000001.meta.json".to_string(),
+            "s3://b/d/train_file_000000.image.npz".to_string(),
+            "s3://b/d/train_file_000000.meta.json".to_string(),
+            "s3://b/d/train_file_000001.image.npz".to_string(),
+            "s3://b/d/manifest.txt".to_string(),
+        ];
+        let groups = group_by_sample(uris, &comps).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], vec!["s3://b/d/train_file_000000.image.npz", "s3://b/d/train_file_000000.meta.json"]);
+
+        let missing = vec!["s3://b/d/train_file_000002.image.npz".to_string()];
+        assert!(group_by_sample(missing, &comps).is_err());
+    }
+
+    #[test]
+    fn test_json_sidecar_size() {
+        let doc = json_sidecar("meta", 4, 200);
+        let parsed: serde_json::Value = serde_json::from_slice(&doc).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 4);
+        assert!((doc.len() as i64 - 4 * 200).abs() < 16);
+    }
+}
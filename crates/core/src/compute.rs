@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/compute.rs
+//
+// Compute simulation models for the per-step `train.computation_time`
+//
+// `sleep` (default) idles the step away, as DLIO does. Sleeping leaves the
+// client CPU and memory bandwidth free for the loader, which real
+// preprocessing does not, so two busier models are offered:
+//
+// - `busy_loop`: spins a calibrated integer kernel for the step time
+// - `matmul`:    repeats a small f32 matrix multiply sized to the step time
+//
+// Both are calibrated once per run and execute on the blocking pool, so they
+// compete with I/O threads for cores the way a real input pipeline would.
+// Independently of the model, `train.gpu_burn` keeps a GPU busy for the same
+// window (see gpu_burn.rs).
+//
+use anyhow::{Context, Result};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::dlio_compat::DlioConfig;
+use crate::gpu_burn::{GpuBurn, GpuBurnSettings};
+
+/// Matrix dimension of the matmul kernel (three N x N f32 matrices, 192 KiB)
+const MATMUL_N: usize = 128;
+
+/// How long calibration runs each kernel
+const CALIBRATION_TIME: Duration = Duration::from_millis(50);
+
+/// How a training step's compute time is spent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputeModel {
+    Sleep,
+    BusyLoop,
+    Matmul,
+}
+
+impl ComputeModel {
+    /// From `train.computation_model`
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        match config.train.as_ref().and_then(|t| t.computation_model.as_deref()).unwrap_or("sleep") {
+            "sleep" => Ok(Self::Sleep),
+            "busy_loop" | "busy-loop" => Ok(Self::BusyLoop),
+            "matmul" => Ok(Self::Matmul),
+            other => anyhow::bail!("Unknown train.computation_model '{}' (expected sleep, busy_loop or matmul)", other),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sleep => "sleep",
+            Self::BusyLoop => "busy_loop",
+            Self::Matmul => "matmul",
+        }
+    }
+}
+
+/// One unit of busy-loop work
+fn spin(iterations: u64) -> u64 {
+    let mut x = 0x9e37_79b9_7f4a_7c15u64;
+    for i in 0..iterations {
+        x = black_box(x.rotate_left(5) ^ i).wrapping_mul(0x0100_0000_01b3);
+    }
+    x
+}
+
+/// One N x N matrix multiply, c = a * b
+fn matmul(a: &[f32], b: &[f32], c: &mut [f32]) {
+    c.fill(0.0);
+    for i in 0..MATMUL_N {
+        for k in 0..MATMUL_N {
+            let aik = a[i * MATMUL_N + k];
+            let row = &b[k * MATMUL_N..(k + 1) * MATMUL_N];
+            for (cij, bkj) in c[i * MATMUL_N..(i + 1) * MATMUL_N].iter_mut().zip(row) {
+                *cij += aik * bkj;
+            }
+        }
+    }
+    black_box(&c);
+}
+
+/// Kernel repetitions per second, measured over CALIBRATION_TIME
+fn calibrate(mut kernel: impl FnMut(u64), unit: u64) -> f64 {
+    let start = Instant::now();
+    let mut done = 0u64;
+    while start.elapsed() < CALIBRATION_TIME {
+        kernel(unit);
+        done += unit;
+    }
+    done as f64 / start.elapsed().as_secs_f64()
+}
+
+/// Spends one step's compute time with the configured model
+pub struct ComputeSimulator {
+    model: ComputeModel,
+    step_time: Duration,
+    /// Kernel units (spin iterations or matmuls) per step
+    units_per_step: u64,
+    matrices: Option<Arc<(Vec<f32>, Vec<f32>)>>,
+    gpu: Option<Arc<GpuBurn>>,
+}
+
+impl ComputeSimulator {
+    /// Simulator for `train.computation_time` / `train.computation_model`, calibrated on this host
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        let seconds = config.train.as_ref().and_then(|t| t.computation_time).unwrap_or(0.0).max(0.0);
+        let step_time = Duration::try_from_secs_f64(seconds)
+            .with_context(|| format!("train.computation_time {} is not a usable duration", seconds))?;
+        let mut simulator = Self::new(ComputeModel::from_config(config)?, step_time);
+        if let Some(burn) = config.train.as_ref().and_then(|t| t.gpu_burn.as_ref()) {
+            simulator.gpu = Some(Arc::new(GpuBurn::new(GpuBurnSettings::from_config(burn)?)?));
+        }
+        Ok(simulator)
+    }
+
+    pub fn new(model: ComputeModel, step_time: Duration) -> Self {
+        let mut simulator = Self { model, step_time, units_per_step: 0, matrices: None, gpu: None };
+        if step_time.is_zero() {
+            return simulator;
+        }
+        match model {
+            ComputeModel::Sleep => {}
+            ComputeModel::BusyLoop => {
+                let rate = calibrate(|n| { black_box(spin(n)); }, 100_000);
+                simulator.units_per_step = (rate * step_time.as_secs_f64()).round().max(1.0) as u64;
+            }
+            ComputeModel::Matmul => {
+                let a: Vec<f32> = (0..MATMUL_N * MATMUL_N).map(|i| (i % 7) as f32 * 0.5).collect();
+                let b: Vec<f32> = (0..MATMUL_N * MATMUL_N).map(|i| (i % 5) as f32 * 0.25).collect();
+                let mut c = vec![0.0f32; MATMUL_N * MATMUL_N];
+                let rate = calibrate(|n| (0..n).for_each(|_| matmul(&a, &b, &mut c)), 1);
+                simulator.units_per_step = (rate * step_time.as_secs_f64()).round().max(1.0) as u64;
+                simulator.matrices = Some(Arc::new((a, b)));
+            }
+        }
+        info!(
+            "🧮 Compute model: {} for {:.1}ms/step ({} kernel units)",
+            model.name(), step_time.as_secs_f64() * 1000.0, simulator.units_per_step
+        );
+        simulator
+    }
+
+    pub fn model(&self) -> ComputeModel {
+        self.model
+    }
+
+    /// Simulate one training step's compute
+    pub async fn step(&self) -> Result<()> {
+        if self.step_time.is_zero() {
+            return Ok(());
+        }
+        match &self.gpu {
+            Some(gpu) => {
+                let (gpu, step_time) = (Arc::clone(gpu), self.step_time);
+                let burn = tokio::task::spawn_blocking(move || gpu.burn(step_time));
+                let (cpu, gpu) = tokio::join!(self.host_step(), burn);
+                cpu?;
+                gpu?
+            }
+            None => self.host_step().await,
+        }
+    }
+
+    /// The host-side model's share of a step
+    async fn host_step(&self) -> Result<()> {
+        let units = self.units_per_step;
+        match self.model {
+            ComputeModel::Sleep => tokio::time::sleep(self.step_time).await,
+            ComputeModel::BusyLoop => {
+                tokio::task::spawn_blocking(move || black_box(spin(units))).await?;
+            }
+            ComputeModel::Matmul => {
+                let matrices = Arc::clone(self.matrices.as_ref().expect("matmul matrices are calibrated"));
+                tokio::task::spawn_blocking(move || {
+                    let mut c = vec![0.0f32; MATMUL_N * MATMUL_N];
+                    for _ in 0..units {
+                        matmul(&matrices.0, &matrices.1, &mut c);
+                    }
+                })
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_compute_models_take_roughly_the_step_time() {
+        for model in [ComputeModel::Sleep, ComputeModel::BusyLoop, ComputeModel::Matmul] {
+            let simulator = ComputeSimulator::new(model, Duration::from_millis(20));
+            let start = Instant::now();
+            simulator.step().await.unwrap();
+            let elapsed = start.elapsed();
+            // Generous bounds: CI machines are noisy, but a miscalibration is off by orders of magnitude
+            assert!(elapsed >= Duration::from_millis(5), "{:?} took {:?}", model, elapsed);
+            assert!(elapsed < Duration::from_millis(500), "{:?} took {:?}", model, elapsed);
+        }
+        assert!(ComputeSimulator::new(ComputeModel::Matmul, Duration::ZERO).matrices.is_none());
+    }
+}
@@ -0,0 +1,203 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/compute_profiles.rs
+//
+// Mixed accelerator fleets (`train.compute_profiles`)
+//
+// Clusters often mix accelerator generations. A slower GPU spends longer per
+// step, so its rank asks storage for data less often and has a longer compute
+// window to hide I/O behind. Profiles give groups of ranks their own
+// computation time:
+//
+//   train:
+//     computation_time: 0.1        # ranks without a profile
+//     compute_profiles:
+//       - { name: A100, ranks: "0-3", computation_time: 0.1 }
+//       - { name: V100, ranks: "4-7", computation_time: 0.25, computation_time_stdev: 0.02 }
+//
+// Each rank trains with its profile's times and records the profile in its
+// results. `aggregate` reports ranks, accelerators, throughput and AU per
+// profile next to the global AU, so one group's effect on the fleet shows.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::dlio_compat::DlioConfig;
+
+/// Computation time of one rank
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComputeProfile {
+    pub name: String,
+    pub computation_time: f64,
+    pub computation_time_stdev: Option<f64>,
+}
+
+impl ComputeProfile {
+    /// The profile covering `rank`, checking every profile's rank list against `world_size`
+    pub fn for_rank(config: &DlioConfig, rank: u32, world_size: u32) -> Result<Option<Self>> {
+        let Some(profiles) = config.train.as_ref().and_then(|t| t.compute_profiles.as_ref()) else {
+            return Ok(None);
+        };
+        let mut covered = BTreeSet::new();
+        let mut found = None;
+        for profile in profiles {
+            if profile.computation_time < 0.0 {
+                anyhow::bail!("compute profile '{}' has a negative computation_time", profile.name);
+            }
+            let ranks = parse_ranks(&profile.ranks, world_size)
+                .with_context(|| format!("Invalid ranks for compute profile '{}'", profile.name))?;
+            for &r in &ranks {
+                if !covered.insert(r) {
+                    anyhow::bail!("Rank {} is in more than one compute profile (again in '{}')", r, profile.name);
+                }
+            }
+            if ranks.contains(&rank) {
+                found = Some(Self {
+                    name: profile.name.clone(),
+                    computation_time: profile.computation_time,
+                    computation_time_stdev: profile.computation_time_stdev,
+                });
+            }
+        }
+        Ok(found)
+    }
+
+    /// `config` with this profile's computation times
+    pub fn apply(&self, config: &DlioConfig) -> DlioConfig {
+        let mut config = config.clone();
+        // Profiles live under `train:`, so a config with one has a train section
+        if let Some(train) = config.train.as_mut() {
+            train.computation_time = Some(self.computation_time);
+            if self.computation_time_stdev.is_some() {
+                train.computation_time_stdev = self.computation_time_stdev;
+            }
+        }
+        config
+    }
+}
+
+/// Ranks of a rank list: "0-3", "4,6" or "8-11,16"; every rank must be below `world_size`
+pub fn parse_ranks(spec: &str, world_size: u32) -> Result<Vec<u32>> {
+    let mut ranks = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.trim(), last.trim()),
+            None => (part, part),
+        };
+        let (first, last): (u32, u32) = (
+            first.parse().with_context(|| format!("'{}' is not a rank or rank range", part))?,
+            last.parse().with_context(|| format!("'{}' is not a rank or rank range", part))?,
+        );
+        if first > last {
+            anyhow::bail!("Rank range '{}' is reversed", part);
+        }
+        // Checked before expanding, so a typo like "0-4000000000" fails instead of allocating
+        if last >= world_size {
+            anyhow::bail!("Rank {} in '{}' is beyond world size {}", last, part, world_size);
+        }
+        ranks.extend(first..=last);
+    }
+    if ranks.is_empty() {
+        anyhow::bail!("Empty rank list");
+    }
+    Ok(ranks)
+}
+
+/// One profile's ranks in aggregated results
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub computation_time: f64,
+    pub ranks: Vec<u64>,
+    pub accelerators: u64,
+    pub throughput_gib_s: f64,
+    /// AU of the profile's accelerators, each rank weighted by its accelerators
+    pub au: f64,
+}
+
+/// Per-profile summary of rank results that carry a `compute_profile`, in order of first appearance
+pub fn summarize(ranks: &[&Value]) -> Vec<ProfileSummary> {
+    let mut summaries: Vec<(ProfileSummary, Vec<(u64, f64)>)> = Vec::new();
+    for rank in ranks {
+        let Some(name) = rank["compute_profile"]["name"].as_str() else {
+            continue;
+        };
+        let index = match summaries.iter().position(|(s, _)| s.name == name) {
+            Some(index) => index,
+            None => {
+                summaries.push((
+                    ProfileSummary {
+                        name: name.to_string(),
+                        computation_time: rank["compute_profile"]["computation_time"].as_f64().unwrap_or(0.0),
+                        ranks: Vec::new(),
+                        accelerators: 0,
+                        throughput_gib_s: 0.0,
+                        au: 0.0,
+                    },
+                    Vec::new(),
+                ));
+                summaries.len() - 1
+            }
+        };
+        let (summary, rank_au) = &mut summaries[index];
+        let accelerators = rank["derived"]["accelerators"].as_u64().unwrap_or(1);
+        summary.ranks.extend(rank["rank"].as_u64());
+        summary.accelerators += accelerators;
+        summary.throughput_gib_s += rank["metrics"]["storage_throughput_gib_s"].as_f64().unwrap_or(0.0);
+        rank_au.push((accelerators, rank["metrics"]["au_fraction"].as_f64().unwrap_or(0.0).min(1.0)));
+    }
+    summaries
+        .into_iter()
+        .map(|(mut summary, rank_au)| {
+            summary.au = crate::accelerators::weighted_au(&rank_au);
+            summary
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_profiles_assign_ranks_and_summarize_au() {
+        let config = DlioConfig::from_yaml(
+            "dataset:\n  data_folder: /data\nreader:\n  batch_size: 4\ntrain:\n  computation_time: 0.1\n  computation_time_stdev: 0.01\n  compute_profiles:\n    - { name: A100, ranks: \"0-1\", computation_time: 0.1 }\n    - { name: V100, ranks: 2, computation_time: 0.25, computation_time_stdev: 0.02 }\n",
+        )
+        .unwrap();
+
+        let a100 = ComputeProfile::for_rank(&config, 1, 4).unwrap().unwrap();
+        assert_eq!((a100.name.as_str(), a100.computation_time), ("A100", 0.1));
+        let v100 = ComputeProfile::for_rank(&config, 2, 4).unwrap().unwrap();
+        let train = v100.apply(&config).train.unwrap();
+        assert_eq!((train.computation_time, train.computation_time_stdev), (Some(0.25), Some(0.02)));
+        // No stdev of its own: the train-wide one stays
+        assert_eq!(a100.apply(&config).train.unwrap().computation_time_stdev, Some(0.01));
+        assert!(ComputeProfile::for_rank(&config, 3, 4).unwrap().is_none());
+
+        assert_eq!(parse_ranks("8-11, 16", 32).unwrap(), vec![8, 9, 10, 11, 16]);
+        for bad in ["", "3-1", "a-b", "1-", "8-16", "0-4000000000"] {
+            assert!(parse_ranks(bad, 16).is_err(), "{}", bad);
+        }
+        let overlapping = DlioConfig::from_yaml(
+            "dataset:\n  data_folder: /data\nreader:\n  batch_size: 4\ntrain:\n  compute_profiles:\n    - { name: A, ranks: \"0-3\", computation_time: 0.1 }\n    - { name: B, ranks: \"3-4\", computation_time: 0.2 }\n",
+        )
+        .unwrap();
+        assert!(ComputeProfile::for_rank(&overlapping, 0, 8).is_err());
+
+        let rank = |rank: u64, profile: &str, au: f64, accelerators: u64| {
+            json!({"rank": rank, "compute_profile": {"name": profile, "computation_time": 0.1},
+                   "metrics": {"au_fraction": au, "storage_throughput_gib_s": 1.5}, "derived": {"accelerators": accelerators}})
+        };
+        let ranks = [rank(0, "A100", 0.6, 1), rank(1, "A100", 0.9, 2), rank(2, "V100", 1.0, 1), json!({"rank": 3})];
+        let summaries = summarize(&ranks.iter().collect::<Vec<_>>());
+        assert_eq!(summaries.len(), 2);
+        assert_eq!((summaries[0].name.as_str(), &summaries[0].ranks, summaries[0].accelerators), ("A100", &vec![0, 1], 3));
+        assert!((summaries[0].au - 0.8).abs() < 1e-12 && (summaries[0].throughput_gib_s - 3.0).abs() < 1e-12);
+        assert_eq!(summaries[1].au, 1.0);
+    }
+}
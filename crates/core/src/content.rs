@@ -0,0 +1,217 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/content.rs
+//
+// Seeded, self-identifying synthetic sample content
+//
+// Every generated sample starts with a 20-byte tag (magic, file index, sample
+// index) followed by bytes derived only from (seed, file index, sample index).
+// A reader can therefore recompute the expected content of any object it
+// reads, in any order, without a manifest: the file index comes from the
+// object's key and the sample index from its position, never from the tag.
+//
+use anyhow::Result;
+use std::borrow::Cow;
+
+use crate::dlio_compat::DlioConfig;
+use crate::rng::splitmix64;
+
+const TAG_MAGIC: &[u8; 4] = b"DLCV";
+const TAG_LEN: usize = 20;
+
+/// Formats whose files wrap the samples in a container seeded content does not fill
+const CONTAINER_FORMATS: &[&str] = &["hdf5", "tfrecord", "csv", "jpeg", "jpg", "png"];
+
+/// FNV-1a 64-bit content hash
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Fill `out` with the content of one sample
+pub fn fill_sample(seed: u64, file_index: u64, sample_index: u64, out: &mut [u8]) {
+    let tag_len = TAG_LEN.min(out.len());
+    let mut tag = [0u8; TAG_LEN];
+    tag[..4].copy_from_slice(TAG_MAGIC);
+    tag[4..12].copy_from_slice(&file_index.to_le_bytes());
+    tag[12..20].copy_from_slice(&sample_index.to_le_bytes());
+    out[..tag_len].copy_from_slice(&tag[..tag_len]);
+
+    // Independent stream per (seed, file, sample)
+    let mut state = seed;
+    for mut key in [file_index, sample_index] {
+        state ^= splitmix64(&mut key);
+        splitmix64(&mut state);
+    }
+    for chunk in out[tag_len..].chunks_mut(8) {
+        let word = splitmix64(&mut state).to_le_bytes();
+        chunk.copy_from_slice(&word[..chunk.len()]);
+    }
+}
+
+/// Contiguous content of `samples` samples of `sample_len` bytes for one file
+pub fn file_payload(seed: u64, file_index: u64, samples: usize, sample_len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; samples * sample_len];
+    if sample_len > 0 {
+        for (i, sample) in data.chunks_mut(sample_len).enumerate() {
+            fill_sample(seed, file_index, i as u64, sample);
+        }
+    }
+    data
+}
+
+/// Outcome of checking the samples of one object
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleCheck {
+    pub verified: u64,
+    pub mismatched: u64,
+    /// Description of the first mismatching sample
+    pub first_error: Option<String>,
+}
+
+/// File index of a generated object, from the `train_file_NNNNNN` name in its key
+pub fn file_index(uri: &str) -> Option<u64> {
+    let digits = uri.rsplit('/').next()?.strip_prefix("train_file_")?;
+    let end = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+/// Check `samples` contiguous samples of `sample_len` bytes against the seeded
+/// content of file `file_index`
+///
+/// Bytes after the last sample (e.g. DirectIO padding) are ignored.
+pub fn verify_samples(seed: u64, file_index: u64, payload: &[u8], samples: usize, sample_len: usize) -> SampleCheck {
+    let mut check = SampleCheck::default();
+    if samples == 0 || sample_len == 0 || payload.len() < samples * sample_len {
+        check.mismatched = samples.max(1) as u64;
+        check.first_error = Some(format!(
+            "payload of {} bytes cannot hold {} samples of {} bytes",
+            payload.len(), samples, sample_len
+        ));
+        return check;
+    }
+
+    let mut expected = vec![0u8; sample_len];
+    for (i, sample) in payload.chunks_exact(sample_len).take(samples).enumerate() {
+        fill_sample(seed, file_index, i as u64, &mut expected);
+        let error = if content_hash(sample) == content_hash(&expected) {
+            None
+        } else if sample_len >= TAG_LEN && &sample[..4] == TAG_MAGIC && sample[4..20] != expected[4..20] {
+            // Well-formed content, but of another sample: a misdirected or stale read
+            let file = u64::from_le_bytes(sample[4..12].try_into().unwrap());
+            let index = u64::from_le_bytes(sample[12..20].try_into().unwrap());
+            Some(format!("file {}, sample {}: holds file {}, sample {} instead", file_index, i, file, index))
+        } else {
+            Some(format!("file {}, sample {}: content hash mismatch", file_index, i))
+        };
+
+        match error {
+            None => check.verified += 1,
+            Some(e) => {
+                check.mismatched += 1;
+                check.first_error.get_or_insert(e);
+            }
+        }
+    }
+    check
+}
+
+/// Seed for verifiable content, when `validation.verify_content` is enabled
+///
+/// Defaults to `validation.content_seed`, then `reader.seed`, then 0.
+/// Multi-component datasets are not generated with verifiable content.
+pub fn content_seed(config: &DlioConfig) -> Option<u64> {
+    if crate::components::components(config).is_some() {
+        return None;
+    }
+    let validation = config.validation.as_ref().filter(|v| v.verify_content.unwrap_or(false))?;
+    Some(validation.content_seed.or(config.reader.seed).unwrap_or(0))
+}
+
+/// Reject verifiable content for formats whose files would not be valid with it
+///
+/// Seeded samples are written into NPZ's `data` array; other formats take them
+/// as raw records, which for container formats would not open as that format.
+pub fn check_format(config: &DlioConfig) -> Result<()> {
+    if content_seed(config).is_none() {
+        return Ok(());
+    }
+    let format = config.dataset.format.as_deref().unwrap_or("npz").to_lowercase();
+    if CONTAINER_FORMATS.contains(&format.as_str()) {
+        anyhow::bail!(
+            "validation.verify_content is not supported for {} datasets: seeded samples are written into npz \
+             files or as raw records, and raw records would not be valid {} files",
+            format, format
+        );
+    }
+    Ok(())
+}
+
+/// Sample region of one object as (payload, samples, sample length)
+///
+/// NPZ samples are the rows of the `data` array; other formats are generated
+/// as raw records of `record_length_bytes`.
+pub fn object_samples<'a>(config: &DlioConfig, data: &'a [u8]) -> Result<(Cow<'a, [u8]>, usize, usize)> {
+    if config.dataset.format.as_deref().unwrap_or("npz").eq_ignore_ascii_case("npz") {
+        let (header, payload) = real_dlio_formats::npz::read_array(data, "data.npy")?;
+        let samples = header.shape.first().copied().unwrap_or(1).max(1);
+        let sample_len = payload.len() / samples;
+        return Ok((Cow::Owned(payload), samples, sample_len));
+    }
+    Ok((
+        Cow::Borrowed(data),
+        config.dataset.num_samples_per_file.unwrap_or(1),
+        config.dataset.record_length_bytes.unwrap_or(1024),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_verifies_and_detects_corruption() {
+        let payload = file_payload(42, 7, 4, 64);
+        assert_eq!(payload, file_payload(42, 7, 4, 64));
+        assert_ne!(payload, file_payload(42, 8, 4, 64));
+
+        let check = verify_samples(42, 7, &payload, 4, 64);
+        assert_eq!((check.verified, check.mismatched), (4, 0));
+        assert_eq!(verify_samples(43, 7, &payload, 4, 64).mismatched, 4);
+
+        let mut corrupt = payload.clone();
+        corrupt[64 * 2 + 40] ^= 0x01;
+        let check = verify_samples(42, 7, &corrupt, 4, 64);
+        assert_eq!((check.verified, check.mismatched), (3, 1));
+        assert!(check.first_error.unwrap().contains("file 7, sample 2"));
+    }
+
+    #[test]
+    fn test_intact_content_of_another_file_is_a_mismatch() {
+        // Self-consistent, but not what file 7's key should hold
+        let check = verify_samples(42, 7, &file_payload(42, 8, 4, 64), 4, 64);
+        assert_eq!((check.verified, check.mismatched), (0, 4));
+        assert!(check.first_error.unwrap().contains("holds file 8, sample 0"));
+
+        assert_eq!(file_index("s3://b/d/0001/train_file_000042.npz"), Some(42));
+        assert_eq!(file_index("file:///d/train_file_000007_xxxx.tfrecord"), Some(7));
+        assert_eq!(file_index("s3://b/d/other_0001.npz"), None);
+    }
+
+    #[test]
+    fn test_container_formats_cannot_carry_seeded_content() {
+        let config = |format: &str, verify: bool| {
+            DlioConfig::from_yaml(&format!(
+                "dataset:\n  data_folder: /tmp/d\n  format: {}\nreader: {{}}\nvalidation:\n  verify_content: {}\n",
+                format, verify
+            ))
+            .unwrap()
+        };
+        assert!(check_format(&config("npz", true)).is_ok());
+        assert!(check_format(&config("npy", true)).is_ok());
+        assert!(check_format(&config("tfrecord", false)).is_ok());
+        for format in ["hdf5", "tfrecord", "csv", "jpeg", "png"] {
+            assert!(check_format(&config(format, true)).is_err(), "{}", format);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/control.rs
+//
+// Live control channel for long runs
+//
+// With `run --control-socket <path>`, the training loop listens on a Unix
+// domain socket for one-line commands, each answered with one JSON line:
+//
+//   pause                        stop consuming batches (loaders back up and go idle)
+//   resume                       continue after a pause
+//   set-target-throughput <MiB/s> pace consumption to at most this rate (0 = unlimited)
+//   dump-metrics-now             current metrics, as in the results JSON
+//   status                       paused flag, target and total paused time
+//   stop                         end the run after the current step (results are still written)
+//   failover-start / failover-end mark the failover window (see failover.rs)
+//
+// e.g. `echo pause | nc -U /tmp/dl-driver.sock` before a storage failover test.
+//
+// The same RunControl executes `schedule:` actions (see schedule.rs), and
+// every state change is recorded on a timeline reported with the results.
+// Injected latency is added to every storage read by DelayedBackend, so it
+// slows the data path (and backs up the loaders), not the training loop.
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::json;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::backends::DataBackend;
+
+/// Rate limiter state since the target was last set
+struct Pacer {
+    target_bytes_per_sec: f64,
+    since: Instant,
+    bytes: u64,
+}
+
+/// One annotated event during a run
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// Seconds since the training phase started
+    pub offset_s: f64,
+    pub action: String,
+    pub detail: Option<String>,
+    /// "control" (socket command) or "schedule"
+    pub source: String,
+}
+
+/// Pause, pacing, latency and stop state shared by the control socket, the schedule and the training loop
+pub struct RunControl {
+    started: Instant,
+    paused: watch::Sender<bool>,
+    pacer: Mutex<Pacer>,
+    paused_total: Mutex<Duration>,
+    injected_latency: Mutex<Duration>,
+    stop: std::sync::atomic::AtomicBool,
+    timeline: Mutex<Vec<TimelineEvent>>,
+}
+
+impl Default for RunControl {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            paused: watch::Sender::new(false),
+            pacer: Mutex::new(Pacer { target_bytes_per_sec: 0.0, since: Instant::now(), bytes: 0 }),
+            paused_total: Mutex::new(Duration::ZERO),
+            injected_latency: Mutex::new(Duration::ZERO),
+            stop: std::sync::atomic::AtomicBool::new(false),
+            timeline: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl RunControl {
+    /// Time since the training phase started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Annotate the results timeline
+    pub fn record(&self, source: &str, action: &str, detail: Option<String>) {
+        self.timeline.lock().unwrap().push(TimelineEvent {
+            offset_s: self.started.elapsed().as_secs_f64(),
+            action: action.to_string(),
+            detail,
+            source: source.to_string(),
+        });
+    }
+
+    pub fn timeline(&self) -> Vec<TimelineEvent> {
+        self.timeline.lock().unwrap().clone()
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Cap consumption at `bytes_per_sec` (0 removes the cap)
+    pub fn set_target_throughput(&self, bytes_per_sec: f64) {
+        let mut pacer = self.pacer.lock().unwrap();
+        *pacer = Pacer { target_bytes_per_sec: bytes_per_sec.max(0.0), since: Instant::now(), bytes: 0 };
+    }
+
+    pub fn target_throughput(&self) -> f64 {
+        self.pacer.lock().unwrap().target_bytes_per_sec
+    }
+
+    /// Extra delay on every storage read, emulating a slower data path
+    pub fn set_injected_latency(&self, latency: Duration) {
+        *self.injected_latency.lock().unwrap() = latency;
+    }
+
+    pub fn injected_latency(&self) -> Duration {
+        *self.injected_latency.lock().unwrap()
+    }
+
+    /// Ask the training loop to end the run after the current step
+    pub fn request_stop(&self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Release);
+    }
+
+    pub fn stop_requested(&self) -> bool {
+        self.stop.load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    /// Total time the run spent paused
+    pub fn paused_time(&self) -> Duration {
+        *self.paused_total.lock().unwrap()
+    }
+
+    /// Called before each batch: waits out a pause, then delays to honor the throughput target
+    pub async fn checkpoint(&self, batch_bytes: usize) {
+        if self.is_paused() {
+            let start = Instant::now();
+            info!("⏸️  Run paused");
+            let mut rx = self.paused.subscribe();
+            let _ = rx.wait_for(|paused| !paused).await;
+            *self.paused_total.lock().unwrap() += start.elapsed();
+            info!("▶️  Run resumed after {:.1}s", start.elapsed().as_secs_f64());
+            // Pacing restarts after a pause rather than bursting to catch up
+            let target = self.target_throughput();
+            self.set_target_throughput(target);
+        }
+
+        let delay = {
+            let mut pacer = self.pacer.lock().unwrap();
+            if pacer.target_bytes_per_sec <= 0.0 {
+                return;
+            }
+            pacer.bytes += batch_bytes as u64;
+            let due = Duration::from_secs_f64(pacer.bytes as f64 / pacer.target_bytes_per_sec);
+            due.saturating_sub(pacer.since.elapsed())
+        };
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn status(&self) -> serde_json::Value {
+        json!({
+            "paused": self.is_paused(),
+            "target_throughput_mib_s": self.target_throughput() / crate::units::MIB,
+            "paused_seconds": self.paused_time().as_secs_f64(),
+            "injected_latency_ms": self.injected_latency().as_secs_f64() * 1000.0,
+            "stop_requested": self.stop_requested(),
+        })
+    }
+}
+
+/// DataBackend adding the run's injected latency to every read
+pub struct DelayedBackend {
+    inner: Arc<dyn DataBackend>,
+    control: Arc<RunControl>,
+}
+
+impl DelayedBackend {
+    pub fn new(inner: Arc<dyn DataBackend>, control: Arc<RunControl>) -> Self {
+        Self { inner, control }
+    }
+
+    async fn delay(&self) {
+        let latency = self.control.injected_latency();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+}
+
+#[async_trait]
+impl DataBackend for DelayedBackend {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        self.inner.list().await
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        self.delay().await;
+        self.inner.get(uri).await
+    }
+
+    async fn head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        self.delay().await;
+        self.inner.head(uri, len).await
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.inner.put(uri, data).await
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.inner.report()
+    }
+}
+
+/// Snapshot of the run's metrics for `dump-metrics-now`
+pub type MetricsSnapshot = Arc<dyn Fn() -> serde_json::Value + Send + Sync>;
+
+/// Handle one command line; returns the JSON reply
+pub fn handle_command(control: &RunControl, snapshot: &MetricsSnapshot, line: &str) -> serde_json::Value {
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("pause"), None) => {
+            control.set_paused(true);
+            control.record("control", "pause", None);
+            json!({"ok": true, "status": control.status()})
+        }
+        (Some("resume"), None) => {
+            control.set_paused(false);
+            control.record("control", "resume", None);
+            json!({"ok": true, "status": control.status()})
+        }
+        (Some("set-target-throughput"), Some(value)) => match value.parse::<f64>() {
+            Ok(mib_s) if mib_s >= 0.0 => {
+                control.set_target_throughput(mib_s * crate::units::MIB);
+                control.record("control", "set_target_throughput", Some(format!("{} MiB/s", mib_s)));
+                info!("🎚️  Target throughput set to {}", if mib_s > 0.0 { format!("{} MiB/s", mib_s) } else { "unlimited".to_string() });
+                json!({"ok": true, "status": control.status()})
+            }
+            _ => json!({"ok": false, "error": format!("invalid throughput '{}' (MiB/s, 0 = unlimited)", value)}),
+        },
+        (Some("failover-start"), None) => {
+            control.record("control", crate::failover::FAILOVER_START, None);
+            info!("🧯 Failover window started");
+            json!({"ok": true, "status": control.status()})
+        }
+        (Some("failover-end"), None) => {
+            control.record("control", crate::failover::FAILOVER_END, None);
+            info!("🧯 Failover window ended");
+            json!({"ok": true, "status": control.status()})
+        }
+        (Some("stop"), None) => {
+            control.request_stop();
+            control.record("control", "stop", None);
+            info!("🛑 Stop requested over the control socket");
+            json!({"ok": true, "status": control.status()})
+        }
+        (Some("dump-metrics-now"), None) => json!({"ok": true, "metrics": snapshot()}),
+        (Some("status"), None) => json!({"ok": true, "status": control.status()}),
+        _ => json!({
+            "ok": false,
+            "error": format!("unknown command '{}'", line.trim()),
+            "commands": [
+                "pause", "resume", "set-target-throughput <MiB/s>", "failover-start", "failover-end",
+                "dump-metrics-now", "status", "stop",
+            ],
+        }),
+    }
+}
+
+/// Unix socket server for RunControl; removes the socket file when dropped
+pub struct ControlServer {
+    path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl ControlServer {
+    pub fn bind(path: &Path, control: Arc<RunControl>, snapshot: MetricsSnapshot) -> Result<Self> {
+        // A socket left behind by a crashed run would make bind fail; anything else is not ours to remove
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                anyhow::bail!("Control socket path {:?} exists and is not a socket", path);
+            }
+            std::fs::remove_file(path).with_context(|| format!("Failed to remove stale control socket {:?}", path))?;
+        }
+        let listener = UnixListener::bind(path).with_context(|| format!("Failed to bind control socket {:?}", path))?;
+        info!("🎛️  Control socket listening on {:?}", path);
+
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(serve_client(stream, Arc::clone(&control), Arc::clone(&snapshot)));
+                    }
+                    Err(e) => {
+                        warn!("Control socket accept failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self { path: path.to_path_buf(), task })
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+async fn serve_client(stream: UnixStream, control: Arc<RunControl>, snapshot: MetricsSnapshot) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut reply = serde_json::to_vec(&handle_command(&control, &snapshot, &line)).unwrap_or_default();
+        reply.push(b'\n');
+        if writer.write_all(&reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_control_commands_pause_and_pace() {
+        let control = Arc::new(RunControl::default());
+        let snapshot: MetricsSnapshot = Arc::new(|| json!({"bytes_read": 42}));
+
+        assert_eq!(handle_command(&control, &snapshot, "dump-metrics-now")["metrics"]["bytes_read"], 42);
+        assert_eq!(handle_command(&control, &snapshot, "set-target-throughput fast")["ok"], false);
+        assert_eq!(handle_command(&control, &snapshot, "reboot")["ok"], false);
+
+        // 1 MiB/s target: a 100 KiB batch is due ~0.1s after pacing starts
+        handle_command(&control, &snapshot, "set-target-throughput 1");
+        let start = Instant::now();
+        control.checkpoint(100 * 1024).await;
+        assert!(start.elapsed() >= Duration::from_millis(80));
+        handle_command(&control, &snapshot, "set-target-throughput 0");
+
+        handle_command(&control, &snapshot, "pause");
+        let waiter = {
+            let control = Arc::clone(&control);
+            tokio::spawn(async move { control.checkpoint(1).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(handle_command(&control, &snapshot, "resume")["status"]["paused"], false);
+        waiter.await.unwrap();
+        assert!(control.paused_time() >= Duration::from_millis(40));
+
+        let actions: Vec<String> = control.timeline().into_iter().map(|e| e.action).collect();
+        assert_eq!(actions, ["set_target_throughput", "set_target_throughput", "pause", "resume"]);
+
+        assert!(!control.stop_requested());
+        assert_eq!(handle_command(&control, &snapshot, "stop")["status"]["stop_requested"], true);
+        assert!(control.stop_requested());
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_stale_sockets_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("control.sock");
+        let control = Arc::new(RunControl::default());
+        let snapshot: MetricsSnapshot = Arc::new(|| json!({}));
+
+        std::fs::write(&path, b"not a socket").unwrap();
+        assert!(ControlServer::bind(&path, Arc::clone(&control), Arc::clone(&snapshot)).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+
+        std::fs::remove_file(&path).unwrap();
+        // A crashed run leaves its socket file behind
+        drop(UnixListener::bind(&path).unwrap());
+        let server = ControlServer::bind(&path, control, snapshot).unwrap();
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_injected_latency_delays_reads() {
+        let config = crate::dlio_compat::DlioConfig::from_yaml(
+            "dataset:\n  data_folder: mem://train\n  num_files_train: 1\n  record_length_bytes: 8\nreader: {}\n",
+        )
+        .unwrap();
+        let inner: Arc<dyn DataBackend> = Arc::new(crate::backends::mem::MemBackend::new(&config).unwrap());
+        let uri = inner.list().await.unwrap().remove(0);
+        let control = Arc::new(RunControl::default());
+        let backend = DelayedBackend::new(inner, Arc::clone(&control));
+
+        control.set_injected_latency(Duration::from_millis(50));
+        let start = Instant::now();
+        backend.get(&uri).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        // The training loop's checkpoint no longer sleeps for it
+        let start = Instant::now();
+        control.checkpoint(8).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
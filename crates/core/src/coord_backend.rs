@@ -0,0 +1,337 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/coord_backend.rs
+//
+// Pluggable rank coordination (`--coord`)
+//
+// A multi-rank run needs the same few things from its coordination group
+// whatever carries it: join, pass named barriers, agree on values, start
+// together (at a broadcast gate with --start-in) and, once every rank is
+// done, share results so each rank sees the aggregate with one global start
+// and end time. RankCoordinatorBackend is that interface; `--coord` picks the
+// implementation:
+//
+//   shm://<id>           ranks on one host share a memory segment (the
+//                        default, with the id derived from config name and
+//                        world size, or --coord-id)
+//   tcp://<host>:<port>  ranks on any number of hosts; rank 0 serves
+//                        barriers on <port> and the others connect to
+//                        <host>:<port>, like MASTER_ADDR/MASTER_PORT in PyTorch
+//
+// `--coord-endpoint host:port` is TCP against a server run elsewhere (by
+// `launch --hosts`). Over TCP each rank measures its clock offset from the
+// server and moves its timestamps onto the server's clock before sharing
+// them, so the aggregate's times are comparable across hosts.
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::coordination::{sleep_until_unix_ns, AggregatedResults, RankCoordinator, RankResults};
+use crate::net_coordination::{ClockOffset, CoordinationServer, NetworkCoordinator, FINISH_BARRIER};
+
+/// How long a rank waits at a TCP barrier for the others
+const BARRIER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Ranks finish at different times, so the finish barrier waits longer
+const FINISH_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What a run's ranks need from their coordination group
+///
+/// Not `Send`: the shared-memory mapping (`shared_memory::Shmem`) is neither Send nor Sync.
+#[async_trait(?Send)]
+pub trait RankCoordinatorBackend {
+    /// The group as a `--coord` URI, for logs and reports
+    fn describe(&self) -> String;
+
+    /// Join the group; returns once every rank has joined
+    async fn register(&self) -> Result<()>;
+
+    /// Block until every rank has entered `name`
+    async fn barrier(&self, name: &str) -> Result<()>;
+
+    /// Barrier for phases that take long on some ranks (e.g. data generation)
+    async fn barrier_within(&self, name: &str, timeout: Duration) -> Result<()>;
+
+    /// Barrier at which every rank must pass the same `value`; fails on every rank when any differs
+    async fn agree(&self, name: &str, value: &str) -> Result<()>;
+
+    /// Pass the execution start barrier, then wait until `start_in` after its release
+    async fn start(&self, start_in: Option<Duration>) -> Result<()>;
+
+    /// This rank's clock offset from the group's reference clock (zero for ranks sharing a host)
+    fn clock_offset(&self) -> ClockOffset;
+
+    /// Share this rank's results and wait for every rank to finish; every rank gets the aggregate
+    async fn finish(&self, results: RankResults) -> Result<AggregatedResults>;
+
+    /// Make the other ranks fail instead of waiting for this one (where the backend can)
+    fn abort(&self, reason: &str);
+}
+
+/// Where a run's ranks coordinate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoordSpec {
+    /// Shared memory on one host, keyed by a coordination id
+    SharedMemory(String),
+    /// TCP barriers at host:port; with `serve`, rank 0 runs the server
+    Tcp { endpoint: String, serve: bool },
+}
+
+impl CoordSpec {
+    /// Parse a `--coord` URI: shm://<id> or tcp://<host>:<port>
+    pub fn parse(uri: &str) -> Result<Self> {
+        if let Some(id) = uri.strip_prefix("shm://") {
+            if id.is_empty() {
+                anyhow::bail!("shm:// coordination needs a group id, e.g. shm://my_run");
+            }
+            return Ok(Self::SharedMemory(id.to_string()));
+        }
+        if let Some(endpoint) = uri.strip_prefix("tcp://") {
+            let endpoint = endpoint.trim_end_matches('/');
+            return match endpoint.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                    Ok(Self::Tcp { endpoint: endpoint.to_string(), serve: true })
+                }
+                _ => anyhow::bail!("tcp:// coordination needs host:port, got '{}'", uri),
+            };
+        }
+        anyhow::bail!("Unknown coordination backend '{}' (expected shm://<id> or tcp://<host>:<port>)", uri)
+    }
+
+    /// Join the group as `rank` of `world_size` (call `register` next)
+    pub async fn connect(&self, rank: u32, world_size: u32) -> Result<Box<dyn RankCoordinatorBackend>> {
+        match self {
+            Self::SharedMemory(id) => Ok(Box::new(
+                RankCoordinator::new(rank, world_size, id).context("Failed to create rank coordinator")?,
+            )),
+            Self::Tcp { endpoint, serve } => {
+                Ok(Box::new(TcpCoordinator::new(endpoint, *serve && rank == 0, rank, world_size).await?))
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl RankCoordinatorBackend for RankCoordinator {
+    fn describe(&self) -> String {
+        format!("shm://{}", self.coordination_id())
+    }
+
+    async fn register(&self) -> Result<()> {
+        self.register_and_wait().await
+    }
+
+    async fn barrier(&self, name: &str) -> Result<()> {
+        RankCoordinator::barrier(self, name).await
+    }
+
+    async fn barrier_within(&self, name: &str, timeout: Duration) -> Result<()> {
+        RankCoordinator::barrier_within(self, name, timeout).await
+    }
+
+    async fn agree(&self, name: &str, value: &str) -> Result<()> {
+        RankCoordinator::agree(self, name, value).await
+    }
+
+    async fn start(&self, start_in: Option<Duration>) -> Result<()> {
+        RankCoordinator::barrier(self, "execution_start").await?;
+        // Rank 0 picks the start gate once everyone is here; all ranks sleep until it
+        if let Some(delay) = start_in {
+            let gate = if self.rank() == 0 {
+                self.broadcast_start_gate(delay).context("Failed to broadcast start gate")?
+            } else {
+                self.wait_for_start_gate().await.context("Failed to receive start gate")?
+            };
+            info!("⏱️  Rank {}: Starting in {:.3}s at the broadcast gate", self.rank(), delay.as_secs_f64());
+            sleep_until_unix_ns(gate).await;
+        }
+        if self.rank() == 0 {
+            self.mark_global_start().context("Failed to mark global start time")?;
+        }
+        Ok(())
+    }
+
+    fn clock_offset(&self) -> ClockOffset {
+        ClockOffset::default()
+    }
+
+    async fn finish(&self, results: RankResults) -> Result<AggregatedResults> {
+        // Stored before this rank counts as finished, so every result is in once all have
+        self.store_results(&results).context("Failed to store results in shared memory")?;
+        self.mark_finished_and_wait().await?;
+        let aggregated = self.get_aggregated_results()?;
+        self.cleanup().context("Failed to cleanup coordination resources")?;
+        Ok(aggregated)
+    }
+
+    fn abort(&self, reason: &str) {
+        RankCoordinator::abort(self, reason)
+    }
+}
+
+/// TCP coordination: a client of a CoordinationServer, which rank 0 may run
+pub struct TcpCoordinator {
+    client: NetworkCoordinator,
+    endpoint: String,
+    server: Mutex<Option<JoinHandle<Result<()>>>>,
+    clock: Mutex<ClockOffset>,
+    /// Release of the execution start barrier (plus any start gate), on the server's clock
+    global_start_ns: Mutex<Option<u64>>,
+}
+
+impl TcpCoordinator {
+    /// Connect to the server at `endpoint`, first starting it there when `serve`
+    ///
+    /// The server binds the same address the other ranks are given, not every interface.
+    pub async fn new(endpoint: &str, serve: bool, rank: u32, world_size: u32) -> Result<Self> {
+        let server = if serve {
+            Some(CoordinationServer::bind(endpoint, world_size).await?.spawn())
+        } else {
+            None
+        };
+        Ok(Self {
+            client: NetworkCoordinator::new(endpoint, rank, world_size),
+            endpoint: endpoint.to_string(),
+            server: Mutex::new(server),
+            clock: Mutex::new(ClockOffset::default()),
+            global_start_ns: Mutex::new(None),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl RankCoordinatorBackend for TcpCoordinator {
+    fn describe(&self) -> String {
+        format!("tcp://{}", self.endpoint)
+    }
+
+    async fn register(&self) -> Result<()> {
+        self.client.barrier("register", BARRIER_TIMEOUT).await.map(|_| ())
+    }
+
+    async fn barrier(&self, name: &str) -> Result<()> {
+        self.client.barrier(name, BARRIER_TIMEOUT).await.map(|_| ())
+    }
+
+    async fn barrier_within(&self, name: &str, timeout: Duration) -> Result<()> {
+        self.client.barrier(name, timeout).await.map(|_| ())
+    }
+
+    async fn agree(&self, name: &str, value: &str) -> Result<()> {
+        self.client.agree(name, value, BARRIER_TIMEOUT).await.map(|_| ())
+    }
+
+    async fn start(&self, start_in: Option<Duration>) -> Result<()> {
+        let clock = self.client.estimate_clock_offset(8).await.context("Failed to estimate clock offset from coordinator")?;
+        *self.clock.lock().unwrap() = clock;
+        let release_ns = self.client.barrier("execution_start", BARRIER_TIMEOUT).await?;
+        let mut start_ns = release_ns;
+        // The release time is on the server's clock; shift the gate onto ours
+        if let Some(delay) = start_in {
+            start_ns = release_ns + delay.as_nanos() as u64;
+            info!("⏱️  Rank {}: Starting in {:.3}s at the broadcast gate", self.client.rank(), delay.as_secs_f64());
+            sleep_until_unix_ns(start_ns.saturating_add_signed(-clock.offset_ns)).await;
+        }
+        *self.global_start_ns.lock().unwrap() = Some(start_ns);
+        Ok(())
+    }
+
+    fn clock_offset(&self) -> ClockOffset {
+        *self.clock.lock().unwrap()
+    }
+
+    async fn finish(&self, mut results: RankResults) -> Result<AggregatedResults> {
+        let offset = self.clock_offset().offset_ns;
+        results.start_time_ns = results.start_time_ns.saturating_add_signed(offset);
+        results.end_time_ns = results.end_time_ns.saturating_add_signed(offset);
+
+        let (end_ns, shared) = self.client.gather(FINISH_BARRIER, &serde_json::to_string(&results)?, FINISH_TIMEOUT).await?;
+        let mut ranks = Vec::new();
+        for (rank, value) in shared.iter().enumerate() {
+            match value.as_deref().map(serde_json::from_str::<RankResults>) {
+                Some(Ok(results)) => ranks.push((rank as u32, results)),
+                _ => warn!("⚠️  Rank {} shared no readable results", rank),
+            }
+        }
+
+        // The server stops once the finish barrier releases
+        let server = self.server.lock().unwrap().take();
+        if let Some(server) = server {
+            server.await.context("Coordination server task failed")??;
+        }
+
+        let mut aggregated = AggregatedResults::from_ranks(self.client.world_size(), &ranks);
+        aggregated.global_start_ns = *self.global_start_ns.lock().unwrap();
+        aggregated.global_end_ns = Some(end_ns);
+        Ok(aggregated)
+    }
+
+    fn abort(&self, reason: &str) {
+        warn!("🚨 Rank {}: Giving up on the coordination group: {}", self.client.rank(), reason);
+        // The server fails the other ranks' barriers; without it they wait out their timeouts
+        if let Err(e) = self.client.abort(reason) {
+            warn!("⚠️  Rank {}: Could not tell the coordination server: {:#}", self.client.rank(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tcp_backend_aggregates_results_across_ranks() {
+        assert_eq!(CoordSpec::parse("shm://run_1").unwrap(), CoordSpec::SharedMemory("run_1".to_string()));
+        assert_eq!(
+            CoordSpec::parse("tcp://node0:29500").unwrap(),
+            CoordSpec::Tcp { endpoint: "node0:29500".to_string(), serve: true }
+        );
+        for bad in ["shm://", "tcp://node0", "tcp://:29500", "tcp://node0:port", "redis://node0:6379"] {
+            assert!(CoordSpec::parse(bad).is_err(), "{}", bad);
+        }
+
+        // Rank 0 serves on a free port; the others connect to it as if from other hosts
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let spec = CoordSpec::parse(&format!("tcp://127.0.0.1:{}", port)).unwrap();
+        let mut ranks = Vec::new();
+        for rank in 0..3u32 {
+            let coord = spec.connect(rank, 3).await.unwrap();
+            ranks.push(async move {
+                coord.register().await?;
+                coord.agree("data_folder", "file:///data").await?;
+                coord.start(Some(Duration::from_millis(20))).await?;
+                let results = RankResults {
+                    files_processed: 10,
+                    bytes_read: 1000,
+                    throughput_gib_s: 1.0,
+                    accelerators: rank + 1,
+                    au_fraction: if rank == 0 { 0.0 } else { 0.9 },
+                    start_time_ns: 1_000_000_000,
+                    end_time_ns: 1_000_000_000 + (rank as u64 + 1) * 500_000_000,
+                    ..Default::default()
+                };
+                anyhow::Ok((coord.describe(), coord.finish(results).await?))
+            });
+        }
+
+        let aggregates: Vec<_> = futures::future::join_all(ranks).await.into_iter().map(Result::unwrap).collect();
+        for (describe, aggregated) in &aggregates {
+            assert_eq!(describe, &format!("tcp://127.0.0.1:{}", port));
+            assert_eq!((aggregated.total_ranks, aggregated.rank_details.len()), (3, 3));
+            assert_eq!((aggregated.total_files_processed, aggregated.total_bytes_read), (30, 3000));
+            // 1 + 2 + 3 accelerators, AU weighted by them: (2 + 3) × 0.9 / 6
+            assert_eq!(aggregated.total_accelerators, 6);
+            assert!((aggregated.global_au_fraction - 0.75).abs() < 1e-12);
+            // Same host, so the clock correction is within probe noise of the 1.0s-2.5s span
+            assert!((aggregated.global_runtime_seconds - 1.5).abs() < 0.01);
+            let (start, end) = (aggregated.global_start_ns.unwrap(), aggregated.global_end_ns.unwrap());
+            assert!(end > start);
+            assert_eq!((start, end), (aggregates[0].1.global_start_ns.unwrap(), aggregates[0].1.global_end_ns.unwrap()));
+        }
+    }
+}
@@ -4,7 +4,10 @@
 //! workload execution without external dependencies like MPI or network services.
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use shared_memory::{Shmem, ShmemConf};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicU32, AtomicU64, AtomicBool, Ordering};
 // Removed unused Arc and Barrier imports
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -19,9 +22,12 @@ struct CoordinationState {
     /// Number of ranks that have registered
     registered_ranks: AtomicU32,
     
-    /// Number of ranks ready to start execution
+    /// Number of ranks waiting at the current barrier
     ready_ranks: AtomicU32,
     
+    /// Barriers released so far; waiters leave when it moves past the one they entered
+    barrier_generation: AtomicU64,
+    
     /// Number of ranks that have finished execution
     finished_ranks: AtomicU32,
     
@@ -31,20 +37,31 @@ struct CoordinationState {
     /// Global end timestamp (nanoseconds since UNIX_EPOCH)
     global_end_time: AtomicU64,
     
+    /// Synchronized start gate broadcast by rank 0 (nanoseconds since UNIX_EPOCH)
+    start_gate_time: AtomicU64,
+    
     /// Flag indicating if coordination is active
     active: AtomicBool,
     
     /// Emergency abort flag
     abort: AtomicBool,
+}
+
+/// Per-rank shared state; `world_size` of these follow the CoordinationState
+/// header in the segment, so any world size fits
+#[repr(C)]
+struct RankSlot {
+    /// Heartbeat timestamp (seconds since UNIX_EPOCH)
+    heartbeat: AtomicU64,
     
-    /// Per-rank heartbeat timestamps (up to 64 ranks supported)
-    rank_heartbeats: [AtomicU64; 64],
+    /// Status flag (0=not_started, 1=ready, 2=running, 3=finished, 4=failed)
+    status: AtomicU32,
     
-    /// Per-rank status flags (0=not_started, 1=ready, 2=running, 3=finished, 4=failed)
-    rank_status: [AtomicU32; 64],
+    /// Hash of the value passed to `agree`
+    value: AtomicU64,
     
-    /// Per-rank metrics results in shared memory (avoid temp files)
-    rank_results: [RankResultsShared; 64],
+    /// Metrics results in shared memory (avoid temp files)
+    results: RankResultsShared,
 }
 
 /// Shared memory results structure for each rank (avoid temp files)
@@ -65,6 +82,9 @@ struct RankResultsShared {
     /// AU fraction for this rank (stored as u64, divide by 1e15 for actual value)
     au_fraction_scaled: AtomicU64,
     
+    /// Accelerators this rank stands for (gpus_per_rank)
+    accelerators: AtomicU32,
+    
     /// Rank execution start time
     start_time_ns: AtomicU64,
     
@@ -83,6 +103,7 @@ impl RankResultsShared {
             throughput_bps: AtomicU64::new(0),
             wall_clock_time_ns: AtomicU64::new(0),
             au_fraction_scaled: AtomicU64::new(0),
+            accelerators: AtomicU32::new(1),
             start_time_ns: AtomicU64::new(0),
             end_time_ns: AtomicU64::new(0),
             results_valid: AtomicBool::new(false),
@@ -92,22 +113,38 @@ impl RankResultsShared {
 
 impl CoordinationState {
     fn new(world_size: u32) -> Self {
-        const INIT_ATOMIC_U64: AtomicU64 = AtomicU64::new(0);
-        const INIT_ATOMIC_U32: AtomicU32 = AtomicU32::new(0);
-        const INIT_RANK_RESULTS: RankResultsShared = RankResultsShared::new();
-        
         Self {
             world_size: AtomicU32::new(world_size),
             registered_ranks: AtomicU32::new(0),
             ready_ranks: AtomicU32::new(0),
+            barrier_generation: AtomicU64::new(0),
             finished_ranks: AtomicU32::new(0),
             global_start_time: AtomicU64::new(0),
             global_end_time: AtomicU64::new(0),
+            start_gate_time: AtomicU64::new(0),
             active: AtomicBool::new(true),
             abort: AtomicBool::new(false),
-            rank_heartbeats: [INIT_ATOMIC_U64; 64],
-            rank_status: [INIT_ATOMIC_U32; 64],
-            rank_results: [INIT_RANK_RESULTS; 64],
+        }
+    }
+    
+    /// Segment size for `world_size` ranks: the header, then one slot per rank
+    fn segment_size(world_size: u32) -> usize {
+        Self::slots_offset() + world_size as usize * std::mem::size_of::<RankSlot>()
+    }
+    
+    /// Offset of the first rank slot, aligned for RankSlot
+    fn slots_offset() -> usize {
+        std::mem::size_of::<Self>().next_multiple_of(std::mem::align_of::<RankSlot>())
+    }
+}
+
+impl RankSlot {
+    const fn new() -> Self {
+        Self {
+            heartbeat: AtomicU64::new(0),
+            status: AtomicU32::new(0),
+            value: AtomicU64::new(0),
+            results: RankResultsShared::new(),
         }
     }
 }
@@ -118,6 +155,7 @@ pub struct RankCoordinator {
     world_size: u32,
     _shared_mem: Shmem,  // Must keep alive to maintain shared memory mapping
     state: &'static CoordinationState,
+    slots: &'static [RankSlot],
     coordination_id: String,
 }
 
@@ -128,12 +166,8 @@ impl RankCoordinator {
             return Err(anyhow::anyhow!("Rank {} >= world_size {}", rank, world_size));
         }
         
-        if world_size > 64 {
-            return Err(anyhow::anyhow!("World size {} > 64 (maximum supported)", world_size));
-        }
-        
         let shmem_name = format!("dl_driver_coord_{}", coordination_id);
-        let shmem_size = std::mem::size_of::<CoordinationState>();
+        let shmem_size = CoordinationState::segment_size(world_size);
         
         info!("🔗 Rank {}: Joining coordination group '{}' (world_size={})", 
               rank, coordination_id, world_size);
@@ -164,28 +198,35 @@ impl RankCoordinator {
         let state_ptr = shared_mem.as_ptr() as *mut CoordinationState;
         let state = unsafe { &*state_ptr };
         
+        let slots_ptr = unsafe { shared_mem.as_ptr().add(CoordinationState::slots_offset()) } as *mut RankSlot;
+        
         // Initialize state if we're the creator
         if is_creator {
             unsafe {
                 std::ptr::write(state_ptr, CoordinationState::new(world_size));
+                for i in 0..world_size as usize {
+                    std::ptr::write(slots_ptr.add(i), RankSlot::new());
+                }
             }
             debug!("Rank {}: Initialized coordination state", rank);
         }
         
-        // Validate world size matches
+        // Validate world size matches (before touching the slots: a group of another size has a different layout)
         let existing_world_size = state.world_size.load(Ordering::Acquire);
-        if existing_world_size != world_size {
+        if existing_world_size != world_size || shared_mem.len() < shmem_size {
             return Err(anyhow::anyhow!(
                 "World size mismatch: expected {}, found {}", 
                 world_size, existing_world_size
             ));
         }
+        let slots = unsafe { std::slice::from_raw_parts(slots_ptr as *const RankSlot, world_size as usize) };
         
         Ok(Self {
             rank,
             world_size,
             _shared_mem: shared_mem,  // Keep shared memory region alive
             state,
+            slots,
             coordination_id: coordination_id.to_string(),
         })
     }
@@ -195,7 +236,7 @@ impl RankCoordinator {
         info!("📝 Rank {}: Registering with coordination group '{}'", self.rank, self.coordination_id);
         
         // Set our status to ready
-        self.state.rank_status[self.rank as usize].store(1, Ordering::Release);
+        self.slots[self.rank as usize].status.store(1, Ordering::Release);
         self.update_heartbeat();
         
         // Increment registered count
@@ -239,25 +280,30 @@ impl RankCoordinator {
     
     /// Synchronization barrier - wait for all ranks to reach this point  
     pub async fn barrier(&self, barrier_name: &str) -> Result<()> {
+        self.barrier_within(barrier_name, Duration::from_secs(30)).await
+    }
+
+    /// Barrier that waits up to `timeout` for the slowest rank
+    pub async fn barrier_within(&self, barrier_name: &str, timeout: Duration) -> Result<()> {
         debug!("🚧 Rank {}: Entering barrier '{}'", self.rank, barrier_name);
         self.update_heartbeat();
         
-        // Set our individual rank bit (avoid using ready_ranks counter which has reset issues)
-        self.state.rank_status[self.rank as usize].store(2, Ordering::Release);
+        // Set our individual rank bit (debug output only)
+        self.slots[self.rank as usize].status.store(2, Ordering::Release);
         debug!("🚧 Rank {}: Set ready status for barrier '{}'", self.rank, barrier_name);
         
-        // Wait for all ranks to set their ready status
+        // The last rank in resets the count and releases everyone by advancing the generation,
+        // so a rank leaving early can never make a slower one miss the release
+        let generation = self.state.barrier_generation.load(Ordering::Acquire);
+        if self.state.ready_ranks.fetch_add(1, Ordering::AcqRel) + 1 == self.world_size {
+            self.state.ready_ranks.store(0, Ordering::Release);
+            self.state.barrier_generation.fetch_add(1, Ordering::AcqRel);
+        }
+        
+        // Wait for the last rank to release the barrier
         let start_wait = Instant::now();
         loop {
-            let mut all_ready = true;
-            for i in 0..self.world_size {
-                if self.state.rank_status[i as usize].load(Ordering::Acquire) < 2 {
-                    all_ready = false;
-                    break;
-                }
-            }
-            
-            if all_ready {
+            if self.state.barrier_generation.load(Ordering::Acquire) != generation {
                 break;
             }
             
@@ -270,7 +316,7 @@ impl RankCoordinator {
             // Debug output every 5 seconds
             if start_wait.elapsed().as_secs() % 5 == 0 && start_wait.elapsed().as_millis() % 5000 < 100 {
                 let ready_count = (0..self.world_size)
-                    .map(|i| if self.state.rank_status[i as usize].load(Ordering::Acquire) >= 2 { 1 } else { 0 })
+                    .map(|i| if self.slots[i as usize].status.load(Ordering::Acquire) >= 2 { 1 } else { 0 })
                     .sum::<u32>();
                 debug!("🚧 Rank {}: Still waiting at barrier '{}' - ready: {}/{}", 
                       self.rank, barrier_name, ready_count, self.world_size);
@@ -278,10 +324,9 @@ impl RankCoordinator {
             
             tokio::time::sleep(Duration::from_millis(100)).await;
             
-            // Timeout after 30 seconds
-            if start_wait.elapsed() > Duration::from_secs(30) {
+            if start_wait.elapsed() > timeout {
                 let ready_count = (0..self.world_size)
-                    .map(|i| if self.state.rank_status[i as usize].load(Ordering::Acquire) >= 2 { 1 } else { 0 })
+                    .map(|i| if self.slots[i as usize].status.load(Ordering::Acquire) >= 2 { 1 } else { 0 })
                     .sum::<u32>();
                 warn!("⚠️  Rank {}: Timeout at barrier '{}' - ready: {}/{}", 
                       self.rank, barrier_name, ready_count, self.world_size);
@@ -293,12 +338,33 @@ impl RankCoordinator {
         debug!("✅ Rank {}: All ranks ready at barrier '{}'", self.rank, barrier_name);
         
         // Reset rank status for next barrier (each rank resets its own)
-        self.state.rank_status[self.rank as usize].store(1, Ordering::Release);
+        self.slots[self.rank as usize].status.store(1, Ordering::Release);
         
         debug!("✅ Rank {}: Exited barrier '{}'", self.rank, barrier_name);
         Ok(())
     }
     
+    /// Barrier at which every rank must pass the same `value` (e.g. a resolved path);
+    /// fails on every rank when any differs
+    pub async fn agree(&self, barrier_name: &str, value: &str) -> Result<()> {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        self.slots[self.rank as usize].value.store(hasher.finish(), Ordering::Release);
+        self.barrier(barrier_name).await?;
+
+        let reference = self.slots[0].value.load(Ordering::Acquire);
+        let differing: Vec<u32> = (0..self.world_size)
+            .filter(|&i| self.slots[i as usize].value.load(Ordering::Acquire) != reference)
+            .collect();
+        if !differing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Ranks {:?} disagree with rank 0 at '{}' (rank {} has {})",
+                differing, barrier_name, self.rank, value
+            ));
+        }
+        Ok(())
+    }
+
     /// Mark global execution start (only rank 0 should call this)
     pub fn mark_global_start(&self) -> Result<u64> {
         if self.rank != 0 {
@@ -315,6 +381,46 @@ impl RankCoordinator {
         Ok(start_time)
     }
     
+    /// Broadcast a start gate `delay` from now to all ranks (only rank 0 should call this)
+    pub fn broadcast_start_gate(&self, delay: Duration) -> Result<u64> {
+        if self.rank != 0 {
+            return Err(anyhow::anyhow!("Only rank 0 can broadcast the start gate"));
+        }
+        
+        let gate = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("Failed to get current time")?
+            .saturating_add(delay)
+            .as_nanos() as u64;
+        
+        self.state.start_gate_time.store(gate, Ordering::Release);
+        info!("⏱️  Rank 0: Broadcast start gate {:.3}s from now", delay.as_secs_f64());
+        Ok(gate)
+    }
+    
+    /// Wait for rank 0 to broadcast the start gate and return it
+    pub async fn wait_for_start_gate(&self) -> Result<u64> {
+        let start_wait = Instant::now();
+        loop {
+            let gate = self.state.start_gate_time.load(Ordering::Acquire);
+            if gate > 0 {
+                debug!("⏱️  Rank {}: Received start gate {}", self.rank, gate);
+                return Ok(gate);
+            }
+            
+            if self.check_abort()? {
+                return Err(anyhow::anyhow!("Coordination aborted while waiting for start gate"));
+            }
+            
+            self.update_heartbeat();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            
+            if start_wait.elapsed() > Duration::from_secs(30) {
+                return Err(anyhow::anyhow!("Timeout waiting for rank 0 to broadcast the start gate"));
+            }
+        }
+    }
+    
     /// Get global execution start time
     pub fn get_global_start_time(&self) -> Option<u64> {
         let start_time = self.state.global_start_time.load(Ordering::Acquire);
@@ -326,7 +432,7 @@ impl RankCoordinator {
         info!("🏁 Rank {}: Marking execution finished", self.rank);
         
         // Set our status to finished
-        self.state.rank_status[self.rank as usize].store(3, Ordering::Release);
+        self.slots[self.rank as usize].status.store(3, Ordering::Release);
         self.update_heartbeat();
         
         // Increment finished count
@@ -372,7 +478,7 @@ impl RankCoordinator {
     /// Mark execution failed
     pub fn mark_failed(&self, error: &str) {
         warn!("💥 Rank {}: Execution failed: {}", self.rank, error);
-        self.state.rank_status[self.rank as usize].store(4, Ordering::Release);
+        self.slots[self.rank as usize].status.store(4, Ordering::Release);
         self.update_heartbeat();
     }
     
@@ -397,7 +503,7 @@ impl RankCoordinator {
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
-        self.state.rank_heartbeats[self.rank as usize].store(now, Ordering::Release);
+        self.slots[self.rank as usize].heartbeat.store(now, Ordering::Release);
     }
     
     /// Get coordination statistics for debugging
@@ -415,36 +521,33 @@ impl RankCoordinator {
         }
     }
     
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+    
     /// Get coordination ID for debugging and cleanup
     pub fn coordination_id(&self) -> &str {
         &self.coordination_id
     }
     
     /// Store rank results in shared memory (eliminates temp files)
-    pub fn store_results(&self, 
-        files_processed: u64,
-        bytes_read: u64, 
-        throughput_gib_s: f64,
-        wall_clock_time_ms: f64,
-        au_fraction: f64,
-        start_time_ns: u64,
-        end_time_ns: u64
-    ) -> Result<()> {
+    pub fn store_results(&self, results: &RankResults) -> Result<()> {
         debug!("📊 Rank {}: Storing results in shared memory", self.rank);
         
-        let rank_results = &self.state.rank_results[self.rank as usize];
+        let rank_results = &self.slots[self.rank as usize].results;
         
         // Convert throughput from GiB/s to bytes/s
-        let throughput_bps = (throughput_gib_s * 1_073_741_824.0) as u64;
+        let throughput_bps = (results.throughput_gib_s * crate::units::GIB) as u64;
         
         // Store results atomically
-        rank_results.files_processed.store(files_processed, Ordering::Release);
-        rank_results.bytes_read.store(bytes_read, Ordering::Release);
+        rank_results.files_processed.store(results.files_processed, Ordering::Release);
+        rank_results.bytes_read.store(results.bytes_read, Ordering::Release);
         rank_results.throughput_bps.store(throughput_bps, Ordering::Release);
-        rank_results.wall_clock_time_ns.store((wall_clock_time_ms * 1_000_000.0) as u64, Ordering::Release);
-        rank_results.au_fraction_scaled.store((au_fraction * 1e15) as u64, Ordering::Release);
-        rank_results.start_time_ns.store(start_time_ns, Ordering::Release);
-        rank_results.end_time_ns.store(end_time_ns, Ordering::Release);
+        rank_results.wall_clock_time_ns.store((results.wall_clock_time_ms * 1_000_000.0) as u64, Ordering::Release);
+        rank_results.au_fraction_scaled.store((results.au_fraction * 1e15) as u64, Ordering::Release);
+        rank_results.accelerators.store(results.accelerators, Ordering::Release);
+        rank_results.start_time_ns.store(results.start_time_ns, Ordering::Release);
+        rank_results.end_time_ns.store(results.end_time_ns, Ordering::Release);
         
         // Mark results as valid (must be last)
         rank_results.results_valid.store(true, Ordering::Release);
@@ -457,16 +560,11 @@ impl RankCoordinator {
     pub fn get_aggregated_results(&self) -> Result<AggregatedResults> {
         info!("📊 Collecting aggregated results from shared memory");
         
-        let mut total_files = 0u64;
-        let mut total_bytes = 0u64;
-        let mut total_throughput_bps = 0u64;
-        let mut min_start_time = u64::MAX;
-        let mut max_end_time = 0u64;
-        let mut rank_details = Vec::new();
+        let mut ranks = Vec::new();
         
         // Collect results from all ranks
         for rank in 0..self.world_size {
-            let rank_results = &self.state.rank_results[rank as usize];
+            let rank_results = &self.slots[rank as usize].results;
             
             // Check if results are valid
             if !rank_results.results_valid.load(Ordering::Acquire) {
@@ -474,48 +572,22 @@ impl RankCoordinator {
                 continue;
             }
             
-            let files_processed = rank_results.files_processed.load(Ordering::Acquire);
-            let bytes_read = rank_results.bytes_read.load(Ordering::Acquire);
-            let throughput_bps = rank_results.throughput_bps.load(Ordering::Acquire);
-            let wall_clock_ns = rank_results.wall_clock_time_ns.load(Ordering::Acquire);
-            let au_fraction_scaled = rank_results.au_fraction_scaled.load(Ordering::Acquire);
-            let start_time_ns = rank_results.start_time_ns.load(Ordering::Acquire);
-            let end_time_ns = rank_results.end_time_ns.load(Ordering::Acquire);
-            
-            total_files += files_processed;
-            total_bytes += bytes_read;
-            total_throughput_bps += throughput_bps;
-            min_start_time = min_start_time.min(start_time_ns);
-            max_end_time = max_end_time.max(end_time_ns);
-            
-            rank_details.push(RankResultDetail {
-                rank,
-                files_processed,
-                bytes_read,
-                throughput_gib_s: throughput_bps as f64 / 1_073_741_824.0,
-                wall_clock_time_ms: wall_clock_ns as f64 / 1_000_000.0,
-                au_fraction: au_fraction_scaled as f64 / 1e15,
-            });
+            ranks.push((rank, RankResults {
+                files_processed: rank_results.files_processed.load(Ordering::Acquire),
+                bytes_read: rank_results.bytes_read.load(Ordering::Acquire),
+                throughput_gib_s: crate::units::gib(rank_results.throughput_bps.load(Ordering::Acquire)),
+                wall_clock_time_ms: rank_results.wall_clock_time_ns.load(Ordering::Acquire) as f64 / 1_000_000.0,
+                au_fraction: rank_results.au_fraction_scaled.load(Ordering::Acquire) as f64 / 1e15,
+                accelerators: rank_results.accelerators.load(Ordering::Acquire),
+                start_time_ns: rank_results.start_time_ns.load(Ordering::Acquire),
+                end_time_ns: rank_results.end_time_ns.load(Ordering::Acquire),
+            }));
         }
         
-        let global_runtime_ns = max_end_time.saturating_sub(min_start_time);
-        let global_runtime_s = global_runtime_ns as f64 / 1e9;
-        let total_throughput_gib_s = total_throughput_bps as f64 / 1_073_741_824.0;
-        
-        info!("📈 Aggregated: {} files, {:.2} GiB, {:.2} GiB/s from {} ranks", 
-              total_files, 
-              total_bytes as f64 / 1_073_741_824.0,
-              total_throughput_gib_s,
-              rank_details.len());
-              
-        Ok(AggregatedResults {
-            total_ranks: self.world_size,
-            total_files_processed: total_files,
-            total_bytes_read: total_bytes,
-            total_throughput_gib_s,
-            global_runtime_seconds: global_runtime_s,
-            rank_details,
-        })
+        let mut aggregated = AggregatedResults::from_ranks(self.world_size, &ranks);
+        aggregated.global_start_ns = self.get_global_start_time();
+        aggregated.global_end_ns = self.get_global_end_time();
+        Ok(aggregated)
     }
     
     /// Cleanup coordination resources (should be called by rank 0 after all processing)
@@ -543,6 +615,38 @@ pub struct CoordinationStats {
     pub aborted: bool,
 }
 
+/// One rank's results as shared with its coordination group
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RankResults {
+    pub files_processed: u64,
+    pub bytes_read: u64,
+    pub throughput_gib_s: f64,
+    pub wall_clock_time_ms: f64,
+    pub au_fraction: f64,
+    /// Accelerators this rank stands for (gpus_per_rank)
+    pub accelerators: u32,
+    /// Rank execution start and end (nanoseconds since UNIX_EPOCH)
+    pub start_time_ns: u64,
+    pub end_time_ns: u64,
+}
+
+impl RankResults {
+    /// Results of a rank's `to_json` metrics
+    pub fn from_metrics(metrics_json: &serde_json::Value, accelerators: u32) -> Self {
+        let metrics = &metrics_json["metrics"];
+        Self {
+            files_processed: metrics["files_processed"].as_u64().unwrap_or(0),
+            bytes_read: metrics["bytes_read"].as_u64().unwrap_or(0),
+            throughput_gib_s: metrics["storage_throughput_gib_s"].as_f64().unwrap_or(0.0),
+            wall_clock_time_ms: metrics["wall_clock_time_ms"].as_u64().unwrap_or(0) as f64,
+            au_fraction: metrics["au_fraction"].as_f64().unwrap_or(0.0),
+            accelerators,
+            start_time_ns: (metrics_json["start_time"].as_f64().unwrap_or(0.0) * 1_000_000_000.0) as u64,
+            end_time_ns: (metrics_json["end_time"].as_f64().unwrap_or(0.0) * 1_000_000_000.0) as u64,
+        }
+    }
+}
+
 /// Aggregated results from all ranks (eliminates temp file aggregation)
 #[derive(Debug, Clone)]
 pub struct AggregatedResults {
@@ -551,9 +655,58 @@ pub struct AggregatedResults {
     pub total_bytes_read: u64,
     pub total_throughput_gib_s: f64,
     pub global_runtime_seconds: f64,
+    /// Accelerators of all ranks that reported
+    pub total_accelerators: u32,
+    /// AU of every accelerator, each rank weighted by its accelerators
+    pub global_au_fraction: f64,
+    /// Group-wide execution start and end (nanoseconds since UNIX_EPOCH, on the group's reference clock)
+    pub global_start_ns: Option<u64>,
+    pub global_end_ns: Option<u64>,
     pub rank_details: Vec<RankResultDetail>,
 }
 
+impl AggregatedResults {
+    /// Combine the results of the ranks that reported, as (rank, results)
+    pub fn from_ranks(world_size: u32, ranks: &[(u32, RankResults)]) -> Self {
+        let total_files: u64 = ranks.iter().map(|(_, r)| r.files_processed).sum();
+        let total_bytes: u64 = ranks.iter().map(|(_, r)| r.bytes_read).sum();
+        let total_throughput_gib_s: f64 = ranks.iter().map(|(_, r)| r.throughput_gib_s).sum();
+        let min_start_time = ranks.iter().map(|(_, r)| r.start_time_ns).min().unwrap_or(0);
+        let max_end_time = ranks.iter().map(|(_, r)| r.end_time_ns).max().unwrap_or(0);
+        let rank_au: Vec<(u64, f64)> = ranks.iter().map(|(_, r)| (r.accelerators as u64, r.au_fraction)).collect();
+        
+        info!("📈 Aggregated: {} files, {:.2} GiB, {:.2} GiB/s from {} ranks", 
+              total_files, 
+              crate::units::gib(total_bytes),
+              total_throughput_gib_s,
+              ranks.len());
+              
+        Self {
+            total_ranks: world_size,
+            total_files_processed: total_files,
+            total_bytes_read: total_bytes,
+            total_throughput_gib_s,
+            global_runtime_seconds: max_end_time.saturating_sub(min_start_time) as f64 / 1e9,
+            total_accelerators: rank_au.iter().map(|&(a, _)| a as u32).sum(),
+            global_au_fraction: crate::accelerators::weighted_au(&rank_au),
+            global_start_ns: None,
+            global_end_ns: None,
+            rank_details: ranks
+                .iter()
+                .map(|(rank, r)| RankResultDetail {
+                    rank: *rank,
+                    files_processed: r.files_processed,
+                    bytes_read: r.bytes_read,
+                    throughput_gib_s: r.throughput_gib_s,
+                    wall_clock_time_ms: r.wall_clock_time_ms,
+                    au_fraction: r.au_fraction,
+                    accelerators: r.accelerators,
+                })
+                .collect(),
+        }
+    }
+}
+
 /// Individual rank result details
 #[derive(Debug, Clone)]
 pub struct RankResultDetail {
@@ -563,6 +716,18 @@ pub struct RankResultDetail {
     pub throughput_gib_s: f64,
     pub wall_clock_time_ms: f64,
     pub au_fraction: f64,
+    pub accelerators: u32,
+}
+
+/// Sleep until a UNIX timestamp (nanoseconds); returns immediately if it has passed
+pub async fn sleep_until_unix_ns(gate_ns: u64) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    if gate_ns > now {
+        tokio::time::sleep(Duration::from_nanos(gate_ns - now)).await;
+    }
 }
 
 /// Cleanup coordination resources (call from rank 0 after all processing)
@@ -585,10 +750,49 @@ mod tests {
         coord.register_and_wait().await.unwrap();
         coord.mark_global_start().unwrap();
         coord.barrier("test_barrier").await.unwrap();
+        coord.agree("data_folder", "file:///data/train").await.unwrap();
         coord.mark_finished_and_wait().await.unwrap();
         
         let stats = coord.get_stats();
         assert_eq!(stats.world_size, 1);
         assert_eq!(stats.finished_ranks, 1);
     }
+    
+    #[tokio::test]
+    async fn test_start_gate() {
+        let id = format!("test_gate_{}", std::process::id());
+        let ranks: Vec<RankCoordinator> = (0..2).map(|rank| RankCoordinator::new(rank, 2, &id).unwrap()).collect();
+        assert!(ranks[1].broadcast_start_gate(Duration::from_millis(50)).is_err());
+        
+        let gate = ranks[0].broadcast_start_gate(Duration::from_millis(50)).unwrap();
+        for coord in &ranks {
+            assert_eq!(coord.wait_for_start_gate().await.unwrap(), gate);
+        }
+        sleep_until_unix_ns(gate).await;
+        assert!(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64 >= gate);
+    }
+    
+    #[tokio::test]
+    async fn test_coordination_beyond_64_ranks() {
+        let id = format!("test_wide_{}", std::process::id());
+        let ranks: Vec<RankCoordinator> = (0..256).map(|rank| RankCoordinator::new(rank, 256, &id).unwrap()).collect();
+        // A group of another size is rejected rather than read with the wrong layout
+        assert!(RankCoordinator::new(0, 8, &id).is_err());
+        
+        futures::future::try_join_all(ranks.iter().map(|coord| coord.register_and_wait())).await.unwrap();
+        // Back-to-back barriers: ranks leaving the first early must not strand the rest
+        for name in ["execution_start", "checkpoint"] {
+            futures::future::try_join_all(ranks.iter().map(|coord| coord.barrier(name))).await.unwrap();
+        }
+        for coord in &ranks {
+            let results = RankResults { files_processed: 1, accelerators: 1, au_fraction: 0.5, ..Default::default() };
+            coord.store_results(&results).unwrap();
+        }
+        futures::future::try_join_all(ranks.iter().map(|coord| coord.mark_finished_and_wait())).await.unwrap();
+        
+        let aggregated = ranks[0].get_aggregated_results().unwrap();
+        assert_eq!((aggregated.rank_details.len(), aggregated.total_files_processed, aggregated.total_accelerators), (256, 256, 256));
+        assert_eq!(aggregated.rank_details[255].rank, 255);
+        assert!((aggregated.global_au_fraction - 0.5).abs() < 1e-12);
+    }
 }
\ No newline at end of file
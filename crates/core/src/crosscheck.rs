@@ -0,0 +1,217 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/crosscheck.rs
+//
+// Side-by-side comparison against the MLCommons DLIO reference implementation
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+
+/// Metrics both implementations report, in common units
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NormalizedMetrics {
+    pub au_percent: Option<f64>,
+    pub throughput_samples_per_s: Option<f64>,
+    pub io_mib_per_s: Option<f64>,
+    pub epochs: Option<u64>,
+}
+
+impl NormalizedMetrics {
+    /// Normalize a dl-driver results JSON (`Metrics::to_json`)
+    pub fn from_dl_driver(results: &serde_json::Value, config: &DlioConfig) -> Self {
+        let m = &results["metrics"];
+        let wall_s = m["wall_clock_time_ms"].as_f64().unwrap_or(0.0) / 1000.0;
+        let bytes = m["bytes_read"].as_f64().unwrap_or(0.0);
+        let record = config.dataset.record_length_bytes.unwrap_or(1024) as f64;
+        let per_second = |v: f64| (wall_s > 0.0).then(|| v / wall_s);
+
+        Self {
+            au_percent: m["au_percent"].as_f64(),
+            throughput_samples_per_s: per_second(bytes / record),
+            io_mib_per_s: per_second(bytes / crate::units::MIB),
+            epochs: results["config"]["epochs"].as_u64(),
+        }
+    }
+
+    /// Normalize a DLIO `summary.json`
+    pub fn from_dlio_summary(summary: &serde_json::Value) -> Self {
+        let m = &summary["metric"];
+        Self {
+            au_percent: m["train_au_mean_percentage"].as_f64(),
+            throughput_samples_per_s: m["train_throughput_mean_samples_per_second"].as_f64(),
+            io_mib_per_s: m["train_io_mean_MB_per_second"].as_f64(),
+            epochs: summary["epochs"].as_u64(),
+        }
+    }
+}
+
+/// One metric compared across implementations
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricComparison {
+    pub metric: String,
+    pub dl_driver: Option<f64>,
+    pub dlio: Option<f64>,
+    /// |dl_driver - dlio| / |dlio|
+    pub relative_diff: Option<f64>,
+    pub within_tolerance: bool,
+}
+
+/// Comparison report for a crosscheck run
+#[derive(Debug, Clone, Serialize)]
+pub struct CrosscheckReport {
+    pub tolerance: f64,
+    pub dl_driver: NormalizedMetrics,
+    pub dlio: NormalizedMetrics,
+    pub comparisons: Vec<MetricComparison>,
+    pub diverged: Vec<String>,
+    pub pass: bool,
+}
+
+/// Compare normalized metrics; a metric missing on either side is not flagged
+pub fn compare(dl_driver: NormalizedMetrics, dlio: NormalizedMetrics, tolerance: f64) -> CrosscheckReport {
+    let pairs = [
+        ("au_percent", dl_driver.au_percent, dlio.au_percent),
+        ("throughput_samples_per_s", dl_driver.throughput_samples_per_s, dlio.throughput_samples_per_s),
+        ("io_mib_per_s", dl_driver.io_mib_per_s, dlio.io_mib_per_s),
+        ("epochs", dl_driver.epochs.map(|e| e as f64), dlio.epochs.map(|e| e as f64)),
+    ];
+
+    let comparisons: Vec<MetricComparison> = pairs
+        .into_iter()
+        .map(|(metric, ours, theirs)| {
+            let relative_diff = match (ours, theirs) {
+                (Some(a), Some(b)) if b != 0.0 => Some((a - b).abs() / b.abs()),
+                (Some(a), Some(_)) => Some(if a == 0.0 { 0.0 } else { f64::INFINITY }),
+                _ => None,
+            };
+            MetricComparison {
+                metric: metric.to_string(),
+                dl_driver: ours,
+                dlio: theirs,
+                within_tolerance: relative_diff.map_or(true, |d| d <= tolerance),
+                relative_diff,
+            }
+        })
+        .collect();
+
+    let diverged: Vec<String> = comparisons
+        .iter()
+        .filter(|c| !c.within_tolerance)
+        .map(|c| c.metric.clone())
+        .collect();
+
+    CrosscheckReport {
+        tolerance,
+        pass: diverged.is_empty(),
+        dl_driver,
+        dlio,
+        comparisons,
+        diverged,
+    }
+}
+
+/// Run the reference DLIO benchmark on a config and return its parsed summary.json
+///
+/// The config is staged as a Hydra workload under `output_dir`, and the data
+/// folder is passed as a plain path since DLIO does not take URIs.
+pub async fn run_dlio(
+    dlio_bin: &Path,
+    config_path: &Path,
+    config: &DlioConfig,
+    output_dir: &Path,
+) -> Result<serde_json::Value> {
+    let workload_dir = output_dir.join("configs").join("workload");
+    std::fs::create_dir_all(&workload_dir)
+        .with_context(|| format!("Failed to create {:?}", workload_dir))?;
+    std::fs::copy(config_path, workload_dir.join("crosscheck.yaml"))
+        .with_context(|| format!("Failed to stage {:?} for DLIO", config_path))?;
+
+    let dlio_output = output_dir.join("dlio");
+    let data_folder = local_path_for_uri(config.data_folder_uri())
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| config.data_folder_uri().to_string());
+
+    let mut cmd = tokio::process::Command::new(dlio_bin);
+    cmd.arg(format!("--config-dir={}", output_dir.join("configs").display()))
+        .arg("workload=crosscheck")
+        .arg(format!("++workload.dataset.data_folder={}", data_folder))
+        .arg(format!("++workload.output.folder={}", dlio_output.display()));
+    info!("🐍 Running reference DLIO: {:?}", cmd);
+
+    let status = cmd
+        .status()
+        .await
+        .with_context(|| format!("Failed to launch DLIO binary {:?}", dlio_bin))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("DLIO exited with {}", status));
+    }
+
+    let summary_path = find_summary(&dlio_output)
+        .ok_or_else(|| anyhow::anyhow!("DLIO produced no summary.json under {:?}", dlio_output))?;
+    let content = std::fs::read_to_string(&summary_path)
+        .with_context(|| format!("Failed to read {:?}", summary_path))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", summary_path))
+}
+
+/// DLIO may nest its output in a timestamped directory
+fn find_summary(dir: &Path) -> Option<PathBuf> {
+    let direct = dir.join("summary.json");
+    if direct.is_file() {
+        return Some(direct);
+    }
+    std::fs::read_dir(dir).ok()?.filter_map(|e| e.ok()).find_map(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            find_summary(&path)
+        } else {
+            None
+        }
+    })
+}
+
+/// Log each metric that diverged beyond tolerance
+pub fn log_divergences(report: &CrosscheckReport) {
+    for c in report.comparisons.iter().filter(|c| !c.within_tolerance) {
+        warn!("⚠️  {} diverges: dl-driver={:?}, DLIO={:?} ({:.1}% > {:.1}%)",
+              c.metric, c.dl_driver, c.dlio,
+              c.relative_diff.unwrap_or(0.0) * 100.0, report.tolerance * 100.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_compare() {
+        let config = DlioConfig::from_yaml(
+            "dataset:\n  data_folder: file:///tmp/data\n  record_length_bytes: 1048576\nreader: {}\n",
+        )
+        .unwrap();
+        let ours = serde_json::json!({
+            "config": { "epochs": 2 },
+            "metrics": { "au_percent": 91.0, "bytes_read": 2147483648u64, "wall_clock_time_ms": 2000 }
+        });
+        let theirs = serde_json::json!({
+            "epochs": 2,
+            "metric": {
+                "train_au_mean_percentage": 90.0,
+                "train_throughput_mean_samples_per_second": 800.0,
+                "train_io_mean_MB_per_second": 1024.0
+            }
+        });
+
+        let dl = NormalizedMetrics::from_dl_driver(&ours, &config);
+        assert_eq!(dl.throughput_samples_per_s, Some(1024.0));
+        assert_eq!(dl.io_mib_per_s, Some(1024.0));
+
+        let report = compare(dl, NormalizedMetrics::from_dlio_summary(&theirs), 0.05);
+        assert_eq!(report.diverged, vec!["throughput_samples_per_s".to_string()]);
+        assert!(!report.pass);
+    }
+}
@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/dataset_growth.rs
+//
+// Growing-dataset simulation (`dataset_growth:` config block)
+//
+// Training corpora that ingest continuously gain files between epochs. Before
+// every epoch after the first, each rank appends `files_per_epoch` files to
+// the data folder (`grow_<epoch>_<rank>_<n>.<format>`, one object per
+// component for multi-component datasets), then the loader re-lists the
+// dataset so the next epoch reads them. Ingest and refresh (re-list) times are
+// measured separately from epoch time and reported per epoch under
+// "dataset_growth", along with how epoch time scales with the dataset.
+// Both fall inside the measured training phase, between epochs. The appended
+// files are deleted once training finishes, so reruns start from the dataset
+// as generated (in-memory backends are left alone; they vanish with the run).
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::backends::DataBackend;
+use crate::canary::Access;
+use crate::components;
+use crate::dlio_compat::{DatasetComponent, DlioConfig};
+
+/// Resolved `dataset_growth:` settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrowthSettings {
+    pub files_per_epoch: usize,
+}
+
+impl GrowthSettings {
+    /// From the config's `dataset_growth:` block; None when growth is off
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let Some(growth) = &config.dataset_growth else {
+            return Ok(None);
+        };
+        let files_per_epoch = growth.files_per_epoch.unwrap_or(10);
+        if files_per_epoch == 0 {
+            anyhow::bail!("dataset_growth.files_per_epoch must be positive");
+        }
+        Ok(Some(Self { files_per_epoch }))
+    }
+}
+
+/// One epoch of a growing dataset
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthEpoch {
+    pub epoch: u32,
+    /// Files (samples' object groups) the epoch read
+    pub files: usize,
+    /// Files this rank appended before the epoch
+    pub files_added: usize,
+    pub ingest_seconds: f64,
+    /// Re-listing the dataset so the loader sees the new files
+    pub refresh_seconds: f64,
+    pub epoch_seconds: f64,
+    pub epoch_seconds_per_file: f64,
+}
+
+/// Dataset-growth section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetGrowthReport {
+    pub files_per_epoch: usize,
+    pub initial_files: usize,
+    pub final_files: usize,
+    pub files_added: usize,
+    pub ingest_seconds_total: f64,
+    pub refresh_seconds_mean: f64,
+    pub refresh_seconds_max: f64,
+    /// Last epoch's time over the first's
+    pub epoch_time_growth: f64,
+    /// Last epoch's files over the first's
+    pub file_count_growth: f64,
+    pub epochs: Vec<GrowthEpoch>,
+}
+
+/// Appends files between epochs and records their cost
+pub struct DatasetGrowth {
+    settings: GrowthSettings,
+    base: String,
+    rank: u32,
+    /// Object suffix and payload of one new file, per component
+    payloads: Vec<(String, Vec<u8>)>,
+    /// Objects appended so far, removed by `cleanup`
+    written: Vec<String>,
+    /// Whether the data folder outlives the run (not mem://, sim:// or null://)
+    persistent: bool,
+    epochs: Vec<GrowthEpoch>,
+}
+
+impl DatasetGrowth {
+    pub fn new(settings: GrowthSettings, config: &DlioConfig, rank: u32) -> Result<Self> {
+        let samples = config.dataset.num_samples_per_file.unwrap_or(1);
+        let single = [DatasetComponent {
+            name: "data".to_string(),
+            format: config.dataset.format.clone().unwrap_or_else(|| "npz".to_string()),
+            record_length_bytes: None,
+        }];
+        let payloads = match components::components(config) {
+            Some(comps) => comps
+                .iter()
+                .map(|c| Ok((components::suffix(c), components::generate_payload(config, c, samples)?)))
+                .collect::<Result<_>>()?,
+            None => vec![(single[0].format.to_lowercase(), components::generate_payload(config, &single[0], samples)?)],
+        };
+        Ok(Self {
+            settings,
+            base: config.data_folder_uri().trim_end_matches('/').to_string(),
+            rank,
+            payloads,
+            written: Vec::new(),
+            persistent: !matches!(config.detect_storage_backend(), "mem" | "sim" | "null"),
+            epochs: Vec::new(),
+        })
+    }
+
+    /// Append this epoch's files; returns the objects written and the time taken
+    pub async fn ingest(&mut self, backend: &dyn DataBackend, epoch: u32) -> Result<(usize, Duration)> {
+        let start = Instant::now();
+        for n in 0..self.settings.files_per_epoch {
+            for (suffix, data) in &self.payloads {
+                let uri = format!("{}/grow_{:04}_{:04}_{:06}.{}", self.base, epoch, self.rank, n, suffix);
+                backend.put(&uri, data).await.with_context(|| format!("Failed to ingest {}", uri))?;
+                self.written.push(uri);
+            }
+        }
+        let elapsed = start.elapsed();
+        info!("🌱 Ingested {} files before epoch {} in {:?}", self.settings.files_per_epoch, epoch + 1, elapsed);
+        Ok((self.settings.files_per_epoch, elapsed))
+    }
+
+    /// Delete the appended objects; returns how many were removed
+    pub async fn cleanup(&mut self) -> Result<usize> {
+        if !self.persistent || self.written.is_empty() {
+            return Ok(0);
+        }
+        let mut removed = 0;
+        for uri in std::mem::take(&mut self.written) {
+            Access::for_uri(&uri)?.delete(&uri).await?;
+            removed += 1;
+        }
+        info!("🧹 Removed {} files appended by dataset_growth", removed);
+        Ok(removed)
+    }
+
+    pub fn record_epoch(&mut self, epoch: u32, files: usize, files_added: usize, ingest: Duration, refresh: Duration, elapsed: Duration) {
+        let epoch_seconds = elapsed.as_secs_f64();
+        self.epochs.push(GrowthEpoch {
+            epoch,
+            files,
+            files_added,
+            ingest_seconds: ingest.as_secs_f64(),
+            refresh_seconds: refresh.as_secs_f64(),
+            epoch_seconds,
+            epoch_seconds_per_file: if files > 0 { epoch_seconds / files as f64 } else { 0.0 },
+        });
+    }
+
+    pub fn report(&self) -> DatasetGrowthReport {
+        let (first, last) = (self.epochs.first(), self.epochs.last());
+        let ratio = |f: fn(&GrowthEpoch) -> f64| match (first, last) {
+            (Some(a), Some(b)) if f(a) > 0.0 => f(b) / f(a),
+            _ => 0.0,
+        };
+        let refreshes: Vec<f64> = self.epochs.iter().skip(1).map(|e| e.refresh_seconds).collect();
+        DatasetGrowthReport {
+            files_per_epoch: self.settings.files_per_epoch,
+            initial_files: first.map_or(0, |e| e.files),
+            final_files: last.map_or(0, |e| e.files),
+            files_added: self.epochs.iter().map(|e| e.files_added).sum(),
+            ingest_seconds_total: self.epochs.iter().map(|e| e.ingest_seconds).sum(),
+            refresh_seconds_mean: if refreshes.is_empty() { 0.0 } else { refreshes.iter().sum::<f64>() / refreshes.len() as f64 },
+            refresh_seconds_max: refreshes.iter().copied().fold(0.0, f64::max),
+            epoch_time_growth: ratio(|e| e.epoch_seconds),
+            file_count_growth: ratio(|e| e.files as f64),
+            epochs: self.epochs.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::MemBackend;
+
+    #[tokio::test]
+    async fn test_ingest_grows_listing_and_report() {
+        let yaml = "dataset:\n  data_folder: mem://grow\n  format: npz\n  num_files_train: 4\n  record_length_bytes: 256\nreader: {}\ndataset_growth: {files_per_epoch: 3}\n";
+        let config = DlioConfig::from_yaml(yaml).unwrap();
+        let backend = MemBackend::new(&config).unwrap();
+        let settings = GrowthSettings::from_config(&config).unwrap().unwrap();
+        let mut growth = DatasetGrowth::new(settings, &config, 1).unwrap();
+
+        growth.record_epoch(1, 4, 0, Duration::ZERO, Duration::ZERO, Duration::from_secs(2));
+        let (added, ingest) = growth.ingest(&backend, 1).await.unwrap();
+        let listed = backend.list().await.unwrap();
+        assert_eq!((added, listed.len()), (3, 7));
+        assert!(listed.contains(&"mem://grow/grow_0001_0001_000002.npz".to_string()));
+        growth.record_epoch(2, listed.len(), added, ingest, Duration::from_millis(10), Duration::from_secs(3));
+
+        let report = growth.report();
+        assert_eq!((report.initial_files, report.final_files, report.files_added), (4, 7, 3));
+        assert_eq!((report.epoch_time_growth, report.file_count_growth), (1.5, 1.75));
+        assert_eq!(report.refresh_seconds_mean, 0.01);
+
+        let mut config = config;
+        config.dataset_growth.as_mut().unwrap().files_per_epoch = Some(0);
+        assert!(GrowthSettings::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_removes_appended_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = format!("file://{}", dir.path().display());
+        let yaml = format!("dataset:\n  data_folder: {}\n  format: npz\n  num_files_train: 1\n  record_length_bytes: 64\nreader: {{}}\ndataset_growth: {{files_per_epoch: 2}}\n", folder);
+        let config = DlioConfig::from_yaml(&yaml).unwrap();
+        std::fs::write(dir.path().join("img_0.npz"), b"generated").unwrap();
+        let settings = GrowthSettings::from_config(&config).unwrap().unwrap();
+        let mut growth = DatasetGrowth::new(settings, &config, 0).unwrap();
+
+        for n in 0..2 {
+            let uri = format!("{}/grow_0001_0000_{:06}.npz", folder, n);
+            Access::for_uri(&uri).unwrap().put(&uri, b"grown").await.unwrap();
+            growth.written.push(uri);
+        }
+        assert_eq!(growth.cleanup().await.unwrap(), 2);
+        let left: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(left, ["img_0.npz"]);
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/dataset_lock.rs
+//
+// Dataset lock object (`dataset.lock`)
+//
+// Two runs accidentally generating (or cleaning up) the same data folder at
+// once interleave their writes and leave a dataset neither of them described.
+// With `dataset.lock` configured, generation and cleanup hold
+// `<data_folder>/_dl_driver.lock`, a small JSON object naming the holder
+// (host, pid, phase) and when the lock expires. A second run finding a live
+// lock fails with the holder's details; a lock past its TTL (left by a
+// crashed run) is taken over with a warning, and `--force-unlock` takes over
+// a live one. The TTL should exceed the longest generation.
+//
+// Object stores offer no conditional PUT here, so the lock is advisory: after
+// writing it, the holder reads it back and only proceeds if its own token is
+// there, which catches all but truly simultaneous starts. In a multi-rank run,
+// rank 0 holds the lock and the ranks pass a barrier after it is taken and
+// before it is released, so every rank's writes fall inside it.
+//
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::backends;
+use crate::canary::Access;
+use crate::dlio_compat::DlioConfig;
+
+/// Name of the lock object inside the data folder
+pub const LOCK_NAME: &str = "_dl_driver.lock";
+
+/// Contents of the lock object
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockHolder {
+    pub host: String,
+    pub pid: u32,
+    /// "generate" or "cleanup"
+    pub phase: String,
+    pub acquired_unix_s: u64,
+    pub expires_unix_s: u64,
+    /// Identifies this holder when the lock is read back
+    pub token: String,
+}
+
+impl LockHolder {
+    fn new(phase: &str, ttl_s: u64) -> Self {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let pid = std::process::id();
+        Self {
+            host: crate::metrics::host_name(),
+            pid,
+            phase: phase.to_string(),
+            acquired_unix_s: now.as_secs(),
+            expires_unix_s: now.as_secs() + ttl_s,
+            token: format!("{}-{}", pid, now.as_nanos()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} pid {} ({}, taken at unix {}, expires at unix {})",
+                self.host, self.pid, self.phase, self.acquired_unix_s, self.expires_unix_s)
+    }
+}
+
+/// A held dataset lock; call `release` when the phase is done
+pub struct DatasetLock {
+    uri: String,
+    access: Access,
+    holder: LockHolder,
+}
+
+impl DatasetLock {
+    /// Lock the data folder for `phase`; None when `dataset.lock` is not configured
+    pub async fn acquire(config: &DlioConfig, phase: &str, force_unlock: bool) -> Result<Option<Self>> {
+        let Some(settings) = config.dataset.lock.as_ref().filter(|l| l.enabled.unwrap_or(true)) else {
+            return Ok(None);
+        };
+        let folder = config.data_folder_uri();
+        if backends::is_native_uri(folder) {
+            return Ok(None);
+        }
+        let uri = format!("{}/{}", folder.trim_end_matches('/'), LOCK_NAME);
+        let access = Access::for_uri(&uri)?;
+
+        if let Some(existing) = read_holder(&access, &uri).await? {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            if force_unlock {
+                warn!("🔓 Breaking the lock on {} held by {}", folder, existing.describe());
+            } else if existing.expires_unix_s > now {
+                anyhow::bail!("Dataset {} is locked by {}; wait for that run to finish, or pass --force-unlock if it is gone",
+                              folder, existing.describe());
+            } else {
+                warn!("🔓 Taking over the expired lock on {} left by {}", folder, existing.describe());
+            }
+        }
+
+        let holder = LockHolder::new(phase, settings.ttl_s.unwrap_or(3600));
+        access.put(&uri, &serde_json::to_vec(&holder)?).await.context("Failed to write the dataset lock")?;
+        // A run that wrote the lock at the same moment wins if its write landed last
+        match read_holder(&access, &uri).await? {
+            Some(current) if current.token == holder.token => {}
+            Some(current) => anyhow::bail!("Dataset {} was locked at the same time by {}", folder, current.describe()),
+            None => anyhow::bail!("Dataset lock {} could not be read back", uri),
+        }
+        info!("🔒 Locked {} for {} (expires in {}s)", folder, phase, holder.expires_unix_s - holder.acquired_unix_s);
+        Ok(Some(Self { uri, access, holder }))
+    }
+
+    /// Delete the lock, unless another run has taken it over (or it went with the dataset)
+    pub async fn release(self) -> Result<()> {
+        match read_holder(&self.access, &self.uri).await? {
+            None => return Ok(()),
+            Some(current) if current.token != self.holder.token => {
+                warn!("⚠️  Lock {} was taken over by {}; leaving it in place", self.uri, current.describe());
+                return Ok(());
+            }
+            Some(_) => {}
+        }
+        self.access.delete(&self.uri).await.context("Failed to release the dataset lock")?;
+        info!("🔓 Released {}", self.uri);
+        Ok(())
+    }
+}
+
+/// Current holder; None when there is no lock object (or it is not valid JSON)
+///
+/// Only a missing object counts as unlocked: a GET that fails for another reason
+/// (throttling, a timeout) is an error, or two runs could both take the lock.
+async fn read_holder(access: &Access, uri: &str) -> Result<Option<LockHolder>> {
+    let Some(bytes) = access.get_if_exists(uri).await.context("Failed to read the dataset lock")? else {
+        return Ok(None);
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(holder) => Ok(Some(holder)),
+        Err(e) => {
+            warn!("⚠️  Ignoring unreadable lock {}: {}", uri, e);
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lock_blocks_second_run_until_released_or_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = |lock: &str| format!("dataset:\n  data_folder: {}\n{}reader: {{}}\n", dir.path().display(), lock);
+        let unlocked = DlioConfig::from_yaml(&yaml("")).unwrap();
+        assert!(DatasetLock::acquire(&unlocked, "generate", false).await.unwrap().is_none());
+
+        let config = DlioConfig::from_yaml(&yaml("  lock: {ttl_s: 600}\n")).unwrap();
+        let lock = DatasetLock::acquire(&config, "generate", false).await.unwrap().unwrap();
+        let holder: LockHolder = serde_json::from_slice(&std::fs::read(dir.path().join(LOCK_NAME)).unwrap()).unwrap();
+        assert_eq!((holder.phase.as_str(), holder.expires_unix_s - holder.acquired_unix_s), ("generate", 600));
+
+        let err = DatasetLock::acquire(&config, "cleanup", false).await.err().unwrap();
+        assert!(format!("{:#}", err).contains("--force-unlock"), "{:#}", err);
+
+        // Forcing takes the lock over; the first holder then leaves it alone
+        let forced = DatasetLock::acquire(&config, "cleanup", true).await.unwrap().unwrap();
+        lock.release().await.unwrap();
+        assert!(dir.path().join(LOCK_NAME).exists());
+        forced.release().await.unwrap();
+        assert!(!dir.path().join(LOCK_NAME).exists());
+
+        // An expired lock is taken over without --force-unlock
+        let expired = DlioConfig::from_yaml(&yaml("  lock: {ttl_s: 0}\n")).unwrap();
+        DatasetLock::acquire(&expired, "generate", false).await.unwrap().unwrap();
+        DatasetLock::acquire(&expired, "generate", false).await.unwrap().unwrap().release().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_lock_location_is_an_error_not_unlocked() {
+        let dir = tempfile::tempdir().unwrap();
+        let access = Access::Local(dir.path().join(LOCK_NAME));
+        assert!(read_holder(&access, "unused").await.unwrap().is_none());
+
+        // A directory where the lock object should be fails the read instead of passing as "no lock"
+        std::fs::create_dir(dir.path().join(LOCK_NAME)).unwrap();
+        assert!(read_holder(&access, "unused").await.is_err());
+    }
+}
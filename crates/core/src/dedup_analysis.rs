@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/dedup_analysis.rs
+//
+// Data-reduction analysis of dataset content (`analyze-data`)
+//
+// Arrays with inline dedup and compression store less than the logical size,
+// so a dataset that reduces well flatters them. The analyzer estimates what an
+// array could save, from a sample of generated (or stored) objects:
+//
+//   content-defined chunks   gear rolling hash, boundaries where the hash's low
+//                            bits are zero (avg `chunk_bytes`, min avg/4, max avg×4),
+//                            as variable-block dedup arrays do
+//   fixed blocks             `chunk_bytes` blocks, as fixed-block dedup arrays do
+//   compression              zstd level 3 per object
+//
+// Chunks are fingerprinted with a 64-bit hash; ratios are logical bytes over
+// unique (or compressed) bytes. Whole-object duplicates are counted separately,
+// since a sample of identical files understates the full dataset's ratio.
+//
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// Ratios above this mean arrays will store noticeably less than the logical size
+pub const REDUCIBLE_RATIO: f64 = 1.1;
+
+/// zstd level used for the compression estimate
+const ZSTD_LEVEL: i32 = 3;
+
+fn fingerprint(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.len().hash(&mut hasher);
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Accumulates chunk fingerprints over analyzed objects
+pub struct DedupAnalyzer {
+    chunk_bytes: usize,
+    mask: u64,
+    gear: [u64; 256],
+    objects: usize,
+    duplicate_objects: usize,
+    logical_bytes: u64,
+    chunks: u64,
+    unique_bytes: u64,
+    fixed_unique_bytes: u64,
+    compressed_bytes: u64,
+    seen_objects: HashSet<u64>,
+    seen_chunks: HashSet<u64>,
+    seen_blocks: HashSet<u64>,
+}
+
+impl DedupAnalyzer {
+    /// Analyzer for an average chunk size (rounded down to a power of two, at least 256)
+    pub fn new(chunk_bytes: usize) -> Self {
+        let chunk_bytes = 1usize << chunk_bytes.max(256).ilog2();
+        let mut state = 0x6765_6172_5f68_6173; // fixed, so fingerprints are reproducible
+        let gear = std::array::from_fn(|_| crate::rng::splitmix64(&mut state));
+        Self {
+            chunk_bytes,
+            mask: chunk_bytes as u64 - 1,
+            gear,
+            objects: 0,
+            duplicate_objects: 0,
+            logical_bytes: 0,
+            chunks: 0,
+            unique_bytes: 0,
+            fixed_unique_bytes: 0,
+            compressed_bytes: 0,
+            seen_objects: HashSet::new(),
+            seen_chunks: HashSet::new(),
+            seen_blocks: HashSet::new(),
+        }
+    }
+
+    /// Content-defined chunk boundaries (end offsets) of `data`
+    fn boundaries(&self, data: &[u8]) -> Vec<usize> {
+        let (min, max) = (self.chunk_bytes / 4, self.chunk_bytes * 4);
+        let mut ends = Vec::new();
+        let (mut start, mut hash) = (0, 0u64);
+        for (i, &byte) in data.iter().enumerate() {
+            hash = (hash << 1).wrapping_add(self.gear[byte as usize]);
+            let len = i + 1 - start;
+            if (len >= min && hash & self.mask == 0) || len >= max {
+                ends.push(i + 1);
+                start = i + 1;
+                hash = 0;
+            }
+        }
+        if start < data.len() {
+            ends.push(data.len());
+        }
+        ends
+    }
+
+    pub fn add(&mut self, data: &[u8]) {
+        self.objects += 1;
+        self.logical_bytes += data.len() as u64;
+        if !self.seen_objects.insert(fingerprint(data)) {
+            self.duplicate_objects += 1;
+        }
+
+        let mut start = 0;
+        for end in self.boundaries(data) {
+            self.chunks += 1;
+            if self.seen_chunks.insert(fingerprint(&data[start..end])) {
+                self.unique_bytes += (end - start) as u64;
+            }
+            start = end;
+        }
+        for block in data.chunks(self.chunk_bytes) {
+            if self.seen_blocks.insert(fingerprint(block)) {
+                self.fixed_unique_bytes += block.len() as u64;
+            }
+        }
+        self.compressed_bytes += zstd::bulk::compress(data, ZSTD_LEVEL).map_or(data.len(), |c| c.len().min(data.len())) as u64;
+    }
+
+    pub fn report(&self) -> DedupReport {
+        let ratio = |reduced: u64| if reduced > 0 { self.logical_bytes as f64 / reduced as f64 } else { 1.0 };
+        let dedup_ratio = ratio(self.unique_bytes);
+        let compression_ratio = ratio(self.compressed_bytes);
+        let combined_ratio = dedup_ratio * compression_ratio;
+
+        let verdict = if self.objects > 1 && self.duplicate_objects == self.objects - 1 {
+            "Every analyzed object is identical: the full dataset deduplicates by roughly its file count; \
+             enable seeded content (validation.verify_content) for unique files".to_string()
+        } else if combined_ratio > REDUCIBLE_RATIO {
+            format!("Data reduces {:.2}x (dedup {:.2}x, compression {:.2}x): dedup/compression-capable arrays \
+                     store less than the logical size, which flatters their results", combined_ratio, dedup_ratio, compression_ratio)
+        } else {
+            "Data is effectively unique and incompressible: arrays cannot reduce it, so results reflect full physical I/O".to_string()
+        };
+
+        DedupReport {
+            objects: self.objects,
+            duplicate_objects: self.duplicate_objects,
+            logical_bytes: self.logical_bytes,
+            avg_chunk_bytes: self.chunk_bytes,
+            chunks: self.chunks,
+            unique_chunks: self.seen_chunks.len() as u64,
+            unique_bytes: self.unique_bytes,
+            dedup_ratio,
+            fixed_block_dedup_ratio: ratio(self.fixed_unique_bytes),
+            compressed_bytes: self.compressed_bytes,
+            compression_ratio,
+            combined_ratio,
+            verdict,
+        }
+    }
+}
+
+/// Theoretical data reduction of the analyzed objects
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupReport {
+    pub objects: usize,
+    /// Objects identical to an earlier one
+    pub duplicate_objects: usize,
+    pub logical_bytes: u64,
+    pub avg_chunk_bytes: usize,
+    pub chunks: u64,
+    pub unique_chunks: u64,
+    pub unique_bytes: u64,
+    /// Variable-block (content-defined) dedup
+    pub dedup_ratio: f64,
+    pub fixed_block_dedup_ratio: f64,
+    pub compressed_bytes: u64,
+    pub compression_ratio: f64,
+    /// Dedup followed by compression (estimate)
+    pub combined_ratio: f64,
+    pub verdict: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pseudo_random(len: usize, mut state: u64) -> Vec<u8> {
+        (0..len).map(|_| crate::rng::splitmix64(&mut state) as u8).collect()
+    }
+
+    #[test]
+    fn test_dedup_and_compression_ratios() {
+        // Unique random content does not reduce
+        let mut unique = DedupAnalyzer::new(8192);
+        for seed in 0..4 {
+            unique.add(&pseudo_random(256 * 1024, seed));
+        }
+        let report = unique.report();
+        assert!(report.dedup_ratio < 1.01 && report.compression_ratio < 1.01, "{:?}", report);
+        assert!(report.verdict.contains("unique"));
+        let avg = report.logical_bytes as f64 / report.chunks as f64;
+        assert!((2048.0..32768.0).contains(&avg), "average chunk {}", avg);
+
+        // The same payload shifted by a few bytes still dedups with content-defined
+        // chunks, but not with fixed blocks
+        let base = pseudo_random(512 * 1024, 99);
+        let mut shifted = b"hdr".to_vec();
+        shifted.extend_from_slice(&base);
+        let mut analyzer = DedupAnalyzer::new(8192);
+        analyzer.add(&base);
+        analyzer.add(&shifted);
+        let report = analyzer.report();
+        assert_eq!(report.duplicate_objects, 0);
+        assert!(report.dedup_ratio > 1.8, "{:?}", report);
+        assert!(report.fixed_block_dedup_ratio < 1.1, "{:?}", report);
+
+        // Identical zero-filled files reduce massively and are flagged
+        let mut zeros = DedupAnalyzer::new(8192);
+        for _ in 0..3 {
+            zeros.add(&vec![0u8; 128 * 1024]);
+        }
+        let report = zeros.report();
+        assert_eq!(report.duplicate_objects, 2);
+        assert!(report.compression_ratio > 50.0);
+        assert!(report.verdict.contains("identical"));
+    }
+}
@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/derived.rs
+//
+// Derived metrics ("derived" in rank and aggregated results)
+//
+// Sizing questions are asked per accelerator and per client, not per run:
+// sample rate per accelerator (one per rank in multi-rank runs, --accelerators
+// for a single rank), bandwidth per rank, and with `--baseline` (results of a
+// reference run, e.g. the same config on local NVMe) the efficiency of this
+// run relative to it. Baselines may be rank or aggregated results.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tracing::info;
+
+use crate::units::Throughput;
+
+/// Per-accelerator and per-rank rates of a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Derived {
+    pub ranks: usize,
+    pub accelerators: u64,
+    pub samples_per_second: f64,
+    pub samples_per_second_per_accelerator: f64,
+    pub throughput_per_rank: Throughput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineEfficiency>,
+}
+
+/// This run relative to a reference run (1.0 = as fast as the baseline)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BaselineEfficiency {
+    pub source: String,
+    pub samples_per_second_per_accelerator: f64,
+    pub throughput_per_rank: Throughput,
+    pub sample_rate_efficiency: f64,
+    pub bandwidth_efficiency: f64,
+}
+
+impl Derived {
+    /// From each rank's results (`metrics`, and `derived.accelerators` when recorded)
+    pub fn from_ranks(ranks: &[&Value]) -> Self {
+        let sum = |field: &str| ranks.iter().filter_map(|r| r["metrics"][field].as_f64()).sum::<f64>();
+        let accelerators: u64 = ranks.iter().map(|r| r["derived"]["accelerators"].as_u64().unwrap_or(1)).sum();
+        let samples_per_second = sum("samples_per_second");
+        Self {
+            ranks: ranks.len(),
+            accelerators,
+            samples_per_second,
+            samples_per_second_per_accelerator: samples_per_second / accelerators.max(1) as f64,
+            throughput_per_rank: Throughput::from_gib_s(sum("storage_throughput_gib_s") / ranks.len().max(1) as f64),
+            baseline: None,
+        }
+    }
+
+    /// From rank results or aggregated results (`aggregated_results.rank_details`)
+    pub fn from_results(results: &Value) -> Self {
+        match results["aggregated_results"]["rank_details"].as_array() {
+            Some(details) => Self::from_ranks(&details.iter().collect::<Vec<_>>()),
+            None => Self::from_ranks(&[results]),
+        }
+    }
+
+    /// Compare with the results of a baseline run
+    pub fn with_baseline(mut self, path: &Path) -> Result<Self> {
+        let content = crate::artifacts::read_to_string(path).with_context(|| format!("Failed to read baseline: {:?}", path))?;
+        let results: Value = serde_json::from_str(&content).with_context(|| format!("Failed to parse baseline: {:?}", path))?;
+        let base = Self::from_results(&results);
+        let ratio = |this: f64, base: f64| if base > 0.0 { this / base } else { 0.0 };
+        let efficiency = BaselineEfficiency {
+            source: path.display().to_string(),
+            samples_per_second_per_accelerator: base.samples_per_second_per_accelerator,
+            throughput_per_rank: base.throughput_per_rank,
+            sample_rate_efficiency: ratio(self.samples_per_second_per_accelerator, base.samples_per_second_per_accelerator),
+            bandwidth_efficiency: ratio(self.throughput_per_rank.gib_s, base.throughput_per_rank.gib_s),
+        };
+        info!("📏 {:.1} samples/s per accelerator ({:.1}% of baseline), {} per rank ({:.1}% of baseline)",
+              self.samples_per_second_per_accelerator, efficiency.sample_rate_efficiency * 100.0,
+              self.throughput_per_rank.display(), efficiency.bandwidth_efficiency * 100.0);
+        self.baseline = Some(efficiency);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rank(samples_per_second: f64, gib_s: f64) -> Value {
+        json!({"metrics": {"samples_per_second": samples_per_second, "storage_throughput_gib_s": gib_s}})
+    }
+
+    #[test]
+    fn test_derived_rates_and_baseline_efficiency() {
+        let aggregated = json!({"aggregated_results": {"rank_details": [rank(400.0, 2.0), rank(200.0, 1.0)]}});
+        let derived = Derived::from_results(&aggregated);
+        assert_eq!((derived.ranks, derived.accelerators), (2, 2));
+        assert_eq!(derived.samples_per_second_per_accelerator, 300.0);
+        assert_eq!(derived.throughput_per_rank.gib_s, 1.5);
+
+        // Single rank standing in for 4 accelerators
+        let mut single = rank(800.0, 3.0);
+        single["derived"] = json!({"accelerators": 4});
+        assert_eq!(Derived::from_results(&single).samples_per_second_per_accelerator, 200.0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = dir.path().join("nvme.json");
+        std::fs::write(&baseline, rank(600.0, 3.0).to_string()).unwrap();
+        let compared = derived.with_baseline(&baseline).unwrap().baseline.unwrap();
+        assert_eq!((compared.sample_rate_efficiency, compared.bandwidth_efficiency), (0.5, 0.5));
+    }
+}
@@ -0,0 +1,213 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/directio.rs
+//
+// Preflight alignment validation for direct:// (O_DIRECT) targets
+//
+use anyhow::Result;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::dlio_compat::DlioConfig;
+
+const DEFAULT_ALIGNMENT_BYTES: usize = 4096;
+
+/// Policy applied when generated file sizes are not sector-aligned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MisalignedPolicy {
+    /// Round file sizes up to the alignment with zero padding
+    Pad,
+    /// Switch the run to buffered file:// I/O
+    Fallback,
+    /// Refuse to run
+    Error,
+}
+
+impl MisalignedPolicy {
+    fn parse(value: Option<&str>) -> Result<Self> {
+        match value.unwrap_or("pad").to_lowercase().as_str() {
+            "pad" => Ok(Self::Pad),
+            "fallback" => Ok(Self::Fallback),
+            "error" => Ok(Self::Error),
+            other => Err(anyhow::anyhow!(
+                "Unknown storage.directio.on_misaligned policy '{}'. Valid options: pad, fallback, error",
+                other
+            )),
+        }
+    }
+}
+
+/// Outcome of the DirectIO alignment preflight, included in results
+#[derive(Debug, Clone, Serialize)]
+pub struct AlignmentReport {
+    pub alignment_bytes: usize,
+    pub record_length_bytes: usize,
+    pub file_size_bytes: usize,
+    pub aligned: bool,
+    pub policy: MisalignedPolicy,
+    /// File size after padding, when the pad policy was applied
+    pub padded_file_size_bytes: Option<usize>,
+    /// Data folder URI the run actually uses (changes on fallback)
+    pub effective_uri: String,
+}
+
+fn is_direct_uri(uri: &str) -> bool {
+    uri.starts_with("direct://") || uri.starts_with("directio://")
+}
+
+fn alignment_bytes(config: &DlioConfig) -> usize {
+    config
+        .storage
+        .as_ref()
+        .and_then(|s| s.directio.as_ref())
+        .and_then(|d| d.alignment_bytes)
+        .unwrap_or(DEFAULT_ALIGNMENT_BYTES)
+        .max(1)
+}
+
+fn round_up(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment) * alignment
+}
+
+/// Validate alignment for direct:// targets before any I/O is issued
+///
+/// Returns None for non-DirectIO targets. With the fallback policy the config's
+/// data_folder is rewritten to file:// so later phases use buffered I/O.
+pub fn preflight(config: &mut DlioConfig) -> Result<Option<AlignmentReport>> {
+    if !is_direct_uri(&config.dataset.data_folder) {
+        return Ok(None);
+    }
+
+    let alignment = alignment_bytes(config);
+    let policy = MisalignedPolicy::parse(
+        config
+            .storage
+            .as_ref()
+            .and_then(|s| s.directio.as_ref())
+            .and_then(|d| d.on_misaligned.as_deref()),
+    )?;
+    let record_length = config.dataset.record_length_bytes.unwrap_or(1024);
+    let file_size = record_length * config.dataset.num_samples_per_file.unwrap_or(1);
+    let aligned = file_size % alignment == 0;
+
+    let mut report = AlignmentReport {
+        alignment_bytes: alignment,
+        record_length_bytes: record_length,
+        file_size_bytes: file_size,
+        aligned,
+        policy,
+        padded_file_size_bytes: None,
+        effective_uri: config.dataset.data_folder.clone(),
+    };
+
+    if aligned {
+        info!("DirectIO preflight: file size {} is {}-byte aligned", file_size, alignment);
+        return Ok(Some(report));
+    }
+
+    match policy {
+        MisalignedPolicy::Pad => {
+            let padded = round_up(file_size, alignment);
+            warn!("⚠️  DirectIO: file size {} is not {}-byte aligned; padding files to {} bytes",
+                  file_size, alignment, padded);
+            report.padded_file_size_bytes = Some(padded);
+        }
+        MisalignedPolicy::Fallback => {
+            let rest = config
+                .dataset
+                .data_folder
+                .split_once("://")
+                .map(|(_, rest)| rest.to_string())
+                .unwrap_or_default();
+            let fallback_uri = format!("file://{}", rest);
+            warn!("⚠️  DirectIO: file size {} is not {}-byte aligned; falling back to buffered I/O at {}",
+                  file_size, alignment, fallback_uri);
+            config.dataset.data_folder = fallback_uri.clone();
+            report.effective_uri = fallback_uri;
+        }
+        MisalignedPolicy::Error => {
+            return Err(anyhow::anyhow!(
+                "DirectIO requires {}-byte aligned files, but record_length_bytes ({}) × num_samples_per_file gives {} bytes. \
+                 Adjust the dataset sizes or set storage.directio.on_misaligned to 'pad' or 'fallback'",
+                alignment, record_length, file_size
+            ));
+        }
+    }
+
+    Ok(Some(report))
+}
+
+/// File size to write for generated data, applying DirectIO padding when required
+pub fn aligned_file_size(config: &DlioConfig, file_size: usize) -> usize {
+    if !is_direct_uri(&config.dataset.data_folder) {
+        return file_size;
+    }
+    let policy = MisalignedPolicy::parse(
+        config
+            .storage
+            .as_ref()
+            .and_then(|s| s.directio.as_ref())
+            .and_then(|d| d.on_misaligned.as_deref()),
+    )
+    .unwrap_or(MisalignedPolicy::Pad);
+
+    if policy == MisalignedPolicy::Pad {
+        round_up(file_size, alignment_bytes(config))
+    } else {
+        file_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(uri: &str, record: usize, policy: &str) -> DlioConfig {
+        let yaml = format!(
+            r#"
+dataset:
+  data_folder: {}
+  record_length_bytes: {}
+  num_samples_per_file: 1
+reader: {{}}
+storage:
+  directio:
+    on_misaligned: {}
+"#,
+            uri, record, policy
+        );
+        DlioConfig::from_yaml(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_non_direct_uri_is_skipped() {
+        let mut cfg = config("file:///tmp/data", 1000, "error");
+        assert!(preflight(&mut cfg).unwrap().is_none());
+        assert_eq!(aligned_file_size(&cfg, 1000), 1000);
+    }
+
+    #[test]
+    fn test_pad_policy() {
+        let mut cfg = config("direct:///tmp/data", 1000, "pad");
+        let report = preflight(&mut cfg).unwrap().unwrap();
+        assert!(!report.aligned);
+        assert_eq!(report.padded_file_size_bytes, Some(4096));
+        assert_eq!(aligned_file_size(&cfg, 1000), 4096);
+    }
+
+    #[test]
+    fn test_fallback_and_error_policies() {
+        let mut cfg = config("direct:///tmp/data", 1000, "fallback");
+        let report = preflight(&mut cfg).unwrap().unwrap();
+        assert_eq!(report.effective_uri, "file:///tmp/data");
+        assert_eq!(cfg.dataset.data_folder, "file:///tmp/data");
+
+        let mut cfg = config("direct:///tmp/data", 1000, "error");
+        assert!(preflight(&mut cfg).is_err());
+
+        let mut cfg = config("direct:///tmp/data", 8192, "error");
+        assert!(preflight(&mut cfg).unwrap().unwrap().aligned);
+    }
+}
@@ -0,0 +1,159 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/epoch_boundary.rs
+//
+// Epoch-boundary prefetch policy (`reader.epoch_boundary`)
+//
+// The background loader reads ahead of training; this decides what happens to
+// its prefetch when an epoch ends:
+//
+//   drain       (default, DLIO) each epoch's loader reads exactly that epoch's
+//               files and training drains it; the next epoch's loader starts
+//               with the next epoch, so nothing crosses the boundary
+//   carry_over  once an epoch's last file is read, the next epoch's loader (in
+//               the next shuffle order) starts prefetching; whatever it holds
+//               at the boundary is trained on in the next epoch
+//   discard     the loader runs ahead as with carry_over, but what it holds at
+//               the boundary is thrown away and the next epoch starts a fresh
+//               loader (a loader reset every epoch)
+//
+// Batches still queued when a run ends early (a stop request or
+// train.total_training_steps) are discarded under every policy. Discarded batches were read but never trained on; they
+// are reported under "epoch_boundary", apart from the bytes read for training.
+//
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::dlio_compat::DlioConfig;
+
+/// What happens to prefetched batches at an epoch boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpochBoundaryPolicy {
+    Drain,
+    Discard,
+    CarryOver,
+}
+
+impl EpochBoundaryPolicy {
+    /// From `reader.epoch_boundary`
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        match config.reader.epoch_boundary.as_deref().unwrap_or("drain") {
+            "drain" => Ok(Self::Drain),
+            "discard" => Ok(Self::Discard),
+            "carry_over" | "carry-over" => Ok(Self::CarryOver),
+            other => anyhow::bail!("Unknown reader.epoch_boundary '{}' (expected 'drain', 'discard' or 'carry_over')", other),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Drain => "drain",
+            Self::Discard => "discard",
+            Self::CarryOver => "carry_over",
+        }
+    }
+
+    /// The next epoch's loader starts before the current epoch ends
+    pub fn runs_ahead(&self) -> bool {
+        *self != Self::Drain
+    }
+}
+
+/// Prefetch handled at the end of one epoch
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BoundaryStats {
+    /// Epoch (1-based) that ended
+    pub after_epoch: u32,
+    /// Batches already loaded for the next epoch and trained on there
+    pub carried_batches: u64,
+    pub discarded_batches: u64,
+    pub discarded_bytes: u64,
+}
+
+/// Epoch boundaries of the last training run
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochBoundaryReport {
+    pub policy: String,
+    pub carried_batches: u64,
+    pub discarded_batches: u64,
+    pub discarded_bytes: u64,
+    /// Boundaries where anything was carried over or discarded
+    pub boundaries: Vec<BoundaryStats>,
+}
+
+/// Tracks carried and discarded prefetch across a run
+pub struct EpochBoundary {
+    policy: EpochBoundaryPolicy,
+    boundaries: Vec<BoundaryStats>,
+}
+
+impl EpochBoundary {
+    pub fn new(policy: EpochBoundaryPolicy) -> Self {
+        Self { policy, boundaries: Vec::new() }
+    }
+
+    pub fn policy(&self) -> EpochBoundaryPolicy {
+        self.policy
+    }
+
+    pub fn record_carried(&mut self, after_epoch: u32, batches: u64) {
+        if batches > 0 {
+            self.entry(after_epoch).carried_batches += batches;
+        }
+    }
+
+    pub fn record_discarded(&mut self, after_epoch: u32, (batches, bytes): (u64, u64)) {
+        if batches > 0 {
+            let entry = self.entry(after_epoch);
+            entry.discarded_batches += batches;
+            entry.discarded_bytes += bytes;
+        }
+    }
+
+    fn entry(&mut self, after_epoch: u32) -> &mut BoundaryStats {
+        match self.boundaries.iter().position(|b| b.after_epoch == after_epoch) {
+            Some(i) => &mut self.boundaries[i],
+            None => {
+                self.boundaries.push(BoundaryStats { after_epoch, ..Default::default() });
+                self.boundaries.last_mut().expect("just pushed")
+            }
+        }
+    }
+
+    /// None under the default drain policy when nothing was discarded
+    pub fn report(&self) -> Option<EpochBoundaryReport> {
+        if self.policy == EpochBoundaryPolicy::Drain && self.boundaries.is_empty() {
+            return None;
+        }
+        Some(EpochBoundaryReport {
+            policy: self.policy.name().to_string(),
+            carried_batches: self.boundaries.iter().map(|b| b.carried_batches).sum(),
+            discarded_batches: self.boundaries.iter().map(|b| b.discarded_batches).sum(),
+            discarded_bytes: self.boundaries.iter().map(|b| b.discarded_bytes).sum(),
+            boundaries: self.boundaries.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_boundary_policy_and_discard_accounting() {
+        let config = |policy: &str| DlioConfig::from_yaml(&format!("dataset:\n  data_folder: /data\nreader:\n  {}\n", policy)).unwrap();
+        assert_eq!(EpochBoundaryPolicy::from_config(&config("batch_size: 1")).unwrap(), EpochBoundaryPolicy::Drain);
+        assert_eq!(EpochBoundaryPolicy::from_config(&config("epoch_boundary: carry_over")).unwrap(), EpochBoundaryPolicy::CarryOver);
+        assert!(EpochBoundaryPolicy::from_config(&config("epoch_boundary: flush")).is_err());
+
+        let mut boundary = EpochBoundary::new(EpochBoundaryPolicy::Discard);
+        boundary.record_discarded(1, (3, 384));
+        boundary.record_carried(2, 0);
+        boundary.record_discarded(2, (1, 16));
+        boundary.record_discarded(2, (1, 16));
+        let report = boundary.report().unwrap();
+        assert_eq!((report.discarded_batches, report.discarded_bytes, report.boundaries.len()), (5, 416, 2));
+        assert!(EpochBoundary::new(EpochBoundaryPolicy::Drain).report().is_none());
+    }
+}
@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/evaluation.rs
+//
+// Evaluation phase (`workflow.evaluation`, DLIO `evaluation:`)
+//
+// The eval split is `dataset.num_files_eval` files in `dataset.eval_folder`
+// (default `<data_folder>_eval`), generated together with the training files.
+// After every `epochs_between_evals` training epochs each rank reads its share
+// of it in steps of `reader.batch_size_eval` samples, `read_threads_eval`
+// reads in flight, and spends `evaluation.eval_time` per step in the
+// configured compute model.
+//
+// Evaluation runs between epochs, outside every training epoch time, so AU and
+// training throughput are unaffected. Each evaluation reports its own
+// throughput and per-step I/O wait under "evaluation".
+//
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::backends::{self, DataBackend, ObjectStoreBackend};
+use crate::compute::{ComputeModel, ComputeSimulator};
+use crate::dlio_compat::DlioConfig;
+use crate::units::Throughput;
+
+/// Resolved evaluation settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalSettings {
+    pub folder: String,
+    pub num_files: usize,
+    pub samples_per_file: usize,
+    pub batch_size: usize,
+    pub read_threads: usize,
+    pub eval_time: Duration,
+    pub epochs_between: usize,
+}
+
+impl EvalSettings {
+    /// None unless `workflow.evaluation` is on and there is an eval split
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let num_files = config.dataset.num_files_eval.unwrap_or(0);
+        if !config.should_evaluate() || num_files == 0 {
+            return Ok(None);
+        }
+        let evaluation = config.evaluation.clone().unwrap_or_default();
+        let eval_time = evaluation.eval_time.unwrap_or(0.0);
+        let eval_time = Duration::try_from_secs_f64(eval_time)
+            .with_context(|| format!("evaluation.eval_time must be a non-negative number of seconds, got {}", eval_time))?;
+        let reader = config.to_run_plan()?.reader.eval;
+        Ok(Some(Self {
+            folder: config.eval_folder_uri(),
+            num_files,
+            samples_per_file: config.dataset.num_samples_per_file.unwrap_or(1).max(1),
+            batch_size: reader.batch_size.max(1),
+            read_threads: reader.read_threads.max(1),
+            eval_time,
+            epochs_between: evaluation.epochs_between_evals.unwrap_or(1).max(1),
+        }))
+    }
+
+    /// An evaluation is due after training epoch `epoch` (1-based)
+    pub fn due_after_epoch(&self, epoch: u32) -> bool {
+        (epoch as usize).is_multiple_of(self.epochs_between)
+    }
+
+    /// Files read per step: enough for batch_size_eval samples
+    pub fn files_per_step(&self) -> usize {
+        self.batch_size.div_ceil(self.samples_per_file).max(1)
+    }
+}
+
+/// Generation config for the eval split: num_files_eval files written to the eval folder
+pub fn eval_split_config(config: &DlioConfig) -> Option<DlioConfig> {
+    let files = config.dataset.num_files_eval.filter(|&n| n > 0)?;
+    let mut eval = config.clone();
+    eval.dataset.data_folder = config.eval_folder_uri();
+    eval.dataset.num_files_train = Some(files);
+    eval.dataset.num_files_eval = None;
+    eval.dataset.data_folders = None;
+    eval.dataset.lock = None;
+    if let Some(storage) = eval.storage.as_mut() {
+        storage.endpoints = None;
+    }
+    Some(eval)
+}
+
+/// One evaluation pass over this rank's share of the eval split
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalRun {
+    /// Training epoch (1-based) this evaluation followed
+    pub epoch: u32,
+    pub files: usize,
+    pub samples: usize,
+    pub bytes: u64,
+    pub steps: usize,
+    pub seconds: f64,
+    pub compute_s: f64,
+    pub samples_per_second: f64,
+    pub throughput: Throughput,
+    /// Time each step waited for its files
+    pub step_io_mean_ms: f64,
+    pub step_io_p99_ms: f64,
+}
+
+/// Evaluations of the last training run
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    pub folder: String,
+    pub batch_size: usize,
+    pub read_threads: usize,
+    pub eval_time_s: f64,
+    pub runs: Vec<EvalRun>,
+}
+
+/// Reads the eval split between training epochs
+pub struct Evaluator {
+    settings: EvalSettings,
+    backend: Arc<dyn DataBackend>,
+    compute: ComputeSimulator,
+    rank: u32,
+    world_size: u32,
+    uris: Option<Vec<String>>,
+    runs: Vec<EvalRun>,
+}
+
+impl Evaluator {
+    pub fn new(settings: EvalSettings, config: &DlioConfig, rank: u32, world_size: u32) -> Result<Self> {
+        let split = eval_split_config(config).context("evaluation needs dataset.num_files_eval")?;
+        let backend = match backends::backend_for_config(&split, None)? {
+            Some(backend) => backend,
+            None => Arc::new(ObjectStoreBackend::new(&settings.folder)?) as Arc<dyn DataBackend>,
+        };
+        let compute = ComputeSimulator::new(ComputeModel::from_config(config)?, settings.eval_time);
+        info!("🧪 Evaluation every {} epoch(s): {} files in {}, batch {} ({} reads in flight), {:.1}ms/step",
+              settings.epochs_between, settings.num_files, settings.folder, settings.batch_size,
+              settings.read_threads, settings.eval_time.as_secs_f64() * 1000.0);
+        Ok(Self { settings, backend, compute, rank, world_size, uris: None, runs: Vec::new() })
+    }
+
+    pub fn settings(&self) -> &EvalSettings {
+        &self.settings
+    }
+
+    /// Evaluate after training epoch `epoch` (1-based); returns the time it took
+    pub async fn run(&mut self, epoch: u32) -> Result<Duration> {
+        if self.uris.is_none() {
+            let (rank, world_size) = (self.rank as usize, self.world_size.max(1) as usize);
+            let listed = self.backend.list().await.with_context(|| format!("Failed to list eval folder {}", self.settings.folder))?;
+            anyhow::ensure!(!listed.is_empty(), "Eval folder {} is empty; generate the dataset first", self.settings.folder);
+            self.uris = Some(listed.into_iter().enumerate().filter(|(i, _)| i % world_size == rank).map(|(_, u)| u).collect());
+        }
+        let uris = self.uris.clone().unwrap_or_default();
+
+        let start = Instant::now();
+        let backend = Arc::clone(&self.backend);
+        let mut reads = stream::iter(uris)
+            .map(|uri| {
+                let backend = Arc::clone(&backend);
+                async move { backend.get(&uri).await }
+            })
+            .buffered(self.settings.read_threads);
+
+        let (mut files, mut bytes, mut steps) = (0usize, 0u64, 0usize);
+        let mut compute_time = Duration::ZERO;
+        let mut io_ms = Vec::new();
+        loop {
+            let wait = Instant::now();
+            let mut in_step = 0;
+            while in_step < self.settings.files_per_step() {
+                match reads.next().await {
+                    Some(data) => {
+                        bytes += data.context("Eval read failed")?.len() as u64;
+                        in_step += 1;
+                    }
+                    None => break,
+                }
+            }
+            if in_step == 0 {
+                break;
+            }
+            io_ms.push(wait.elapsed().as_secs_f64() * 1000.0);
+            let compute_start = Instant::now();
+            self.compute.step().await?;
+            compute_time += compute_start.elapsed();
+            files += in_step;
+            steps += 1;
+        }
+
+        let elapsed = start.elapsed();
+        let samples = files * self.settings.samples_per_file;
+        io_ms.sort_by(f64::total_cmp);
+        let run = EvalRun {
+            epoch,
+            files,
+            samples,
+            bytes,
+            steps,
+            seconds: elapsed.as_secs_f64(),
+            compute_s: compute_time.as_secs_f64(),
+            samples_per_second: if elapsed.is_zero() { 0.0 } else { samples as f64 / elapsed.as_secs_f64() },
+            throughput: Throughput::new(bytes, elapsed.as_secs_f64()),
+            step_io_mean_ms: io_ms.iter().sum::<f64>() / io_ms.len().max(1) as f64,
+            step_io_p99_ms: percentile(&io_ms, 0.99),
+        };
+        info!("🧪 Eval after epoch {}: {} samples in {} steps, {:?} ({:.1} samples/s, {}; step I/O mean {:.2}ms, p99 {:.2}ms)",
+              epoch, run.samples, run.steps, elapsed, run.samples_per_second, run.throughput.display(),
+              run.step_io_mean_ms, run.step_io_p99_ms);
+        self.runs.push(run);
+        Ok(elapsed)
+    }
+
+    pub fn report(&self) -> EvalReport {
+        EvalReport {
+            folder: self.settings.folder.clone(),
+            batch_size: self.settings.batch_size,
+            read_threads: self.settings.read_threads,
+            eval_time_s: self.settings.eval_time.as_secs_f64(),
+            runs: self.runs.clone(),
+        }
+    }
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        0.0
+    } else {
+        sorted[((p * (sorted.len() - 1) as f64) as usize).min(sorted.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_evaluation_reads_rank_share_of_eval_split() {
+        let yaml = "dataset:\n  data_folder: mem://train\n  num_files_train: 8\n  num_files_eval: 10\n  record_length_bytes: 64\n  \
+                    num_samples_per_file: 2\nreader:\n  batch_size: 8\n  batch_size_eval: 3\n  read_threads_eval: 2\n\
+                    workflow:\n  evaluation: true\nevaluation:\n  epochs_between_evals: 2\n";
+        let config = DlioConfig::from_yaml(yaml).unwrap();
+        let settings = EvalSettings::from_config(&config).unwrap().unwrap();
+        assert_eq!((settings.folder.as_str(), settings.batch_size, settings.read_threads), ("mem://train_eval", 3, 2));
+        assert_eq!(settings.files_per_step(), 2);
+        assert_eq!((1..=6).filter(|&e| settings.due_after_epoch(e)).collect::<Vec<_>>(), [2, 4, 6]);
+
+        let split = eval_split_config(&config).unwrap();
+        assert_eq!((split.data_folder_uri(), split.dataset.num_files_train), ("mem://train_eval", Some(10)));
+
+        // Rank 1 of 2 reads every other eval file: 5 files (10 samples) in 3 steps
+        let mut evaluator = Evaluator::new(settings, &config, 1, 2).unwrap();
+        evaluator.run(2).await.unwrap();
+        let report = evaluator.report();
+        assert_eq!(report.runs.len(), 1);
+        let run = &report.runs[0];
+        assert_eq!((run.epoch, run.files, run.samples, run.steps), (2, 5, 10, 3));
+        assert!(run.bytes > 0);
+
+        let off = DlioConfig::from_yaml("dataset:\n  data_folder: mem://train\n  num_files_eval: 10\nreader: {}\n").unwrap();
+        assert!(EvalSettings::from_config(&off).unwrap().is_none());
+
+        let mut too_long = config.clone();
+        too_long.evaluation.as_mut().unwrap().eval_time = Some(1e300);
+        assert!(EvalSettings::from_config(&too_long).is_err());
+    }
+}
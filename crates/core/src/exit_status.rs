@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/exit_status.rs
+//
+// Process exit-code contract, so automation can act on the code alone:
+//
+//   0  success
+//   1  any other failure
+//   2  config error       unreadable/invalid config or arguments (also clap usage errors)
+//   3  AU fail            accelerator utilization below threshold in strict AU mode
+//   4  SLO fail           the run completed but another acceptance check failed
+//                         (content verification, crosscheck divergence,
+//                         encryption read-back)
+//   5  storage error      generating, listing or reading the dataset failed
+//   6  interrupted        SIGINT/SIGTERM before the command finished
+//
+// Errors are classified by tagging them with an ExitStatus as anyhow context
+// where the failure is detected, e.g. `.context(ExitStatus::StorageError)`.
+// The outermost tag in the chain decides the exit code.
+//
+use std::fmt;
+
+/// Classified outcome of a command, mapped to its process exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitStatus {
+    Success,
+    Error,
+    ConfigError,
+    AuFail,
+    SloFail,
+    StorageError,
+    Interrupted,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> i32 {
+        match self {
+            Self::Success => 0,
+            Self::Error => 1,
+            Self::ConfigError => 2,
+            Self::AuFail => 3,
+            Self::SloFail => 4,
+            Self::StorageError => 5,
+            Self::Interrupted => 6,
+        }
+    }
+
+    /// Status for an exit code, e.g. a child rank's; codes outside the contract are `Error`
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => Self::Success,
+            2 => Self::ConfigError,
+            3 => Self::AuFail,
+            4 => Self::SloFail,
+            5 => Self::StorageError,
+            6 => Self::Interrupted,
+            _ => Self::Error,
+        }
+    }
+
+    /// Status of a failed command, from the outermost tag in its error chain
+    pub fn of(error: &anyhow::Error) -> Self {
+        error.downcast_ref::<Self>().copied().unwrap_or(Self::Error)
+    }
+
+    /// Stable machine-readable name (`--quiet` output)
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::ConfigError => "config_error",
+            Self::AuFail => "au_fail",
+            Self::SloFail => "slo_fail",
+            Self::StorageError => "storage_error",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Success => "success",
+            Self::Error => "failed",
+            Self::ConfigError => "configuration error",
+            Self::AuFail => "AU below threshold",
+            Self::SloFail => "acceptance check failed",
+            Self::StorageError => "storage error",
+            Self::Interrupted => "interrupted",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_status_from_error_chain() {
+        let storage: anyhow::Result<()> = Err(anyhow::anyhow!("GET failed")).context(ExitStatus::StorageError);
+        let wrapped = storage.context("Training workload failed").unwrap_err();
+        assert_eq!(ExitStatus::of(&wrapped), ExitStatus::StorageError);
+        assert_eq!(ExitStatus::of(&wrapped).code(), 5);
+        assert_eq!(format!("{:#}", wrapped), "Training workload failed: storage error: GET failed");
+
+        assert_eq!(ExitStatus::of(&anyhow::anyhow!("untagged")), ExitStatus::Error);
+        for code in 0..=6 {
+            assert_eq!(ExitStatus::from_code(code).code(), code);
+        }
+        assert_eq!(ExitStatus::from_code(137), ExitStatus::Error);
+    }
+}
@@ -0,0 +1,366 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/failover.rs
+//
+// Storage failover resilience test (`failover:` config block)
+//
+// Runs a steady training load whose reads retry instead of failing the run,
+// and measures the disruption around an operator-marked failover window:
+//
+//   failover-start / failover-end    control socket commands
+//   failover_start / failover_end    schedule actions
+//
+// Reported under "failover" in the results:
+//
+//   time_to_first_error_s   window start -> first failed read
+//   error_duration_s        first -> last failed read
+//   recovery_time_s         window end (or last error, if later) -> end of the first
+//                           throughput bucket back at recovery_fraction of the
+//                           pre-failover rate
+//
+// A read is retried for at most max_retry_s. Errors no retry can fix (missing
+// objects, denied access) fail the read at once.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::backends::DataBackend;
+use crate::control::RunControl;
+use crate::dlio_compat::DlioConfig;
+use crate::request_timing;
+
+/// Timeline action opening the failover window
+pub const FAILOVER_START: &str = "failover_start";
+/// Timeline action closing the failover window
+pub const FAILOVER_END: &str = "failover_end";
+
+/// Resolved `failover:` settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FailoverSettings {
+    pub bucket: Duration,
+    pub recovery_fraction: f64,
+    pub retry_delay: Duration,
+    pub max_retry: Duration,
+}
+
+impl FailoverSettings {
+    /// From the config's `failover:` block; None when the mode is off
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let Some(failover) = &config.failover else {
+            return Ok(None);
+        };
+        let recovery_fraction = failover.recovery_fraction.unwrap_or(0.9);
+        if !(recovery_fraction > 0.0 && recovery_fraction <= 1.0) {
+            anyhow::bail!("failover.recovery_fraction must be in (0, 1], got {}", recovery_fraction);
+        }
+        let bucket_ms = failover.bucket_ms.unwrap_or(1000);
+        if bucket_ms == 0 {
+            anyhow::bail!("failover.bucket_ms must be positive");
+        }
+        Ok(Some(Self {
+            bucket: Duration::from_millis(bucket_ms),
+            recovery_fraction,
+            retry_delay: Duration::from_millis(failover.retry_delay_ms.unwrap_or(100)),
+            max_retry: Duration::from_secs(failover.max_retry_s.unwrap_or(300)),
+        }))
+    }
+}
+
+/// Disruption measured around the failover window (offsets in seconds since training started)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FailoverReport {
+    pub window_start_s: Option<f64>,
+    pub window_end_s: Option<f64>,
+    pub errors: usize,
+    pub first_error_s: Option<f64>,
+    pub last_error_s: Option<f64>,
+    pub time_to_first_error_s: Option<f64>,
+    pub error_duration_s: Option<f64>,
+    pub baseline_throughput_mib_s: Option<f64>,
+    pub min_throughput_mib_s: Option<f64>,
+    pub recovery_time_s: Option<f64>,
+    pub recovered: bool,
+    pub last_error: Option<String>,
+}
+
+impl FailoverReport {
+    /// Compute the report from batch completions `(offset_s, bytes)` and error offsets
+    pub fn compute(
+        settings: &FailoverSettings,
+        batches: &[(f64, u64)],
+        errors: &[f64],
+        window_start: Option<f64>,
+        window_end: Option<f64>,
+    ) -> Self {
+        let first_error = errors.iter().copied().filter(|&t| t >= window_start.unwrap_or(0.0)).reduce(f64::min);
+        let last_error = errors.iter().copied().reduce(f64::max);
+        let mut report = Self {
+            window_start_s: window_start,
+            window_end_s: window_end,
+            errors: errors.len(),
+            first_error_s: first_error,
+            last_error_s: last_error,
+            time_to_first_error_s: window_start.zip(first_error).map(|(start, first)| first - start),
+            error_duration_s: first_error.zip(last_error).map(|(first, last)| (last - first).max(0.0)),
+            ..Default::default()
+        };
+
+        // Throughput per complete bucket; the run's tail bucket is partial and ignored
+        let bucket_s = settings.bucket.as_secs_f64();
+        let run_end = batches.iter().map(|&(t, _)| t).fold(0.0, f64::max);
+        let complete = (run_end / bucket_s).floor() as usize;
+        let mut buckets = vec![0u64; complete];
+        for &(t, bytes) in batches {
+            if let Some(b) = buckets.get_mut((t / bucket_s) as usize) {
+                *b += bytes;
+            }
+        }
+        let mib_s = |i: usize| crate::units::mib(buckets[i]) / bucket_s;
+
+        // Steady state: complete buckets before the disruption, skipping the warm-up bucket
+        let Some(disrupted_at) = window_start.or(first_error) else {
+            return report;
+        };
+        let before = ((disrupted_at / bucket_s).floor() as usize).min(complete);
+        let steady = if before >= 2 { 1..before } else { 0..before };
+        if steady.is_empty() {
+            return report;
+        }
+        let baseline = steady.clone().map(mib_s).sum::<f64>() / steady.len() as f64;
+        report.baseline_throughput_mib_s = Some(baseline);
+
+        let recover_from = match (window_end, last_error) {
+            (Some(end), Some(last)) => end.max(last),
+            (end, last) => end.or(last).unwrap_or(disrupted_at),
+        };
+        let mut min_throughput = f64::INFINITY;
+        for i in before..complete {
+            min_throughput = min_throughput.min(mib_s(i));
+            let bucket_end = (i + 1) as f64 * bucket_s;
+            if bucket_end > recover_from && mib_s(i) >= baseline * settings.recovery_fraction {
+                report.recovery_time_s = Some((bucket_end - recover_from).max(0.0));
+                report.recovered = true;
+                break;
+            }
+        }
+        report.min_throughput_mib_s = min_throughput.is_finite().then_some(min_throughput);
+        report
+    }
+
+    pub fn print_summary(&self) {
+        let secs = |v: Option<f64>| v.map_or("n/a".to_string(), |s| format!("{:.2}s", s));
+        info!("🧯 Failover window: {} -> {}", secs(self.window_start_s), secs(self.window_end_s));
+        info!(
+            "🧯 {} errors | time to first error {} | error duration {} | recovery {}",
+            self.errors, secs(self.time_to_first_error_s), secs(self.error_duration_s),
+            if self.recovered { secs(self.recovery_time_s) } else { "not recovered".to_string() }
+        );
+        if let (Some(baseline), Some(min)) = (self.baseline_throughput_mib_s, self.min_throughput_mib_s) {
+            info!("🧯 Throughput: baseline {:.1} MiB/s, minimum {:.1} MiB/s", baseline, min);
+        }
+    }
+}
+
+/// Collects batch completions and read errors during a failover test
+pub struct FailoverTracker {
+    settings: FailoverSettings,
+    control: Arc<RunControl>,
+    batches: Mutex<Vec<(f64, u64)>>,
+    errors: Mutex<Vec<f64>>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl FailoverTracker {
+    pub fn new(settings: FailoverSettings, control: Arc<RunControl>) -> Self {
+        Self { settings, control, batches: Mutex::new(Vec::new()), errors: Mutex::new(Vec::new()), last_error: Mutex::new(None) }
+    }
+
+    pub fn record_batch(&self, bytes: u64) {
+        self.batches.lock().unwrap().push((self.control.elapsed().as_secs_f64(), bytes));
+    }
+
+    pub fn record_error(&self, error: &anyhow::Error) {
+        self.errors.lock().unwrap().push(self.control.elapsed().as_secs_f64());
+        *self.last_error.lock().unwrap() = Some(format!("{:#}", error));
+    }
+
+    /// Report against the first failover window on the run's timeline
+    pub fn report(&self) -> FailoverReport {
+        let timeline = self.control.timeline();
+        let start = timeline.iter().find(|e| e.action == FAILOVER_START).map(|e| e.offset_s);
+        let end = start.and_then(|start| {
+            timeline.iter().find(|e| e.action == FAILOVER_END && e.offset_s >= start).map(|e| e.offset_s)
+        });
+        if start.is_none() {
+            warn!("Failover test ran without a failover_start mark; reporting against the first error");
+        }
+        let mut report = FailoverReport::compute(
+            &self.settings,
+            &self.batches.lock().unwrap(),
+            &self.errors.lock().unwrap(),
+            start,
+            end,
+        );
+        report.last_error = self.last_error.lock().unwrap().clone();
+        report
+    }
+}
+
+/// Markers (lowercase) of storage errors that no retry can fix
+const PERMANENT_ERRORS: &[&str] = &[
+    "nosuchkey", "nosuchbucket", "notfound", "not found", "accessdenied", "access denied", "forbidden",
+    "invalidaccesskeyid", "signaturedoesnotmatch",
+];
+
+/// Whether `error` is a missing object or denied access rather than an outage
+fn is_permanent(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            return matches!(io.kind(), std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied);
+        }
+        let message = cause.to_string().to_lowercase();
+        PERMANENT_ERRORS.iter().any(|marker| message.contains(marker))
+    })
+}
+
+/// Wraps the data backend so failed reads are recorded and retried instead of ending the run
+pub struct RetryingBackend {
+    inner: Arc<dyn DataBackend>,
+    tracker: Arc<FailoverTracker>,
+}
+
+impl RetryingBackend {
+    pub fn new(inner: Arc<dyn DataBackend>, tracker: Arc<FailoverTracker>) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+#[async_trait]
+impl DataBackend for RetryingBackend {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        self.inner.list().await
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let started = Instant::now();
+        loop {
+            match self.inner.get(uri).await {
+                Ok(data) => return Ok(data),
+                Err(e) => {
+                    self.tracker.record_error(&e);
+                    // A stopped run gives up rather than retrying forever
+                    if self.tracker.control.stop_requested() || is_permanent(&e) {
+                        return Err(e);
+                    }
+                    if started.elapsed() >= self.tracker.settings.max_retry {
+                        return Err(e.context(format!("{} still failing after {:?} of retries", uri, self.tracker.settings.max_retry)));
+                    }
+                    request_timing::note_retry();
+                    tokio::time::sleep(self.tracker.settings.retry_delay).await;
+                }
+            }
+        }
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.inner.put(uri, data).await
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.inner.report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failover_report_timings() {
+        let settings = FailoverSettings {
+            bucket: Duration::from_secs(1),
+            recovery_fraction: 0.9,
+            retry_delay: Duration::ZERO,
+            max_retry: Duration::from_secs(300),
+        };
+        // 1 MiB every 100 ms, nothing completes from 4.0s to 6.0s
+        let batches: Vec<(f64, u64)> = (0..100)
+            .filter(|i| !(40..60).contains(i))
+            .map(|i| (i as f64 / 10.0, 1_048_576))
+            .collect();
+        let errors = [4.25, 4.5, 5.75];
+
+        let report = FailoverReport::compute(&settings, &batches, &errors, Some(4.0), Some(5.0));
+        assert_eq!(report.errors, 3);
+        assert_eq!(report.time_to_first_error_s, Some(0.25));
+        assert_eq!(report.error_duration_s, Some(1.5));
+        assert_eq!(report.baseline_throughput_mib_s, Some(10.0));
+        assert_eq!(report.min_throughput_mib_s, Some(0.0));
+        // Recovery counts from the last error (after the window closed) to the end of the 6-7s bucket
+        assert!(report.recovered);
+        assert!((report.recovery_time_s.unwrap() - 1.25).abs() < 1e-9);
+
+        let quiet = FailoverReport::compute(&settings, &batches[..30], &[], None, None);
+        assert_eq!(quiet.errors, 0);
+        assert!(quiet.baseline_throughput_mib_s.is_none());
+    }
+
+    /// Fails every read with `error`, counting the attempts
+    struct FailingBackend {
+        error: &'static str,
+        attempts: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DataBackend for FailingBackend {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn list(&self) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn get(&self, _uri: &str) -> Result<Vec<u8>> {
+            self.attempts.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Err(anyhow::anyhow!(self.error))
+        }
+
+        async fn put(&self, _uri: &str, _data: &[u8]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_capped_and_skip_permanent_errors() {
+        let settings = FailoverSettings {
+            bucket: Duration::from_secs(1),
+            recovery_fraction: 0.9,
+            retry_delay: Duration::from_millis(5),
+            max_retry: Duration::from_millis(50),
+        };
+        let tracker = Arc::new(FailoverTracker::new(settings, Arc::new(RunControl::default())));
+        let retrying = |error| {
+            let inner = Arc::new(FailingBackend { error, attempts: Default::default() });
+            (RetryingBackend::new(Arc::clone(&inner) as Arc<dyn DataBackend>, Arc::clone(&tracker)), inner)
+        };
+
+        let (backend, inner) = retrying("connection reset by peer");
+        let error = backend.get("s3://bucket/a").await.unwrap_err();
+        assert!(format!("{:#}", error).contains("still failing"), "{:#}", error);
+        assert!(inner.attempts.load(std::sync::atomic::Ordering::Relaxed) > 1);
+
+        let (backend, inner) = retrying("service error: NoSuchKey");
+        assert!(backend.get("s3://bucket/b").await.is_err());
+        assert_eq!(inner.attempts.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,173 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/format_probe.rs
+//
+// Dataset format auto-detection for pre-existing datasets
+//
+// A few objects are sampled across the listing and their leading bytes sniffed.
+// An omitted `dataset.format` is filled in from the result; a configured format
+// that disagrees with the content fails before training starts.
+//
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::backends::{self, DataBackend, ObjectStoreBackend};
+use crate::dlio_compat::DlioConfig;
+use real_dlio_formats::{detect_format, DETECT_HEAD_BYTES};
+
+/// Objects sampled from the dataset listing
+pub const SAMPLE_OBJECTS: usize = 8;
+
+/// Formats that can be recognized from content; others (csv, ...) are not checked
+const DETECTABLE: &[&str] = &["npz", "hdf5", "tfrecord", "parquet", "jpeg", "png"];
+
+/// Result of sniffing a sample of dataset objects
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FormatProbe {
+    pub sampled: usize,
+    /// Objects per detected format name
+    pub counts: BTreeMap<String, usize>,
+    /// Sampled objects whose format was not recognized
+    pub unknown: Vec<String>,
+    /// One example object URI per detected format
+    pub examples: BTreeMap<String, String>,
+}
+
+impl FormatProbe {
+    /// Most common detected format, if any object was recognized
+    pub fn detected(&self) -> Option<&str> {
+        self.counts
+            .iter()
+            .max_by_key(|(_, &n)| n)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Sniff the format of up to `SAMPLE_OBJECTS` objects spread across the listing
+pub async fn sample(backend: &dyn DataBackend) -> Result<FormatProbe> {
+    let uris = backend.list().await?;
+    let step = (uris.len() / SAMPLE_OBJECTS).max(1);
+
+    let mut probe = FormatProbe::default();
+    for uri in uris.iter().step_by(step).take(SAMPLE_OBJECTS) {
+        let head = backend.head(uri, DETECT_HEAD_BYTES).await?;
+        probe.sampled += 1;
+        match detect_format(&head) {
+            Some(format) => {
+                *probe.counts.entry(format.name().to_string()).or_default() += 1;
+                probe.examples.entry(format.name().to_string()).or_insert_with(|| uri.clone());
+            }
+            None => probe.unknown.push(uri.clone()),
+        }
+    }
+    Ok(probe)
+}
+
+/// Apply a probe to the config: fill in an omitted format or reject a mismatch
+pub fn resolve(config: &mut DlioConfig, probe: &FormatProbe) -> Result<()> {
+    let configured = config.dataset.format.as_deref().map(str::to_lowercase);
+    let Some(detected) = probe.detected() else {
+        if configured.is_none() {
+            anyhow::bail!(
+                "dataset.format is not set and none of {} sampled objects in {} has a recognized format; \
+                 set dataset.format explicitly",
+                probe.sampled,
+                config.data_folder_uri()
+            );
+        }
+        return Ok(());
+    };
+
+    if probe.counts.len() > 1 {
+        warn!("⚠️  Dataset contains several formats: {:?}", probe.counts);
+    }
+
+    match configured {
+        None => {
+            info!("🔎 dataset.format not set; detected {} from {} sampled objects", detected, probe.sampled);
+            config.dataset.format = Some(detected.to_string());
+        }
+        Some(format) if DETECTABLE.contains(&format.as_str()) && !probe.counts.contains_key(&format) => {
+            anyhow::bail!(
+                "dataset.format is '{}' but {}/{} sampled objects in {} are {} (e.g. {}); \
+                 set dataset.format: {} or point data_folder at a {} dataset",
+                format,
+                probe.counts[detected],
+                probe.sampled,
+                config.data_folder_uri(),
+                detected,
+                probe.examples[detected],
+                detected,
+                format
+            );
+        }
+        Some(_) => {}
+    }
+    Ok(())
+}
+
+/// Backend over a pre-existing dataset; None when the run generates its own
+/// data or the backend synthesizes it
+pub fn existing_dataset(config: &DlioConfig, file_list: Option<&[String]>) -> Result<Option<Arc<dyn DataBackend>>> {
+    let uri = config.data_folder_uri().to_string();
+    if config.should_generate_data() || (backends::is_native_uri(&uri) && !backends::is_read_only_uri(&uri)) {
+        return Ok(None);
+    }
+    Ok(Some(match backends::backend_for_config(config, file_list)? {
+        Some(backend) => backend,
+        None => Arc::new(ObjectStoreBackend::new(&uri)?),
+    }))
+}
+
+/// Detect the format of an existing dataset and reconcile it with the config
+///
+/// Skipped when the run generates its own data or the backend synthesizes it.
+pub async fn check(config: &mut DlioConfig, file_list: Option<&[String]>) -> Result<Option<FormatProbe>> {
+    let Some(backend) = existing_dataset(config, file_list)? else {
+        return Ok(None);
+    };
+    let probe = sample(backend.as_ref()).await?;
+    resolve(config, &probe)?;
+    Ok(Some(probe))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(format: Option<&str>) -> DlioConfig {
+        let format = format.map(|f| format!("  format: {}\n", f)).unwrap_or_default();
+        let yaml = format!("dataset:\n  data_folder: s3://bucket/data\n{}reader: {{}}\n", format);
+        DlioConfig::from_yaml(&yaml).unwrap()
+    }
+
+    fn probe(tfrecord: usize, npz: usize) -> FormatProbe {
+        let mut probe = FormatProbe { sampled: tfrecord + npz, ..Default::default() };
+        for (name, n) in [("tfrecord", tfrecord), ("npz", npz)] {
+            if n > 0 {
+                probe.counts.insert(name.to_string(), n);
+                probe.examples.insert(name.to_string(), format!("s3://bucket/data/a.{}", name));
+            }
+        }
+        probe
+    }
+
+    #[test]
+    fn test_resolve_fills_and_rejects() {
+        let mut cfg = config(None);
+        resolve(&mut cfg, &probe(8, 0)).unwrap();
+        assert_eq!(cfg.dataset.format.as_deref(), Some("tfrecord"));
+
+        let err = resolve(&mut config(Some("npz")), &probe(8, 0)).unwrap_err().to_string();
+        assert!(err.contains("set dataset.format: tfrecord"), "{}", err);
+
+        // Undetectable configured formats and partial matches are left alone
+        resolve(&mut config(Some("jpeg")), &probe(8, 0)).unwrap();
+        resolve(&mut config(Some("npz")), &probe(6, 2)).unwrap();
+        assert!(resolve(&mut config(None), &FormatProbe { sampled: 3, ..Default::default() }).is_err());
+    }
+}
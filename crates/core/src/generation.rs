@@ -205,6 +205,9 @@ impl DatasetGenerator {
             "npz" => "npz",
             "hdf5" => "h5",
             "tfrecord" => "tfrecord",
+            "csv" => "csv",
+            "jpeg" | "jpg" => "jpeg",
+            "png" => "png",
             _ => "bin", // Default binary extension
         }
     }
@@ -237,10 +240,14 @@ mod tests {
                 num_samples_per_file: Some(10),
                 num_files_eval: Some(0),
                 compression: None,
+                record_dims: None,
+                record_element_type: None,
+                components: None,
             },
             reader: crate::dlio_compat::ReaderConfig {
                 data_loader: Some("pytorch".to_string()),
                 batch_size: Some(32),
+                batch_size_bytes: None,
                 read_threads: Some(4),
                 prefetch: None,
                 shuffle: Some(false),
@@ -248,6 +255,10 @@ mod tests {
                 transfer_size: None,
                 file_access_type: None,
                 seed: None,
+                drop_last: None,
+                shuffle_strategy: None,
+                shuffle_size: None,
+                thread_model: None,
             },
             checkpointing: None,
             profiling: None,
@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/gpu_burn.rs
+//
+// GPU burn kernel run during compute windows
+//
+// With `train.gpu_burn` set, every training step also keeps a real GPU busy
+// for the step's computation_time, so storage is measured under realistic
+// power, thermal, host and PCIe contention. `occupancy` (0-1] scales the grid
+// to a fraction of the device's SMs. Needs the `cuda` feature (CUDA driver and
+// NVRTC at runtime); without it a configured burn fails with a clear error.
+//
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::dlio_compat::GpuBurnConfig;
+
+/// Error text for GPU burn in builds without CUDA
+pub const GPU_BURN_DISABLED_MSG: &str =
+    "GPU burn is not compiled in: rebuild with `--features cuda` (requires the CUDA driver and NVRTC)";
+
+/// Resolved GPU burn settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuBurnSettings {
+    pub device: usize,
+    /// Fraction of the device's SMs given blocks
+    pub occupancy: f64,
+}
+
+impl GpuBurnSettings {
+    pub fn from_config(config: &GpuBurnConfig) -> Result<Self> {
+        let occupancy = config.occupancy.unwrap_or(1.0);
+        if !(occupancy > 0.0 && occupancy <= 1.0) {
+            anyhow::bail!("train.gpu_burn.occupancy must be in (0, 1], got {}", occupancy);
+        }
+        Ok(Self { device: config.device.unwrap_or(0), occupancy })
+    }
+}
+
+#[cfg(feature = "cuda")]
+mod imp {
+    use super::*;
+    use anyhow::Context;
+    use cudarc::driver::sys::CUdevice_attribute;
+    use cudarc::driver::{CudaDevice, CudaFunction, CudaSlice, LaunchAsync, LaunchConfig};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tracing::info;
+
+    const KERNEL_SRC: &str = r#"
+extern "C" __global__ void burn(float *out, unsigned int iters) {
+    unsigned int tid = blockIdx.x * blockDim.x + threadIdx.x;
+    float a = 1.0f + tid * 1e-7f, b = 0.999999f;
+    for (unsigned int i = 0; i < iters; i++) {
+        a = fmaf(a, b, 1e-7f);
+        b = fmaf(b, a, -1e-7f);
+    }
+    out[tid] = a + b;
+}
+"#;
+
+    const THREADS_PER_BLOCK: u32 = 256;
+    /// Resident blocks per SM at full occupancy
+    const BLOCKS_PER_SM: u32 = 8;
+
+    /// A calibrated burn kernel on one device
+    pub struct GpuBurn {
+        device: Arc<CudaDevice>,
+        kernel: CudaFunction,
+        out: Mutex<CudaSlice<f32>>,
+        blocks: u32,
+        /// Kernel loop iterations per second of wall time
+        iters_per_sec: f64,
+    }
+
+    impl GpuBurn {
+        pub fn new(settings: GpuBurnSettings) -> Result<Self> {
+            let device = CudaDevice::new(settings.device)
+                .with_context(|| format!("Failed to open CUDA device {}", settings.device))?;
+            let ptx = cudarc::nvrtc::compile_ptx(KERNEL_SRC).context("Failed to compile GPU burn kernel")?;
+            device.load_ptx(ptx, "dl_driver_burn", &["burn"]).context("Failed to load GPU burn kernel")?;
+            let kernel = device.get_func("dl_driver_burn", "burn").context("GPU burn kernel missing")?;
+
+            let sms = device
+                .attribute(CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MULTIPROCESSOR_COUNT)
+                .context("Failed to query SM count")? as u32;
+            let blocks = ((sms * BLOCKS_PER_SM) as f64 * settings.occupancy).ceil().max(1.0) as u32;
+            let out = device.alloc_zeros::<f32>((blocks * THREADS_PER_BLOCK) as usize)?;
+
+            let mut burn = Self { device, kernel, out: Mutex::new(out), blocks, iters_per_sec: 0.0 };
+            burn.launch(1_000)?; // warm up (JIT, context creation)
+            let calibration_iters = 200_000;
+            let start = Instant::now();
+            burn.launch(calibration_iters)?;
+            burn.iters_per_sec = calibration_iters as f64 / start.elapsed().as_secs_f64();
+            info!(
+                "🔥 GPU burn on device {}: {} blocks ({} SMs, occupancy {:.0}%), {:.0} iters/s",
+                settings.device, blocks, sms, settings.occupancy * 100.0, burn.iters_per_sec
+            );
+            Ok(burn)
+        }
+
+        /// Launch one kernel and wait for it to finish
+        fn launch(&self, iters: u32) -> Result<()> {
+            let config = LaunchConfig {
+                grid_dim: (self.blocks, 1, 1),
+                block_dim: (THREADS_PER_BLOCK, 1, 1),
+                shared_mem_bytes: 0,
+            };
+            let mut out = self.out.lock().unwrap();
+            // Safety: the kernel writes one float per thread and `out` holds blocks * threads floats
+            unsafe { self.kernel.clone().launch(config, (&mut *out, iters)) }.context("GPU burn launch failed")?;
+            self.device.synchronize().context("GPU burn kernel failed")?;
+            Ok(())
+        }
+
+        /// Keep the GPU busy for about `duration` (blocking)
+        pub fn burn(&self, duration: Duration) -> Result<()> {
+            let iters = (self.iters_per_sec * duration.as_secs_f64()).round().clamp(1.0, u32::MAX as f64) as u32;
+            self.launch(iters)
+        }
+    }
+}
+
+#[cfg(not(feature = "cuda"))]
+mod imp {
+    use super::*;
+
+    /// Placeholder in builds without CUDA; construction always fails
+    pub struct GpuBurn;
+
+    impl GpuBurn {
+        pub fn new(_settings: GpuBurnSettings) -> Result<Self> {
+            anyhow::bail!(GPU_BURN_DISABLED_MSG)
+        }
+
+        pub fn burn(&self, _duration: Duration) -> Result<()> {
+            anyhow::bail!(GPU_BURN_DISABLED_MSG)
+        }
+    }
+}
+
+pub use imp::GpuBurn;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_burn_settings() {
+        let settings = GpuBurnSettings::from_config(&GpuBurnConfig { occupancy: Some(0.5), device: None }).unwrap();
+        assert_eq!(settings, GpuBurnSettings { device: 0, occupancy: 0.5 });
+        assert!(GpuBurnSettings::from_config(&GpuBurnConfig { occupancy: Some(1.5), device: None }).is_err());
+
+        #[cfg(not(feature = "cuda"))]
+        {
+            let err = GpuBurn::new(settings).err().unwrap();
+            assert!(err.to_string().contains("--features cuda"));
+        }
+    }
+}
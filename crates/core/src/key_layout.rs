@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/key_layout.rs
+//
+// Object key naming for generated datasets (`dataset.key_layout`)
+//
+// Some namespaces degrade with deep prefixes or long keys, and S3-style stores
+// partition on key prefixes. The layout places each file under `prefix_depth`
+// prefix levels and pads its name to `key_length`:
+//
+//   sequential   0000/0003/train_file_000049.npz     consecutive files share
+//                                                      prefixes (`fanout` per level)
+//   random       9f3a/c41e/train_file_000049.npz     per-file hashed prefixes of
+//                                                      `prefix_width` hex chars, from `seed`
+//
+// Names keep the `train_file_NNNNNN` stem (padded with `_xxx...` before the
+// extension), so multi-component grouping and listing work unchanged. Keys are
+// deterministic; the resolved scheme is recorded under "key_layout" in results.
+//
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::components;
+use crate::dlio_compat::DlioConfig;
+
+/// S3's maximum object key length
+pub const MAX_KEY_LENGTH: usize = 1024;
+
+/// How prefix levels are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrefixMode {
+    Sequential,
+    Random,
+}
+
+/// Resolved key naming scheme
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyLayout {
+    pub prefix_depth: usize,
+    pub prefix_mode: PrefixMode,
+    /// Sequential: files per leaf prefix and prefixes per level
+    pub fanout: usize,
+    /// Random: hex characters per prefix level
+    pub prefix_width: usize,
+    /// Target key length (relative to the data folder) for the longest extension
+    pub key_length: Option<usize>,
+    pub seed: u64,
+    /// Longest object extension, which `key_length` is measured with
+    #[serde(skip)]
+    longest_suffix: usize,
+}
+
+impl Default for KeyLayout {
+    /// The flat `train_file_NNNNNN.<ext>` naming
+    fn default() -> Self {
+        Self {
+            prefix_depth: 0,
+            prefix_mode: PrefixMode::Sequential,
+            fanout: 16,
+            prefix_width: 4,
+            key_length: None,
+            seed: 0,
+            longest_suffix: 3,
+        }
+    }
+}
+
+impl KeyLayout {
+    /// Layout for the config; the flat default when `dataset.key_layout` is absent
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        let suffixes: Vec<String> = match components::components(config) {
+            Some(comps) => comps.iter().map(components::suffix).collect(),
+            None => vec![config.dataset.format.as_deref().unwrap_or("npz").to_string()],
+        };
+        let longest_suffix = suffixes.iter().map(String::len).max().unwrap_or(0);
+        let Some(settings) = &config.dataset.key_layout else {
+            return Ok(Self { longest_suffix, ..Self::default() });
+        };
+
+        let prefix_mode = match settings.prefix_mode.as_deref().unwrap_or("sequential") {
+            "sequential" => PrefixMode::Sequential,
+            "random" | "hashed" => PrefixMode::Random,
+            other => anyhow::bail!("Unknown dataset.key_layout.prefix_mode '{}' (expected sequential or random)", other),
+        };
+        let layout = Self {
+            prefix_depth: settings.prefix_depth.unwrap_or(0),
+            prefix_mode,
+            fanout: settings.fanout.unwrap_or(16),
+            prefix_width: settings.prefix_width.unwrap_or(4),
+            key_length: settings.key_length,
+            seed: settings.seed.or(config.reader.seed).unwrap_or(0),
+            longest_suffix,
+        };
+        if layout.fanout < 2 {
+            anyhow::bail!("dataset.key_layout.fanout must be at least 2");
+        }
+        if !(1..=16).contains(&layout.prefix_width) {
+            anyhow::bail!("dataset.key_layout.prefix_width must be 1..=16 hex characters");
+        }
+        if let Some(length) = layout.key_length {
+            let minimum = layout.unpadded_length(config.dataset.num_files_train.unwrap_or(100).saturating_sub(1));
+            if length < minimum || length > MAX_KEY_LENGTH {
+                anyhow::bail!(
+                    "dataset.key_layout.key_length {} must be between {} (the unpadded key) and {}",
+                    length, minimum, MAX_KEY_LENGTH
+                );
+            }
+        }
+        Ok(layout)
+    }
+
+    /// Whether this is the flat default `train_file_NNNNNN.<ext>` naming
+    pub fn is_flat(&self) -> bool {
+        self.prefix_depth == 0 && self.key_length.is_none()
+    }
+
+    /// Prefix levels of file `index`, without trailing '/'
+    fn prefixes(&self, index: usize) -> Vec<String> {
+        (0..self.prefix_depth)
+            .map(|level| match self.prefix_mode {
+                PrefixMode::Sequential => {
+                    // Level `level` counts groups of fanout^(depth - level) files
+                    let group = self.fanout.saturating_pow((self.prefix_depth - level) as u32);
+                    format!("{:04}", (index / group) % self.fanout)
+                }
+                PrefixMode::Random => {
+                    let mut state = self.seed ^ (index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ level as u64;
+                    let hash = crate::rng::splitmix64(&mut state);
+                    format!("{:016x}", hash)[..self.prefix_width].to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn unpadded_length(&self, index: usize) -> usize {
+        self.prefixes(index).iter().map(|p| p.len() + 1).sum::<usize>()
+            + format!("train_file_{:06}", index).len()
+            + 1
+            + self.longest_suffix
+    }
+
+    /// Object key of file `index` with extension `suffix`, relative to the data folder
+    pub fn key(&self, index: usize, suffix: &str) -> String {
+        let mut stem = format!("train_file_{:06}", index);
+        if let Some(length) = self.key_length {
+            let pad = length.saturating_sub(self.unpadded_length(index));
+            if pad > 0 {
+                stem.push('_');
+                stem.push_str(&"x".repeat(pad - 1));
+            }
+        }
+        let mut key: String = self.prefixes(index).iter().map(|p| format!("{}/", p)).collect();
+        key.push_str(&stem);
+        key.push('.');
+        key.push_str(suffix);
+        key
+    }
+
+    /// Full URI of file `index` under `data_folder`
+    pub fn uri(&self, data_folder: &str, index: usize, suffix: &str) -> String {
+        format!("{}/{}", data_folder.trim_end_matches('/'), self.key(index, suffix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(extra: &str) -> KeyLayout {
+        let yaml = format!(
+            "dataset:\n  data_folder: s3://b/d\n  format: npz\n  num_files_train: 100\n  key_layout: {{{}}}\nreader: {{}}\n",
+            extra
+        );
+        KeyLayout::from_config(&DlioConfig::from_yaml(&yaml).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_key_layouts() {
+        let flat = KeyLayout::default();
+        assert_eq!(flat.uri("s3://b/d/", 42, "npz"), "s3://b/d/train_file_000042.npz");
+
+        let sequential = layout("prefix_depth: 2, fanout: 4");
+        assert_eq!(sequential.key(0, "npz"), "0000/0000/train_file_000000.npz");
+        assert_eq!(sequential.key(5, "npz"), "0000/0001/train_file_000005.npz");
+        assert_eq!(sequential.key(17, "npz"), "0001/0000/train_file_000017.npz");
+
+        let random = layout("prefix_depth: 3, prefix_mode: random, prefix_width: 6, seed: 7, key_length: 120");
+        let key = random.key(9, "npz");
+        assert_eq!(key.len(), 120);
+        assert_eq!(key.split('/').count(), 4);
+        assert!(key.split('/').last().unwrap().starts_with("train_file_000009_x"));
+        assert_eq!(key, random.key(9, "npz"), "keys are deterministic");
+        assert_ne!(key[..6], random.key(10, "npz")[..6]);
+
+        let yaml = "dataset:\n  data_folder: s3://b/d\n  key_layout: {key_length: 10}\nreader: {}\n";
+        assert!(KeyLayout::from_config(&DlioConfig::from_yaml(yaml).unwrap()).is_err());
+    }
+}
@@ -3,7 +3,7 @@
 
 //! Core library for dl-driver ─ Enhanced with s3dlio data loading and DLIO compatibility.
 
-// Main DLIO compatibility module with train/metric support
+// Main DLIO compatibility module with train/metric support (config types from dl_driver_config)
 pub mod dlio_compat;
 
 // Multi-rank coordination using shared memory and atomics
@@ -18,6 +18,150 @@ pub mod plan;
 // pub mod generation;
 pub mod metrics;
 pub mod mlperf;
+// DirectIO alignment preflight and fallback policy
+pub mod directio;
+// Filesystem/mount characteristics probe for file:// targets
+pub mod mount_probe;
+// Backend capability matrix (range GET, multipart, consistency, listing) probed at run start
+pub mod capabilities;
+// Canary write/read/delete in the data and checkpoint folders (validate --probe)
+pub mod canary;
+// Lock object guarding a data folder during generation and cleanup (dataset.lock)
+pub mod dataset_lock;
+// Resumable generation: skip objects already written at their expected size (--skip-existing)
+pub mod resume;
+// Lustre/GPFS striping hints and reporting
+pub mod striping;
+// Multipart PUT / ranged GET tuning for large objects
+pub mod multipart;
+// Overwrite/churn workload for versioned buckets
+pub mod overwrite;
+// Native dl-driver backends for schemes s3dlio does not serve (http/https, mem, null, sim)
+pub mod backends;
+// Batch arrival timing record/replay
+pub mod arrivals;
+// s3dlio operation-log replay against other storage (`replay --op-log`)
+pub mod op_replay;
+// Storage operation log in s3dlio's op-log layout (`run --record-oplog`)
+pub mod oplog;
+// zstd-compressed results and traces, read back transparently (`run --compress`)
+pub mod artifacts;
+// Per-epoch and per-step metrics over time (--timeline)
+pub mod timeseries;
+// Side-by-side comparison against the reference DLIO implementation
+pub mod crosscheck;
+// Results conversion to MLPerf Storage reporter format
+pub mod mlperf_storage;
+// Dataset format auto-detection from object content
+pub mod format_probe;
+// Record-size discovery for existing datasets with unknown record_length
+pub mod record_sizes;
+// Seeded normal/lognormal record sizes for generated datasets (record_length_bytes_stdev)
+pub mod size_distribution;
+// Seeded, self-identifying sample content for end-to-end verification
+pub mod content;
+// Generated file contents per format (generate and the testing fixtures)
+pub mod payload;
+// Multi-component (mixed-format) datasets
+pub mod components;
+// Sample-based batching (files split into num_samples_per_file samples)
+pub mod batching;
+// Epoch shuffling: full permutation or tf.data-style shuffle buffer
+pub mod shuffle;
+// Per-rank results upload to (and aggregation from) object storage
+pub mod results_upload;
+// CSV export of aggregated multi-rank results (per-rank rows plus a global row)
+pub mod results_csv;
+// TCP barrier coordination for multi-node launches
+pub mod net_coordination;
+// Pluggable rank coordination backends (--coord shm:// or tcp://)
+pub mod coord_backend;
+// Benchmark suites: many configs, one combined report
+pub mod suite;
+// Repeat-and-average runs with outlier rejection
+pub mod repeats;
+// Shuffle seed sweep separating access-order sensitivity from storage noise (`run --seed-sweep`)
+pub mod seed_sweep;
+// Compute simulation models (sleep, busy-loop, matmul)
+pub mod compute;
+// Optional GPU burn kernel during compute windows (`cuda` feature)
+pub mod gpu_burn;
+// Live control socket: pause/resume, pacing, metrics dumps
+pub mod control;
+// Scheduled actions within a run (stress/failure scripting)
+pub mod schedule;
+// Storage failover resilience test (time to first error, recovery time)
+pub mod failover;
+// Per-request GET timing (TTFB, transfer, retries) via request-scope hooks
+pub mod request_timing;
+// Per-worker loader request spread (fairness across pool slots)
+pub mod worker_balance;
+// Loader pool telemetry sampled during training (`storage.pool_stats_interval_ms`)
+pub mod pool_stats;
+// Per-epoch cache vs backend byte attribution (`storage.cache_analysis`)
+pub mod cache_warming;
+// Growing-dataset simulation: files appended between epochs, refresh cost
+pub mod dataset_growth;
+// Data aging: migrate files to a cold tier between epochs, recall latency attribution
+pub mod tier_migration;
+// Checkpoint writes during training via s3dlio's CheckpointStore (kept out of AU)
+pub mod checkpoint;
+// Evaluation phase between epochs: eval split reads and eval compute, reported apart from training
+pub mod evaluation;
+// Epoch-boundary prefetch policy (drain, discard, carry-over) and discarded-read accounting
+pub mod epoch_boundary;
+// Bounded, cancellable queue between background loaders and the training loop
+pub mod batch_channel;
+// Checkpoint storm: synchronized multi-rank checkpoint bursts (burst bandwidth, tail latency, skew)
+pub mod checkpoint_storm;
+// Storage micro-benchmarks: put/get/stat/list/delete at fixed sizes and queue depths (`micro`)
+pub mod micro;
+// Client footprint limits: CPU affinity and cgroup v2 memory limit
+pub mod client_limits;
+// Network path to cloud backends: proxy, IP family, reachability probe
+pub mod network;
+// Resolved cloud context (endpoint, region, addressing, TLS, storage class) recorded in results
+pub mod cloud_context;
+// Server-side encryption (SSE-S3/SSE-KMS) for S3 writes, verified by HEAD
+pub mod encryption;
+// Background LIST load during training and its impact on training I/O
+pub mod list_interference;
+// Reader settings advisor: probe storage, suggest batch/threads/prefetch for the AU target
+pub mod advisor;
+// Dedup/compression analysis of dataset content (content-defined chunking, zstd)
+pub mod dedup_analysis;
+// Dataset snapshot/clone benchmark with pluggable clone hooks
+pub mod clone_bench;
+// Client-count scaling projection from single-host saturation results
+pub mod scaling;
+// Derived report metrics: per-accelerator sample rate, per-rank bandwidth, baseline efficiency
+pub mod derived;
+// Accelerators per rank (gpus_per_rank): per-step batch scaling and accelerator-weighted AU
+pub mod accelerators;
+// Per-rank computation times for mixed accelerator fleets (train.compute_profiles)
+pub mod compute_profiles;
+// Per-phase wall-clock breakdown (generate, list, train, checkpoint, teardown)
+pub mod phases;
+// Time to first batch: config parse, dataset listing and loader warmup
+pub mod startup;
+// Exit-code contract (config error, AU fail, SLO fail, storage error, interrupted)
+pub mod exit_status;
+// Byte/throughput units: decimal (MB/s) and binary (MiB/s) side by side
+pub mod units;
+// Documented PRNG (ChaCha8) and run -> rank -> epoch -> file seed derivation
+pub mod rng;
+// Results anonymization for sharing outside the organization
+pub mod redact;
+// Generated object key naming: prefix depth, hashed prefixes, key length
+pub mod key_layout;
+// Results database (`history`) with run-to-run change annotations
+pub mod results_db;
+// PR-ready run summaries and badges, uploaded for CI (`report publish`)
+pub mod publish;
+// Run manifest: config hashes, versions, environment and seeds (`run --manifest`)
+pub mod manifest;
+// Fixtures for testing plugins and storage backends out of tree
+pub mod testing;
 pub mod plugins;
 pub mod runner;
 pub mod workload;
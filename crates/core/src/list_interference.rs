@@ -0,0 +1,316 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/list_interference.rs
+//
+// LIST-while-training interference test (`list_interference:` config block)
+//
+// A background task lists the dataset prefix at `rate_per_s` while training
+// runs, emulating backup or indexing agents scanning the namespace. Listing
+// starts `start_after_s` into training (the clean baseline) and runs for
+// `duration_s` (default: until training ends). Training throughput and step
+// time (interval between consecutive batches) are compared between batches
+// completed inside the listing window and outside it; reported under
+// "list_interference" in the results.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::backends::{self, DataBackend};
+use crate::dlio_compat::DlioConfig;
+
+/// Resolved `list_interference:` settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListInterferenceSettings {
+    pub rate_per_s: f64,
+    /// Interval between lists, 1 / rate_per_s
+    pub tick: Duration,
+    pub start_after: Duration,
+    pub duration: Option<Duration>,
+    /// Prefix to list (default: the dataset's data folder)
+    pub prefix: String,
+}
+
+impl ListInterferenceSettings {
+    /// From the config's `list_interference:` block; None when the test is off
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let Some(settings) = &config.list_interference else {
+            return Ok(None);
+        };
+        let rate_per_s = settings.rate_per_s.unwrap_or(1.0);
+        let tick = Duration::try_from_secs_f64(1.0 / rate_per_s)
+            .ok()
+            .filter(|tick| !tick.is_zero())
+            .with_context(|| format!("list_interference.rate_per_s must be a positive, finite rate, got {}", rate_per_s))?;
+        let seconds = |name: &str, value: f64| {
+            Duration::try_from_secs_f64(value)
+                .with_context(|| format!("list_interference.{} must be a non-negative number of seconds, got {}", name, value))
+        };
+        Ok(Some(Self {
+            rate_per_s,
+            tick,
+            start_after: seconds("start_after_s", settings.start_after_s.unwrap_or(10.0))?,
+            duration: settings.duration_s.map(|d| seconds("duration_s", d)).transpose()?,
+            prefix: settings.prefix.clone().unwrap_or_else(|| config.data_folder_uri().to_string()),
+        }))
+    }
+}
+
+/// Mean and p99 of a set of millisecond samples
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl LatencySummary {
+    pub fn from_ms(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Self {
+            count: sorted.len(),
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p99_ms: sorted[((0.99 * (sorted.len() - 1) as f64) as usize).min(sorted.len() - 1)],
+        }
+    }
+}
+
+/// Training figures for the batches of one phase (baseline or under listing)
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct PhaseFigures {
+    pub seconds: f64,
+    pub batches: usize,
+    pub throughput_mib_s: f64,
+    pub step: LatencySummary,
+}
+
+/// One completed LIST
+#[derive(Debug, Clone, Copy)]
+pub struct ListSample {
+    pub offset_s: f64,
+    pub latency_ms: f64,
+    pub objects: usize,
+    pub ok: bool,
+}
+
+/// Impact of the background listing on training
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListInterferenceReport {
+    pub prefix: String,
+    pub rate_per_s: f64,
+    pub window_start_s: f64,
+    pub window_end_s: f64,
+    pub lists: usize,
+    pub list_errors: usize,
+    pub list_latency: LatencySummary,
+    pub objects_per_list: f64,
+    pub baseline: PhaseFigures,
+    pub during_listing: PhaseFigures,
+    /// (during - baseline) / baseline, in percent; None without baseline batches
+    pub throughput_delta_pct: Option<f64>,
+    pub step_mean_delta_ms: Option<f64>,
+    pub step_p99_delta_ms: Option<f64>,
+}
+
+impl ListInterferenceReport {
+    /// From batch completions `(offset_s, bytes)`, LIST samples and the listing window
+    pub fn compute(
+        settings: &ListInterferenceSettings,
+        batches: &[(f64, u64)],
+        lists: &[ListSample],
+        window: (f64, f64),
+    ) -> Self {
+        let run_end = batches.iter().map(|&(t, _)| t).fold(0.0, f64::max);
+        let (start, end) = (window.0.min(run_end), window.1.min(run_end));
+        let inside = |t: f64| t > start && t <= end;
+
+        let mut figures = [PhaseFigures::default(), PhaseFigures::default()];
+        let mut steps: [Vec<f64>; 2] = [Vec::new(), Vec::new()];
+        let mut bytes = [0u64; 2];
+        let mut previous = 0.0;
+        for &(t, b) in batches {
+            let phase = inside(t) as usize;
+            figures[phase].batches += 1;
+            bytes[phase] += b;
+            steps[phase].push((t - previous) * 1000.0);
+            previous = t;
+        }
+        figures[0].seconds = start + (run_end - end);
+        figures[1].seconds = end - start;
+        for (phase, f) in figures.iter_mut().enumerate() {
+            if f.seconds > 0.0 {
+                f.throughput_mib_s = crate::units::mib(bytes[phase]) / f.seconds;
+            }
+            f.step = LatencySummary::from_ms(&steps[phase]);
+        }
+        let [baseline, during_listing] = figures;
+
+        let ok: Vec<&ListSample> = lists.iter().filter(|l| l.ok).collect();
+        let has_baseline = baseline.batches > 0 && during_listing.batches > 0;
+        Self {
+            prefix: settings.prefix.clone(),
+            rate_per_s: settings.rate_per_s,
+            window_start_s: start,
+            window_end_s: end,
+            lists: lists.len(),
+            list_errors: lists.len() - ok.len(),
+            list_latency: LatencySummary::from_ms(&ok.iter().map(|l| l.latency_ms).collect::<Vec<_>>()),
+            objects_per_list: if ok.is_empty() { 0.0 } else { ok.iter().map(|l| l.objects).sum::<usize>() as f64 / ok.len() as f64 },
+            throughput_delta_pct: (has_baseline && baseline.throughput_mib_s > 0.0)
+                .then(|| (during_listing.throughput_mib_s - baseline.throughput_mib_s) / baseline.throughput_mib_s * 100.0),
+            step_mean_delta_ms: has_baseline.then(|| during_listing.step.mean_ms - baseline.step.mean_ms),
+            step_p99_delta_ms: has_baseline.then(|| during_listing.step.p99_ms - baseline.step.p99_ms),
+            baseline,
+            during_listing,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        info!(
+            "📇 LIST interference: {} lists of {} ({} errors, mean {:.1}ms, p99 {:.1}ms, {:.0} objects each) from {:.1}s to {:.1}s",
+            self.lists, self.prefix, self.list_errors, self.list_latency.mean_ms, self.list_latency.p99_ms,
+            self.objects_per_list, self.window_start_s, self.window_end_s
+        );
+        match (self.throughput_delta_pct, self.step_p99_delta_ms) {
+            (Some(throughput), Some(p99)) => info!(
+                "📇 Training: {:.1} -> {:.1} MiB/s ({:+.1}%), step p99 {:.1} -> {:.1}ms ({:+.1}ms)",
+                self.baseline.throughput_mib_s, self.during_listing.throughput_mib_s, throughput,
+                self.baseline.step.p99_ms, self.during_listing.step.p99_ms, p99
+            ),
+            _ => warn!("📇 No baseline batches outside the listing window; lower list_interference.start_after_s or train longer"),
+        }
+    }
+}
+
+/// Runs the background lister and collects training batch completions
+pub struct ListInterference {
+    settings: ListInterferenceSettings,
+    started: Instant,
+    batches: Mutex<Vec<(f64, u64)>>,
+    lists: Mutex<Vec<ListSample>>,
+    window: Mutex<Option<(f64, f64)>>,
+}
+
+impl ListInterference {
+    pub fn new(settings: ListInterferenceSettings) -> Self {
+        Self {
+            settings,
+            started: Instant::now(),
+            batches: Mutex::new(Vec::new()),
+            lists: Mutex::new(Vec::new()),
+            window: Mutex::new(None),
+        }
+    }
+
+    fn offset_s(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    pub fn record_batch(&self, bytes: u64) {
+        self.batches.lock().unwrap().push((self.offset_s(), bytes));
+    }
+
+    /// Start listing after the baseline period; abort the handle when training ends
+    pub fn spawn(self: &Arc<Self>, config: &DlioConfig) -> Result<JoinHandle<()>> {
+        let mut list_config = config.clone();
+        list_config.dataset.data_folder = self.settings.prefix.clone();
+        let lister: Arc<dyn DataBackend> = match backends::backend_for_config(&list_config, None)? {
+            Some(backend) => backend,
+            None => Arc::new(backends::ObjectStoreBackend::new(&self.settings.prefix)?),
+        };
+        let this = Arc::clone(self);
+        Ok(tokio::spawn(async move {
+            tokio::time::sleep(this.settings.start_after.saturating_sub(this.started.elapsed())).await;
+            let start = this.offset_s();
+            *this.window.lock().unwrap() = Some((start, f64::INFINITY));
+            info!("📇 Listing {} at {}/s during training", this.settings.prefix, this.settings.rate_per_s);
+
+            let mut ticks = tokio::time::interval(this.settings.tick);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                if this.settings.duration.is_some_and(|d| this.offset_s() - start >= d.as_secs_f64()) {
+                    break;
+                }
+                let list_start = Instant::now();
+                let outcome = lister.list().await;
+                let sample = ListSample {
+                    offset_s: this.offset_s(),
+                    latency_ms: list_start.elapsed().as_secs_f64() * 1000.0,
+                    objects: outcome.as_ref().map_or(0, Vec::len),
+                    ok: outcome.is_ok(),
+                };
+                if let Err(e) = outcome {
+                    warn!("Background LIST of {} failed: {:#}", this.settings.prefix, e);
+                }
+                this.lists.lock().unwrap().push(sample);
+            }
+            *this.window.lock().unwrap() = Some((start, this.offset_s()));
+        }))
+    }
+
+    /// Report for the run so far (a window still open ends at the last batch)
+    pub fn report(&self) -> ListInterferenceReport {
+        let window = self.window.lock().unwrap().unwrap_or((f64::INFINITY, f64::INFINITY));
+        ListInterferenceReport::compute(&self.settings, &self.batches.lock().unwrap(), &self.lists.lock().unwrap(), window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_reject_unrepresentable_durations() {
+        let settings = |block: &str| {
+            let yaml = format!("dataset:\n  data_folder: s3://b/d/\nreader: {{}}\nlist_interference: {}\n", block);
+            ListInterferenceSettings::from_config(&DlioConfig::from_yaml(&yaml).unwrap())
+        };
+        assert_eq!(settings("{rate_per_s: 4}").unwrap().unwrap().tick, Duration::from_millis(250));
+        assert!(settings("{rate_per_s: 1e-300}").is_err());
+        assert!(settings("{rate_per_s: 0}").is_err());
+        assert!(settings("{start_after_s: 1e300}").is_err());
+        assert!(settings("{duration_s: -1}").is_err());
+    }
+
+    #[test]
+    fn test_interference_deltas() {
+        let settings = ListInterferenceSettings {
+            rate_per_s: 2.0,
+            tick: Duration::from_millis(500),
+            start_after: Duration::from_secs(5),
+            duration: Some(Duration::from_secs(5)),
+            prefix: "s3://b/d/".into(),
+        };
+        // 1 MiB every 100 ms, slowed to every 200 ms while listing (5s-10s), then back
+        let mut batches = Vec::new();
+        let mut t = 0.0;
+        while t < 15.0 - 1e-9 {
+            t += if (5.0..10.0).contains(&t) { 0.2 } else { 0.1 };
+            batches.push((t, 1_048_576));
+        }
+        let lists: Vec<ListSample> = (0..10)
+            .map(|i| ListSample { offset_s: 5.0 + i as f64 * 0.5, latency_ms: 40.0, objects: 1000, ok: i != 3 })
+            .collect();
+
+        let report = ListInterferenceReport::compute(&settings, &batches, &lists, (5.0, 10.0));
+        assert_eq!((report.lists, report.list_errors), (10, 1));
+        assert_eq!(report.objects_per_list, 1000.0);
+        assert!((report.baseline.throughput_mib_s - 10.0).abs() < 0.3, "{:?}", report.baseline);
+        assert!((report.during_listing.throughput_mib_s - 5.0).abs() < 0.3, "{:?}", report.during_listing);
+        assert!((report.throughput_delta_pct.unwrap() + 50.0).abs() < 3.0);
+        assert!((report.step_mean_delta_ms.unwrap() - 100.0).abs() < 5.0);
+
+        let never = ListInterferenceReport::compute(&settings, &batches, &[], (f64::INFINITY, f64::INFINITY));
+        assert_eq!(never.during_listing.batches, 0);
+        assert!(never.throughput_delta_pct.is_none());
+    }
+}
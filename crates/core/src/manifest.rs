@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/manifest.rs
+//
+// Run manifest: what is needed to reproduce a rank's run (`run --manifest`)
+//
+// Results say how a run went; the manifest says exactly what ran:
+//
+//   config_sha256   SHA-256 of the config file as written
+//   config_hash     hash of the normalized config (the results' config_hash)
+//   config          the full normalized DlioConfig the run used, after
+//                   defaults, format substitution and --workdir anchoring
+//   versions        dl-driver and s3dlio
+//   environment     host, OS, architecture, CPUs, rank and world size
+//   cli_args        the command line, flags overriding the config included
+//   rng             run and rank seeds and the generators they feed
+//
+// Every rank's results carry the manifest under `manifest`; `--manifest`
+// also writes it to its own file. `aggregate` refuses to combine ranks whose
+// config files hash differently, since their metrics would not describe one
+// workload.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::warn;
+
+use crate::dlio_compat::DlioConfig;
+use crate::rng::RngReport;
+
+/// Host a rank ran on
+#[derive(Debug, Clone, Serialize)]
+pub struct Environment {
+    pub hostname: String,
+    pub os: String,
+    pub arch: String,
+    pub cpus: usize,
+    pub rank: u32,
+    pub world_size: u32,
+}
+
+/// `manifest` section of the results, and the `--manifest` file
+#[derive(Debug, Clone, Serialize)]
+pub struct RunManifest {
+    pub config_file: String,
+    pub config_sha256: String,
+    pub config_hash: String,
+    pub dl_driver_version: String,
+    pub s3dlio_version: String,
+    pub environment: Environment,
+    pub cli_args: Vec<String>,
+    /// Seeds of the training run; None when no training ran
+    pub rng: Option<RngReport>,
+    pub config: DlioConfig,
+}
+
+impl RunManifest {
+    /// Manifest of rank `rank` running `config`, parsed from `config_path`
+    pub fn new(config_path: &Path, config: &DlioConfig, rank: u32, world_size: u32, cli_args: Vec<String>) -> Result<Self> {
+        let yaml = std::fs::read(config_path).with_context(|| format!("Failed to read config {:?}", config_path))?;
+        Ok(Self {
+            config_file: config_path.to_string_lossy().into_owned(),
+            config_sha256: sha256_hex(&yaml),
+            config_hash: crate::results_db::config_hash(config),
+            dl_driver_version: env!("CARGO_PKG_VERSION").to_string(),
+            s3dlio_version: crate::results_db::S3DLIO_VERSION.to_string(),
+            environment: Environment {
+                hostname: crate::metrics::host_name(),
+                os: std::env::consts::OS.to_string(),
+                arch: std::env::consts::ARCH.to_string(),
+                cpus: num_cpus::get(),
+                rank,
+                world_size,
+            },
+            cli_args,
+            rng: None,
+            config: config.clone(),
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write run manifest to {:?}", path))
+    }
+}
+
+/// Lowercase hex SHA-256
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The config SHA-256 shared by every rank result's manifest
+///
+/// None when no result carries a manifest (older results); an error naming
+/// the files of each hash when they differ.
+pub fn verify_config_hashes(ranks: &[(String, Value)]) -> Result<Option<String>> {
+    let mut by_hash: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut missing = 0;
+    for (name, data) in ranks {
+        match data["manifest"]["config_sha256"].as_str() {
+            Some(hash) => by_hash.entry(hash).or_default().push(name),
+            None => missing += 1,
+        }
+    }
+    if by_hash.len() > 1 {
+        let groups: Vec<String> = by_hash
+            .iter()
+            .map(|(hash, names)| format!("{}… ({})", hash.chars().take(12).collect::<String>(), names.join(", ")))
+            .collect();
+        anyhow::bail!("Rank results come from different config files: {}", groups.join("; "));
+    }
+    if missing > 0 && !by_hash.is_empty() {
+        warn!("⚠️  {} of {} rank results have no run manifest; their config could not be checked", missing, ranks.len());
+    }
+    Ok(by_hash.into_keys().next().map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn test_manifest_hashes_config_and_aggregate_check() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("unet3d.yaml");
+        let builder = ConfigBuilder::new("mem://bench");
+        let yaml = builder.to_yaml();
+        std::fs::write(&path, &yaml).unwrap();
+        let config = builder.build().unwrap();
+        let manifest = RunManifest::new(&path, &config, 1, 4, vec!["run".to_string(), "--rank".to_string(), "1".to_string()]).unwrap();
+        assert_eq!(manifest.config_sha256, sha256_hex(yaml.as_bytes()));
+        assert_eq!((manifest.environment.rank, manifest.environment.world_size), (1, 4));
+        let written = dir.path().join("manifest.json");
+        manifest.write(&written).unwrap();
+        let value: Value = serde_json::from_str(&std::fs::read_to_string(&written).unwrap()).unwrap();
+        assert_eq!(value["config"]["dataset"]["data_folder"], "mem://bench");
+        assert_eq!(value["cli_args"][1], "--rank");
+
+        let rank = |name: &str, hash: Option<&str>| {
+            let data = match hash {
+                Some(hash) => json!({"manifest": {"config_sha256": hash}}),
+                None => json!({}),
+            };
+            (name.to_string(), data)
+        };
+        let same = [rank("rank_0.json", Some("aa")), rank("rank_1.json", Some("aa")), rank("rank_2.json", None)];
+        assert_eq!(verify_config_hashes(&same).unwrap().as_deref(), Some("aa"));
+        assert_eq!(verify_config_hashes(&[rank("old.json", None)]).unwrap(), None);
+        let mixed = [rank("rank_0.json", Some("aa")), rank("rank_1.json", Some("bb"))];
+        let err = verify_config_hashes(&mixed).unwrap_err().to_string();
+        assert!(err.contains("rank_0.json") && err.contains("rank_1.json"), "{}", err);
+    }
+}
@@ -6,6 +6,7 @@ use std::sync::Mutex;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use crate::dlio_compat::DlioConfig;
+use crate::units::{self, Throughput};
 
 /// Performance metrics collection with interior mutability for Arc compatibility
 #[derive(Debug, Default)]
@@ -17,14 +18,30 @@ pub struct Metrics {
 struct MetricsData {
     pub total_time: Option<Duration>,
     pub read_times: Vec<Duration>,        // Pure I/O times
+    pub wait_times: Vec<Duration>,        // Training loop waiting on the loader for each batch
     pub write_times: Vec<Duration>,
     pub compute_times: Vec<Duration>,     // Pure computation times
     pub batch_times: Vec<Duration>,       // Total batch times (I/O + compute)
     pub epoch_times: Vec<Duration>,       // Per-epoch times
+    pub epoch_compute_times: Vec<Duration>, // Per-epoch computation times
+    pub decode_times: Vec<Duration>,      // Per-sample full decode times (sampled validation)
+    pub checkpoint_times: Vec<Duration>,  // Checkpoint write stalls (kept out of epoch times)
+    pub checkpoint_bytes: u64,
     pub files_processed: u64,
     pub bytes_read: u64,
+    pub sample_bytes: u64,                // Bytes of the samples delivered (bytes_read counts whole files read)
     pub bytes_written: u64,
     pub batches_processed: u64,
+    pub samples_read: u64,                // Training samples consumed (batch_size counts samples)
+}
+
+/// Per-stage latencies in milliseconds, in recording order
+#[derive(Debug, Clone, Default)]
+pub struct StageLatencies {
+    pub batch_ms: Vec<f64>,
+    /// Time each step waited for its batch
+    pub wait_ms: Vec<f64>,
+    pub decode_ms: Vec<f64>,
 }
 
 /// Result of Accelerator Utilization calculation
@@ -72,10 +89,19 @@ impl Metrics {
         self.data.lock().unwrap().bytes_read
     }
 
+    pub fn samples_read(&self) -> u64 {
+        self.data.lock().unwrap().samples_read
+    }
+
     pub fn bytes_written(&self) -> u64 {
         self.data.lock().unwrap().bytes_written
     }
 
+    /// Epochs with a recorded epoch time
+    pub fn epochs_completed(&self) -> usize {
+        self.data.lock().unwrap().epoch_times.len()
+    }
+
     pub fn total_time(&self) -> Option<Duration> {
         self.data.lock().unwrap().total_time
     }
@@ -99,6 +125,31 @@ impl Metrics {
         data.bytes_read += bytes;
     }
 
+    /// Record the bytes of the samples one batch delivered, apart from the bytes read for it
+    pub fn record_sample_bytes(&self, bytes: u64) {
+        let mut data = self.data.lock().unwrap();
+        data.sample_bytes += bytes;
+    }
+
+    /// Record training samples consumed by one batch
+    pub fn record_samples_read(&self, samples: u64) {
+        let mut data = self.data.lock().unwrap();
+        data.samples_read += samples;
+    }
+
+    /// Record how long the training loop waited for a batch
+    pub fn record_wait_time(&self, duration: Duration) {
+        let mut data = self.data.lock().unwrap();
+        data.wait_times.push(duration);
+    }
+
+    /// Batch, wait and decode latencies, for percentile reports (MLPerf)
+    pub fn stage_latencies_ms(&self) -> StageLatencies {
+        let data = self.data.lock().unwrap();
+        let ms = |times: &[Duration]| times.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        StageLatencies { batch_ms: ms(&data.batch_times), wait_ms: ms(&data.wait_times), decode_ms: ms(&data.decode_times) }
+    }
+
     /// Record computation time (GPU simulation)
     pub fn record_compute_time(&self, duration: Duration) {
         let mut data = self.data.lock().unwrap();
@@ -117,6 +168,25 @@ impl Metrics {
         data.epoch_times.push(duration);
     }
 
+    /// Record total computation time for one epoch (per-epoch AU)
+    pub fn record_epoch_compute_time(&self, duration: Duration) {
+        let mut data = self.data.lock().unwrap();
+        data.epoch_compute_times.push(duration);
+    }
+
+    /// Record the full decode time of one validated sample
+    pub fn record_decode_time(&self, duration: Duration) {
+        let mut data = self.data.lock().unwrap();
+        data.decode_times.push(duration);
+    }
+
+    /// Record one checkpoint write; its stall is not part of any epoch time
+    pub fn record_checkpoint_write(&self, bytes: u64, duration: Duration) {
+        let mut data = self.data.lock().unwrap();
+        data.checkpoint_bytes += bytes;
+        data.checkpoint_times.push(duration);
+    }
+
     /// Record bytes written
     pub fn record_bytes_written(&self, bytes: u64) {
         let mut data = self.data.lock().unwrap();
@@ -137,20 +207,17 @@ impl Metrics {
         println!("\n=== Performance Summary ===");
         println!("Files processed: {}", data.files_processed);
         println!("Batches processed: {}", data.batches_processed);
-        println!("Bytes written: {} MB", data.bytes_written / 1024 / 1024);
-        println!("Bytes read: {} MB", data.bytes_read / 1024 / 1024);
+        println!("Samples processed: {}", data.samples_read);
+        println!("Bytes written: {}", units::fmt_bytes(data.bytes_written));
+        println!("Bytes read: {}", units::fmt_bytes(data.bytes_read));
 
         if !data.write_times.is_empty() {
             let avg_write =
                 data.write_times.iter().sum::<Duration>() / data.write_times.len() as u32;
             let total_write_time = data.write_times.iter().sum::<Duration>();
-            let write_throughput = if total_write_time.as_secs_f64() > 0.0 {
-                (data.bytes_written as f64) / (1024.0 * 1024.0) / total_write_time.as_secs_f64()
-            } else {
-                0.0
-            };
+            let write_throughput = Throughput::new(data.bytes_written, total_write_time.as_secs_f64());
             println!("Average write time: {:?}", avg_write);
-            println!("Write throughput: {:.2} MB/s", write_throughput);
+            println!("Write throughput: {}", write_throughput.display());
         }
 
         if !data.read_times.is_empty() {
@@ -165,17 +232,11 @@ impl Metrics {
                 data.total_time.unwrap_or(Duration::from_secs(1)) // Fallback to 1 second
             };
             
-            let storage_throughput_mbps = if wall_clock_time.as_secs_f64() > 0.0 {
-                (data.bytes_read as f64) / (1024.0 * 1024.0) / wall_clock_time.as_secs_f64()
-            } else {
-                0.0
-            };
-            
-            let storage_throughput_gibps = storage_throughput_mbps / 1024.0; // Convert MB/s to GiB/s
+            let storage_throughput = Throughput::new(data.bytes_read, wall_clock_time.as_secs_f64());
             
             println!("Average read time: {:?}", avg_read);
-            println!("Read throughput: {:.2} MB/s ({:.2} GiB/s) [STORAGE WALL-CLOCK]", 
-                     storage_throughput_mbps, storage_throughput_gibps);
+            println!("Read throughput: {:.2} MiB/s, {:.2} MB/s ({:.2} GiB/s, {:.2} GB/s) [STORAGE WALL-CLOCK]", 
+                     storage_throughput.mib_s, storage_throughput.mb_s, storage_throughput.gib_s, storage_throughput.gb_s);
         }
 
         // Enhanced timing breakdown
@@ -193,6 +254,19 @@ impl Metrics {
             println!("Average batch time: {:?}", avg_batch);
         }
 
+        if !data.decode_times.is_empty() {
+            let total_decode = data.decode_times.iter().sum::<Duration>();
+            let avg_decode = total_decode / data.decode_times.len() as u32;
+            println!("Samples decoded for validation: {}", data.decode_times.len());
+            println!("Average decode time: {:?}", avg_decode);
+        }
+
+        if !data.checkpoint_times.is_empty() {
+            let total_checkpoint = data.checkpoint_times.iter().sum::<Duration>();
+            println!("Checkpoints written: {} ({}) in {:?}",
+                     data.checkpoint_times.len(), units::fmt_bytes(data.checkpoint_bytes), total_checkpoint);
+        }
+
         if !data.epoch_times.is_empty() {
             let total_epoch = data.epoch_times.iter().sum::<Duration>();
             let avg_epoch = total_epoch / data.epoch_times.len() as u32;
@@ -222,12 +296,12 @@ impl Metrics {
         Some(total / data.write_times.len() as u32)
     }
 
-    pub fn read_throughput_mbps(&self) -> Option<f64> {
+    /// Read throughput in MiB/s
+    pub fn read_throughput_mib_s(&self) -> Option<f64> {
         if let Some(avg_time) = self.average_read_time() {
             let bytes_read = self.bytes_read();
             if avg_time.as_secs_f64() > 0.0 && bytes_read > 0 {
-                let mb_per_sec = (bytes_read as f64) / (1024.0 * 1024.0) / avg_time.as_secs_f64();
-                Some(mb_per_sec)
+                Some(Throughput::new(bytes_read, avg_time.as_secs_f64()).mib_s)
             } else {
                 Some(0.0)
             }
@@ -236,13 +310,12 @@ impl Metrics {
         }
     }
 
-    pub fn write_throughput_mbps(&self) -> Option<f64> {
+    /// Write throughput in MiB/s
+    pub fn write_throughput_mib_s(&self) -> Option<f64> {
         if let Some(avg_time) = self.average_write_time() {
             let bytes_written = self.bytes_written();
             if avg_time.as_secs_f64() > 0.0 && bytes_written > 0 {
-                let mb_per_sec =
-                    (bytes_written as f64) / (1024.0 * 1024.0) / avg_time.as_secs_f64();
-                Some(mb_per_sec)
+                Some(Throughput::new(bytes_written, avg_time.as_secs_f64()).mib_s)
             } else {
                 Some(0.0)
             }
@@ -303,13 +376,11 @@ impl Metrics {
         let total_read_time: Duration = data.read_times.iter().sum();
         let total_compute_time: Duration = data.compute_times.iter().sum();
         let total_batch_time: Duration = data.batch_times.iter().sum();
+        let total_decode_time: Duration = data.decode_times.iter().sum();
+        let total_checkpoint_time: Duration = data.checkpoint_times.iter().sum();
         let wall_clock_time = data.epoch_times.iter().sum::<Duration>();
         
-        let throughput_gib_s = if wall_clock_time.as_secs_f64() > 0.0 {
-            (data.bytes_read as f64) / (1024.0_f64.powi(3)) / wall_clock_time.as_secs_f64()
-        } else {
-            0.0
-        };
+        let throughput = Throughput::new(data.bytes_read, wall_clock_time.as_secs_f64());
         
         // Calculate AU if we have the data
         let au_result = if !data.compute_times.is_empty() && !data.batch_times.is_empty() {
@@ -320,28 +391,48 @@ impl Metrics {
         
         serde_json::json!({
             "rank": rank,
+            "hostname": host_name(),
+            "dl_driver_version": env!("CARGO_PKG_VERSION"),
+            "s3dlio_version": crate::results_db::S3DLIO_VERSION,
+            "config_hash": crate::results_db::config_hash(config),
             "timestamp": now,
             "start_time": now - wall_clock_time.as_secs_f64(),
             "end_time": now,
             "config": {
                 "data_folder": config.data_folder_uri(),
                 "batch_size": config.reader.batch_size.unwrap_or(1),
+                "batch_size_bytes": config.reader.batch_size_bytes,
                 "epochs": config.train.as_ref().and_then(|t| t.epochs).unwrap_or(1),
-                "computation_time": config.train.as_ref().and_then(|t| t.computation_time).unwrap_or(0.1)
+                "computation_time": config.train.as_ref().and_then(|t| t.computation_time).unwrap_or(0.1),
+                "computation_model": config.train.as_ref().and_then(|t| t.computation_model.clone()).unwrap_or_else(|| "sleep".to_string()),
+                "model": config.model.as_ref().and_then(|m| m.name.clone()),
+                "num_files_train": config.dataset.num_files_train,
+                "num_samples_per_file": config.dataset.num_samples_per_file.unwrap_or(1),
+                "record_length_bytes": config.dataset.record_length_bytes.unwrap_or(1024)
             },
             "metrics": {
                 "files_processed": data.files_processed,
                 "bytes_read": data.bytes_read,
+                "sample_bytes": data.sample_bytes,
                 "bytes_written": data.bytes_written,
                 "batches_processed": data.batches_processed,
-                "storage_throughput_gib_s": throughput_gib_s,
+                "samples_processed": data.samples_read,
+                "samples_per_second": if wall_clock_time.as_secs_f64() > 0.0 {
+                    data.samples_read as f64 / wall_clock_time.as_secs_f64()
+                } else { 0.0 },
+                "storage_throughput_gib_s": throughput.gib_s,
+                "storage_throughput": throughput,
                 "total_read_time_ms": total_read_time.as_millis(),
                 "total_compute_time_ms": total_compute_time.as_millis(),
                 "total_batch_time_ms": total_batch_time.as_millis(),
+                "total_decode_time_ms": total_decode_time.as_millis(),
                 "wall_clock_time_ms": wall_clock_time.as_millis(),
                 "average_batch_time_ms": if !data.batch_times.is_empty() {
                     total_batch_time.as_millis() / data.batch_times.len() as u128
                 } else { 0 },
+                "checkpoints_written": data.checkpoint_times.len(),
+                "checkpoint_bytes_written": data.checkpoint_bytes,
+                "total_checkpoint_time_ms": total_checkpoint_time.as_millis(),
                 "au_fraction": au_result.au_fraction,
                 "au_percent": au_result.au_percent,
                 "au_pass": au_result.pass
@@ -350,7 +441,9 @@ impl Metrics {
                 "read_times_ms": data.read_times.iter().map(|d| d.as_millis()).collect::<Vec<_>>(),
                 "compute_times_ms": data.compute_times.iter().map(|d| d.as_millis()).collect::<Vec<_>>(),
                 "batch_times_ms": data.batch_times.iter().map(|d| d.as_millis()).collect::<Vec<_>>(),
-                "epoch_times_ms": data.epoch_times.iter().map(|d| d.as_millis()).collect::<Vec<_>>()
+                "epoch_times_ms": data.epoch_times.iter().map(|d| d.as_millis()).collect::<Vec<_>>(),
+                "epoch_compute_times_ms": data.epoch_compute_times.iter().map(|d| d.as_millis()).collect::<Vec<_>>(),
+                "decode_times_us": data.decode_times.iter().map(|d| d.as_micros()).collect::<Vec<_>>()
             }
         })
     }
@@ -376,6 +469,16 @@ impl Metrics {
     }
 }
 
+/// Host name for per-host result grouping
+pub fn host_name() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|h| h.trim().to_string())
+        .ok()
+        .filter(|h| !h.is_empty())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
 /// Enhanced async metrics for workload benchmarking
 #[derive(Debug)]
 pub struct WorkloadMetrics {
@@ -464,7 +567,7 @@ impl WorkloadMetrics {
 
         println!("Total Batches: {}", data.total_batches);
         println!("Total Samples: {}", data.total_samples);
-        println!("Total Bytes: {} MB", data.total_bytes / 1024 / 1024);
+        println!("Total Bytes: {}", units::fmt_bytes(data.total_bytes));
 
         if !data.batch_times.is_empty() {
             let avg_batch_time =
@@ -0,0 +1,339 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/micro.rs
+//
+// Storage micro-benchmarks (`dl-driver micro`)
+//
+// Quick triage before a full DLIO workload: time-bounded put, get, stat, list
+// and delete phases against any s3dlio-served URI (file://, direct://, s3://,
+// az://, gs://), for every object size × queue depth combination. Each
+// combination works in its own prefix, `<uri>/micro_<size>_qd<n>/`:
+//
+//   put      writes new objects until the duration elapses
+//   get/stat cycle over the objects put wrote
+//   list     lists the prefix repeatedly
+//   delete   removes the objects; any left over are removed untimed afterwards
+//
+// Without put, get/stat/delete work on objects written untimed beforehand.
+// Jobs print as fio-style lines and are reported under "micro".
+//
+use anyhow::{Context, Result};
+use futures::future::try_join_all;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::request_timing::TimingSummary;
+use crate::units::Throughput;
+use s3dlio::object_store::{store_for_uri, ObjectStore};
+
+/// Objects per worker written untimed when get/stat/delete run without put
+const PREFILL_PER_WORKER: usize = 16;
+
+/// One micro-benchmark primitive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MicroOp {
+    Put,
+    Get,
+    Stat,
+    List,
+    Delete,
+}
+
+impl MicroOp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MicroOp::Put => "put",
+            MicroOp::Get => "get",
+            MicroOp::Stat => "stat",
+            MicroOp::List => "list",
+            MicroOp::Delete => "delete",
+        }
+    }
+}
+
+impl std::str::FromStr for MicroOp {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "put" | "write" => Ok(MicroOp::Put),
+            "get" | "read" => Ok(MicroOp::Get),
+            "stat" | "head" => Ok(MicroOp::Stat),
+            "list" => Ok(MicroOp::List),
+            "delete" | "rm" => Ok(MicroOp::Delete),
+            other => Err(format!("unknown operation '{}' (expected put, get, stat, list or delete)", other)),
+        }
+    }
+}
+
+/// Parse an object size: bytes, or k/m/g with binary units as in fio (4k, 1m, 64KiB)
+pub fn parse_size(value: &str) -> Result<usize, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let shift = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        _ => return Err(format!("invalid size '{}' (expected e.g. 4096, 4k, 1m)", value)),
+    };
+    number
+        .parse::<usize>()
+        .map(|n| n << shift)
+        .map_err(|_| format!("invalid size '{}' (expected e.g. 4096, 4k, 1m)", value))
+}
+
+/// Size as fio prints block sizes (4k, 1m, 100)
+fn size_label(bytes: usize) -> String {
+    match bytes {
+        0 => "0".to_string(),
+        b if b % (1 << 30) == 0 => format!("{}g", b >> 30),
+        b if b % (1 << 20) == 0 => format!("{}m", b >> 20),
+        b if b % (1 << 10) == 0 => format!("{}k", b >> 10),
+        b => b.to_string(),
+    }
+}
+
+/// What to run and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicroSettings {
+    pub uri: String,
+    /// In phase order, without duplicates
+    pub ops: Vec<MicroOp>,
+    pub sizes: Vec<usize>,
+    pub queue_depths: Vec<usize>,
+    /// Length of each timed phase
+    pub duration: Duration,
+}
+
+impl MicroSettings {
+    pub fn new(uri: &str, mut ops: Vec<MicroOp>, sizes: Vec<usize>, queue_depths: Vec<usize>, duration: Duration) -> Result<Self> {
+        ops.sort();
+        ops.dedup();
+        if ops.is_empty() || sizes.is_empty() || queue_depths.is_empty() {
+            anyhow::bail!("micro needs at least one operation, size and queue depth");
+        }
+        if queue_depths.contains(&0) {
+            anyhow::bail!("queue depths must be positive");
+        }
+        if duration.is_zero() {
+            anyhow::bail!("micro duration must be positive");
+        }
+        Ok(Self { uri: uri.trim_end_matches('/').to_string(), ops, sizes, queue_depths, duration })
+    }
+}
+
+/// One operation at one size and queue depth
+#[derive(Debug, Clone, Serialize)]
+pub struct MicroJob {
+    pub op: MicroOp,
+    pub size_bytes: usize,
+    pub queue_depth: usize,
+    pub ops: usize,
+    pub bytes: u64,
+    pub seconds: f64,
+    pub iops: f64,
+    pub throughput: Throughput,
+    pub latency: Option<TimingSummary>,
+}
+
+impl MicroJob {
+    /// fio-style summary line
+    pub fn fio_line(&self) -> String {
+        let lat = self.latency.clone().unwrap_or_default();
+        format!(
+            "{:<6} bs={:<10} qd={:<4}: IOPS={:.1}, BW={} ({} ops in {:.2}s)  lat (ms): avg={:.3}, p50={:.3}, p99={:.3}, max={:.3}",
+            self.op.name(), size_label(self.size_bytes), self.queue_depth, self.iops, self.throughput.display(),
+            self.ops, self.seconds, lat.mean_ms, lat.p50_ms, lat.p99_ms, lat.max_ms
+        )
+    }
+}
+
+/// Micro-benchmark section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct MicroReport {
+    pub uri: String,
+    pub duration_s: f64,
+    pub jobs: Vec<MicroJob>,
+}
+
+/// Latencies and bytes of one phase
+struct Phase {
+    latencies: Vec<Duration>,
+    bytes: u64,
+    elapsed: Duration,
+}
+
+impl Phase {
+    fn job(self, op: MicroOp, size_bytes: usize, queue_depth: usize) -> MicroJob {
+        let seconds = self.elapsed.as_secs_f64();
+        let job = MicroJob {
+            op,
+            size_bytes,
+            queue_depth,
+            ops: self.latencies.len(),
+            bytes: self.bytes,
+            seconds,
+            iops: if seconds > 0.0 { self.latencies.len() as f64 / seconds } else { 0.0 },
+            throughput: Throughput::new(self.bytes, seconds),
+            latency: TimingSummary::from_ms(self.latencies.iter().map(|d| d.as_secs_f64() * 1000.0).collect()),
+        };
+        info!("{}", job.fio_line());
+        job
+    }
+}
+
+/// Run `op(0)`, `op(1)`, ... on `queue_depth` workers until the deadline or `limit` operations
+async fn phase<F, Fut>(queue_depth: usize, deadline: Option<Instant>, limit: usize, op: F) -> Result<Phase>
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = Result<u64>>,
+{
+    let next = AtomicUsize::new(0);
+    let start = Instant::now();
+    let workers = (0..queue_depth).map(|_| async {
+        let (mut latencies, mut bytes) = (Vec::new(), 0);
+        while deadline.is_none_or(|d| Instant::now() < d) {
+            let n = next.fetch_add(1, Ordering::Relaxed);
+            if n >= limit {
+                break;
+            }
+            let op_start = Instant::now();
+            bytes += op(n).await?;
+            latencies.push(op_start.elapsed());
+        }
+        Ok::<_, anyhow::Error>((latencies, bytes))
+    });
+    let results = try_join_all(workers).await?;
+    let elapsed = start.elapsed();
+    let bytes = results.iter().map(|(_, b)| b).sum();
+    Ok(Phase { latencies: results.into_iter().flat_map(|(l, _)| l).collect(), bytes, elapsed })
+}
+
+/// Run every job against the settings' URI
+pub async fn run(settings: &MicroSettings) -> Result<MicroReport> {
+    info!("🔬 Micro-benchmarks: {} at sizes {:?} × queue depths {:?}, {:?} per phase under {}",
+          settings.ops.iter().map(MicroOp::name).collect::<Vec<_>>().join(","),
+          settings.sizes, settings.queue_depths, settings.duration, settings.uri);
+    let store = store_for_uri(&settings.uri).with_context(|| format!("Failed to create object store for {}", settings.uri))?;
+    let mut jobs = Vec::new();
+    for &size in &settings.sizes {
+        for &queue_depth in &settings.queue_depths {
+            jobs.extend(run_combination(store.as_ref(), settings, size, queue_depth).await?);
+        }
+    }
+    Ok(MicroReport { uri: settings.uri.clone(), duration_s: settings.duration.as_secs_f64(), jobs })
+}
+
+async fn run_combination(store: &dyn ObjectStore, settings: &MicroSettings, size: usize, queue_depth: usize) -> Result<Vec<MicroJob>> {
+    let prefix = format!("{}/micro_{}_qd{}/", settings.uri, size, queue_depth);
+    let key = |n: usize| format!("{}obj_{:08}", prefix, n);
+    let data = &s3dlio::generate_controlled_data(size, 0, 0);
+    let put = |n: usize| {
+        let uri = key(n);
+        async move {
+            store.put(&uri, data).await.with_context(|| format!("PUT {} failed", uri))?;
+            Ok(size as u64)
+        }
+    };
+
+    let mut jobs = Vec::new();
+    let mut written = 0;
+    let needs_objects = settings.ops.iter().any(|op| matches!(op, MicroOp::Get | MicroOp::Stat | MicroOp::Delete));
+    if needs_objects && !settings.ops.contains(&MicroOp::Put) {
+        written = phase(queue_depth, None, queue_depth * PREFILL_PER_WORKER, put).await?.latencies.len();
+    }
+    let mut deleted = 0;
+    for &op in &settings.ops {
+        let deadline = Some(Instant::now() + settings.duration);
+        let result = match op {
+            MicroOp::Put => phase(queue_depth, deadline, usize::MAX, put).await?,
+            MicroOp::Get => {
+                let objects = written;
+                anyhow::ensure!(objects > 0, "No objects under {} to {}", prefix, op.name());
+                phase(queue_depth, deadline, usize::MAX, |n| {
+                    let uri = key(n % objects);
+                    async move { Ok(store.get(&uri).await.with_context(|| format!("GET {} failed", uri))?.len() as u64) }
+                })
+                .await?
+            }
+            MicroOp::Stat => {
+                let objects = written;
+                anyhow::ensure!(objects > 0, "No objects under {} to {}", prefix, op.name());
+                phase(queue_depth, deadline, usize::MAX, |n| {
+                    let uri = key(n % objects);
+                    async move {
+                        store.stat(&uri).await.with_context(|| format!("STAT {} failed", uri))?;
+                        Ok(0)
+                    }
+                })
+                .await?
+            }
+            MicroOp::List => {
+                phase(queue_depth, deadline, usize::MAX, |_| async {
+                    store.list(&prefix, true).await.with_context(|| format!("LIST {} failed", prefix))?;
+                    Ok(0)
+                })
+                .await?
+            }
+            MicroOp::Delete => phase(queue_depth, deadline, written, |n| {
+                let uri = key(n);
+                async move {
+                    store.delete(&uri).await.with_context(|| format!("DELETE {} failed", uri))?;
+                    Ok(0)
+                }
+            })
+            .await?,
+        };
+        match op {
+            MicroOp::Put => written = result.latencies.len(),
+            MicroOp::Delete => deleted = result.latencies.len(),
+            _ => {}
+        }
+        jobs.push(result.job(op, size, queue_depth));
+    }
+
+    // Leave nothing behind
+    phase(queue_depth, None, written - deleted, |n| {
+        let uri = key(deleted + n);
+        async move {
+            store.delete(&uri).await.with_context(|| format!("Cleanup DELETE {} failed", uri))?;
+            Ok(0)
+        }
+    })
+    .await?;
+    Ok(jobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_parsing_and_fio_line() {
+        assert_eq!(["4096", "4k", "64KiB", "1m", "2g"].map(|s| parse_size(s).unwrap()), [4096, 4096, 65536, 1 << 20, 2 << 30]);
+        assert!(parse_size("4x").is_err());
+        let ops: Vec<MicroOp> = ["delete", "get", "put", "GET"].iter().map(|s| s.parse().unwrap()).collect();
+        let settings = MicroSettings::new("file:///tmp/m/", ops, vec![4096], vec![1, 8], Duration::from_secs(1)).unwrap();
+        assert_eq!(settings.ops, [MicroOp::Put, MicroOp::Get, MicroOp::Delete]);
+        assert_eq!(settings.uri, "file:///tmp/m");
+        assert!(MicroSettings::new("file:///tmp/m", vec![MicroOp::Put], vec![1], vec![0], Duration::from_secs(1)).is_err());
+
+        let phase = Phase {
+            latencies: vec![Duration::from_millis(1), Duration::from_millis(3)],
+            bytes: 8192,
+            elapsed: Duration::from_millis(500),
+        };
+        let job = phase.job(MicroOp::Get, 4096, 2);
+        assert_eq!((job.ops, job.iops), (2, 4.0));
+        assert_eq!(job.latency.as_ref().unwrap().mean_ms, 2.0);
+        assert!(job.fio_line().starts_with("get    bs=4k "));
+        assert_eq!([1 << 20, 3 << 30, 1000].map(size_label), ["1m", "3g", "1000"]);
+    }
+}
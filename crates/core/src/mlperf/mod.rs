@@ -5,15 +5,16 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
-use futures_util::StreamExt;
+use futures_util::{StreamExt, TryStreamExt};
 use tracing::info;
 
+use crate::backends::{DataBackend, ObjectStoreBackend};
 use crate::config::DlioConfig;
+use crate::metrics::Metrics;
 use crate::plan::RunPlan;
 use crate::plugins::PluginManager;
-
-// Import s3dlio components
-use s3dlio::data_loader::{AsyncPoolDataLoader, MultiBackendDataset};
+use crate::rng::SeedTree;
+use crate::shuffle::{access_key, AccessOrder, ShuffleReport, ACCESS_ORDER_SAMPLE};
 
 #[derive(Debug)]
 pub struct MlperfRunner {
@@ -69,73 +70,70 @@ impl MlperfRunner {
                 .context("Data generation failed")?;
         }
 
-        // Build dataset from URI (supports file://, directio://, s3://, az://)
-        let dataset = MultiBackendDataset::from_prefix(&self.plan.uri).await
-            .context("Failed to create dataset from URI")?;
-
-        // Create loader with plan configuration
-        let loader_opts = self.plan.to_loader_options();
-        let pool_config = self.plan.to_pool_config();
-        
-        let loader = AsyncPoolDataLoader::new(dataset, loader_opts);
-
-        let mut stream = loader.stream_with_pool(pool_config);
+        // dl-driver issues the reads, so the access order records the keys actually read
+        let backend = ObjectStoreBackend::new(&self.plan.uri)
+            .context("Failed to create dataset backend from URI")?;
+        let uris = backend.list().await
+            .context("Failed to list dataset")?;
+        if uris.is_empty() {
+            anyhow::bail!("No objects found under {}", self.plan.uri);
+        }
+        let batch_size = self.config.reader.batch_size.unwrap_or(1).max(1);
+        let read_threads = self.config.reader.read_threads.unwrap_or(1).max(1);
+        let shuffle = self.config.reader.shuffle.unwrap_or(false);
+        let seeds = SeedTree::new(self.config.reader.seed.unwrap_or(0), 0);
 
         self.metrics.begin_run();
 
         let mut step: u32 = 0;
-        let mut epoch: u32 = 0;
-        let samples_per_epoch = self.plan.num_files_train.unwrap_or(100) 
-            * self.plan.num_samples_per_file.unwrap_or(1);
-        let mut samples_this_epoch = 0;
-
-        info!("Starting data streaming with {} samples per epoch", samples_per_epoch);
+        info!("Starting data streaming over {} objects", uris.len());
 
         // Main data streaming loop
-        while let Some(batch_result) = stream.next().await {
-            let batch = batch_result.context("Failed to load batch")?;
-            
-            // Record metrics for this batch
-            self.metrics.on_batch(&batch);
-            
-            // Record access order for deterministic validation
-            // TODO: Enhance s3dlio to expose actual item keys/paths instead of step indices
-            // For now, record step indices as a fallback for deterministic ordering
-            self.metrics.record_item_access(format!("step_{:08}", step));
-            
-            // Update sample count
-            samples_this_epoch += batch.len();
-            
-            // Plugin hook after each step
-            self.plugins.after_step(step).await
-                .context("Plugin after_step failed")?;
-            
-            step += 1;
-
-            // Check if we've completed an epoch
-            if samples_this_epoch >= samples_per_epoch {
-                epoch += 1;
-                samples_this_epoch = 0;
-                
-                info!("Completed epoch {} after {} steps", epoch, step);
-                
-                // Plugin hook after each epoch
-                self.plugins.after_epoch(epoch).await
-                    .context("Plugin after_epoch failed")?;
-
-                // Check configurable epoch limit
-                if epoch >= self.max_epochs {
-                    info!("Completed {} epochs (limit: {}), ending benchmark", epoch, self.max_epochs);
-                    break;
-                }
+        'epochs: for epoch in 0..self.max_epochs {
+            let mut order = uris.clone();
+            if shuffle {
+                crate::shuffle::permute(&mut order, seeds.epoch(epoch));
             }
+            for keys in order.chunks(batch_size) {
+                let batch: Vec<Vec<u8>> = futures_util::stream::iter(keys)
+                    .map(|uri| backend.get(uri))
+                    .buffered(read_threads)
+                    .try_collect()
+                    .await
+                    .context("Failed to load batch")?;
+
+                // Record metrics for this batch
+                self.metrics.on_batch(&batch);
+
+                // Record access order for deterministic validation
+                for uri in keys {
+                    self.metrics.record_item_access(access_key(uri, &self.plan.uri).to_string());
+                }
+
+                // Plugin hook after each step
+                self.plugins.after_step(step).await
+                    .context("Plugin after_step failed")?;
 
-            // Check configurable step limit
-            if step >= self.max_steps {
-                info!("Reached step limit ({} steps), ending benchmark", self.max_steps);
-                break;
+                step += 1;
+
+                // Check configurable step limit
+                if step >= self.max_steps {
+                    info!("Reached step limit ({} steps), ending benchmark", self.max_steps);
+                    break 'epochs;
+                }
             }
+
+            info!("Completed epoch {} after {} steps", epoch + 1, step);
+            self.metrics.end_access_epoch();
+
+            // Plugin hook after each epoch
+            self.plugins.after_epoch(epoch + 1).await
+                .context("Plugin after_epoch failed")?;
         }
+        info!("Ended benchmark after {} steps", step);
+
+        // A partial last epoch still gets its digest
+        self.metrics.end_access_epoch();
 
         // Finalize plugins
         self.plugins.finalize().await
@@ -240,7 +238,9 @@ pub struct MlperfMetrics {
     pub decode_latencies_ms: Vec<f64>,    // format decode timing  
     pub h2d_latencies_ms: Vec<f64>,       // host→device transfer (stub for now)
     // Access order tracking for deterministic validation
-    pub visited_items: Vec<String>,       // file paths or dataset indices for determinism
+    pub visited_items: Vec<String>,       // first items accessed (file paths or dataset indices)
+    pub access_order: AccessOrder,        // digest of the current epoch's full access order
+    pub access_epochs: Vec<AccessOrder>,  // digests of completed epochs
 }
 
 impl MlperfMetrics {
@@ -283,7 +283,17 @@ impl MlperfMetrics {
     /// Record an accessed item for deterministic validation
     /// This tracks the order in which dataset items are accessed
     pub fn record_item_access(&mut self, item_id: String) {
-        self.visited_items.push(item_id);
+        self.access_order.record(&item_id);
+        if self.visited_items.len() < ACCESS_ORDER_SAMPLE {
+            self.visited_items.push(item_id);
+        }
+    }
+
+    /// Close the current epoch's access-order digest (no-op when nothing was accessed)
+    pub fn end_access_epoch(&mut self) {
+        if self.access_order.items() > 0 {
+            self.access_epochs.push(std::mem::take(&mut self.access_order));
+        }
     }
 
     pub fn complete_run(&mut self, duration: std::time::Duration) {
@@ -371,9 +381,16 @@ pub struct MlperfReport {
     pub dl_driver_version: String,
     pub s3dlio_version: String,
     pub total_execution_time_secs: f64,
+    #[serde(default)]
+    pub au_percent: Option<f64>,
+    /// Against metric.au; None without a threshold
+    #[serde(default)]
+    pub au_pass: Option<bool>,
     // Access order for deterministic validation (not included in CSV to avoid bloat)
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub access_order_sample: Vec<String>, // First 10 items for validation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub access_order_digests: Vec<String>, // Per-epoch digest of the full access order
 }
 
 impl MlperfReport {
@@ -407,15 +424,70 @@ impl MlperfReport {
             read_threads: config.reader.read_threads.unwrap_or(1),
             shuffle: config.reader.shuffle.unwrap_or(false),
             dl_driver_version: env!("CARGO_PKG_VERSION").to_string(),
-            // Note: s3dlio version matches s3dlio/Cargo.toml version 0.8.1
-            // When s3dlio is updated, update this version string accordingly
-            s3dlio_version: "0.8.1".to_string(),
+            s3dlio_version: crate::results_db::S3DLIO_VERSION.to_string(),
             total_execution_time_secs: metrics.total_execution_time_secs(),
+            au_percent: None,
+            au_pass: None,
             // Include first 10 access order items for deterministic validation
             access_order_sample: metrics.visited_items.iter()
-                .take(10)
+                .take(ACCESS_ORDER_SAMPLE)
                 .cloned()
                 .collect(),
+            access_order_digests: metrics.access_epochs.iter().map(|a| a.hex()).collect(),
+        }
+    }
+
+    /// Report of a `run --mlperf` training run from its WorkloadRunner metrics and shuffle record
+    ///
+    /// The I/O stage is the time each step waited for its batch; runs have no
+    /// host-to-device stage, so those percentiles are 0.
+    pub fn from_workload(metrics: &Metrics, shuffle: Option<&ShuffleReport>, config: &crate::dlio_compat::DlioConfig, rank: u32) -> Self {
+        let results = metrics.to_json(rank, config);
+        let m = &results["metrics"];
+        let latencies = metrics.stage_latencies_ms();
+        let percentile = MlperfMetrics::calculate_percentile;
+        Self {
+            benchmark_name: config.model.as_ref()
+                .and_then(|m| m.name.clone())
+                .unwrap_or_else(|| "dl-driver-benchmark".to_string()),
+            backend_type: backend_from_uri(config.data_folder_uri()),
+            framework: config.framework.clone(),
+            total_samples: m["samples_processed"].as_u64().unwrap_or(0),
+            total_bytes: m["bytes_read"].as_u64().unwrap_or(0),
+            throughput_samples_per_sec: m["samples_per_second"].as_f64().unwrap_or(0.0),
+            p50_latency_ms: percentile(&latencies.batch_ms, 50.0),
+            p95_latency_ms: percentile(&latencies.batch_ms, 95.0),
+            p99_latency_ms: percentile(&latencies.batch_ms, 99.0),
+            io_p50_latency_ms: percentile(&latencies.wait_ms, 50.0),
+            io_p95_latency_ms: percentile(&latencies.wait_ms, 95.0),
+            io_p99_latency_ms: percentile(&latencies.wait_ms, 99.0),
+            decode_p50_latency_ms: percentile(&latencies.decode_ms, 50.0),
+            decode_p95_latency_ms: percentile(&latencies.decode_ms, 95.0),
+            decode_p99_latency_ms: percentile(&latencies.decode_ms, 99.0),
+            h2d_p50_latency_ms: 0.0,
+            h2d_p95_latency_ms: 0.0,
+            h2d_p99_latency_ms: 0.0,
+            // The seed actually used: an unseeded run draws one
+            seed: shuffle.map(|s| s.seed).or(config.reader.seed),
+            data_folder: config.data_folder_uri().to_string(),
+            format: config.dataset.format.clone().unwrap_or_else(|| "npz".to_string()),
+            batch_size: config.reader.batch_size.unwrap_or(1),
+            read_threads: config.reader.read_threads.unwrap_or(1),
+            shuffle: shuffle.map_or(config.reader.shuffle.unwrap_or(false), |s| s.strategy != "none"),
+            dl_driver_version: env!("CARGO_PKG_VERSION").to_string(),
+            s3dlio_version: crate::results_db::S3DLIO_VERSION.to_string(),
+            total_execution_time_secs: m["wall_clock_time_ms"].as_f64().unwrap_or(0.0) / 1000.0,
+            au_percent: m["au_percent"].as_f64(),
+            au_pass: m["au_pass"].as_bool(),
+            access_order_sample: shuffle
+                .and_then(|s| s.epochs.first())
+                .map(|e| e.access_order_sample.clone())
+                .unwrap_or_default(),
+            // Keys read, in order; only known when dl-driver issued the reads (see
+            // WorkloadRunner::with_access_order), never substituted by sample positions
+            access_order_digests: shuffle
+                .map(|s| s.epochs.iter().filter_map(|e| e.access_order_digest.clone()).collect())
+                .unwrap_or_default(),
         }
     }
 
@@ -425,12 +497,12 @@ impl MlperfReport {
     }
 
     pub fn to_csv_header() -> String {
-        "benchmark_name,backend_type,framework,total_samples,total_bytes,throughput_samples_per_sec,p50_latency_ms,p95_latency_ms,p99_latency_ms,io_p50_latency_ms,io_p95_latency_ms,io_p99_latency_ms,decode_p50_latency_ms,decode_p95_latency_ms,decode_p99_latency_ms,h2d_p50_latency_ms,h2d_p95_latency_ms,h2d_p99_latency_ms,batch_size,read_threads,shuffle,data_folder,dl_driver_version,s3dlio_version".to_string()
+        "benchmark_name,backend_type,framework,total_samples,total_bytes,throughput_samples_per_sec,p50_latency_ms,p95_latency_ms,p99_latency_ms,io_p50_latency_ms,io_p95_latency_ms,io_p99_latency_ms,decode_p50_latency_ms,decode_p95_latency_ms,decode_p99_latency_ms,h2d_p50_latency_ms,h2d_p95_latency_ms,h2d_p99_latency_ms,batch_size,read_threads,shuffle,data_folder,dl_driver_version,s3dlio_version,au_percent,au_pass".to_string()
     }
 
     pub fn to_csv_row(&self) -> String {
         format!(
-            "{},{},{},{},{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{},{},{},{},{}",
+            "{},{},{},{},{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{},{},{},{},{},{},{}",
             self.benchmark_name,
             self.backend_type,
             self.framework.as_deref().unwrap_or("none"),
@@ -454,7 +526,9 @@ impl MlperfReport {
             self.shuffle,
             self.data_folder,
             self.dl_driver_version,
-            self.s3dlio_version
+            self.s3dlio_version,
+            self.au_percent.map_or_else(String::new, |au| format!("{:.3}", au)),
+            self.au_pass.map_or_else(String::new, |pass| pass.to_string())
         )
     }
 }
@@ -537,4 +611,73 @@ mod tests {
         assert!(json.contains("test_model"));
         assert!(json.contains("s3"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_mlperf_report_from_workload_metrics() {
+        use crate::rng::SeedTree;
+        use crate::shuffle::{EpochShuffle, ShuffleStrategy};
+        use std::time::Duration;
+
+        let config = crate::testing::ConfigBuilder::new("s3://bench/train")
+            .yaml("model:\n  name: unet3d\nmetric:\n  au: 0.5\n")
+            .build()
+            .unwrap();
+        let metrics = Metrics::new();
+        for wait_ms in [1, 2, 3, 4] {
+            metrics.record_bytes_read(4096);
+            metrics.record_samples_read(4);
+            metrics.record_wait_time(Duration::from_millis(wait_ms));
+            metrics.record_compute_time(Duration::from_millis(30));
+            metrics.record_batch_time(Duration::from_millis(30 + wait_ms));
+        }
+        metrics.record_epoch_time(Duration::from_millis(150));
+        let mut shuffle = ShuffleReport::new(ShuffleStrategy::Permutation, &SeedTree::new(7, 0), true);
+        shuffle.epochs.push(EpochShuffle {
+            epoch: 1,
+            seed: 7,
+            file_order_digest: None,
+            sample_order_digest: "sample".to_string(),
+            access_order_digest: Some("access".to_string()),
+            access_order_items: Some(2),
+            access_order_sample: vec!["img_0.npz".to_string(), "img_1.npz".to_string()],
+            file_reads: None,
+            bytes_fetched: None,
+        });
+
+        let report = MlperfReport::from_workload(&metrics, Some(&shuffle), &config, 0);
+        assert_eq!((report.benchmark_name.as_str(), report.backend_type.as_str()), ("unet3d", "s3"));
+        assert_eq!((report.total_samples, report.total_bytes, report.seed, report.shuffle), (16, 16384, Some(7), true));
+        assert!((report.io_p50_latency_ms - 2.0).abs() < 1e-9 && (report.p50_latency_ms - 32.0).abs() < 1e-9);
+        assert!((report.total_execution_time_secs - 0.15).abs() < 1e-9);
+        assert_eq!(report.au_pass, Some(true));
+        assert_eq!((report.access_order_sample.len(), report.access_order_digests.clone()), (2, vec!["access".to_string()]));
+
+        let header = MlperfReport::to_csv_header();
+        let row = report.to_csv_row();
+        assert_eq!(header.split(',').count(), row.split(',').count());
+        assert!(header.ends_with("au_percent,au_pass") && row.ends_with(",true"));
+    }
+
+    #[test]
+    fn test_access_order_digests_cover_every_item_per_epoch() {
+        let run = |steps: u32| {
+            let mut metrics = MlperfMetrics::new();
+            for step in 0..steps {
+                metrics.record_item_access(format!("train_file_{:06}.npz", step));
+                if step % 20 == 19 {
+                    metrics.end_access_epoch();
+                }
+            }
+            metrics.end_access_epoch();
+            metrics
+        };
+        let metrics = run(50);
+        // Only the sample is kept verbatim; every item feeds its epoch's digest
+        assert_eq!(metrics.visited_items.len(), ACCESS_ORDER_SAMPLE);
+        let items: Vec<u64> = metrics.access_epochs.iter().map(|a| a.items()).collect();
+        assert_eq!(items, [20, 20, 10]);
+        let hex = |m: &MlperfMetrics| m.access_epochs.iter().map(|a| a.hex()).collect::<Vec<_>>();
+        assert_eq!(hex(&metrics)[..2], hex(&run(40))[..]);
+        assert_ne!(hex(&metrics)[0], hex(&metrics)[1]);
+    }
+}
@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/mlperf_storage.rs
+//
+// Convert dl-driver rank results into MLPerf Storage (DLIO reporter) files
+//
+// Each host gets a DLIO-style `summary.json` and `per_epoch_stats.json`; a
+// run-level `summary.json` aggregates all hosts. Every rank counts as the
+// accelerators it stood for (`derived.accelerators`, one unless the run used
+// gpus_per_rank), and per-epoch AU averages over accelerators.
+//
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Per-epoch figures for one host (or the whole run)
+#[derive(Debug, Clone, Default)]
+struct EpochStats {
+    start: f64,
+    end: f64,
+    au_percent: Vec<f64>,
+    samples_per_s: f64,
+}
+
+/// Accelerators one rank stood for
+fn accelerators(rank: &Value) -> usize {
+    rank["derived"]["accelerators"].as_u64().unwrap_or(1).max(1) as usize
+}
+
+/// Aggregate the ranks of one host (or all ranks) into DLIO summary and epoch stats
+fn summarize(ranks: &[&Value]) -> (Value, Value) {
+    let first = ranks[0];
+    let config = &first["config"];
+    let record = config["record_length_bytes"].as_f64().unwrap_or(1024.0).max(1.0);
+    let epochs = ranks
+        .iter()
+        .map(|r| r["timing_details"]["epoch_times_ms"].as_array().map_or(0, |a| a.len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut per_epoch = vec![EpochStats::default(); epochs];
+    for rank in ranks {
+        let epoch_ms = ms_list(&rank["timing_details"]["epoch_times_ms"]);
+        let compute_ms = ms_list(&rank["timing_details"]["epoch_compute_times_ms"]);
+        let samples = rank["metrics"]["bytes_read"].as_f64().unwrap_or(0.0) / record;
+        let samples_per_epoch = samples / epoch_ms.len().max(1) as f64;
+        let accelerators = accelerators(rank);
+
+        // Epochs run back to back from the rank's start time
+        let mut t = rank["start_time"].as_f64().unwrap_or(0.0);
+        for (i, &ms) in epoch_ms.iter().enumerate() {
+            let stats = &mut per_epoch[i];
+            let secs = ms / 1000.0;
+            stats.start = if stats.start == 0.0 { t } else { stats.start.min(t) };
+            stats.end = stats.end.max(t + secs);
+            t += secs;
+            if let Some(&compute) = compute_ms.get(i) {
+                if ms > 0.0 {
+                    let au = (compute * 100.0 / ms).min(100.0);
+                    stats.au_percent.extend(std::iter::repeat_n(au, accelerators));
+                }
+            }
+            if secs > 0.0 {
+                stats.samples_per_s += samples_per_epoch / secs;
+            }
+        }
+    }
+
+    let au: Vec<f64> = per_epoch
+        .iter()
+        .map(|e| mean_stdev(&e.au_percent).0)
+        .collect();
+    let throughput: Vec<f64> = per_epoch.iter().map(|e| e.samples_per_s).collect();
+    // DLIO's "MB" is MiB (2^20); the key names stay as DLIO writes them
+    let io_mb: Vec<f64> = throughput.iter().map(|t| t * record / crate::units::MIB).collect();
+    let (au_mean, au_stdev) = mean_stdev(&au);
+    let (tp_mean, tp_stdev) = mean_stdev(&throughput);
+    let (io_mean, io_stdev) = mean_stdev(&io_mb);
+
+    let passes: Vec<bool> = ranks.iter().filter_map(|r| r["metrics"]["au_pass"].as_bool()).collect();
+    let meets = if passes.is_empty() {
+        Value::Null
+    } else if passes.iter().all(|&p| p) {
+        json!("success")
+    } else {
+        json!("fail")
+    };
+
+    let start = ranks.iter().filter_map(|r| r["start_time"].as_f64()).fold(f64::INFINITY, f64::min);
+    let end = ranks.iter().filter_map(|r| r["end_time"].as_f64()).fold(0.0, f64::max);
+    let mut hosts: Vec<&str> = ranks.iter().filter_map(|r| r["hostname"].as_str()).collect();
+    hosts.sort();
+    hosts.dedup();
+
+    let mut summary = json!({
+        "model": config["model"],
+        "start": timestamp(start),
+        "end": timestamp(end),
+        "num_accelerators": ranks.iter().map(|r| accelerators(r)).sum::<usize>(),
+        "num_hosts": hosts.len(),
+        "hostname": if hosts.len() == 1 { json!(hosts[0]) } else { json!(hosts) },
+        "epochs": epochs,
+        "num_files_train": config["num_files_train"],
+        "num_samples_per_file": config["num_samples_per_file"],
+        "record_length_bytes": config["record_length_bytes"],
+        "metric": {
+            "train_au_percentage": au,
+            "train_au_mean_percentage": au_mean,
+            "train_au_meet_expectation": meets,
+            "train_au_stdev_percentage": au_stdev,
+            "train_throughput_samples_per_second": throughput,
+            "train_throughput_mean_samples_per_second": tp_mean,
+            "train_throughput_stdev_samples_per_second": tp_stdev,
+            "train_io_MB_per_second": io_mb,
+            "train_io_mean_MB_per_second": io_mean,
+            "train_io_stdev_MB_per_second": io_stdev,
+        }
+    });
+
+    // Evaluations (`evaluation.runs` in dl-driver results), summed across ranks per evaluation
+    let mut eval_samples_per_s: Vec<f64> = Vec::new();
+    let mut eval_io_mb: Vec<f64> = Vec::new();
+    for rank in ranks {
+        for (i, run) in rank["evaluation"]["runs"].as_array().into_iter().flatten().enumerate() {
+            if eval_samples_per_s.len() <= i {
+                eval_samples_per_s.resize(i + 1, 0.0);
+                eval_io_mb.resize(i + 1, 0.0);
+            }
+            eval_samples_per_s[i] += run["samples_per_second"].as_f64().unwrap_or(0.0);
+            eval_io_mb[i] += run["throughput"]["bytes_per_s"].as_f64().unwrap_or(0.0) / crate::units::MIB;
+        }
+    }
+    if !eval_samples_per_s.is_empty() {
+        let metric = &mut summary["metric"];
+        metric["eval_throughput_samples_per_second"] = json!(eval_samples_per_s);
+        metric["eval_throughput_mean_samples_per_second"] = json!(mean_stdev(&eval_samples_per_s).0);
+        metric["eval_throughput_stdev_samples_per_second"] = json!(mean_stdev(&eval_samples_per_s).1);
+        metric["eval_io_MB_per_second"] = json!(eval_io_mb);
+        metric["eval_io_mean_MB_per_second"] = json!(mean_stdev(&eval_io_mb).0);
+        metric["eval_io_stdev_MB_per_second"] = json!(mean_stdev(&eval_io_mb).1);
+    }
+
+    let epoch_stats: serde_json::Map<String, Value> = per_epoch
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            (
+                (i + 1).to_string(),
+                json!({
+                    "start": timestamp(e.start),
+                    "end": timestamp(e.end),
+                    "duration": e.end - e.start,
+                    "au": au[i],
+                    "throughput": throughput[i],
+                }),
+            )
+        })
+        .collect();
+
+    (summary, Value::Object(epoch_stats))
+}
+
+fn ms_list(value: &Value) -> Vec<f64> {
+    value
+        .as_array()
+        .map(|a| a.iter().filter_map(Value::as_f64).collect())
+        .unwrap_or_default()
+}
+
+fn mean_stdev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, var.sqrt())
+}
+
+/// DLIO-style local timestamp for a unix time in seconds
+fn timestamp(unix_secs: f64) -> String {
+    if !unix_secs.is_finite() {
+        return String::new();
+    }
+    let secs = unix_secs.floor() as i64;
+    let nanos = ((unix_secs - secs as f64) * 1e9) as u32;
+    chrono::DateTime::from_timestamp(secs, nanos)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%dT%H:%M:%S%.6f").to_string())
+        .unwrap_or_default()
+}
+
+/// Write MLPerf Storage reporter files for a set of dl-driver rank results
+///
+/// Returns the files written.
+pub fn convert(results: &[Value], output_dir: &Path) -> Result<Vec<PathBuf>> {
+    if results.is_empty() {
+        return Err(anyhow::anyhow!("No dl-driver results to convert"));
+    }
+
+    let mut by_host: BTreeMap<String, Vec<&Value>> = BTreeMap::new();
+    for result in results {
+        let host = result["hostname"].as_str().unwrap_or("localhost").to_string();
+        by_host.entry(host).or_default().push(result);
+    }
+
+    let mut written = Vec::new();
+    let mut write = |path: PathBuf, value: &Value| -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(value)?)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        written.push(path);
+        Ok(())
+    };
+
+    for (host, ranks) in &by_host {
+        let (summary, epochs) = summarize(ranks);
+        write(output_dir.join(host).join("summary.json"), &summary)?;
+        write(output_dir.join(host).join("per_epoch_stats.json"), &epochs)?;
+    }
+
+    let all: Vec<&Value> = results.iter().collect();
+    let (summary, epochs) = summarize(&all);
+    write(output_dir.join("summary.json"), &summary)?;
+    write(output_dir.join("per_epoch_stats.json"), &epochs)?;
+
+    info!("📝 MLPerf Storage report: {} hosts, {} ranks → {:?}", by_host.len(), results.len(), output_dir);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(host: &str, compute: [u64; 2]) -> Value {
+        json!({
+            "hostname": host,
+            "start_time": 1_700_000_000.0,
+            "end_time": 1_700_000_002.0,
+            "config": { "model": "unet3d", "record_length_bytes": 1048576, "num_samples_per_file": 1 },
+            "metrics": { "bytes_read": 2u64 * 1024 * 1048576, "au_pass": true },
+            "timing_details": { "epoch_times_ms": [1000, 1000], "epoch_compute_times_ms": compute }
+        })
+    }
+
+    #[test]
+    fn test_summary_per_epoch_metrics() {
+        let a = rank("node1", [900, 800]);
+        let b = rank("node1", [700, 800]);
+        let (summary, epochs) = summarize(&[&a, &b]);
+
+        let m = &summary["metric"];
+        assert_eq!(summary["num_accelerators"], 2);
+        assert_eq!(summary["hostname"], "node1");
+        assert_eq!(m["train_au_percentage"], json!([80.0, 80.0]));
+        // Each rank reads 1024 samples per 1s epoch
+        assert_eq!(m["train_throughput_mean_samples_per_second"], 2048.0);
+        assert_eq!(m["train_io_mean_MB_per_second"], 2048.0);
+        assert_eq!(m["train_au_meet_expectation"], "success");
+        assert!(epochs["2"]["duration"].as_f64().unwrap() > 0.99);
+        assert!(m.get("eval_throughput_samples_per_second").is_none());
+
+        // Evaluation throughput is summed across ranks per evaluation
+        let mut c = rank("node1", [900, 900]);
+        c["evaluation"] = json!({ "runs": [{ "samples_per_second": 100.0, "throughput": { "bytes_per_s": 1048576.0 } }] });
+        let mut d = c.clone();
+        d["evaluation"]["runs"][0]["samples_per_second"] = json!(50.0);
+        let (summary, _) = summarize(&[&c, &d]);
+        assert_eq!(summary["metric"]["eval_throughput_samples_per_second"], json!([150.0]));
+        assert_eq!(summary["metric"]["eval_io_mean_MB_per_second"], 2.0);
+
+        // A rank standing for 3 GPUs counts 3 times in accelerators and AU
+        let mut e = rank("node1", [900, 900]);
+        e["derived"] = json!({ "accelerators": 3 });
+        let f = rank("node1", [500, 500]);
+        let (summary, _) = summarize(&[&e, &f]);
+        assert_eq!(summary["num_accelerators"], 4);
+        assert_eq!(summary["metric"]["train_au_percentage"], json!([80.0, 80.0]));
+    }
+
+    #[test]
+    fn test_convert_groups_hosts() {
+        let dir = tempfile::tempdir().unwrap();
+        let results = vec![rank("node1", [900, 900]), rank("node2", [900, 900])];
+        let files = convert(&results, dir.path()).unwrap();
+        assert_eq!(files.len(), 6);
+        assert!(dir.path().join("node2").join("summary.json").is_file());
+    }
+}
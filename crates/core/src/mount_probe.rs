@@ -0,0 +1,185 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/mount_probe.rs
+//
+// Filesystem/mount characteristics probe for file:// targets
+//
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+
+/// Filesystem types served over the network (or shared across nodes)
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smb3", "smbfs", "lustre", "gpfs", "beegfs", "ceph",
+    "glusterfs", "fuse.glusterfs", "fuse.sshfs", "fuse.s3fs", "wekafs", "9p",
+];
+
+/// Mount characteristics recorded for file:// targets
+#[derive(Debug, Clone, Serialize)]
+pub struct MountInfo {
+    pub target_path: String,
+    pub mount_point: String,
+    pub source: String,
+    pub fs_type: String,
+    pub options: Vec<String>,
+    pub is_network: bool,
+    /// Dataset bytes that fit in RAM are likely served from page cache after epoch 1
+    pub page_cache_dominant: bool,
+    pub dataset_bytes: u64,
+    pub mem_total_bytes: Option<u64>,
+}
+
+impl MountInfo {
+    /// Look up a mount option value such as `rsize` or `nconnect`
+    pub fn option(&self, key: &str) -> Option<&str> {
+        self.options.iter().find_map(|opt| {
+            opt.strip_prefix(key).and_then(|rest| rest.strip_prefix('='))
+        })
+    }
+}
+
+/// Probe the mount backing the config's data folder (file:// and direct:// only)
+pub fn probe(config: &DlioConfig) -> Option<MountInfo> {
+    let path = local_path_for_uri(&config.dataset.data_folder)?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts")
+        .or_else(|_| std::fs::read_to_string("/proc/mounts"))
+        .ok()?;
+    let resolved = resolve_existing(&path);
+    let (mount_point, source, fs_type, options) = find_mount(&mounts, &resolved)?;
+
+    let dataset_bytes = (config.dataset.num_files_train.unwrap_or(0) as u64)
+        * (config.dataset.num_samples_per_file.unwrap_or(1) as u64)
+        * (config.dataset.record_length_bytes.unwrap_or(0) as u64);
+    let mem_total_bytes = mem_total_bytes();
+    let is_direct = config.dataset.data_folder.starts_with("direct");
+    let page_cache_dominant = !is_direct
+        && mem_total_bytes.map_or(false, |mem| dataset_bytes > 0 && dataset_bytes < mem);
+
+    let info = MountInfo {
+        target_path: path.display().to_string(),
+        is_network: NETWORK_FS_TYPES.contains(&fs_type.as_str()),
+        mount_point,
+        source,
+        fs_type,
+        options,
+        page_cache_dominant,
+        dataset_bytes,
+        mem_total_bytes,
+    };
+    debug!("Mount probe: {:?}", info);
+
+    if info.page_cache_dominant {
+        warn!("⚠️  Dataset ({:.2} GiB) fits in RAM ({:.2} GiB) on {} ({}); page cache will dominate read results after the first epoch",
+              crate::units::gib(dataset_bytes),
+              crate::units::gib(mem_total_bytes.unwrap_or(0)),
+              info.mount_point, info.fs_type);
+    }
+    Some(info)
+}
+
+/// Walk up to the nearest existing ancestor so unborn data folders still resolve
+fn resolve_existing(path: &Path) -> PathBuf {
+    let mut current = path.to_path_buf();
+    loop {
+        if let Ok(canonical) = current.canonicalize() {
+            return canonical;
+        }
+        if !current.pop() {
+            return path.to_path_buf();
+        }
+    }
+}
+
+/// Decode octal escapes (e.g. `\040` for space) used in /proc/mounts
+fn unescape_mount_field(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let code: String = chars.by_ref().take(3).collect();
+            match u8::from_str_radix(&code, 8) {
+                Ok(b) => out.push(b as char),
+                Err(_) => {
+                    out.push('\\');
+                    out.push_str(&code);
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Find the longest mount point containing `path` in /proc/mounts text
+fn find_mount(mounts: &str, path: &Path) -> Option<(String, String, String, Vec<String>)> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = unescape_mount_field(fields.next()?);
+            let mount_point = unescape_mount_field(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            let options = fields.next()?.split(',').map(str::to_string).collect();
+            Some((mount_point, source, fs_type, options))
+        })
+        .filter(|(mount_point, ..)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, ..)| mount_point.len())
+}
+
+pub(crate) fn mem_total_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo
+        .lines()
+        .find(|l| l.starts_with("MemTotal:"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+tmpfs /tmp tmpfs rw,nosuid,nodev 0 0
+filer:/export /mnt/nfs\\040data nfs4 rw,vers=4.2,rsize=1048576,wsize=1048576,nconnect=8 0 0
+";
+
+    #[test]
+    fn test_longest_prefix_mount() {
+        let (mp, _, fs, _) = find_mount(MOUNTS, Path::new("/tmp/dl/data")).unwrap();
+        assert_eq!(mp, "/tmp");
+        assert_eq!(fs, "tmpfs");
+
+        let (mp, _, fs, _) = find_mount(MOUNTS, Path::new("/home/user")).unwrap();
+        assert_eq!(mp, "/");
+        assert_eq!(fs, "ext4");
+    }
+
+    #[test]
+    fn test_nfs_options_and_escapes() {
+        let (mp, source, fs, options) = find_mount(MOUNTS, Path::new("/mnt/nfs data/train")).unwrap();
+        assert_eq!(mp, "/mnt/nfs data");
+        assert_eq!(source, "filer:/export");
+        let info = MountInfo {
+            target_path: "/mnt/nfs data/train".into(),
+            mount_point: mp,
+            source,
+            is_network: NETWORK_FS_TYPES.contains(&fs.as_str()),
+            fs_type: fs,
+            options,
+            page_cache_dominant: false,
+            dataset_bytes: 0,
+            mem_total_bytes: None,
+        };
+        assert!(info.is_network);
+        assert_eq!(info.option("nconnect"), Some("8"));
+        assert_eq!(info.option("rsize"), Some("1048576"));
+        assert_eq!(info.option("timeo"), None);
+    }
+}
@@ -0,0 +1,376 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/multipart.rs
+//
+// Multipart PUT / ranged GET helpers for large-object workloads
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::dlio_compat::DlioConfig;
+use s3dlio::object_store::ObjectStore;
+
+const DEFAULT_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_PART_SIZE_BYTES: u64 = 16 * 1024 * 1024;
+const DEFAULT_CONCURRENCY: usize = 8;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Resolved multipart settings with defaults applied
+#[derive(Debug, Clone, Copy)]
+pub struct MultipartSettings {
+    pub threshold_bytes: u64,
+    pub part_size_bytes: u64,
+    pub concurrency: usize,
+    pub max_retries: u32,
+}
+
+impl Default for MultipartSettings {
+    fn default() -> Self {
+        Self {
+            threshold_bytes: DEFAULT_THRESHOLD_BYTES,
+            part_size_bytes: DEFAULT_PART_SIZE_BYTES,
+            concurrency: DEFAULT_CONCURRENCY,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+}
+
+impl MultipartSettings {
+    /// Build settings from the optional `storage.multipart` config section
+    pub fn from_config(config: &DlioConfig) -> Self {
+        let defaults = Self::default();
+        let Some(mp) = config.storage.as_ref().and_then(|s| s.multipart.as_ref()) else {
+            return defaults;
+        };
+
+        Self {
+            threshold_bytes: mp.threshold_bytes.unwrap_or(defaults.threshold_bytes),
+            part_size_bytes: mp.part_size_bytes.unwrap_or(defaults.part_size_bytes).max(1),
+            concurrency: mp.concurrency.unwrap_or(defaults.concurrency).max(1),
+            max_retries: mp.max_retries.unwrap_or(defaults.max_retries),
+        }
+    }
+
+    /// Whether an object of `size` bytes should be transferred in parts
+    pub fn use_multipart(&self, size: u64) -> bool {
+        size >= self.threshold_bytes
+    }
+
+    /// Split an object of `size` bytes into (offset, length) part ranges
+    pub fn part_ranges(&self, size: u64) -> Vec<(u64, u64)> {
+        let mut ranges = Vec::new();
+        let mut offset = 0;
+        while offset < size {
+            let len = self.part_size_bytes.min(size - offset);
+            ranges.push((offset, len));
+            offset += len;
+        }
+        ranges
+    }
+}
+
+/// Latency and retry statistics for multipart transfers
+///
+/// Ranged GET parts are timed individually. s3dlio's multipart uploader does not
+/// expose its parts, so uploads are timed as whole objects.
+#[derive(Debug, Default)]
+pub struct MultipartStats {
+    data: Mutex<MultipartStatsData>,
+}
+
+#[derive(Debug, Default)]
+struct MultipartStatsData {
+    objects: u64,
+    multipart_objects: u64,
+    part_latencies: Vec<Duration>,
+    upload_latencies: Vec<Duration>,
+    part_retries: u64,
+    failed_parts: u64,
+}
+
+/// Snapshot of multipart statistics for reporting
+#[derive(Debug, Clone)]
+pub struct MultipartSummary {
+    pub objects: u64,
+    pub multipart_objects: u64,
+    pub parts: u64,
+    pub part_retries: u64,
+    pub failed_parts: u64,
+    pub part_p50_ms: f64,
+    pub part_p99_ms: f64,
+    pub part_max_ms: f64,
+    pub uploads: u64,
+    pub upload_p50_ms: f64,
+    pub upload_p99_ms: f64,
+    pub upload_max_ms: f64,
+}
+
+impl MultipartStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_object(&self, multipart: bool) {
+        let mut data = self.data.lock().unwrap();
+        data.objects += 1;
+        if multipart {
+            data.multipart_objects += 1;
+        }
+    }
+
+    pub(crate) fn record_part(&self, latency: Duration, retries: u32) {
+        let mut data = self.data.lock().unwrap();
+        data.part_latencies.push(latency);
+        data.part_retries += retries as u64;
+    }
+
+    pub(crate) fn record_upload(&self, latency: Duration, retries: u32) {
+        let mut data = self.data.lock().unwrap();
+        data.upload_latencies.push(latency);
+        data.part_retries += retries as u64;
+    }
+
+    pub(crate) fn record_failed_part(&self, retries: u32) {
+        let mut data = self.data.lock().unwrap();
+        data.failed_parts += 1;
+        data.part_retries += retries as u64;
+    }
+
+    pub fn summary(&self) -> MultipartSummary {
+        let data = self.data.lock().unwrap();
+        let sorted_ms = |latencies: &[Duration]| {
+            let mut sorted: Vec<f64> = latencies.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            sorted
+        };
+        let percentile = |sorted: &[f64], p: f64| -> f64 {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            let index = ((p / 100.0) * (sorted.len() - 1) as f64) as usize;
+            sorted[index.min(sorted.len() - 1)]
+        };
+        let parts = sorted_ms(&data.part_latencies);
+        let uploads = sorted_ms(&data.upload_latencies);
+
+        MultipartSummary {
+            objects: data.objects,
+            multipart_objects: data.multipart_objects,
+            parts: parts.len() as u64,
+            part_retries: data.part_retries,
+            failed_parts: data.failed_parts,
+            part_p50_ms: percentile(&parts, 50.0),
+            part_p99_ms: percentile(&parts, 99.0),
+            part_max_ms: parts.last().copied().unwrap_or(0.0),
+            uploads: uploads.len() as u64,
+            upload_p50_ms: percentile(&uploads, 50.0),
+            upload_p99_ms: percentile(&uploads, 99.0),
+            upload_max_ms: uploads.last().copied().unwrap_or(0.0),
+        }
+    }
+
+    /// Export statistics as JSON for results files
+    pub fn to_json(&self) -> serde_json::Value {
+        let s = self.summary();
+        serde_json::json!({
+            "objects": s.objects,
+            "multipart_objects": s.multipart_objects,
+            "parts": s.parts,
+            "part_retries": s.part_retries,
+            "failed_parts": s.failed_parts,
+            "part_p50_ms": s.part_p50_ms,
+            "part_p99_ms": s.part_p99_ms,
+            "part_max_ms": s.part_max_ms,
+            "uploads": s.uploads,
+            "upload_p50_ms": s.upload_p50_ms,
+            "upload_p99_ms": s.upload_p99_ms,
+            "upload_max_ms": s.upload_max_ms,
+        })
+    }
+}
+
+/// Where `put_object` writes: an s3dlio store, or a writer adding request headers it lacks
+#[async_trait]
+pub trait Uploader: Send + Sync {
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()>;
+    async fn put_multipart(&self, uri: &str, data: &[u8], part_size: usize) -> Result<()>;
+}
+
+#[async_trait]
+impl Uploader for dyn ObjectStore {
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        ObjectStore::put(self, uri, data).await
+    }
+
+    async fn put_multipart(&self, uri: &str, data: &[u8], part_size: usize) -> Result<()> {
+        ObjectStore::put_multipart(self, uri, data, Some(part_size)).await
+    }
+}
+
+/// Write an object, using multipart upload when it is over the threshold
+///
+/// Part concurrency is governed by s3dlio's uploader, so only the whole upload is timed.
+pub async fn put_object(
+    store: &dyn ObjectStore,
+    uri: &str,
+    data: &[u8],
+    settings: &MultipartSettings,
+    stats: &MultipartStats,
+) -> Result<()> {
+    upload(store, uri, data, settings, stats).await
+}
+
+/// `put_object` through any uploader, with the same retries and statistics
+pub async fn upload<U: Uploader + ?Sized>(
+    store: &U,
+    uri: &str,
+    data: &[u8],
+    settings: &MultipartSettings,
+    stats: &MultipartStats,
+) -> Result<()> {
+    let size = data.len() as u64;
+    let multipart = settings.use_multipart(size);
+    stats.record_object(multipart);
+
+    let mut attempt = 0;
+    loop {
+        let start = Instant::now();
+        let result = if multipart {
+            store.put_multipart(uri, data, settings.part_size_bytes as usize).await
+        } else {
+            store.put(uri, data).await
+        };
+
+        match result {
+            Ok(()) => {
+                stats.record_upload(start.elapsed(), attempt);
+                return Ok(());
+            }
+            Err(e) if attempt < settings.max_retries => {
+                attempt += 1;
+                warn!("PUT {} failed (attempt {}/{}): {}", uri, attempt, settings.max_retries, e);
+            }
+            Err(e) => {
+                stats.record_failed_part(attempt);
+                return Err(e).with_context(|| format!("Failed to write {} after {} retries", uri, attempt));
+            }
+        }
+    }
+}
+
+/// Read an object, using concurrent ranged GETs when it is over the threshold
+pub async fn get_object(
+    store: &dyn ObjectStore,
+    uri: &str,
+    size: u64,
+    settings: &MultipartSettings,
+    stats: &MultipartStats,
+) -> Result<Vec<u8>> {
+    let multipart = settings.use_multipart(size);
+    stats.record_object(multipart);
+
+    if !multipart {
+        let start = Instant::now();
+        let data = store.get(uri).await
+            .with_context(|| format!("Failed to read {}", uri))?;
+        stats.record_part(start.elapsed(), 0);
+        return Ok(data.to_vec());
+    }
+
+    let ranges = settings.part_ranges(size);
+    debug!("Ranged GET {}: {} parts of up to {} bytes, concurrency {}",
+           uri, ranges.len(), settings.part_size_bytes, settings.concurrency);
+
+    let parts: Vec<Vec<u8>> = stream::iter(ranges)
+        .map(|(offset, len)| get_part(store, uri, offset, len, settings, stats))
+        .buffered(settings.concurrency)
+        .try_collect()
+        .await?;
+
+    let mut data = Vec::with_capacity(size as usize);
+    for part in parts {
+        data.extend_from_slice(&part);
+    }
+    Ok(data)
+}
+
+async fn get_part(
+    store: &dyn ObjectStore,
+    uri: &str,
+    offset: u64,
+    len: u64,
+    settings: &MultipartSettings,
+    stats: &MultipartStats,
+) -> Result<Vec<u8>> {
+    let mut attempt = 0;
+    loop {
+        let start = Instant::now();
+        match store.get_range(uri, offset, Some(len)).await {
+            Ok(part) => {
+                stats.record_part(start.elapsed(), attempt);
+                return Ok(part.to_vec());
+            }
+            Err(e) if attempt < settings.max_retries => {
+                attempt += 1;
+                warn!("GET {} range {}+{} failed (attempt {}/{}): {}",
+                      uri, offset, len, attempt, settings.max_retries, e);
+            }
+            Err(e) => {
+                stats.record_failed_part(attempt);
+                return Err(e).with_context(|| format!("Failed to read {} range {}+{}", uri, offset, len));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_from_config() {
+        let yaml = r#"
+dataset:
+  data_folder: s3://bucket/data
+reader: {}
+storage:
+  multipart:
+    threshold_bytes: 1048576
+    part_size_bytes: 262144
+    concurrency: 4
+"#;
+        let config = DlioConfig::from_yaml(yaml).expect("Should parse storage section");
+        let settings = MultipartSettings::from_config(&config);
+
+        assert_eq!(settings.threshold_bytes, 1_048_576);
+        assert_eq!(settings.part_size_bytes, 262_144);
+        assert_eq!(settings.concurrency, 4);
+        assert_eq!(settings.max_retries, DEFAULT_MAX_RETRIES);
+        assert!(settings.use_multipart(1_048_576));
+        assert!(!settings.use_multipart(1_048_575));
+    }
+
+    #[test]
+    fn test_part_ranges_cover_object() {
+        let settings = MultipartSettings { part_size_bytes: 100, ..Default::default() };
+        let ranges = settings.part_ranges(250);
+        assert_eq!(ranges, vec![(0, 100), (100, 100), (200, 50)]);
+        assert!(settings.part_ranges(0).is_empty());
+    }
+
+    #[test]
+    fn test_uploads_are_not_reported_as_parts() {
+        let stats = MultipartStats::new();
+        stats.record_upload(Duration::from_millis(40), 1);
+        stats.record_part(Duration::from_millis(5), 0);
+        let summary = stats.summary();
+        assert_eq!((summary.parts, summary.uploads, summary.part_retries), (1, 1, 1));
+        assert_eq!(summary.part_max_ms, 5.0);
+        assert_eq!(summary.upload_max_ms, 40.0);
+    }
+}
@@ -0,0 +1,458 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/net_coordination.rs
+//
+// TCP barrier coordination for ranks spread across hosts
+//
+// Shared-memory coordination only works within one host. For multi-node
+// launches, the launcher runs a CoordinationServer and every rank connects to
+// its endpoint (host:port) with --coord-endpoint; with --coord tcp://host:port
+// rank 0 runs it instead (see coord_backend). Each barrier is one short
+// connection: the rank sends a JSON line naming the barrier and blocks until
+// the server has heard from all `world_size` ranks, then every waiter receives
+// the same release timestamp.
+//
+// A barrier can also carry a value every rank must agree on (e.g. the
+// resolved data folder); the server then fails the barrier on all ranks
+// when any value differs. Or it can gather: each rank contributes a value
+// (e.g. its results) and every rank is released with all of them, by rank.
+//
+// Before the first barrier each rank also probes the server's clock a few
+// times (NTP-style, keeping the lowest round trip) so per-rank UNIX timestamps
+// can be corrected for clock drift across nodes when results are aggregated.
+//
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Barrier every rank enters after its run completes; the server stops once it releases
+pub const FINISH_BARRIER: &str = "finished";
+
+/// How long a rank keeps retrying to reach the server before giving up
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long the server waits for a connected client's request line
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long an aborting rank tries to notify the server
+const ABORT_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BarrierRequest {
+    barrier: String,
+    rank: u32,
+    world_size: u32,
+    /// Reply with the server time immediately instead of entering the barrier
+    #[serde(default)]
+    clock_probe: bool,
+    /// Value all ranks must pass at this barrier
+    #[serde(default)]
+    value: Option<String>,
+    /// This rank's contribution to a gathering barrier
+    #[serde(default)]
+    shared: Option<String>,
+    /// This rank gave up: fail every waiting and later barrier with this reason
+    #[serde(default)]
+    abort: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BarrierReply {
+    /// Release time in nanoseconds since UNIX_EPOCH, identical for all ranks
+    release_ns: u64,
+    error: Option<String>,
+    /// Contributions of a gathering barrier, by rank
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shared: Vec<Option<String>>,
+}
+
+/// A rank's clock offset from a reference clock
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockOffset {
+    /// Reference time minus local time, in nanoseconds
+    pub offset_ns: i64,
+    /// Half the best probe round trip: the true offset lies within ± this
+    pub uncertainty_ns: u64,
+}
+
+impl ClockOffset {
+    /// This offset re-expressed against another rank's clock (e.g. rank 0)
+    pub fn relative_to(&self, reference: &ClockOffset) -> ClockOffset {
+        ClockOffset {
+            offset_ns: self.offset_ns - reference.offset_ns,
+            uncertainty_ns: self.uncertainty_ns + reference.uncertainty_ns,
+        }
+    }
+
+    /// Convert a local UNIX timestamp (seconds) to the reference clock
+    pub fn correct(&self, unix_secs: f64) -> f64 {
+        unix_secs + self.offset_ns as f64 / 1e9
+    }
+}
+
+fn now_ns() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+}
+
+async fn reply(stream: &mut TcpStream, reply: &BarrierReply) {
+    let mut line = serde_json::to_vec(reply).unwrap_or_default();
+    line.push(b'\n');
+    if let Err(e) = stream.write_all(&line).await {
+        warn!("Failed to release a coordination client: {}", e);
+    }
+}
+
+/// Read one request line; None (logged) for a silent, closed or malformed client
+async fn read_request(stream: &mut TcpStream, peer: SocketAddr) -> Option<BarrierRequest> {
+    let mut line = String::new();
+    let mut reader = BufReader::new(stream);
+    match tokio::time::timeout(REQUEST_TIMEOUT, reader.read_line(&mut line)).await {
+        Ok(Ok(read)) if read > 0 => {}
+        Ok(_) => return None,
+        Err(_) => {
+            warn!("Dropping coordination client {} that sent no request within {:?}", peer, REQUEST_TIMEOUT);
+            return None;
+        }
+    }
+    match serde_json::from_str(&line) {
+        Ok(request) => Some(request),
+        Err(e) => {
+            warn!("Ignoring malformed coordination request from {}: {}", peer, e);
+            None
+        }
+    }
+}
+
+/// Barrier server run by the launcher (or rank 0)
+pub struct CoordinationServer {
+    listener: TcpListener,
+    world_size: u32,
+}
+
+impl CoordinationServer {
+    pub async fn bind(addr: &str, world_size: u32) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind coordination server on {}", addr))?;
+        Ok(Self { listener, world_size })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve barriers until `FINISH_BARRIER` releases
+    pub fn spawn(self) -> JoinHandle<Result<()>> {
+        tokio::spawn(async move { self.serve().await })
+    }
+
+    async fn serve(self) -> Result<()> {
+        info!("🔗 Coordination server on {} for {} ranks", self.local_addr()?, self.world_size);
+        let mut waiting: HashMap<String, Vec<(BarrierRequest, TcpStream)>> = HashMap::new();
+        let mut aborted: Option<String> = None;
+        // Each connection reads its request in its own task, so a client that
+        // connects and sends nothing cannot hold up the other ranks
+        let (requests, mut received) = mpsc::channel::<(BarrierRequest, TcpStream, SocketAddr)>(64);
+
+        loop {
+            let (request, mut stream, peer) = tokio::select! {
+                accepted = self.listener.accept() => {
+                    let (mut stream, peer) = accepted.context("Coordination server accept failed")?;
+                    let requests = requests.clone();
+                    tokio::spawn(async move {
+                        if let Some(request) = read_request(&mut stream, peer).await {
+                            let _ = requests.send((request, stream, peer)).await;
+                        }
+                    });
+                    continue;
+                }
+                Some(request) = received.recv() => request,
+            };
+
+            if request.world_size != self.world_size || request.rank >= self.world_size {
+                let error = format!(
+                    "rank {} / world_size {} does not match coordination group of {} ranks",
+                    request.rank, request.world_size, self.world_size
+                );
+                reply(&mut stream, &BarrierReply { release_ns: 0, error: Some(error), shared: Vec::new() }).await;
+                continue;
+            }
+
+            if request.clock_probe {
+                reply(&mut stream, &BarrierReply { release_ns: now_ns(), error: None, shared: Vec::new() }).await;
+                continue;
+            }
+
+            if let Some(reason) = &request.abort {
+                let error = format!("rank {} aborted: {}", request.rank, reason);
+                warn!("🚨 {}; failing every barrier", error);
+                for (_, mut waiter) in waiting.drain().flat_map(|(_, entered)| entered) {
+                    reply(&mut waiter, &BarrierReply { release_ns: 0, error: Some(error.clone()), shared: Vec::new() }).await;
+                }
+                reply(&mut stream, &BarrierReply { release_ns: now_ns(), error: None, shared: Vec::new() }).await;
+                aborted.get_or_insert(error);
+                continue;
+            }
+            if let Some(error) = &aborted {
+                reply(&mut stream, &BarrierReply { release_ns: 0, error: Some(error.clone()), shared: Vec::new() }).await;
+                continue;
+            }
+
+            debug!("Rank {} ({}) entered barrier '{}'", request.rank, peer, request.barrier);
+            let barrier = request.barrier.clone();
+            let entered = waiting.entry(barrier.clone()).or_default();
+            entered.retain(|(waiter, _)| waiter.rank != request.rank);
+            entered.push((request, stream));
+            if entered.len() < self.world_size as usize {
+                continue;
+            }
+
+            let mut entered = waiting.remove(&barrier).unwrap_or_default();
+            entered.sort_by_key(|(waiter, _)| waiter.rank);
+            let error = entered.iter().any(|(waiter, _)| waiter.value != entered[0].0.value).then(|| {
+                let values: Vec<String> = entered
+                    .iter()
+                    .map(|(waiter, _)| format!("rank {}: {}", waiter.rank, waiter.value.as_deref().unwrap_or("-")))
+                    .collect();
+                format!("ranks disagree at barrier '{}' ({})", barrier, values.join(", "))
+            });
+            let shared = if entered.iter().any(|(waiter, _)| waiter.shared.is_some()) {
+                entered.iter_mut().map(|(waiter, _)| waiter.shared.take()).collect()
+            } else {
+                Vec::new()
+            };
+            match &error {
+                Some(e) => warn!("❌ {}", e),
+                None => info!("✅ All {} ranks passed barrier '{}'", self.world_size, barrier),
+            }
+            let release = BarrierReply { release_ns: now_ns(), error, shared };
+            for (_, mut stream) in entered {
+                reply(&mut stream, &release).await;
+            }
+            if barrier == FINISH_BARRIER {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Rank-side client of a CoordinationServer
+pub struct NetworkCoordinator {
+    endpoint: String,
+    rank: u32,
+    world_size: u32,
+}
+
+impl NetworkCoordinator {
+    pub fn new(endpoint: &str, rank: u32, world_size: u32) -> Self {
+        Self { endpoint: endpoint.to_string(), rank, world_size }
+    }
+
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    pub fn world_size(&self) -> u32 {
+        self.world_size
+    }
+
+    /// Block until every rank has entered `barrier`; returns the shared release time (ns since epoch)
+    pub async fn barrier(&self, barrier: &str, timeout: Duration) -> Result<u64> {
+        debug!("🚧 Rank {}: Entering network barrier '{}' at {}", self.rank, barrier, self.endpoint);
+        let release_ns = self.request(barrier, false, None, None, timeout).await?.release_ns;
+        debug!("✅ Rank {}: Released from network barrier '{}'", self.rank, barrier);
+        Ok(release_ns)
+    }
+
+    /// Barrier at which every rank must pass the same `value`; fails on every rank when any differs
+    pub async fn agree(&self, barrier: &str, value: &str, timeout: Duration) -> Result<u64> {
+        Ok(self.request(barrier, false, Some(value), None, timeout).await?.release_ns)
+    }
+
+    /// Barrier at which every rank contributes `value`; returns the release time and all contributions, by rank
+    pub async fn gather(&self, barrier: &str, value: &str, timeout: Duration) -> Result<(u64, Vec<Option<String>>)> {
+        let reply = self.request(barrier, false, None, Some(value), timeout).await?;
+        Ok((reply.release_ns, reply.shared))
+    }
+
+    /// Estimate this host's clock offset from the server with `probes` round trips
+    pub async fn estimate_clock_offset(&self, probes: u32) -> Result<ClockOffset> {
+        let mut best: Option<ClockOffset> = None;
+        for _ in 0..probes.max(1) {
+            let sent = now_ns();
+            let server = self.request("clock", true, None, None, Duration::from_secs(10)).await?.release_ns;
+            let received = now_ns();
+            let round_trip = received.saturating_sub(sent);
+            let midpoint = sent as i128 + round_trip as i128 / 2;
+            let probe = ClockOffset { offset_ns: (server as i128 - midpoint) as i64, uncertainty_ns: round_trip / 2 };
+            if best.is_none_or(|b| probe.uncertainty_ns < b.uncertainty_ns) {
+                best = Some(probe);
+            }
+        }
+        let best = best.unwrap_or_default();
+        info!(
+            "🕒 Rank {}: clock offset {:+.3} ms (± {:.3} ms) from coordinator",
+            self.rank, best.offset_ns as f64 / 1e6, best.uncertainty_ns as f64 / 1e6
+        );
+        Ok(best)
+    }
+
+    async fn request(
+        &self,
+        barrier: &str,
+        clock_probe: bool,
+        value: Option<&str>,
+        shared: Option<&str>,
+        timeout: Duration,
+    ) -> Result<BarrierReply> {
+        let mut stream = self.connect().await?;
+
+        let request = BarrierRequest {
+            barrier: barrier.to_string(),
+            rank: self.rank,
+            world_size: self.world_size,
+            clock_probe,
+            value: value.map(str::to_string),
+            shared: shared.map(str::to_string),
+            abort: None,
+        };
+        let mut line = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+        stream.write_all(&line).await.context("Failed to send barrier request")?;
+
+        let mut response = String::new();
+        tokio::time::timeout(timeout, BufReader::new(&mut stream).read_line(&mut response))
+            .await
+            .map_err(|_| anyhow::anyhow!("Timeout after {:?} at network barrier '{}'", timeout, barrier))?
+            .context("Coordination server closed the connection")?;
+        let reply: BarrierReply = serde_json::from_str(&response)
+            .with_context(|| format!("Malformed coordination reply: {:?}", response))?;
+        if let Some(error) = reply.error {
+            anyhow::bail!("Coordination server rejected rank {}: {}", self.rank, error);
+        }
+        Ok(reply)
+    }
+
+    /// Tell the server this rank gave up, so ranks waiting at a barrier fail now
+    /// rather than at their timeout
+    ///
+    /// Blocking with short timeouts: it is called on the way out of a failed run,
+    /// where there may be no chance to await it.
+    pub fn abort(&self, reason: &str) -> Result<()> {
+        use std::io::{BufRead, Write};
+        let request = BarrierRequest {
+            barrier: String::new(),
+            rank: self.rank,
+            world_size: self.world_size,
+            clock_probe: false,
+            value: None,
+            shared: None,
+            abort: Some(reason.to_string()),
+        };
+        let addr = std::net::ToSocketAddrs::to_socket_addrs(self.endpoint.as_str())
+            .with_context(|| format!("Cannot resolve coordination server {}", self.endpoint))?
+            .next()
+            .with_context(|| format!("{} resolves to no address", self.endpoint))?;
+        let mut stream = std::net::TcpStream::connect_timeout(&addr, ABORT_TIMEOUT)
+            .with_context(|| format!("Cannot reach coordination server {}", self.endpoint))?;
+        stream.set_read_timeout(Some(ABORT_TIMEOUT))?;
+        stream.set_write_timeout(Some(ABORT_TIMEOUT))?;
+        let mut line = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+        stream.write_all(&line).context("Failed to send abort")?;
+        // Wait for the acknowledgement, so the server has acted before this process exits
+        std::io::BufReader::new(stream).read_line(&mut String::new()).context("No acknowledgement of the abort")?;
+        Ok(())
+    }
+
+    /// The server may still be starting (or the SSH session slow), so retry for a while
+    async fn connect(&self) -> Result<TcpStream> {
+        let start = Instant::now();
+        loop {
+            match TcpStream::connect(&self.endpoint).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) if start.elapsed() < CONNECT_TIMEOUT => {
+                    debug!("Coordination server {} not reachable yet: {}", self.endpoint, e);
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Cannot reach coordination server {}", self.endpoint));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_network_barrier_releases_all_ranks() {
+        let server = CoordinationServer::bind("127.0.0.1:0", 3).await.unwrap();
+        let endpoint = server.local_addr().unwrap().to_string();
+        let handle = server.spawn();
+
+        let mismatched = NetworkCoordinator::new(&endpoint, 0, 2);
+        assert!(mismatched.barrier("execution_start", Duration::from_secs(5)).await.is_err());
+        // A client that connects and never sends a request does not block the ranks
+        let _silent = TcpStream::connect(&endpoint).await.unwrap();
+
+        let ranks: Vec<_> = (0..3)
+            .map(|rank| {
+                let coord = NetworkCoordinator::new(&endpoint, rank, 3);
+                tokio::spawn(async move {
+                    // Same host, so the offset is within the probe uncertainty
+                    let clock = coord.estimate_clock_offset(3).await?;
+                    assert!(clock.offset_ns.unsigned_abs() <= clock.uncertainty_ns + 1);
+                    let start = coord.barrier("execution_start", Duration::from_secs(10)).await?;
+                    // Rank 2 resolved a different data folder: every rank fails the agreement
+                    let folder = if rank == 2 { "file:///home/rank2/data" } else { "file:///data" };
+                    let agreed = coord.agree("data_folder", folder, Duration::from_secs(10)).await;
+                    assert!(agreed.unwrap_err().to_string().contains("rank 2: file:///home/rank2/data"));
+                    let end = coord.barrier(FINISH_BARRIER, Duration::from_secs(10)).await?;
+                    anyhow::Ok((start, end))
+                })
+            })
+            .collect();
+        let mut times = Vec::new();
+        for rank in ranks {
+            times.push(rank.await.unwrap().unwrap());
+        }
+        assert!(times.iter().all(|t| *t == times[0]));
+        handle.await.unwrap().unwrap();
+
+        let rank0 = ClockOffset { offset_ns: 5_000_000, uncertainty_ns: 100 };
+        let rank1 = ClockOffset { offset_ns: -2_000_000, uncertainty_ns: 200 };
+        let relative = rank1.relative_to(&rank0);
+        assert_eq!(relative, ClockOffset { offset_ns: -7_000_000, uncertainty_ns: 300 });
+        assert!((relative.correct(100.0) - 99.993).abs() < 1e-9);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_abort_fails_waiting_and_later_barriers() {
+        let server = CoordinationServer::bind("127.0.0.1:0", 3).await.unwrap();
+        let endpoint = server.local_addr().unwrap().to_string();
+        let _handle = server.spawn();
+
+        let waiter = NetworkCoordinator::new(&endpoint, 1, 3);
+        let waiting = tokio::spawn(async move { waiter.barrier("execution_start", Duration::from_secs(30)).await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let failed = NetworkCoordinator::new(&endpoint, 2, 3);
+        tokio::task::spawn_blocking(move || failed.abort("dataset is locked")).await.unwrap().unwrap();
+        let error = format!("{:#}", waiting.await.unwrap().unwrap_err());
+        assert!(error.contains("rank 2 aborted: dataset is locked"), "{}", error);
+
+        let late = NetworkCoordinator::new(&endpoint, 0, 3);
+        assert!(late.barrier("execution_start", Duration::from_secs(5)).await.is_err());
+    }
+}
@@ -0,0 +1,345 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/op_replay.rs
+//
+// s3dlio operation-log replay (`replay --op-log`)
+//
+// s3dlio can log every operation it issues with its start time, size and
+// duration (JSONL or TSV, optionally zstd-compressed; read with s3dlio-oplog).
+// Replay re-issues a log's GETs and PUTs against other storage, so a
+// production I/O pattern can be reproduced on a new system without the
+// application that produced it:
+//
+//   ordering   operations are issued in recorded start order
+//   timing     without --speed, one at a time, back to back; with --speed s,
+//              each operation is issued at its recorded offset / s, so
+//              operations that overlapped in the log overlap again (up to
+//              MAX_REPLAY_INFLIGHT at once)
+//   target     --target replaces the longest common directory prefix of the
+//              recorded URIs, keeping the key layout below it
+//
+// PUTs write generated data of the recorded size. Other operations (DELETE,
+// LIST, STAT, ...) are counted as skipped and never issued. A failed
+// operation is counted, not fatal. With --speed, the issue lag (how late an
+// operation started against its scaled schedule) shows whether the client
+// kept up with the log.
+//
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use s3dlio_oplog::{OpLogReader, OpType};
+use serde::Serialize;
+use std::collections::hash_map::{Entry, HashMap};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::canary::Access;
+use crate::dlio_compat::local_path_for_uri;
+use crate::units::Throughput;
+
+/// Most operations in flight during a timed replay
+pub const MAX_REPLAY_INFLIGHT: usize = 256;
+
+/// Operations replay re-issues
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ReplayOp {
+    Get,
+    Put,
+}
+
+/// One logged operation
+#[derive(Debug, Clone)]
+pub struct RecordedOp {
+    pub op: ReplayOp,
+    pub uri: String,
+    pub bytes: u64,
+    /// Start relative to the first logged operation
+    pub offset: Duration,
+    /// Duration in the log, when recorded
+    pub recorded_ms: Option<f64>,
+}
+
+/// The replayable part of an op-log, in start order
+#[derive(Debug, Clone, Default)]
+pub struct OpLog {
+    pub path: String,
+    pub ops: Vec<RecordedOp>,
+    /// Logged operations other than GET and PUT
+    pub skipped: usize,
+}
+
+impl OpLog {
+    pub fn load(path: &Path) -> Result<Self> {
+        let path = &crate::artifacts::resolve(path);
+        let reader = OpLogReader::from_file(path).with_context(|| format!("Failed to read op-log {:?}", path))?;
+        let mut entries: Vec<_> = reader.entries().iter().collect();
+        // Stable: operations logged with the same start keep their log order
+        entries.sort_by_key(|e| e.start);
+        let first = entries.first().map(|e| e.start);
+        let mut skipped = 0;
+        let ops = entries
+            .into_iter()
+            .filter_map(|e| {
+                let op = match e.op {
+                    OpType::GET => ReplayOp::Get,
+                    OpType::PUT => ReplayOp::Put,
+                    _ => {
+                        skipped += 1;
+                        return None;
+                    }
+                };
+                Some(RecordedOp {
+                    op,
+                    uri: logged_uri(&e.endpoint, &e.file),
+                    bytes: e.bytes,
+                    offset: (e.start - first.unwrap_or(e.start)).to_std().unwrap_or_default(),
+                    recorded_ms: e.duration_ns.map(|ns| ns as f64 / 1e6),
+                })
+            })
+            .collect();
+        Ok(Self { path: path.to_string_lossy().into_owned(), ops, skipped })
+    }
+}
+
+/// Full URI of a logged operation: `file` as is when it has a scheme, else under `endpoint`
+fn logged_uri(endpoint: &str, file: &str) -> String {
+    if file.contains("://") || endpoint.is_empty() {
+        file.to_string()
+    } else {
+        format!("{}/{}", endpoint.trim_end_matches('/'), file.trim_start_matches('/'))
+    }
+}
+
+/// Longest common prefix of `uris` that ends with '/'
+fn common_dir<'a>(uris: impl IntoIterator<Item = &'a str>) -> String {
+    let mut uris = uris.into_iter();
+    let Some(first) = uris.next() else { return String::new() };
+    let common = uris.fold(first.len(), |len, uri| {
+        first.bytes().zip(uri.bytes()).take(len).take_while(|(a, b)| a == b).count()
+    });
+    match first.as_bytes()[..common].iter().rposition(|&b| b == b'/') {
+        Some(slash) => first[..=slash].to_string(),
+        None => String::new(),
+    }
+}
+
+/// Replay settings
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+    /// Prefix replacing the recorded URIs' common prefix
+    pub target: Option<String>,
+    /// Timing fidelity: 1.0 replays at the recorded pace, 2.0 twice as fast; None back to back
+    pub speed: Option<f64>,
+}
+
+/// Replayed operations of one kind
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpStats {
+    pub count: u64,
+    pub errors: u64,
+    pub bytes: u64,
+    pub throughput: Throughput,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    /// Median duration of the same operations in the log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_p50_ms: Option<f64>,
+}
+
+/// `replay --op-log` results
+#[derive(Debug, Clone, Serialize)]
+pub struct OpReplayReport {
+    pub op_log: String,
+    /// Recorded URI prefix replaced by `target`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub speed: Option<f64>,
+    pub ops: u64,
+    pub skipped: usize,
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_error: Option<String>,
+    /// First to last logged operation start
+    pub recorded_seconds: f64,
+    pub replay_seconds: f64,
+    pub get: OpStats,
+    pub put: OpStats,
+    /// How late operations were issued against the scaled schedule (timed replay only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag_p99_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag_max_ms: Option<f64>,
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        0.0
+    } else {
+        sorted[((p * (sorted.len() - 1) as f64) as usize).min(sorted.len() - 1)]
+    }
+}
+
+fn sorted(mut values: Vec<f64>) -> Vec<f64> {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values
+}
+
+struct Outcome {
+    op: ReplayOp,
+    result: Result<u64>,
+    latency_ms: f64,
+    lag_ms: f64,
+}
+
+fn op_stats(op: ReplayOp, ops: &[RecordedOp], outcomes: &[Outcome], seconds: f64) -> OpStats {
+    let done: Vec<&Outcome> = outcomes.iter().filter(|o| o.op == op).collect();
+    let latencies = sorted(done.iter().filter(|o| o.result.is_ok()).map(|o| o.latency_ms).collect());
+    let recorded = sorted(ops.iter().filter(|o| o.op == op).filter_map(|o| o.recorded_ms).collect());
+    let bytes = done.iter().filter_map(|o| o.result.as_ref().ok()).sum();
+    OpStats {
+        count: done.len() as u64,
+        errors: done.iter().filter(|o| o.result.is_err()).count() as u64,
+        bytes,
+        throughput: Throughput::new(bytes, seconds),
+        p50_ms: percentile(&latencies, 0.50),
+        p99_ms: percentile(&latencies, 0.99),
+        max_ms: latencies.last().copied().unwrap_or(0.0),
+        recorded_p50_ms: (!recorded.is_empty()).then(|| percentile(&recorded, 0.50)),
+    }
+}
+
+/// Re-issue the log's operations in order, against `options.target` when set
+pub async fn replay(log: &OpLog, options: &ReplayOptions) -> Result<OpReplayReport> {
+    if let Some(speed) = options.speed {
+        anyhow::ensure!(speed.is_finite() && speed > 0.0, "--speed must be a positive number, got {}", speed);
+    }
+    let source_prefix = options.target.as_ref().map(|_| common_dir(log.ops.iter().map(|o| o.uri.as_str())));
+    let uri_of = |op: &RecordedOp| match (&source_prefix, &options.target) {
+        (Some(prefix), Some(target)) => format!("{}/{}", target.trim_end_matches('/'), &op.uri[prefix.len()..]),
+        _ => op.uri.clone(),
+    };
+
+    // One object store per scheme; local paths go through the filesystem
+    let mut stores: HashMap<String, Access> = HashMap::new();
+    for op in &log.ops {
+        let uri = uri_of(op);
+        if local_path_for_uri(&uri).is_none() {
+            let scheme = uri.split("://").next().unwrap_or_default().to_string();
+            if let Entry::Vacant(slot) = stores.entry(scheme) {
+                slot.insert(Access::for_uri(&uri)?);
+            }
+        }
+    }
+
+    let recorded_seconds = log.ops.last().map_or(0.0, |o| o.offset.as_secs_f64());
+    info!("🔁 Replaying {} operations ({} skipped) from {}{}, {}", log.ops.len(), log.skipped, log.path,
+          options.target.as_ref().map(|t| format!(" against {}", t)).unwrap_or_default(),
+          options.speed.map(|s| format!("at {}x recorded pace", s)).unwrap_or_else(|| "back to back".to_string()));
+
+    let start = Instant::now();
+    let inflight = if options.speed.is_some() { MAX_REPLAY_INFLIGHT } else { 1 };
+    let outcomes: Vec<Outcome> = stream::iter(&log.ops)
+        .map(|op| {
+            let (uri, stores) = (uri_of(op), &stores);
+            async move {
+                let due = options.speed.map(|speed| start + op.offset.div_f64(speed));
+                if let Some(due) = due {
+                    tokio::time::sleep_until(due.into()).await;
+                }
+                let issued = Instant::now();
+                let lag_ms = due.map_or(0.0, |due| issued.saturating_duration_since(due).as_secs_f64() * 1000.0);
+                let local;
+                let access = match local_path_for_uri(&uri) {
+                    Some(path) => {
+                        local = Access::Local(path);
+                        &local
+                    }
+                    None => &stores[uri.split("://").next().unwrap_or_default()],
+                };
+                let result = match op.op {
+                    ReplayOp::Get => access.get(&uri).await.map(|data| data.len() as u64),
+                    ReplayOp::Put => {
+                        let data = s3dlio::generate_controlled_data(op.bytes as usize, 0, 0);
+                        access.put(&uri, &data).await.map(|()| op.bytes)
+                    }
+                };
+                Outcome { op: op.op, result, latency_ms: issued.elapsed().as_secs_f64() * 1000.0, lag_ms }
+            }
+        })
+        .buffered(inflight)
+        .collect()
+        .await;
+    let replay_seconds = start.elapsed().as_secs_f64();
+
+    let errors = outcomes.iter().filter(|o| o.result.is_err()).count() as u64;
+    let first_error = outcomes.iter().find_map(|o| o.result.as_ref().err()).map(|e| format!("{:#}", e));
+    if let Some(e) = &first_error {
+        warn!("⚠️  {} of {} replayed operations failed; first: {}", errors, outcomes.len(), e);
+    }
+    let lags = sorted(outcomes.iter().map(|o| o.lag_ms).collect());
+    let report = OpReplayReport {
+        op_log: log.path.clone(),
+        source_prefix,
+        target: options.target.clone(),
+        speed: options.speed,
+        ops: outcomes.len() as u64,
+        skipped: log.skipped,
+        errors,
+        first_error,
+        recorded_seconds,
+        replay_seconds,
+        get: op_stats(ReplayOp::Get, &log.ops, &outcomes, replay_seconds),
+        put: op_stats(ReplayOp::Put, &log.ops, &outcomes, replay_seconds),
+        lag_p99_ms: options.speed.map(|_| percentile(&lags, 0.99)),
+        lag_max_ms: options.speed.map(|_| lags.last().copied().unwrap_or(0.0)),
+    };
+    info!("🔁 Replayed {} operations in {:.3}s (log {:.3}s): {} GETs {}, {} PUTs {}, {} errors",
+          report.ops, report.replay_seconds, report.recorded_seconds, report.get.count, report.get.throughput.display(),
+          report.put.count, report.put.throughput.display(), report.errors);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replay_preserves_order_and_retargets() {
+        assert_eq!(logged_uri("s3://prod", "/train/a.npz"), "s3://prod/train/a.npz");
+        assert_eq!(logged_uri("s3://prod", "s3://prod/train/a.npz"), "s3://prod/train/a.npz");
+        assert_eq!(common_dir(["s3://prod/train/a/1.npz", "s3://prod/train/b.npz"]), "s3://prod/train/");
+        assert_eq!(common_dir(["s3://prod/train/a.npz"]), "s3://prod/train/");
+
+        // PUT then GET of the same key: only works when issued in order
+        let dir = tempfile::tempdir().unwrap();
+        let op = |op, key: &str, bytes, ms| RecordedOp {
+            op,
+            uri: format!("s3://prod/train/{}", key),
+            bytes,
+            offset: Duration::from_millis(ms),
+            recorded_ms: Some(2.0),
+        };
+        let log = OpLog {
+            path: "trace.jsonl.zst".to_string(),
+            ops: vec![op(ReplayOp::Put, "a/1.npz", 1000, 0), op(ReplayOp::Get, "a/1.npz", 1000, 20), op(ReplayOp::Get, "missing.npz", 10, 40)],
+            skipped: 1,
+        };
+        let target = format!("file://{}/replayed", dir.path().display());
+        for speed in [None, Some(2.0)] {
+            let options = ReplayOptions { target: Some(target.clone()), speed };
+            let report = replay(&log, &options).await.unwrap();
+            assert_eq!(report.source_prefix.as_deref(), Some("s3://prod/train/"));
+            assert_eq!((report.ops, report.skipped, report.errors), (3, 1, 1));
+            assert_eq!((report.put.bytes, report.get.bytes, report.get.errors), (1000, 1000, 1));
+            assert_eq!(report.get.recorded_p50_ms, Some(2.0));
+            assert_eq!(report.lag_max_ms.is_some(), speed.is_some());
+        }
+        assert_eq!(std::fs::metadata(dir.path().join("replayed/a/1.npz")).unwrap().len(), 1000);
+        assert!(replay(&log, &ReplayOptions { speed: Some(0.0), ..Default::default() }).await.is_err());
+    }
+}
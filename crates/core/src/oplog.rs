@@ -0,0 +1,349 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/oplog.rs
+//
+// Storage operation log (`run --record-oplog`)
+//
+// Records the training phase's storage operations, one line each, in
+// s3dlio's op-log layout so s3dlio-oplog consumers (and `replay --op-log`)
+// can read them:
+//
+//   idx thread op client_id n_objects bytes endpoint file error start
+//   first_byte end duration_ns rank
+//
+// `file` is the full object URI, `endpoint` its scheme and bucket or host,
+// `client_id` the host name and `rank` the rank that issued the operation.
+// Start and end are UTC wall-clock times with nanoseconds; first_byte is not
+// measured and left empty. The file name picks the format: `.tsv` writes a
+// header and tab-separated rows, anything else JSON lines, and a trailing
+// `.zst` compresses either with zstd (`run --compress` appends it by default).
+//
+// While recording, training reads go through a dl-driver backend (as with
+// request timing) so the dataset LIST and every GET are logged. Checkpoint
+// writes are not logged: s3dlio's CheckpointStore picks its own object names
+// and sizes, so there is no per-object operation to record or replay; their
+// timings are under "checkpointing" in the results. With several ranks each
+// writes its own log (see `artifacts::rank_path`).
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::backends::DataBackend;
+
+/// Op-log columns: s3dlio's, then the issuing rank
+pub const COLUMNS: &[&str] = &[
+    "idx", "thread", "op", "client_id", "n_objects", "bytes", "endpoint", "file", "error", "start", "first_byte", "end",
+    "duration_ns", "rank",
+];
+
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Small per-thread number for the `thread` column
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Line format of an op-log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpLogFormat {
+    Jsonl,
+    Tsv,
+}
+
+impl OpLogFormat {
+    /// Format and compression for a file name: `.tsv[.zst]` or JSON lines, `.zst` compressed
+    pub fn for_path(path: &Path) -> (Self, bool) {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let (stem, compressed) = match name.strip_suffix(".zst") {
+            Some(stem) => (stem.to_string(), true),
+            None => (name, false),
+        };
+        (if stem.ends_with(".tsv") { Self::Tsv } else { Self::Jsonl }, compressed)
+    }
+}
+
+/// One operation as a JSON line
+#[derive(Serialize)]
+struct OpRecord<'a> {
+    idx: u64,
+    thread: u64,
+    op: &'a str,
+    client_id: &'a str,
+    n_objects: u64,
+    bytes: u64,
+    endpoint: &'a str,
+    file: &'a str,
+    error: Option<&'a str>,
+    start: String,
+    first_byte: Option<String>,
+    end: String,
+    duration_ns: u64,
+    rank: u32,
+}
+
+impl OpRecord<'_> {
+    fn tsv(&self) -> String {
+        let error = self.error.unwrap_or_default().replace(['\t', '\n'], " ");
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t\t{}\t{}\t{}",
+            self.idx, self.thread, self.op, self.client_id, self.n_objects, self.bytes, self.endpoint, self.file, error,
+            self.start, self.end, self.duration_ns, self.rank
+        )
+    }
+}
+
+/// Scheme and bucket/host of a URI ("s3://bucket"), "file://" for local files
+fn endpoint(uri: &str) -> &str {
+    match uri.split_once("://") {
+        Some((scheme, rest)) => &uri[..scheme.len() + 3 + rest.find('/').unwrap_or(rest.len())],
+        None => "",
+    }
+}
+
+fn timestamp(t: SystemTime) -> String {
+    DateTime::<Utc>::from(t).to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Sink {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(w) => w,
+            Self::Zstd(w) => w,
+        }
+    }
+
+    fn close(self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(mut w) => w.flush(),
+            Self::Zstd(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    sink: Option<Sink>,
+    /// First write error; the log is incomplete from there on
+    error: Option<String>,
+}
+
+/// `oplog` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct OpLogSummary {
+    pub path: String,
+    pub format: OpLogFormat,
+    pub compressed: bool,
+    pub ops: u64,
+}
+
+/// Appends one rank's operations to an op-log file
+pub struct OpLogWriter {
+    path: String,
+    format: OpLogFormat,
+    compressed: bool,
+    rank: u32,
+    client_id: String,
+    next_idx: AtomicU64,
+    state: Mutex<State>,
+}
+
+impl OpLogWriter {
+    pub fn create(path: &Path, rank: u32) -> Result<Self> {
+        let (format, compressed) = OpLogFormat::for_path(path);
+        let file = BufWriter::new(File::create(path).with_context(|| format!("Failed to create op-log {:?}", path))?);
+        let mut sink = if compressed { Sink::Zstd(zstd::Encoder::new(file, 3)?) } else { Sink::Plain(file) };
+        if format == OpLogFormat::Tsv {
+            writeln!(sink.writer(), "{}", COLUMNS.join("\t"))?;
+        }
+        Ok(Self {
+            path: path.to_string_lossy().into_owned(),
+            format,
+            compressed,
+            rank,
+            client_id: crate::metrics::host_name(),
+            next_idx: AtomicU64::new(0),
+            state: Mutex::new(State { sink: Some(sink), error: None }),
+        })
+    }
+
+    /// Log one operation on `uri` that moved `(objects, bytes)`
+    pub fn record(&self, op: &str, uri: &str, size: (u64, u64), start: SystemTime, elapsed: Duration, error: Option<&str>) {
+        let record = OpRecord {
+            idx: self.next_idx.fetch_add(1, Ordering::Relaxed),
+            thread: THREAD.with(|t| *t),
+            op,
+            client_id: &self.client_id,
+            n_objects: size.0,
+            bytes: size.1,
+            endpoint: endpoint(uri),
+            file: uri,
+            error,
+            start: timestamp(start),
+            first_byte: None,
+            end: timestamp(start + elapsed),
+            duration_ns: elapsed.as_nanos() as u64,
+            rank: self.rank,
+        };
+        let line = match self.format {
+            OpLogFormat::Jsonl => serde_json::to_string(&record).expect("op records serialize"),
+            OpLogFormat::Tsv => record.tsv(),
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.error.is_some() {
+            return;
+        }
+        if let Some(sink) = state.sink.as_mut() {
+            if let Err(e) = writeln!(sink.writer(), "{}", line) {
+                state.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Run `operation` on `uri` and log it; `size` gives the (objects, bytes) a success moved
+    pub async fn timed<T>(&self, op: &str, uri: &str, operation: impl Future<Output = Result<T>>, size: impl FnOnce(&T) -> (u64, u64)) -> Result<T> {
+        let (start, clock) = (SystemTime::now(), Instant::now());
+        let result = operation.await;
+        match &result {
+            Ok(value) => self.record(op, uri, size(value), start, clock.elapsed(), None),
+            Err(e) => self.record(op, uri, (0, 0), start, clock.elapsed(), Some(&format!("{:#}", e))),
+        }
+        result
+    }
+
+    fn close(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(sink) = state.sink.take() {
+            sink.close().with_context(|| format!("Failed to finish op-log {}", self.path))?;
+        }
+        match &state.error {
+            Some(e) => anyhow::bail!("Op-log {} is incomplete: {}", self.path, e),
+            None => Ok(()),
+        }
+    }
+
+    /// Flush (and end the zstd frame); later operations are not logged
+    pub fn finish(&self) -> Result<OpLogSummary> {
+        self.close()?;
+        Ok(OpLogSummary {
+            path: self.path.clone(),
+            format: self.format,
+            compressed: self.compressed,
+            ops: self.next_idx.load(Ordering::Relaxed),
+        })
+    }
+}
+
+impl Drop for OpLogWriter {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// DataBackend wrapper logging each LIST, GET and PUT
+pub struct RecordingBackend {
+    inner: Arc<dyn DataBackend>,
+    log: Arc<OpLogWriter>,
+    /// Data folder, the URI of LIST operations
+    prefix: String,
+}
+
+impl RecordingBackend {
+    pub fn new(inner: Arc<dyn DataBackend>, log: Arc<OpLogWriter>, prefix: &str) -> Self {
+        Self { inner, log, prefix: prefix.to_string() }
+    }
+}
+
+#[async_trait]
+impl DataBackend for RecordingBackend {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        self.log.timed("LIST", &self.prefix, self.inner.list(), |uris| (uris.len() as u64, 0)).await
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        self.log.timed("GET", uri, self.inner.get(uri), |data| (1, data.len() as u64)).await
+    }
+
+    async fn head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        self.log.timed("GET", uri, self.inner.head(uri, len), |data| (1, data.len() as u64)).await
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.log.timed("PUT", uri, self.inner.put(uri, data), |()| (1, data.len() as u64)).await
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.inner.report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::MemBackend;
+    use crate::dlio_compat::DlioConfig;
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn test_oplog_records_backend_ops_as_jsonl_and_tsv() {
+        assert_eq!(OpLogFormat::for_path(Path::new("run.tsv.zst")), (OpLogFormat::Tsv, true));
+        assert_eq!(OpLogFormat::for_path(Path::new("run.jsonl")), (OpLogFormat::Jsonl, false));
+        assert_eq!(endpoint("s3://bucket/train/a.npz"), "s3://bucket");
+        assert_eq!(endpoint("file:///data/a.npz"), "file://");
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = DlioConfig::from_yaml("dataset:\n  data_folder: mem://oplog\n  num_files_train: 3\n  record_length_bytes: 64\nreader: {}\n").unwrap();
+        for name in ["ops.jsonl.zst", "ops.tsv"] {
+            let path = dir.path().join(name);
+            let log = Arc::new(OpLogWriter::create(&path, 2).unwrap());
+            let backend = RecordingBackend::new(Arc::new(MemBackend::new(&config).unwrap()), Arc::clone(&log), "mem://oplog");
+            let uris = backend.list().await.unwrap();
+            backend.get(&uris[0]).await.unwrap();
+            assert!(backend.get("mem://oplog/missing.npz").await.is_err());
+            let summary = log.finish().unwrap();
+            assert_eq!(summary.ops, 3);
+
+            let mut text = String::new();
+            if summary.compressed {
+                zstd::Decoder::new(File::open(&path).unwrap()).unwrap().read_to_string(&mut text).unwrap();
+            } else {
+                text = std::fs::read_to_string(&path).unwrap();
+            }
+            let lines: Vec<&str> = text.lines().collect();
+            match summary.format {
+                OpLogFormat::Jsonl => {
+                    let ops: Vec<serde_json::Value> = lines.iter().map(|l| serde_json::from_str(l).unwrap()).collect();
+                    assert_eq!((ops[0]["op"].as_str(), ops[0]["n_objects"].as_u64()), (Some("LIST"), Some(3)));
+                    assert_eq!((ops[1]["file"].as_str(), ops[1]["endpoint"].as_str()), (Some(uris[0].as_str()), Some("mem://oplog")));
+                    assert!(ops[1]["bytes"].as_u64().unwrap() > 0 && ops[1]["error"].is_null());
+                    assert_eq!((ops[2]["rank"].as_u64(), ops[2]["error"].is_string()), (Some(2), true));
+                    assert!(ops[1]["end"].as_str() >= ops[1]["start"].as_str());
+                }
+                OpLogFormat::Tsv => {
+                    assert_eq!(lines[0].split('\t').collect::<Vec<_>>(), COLUMNS);
+                    let get: Vec<&str> = lines[2].split('\t').collect();
+                    assert_eq!((get.len(), get[2], get[7], get[13]), (COLUMNS.len(), "GET", uris[0].as_str(), "2"));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/overwrite.rs
+//
+// Overwrite (checkpoint-style churn) workload for versioned buckets and GC behavior
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+use crate::encryption::{EncryptionSettings, SseWriter};
+use crate::multipart::{self, MultipartSettings, MultipartStats};
+use s3dlio::object_store::store_for_uri;
+
+/// Resolved overwrite workload settings
+#[derive(Debug, Clone)]
+pub struct OverwriteSettings {
+    pub working_set: usize,
+    pub object_size_bytes: usize,
+    pub iterations: u32,
+    pub prefix_uri: String,
+}
+
+impl OverwriteSettings {
+    pub fn from_config(config: &DlioConfig) -> Self {
+        let ow = config.overwrite.clone().unwrap_or_default();
+        let default_size = config.dataset.record_length_bytes.unwrap_or(1024)
+            * config.dataset.num_samples_per_file.unwrap_or(1);
+        let prefix = ow.prefix.unwrap_or_else(|| "overwrite".to_string());
+        let data_folder = config.dataset.data_folder.trim_end_matches('/');
+
+        Self {
+            working_set: ow.working_set.unwrap_or(16).max(1),
+            object_size_bytes: ow.object_size_bytes.unwrap_or(default_size),
+            iterations: ow.iterations.unwrap_or(10).max(1),
+            prefix_uri: format!("{}/{}/", data_folder, prefix.trim_matches('/')),
+        }
+    }
+
+    fn object_uri(&self, index: usize) -> String {
+        format!("{}object_{:06}.bin", self.prefix_uri, index)
+    }
+}
+
+/// Overwrite latency for one pass over the working set
+#[derive(Debug, Clone, Serialize)]
+pub struct OverwriteIteration {
+    pub iteration: u32,
+    pub elapsed_s: f64,
+    pub mean_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Results of an overwrite workload run
+#[derive(Debug, Clone, Serialize)]
+pub struct OverwriteReport {
+    pub prefix_uri: String,
+    pub working_set: usize,
+    pub object_size_bytes: usize,
+    pub iterations: Vec<OverwriteIteration>,
+    pub logical_bytes: u64,
+    /// Bytes consumed on the backend, when the backend exposes it (file:// only)
+    pub physical_bytes: Option<u64>,
+    /// physical_bytes / logical_bytes, when available
+    pub space_amplification: Option<f64>,
+}
+
+/// Repeatedly overwrites a fixed working set of objects and tracks latency over time
+pub struct OverwriteRunner {
+    config: DlioConfig,
+    settings: OverwriteSettings,
+}
+
+impl OverwriteRunner {
+    pub fn new(config: DlioConfig) -> Self {
+        let settings = OverwriteSettings::from_config(&config);
+        Self { config, settings }
+    }
+
+    pub fn settings(&self) -> &OverwriteSettings {
+        &self.settings
+    }
+
+    pub async fn run(&self) -> Result<OverwriteReport> {
+        let s = &self.settings;
+        info!("🔁 Overwrite workload: {} objects × {} bytes, {} iterations under {}",
+              s.working_set, s.object_size_bytes, s.iterations, s.prefix_uri);
+
+        let store = store_for_uri(&s.prefix_uri)
+            .with_context(|| format!("Failed to create object store for {}", s.prefix_uri))?;
+        let mp_settings = MultipartSettings::from_config(&self.config);
+        let mp_stats = MultipartStats::new();
+        // Requested SSE headers need the encrypting writer
+        let sse = match EncryptionSettings::from_config(&self.config)? {
+            Some(settings) => Some(SseWriter::new(settings, &self.config).await?),
+            None => None,
+        };
+
+        let mut iterations = Vec::with_capacity(s.iterations as usize);
+        for iteration in 0..s.iterations {
+            // Fresh content each pass so dedup-capable backends still store new data
+            let data = s3dlio::generate_controlled_data(s.object_size_bytes, 0, 0);
+            let pass_start = Instant::now();
+            let mut latencies = Vec::with_capacity(s.working_set);
+
+            for index in 0..s.working_set {
+                let uri = s.object_uri(index);
+                let start = Instant::now();
+                match &sse {
+                    Some(writer) => multipart::upload(writer, &uri, &data, &mp_settings, &mp_stats).await,
+                    None => multipart::put_object(store.as_ref(), &uri, &data, &mp_settings, &mp_stats).await,
+                }
+                .with_context(|| format!("Overwrite of {} failed", uri))?;
+                latencies.push(start.elapsed());
+            }
+
+            let stats = summarize(iteration, pass_start.elapsed(), &latencies);
+            info!("Overwrite pass {}/{}: mean {:.2}ms, p99 {:.2}ms, max {:.2}ms",
+                  iteration + 1, s.iterations, stats.mean_ms, stats.p99_ms, stats.max_ms);
+            iterations.push(stats);
+        }
+
+        let logical_bytes = (s.working_set * s.object_size_bytes) as u64;
+        let physical_bytes = local_path_for_uri(&s.prefix_uri).and_then(|p| allocated_bytes(&p));
+        let space_amplification = physical_bytes
+            .filter(|_| logical_bytes > 0)
+            .map(|p| p as f64 / logical_bytes as f64);
+        debug!("Overwrite space: logical={} physical={:?}", logical_bytes, physical_bytes);
+
+        Ok(OverwriteReport {
+            prefix_uri: s.prefix_uri.clone(),
+            working_set: s.working_set,
+            object_size_bytes: s.object_size_bytes,
+            iterations,
+            logical_bytes,
+            physical_bytes,
+            space_amplification,
+        })
+    }
+}
+
+fn summarize(iteration: u32, elapsed: Duration, latencies: &[Duration]) -> OverwriteIteration {
+    let mut ms: Vec<f64> = latencies.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+    ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mean_ms = if ms.is_empty() { 0.0 } else { ms.iter().sum::<f64>() / ms.len() as f64 };
+    let p99_ms = if ms.is_empty() {
+        0.0
+    } else {
+        ms[((0.99 * (ms.len() - 1) as f64) as usize).min(ms.len() - 1)]
+    };
+
+    OverwriteIteration {
+        iteration,
+        elapsed_s: elapsed.as_secs_f64(),
+        mean_ms,
+        p99_ms,
+        max_ms: ms.last().copied().unwrap_or(0.0),
+    }
+}
+
+/// Sum of allocated blocks under a local directory (unix only)
+#[cfg(unix)]
+fn allocated_bytes(path: &std::path::Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    let mut total = 0u64;
+    for entry in std::fs::read_dir(path).ok()? {
+        let meta = entry.ok()?.metadata().ok()?;
+        if meta.is_file() {
+            total += meta.blocks() * 512;
+        }
+    }
+    Some(total)
+}
+
+#[cfg(not(unix))]
+fn allocated_bytes(_path: &std::path::Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_defaults_and_prefix() {
+        let yaml = r#"
+dataset:
+  data_folder: s3://bucket/data/
+  record_length_bytes: 4096
+  num_samples_per_file: 2
+reader: {}
+overwrite:
+  working_set: 4
+"#;
+        let config = DlioConfig::from_yaml(yaml).unwrap();
+        let settings = OverwriteSettings::from_config(&config);
+        assert_eq!(settings.working_set, 4);
+        assert_eq!(settings.object_size_bytes, 8192);
+        assert_eq!(settings.iterations, 10);
+        assert_eq!(settings.prefix_uri, "s3://bucket/data/overwrite/");
+        assert_eq!(settings.object_uri(3), "s3://bucket/data/overwrite/object_000003.bin");
+    }
+
+    #[test]
+    fn test_summarize_latencies() {
+        let latencies: Vec<Duration> = (1..=4).map(Duration::from_millis).collect();
+        let it = summarize(0, Duration::from_millis(10), &latencies);
+        assert!((it.mean_ms - 2.5).abs() < 1e-6);
+        assert!((it.max_ms - 4.0).abs() < 1e-6);
+    }
+}
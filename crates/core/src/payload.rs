@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/payload.rs
+//
+// Contents of generated dataset files, per format
+//
+// `dl-driver generate` and the `testing` fixtures build files with the same
+// function, so fixture datasets hold exactly what generation writes.
+//
+use anyhow::{Context, Result};
+use real_dlio_formats::{
+    npz::NpzStreamingFormat, CsvFormat, Hdf5Format, JpegFormat, NpyDtype, PngFormat, StreamingFormat, TfRecordFormat,
+};
+use tracing::warn;
+
+use crate::dlio_compat::DlioConfig;
+
+/// Build the contents of one generated file in the configured format
+///
+/// NPZ files hold real numpy arrays shaped `[num_samples_per_file, *record_dims]`
+/// with dtype `record_element_type` (DLIO default uint8). HDF5 files are real
+/// HDF5 with a float32 `records` dataset of one row per sample, as DLIO writes
+/// them, and TFRecord files hold one tf.train.Example per sample. CSV files
+/// have one row per sample; JPEG and PNG files are one image of
+/// `record_size`. Other formats use raw bytes. `record_size` is
+/// `record_length_bytes`, or the file's draw under `record_length_bytes_stdev`.
+/// With `seeded = (seed, file_index)` the samples carry verifiable seeded
+/// content (raw records for formats other than NPZ; container formats are
+/// rejected). Builds without libhdf5 write dl-driver's SHD5 layout for HDF5.
+pub fn generate_file_payload(config: &DlioConfig, record_size: usize, seeded: Option<(u64, u64)>) -> Result<Vec<u8>> {
+    let samples = config.dataset.num_samples_per_file.unwrap_or(1);
+    if seeded.is_some() {
+        crate::content::check_format(config)?;
+    }
+
+    match config.dataset.format.as_deref().unwrap_or("npz") {
+        "npz" => {
+            let dtype = NpyDtype::from_dlio(config.dataset.record_element_type.as_deref().unwrap_or("uint8"))?;
+            let dims = config.dataset.record_dims.clone()
+                .unwrap_or_else(|| vec![(record_size / dtype.item_size()).max(1)]);
+            let dims_bytes = dims.iter().product::<usize>() * dtype.item_size();
+            if config.dataset.record_dims.is_some() && dims_bytes != record_size {
+                warn!("record_dims {:?} × {} = {} bytes overrides record_length_bytes ({})",
+                      dims, dtype.descr(), dims_bytes, record_size);
+            }
+
+            let mut format = NpzStreamingFormat::new([vec![samples], dims].concat(), 2).with_dtype(dtype);
+            if let Some((seed, file_index)) = seeded {
+                format = format.with_data(crate::content::file_payload(seed, file_index, samples, dims_bytes));
+            }
+            format.generate_bytes("").context("Failed to build NPZ payload")
+        }
+        "hdf5" => {
+            let dims = config.dataset.record_dims.clone().unwrap_or_else(|| vec![(record_size / 4).max(1)]);
+            let format = Hdf5Format::new([vec![samples], dims].concat(), Some("records".to_string()));
+            let bytes = if real_dlio_formats::HDF5_ENABLED { format.file_bytes() } else { format.generate_bytes("") };
+            bytes.context("Failed to build HDF5 payload")
+        }
+        "tfrecord" => TfRecordFormat::new(samples, record_size)
+            .generate_bytes("")
+            .context("Failed to build TFRecord payload"),
+        "csv" => CsvFormat::new(samples, record_size)
+            .generate_bytes("")
+            .context("Failed to build CSV payload"),
+        // One image per file, as DLIO writes image datasets
+        "jpeg" | "jpg" => JpegFormat::new(record_size).generate_bytes(""),
+        "png" => PngFormat::new(record_size).generate_bytes(""),
+        _ => Ok(match seeded {
+            Some((seed, file_index)) => crate::content::file_payload(seed, file_index, samples, record_size),
+            None => generate_synthetic_data(samples, record_size),
+        }),
+    }
+}
+
+/// Raw file contents: a repeating 0..=255 byte pattern
+fn generate_synthetic_data(samples: usize, record_size: usize) -> Vec<u8> {
+    (0..samples * record_size).map(|i| (i % 256) as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigBuilder;
+
+    #[test]
+    fn test_seeded_payloads_keep_the_unseeded_size() {
+        for format in ["npz", "bin"] {
+            let config = ConfigBuilder::new("file:///tmp/payload").format(format).record_length(100).build().unwrap();
+            let plain = generate_file_payload(&config, 100, None).unwrap();
+            let seeded = generate_file_payload(&config, 100, Some((7, 3))).unwrap();
+            assert_eq!(plain.len(), seeded.len(), "{}", format);
+            assert_ne!(plain, seeded, "{}", format);
+        }
+    }
+}
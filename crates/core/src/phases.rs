@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/phases.rs
+//
+// Wall-clock time per lifecycle phase of a run
+//
+//   generate     dataset generation (workflow.generate_data)
+//   list         dataset listing / backend setup before the first epoch
+//   train        epochs, from the first batch request to the last batch
+//   checkpoint   checkpoint step (workflow.checkpoint)
+//   teardown     finish barrier and coordination cleanup
+//
+// Generation and listing often dominate short exploratory runs; the measured
+// training time (and so throughput and AU) never includes them, so they are
+// reported separately under "phases" in the results. Phases recorded more
+// than once (e.g. over --repeats) accumulate. Point-in-time marks (first
+// occurrence only) feed the startup breakdown, see startup.rs.
+//
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// One phase's accumulated wall-clock time
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PhaseTime {
+    pub phase: String,
+    pub seconds: f64,
+    /// Share of the total over all recorded phases
+    pub share: f64,
+}
+
+/// Ordered per-phase wall-clock timers, shared between the CLI and the workload runner
+#[derive(Default)]
+pub struct PhaseTimes {
+    phases: Mutex<Vec<(String, Duration)>>,
+    marks: Mutex<Vec<(String, Instant)>>,
+}
+
+impl PhaseTimes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `elapsed` to `phase`, keeping phases in first-recorded order
+    pub fn record(&self, phase: &str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap();
+        match phases.iter_mut().find(|(name, _)| name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((phase.to_string(), elapsed)),
+        }
+    }
+
+    /// Run `future` and record its wall-clock time under `phase`
+    pub async fn time<F: Future>(&self, phase: &str, future: F) -> F::Output {
+        let start = Instant::now();
+        let output = future.await;
+        self.record(phase, start.elapsed());
+        output
+    }
+
+    /// Note that `event` happened now; only its first occurrence is kept
+    pub fn mark(&self, event: &str) {
+        self.mark_at(event, Instant::now());
+    }
+
+    pub fn mark_at(&self, event: &str, at: Instant) {
+        let mut marks = self.marks.lock().unwrap();
+        if !marks.iter().any(|(name, _)| name == event) {
+            marks.push((event.to_string(), at));
+        }
+    }
+
+    pub fn mark_time(&self, event: &str) -> Option<Instant> {
+        self.marks.lock().unwrap().iter().find(|(name, _)| name == event).map(|(_, at)| *at)
+    }
+
+    pub fn get(&self, phase: &str) -> Option<Duration> {
+        self.phases.lock().unwrap().iter().find(|(name, _)| name == phase).map(|(_, d)| *d)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phases.lock().unwrap().is_empty()
+    }
+
+    pub fn breakdown(&self) -> Vec<PhaseTime> {
+        let phases = self.phases.lock().unwrap();
+        let total: f64 = phases.iter().map(|(_, d)| d.as_secs_f64()).sum();
+        phases
+            .iter()
+            .map(|(phase, d)| PhaseTime {
+                phase: phase.clone(),
+                seconds: d.as_secs_f64(),
+                share: if total > 0.0 { d.as_secs_f64() / total } else { 0.0 },
+            })
+            .collect()
+    }
+
+    pub fn print_summary(&self) {
+        let breakdown = self.breakdown();
+        let line: Vec<String> = breakdown
+            .iter()
+            .map(|p| format!("{} {:.3}s ({:.0}%)", p.phase, p.seconds, p.share * 100.0))
+            .collect();
+        info!("⏱️  Phases: {}", line.join(" | "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_phase_times_accumulate_in_order() {
+        let phases = PhaseTimes::new();
+        phases.record("generate", Duration::from_millis(300));
+        let value = phases.time("list", async { 7 }).await;
+        assert_eq!(value, 7);
+        phases.record("train", Duration::from_millis(600));
+        phases.record("generate", Duration::from_millis(100));
+
+        let breakdown = phases.breakdown();
+        assert_eq!(breakdown.iter().map(|p| p.phase.as_str()).collect::<Vec<_>>(), ["generate", "list", "train"]);
+        assert_eq!(phases.get("generate"), Some(Duration::from_millis(400)));
+        let shares: f64 = breakdown.iter().map(|p| p.share).sum();
+        assert!((shares - 1.0).abs() < 1e-9);
+        assert!(breakdown[0].share > 0.39 && breakdown[0].share <= 0.4);
+        assert!(phases.get("teardown").is_none());
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/core/src/plugins/decode_validation.rs
+//
+// Sampled full-decode validation of training batches
+//
+// Decoding every sample would distort bandwidth tests, so only an evenly spread
+// fraction of batches is decoded. Each decoded sample feeds the decode latency
+// metrics; failures are counted and a few kept as examples.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{info, warn};
+
+use super::Plugin;
+use crate::dlio_compat::DlioConfig;
+use crate::metrics::Metrics;
+use real_dlio_formats::decode_object;
+
+const DEFAULT_MAX_FAILURE_EXAMPLES: usize = 5;
+
+/// Decode validation results for the run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DecodeValidationReport {
+    pub sample_rate: f64,
+    pub batches_seen: u64,
+    pub batches_validated: u64,
+    pub samples_validated: u64,
+    pub samples_failed: u64,
+    /// Fraction of validated samples that decoded cleanly (1.0 when none were validated)
+    pub success_rate: f64,
+    pub mean_decode_us: f64,
+    /// "epoch E batch B item I: error" for the first few failures
+    pub failure_examples: Vec<String>,
+}
+
+/// Plugin that fully decodes a configurable fraction of batches
+pub struct DecodeValidationPlugin {
+    format: String,
+    sample_rate: f64,
+    max_examples: usize,
+    metrics: Arc<Metrics>,
+    epoch: u32,
+    batch_in_epoch: u64,
+    decode_us_total: f64,
+    report: Arc<Mutex<DecodeValidationReport>>,
+}
+
+impl DecodeValidationPlugin {
+    /// Build from `validation.decode_sample_rate`; None when validation is off
+    pub fn from_config(config: &DlioConfig, metrics: Arc<Metrics>) -> Option<Self> {
+        let validation = config.validation.as_ref()?;
+        let rate = validation.decode_sample_rate.filter(|&r| r > 0.0)?.min(1.0);
+        info!("🔬 Decode validation enabled: {:.2}% of batches", rate * 100.0);
+        Some(Self {
+            format: config.dataset.format.clone().unwrap_or_default(),
+            sample_rate: rate,
+            max_examples: validation.max_failure_examples.unwrap_or(DEFAULT_MAX_FAILURE_EXAMPLES),
+            metrics,
+            epoch: 0,
+            batch_in_epoch: 0,
+            decode_us_total: 0.0,
+            report: Arc::new(Mutex::new(DecodeValidationReport {
+                sample_rate: rate,
+                success_rate: 1.0,
+                ..Default::default()
+            })),
+        })
+    }
+
+    /// Shared handle to the report, readable after the plugin is boxed
+    pub fn report(&self) -> Arc<Mutex<DecodeValidationReport>> {
+        Arc::clone(&self.report)
+    }
+
+    /// Deterministic, evenly spread selection: batch n is validated when
+    /// floor((n + 1) × rate) steps past floor(n × rate)
+    fn selected(&self, batch: u64) -> bool {
+        let rate = self.sample_rate;
+        ((batch + 1) as f64 * rate).floor() > (batch as f64 * rate).floor()
+    }
+}
+
+#[async_trait]
+impl Plugin for DecodeValidationPlugin {
+    async fn after_batch(&mut self, batch: &[Vec<u8>]) -> Result<()> {
+        let index = self.batch_in_epoch;
+        self.batch_in_epoch += 1;
+        let selected = self.selected(index);
+
+        let mut report = self.report.lock().unwrap();
+        report.batches_seen += 1;
+        if !selected {
+            return Ok(());
+        }
+        report.batches_validated += 1;
+
+        for (item, data) in batch.iter().enumerate() {
+            let start = Instant::now();
+            let result = decode_object(&self.format, data);
+            let elapsed = start.elapsed();
+            self.metrics.record_decode_time(elapsed);
+            self.decode_us_total += elapsed.as_secs_f64() * 1e6;
+            report.samples_validated += 1;
+
+            if let Err(e) = result {
+                report.samples_failed += 1;
+                if report.failure_examples.len() < self.max_examples {
+                    let example = format!("epoch {} batch {} item {}: {:#}", self.epoch + 1, index, item, e);
+                    warn!("❌ Decode validation failure: {}", example);
+                    report.failure_examples.push(example);
+                }
+            }
+        }
+        report.success_rate = 1.0 - report.samples_failed as f64 / report.samples_validated as f64;
+        report.mean_decode_us = self.decode_us_total / report.samples_validated as f64;
+        Ok(())
+    }
+
+    async fn after_epoch(&mut self, epoch: u32) -> Result<()> {
+        self.epoch = epoch + 1;
+        self.batch_in_epoch = 0;
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        let report = self.report.lock().unwrap();
+        info!(
+            "🔬 Decode validation: {}/{} batches, {} samples, {:.2}% decoded OK, mean {:.1}µs",
+            report.batches_validated, report.batches_seen, report.samples_validated,
+            report.success_rate * 100.0, report.mean_decode_us
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use real_dlio_formats::{NpzStreamingFormat, StreamingFormat};
+
+    fn plugin(rate: &str) -> DecodeValidationPlugin {
+        let yaml = format!(
+            "dataset:\n  data_folder: file:///tmp/d\n  format: npz\nreader: {{}}\nvalidation:\n  decode_sample_rate: {}\n",
+            rate
+        );
+        let config = DlioConfig::from_yaml(&yaml).unwrap();
+        DecodeValidationPlugin::from_config(&config, Arc::new(Metrics::new())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sampled_decode_reports_failures() {
+        let mut p = plugin("25");
+        let good = NpzStreamingFormat::new(vec![2, 4], 2).generate_bytes("a.npz").unwrap();
+        let batch = vec![good, b"corrupt".to_vec()];
+        for _ in 0..8 {
+            p.after_batch(&batch).await.unwrap();
+        }
+
+        let report = p.report().lock().unwrap().clone();
+        assert_eq!(report.batches_seen, 8);
+        assert_eq!(report.batches_validated, 2);
+        assert_eq!(report.samples_validated, 4);
+        assert_eq!(report.samples_failed, 2);
+        assert_eq!(report.success_rate, 0.5);
+        assert_eq!(report.failure_examples.len(), 2);
+    }
+}
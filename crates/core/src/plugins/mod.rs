@@ -10,6 +10,7 @@ use crate::config::DlioConfig;
 pub trait Plugin: Send + Sync {
     async fn initialize(&mut self, _cfg: &DlioConfig) -> Result<()> { Ok(()) }
     async fn after_step(&mut self, _step: u32) -> Result<()> { Ok(()) }
+    async fn after_batch(&mut self, _batch: &[Vec<u8>]) -> Result<()> { Ok(()) }
     async fn after_epoch(&mut self, _epoch: u32) -> Result<()> { Ok(()) }
     async fn finalize(&mut self) -> Result<()> { Ok(()) }
 }
@@ -55,6 +56,13 @@ impl PluginManager {
         Ok(())
     }
     
+    pub async fn after_batch(&mut self, batch: &[Vec<u8>]) -> Result<()> {
+        for p in self.plugins.iter_mut() { 
+            p.after_batch(batch).await?; 
+        }
+        Ok(())
+    }
+    
     pub async fn after_epoch(&mut self, epoch: u32) -> Result<()> {
         for p in self.plugins.iter_mut() { 
             p.after_epoch(epoch).await?; 
@@ -70,6 +78,10 @@ impl PluginManager {
     }
 }
 
-// CheckpointPlugin implementation for M5
-pub mod checkpoint;
-pub use checkpoint::CheckpointPlugin;
\ No newline at end of file
+// Sampled full-decode validation of training batches
+pub mod decode_validation;
+pub use decode_validation::{DecodeValidationPlugin, DecodeValidationReport};
+
+// Seeded end-to-end content verification
+pub mod content_verify;
+pub use content_verify::{ContentVerificationPlugin, ContentVerificationReport};
\ No newline at end of file
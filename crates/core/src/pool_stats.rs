@@ -0,0 +1,335 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/pool_stats.rs
+//
+// Loader pool telemetry over time (`storage.pool_stats_interval_ms`)
+//
+// Batch and step times show that training waited, not why. A background
+// sampler polls the loader pool every interval during training and records:
+//
+//   active_requests  reads in flight (native loaders: worker slots in use)
+//   queue_depth      batches loaded and waiting for the training loop
+//   completed/errors reads finished so far (native loaders)
+//   retries          reads retried so far (http backend, failover retries)
+//
+// A full queue with idle slots means training is the bottleneck; an empty
+// queue with every slot busy means storage is; an empty queue with idle
+// slots points at the loader itself. The s3dlio loader's pool is internal to
+// s3dlio and exposes no telemetry, so its samples carry dl-driver's queue
+// depth only and the report says so in `note`. Retries are counted per run:
+// reads go through the sampler's backend wrapper, which opens a task-local
+// counter that `request_timing::note_retry` adds to. Samples are
+// capped at MAX_POOL_SAMPLES: when the cap is hit every other sample is
+// dropped and the interval doubles, as for `--timeline` step rows. Peaks and
+// means cover every sample taken.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::backends::DataBackend;
+use crate::batch_channel::QueueProbe;
+use crate::worker_balance::WorkerBalance;
+
+/// Most samples kept per rank
+pub const MAX_POOL_SAMPLES: usize = 10_000;
+
+/// Why an s3dlio-loader report has no pool figures
+const S3DLIO_NOTE: &str = "the s3dlio loader's pool is internal to s3dlio and exposes no telemetry: \
+                           in-flight reads, completions, errors and retries are not sampled";
+
+tokio::task_local! {
+    /// Retries of the sampled run, counted by `request_timing::note_retry`
+    static RETRIES: Arc<AtomicU64>;
+}
+
+/// Count one retried read against the run it belongs to (a no-op outside one)
+pub fn count_retry() {
+    let _ = RETRIES.try_with(|retries| retries.fetch_add(1, Ordering::Relaxed));
+}
+
+/// The pool being sampled
+#[derive(Debug, Clone)]
+pub struct PoolInfo {
+    /// "native" or "s3dlio"
+    pub loader: String,
+    pub pool_size: usize,
+    pub max_inflight: usize,
+    pub queue_capacity: usize,
+}
+
+/// The pool at one instant
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolSample {
+    /// Seconds from training start
+    pub t_s: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_requests: Option<u64>,
+    pub queue_depth: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u64>,
+    #[serde(skip)]
+    tick: u64,
+}
+
+/// `pool_stats` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStatsReport {
+    pub loader: String,
+    pub pool_size: usize,
+    pub max_inflight: usize,
+    pub queue_capacity: usize,
+    /// Time between kept samples at the end of the run
+    pub interval_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_active_requests: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_active_requests: Option<f64>,
+    pub peak_queue_depth: u64,
+    pub mean_queue_depth: f64,
+    /// Share of samples with no batch waiting: training was waiting on the loader
+    pub empty_queue_fraction: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u64>,
+    /// What this loader's samples leave out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub samples: Vec<PoolSample>,
+}
+
+#[derive(Debug, Default)]
+struct Totals {
+    ticks: u64,
+    stride: u64,
+    peak_active: u64,
+    active_sum: u64,
+    peak_queue: u64,
+    queue_sum: u64,
+    empty: u64,
+    samples: Vec<PoolSample>,
+}
+
+struct Shared {
+    info: PoolInfo,
+    start: Instant,
+    interval: Duration,
+    max_samples: usize,
+    retries: Arc<AtomicU64>,
+    reads: Option<Arc<WorkerBalance>>,
+    queue: Mutex<Option<QueueProbe>>,
+    totals: Mutex<Totals>,
+}
+
+impl Shared {
+    /// Take a sample; `last` keeps it whatever the current stride
+    fn sample(&self, last: bool) {
+        let queue_depth = self.queue.lock().unwrap().as_ref().map_or(0, |q| q.queued()) as u64;
+        let active = self.reads.as_ref().map(|r| r.in_flight() as u64);
+        let done = self.reads.as_ref().map(|r| r.totals());
+        let mut totals = self.totals.lock().unwrap();
+        let tick = totals.ticks;
+        totals.ticks += 1;
+        totals.peak_active = totals.peak_active.max(active.unwrap_or(0));
+        totals.active_sum += active.unwrap_or(0);
+        totals.peak_queue = totals.peak_queue.max(queue_depth);
+        totals.queue_sum += queue_depth;
+        totals.empty += (queue_depth == 0) as u64;
+        if !last && !tick.is_multiple_of(totals.stride) {
+            return;
+        }
+        totals.samples.push(PoolSample {
+            t_s: self.start.elapsed().as_secs_f64(),
+            active_requests: active,
+            queue_depth,
+            completed: done.map(|(completed, _)| completed),
+            errors: done.map(|(_, errors)| errors),
+            retries: self.reads.is_some().then(|| self.retries.load(Ordering::Relaxed)),
+            tick,
+        });
+        if !last && totals.samples.len() >= self.max_samples {
+            totals.stride *= 2;
+            let stride = totals.stride;
+            totals.samples.retain(|s| s.tick.is_multiple_of(stride));
+        }
+    }
+}
+
+/// Background task sampling one training run's loader pool
+pub struct PoolSampler {
+    shared: Arc<Shared>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl PoolSampler {
+    /// Start sampling every `interval`; `reads` is the native loader's worker pool, if any
+    pub fn spawn(info: PoolInfo, interval: Duration, reads: Option<Arc<WorkerBalance>>) -> Self {
+        Self::with_max_samples(info, interval, reads, MAX_POOL_SAMPLES)
+    }
+
+    /// As `spawn`, keeping at most `max` (at least 2) samples
+    pub fn with_max_samples(info: PoolInfo, interval: Duration, reads: Option<Arc<WorkerBalance>>, max: usize) -> Self {
+        let interval = interval.max(Duration::from_millis(1));
+        let shared = Arc::new(Shared {
+            info,
+            start: Instant::now(),
+            interval,
+            max_samples: max.max(2),
+            retries: Arc::new(AtomicU64::new(0)),
+            reads,
+            queue: Mutex::new(None),
+            totals: Mutex::new(Totals { stride: 1, ..Totals::default() }),
+        });
+        let sampler = Arc::clone(&shared);
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                sampler.sample(false);
+            }
+        });
+        Self { shared, task: Some(task) }
+    }
+
+    /// Run `operation` with its retries counted for this run
+    pub async fn count_retries<F: Future>(&self, operation: F) -> F::Output {
+        RETRIES.scope(Arc::clone(&self.shared.retries), operation).await
+    }
+
+    /// Wrap the run's backend so retries of its reads are counted for this run
+    pub fn backend(&self, inner: Arc<dyn DataBackend>) -> RetryCountingBackend {
+        RetryCountingBackend { inner, retries: Arc::clone(&self.shared.retries) }
+    }
+
+    /// Sample this loader's queue from now on (each epoch has its own loader)
+    pub fn watch_queue(&self, probe: QueueProbe) {
+        *self.shared.queue.lock().unwrap() = Some(probe);
+    }
+
+    /// Stop sampling and summarize, with one last sample at the end of training
+    pub fn finish(mut self) -> PoolStatsReport {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.shared.sample(true);
+        let shared = &self.shared;
+        let mut totals = shared.totals.lock().unwrap();
+        let ticks = totals.ticks.max(1) as f64;
+        let native = shared.reads.is_some();
+        PoolStatsReport {
+            loader: shared.info.loader.clone(),
+            pool_size: shared.info.pool_size,
+            max_inflight: shared.info.max_inflight,
+            queue_capacity: shared.info.queue_capacity,
+            interval_ms: (shared.interval * totals.stride as u32).as_millis() as u64,
+            peak_active_requests: native.then_some(totals.peak_active),
+            mean_active_requests: native.then(|| totals.active_sum as f64 / ticks),
+            peak_queue_depth: totals.peak_queue,
+            mean_queue_depth: totals.queue_sum as f64 / ticks,
+            empty_queue_fraction: totals.empty as f64 / ticks,
+            retries: native.then(|| shared.retries.load(Ordering::Relaxed)),
+            note: (!native).then(|| S3DLIO_NOTE.to_string()),
+            samples: std::mem::take(&mut totals.samples),
+        }
+    }
+}
+
+/// DataBackend whose operations count their retries for one sampled run
+pub struct RetryCountingBackend {
+    inner: Arc<dyn DataBackend>,
+    retries: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl DataBackend for RetryCountingBackend {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        RETRIES.scope(Arc::clone(&self.retries), self.inner.list()).await
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        RETRIES.scope(Arc::clone(&self.retries), self.inner.get(uri)).await
+    }
+
+    async fn head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        RETRIES.scope(Arc::clone(&self.retries), self.inner.head(uri, len)).await
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        RETRIES.scope(Arc::clone(&self.retries), self.inner.put(uri, data)).await
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.inner.report()
+    }
+}
+
+impl Drop for PoolSampler {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch_channel::LoaderHandle;
+
+    #[tokio::test]
+    async fn test_pool_sampler_tracks_slots_queue_and_retries() {
+        let info = PoolInfo { loader: "native".to_string(), pool_size: 4, max_inflight: 4, queue_capacity: 8 };
+        let reads = Arc::new(WorkerBalance::new(4));
+        let sampler = PoolSampler::with_max_samples(info, Duration::from_millis(1), Some(Arc::clone(&reads)), 8);
+
+        let mut loader = LoaderHandle::spawn(8, |tx| async move {
+            for _ in 0..3 {
+                tx.send(Ok((Instant::now(), vec![vec![0u8; 4]]))).await;
+            }
+        });
+        sampler.watch_queue(loader.queue_probe());
+        while loader.queue_probe().queued() < 3 {
+            tokio::task::yield_now().await;
+        }
+        let busy: Vec<_> = (0..2).map(|_| reads.acquire()).collect();
+        sampler.count_retries(async { count_retry() }).await;
+        // Outside the run's scope: not this run's retry
+        count_retry();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        busy[0].record(Instant::now(), 100);
+        drop(busy);
+        while loader.recv().await.is_some() {}
+
+        let report = sampler.finish();
+        assert_eq!((report.peak_active_requests, report.peak_queue_depth), (Some(2), 3));
+        assert_eq!(report.retries, Some(1));
+        assert!(report.note.is_none());
+        // Capped: thinned to evenly spaced samples with a longer interval
+        assert!(report.samples.len() < 8 && report.interval_ms > 1, "{:?}", report.interval_ms);
+        let last = report.samples.last().unwrap();
+        assert_eq!((last.active_requests, last.queue_depth, last.completed), (Some(0), 0, Some(1)));
+        assert!(report.empty_queue_fraction < 1.0 && report.mean_queue_depth > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_s3dlio_loader_report_says_what_is_missing() {
+        let info = PoolInfo { loader: "s3dlio".to_string(), pool_size: 4, max_inflight: 4, queue_capacity: 8 };
+        let report = PoolSampler::spawn(info, Duration::from_millis(1), None).finish();
+        assert!(report.retries.is_none() && report.peak_active_requests.is_none());
+        assert!(report.note.unwrap().contains("no telemetry"));
+    }
+}
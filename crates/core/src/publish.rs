@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/publish.rs
+//
+// Run summaries for code review (`report publish`)
+//
+// CI runs dl-driver on a PR's build and on its base branch; `report publish`
+// turns the PR run's rank results (and the base run's, when given) into three
+// small files:
+//
+//   summary.json  throughput, AU, versions and the deltas against the base
+//   summary.md    a markdown table ready to post as a PR comment
+//   badge.json    a shields.io endpoint badge ("1.23 GiB/s (+2.1%)")
+//
+// and uploads them under an object-store prefix (`--to`, else
+// DL_DRIVER_PUBLISH_URI). Posting is left to the CI's own automation; a
+// throughput drop beyond results_db::REGRESSION_PCT is marked as a regression,
+// the same threshold `history` uses.
+//
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::results_db::{Change, RunRecord, REGRESSION_PCT};
+
+/// Environment variable naming the default publish prefix
+pub const PUBLISH_URI_ENV: &str = "DL_DRIVER_PUBLISH_URI";
+
+/// The base run and the change from it
+#[derive(Debug, Clone, Serialize)]
+pub struct BaseComparison {
+    pub source: String,
+    pub throughput_gib_s: f64,
+    pub au_percent: Option<f64>,
+    pub throughput_change_pct: Option<f64>,
+    /// AU difference in percentage points
+    pub au_change_points: Option<f64>,
+    /// Versions or config that differ from the base run
+    pub changes: Vec<Change>,
+}
+
+/// `summary.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishSummary {
+    pub label: String,
+    pub config: String,
+    pub source: String,
+    pub dl_driver_version: Option<String>,
+    pub s3dlio_version: Option<String>,
+    pub ranks: usize,
+    pub throughput_gib_s: f64,
+    pub au_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<BaseComparison>,
+    pub regression: bool,
+}
+
+impl PublishSummary {
+    pub fn new(label: &str, mut run: RunRecord, base: Option<RunRecord>) -> Self {
+        let base = base.map(|base| {
+            run.annotate(&base);
+            BaseComparison {
+                au_change_points: run.au_percent.zip(base.au_percent).map(|(au, base)| au - base),
+                throughput_change_pct: run.throughput_change_pct,
+                changes: run.changes.clone(),
+                source: base.source,
+                throughput_gib_s: base.throughput_gib_s,
+                au_percent: base.au_percent,
+            }
+        });
+        Self {
+            label: label.to_string(),
+            config: run.config,
+            source: run.source,
+            dl_driver_version: run.dl_driver_version,
+            s3dlio_version: run.s3dlio_version,
+            ranks: run.ranks,
+            throughput_gib_s: run.throughput_gib_s,
+            au_percent: run.au_percent,
+            base,
+            regression: run.regression,
+        }
+    }
+
+    fn change_pct(&self) -> Option<f64> {
+        self.base.as_ref().and_then(|b| b.throughput_change_pct)
+    }
+
+    /// shields.io endpoint badge: blue without a base, red on a regression, yellow on a smaller drop
+    pub fn badge(&self) -> Value {
+        let mut message = format!("{:.2} GiB/s", self.throughput_gib_s);
+        if let Some(pct) = self.change_pct() {
+            message.push_str(&format!(" ({:+.1}%)", pct));
+        }
+        let color = match self.change_pct() {
+            None => "blue",
+            Some(_) if self.regression => "red",
+            Some(pct) if pct < 0.0 => "yellow",
+            Some(_) => "brightgreen",
+        };
+        json!({ "schemaVersion": 1, "label": self.label, "message": message, "color": color })
+    }
+
+    /// Markdown table for a PR comment
+    pub fn markdown(&self) -> String {
+        let au = |au: Option<f64>| au.map_or_else(|| "-".to_string(), |au| format!("{:.1}%", au));
+        let mut md = format!("### dl-driver: {} ({})\n\n", self.label, self.config);
+        match &self.base {
+            Some(base) => {
+                md.push_str("| Metric | This run | Base | Change |\n|---|---|---|---|\n");
+                md.push_str(&format!(
+                    "| Throughput | {:.3} GiB/s | {:.3} GiB/s | {} |\n",
+                    self.throughput_gib_s,
+                    base.throughput_gib_s,
+                    base.throughput_change_pct.map_or_else(|| "-".to_string(), |pct| format!("{:+.1}%", pct))
+                ));
+                md.push_str(&format!(
+                    "| AU | {} | {} | {} |\n",
+                    au(self.au_percent),
+                    au(base.au_percent),
+                    base.au_change_points.map_or_else(|| "-".to_string(), |pts| format!("{:+.1} pts", pts))
+                ));
+            }
+            None => {
+                md.push_str("| Metric | This run |\n|---|---|\n");
+                md.push_str(&format!("| Throughput | {:.3} GiB/s |\n| AU | {} |\n", self.throughput_gib_s, au(self.au_percent)));
+            }
+        }
+        md.push_str(&format!(
+            "\n{} rank(s), dl-driver {}, s3dlio {}\n",
+            self.ranks,
+            self.dl_driver_version.as_deref().unwrap_or("?"),
+            self.s3dlio_version.as_deref().unwrap_or("?")
+        ));
+        if let Some(base) = self.base.as_ref().filter(|b| !b.changes.is_empty()) {
+            let fields: Vec<&str> = base.changes.iter().map(|c| c.field.as_str()).collect();
+            md.push_str(&format!("Changed since base: {}\n", fields.join(", ")));
+        }
+        if self.regression {
+            md.push_str(&format!("\n**📉 Throughput regression** (more than {:.0}% below base)\n", REGRESSION_PCT));
+        }
+        md
+    }
+
+    /// (name, contents) of summary.json, summary.md and badge.json
+    pub fn files(&self) -> Result<Vec<(String, Vec<u8>)>> {
+        Ok(vec![
+            ("summary.json".to_string(), serde_json::to_vec_pretty(self)?),
+            ("summary.md".to_string(), self.markdown().into_bytes()),
+            ("badge.json".to_string(), serde_json::to_vec_pretty(&self.badge())?),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_summary_deltas_badge_and_markdown() {
+        let ranks = |gib_s: f64, au: f64, version: &str| {
+            (0..2)
+                .map(|rank| {
+                    json!({"rank": rank, "config_file": "unet3d.yaml", "dl_driver_version": version, "s3dlio_version": "0.8.4",
+                           "metrics": {"storage_throughput_gib_s": gib_s, "au_percent": au}})
+                })
+                .collect::<Vec<_>>()
+        };
+        let record = |gib_s, au, version| RunRecord::from_rank_results(&ranks(gib_s, au, version), "rank_*.json").unwrap();
+
+        let summary = PublishSummary::new("PR #42", record(0.9, 85.0, "0.9.1"), Some(record(1.0, 90.0, "0.9.0")));
+        let base = summary.base.as_ref().unwrap();
+        assert!((base.throughput_change_pct.unwrap() + 10.0).abs() < 1e-9);
+        assert!((base.au_change_points.unwrap() + 5.0).abs() < 1e-9);
+        assert_eq!(base.changes.len(), 1);
+        assert!(summary.regression);
+        let badge = summary.badge();
+        assert_eq!((badge["message"].as_str(), badge["color"].as_str()), (Some("1.80 GiB/s (-10.0%)"), Some("red")));
+        let md = summary.markdown();
+        assert!(md.contains("| Throughput | 1.800 GiB/s | 2.000 GiB/s | -10.0% |"), "{}", md);
+        assert!(md.contains("-5.0 pts") && md.contains("regression") && md.contains("dl_driver_version"));
+
+        let small_drop = PublishSummary::new("PR", record(0.99, 90.0, "0.9.0"), Some(record(1.0, 90.0, "0.9.0")));
+        assert_eq!(small_drop.badge()["color"], "yellow");
+        assert!(!small_drop.regression && small_drop.base.as_ref().unwrap().changes.is_empty());
+
+        let alone = PublishSummary::new("main", record(1.0, 90.0, "0.9.0"), None);
+        assert_eq!(alone.badge()["color"], "blue");
+        assert!(!alone.markdown().contains("Base"));
+        let names: Vec<String> = alone.files().unwrap().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["summary.json", "summary.md", "badge.json"]);
+    }
+}
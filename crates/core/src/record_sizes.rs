@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/record_sizes.rs
+//
+// Record-size discovery for pre-existing datasets
+//
+// When `dataset.record_length_bytes` or `dataset.num_samples_per_file` is
+// missing and the run reads existing data, a few objects spread across the
+// listing are read whole. Their sizes and per-sample sizes (from format
+// metadata: TFRecord records, NPZ array rows; other objects are one sample)
+// fill in the missing values, so the RunPlan and AU/throughput normalization
+// use the data's real record size. A wide spread of sample sizes is flagged:
+// one record size then misrepresents the dataset.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::components;
+use crate::dlio_compat::DlioConfig;
+use crate::format_probe::{self, SAMPLE_OBJECTS};
+use real_dlio_formats::sample_sizes;
+
+/// Coefficient of variation of sample sizes above which one record size is misleading
+pub const HIGH_VARIANCE_CV: f64 = 0.25;
+
+/// Measured object and sample sizes of an existing dataset
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecordSizes {
+    pub sampled_objects: usize,
+    pub object_bytes_mean: f64,
+    pub samples_per_object_mean: f64,
+    pub sample_bytes_mean: f64,
+    pub sample_bytes_min: usize,
+    pub sample_bytes_max: usize,
+    /// Coefficient of variation of sample sizes
+    pub sample_bytes_cv: f64,
+    pub high_variance: bool,
+    /// Config fields filled in from the measurement
+    pub filled: Vec<String>,
+}
+
+fn mean_cv(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, if mean > 0.0 { variance.sqrt() / mean } else { 0.0 })
+}
+
+/// Sizes of sampled objects in `format`
+pub fn measure(format: &str, objects: &[(String, Vec<u8>)]) -> Result<RecordSizes> {
+    let mut samples_per_object = Vec::new();
+    let mut sample_bytes = Vec::new();
+    for (uri, data) in objects {
+        let sizes = sample_sizes(format, data).with_context(|| format!("Failed to read records of {}", uri))?;
+        samples_per_object.push(sizes.len() as f64);
+        sample_bytes.extend(sizes);
+    }
+    let object_bytes: Vec<f64> = objects.iter().map(|(_, data)| data.len() as f64).collect();
+    let (sample_bytes_mean, sample_bytes_cv) = mean_cv(&sample_bytes.iter().map(|&b| b as f64).collect::<Vec<_>>());
+    Ok(RecordSizes {
+        sampled_objects: objects.len(),
+        object_bytes_mean: mean_cv(&object_bytes).0,
+        samples_per_object_mean: mean_cv(&samples_per_object).0,
+        sample_bytes_mean,
+        sample_bytes_min: sample_bytes.iter().copied().min().unwrap_or(0),
+        sample_bytes_max: sample_bytes.iter().copied().max().unwrap_or(0),
+        sample_bytes_cv,
+        high_variance: sample_bytes_cv > HIGH_VARIANCE_CV,
+        filled: Vec::new(),
+    })
+}
+
+/// Fill in an omitted record length and samples per file from the measurement
+pub fn resolve(config: &mut DlioConfig, sizes: &mut RecordSizes) {
+    if sizes.sampled_objects == 0 {
+        return;
+    }
+    let dataset = &mut config.dataset;
+    if dataset.record_length_bytes.is_none() {
+        dataset.record_length_bytes = Some((sizes.sample_bytes_mean.round() as usize).max(1));
+        sizes.filled.push("record_length_bytes".to_string());
+    }
+    if dataset.num_samples_per_file.is_none() {
+        dataset.num_samples_per_file = Some((sizes.samples_per_object_mean.round() as usize).max(1));
+        sizes.filled.push("num_samples_per_file".to_string());
+    }
+    if !sizes.filled.is_empty() {
+        info!("📐 Discovered from {} objects: record_length_bytes={:?}, num_samples_per_file={:?} (filled: {})",
+              sizes.sampled_objects, dataset.record_length_bytes, dataset.num_samples_per_file, sizes.filled.join(", "));
+    }
+    if sizes.high_variance {
+        warn!("⚠️  Sample sizes vary widely ({}..{} bytes, cv {:.2}); record_length_bytes is only an average",
+              sizes.sample_bytes_min, sizes.sample_bytes_max, sizes.sample_bytes_cv);
+    }
+}
+
+/// Discover record sizes of an existing dataset when the config leaves them out
+///
+/// Skipped for generated or synthesized data and multi-component datasets.
+pub async fn check(config: &mut DlioConfig, file_list: Option<&[String]>) -> Result<Option<RecordSizes>> {
+    let dataset = &config.dataset;
+    if (dataset.record_length_bytes.is_some() && dataset.num_samples_per_file.is_some())
+        || components::components(config).is_some()
+    {
+        return Ok(None);
+    }
+    let Some(backend) = format_probe::existing_dataset(config, file_list)? else {
+        return Ok(None);
+    };
+
+    let uris = backend.list().await?;
+    let step = (uris.len() / SAMPLE_OBJECTS).max(1);
+    let mut objects = Vec::new();
+    for uri in uris.iter().step_by(step).take(SAMPLE_OBJECTS) {
+        objects.push((uri.clone(), backend.get(uri).await?));
+    }
+    let mut sizes = measure(config.dataset.format.as_deref().unwrap_or(""), &objects)?;
+    resolve(config, &mut sizes);
+    Ok(Some(sizes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use real_dlio_formats::{StreamingFormat, TfRecordFormat};
+
+    #[test]
+    fn test_measure_fills_config_and_flags_variance() {
+        let object = |records, size| TfRecordFormat::new(records, size).generate_bytes("a.tfrecord").unwrap();
+        let uniform = [("a".to_string(), object(4, 1024)), ("b".to_string(), object(4, 1024))];
+        let mut sizes = measure("tfrecord", &uniform).unwrap();
+        assert_eq!((sizes.sampled_objects, sizes.samples_per_object_mean), (2, 4.0));
+        assert!(!sizes.high_variance);
+
+        let mut config = DlioConfig::from_yaml("dataset:\n  data_folder: s3://bucket/data\n  num_samples_per_file: 8\nreader: {}\n").unwrap();
+        resolve(&mut config, &mut sizes);
+        assert_eq!(sizes.filled, ["record_length_bytes"]);
+        assert_eq!(config.dataset.record_length_bytes, Some(sizes.sample_bytes_mean.round() as usize));
+        // Configured values are kept
+        assert_eq!(config.dataset.num_samples_per_file, Some(8));
+
+        // Whole objects of very different sizes (one sample each) are flagged
+        let mixed = [("a".to_string(), vec![0u8; 1000]), ("b".to_string(), vec![0u8; 9000])];
+        let sizes = measure("jpeg", &mixed).unwrap();
+        assert_eq!((sizes.sample_bytes_min, sizes.sample_bytes_max, sizes.sample_bytes_mean), (1000, 9000, 5000.0));
+        assert!(sizes.high_variance && sizes.sample_bytes_cv > HIGH_VARIANCE_CV);
+    }
+}
@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/redact.rs
+//
+// Results anonymization for sharing (`report export --redact`)
+//
+// Identifying strings are replaced by stable pseudonyms, consistent across
+// every file of one export so multi-rank results still group by host:
+//
+//   hostnames           host-1, host-2, ...     ("hostname" etc. fields, and
+//                                                wherever those names recur in text)
+//   URI authorities     s3://bucket-1/path-3    (buckets, endpoints, servers)
+//   absolute paths      /path-2                 (data folders, mount points)
+//   IPv4 addresses      ip-1                    (e.g. NFS `addr=` mount options)
+//
+// Credentials-adjacent fields (secret, token, password, ...) are replaced by
+// "[REDACTED]". Numbers, booleans and all other strings are left untouched,
+// so metrics stay intact.
+//
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Replacement for credentials-adjacent values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Fields holding a host name
+const HOST_KEYS: &[&str] = &["hostname", "host", "hosts", "coord_host", "node", "server"];
+
+/// Key fragments marking credentials-adjacent fields
+const SECRET_KEY_PARTS: &[&str] = &["secret", "password", "passwd", "token", "credential", "access_key", "api_key", "authorization"];
+
+/// Characters that separate tokens inside free text
+const DELIMITERS: &[char] = &[' ', '\t', '\n', ',', ';', '=', '(', ')', '[', ']', '{', '}', '"', '\''];
+
+/// Pseudonymizes identifying strings in results JSON
+#[derive(Debug, Default)]
+pub struct Redactor {
+    /// (kind, original) -> pseudonym
+    names: HashMap<(&'static str, String), String>,
+    counters: HashMap<&'static str, usize>,
+    /// Host names seen in host fields, replaced wherever they recur
+    hosts: HashMap<String, String>,
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redact one results document in place
+    pub fn redact(&mut self, value: &mut Value) {
+        // Learn host names first so free text mentioning them is caught too
+        self.collect_hosts(value);
+        self.redact_value(None, value);
+    }
+
+    /// Original -> pseudonym table, for the sharer to map vendor feedback back
+    pub fn mapping(&self) -> BTreeMap<String, String> {
+        self.names.iter().map(|((_, original), alias)| (alias.clone(), original.clone())).collect()
+    }
+
+    fn pseudonym(&mut self, kind: &'static str, original: &str) -> String {
+        if let Some(alias) = self.names.get(&(kind, original.to_string())) {
+            return alias.clone();
+        }
+        let counter = self.counters.entry(kind).or_insert(0);
+        *counter += 1;
+        let alias = format!("{}-{}", kind, counter);
+        self.names.insert((kind, original.to_string()), alias.clone());
+        alias
+    }
+
+    fn collect_hosts(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, v) in map {
+                    if is_host_key(key) {
+                        for host in strings(v) {
+                            let alias = self.pseudonym("host", host);
+                            self.hosts.insert(host.to_string(), alias);
+                        }
+                    } else {
+                        self.collect_hosts(v);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| self.collect_hosts(v)),
+            _ => {}
+        }
+    }
+
+    fn redact_value(&mut self, key: Option<&str>, value: &mut Value) {
+        if let Some(key) = key {
+            let lower = key.to_ascii_lowercase();
+            if SECRET_KEY_PARTS.iter().any(|part| lower.contains(part)) && !value.is_null() {
+                *value = Value::String(REDACTED.to_string());
+                return;
+            }
+        }
+        match value {
+            Value::Object(map) => {
+                for (k, v) in map.iter_mut() {
+                    self.redact_value(Some(k), v);
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_value(key, v)),
+            Value::String(s) => {
+                *s = match key.filter(|k| is_host_key(k)) {
+                    Some(_) => self.pseudonym("host", s),
+                    None => self.redact_text(s),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Redact every identifying token in free text, keeping the delimiters
+    pub fn redact_text(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut token = String::new();
+        for c in text.chars() {
+            if DELIMITERS.contains(&c) {
+                out.push_str(&self.redact_token(&token));
+                token.clear();
+                out.push(c);
+            } else {
+                token.push(c);
+            }
+        }
+        out.push_str(&self.redact_token(&token));
+        out
+    }
+
+    fn redact_token(&mut self, token: &str) -> String {
+        if token.is_empty() {
+            return String::new();
+        }
+        if let Some(alias) = self.hosts.get(token) {
+            return alias.clone();
+        }
+        // scheme://authority/path, possibly after some prefix (e.g. "uri:s3://...")
+        if let Some(sep) = token.find("://") {
+            let scheme_start = token[..sep].rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '+' || c == '-')).map_or(0, |i| i + 1);
+            let rest = &token[sep + 3..];
+            let (authority, path) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+            let authority = if authority.is_empty() { String::new() } else { self.redact_authority(authority) };
+            let (prefix, scheme) = token[..sep].split_at(scheme_start);
+            return format!("{}{}://{}{}", prefix, scheme, authority, self.redact_path(path));
+        }
+        if token.starts_with('/') {
+            return self.redact_path(token);
+        }
+        // host:/export (NFS sources) and host:port
+        if let Some((host, rest)) = token.split_once(':') {
+            if rest.starts_with('/') && !host.is_empty() {
+                return format!("{}:{}", self.redact_authority(host), self.redact_path(rest));
+            }
+            if !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()) && (self.hosts.contains_key(host) || is_ipv4(host)) {
+                return format!("{}:{}", self.redact_authority(host), rest);
+            }
+        }
+        if is_ipv4(token) {
+            return self.pseudonym("ip", token);
+        }
+        token.to_string()
+    }
+
+    /// Bucket, endpoint or server name (with optional :port and user@)
+    fn redact_authority(&mut self, authority: &str) -> String {
+        let host_port = authority.rsplit_once('@').map_or(authority, |(_, hp)| hp);
+        let (host, port) = match host_port.rsplit_once(':') {
+            Some((h, p)) if p.chars().all(|c| c.is_ascii_digit()) => (h, Some(p)),
+            _ => (host_port, None),
+        };
+        let alias = if let Some(alias) = self.hosts.get(host) {
+            alias.clone()
+        } else if is_ipv4(host) {
+            self.pseudonym("ip", host)
+        } else {
+            self.pseudonym("bucket", host)
+        };
+        port.map_or(alias.clone(), |p| format!("{}:{}", alias, p))
+    }
+
+    /// Absolute path -> /path-N, keeping a trailing slash
+    fn redact_path(&mut self, path: &str) -> String {
+        let trimmed = path.trim_end_matches('/');
+        if trimmed.is_empty() {
+            return path.to_string();
+        }
+        let alias = self.pseudonym("path", trimmed);
+        format!("/{}{}", alias, if path.ends_with('/') { "/" } else { "" })
+    }
+}
+
+fn is_host_key(key: &str) -> bool {
+    HOST_KEYS.contains(&key.to_ascii_lowercase().as_str())
+}
+
+fn is_ipv4(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('.').collect();
+    parts.len() == 4 && parts.iter().all(|p| !p.is_empty() && p.len() <= 3 && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redaction_keeps_metrics_and_is_consistent() {
+        let mut rank0 = json!({
+            "hostname": "gpu-node-17.corp.example",
+            "config": {"data_folder": "s3://acme-training/imagenet/train/"},
+            "metrics": {"storage_throughput_gib_s": 3.25, "files_processed": 1200},
+            "mount": {"mount_point": "/mnt/vast1", "source": "10.1.2.3:/export/data", "options": ["rw", "addr=10.1.2.3"]},
+            "content_verification": {"mismatch_examples": ["s3://acme-training/imagenet/train/f_01.npz on gpu-node-17.corp.example"]},
+            "storage": {"aws_secret_access_key": "abc", "session_token": null}
+        });
+        let mut rank1 = json!({"hostname": "gpu-node-17.corp.example", "config": {"data_folder": "file:///mnt/vast1/"}});
+
+        let mut redactor = Redactor::new();
+        redactor.redact(&mut rank0);
+        redactor.redact(&mut rank1);
+
+        let text = |v: &Value| v.as_str().unwrap().to_string();
+        assert_eq!(rank0["hostname"], "host-1");
+        assert_eq!(rank1["hostname"], "host-1");
+        assert_eq!(rank0["metrics"], json!({"storage_throughput_gib_s": 3.25, "files_processed": 1200}));
+        let folder = text(&rank0["config"]["data_folder"]);
+        assert!(folder.starts_with("s3://bucket-1/path-") && folder.ends_with('/'), "{}", folder);
+        // The same path is the same pseudonym in every file and field
+        assert_eq!(text(&rank1["config"]["data_folder"]), format!("file://{}/", text(&rank0["mount"]["mount_point"])));
+        assert!(text(&rank0["mount"]["source"]).starts_with("ip-1:/path-"));
+        assert_eq!(rank0["mount"]["options"], json!(["rw", "addr=ip-1"]));
+        let example = text(&rank0["content_verification"]["mismatch_examples"][0]);
+        assert!(example.starts_with("s3://bucket-1/path-") && example.ends_with(" on host-1"), "{}", example);
+        assert_eq!(rank0["storage"]["aws_secret_access_key"], REDACTED);
+        assert!(rank0["storage"]["session_token"].is_null());
+
+        let text = serde_json::to_string(&rank0).unwrap();
+        assert!(!text.contains("acme") && !text.contains("corp.example") && !text.contains("vast1"));
+        assert_eq!(redactor.mapping()["host-1"], "gpu-node-17.corp.example");
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/repeats.rs
+//
+// Repeat-and-average runs
+//
+// Single runs on shared storage are noisy. With `run --repeats N` the training
+// phase runs N times on the same dataset and each headline metric is reported
+// as a mean or median with its spread. `--discard-outliers` drops repetitions
+// further than 3 scaled MADs from the median before aggregating (the median
+// absolute deviation stays robust at the small N typical here).
+//
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::suite::RunMetrics;
+
+/// MAD-to-standard-deviation factor for normally distributed data
+const MAD_SCALE: f64 = 1.4826;
+
+/// Scaled MADs from the median beyond which a repetition is an outlier
+const OUTLIER_MADS: f64 = 3.0;
+
+/// Central tendency reported for repeated runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Mean,
+    Median,
+}
+
+impl Aggregate {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "mean" => Ok(Self::Mean),
+            "median" => Ok(Self::Median),
+            other => anyhow::bail!("Unknown aggregate '{}' (expected 'mean' or 'median')", other),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mean => "mean",
+            Self::Median => "median",
+        }
+    }
+}
+
+/// How many times to run and how to combine the results
+#[derive(Debug, Clone, Copy)]
+pub struct RepeatSettings {
+    pub repeats: u32,
+    pub aggregate: Aggregate,
+    pub discard_outliers: bool,
+}
+
+impl Default for RepeatSettings {
+    fn default() -> Self {
+        Self { repeats: 1, aggregate: Aggregate::Mean, discard_outliers: false }
+    }
+}
+
+/// One metric across repetitions
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricStats {
+    /// Mean or median of the kept repetitions
+    pub value: f64,
+    pub stddev: f64,
+    /// stddev / mean of the kept repetitions
+    pub cv: f64,
+    pub min: f64,
+    pub max: f64,
+    pub kept: usize,
+    /// Repetitions (1-based) dropped as outliers
+    pub discarded: Vec<u32>,
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Combine (repetition, value) pairs; None when no repetition reported the metric
+pub fn summarize(values: &[(u32, f64)], aggregate: Aggregate, discard_outliers: bool) -> Option<MetricStats> {
+    if values.is_empty() {
+        return None;
+    }
+    let all: Vec<f64> = values.iter().map(|(_, v)| *v).collect();
+    let center = median(&all);
+    let spread = MAD_SCALE * median(&all.iter().map(|v| (v - center).abs()).collect::<Vec<_>>());
+    let is_outlier = |v: f64| discard_outliers && values.len() >= 3 && spread > 0.0 && (v - center).abs() > OUTLIER_MADS * spread;
+
+    let discarded: Vec<u32> = values.iter().filter(|(_, v)| is_outlier(*v)).map(|(rep, _)| *rep).collect();
+    let kept: Vec<f64> = all.iter().copied().filter(|v| !is_outlier(*v)).collect();
+
+    let n = kept.len() as f64;
+    let mean = kept.iter().sum::<f64>() / n;
+    let stddev = if kept.len() > 1 {
+        (kept.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+    } else {
+        0.0
+    };
+    Some(MetricStats {
+        value: match aggregate {
+            Aggregate::Mean => mean,
+            Aggregate::Median => median(&kept),
+        },
+        stddev,
+        cv: if mean != 0.0 { stddev / mean.abs() } else { 0.0 },
+        min: kept.iter().copied().fold(f64::INFINITY, f64::min),
+        max: kept.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        kept: kept.len(),
+        discarded,
+    })
+}
+
+/// Per-repetition metrics and their aggregate
+#[derive(Debug, Clone, Serialize)]
+pub struct RepeatReport {
+    pub aggregate: String,
+    pub discard_outliers: bool,
+    pub repetitions: Vec<RunMetrics>,
+    pub throughput_gib_s: Option<MetricStats>,
+    pub samples_per_second: Option<MetricStats>,
+    pub au_percent: Option<MetricStats>,
+    pub wall_clock_s: Option<MetricStats>,
+}
+
+impl RepeatReport {
+    pub fn new(repetitions: Vec<RunMetrics>, settings: &RepeatSettings) -> Self {
+        let stats = |field: fn(&RunMetrics) -> Option<f64>| {
+            let values: Vec<(u32, f64)> = repetitions
+                .iter()
+                .enumerate()
+                .filter_map(|(i, m)| field(m).map(|v| (i as u32 + 1, v)))
+                .collect();
+            summarize(&values, settings.aggregate, settings.discard_outliers)
+        };
+        Self {
+            aggregate: settings.aggregate.name().to_string(),
+            discard_outliers: settings.discard_outliers,
+            throughput_gib_s: stats(|m| m.throughput_gib_s),
+            samples_per_second: stats(|m| m.samples_per_second),
+            au_percent: stats(|m| m.au_percent),
+            wall_clock_s: stats(|m| m.wall_clock_s),
+            repetitions,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n=== {} Repetitions ({}{}) ===", self.repetitions.len(), self.aggregate,
+                 if self.discard_outliers { ", outliers discarded" } else { "" });
+        let rows = [
+            ("Throughput (GiB/s)", &self.throughput_gib_s),
+            ("Samples/s", &self.samples_per_second),
+            ("AU %", &self.au_percent),
+            ("Wall clock (s)", &self.wall_clock_s),
+        ];
+        for (label, stats) in rows {
+            if let Some(s) = stats {
+                let dropped = if s.discarded.is_empty() {
+                    String::new()
+                } else {
+                    format!("  discarded rep {:?}", s.discarded)
+                };
+                println!("{:<20} {:>12.3} ± {:<10.3} (cv {:.1}%, range {:.3}-{:.3}){}",
+                         label, s.value, s.stddev, s.cv * 100.0, s.min, s.max, dropped);
+            }
+        }
+        println!("==============================\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_with_outlier_rejection() {
+        let values: Vec<(u32, f64)> = [10.0, 11.0, 9.0, 10.5, 40.0].iter().enumerate().map(|(i, v)| (i as u32 + 1, *v)).collect();
+
+        let kept_all = summarize(&values, Aggregate::Mean, false).unwrap();
+        assert_eq!((kept_all.kept, kept_all.value), (5, 16.1));
+
+        let robust = summarize(&values, Aggregate::Mean, true).unwrap();
+        assert_eq!(robust.discarded, vec![5]);
+        assert_eq!((robust.kept, robust.value, robust.max), (4, 10.125, 11.0));
+
+        let median = summarize(&values, Aggregate::Median, true).unwrap();
+        assert_eq!(median.value, 10.25);
+        assert!(summarize(&[], Aggregate::Mean, true).is_none());
+        assert!(Aggregate::parse("mode").is_err());
+    }
+}
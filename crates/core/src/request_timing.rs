@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/request_timing.rs
+//
+// Per-request GET timing (`storage.request_timing: true`)
+//
+// Batch latency covers several objects at once, so it hides what a single
+// request costs. With request timing on, every GET goes through TimedBackend,
+// which opens a request scope (a tokio task-local) around the read. Code
+// serving the read reports into the scope through two hooks:
+//
+//   mark_first_byte()   first response bytes arrived (TTFB)
+//   note_retry()        the request is being retried
+//
+// The hooks are no-ops outside a scope, so storage layers can call them
+// unconditionally. The http backend, the latency model (null://, sim://) and
+// failover retries call them; s3dlio reads report total time only until the
+// s3dlio store calls the hooks too. Request-level percentiles of total time,
+// TTFB and transfer time (total - TTFB) are reported under "request_timing".
+// Retries are also counted for `pool_stats`, in the run's own counter.
+//
+// s3dlio's loader reads through its own pool, which has no request hooks, so
+// timing an object-store run switches it to dl-driver's per-object reader on
+// the same store. The report's `read_path` says so: those timings describe
+// that reader, not the s3dlio loader a run without timing would use.
+//
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::backends::DataBackend;
+
+tokio::task_local! {
+    static REQUEST: RequestScope;
+}
+
+#[derive(Default)]
+struct RequestScope {
+    first_byte: Cell<Option<Instant>>,
+    retries: Cell<u32>,
+}
+
+/// Hook: the current request received its first response bytes (later calls are ignored)
+pub fn mark_first_byte() {
+    let _ = REQUEST.try_with(|scope| {
+        if scope.first_byte.get().is_none() {
+            scope.first_byte.set(Some(Instant::now()));
+        }
+    });
+}
+
+/// Hook: the current request is being retried
+pub fn note_retry() {
+    crate::pool_stats::count_retry();
+    let _ = REQUEST.try_with(|scope| scope.retries.set(scope.retries.get() + 1));
+}
+
+/// One completed request
+#[derive(Debug, Clone, Copy)]
+pub struct RequestEvent {
+    pub total: Duration,
+    pub ttfb: Option<Duration>,
+    pub bytes: usize,
+    pub retries: u32,
+    pub ok: bool,
+}
+
+/// Distribution of one per-request time, in ms
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct TimingSummary {
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl TimingSummary {
+    pub(crate) fn from_ms(mut ms: Vec<f64>) -> Option<Self> {
+        if ms.is_empty() {
+            return None;
+        }
+        ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let pct = |p: f64| ms[((p * (ms.len() - 1) as f64) as usize).min(ms.len() - 1)];
+        Some(Self {
+            mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+            p50_ms: pct(0.50),
+            p90_ms: pct(0.90),
+            p99_ms: pct(0.99),
+            max_ms: ms[ms.len() - 1],
+        })
+    }
+}
+
+/// Request-level timing of a run's GETs
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RequestTimingReport {
+    pub backend: String,
+    /// Set when the timed reads replaced the s3dlio loader's read path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_path: Option<String>,
+    pub requests: usize,
+    pub errors: usize,
+    pub bytes: u64,
+    pub retried_requests: usize,
+    pub retries: u64,
+    /// Successful requests only
+    pub total: Option<TimingSummary>,
+    /// Requests whose storage layer reported a first byte
+    pub ttfb_requests: usize,
+    pub ttfb: Option<TimingSummary>,
+    pub transfer: Option<TimingSummary>,
+}
+
+impl RequestTimingReport {
+    pub fn from_events(backend: &str, events: &[RequestEvent]) -> Self {
+        let ok: Vec<&RequestEvent> = events.iter().filter(|e| e.ok).collect();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let with_ttfb: Vec<(Duration, Duration)> = ok.iter().filter_map(|e| e.ttfb.map(|t| (e.total, t))).collect();
+        Self {
+            backend: backend.to_string(),
+            read_path: None,
+            requests: events.len(),
+            errors: events.len() - ok.len(),
+            bytes: ok.iter().map(|e| e.bytes as u64).sum(),
+            retried_requests: events.iter().filter(|e| e.retries > 0).count(),
+            retries: events.iter().map(|e| e.retries as u64).sum(),
+            total: TimingSummary::from_ms(ok.iter().map(|e| ms(e.total)).collect()),
+            ttfb_requests: with_ttfb.len(),
+            ttfb: TimingSummary::from_ms(with_ttfb.iter().map(|&(_, t)| ms(t)).collect()),
+            transfer: TimingSummary::from_ms(with_ttfb.iter().map(|&(total, t)| ms(total.saturating_sub(t))).collect()),
+        }
+    }
+}
+
+/// Collected request events of one run
+#[derive(Default)]
+pub struct RequestTimings {
+    events: Mutex<Vec<RequestEvent>>,
+}
+
+impl RequestTimings {
+    pub fn record(&self, event: RequestEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    pub fn report(&self, backend: &str) -> RequestTimingReport {
+        RequestTimingReport::from_events(backend, &self.events.lock().unwrap())
+    }
+}
+
+/// DataBackend wrapper timing each GET inside a request scope
+pub struct TimedBackend {
+    inner: Arc<dyn DataBackend>,
+    timings: Arc<RequestTimings>,
+}
+
+impl TimedBackend {
+    pub fn new(inner: Arc<dyn DataBackend>, timings: Arc<RequestTimings>) -> Self {
+        Self { inner, timings }
+    }
+}
+
+#[async_trait]
+impl DataBackend for TimedBackend {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        self.inner.list().await
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let start = Instant::now();
+        let (result, first_byte, retries) = REQUEST
+            .scope(RequestScope::default(), async {
+                let result = self.inner.get(uri).await;
+                let (first_byte, retries) = REQUEST.with(|scope| (scope.first_byte.get(), scope.retries.get()));
+                (result, first_byte, retries)
+            })
+            .await;
+        self.timings.record(RequestEvent {
+            total: start.elapsed(),
+            ttfb: first_byte.map(|t| t.saturating_duration_since(start)),
+            bytes: result.as_ref().map_or(0, Vec::len),
+            retries,
+            ok: result.is_ok(),
+        });
+        result
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.inner.put(uri, data).await
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.inner.report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sleeps, marks the first byte, sleeps again; fails the first attempt of "flaky"
+    struct SlowBackend;
+
+    #[async_trait]
+    impl DataBackend for SlowBackend {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        async fn list(&self) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+            if uri == "flaky" {
+                note_retry();
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            mark_first_byte();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            mark_first_byte();
+            Ok(vec![0; 100])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_scope_hooks() {
+        // Outside a scope the hooks do nothing
+        mark_first_byte();
+        note_retry();
+
+        let timings = Arc::new(RequestTimings::default());
+        let backend = TimedBackend::new(Arc::new(SlowBackend), Arc::clone(&timings));
+        backend.get("a").await.unwrap();
+        backend.get("flaky").await.unwrap();
+
+        let report = timings.report("slow");
+        assert_eq!((report.requests, report.errors, report.bytes), (2, 0, 200));
+        assert_eq!((report.retried_requests, report.retries, report.ttfb_requests), (1, 1, 2));
+        let (ttfb, transfer, total) = (report.ttfb.unwrap(), report.transfer.unwrap(), report.total.unwrap());
+        assert!(ttfb.p50_ms >= 20.0 && ttfb.p50_ms < total.p50_ms, "{:?} {:?}", ttfb, total);
+        assert!(transfer.p50_ms >= 10.0);
+        assert!((ttfb.mean_ms + transfer.mean_ms - total.mean_ms).abs() < 1.0);
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/results_csv.rs
+//
+// CSV export of aggregated multi-rank results (`aggregate --csv`)
+//
+// One row per rank followed by a "global" summary row, under a fixed column
+// schema: columns are only ever appended, so spreadsheets comparing storage
+// systems keep lining up across dl-driver versions. Values missing from the
+// results (e.g. no startup section) are left empty; per-rank rows leave the
+// global-only columns empty and the global row leaves the per-rank ones.
+//
+use serde_json::Value;
+
+/// Column schema of the aggregated CSV
+pub const COLUMNS: &[&str] = &[
+    "scope",
+    "rank",
+    "hostname",
+    "files_processed",
+    "samples_processed",
+    "bytes_read",
+    "throughput_gib_s",
+    "samples_per_second",
+    "wall_clock_s",
+    "au_fraction",
+    "time_to_first_batch_s",
+    "clock_offset_s",
+    "pass",
+];
+
+/// CSV (header, per-rank rows, global row) of an `aggregated_results` document
+pub fn aggregated_to_csv(aggregated: &Value) -> String {
+    let results = &aggregated["aggregated_results"];
+    let mut lines = vec![COLUMNS.join(",")];
+
+    for rank in results["rank_details"].as_array().into_iter().flatten() {
+        let metrics = &rank["metrics"];
+        let wall_clock_s = metrics["wall_clock_time_ms"].as_f64().map(|ms| Value::from(ms / 1000.0));
+        lines.push(row([
+            Some(Value::from("rank")),
+            Some(rank["rank"].clone()),
+            Some(rank["hostname"].clone()),
+            Some(metrics["files_processed"].clone()),
+            Some(metrics["samples_processed"].clone()),
+            Some(metrics["bytes_read"].clone()),
+            Some(metrics["storage_throughput_gib_s"].clone()),
+            Some(metrics["samples_per_second"].clone()),
+            wall_clock_s,
+            Some(metrics["au_fraction"].clone()),
+            Some(rank["startup"]["time_to_first_batch_s"].clone()),
+            Some(rank["clock_offset_seconds"].clone()),
+            None,
+        ]));
+    }
+
+    let global = &results["global_metrics"];
+    let ranks = results["rank_details"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let sum = |key: &str| -> Option<Value> {
+        let values: Vec<f64> = ranks.iter().filter_map(|r| r["metrics"][key].as_f64()).collect();
+        (!values.is_empty()).then(|| Value::from(values.iter().sum::<f64>()))
+    };
+    lines.push(row([
+        Some(Value::from("global")),
+        None,
+        None,
+        Some(global["total_files_processed"].clone()),
+        sum("samples_processed"),
+        Some(global["total_bytes_read"].clone()),
+        Some(global["total_throughput_gib_s"].clone()),
+        sum("samples_per_second"),
+        Some(global["global_runtime_seconds"].clone()),
+        Some(global["global_au"].clone()),
+        Some(global["time_to_first_batch_s"].clone()),
+        None,
+        Some(global["pass"].clone()),
+    ]));
+
+    lines.join("\n") + "\n"
+}
+
+fn row(values: [Option<Value>; 13]) -> String {
+    values.iter().map(|v| field(v.as_ref())).collect::<Vec<_>>().join(",")
+}
+
+/// One CSV field: empty for missing values, quoted when it contains a separator
+fn field(value: Option<&Value>) -> String {
+    let text = match value {
+        None | Some(Value::Null) => return String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregated_csv_rows() {
+        let aggregated = serde_json::json!({"aggregated_results": {
+            "total_ranks": 2,
+            "global_metrics": {"total_files_processed": 20, "total_bytes_read": 2048, "total_throughput_gib_s": 3.5,
+                               "global_runtime_seconds": 12.0, "global_au": 0.95, "time_to_first_batch_s": null, "pass": true},
+            "rank_details": [
+                {"rank": 0, "hostname": "node,1", "clock_offset_seconds": 0.0,
+                 "metrics": {"files_processed": 10, "samples_processed": 100, "bytes_read": 1024, "storage_throughput_gib_s": 1.5,
+                             "samples_per_second": 10.0, "wall_clock_time_ms": 11500, "au_fraction": 0.9},
+                 "startup": {"time_to_first_batch_s": 2.5}},
+                {"rank": 1, "hostname": "node2", "clock_offset_seconds": 0.25,
+                 "metrics": {"files_processed": 10, "samples_processed": 100, "bytes_read": 1024, "storage_throughput_gib_s": 2.0,
+                             "samples_per_second": 12.0, "wall_clock_time_ms": 12000, "au_fraction": 1.0},
+                 "startup": null}
+            ]
+        }});
+        let csv = aggregated_to_csv(&aggregated);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert_eq!(lines[1], "rank,0,\"node,1\",10,100,1024,1.5,10.0,11.5,0.9,2.5,0.0,");
+        assert_eq!(lines[2], "rank,1,node2,10,100,1024,2.0,12.0,12.0,1.0,,0.25,");
+        assert_eq!(lines[3], "global,,,20,200.0,2048,3.5,22.0,12.0,0.95,,,true");
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/results_db.rs
+//
+// Results database and change annotations (`history`)
+//
+// The database is a JSON-lines file with one record per run, added from the
+// run's rank results (`history add`), so it can sit next to the results and be
+// read with jq. Each run is keyed by its config file name (model name or data
+// folder for older results) and, when added, annotated with what changed since
+// the previous run of the same config:
+//
+//   dl_driver_version   dl-driver upgraded or downgraded
+//   s3dlio_version      storage library changed
+//   config_hash         config edited (hash of the parsed config, not the file)
+//
+// together with the throughput change. `history show` prints runs with their
+// annotations, so a regression sits next to its likely cause.
+//
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+
+use crate::dlio_compat::DlioConfig;
+
+/// s3dlio version dl-driver is built against, read from Cargo.lock by build.rs
+pub const S3DLIO_VERSION: &str = env!("S3DLIO_VERSION");
+
+/// Throughput drop (percent) flagged as a regression
+pub const REGRESSION_PCT: f64 = 5.0;
+
+/// Fields whose change is annotated, as named in rank results and records
+const TRACKED: [&str; 3] = ["dl_driver_version", "s3dlio_version", "config_hash"];
+
+/// Stable hash of a parsed config; comments and formatting do not change it
+pub fn config_hash(config: &DlioConfig) -> String {
+    format!("{:016x}", crate::rng::stable_hash(&serde_json::to_vec(config).unwrap_or_default()))
+}
+
+/// One tracked field that differs from the previous run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// One run in the database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// End of the run (Unix seconds)
+    pub timestamp: f64,
+    /// Config identity: config file name, else model name, else data folder
+    pub config: String,
+    pub config_hash: Option<String>,
+    pub dl_driver_version: Option<String>,
+    pub s3dlio_version: Option<String>,
+    pub hostname: Option<String>,
+    pub ranks: usize,
+    /// Sum over ranks
+    pub throughput_gib_s: f64,
+    /// Mean over ranks
+    pub au_percent: Option<f64>,
+    /// Results the record was built from
+    pub source: String,
+    /// Changes since the previous run of the same config
+    #[serde(default)]
+    pub changes: Vec<Change>,
+    #[serde(default)]
+    pub throughput_change_pct: Option<f64>,
+    #[serde(default)]
+    pub regression: bool,
+}
+
+impl RunRecord {
+    /// Record of one run from its rank results JSON documents
+    pub fn from_rank_results(ranks: &[Value], source: &str) -> Result<Self> {
+        let first = ranks
+            .iter()
+            .find(|r| r["rank"].as_u64() == Some(0))
+            .or_else(|| ranks.first())
+            .context("No rank results")?;
+        let text = |value: &Value| value.as_str().map(str::to_string);
+        let config = [&first["config_file"], &first["config"]["model"], &first["config"]["data_folder"]]
+            .into_iter()
+            .find_map(text)
+            .context("Rank results carry no config name, model or data folder")?;
+        let au: Vec<f64> = ranks.iter().filter_map(|r| r["metrics"]["au_percent"].as_f64()).collect();
+
+        Ok(Self {
+            timestamp: ranks.iter().filter_map(|r| r["end_time"].as_f64()).fold(0.0, f64::max),
+            config,
+            config_hash: text(&first["config_hash"]),
+            dl_driver_version: text(&first["dl_driver_version"]),
+            s3dlio_version: text(&first["s3dlio_version"]),
+            hostname: text(&first["hostname"]),
+            ranks: ranks.len(),
+            throughput_gib_s: ranks.iter().filter_map(|r| r["metrics"]["storage_throughput_gib_s"].as_f64()).sum(),
+            au_percent: (!au.is_empty()).then(|| au.iter().sum::<f64>() / au.len() as f64),
+            source: source.to_string(),
+            changes: Vec::new(),
+            throughput_change_pct: None,
+            regression: false,
+        })
+    }
+
+    fn field(&self, name: &str) -> Option<&String> {
+        match name {
+            "dl_driver_version" => self.dl_driver_version.as_ref(),
+            "s3dlio_version" => self.s3dlio_version.as_ref(),
+            _ => self.config_hash.as_ref(),
+        }
+    }
+
+    /// Annotate with the differences from the previous run of the same config
+    pub fn annotate(&mut self, previous: &RunRecord) {
+        self.changes = TRACKED
+            .iter()
+            .filter(|field| self.field(field) != previous.field(field))
+            .map(|field| Change {
+                field: field.to_string(),
+                from: previous.field(field).cloned(),
+                to: self.field(field).cloned(),
+            })
+            .collect();
+        self.throughput_change_pct = (previous.throughput_gib_s > 0.0)
+            .then(|| (self.throughput_gib_s / previous.throughput_gib_s - 1.0) * 100.0);
+        self.regression = self.throughput_change_pct.is_some_and(|pct| pct < -REGRESSION_PCT);
+    }
+
+    /// One `history` line: time, config, throughput (and change), AU, annotations
+    pub fn history_line(&self) -> String {
+        let time = chrono::DateTime::from_timestamp(self.timestamp as i64, 0)
+            .map_or_else(|| "-".to_string(), |t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+        let mut line = format!("{}  {}  {:.3} GiB/s", time, self.config, self.throughput_gib_s);
+        if let Some(pct) = self.throughput_change_pct {
+            line.push_str(&format!(" ({:+.1}%{})", pct, if self.regression { ", regression" } else { "" }));
+        }
+        if let Some(au) = self.au_percent {
+            line.push_str(&format!("  AU {:.1}%", au));
+        }
+        let changes: Vec<String> = self
+            .changes
+            .iter()
+            .map(|c| match c.field.as_str() {
+                "config_hash" => "config changed".to_string(),
+                field => format!(
+                    "{} {} → {}",
+                    field.trim_end_matches("_version").replace('_', "-"),
+                    c.from.as_deref().unwrap_or("?"),
+                    c.to.as_deref().unwrap_or("?")
+                ),
+            })
+            .collect();
+        if !changes.is_empty() {
+            line.push_str(&format!("  [{}]", changes.join("; ")));
+        }
+        line
+    }
+}
+
+/// Every record in the database (none when the file does not exist yet)
+pub fn load(db: &Path) -> Result<Vec<RunRecord>> {
+    let text = match std::fs::read_to_string(db) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read results database {:?}", db)),
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).with_context(|| format!("{:?} line {} is not a run record", db, i + 1)))
+        .collect()
+}
+
+/// Annotate a record against the latest earlier run of its config and append it
+pub fn add(db: &Path, mut record: RunRecord) -> Result<RunRecord> {
+    let previous = load(db)?
+        .into_iter()
+        .filter(|r| r.config == record.config && r.timestamp <= record.timestamp)
+        .max_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    if let Some(previous) = &previous {
+        record.annotate(previous);
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(db)
+        .with_context(|| format!("Failed to open results database {:?}", db))?;
+    writeln!(file, "{}", serde_json::to_string(&record)?).with_context(|| format!("Failed to append to {:?}", db))?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rank(rank: u32, end: f64, gib_s: f64, version: &str, hash: &str) -> Value {
+        json!({
+            "rank": rank, "end_time": end, "hostname": "node1", "config_file": "unet3d.yaml",
+            "config_hash": hash, "dl_driver_version": version, "s3dlio_version": S3DLIO_VERSION,
+            "config": {"model": "unet3d"},
+            "metrics": {"storage_throughput_gib_s": gib_s, "au_percent": 92.0},
+        })
+    }
+
+    #[test]
+    fn test_history_annotates_changes_since_previous_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("history.jsonl");
+
+        let first = add(&db, RunRecord::from_rank_results(&[rank(0, 100.0, 2.0, "0.6.2", "aa"), rank(1, 101.0, 2.0, "0.6.2", "aa")], "r1").unwrap()).unwrap();
+        assert_eq!((first.ranks, first.throughput_gib_s, first.timestamp), (2, 4.0, 101.0));
+        assert!(first.changes.is_empty() && first.throughput_change_pct.is_none());
+
+        // Same config, same versions: no changes, small drift is not a regression
+        let steady = add(&db, RunRecord::from_rank_results(&[rank(0, 200.0, 3.9, "0.6.2", "aa")], "r2").unwrap()).unwrap();
+        assert!(steady.changes.is_empty() && !steady.regression);
+
+        // Upgrade and config edit with a throughput drop: annotated and flagged
+        let upgraded = add(&db, RunRecord::from_rank_results(&[rank(0, 300.0, 3.0, "0.6.3", "bb")], "r3").unwrap()).unwrap();
+        let fields: Vec<&str> = upgraded.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["dl_driver_version", "config_hash"]);
+        assert_eq!(upgraded.changes[0].from.as_deref(), Some("0.6.2"));
+        assert!(upgraded.regression);
+        let line = upgraded.history_line();
+        assert!(line.contains("3.000 GiB/s (-23.1%, regression)"), "{}", line);
+        assert!(line.contains("[dl-driver 0.6.2 → 0.6.3; config changed]"), "{}", line);
+
+        // Other configs are tracked separately
+        let mut other = rank(0, 400.0, 1.0, "0.6.3", "cc");
+        other["config_file"] = json!("resnet50.yaml");
+        assert!(add(&db, RunRecord::from_rank_results(&[other], "r4").unwrap()).unwrap().changes.is_empty());
+
+        assert_eq!(load(&db).unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/results_upload.rs
+//
+// Per-rank results in object storage
+//
+// Multi-node runs otherwise leave each rank's results JSON and traces on that
+// node's local disk. With `--results-uri`, every rank uploads its files under
+// one prefix (e.g. s3://bucket/results/<run_id>/) in the background as soon as
+// training finishes, and `aggregate` can read them back from that prefix.
+//
+use anyhow::{Context, Result};
+use s3dlio::object_store::store_for_uri;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::artifacts::{self, ZSTD_SUFFIX};
+
+/// Suffix of per-rank results objects (optionally followed by `.zst`); `aggregate` reads only these
+pub const RESULTS_SUFFIX: &str = "_results.json";
+
+/// Whether a results location is an object storage URI rather than a local path or glob
+pub fn is_results_uri(location: &str) -> bool {
+    location.contains("://")
+}
+
+/// Object name of one rank's results JSON
+pub fn rank_results_name(rank: u32) -> String {
+    format!("rank_{:04}{}", rank, RESULTS_SUFFIX)
+}
+
+/// Object name of one rank's batch arrival trace
+pub fn rank_trace_name(rank: u32) -> String {
+    format!("rank_{:04}_arrivals.json", rank)
+}
+
+fn object_uri(prefix: &str, name: &str) -> String {
+    format!("{}/{}", prefix.trim_end_matches('/'), name)
+}
+
+/// Upload (name, contents) pairs under `prefix`; returns the object URIs written
+pub async fn upload(prefix: &str, files: Vec<(String, Vec<u8>)>) -> Result<Vec<String>> {
+    let store = store_for_uri(prefix).with_context(|| format!("Failed to create object store for {}", prefix))?;
+    let mut written = Vec::with_capacity(files.len());
+    for (name, data) in files {
+        let uri = object_uri(prefix, &name);
+        store.put(&uri, &data).await.with_context(|| format!("Failed to upload results to {}", uri))?;
+        info!("☁️  Uploaded {} ({} bytes)", uri, data.len());
+        written.push(uri);
+    }
+    Ok(written)
+}
+
+/// Start uploading in the background; await the handle before exiting
+pub fn spawn_upload(prefix: &str, files: Vec<(String, Vec<u8>)>) -> JoinHandle<Result<Vec<String>>> {
+    let prefix = prefix.to_string();
+    tokio::spawn(async move { upload(&prefix, files).await })
+}
+
+/// Fetch every per-rank results JSON under `prefix` as (object URI, contents), sorted by URI
+pub async fn fetch_rank_results(prefix: &str) -> Result<Vec<(String, String)>> {
+    let store = store_for_uri(prefix).with_context(|| format!("Failed to create object store for {}", prefix))?;
+    let mut uris: Vec<String> = store
+        .list(prefix, true)
+        .await
+        .with_context(|| format!("Failed to list results under {}", prefix))?
+        .into_iter()
+        .filter(|uri| uri.trim_end_matches(ZSTD_SUFFIX).ends_with(RESULTS_SUFFIX))
+        .collect();
+    uris.sort();
+
+    let mut results = Vec::with_capacity(uris.len());
+    for uri in uris {
+        let data = store.get(&uri).await.with_context(|| format!("Failed to read {}", uri))?;
+        let data = artifacts::decode(data.to_vec()).with_context(|| format!("Failed to decompress {}", uri))?;
+        let content = String::from_utf8(data).with_context(|| format!("{} is not UTF-8 JSON", uri))?;
+        results.push((uri, content));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_naming() {
+        assert!(is_results_uri("s3://bucket/results/run1/"));
+        assert!(!is_results_uri("/results/rank*.json"));
+        assert_eq!(object_uri("s3://b/r/run1/", &rank_results_name(3)), "s3://b/r/run1/rank_0003_results.json");
+        assert!(!rank_trace_name(3).ends_with(RESULTS_SUFFIX));
+    }
+}
@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/resume.rs
+//
+// Resumable dataset generation (`dl-driver generate --skip-existing`)
+//
+// Before writing, the data folder is listed and the size of every object read
+// (STAT, or the native backend's size lookup, `STAT_CONCURRENCY` at a time).
+// Generation then skips objects that already exist at the size it would
+// write, before building their contents, and writes only missing or
+// size-mismatched ones, so an interrupted run resumes instead of rewriting the
+// whole dataset.
+//
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::info;
+
+use crate::backends::DataBackend;
+use crate::shuffle::access_key;
+use s3dlio::object_store::ObjectStore;
+
+/// Object sizes read concurrently while listing
+const STAT_CONCURRENCY: usize = 64;
+
+/// Objects found in the data folder, and what generation did with them
+pub struct ExistingObjects {
+    folder: String,
+    /// Size by key relative to the folder
+    sizes: HashMap<String, u64>,
+    skipped: AtomicU64,
+    skipped_bytes: AtomicU64,
+    mismatched: AtomicU64,
+}
+
+/// Outcome of a resumed generation
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResumeSummary {
+    /// Objects already in the data folder
+    pub listed: u64,
+    /// Objects kept because they exist at the expected size
+    pub skipped: u64,
+    pub skipped_bytes: u64,
+    /// Objects rewritten because their size was wrong
+    pub size_mismatched: u64,
+}
+
+impl ExistingObjects {
+    /// List `folder` through the native backend or object store generation writes with
+    pub async fn list(folder: &str, backend: Option<&dyn DataBackend>, store: Option<&dyn ObjectStore>) -> Result<Self> {
+        let sizes: Vec<(String, u64)> = match (backend, store) {
+            (Some(backend), _) => {
+                stream::iter(backend.list().await?)
+                    .map(|uri| async move {
+                        let size = backend.size(&uri).await.with_context(|| format!("Failed to size {}", uri))?;
+                        Ok::<_, anyhow::Error>((uri, size))
+                    })
+                    .buffer_unordered(STAT_CONCURRENCY)
+                    .try_collect()
+                    .await?
+            }
+            (None, Some(store)) => {
+                let uris = store.list(folder, true).await.with_context(|| format!("Failed to list {}", folder))?;
+                stream::iter(uris)
+                    .map(|uri| async move {
+                        let meta = store.stat(&uri).await.with_context(|| format!("Failed to stat {}", uri))?;
+                        Ok::<_, anyhow::Error>((uri, meta.size))
+                    })
+                    .buffer_unordered(STAT_CONCURRENCY)
+                    .try_collect()
+                    .await?
+            }
+            (None, None) => Vec::new(),
+        };
+        let existing = Self::from_sizes(folder, sizes);
+        info!("♻️  Resuming generation: {} objects already in {}", existing.sizes.len(), folder);
+        Ok(existing)
+    }
+
+    pub fn from_sizes(folder: &str, sizes: impl IntoIterator<Item = (String, u64)>) -> Self {
+        let sizes = sizes.into_iter().map(|(uri, size)| (access_key(&uri, folder).to_string(), size)).collect();
+        Self {
+            folder: folder.to_string(),
+            sizes,
+            skipped: AtomicU64::new(0),
+            skipped_bytes: AtomicU64::new(0),
+            mismatched: AtomicU64::new(0),
+        }
+    }
+
+    /// `uri` is in the data folder, at whatever size
+    pub fn contains(&self, uri: &str) -> bool {
+        self.sizes.contains_key(access_key(uri, &self.folder))
+    }
+
+    /// `uri` already exists with `len` bytes and need not be written again
+    pub fn skip(&self, uri: &str, len: u64) -> bool {
+        match self.sizes.get(access_key(uri, &self.folder)) {
+            Some(&size) if size == len => {
+                self.skipped.fetch_add(1, Ordering::Relaxed);
+                self.skipped_bytes.fetch_add(len, Ordering::Relaxed);
+                true
+            }
+            Some(_) => {
+                self.mismatched.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn summary(&self) -> ResumeSummary {
+        ResumeSummary {
+            listed: self.sizes.len() as u64,
+            skipped: self.skipped.load(Ordering::Relaxed),
+            skipped_bytes: self.skipped_bytes.load(Ordering::Relaxed),
+            size_mismatched: self.mismatched.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_matches_name_and_size() {
+        let existing = ExistingObjects::from_sizes(
+            "s3://bucket/train/",
+            [
+                ("s3://bucket/train/train_file_000000.npz".to_string(), 100),
+                ("s3://bucket/train/train_file_000001.npz".to_string(), 37),
+            ],
+        );
+        assert!(existing.contains("s3://bucket/train/train_file_000001.npz"));
+        assert!(!existing.contains("s3://bucket/train/train_file_000002.npz"));
+        assert!(existing.skip("s3://bucket/train/train_file_000000.npz", 100));
+        // Truncated by an interrupted write: rewritten
+        assert!(!existing.skip("s3://bucket/train/train_file_000001.npz", 100));
+        assert!(!existing.skip("s3://bucket/train/train_file_000002.npz", 100));
+        assert_eq!(
+            existing.summary(),
+            ResumeSummary { listed: 2, skipped: 1, skipped_bytes: 100, size_mismatched: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_native_backends_are_matched_by_size() {
+        let config = crate::dlio_compat::DlioConfig::from_yaml(
+            "dataset:\n  data_folder: mem://train\n  num_files_train: 2\n  record_length_bytes: 64\nreader: {}\n",
+        )
+        .unwrap();
+        let backend = crate::backends::mem::MemBackend::new(&config).unwrap();
+        let uris = backend.list().await.unwrap();
+        let existing = ExistingObjects::list("mem://train", Some(&backend as &dyn DataBackend), None).await.unwrap();
+        assert!(existing.skip(&uris[0], 64));
+        assert!(!existing.skip(&uris[1], 65));
+    }
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/rng.rs
+//
+// Random number generation and seed derivation
+//
+// Everything dl-driver randomizes deterministically draws from one of two
+// fully specified generators, so a seed names the same stream on every
+// platform and across dl-driver (and `rand`) versions:
+//
+//   ChaCha8            shuffles (file permutation, sample order, shuffle
+//                      buffer); rand_chacha's ChaCha8Rng seeded with
+//                      `seed_from_u64` (PCG32 key expansion)
+//   SplitMix64         seed derivation, verifiable sample content and random
+//                      key prefixes
+//
+// Shuffle seeds are derived hierarchically from the run seed (`reader.seed`,
+// or drawn at random and recorded):
+//
+//   run → rank → epoch → file       child = derive(parent, level, index)
+//
+// `derive` XORs the parent with a per-level constant and the SplitMix64 hash
+// of the index, then applies one SplitMix64 step, so siblings and levels never
+// share a stream. Known-answer tests pin the derivation, the ChaCha8 output and
+// the permutation order (shuffle.rs); changing any of them is a reproducibility
+// break, not a refactor. The algorithms
+// and seeds are recorded under "shuffle.rng" in the results.
+//
+// s3dlio's loader shuffles with its own generator when dl-driver hands it a
+// (derived) epoch seed; that order is outside this guarantee.
+//
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+/// Generator used for shuffles
+pub type Prng = ChaCha8Rng;
+
+pub const PRNG_ALGORITHM: &str = "ChaCha8 (rand_chacha ChaCha8Rng, seed_from_u64)";
+pub const SEED_DERIVATION: &str = "splitmix64(parent ^ level * 0xd1b54a32d192ed03 ^ splitmix64(index)); run -> rank -> epoch -> file";
+pub const CONTENT_GENERATOR: &str = "SplitMix64 keyed by (content seed, file index, sample index)";
+
+const LEVEL_RANK: u64 = 1;
+const LEVEL_EPOCH: u64 = 2;
+const LEVEL_FILE: u64 = 3;
+
+/// splitmix64 step, used both to derive stream seeds and to fill content
+pub fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Seed of child `index` at `level` below `parent`
+fn derive(parent: u64, level: u64, index: u64) -> u64 {
+    let mut key = index;
+    let mut state = parent ^ level.wrapping_mul(0xd1b5_4a32_d192_ed03) ^ splitmix64(&mut key);
+    splitmix64(&mut state)
+}
+
+/// The shuffle generator for a seed
+pub fn prng(seed: u64) -> Prng {
+    Prng::seed_from_u64(seed)
+}
+
+/// Seed of the file at `file_position` within an epoch
+pub fn file_seed(epoch_seed: u64, file_position: u64) -> u64 {
+    derive(epoch_seed, LEVEL_FILE, file_position)
+}
+
+/// FNV-1a 64-bit hash; unlike std's DefaultHasher, stable across Rust releases
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Standard normal draw (Box-Muller) from two uniform [0, 1) draws, taken in order
+pub fn standard_normal(mut uniform: impl FnMut() -> f64) -> f64 {
+    // 1 - u keeps the log argument in (0, 1]
+    let u1 = 1.0 - uniform();
+    let u2 = uniform();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Seeds of one rank of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedTree {
+    pub run_seed: u64,
+    pub rank: u32,
+    pub rank_seed: u64,
+}
+
+impl SeedTree {
+    pub fn new(run_seed: u64, rank: u32) -> Self {
+        Self { run_seed, rank, rank_seed: derive(run_seed, LEVEL_RANK, rank as u64) }
+    }
+
+    pub fn epoch(&self, epoch: u32) -> u64 {
+        derive(self.rank_seed, LEVEL_EPOCH, epoch as u64)
+    }
+
+    pub fn report(&self, seed_configured: bool) -> RngReport {
+        RngReport {
+            algorithm: PRNG_ALGORITHM,
+            seed_derivation: SEED_DERIVATION,
+            content_generator: CONTENT_GENERATOR,
+            run_seed: self.run_seed,
+            seed_configured,
+            rank: self.rank,
+            rank_seed: self.rank_seed,
+        }
+    }
+}
+
+/// Generators and seeds of a run, for reproducibility audits
+#[derive(Debug, Clone, Serialize)]
+pub struct RngReport {
+    pub algorithm: &'static str,
+    pub seed_derivation: &'static str,
+    pub content_generator: &'static str,
+    pub run_seed: u64,
+    /// Whether the run seed came from `reader.seed` (false: drawn at random)
+    pub seed_configured: bool,
+    pub rank: u32,
+    pub rank_seed: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    #[test]
+    fn test_known_answers_are_stable() {
+        // Pinned values: a failure here means seeded runs no longer reproduce
+        let tree = SeedTree::new(42, 0);
+        assert_eq!(tree.rank_seed, 0xe30a_b5bf_26bd_65cb);
+        assert_eq!(SeedTree::new(42, 1).rank_seed, 0x3b09_de0d_8755_0064);
+        assert_eq!(tree.epoch(0), 0xb787_62e3_4042_3eed);
+        assert_eq!(tree.epoch(1), 0x4df4_1c94_2e5e_c36a);
+        assert_eq!(file_seed(tree.epoch(0), 5), 0x998b_76e7_2506_7b5d);
+
+        let mut rng = prng(42);
+        assert_eq!(rng.next_u64(), 0xae90_bfb5_395d_5ba1);
+        assert_eq!(rng.next_u64(), 0xf345_3fc6_2579_9188);
+        assert_eq!(prng(tree.epoch(0)).next_u64(), 0x62fa_93ce_8121_9ec0);
+
+        assert_eq!(stable_hash(b"train_file_000001.npz"), 0x8ac0_87a1_95e3_9924);
+
+        let mut uniforms = [0.5, 0.0].into_iter();
+        assert!((standard_normal(|| uniforms.next().unwrap()) - (2.0 * 2f64.ln()).sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,257 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/scaling.rs
+//
+// Client-count scaling projection (`report project`)
+//
+// Single-host results at increasing concurrency (per-rank results, or
+// aggregated results of N local ranks) describe how one client saturates.
+// They are fitted with the Universal Scalability Law,
+//
+//   X(n) = λn / (1 + σ(n-1) + κn(n-1))
+//
+// and aggregate throughput for N clients is projected as each client running
+// at the measured single-host peak, with σ as the cross-client contention
+// coefficient (overridable) and an optional storage-side cap:
+//
+//   X_total(N) = min(N · X_host / (1 + σ(N-1)), storage_limit)
+//
+// Measured multi-client aggregated results are compared with the projection
+// and flagged when they deviate by more than the tolerance.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// One measured load level
+#[derive(Debug, Clone, Serialize)]
+pub struct ScalingPoint {
+    pub source: String,
+    /// Ranks on the host (single-host points) or client hosts (measured points)
+    pub concurrency: usize,
+    pub throughput_gib_s: f64,
+}
+
+impl ScalingPoint {
+    /// Concurrency = ranks, from an aggregated results file or a single rank's results
+    pub fn single_host(source: &str, results: &serde_json::Value) -> Result<Self> {
+        let (concurrency, throughput_gib_s) = match results.get("aggregated_results") {
+            Some(agg) => (
+                agg["total_ranks"].as_u64().context("aggregated results without total_ranks")? as usize,
+                agg["global_metrics"]["total_throughput_gib_s"].as_f64(),
+            ),
+            None => (1, results["metrics"]["storage_throughput_gib_s"].as_f64()),
+        };
+        let throughput_gib_s = throughput_gib_s.with_context(|| format!("{} has no throughput metric", source))?;
+        Ok(Self { source: source.to_string(), concurrency, throughput_gib_s })
+    }
+
+    /// Concurrency = distinct client hosts, from an aggregated results file
+    pub fn multi_client(source: &str, results: &serde_json::Value) -> Result<Self> {
+        let agg = results.get("aggregated_results").with_context(|| format!("{} is not an aggregated results file", source))?;
+        let details = agg["rank_details"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let hosts: BTreeSet<&str> = details.iter().filter_map(|d| d["hostname"].as_str()).collect();
+        let concurrency = if hosts.is_empty() { details.len().max(1) } else { hosts.len() };
+        let throughput_gib_s = agg["global_metrics"]["total_throughput_gib_s"]
+            .as_f64()
+            .with_context(|| format!("{} has no total_throughput_gib_s", source))?;
+        Ok(Self { source: source.to_string(), concurrency, throughput_gib_s })
+    }
+}
+
+/// Universal Scalability Law coefficients
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct UslFit {
+    /// Throughput of one unit of concurrency without contention (GiB/s)
+    pub lambda: f64,
+    /// Contention (serialization) coefficient
+    pub sigma: f64,
+    /// Coherency (crosstalk) coefficient
+    pub kappa: f64,
+}
+
+impl UslFit {
+    /// Least-squares fit of σ and κ (both ≥ 0) on the linearized law; λ from the lowest load level
+    pub fn fit(points: &[ScalingPoint]) -> Result<Self> {
+        let base = points
+            .iter()
+            .filter(|p| p.throughput_gib_s > 0.0)
+            .min_by_key(|p| p.concurrency)
+            .context("No single-host results with positive throughput")?;
+        let lambda = base.throughput_gib_s / base.concurrency as f64;
+
+        // n / C(n) - 1 = σ(n-1) + κn(n-1), with C(n) = X(n) / λ
+        let rows: Vec<(f64, f64, f64)> = points
+            .iter()
+            .filter(|p| p.concurrency > 1 && p.throughput_gib_s > 0.0)
+            .map(|p| {
+                let n = p.concurrency as f64;
+                (n - 1.0, n * (n - 1.0), n * lambda / p.throughput_gib_s - 1.0)
+            })
+            .collect();
+        let sigma_only = |rows: &[(f64, f64, f64)]| {
+            let sxx: f64 = rows.iter().map(|r| r.0 * r.0).sum();
+            if sxx > 0.0 { (rows.iter().map(|r| r.0 * r.2).sum::<f64>() / sxx).max(0.0) } else { 0.0 }
+        };
+
+        let (s11, s12, s22) = rows.iter().fold((0.0, 0.0, 0.0), |(a, b, c), r| (a + r.0 * r.0, b + r.0 * r.1, c + r.1 * r.1));
+        let (s1y, s2y) = rows.iter().fold((0.0, 0.0), |(a, b), r| (a + r.0 * r.2, b + r.1 * r.2));
+        let det = s11 * s22 - s12 * s12;
+        let (sigma, kappa) = if rows.len() >= 2 && det.abs() > 1e-12 {
+            let sigma = (s1y * s22 - s2y * s12) / det;
+            let kappa = (s2y * s11 - s1y * s12) / det;
+            match (sigma >= 0.0, kappa >= 0.0) {
+                (true, true) => (sigma, kappa),
+                // A negative coefficient means the data don't support it; refit without it
+                (_, false) => (sigma_only(&rows), 0.0),
+                (false, true) => {
+                    let sxx: f64 = rows.iter().map(|r| r.1 * r.1).sum();
+                    (0.0, (rows.iter().map(|r| r.1 * r.2).sum::<f64>() / sxx).max(0.0))
+                }
+            }
+        } else {
+            (sigma_only(&rows), 0.0)
+        };
+        Ok(Self { lambda, sigma, kappa })
+    }
+
+    pub fn throughput(&self, n: f64) -> f64 {
+        self.lambda * n / (1.0 + self.sigma * (n - 1.0) + self.kappa * n * (n - 1.0))
+    }
+}
+
+/// Projected (and, where measured, actual) throughput for one client count
+#[derive(Debug, Clone, Serialize)]
+pub struct Projection {
+    pub clients: usize,
+    pub projected_gib_s: f64,
+    pub measured_gib_s: Option<f64>,
+    /// (measured - projected) / projected
+    pub deviation: Option<f64>,
+    pub flagged: bool,
+}
+
+/// Projection options
+#[derive(Debug, Clone, Default)]
+pub struct ProjectionOptions {
+    pub clients: Vec<usize>,
+    /// Cross-client contention; defaults to the single-host σ
+    pub contention: Option<f64>,
+    pub storage_limit_gib_s: Option<f64>,
+    pub tolerance: f64,
+}
+
+/// Scaling projection report
+#[derive(Debug, Clone, Serialize)]
+pub struct ScalingReport {
+    pub single_host: Vec<ScalingPoint>,
+    pub fit: UslFit,
+    pub host_peak_gib_s: f64,
+    pub contention: f64,
+    pub storage_limit_gib_s: Option<f64>,
+    pub tolerance: f64,
+    pub projections: Vec<Projection>,
+    pub flagged: usize,
+}
+
+impl ScalingReport {
+    pub fn new(single_host: Vec<ScalingPoint>, measured: &[ScalingPoint], options: &ProjectionOptions) -> Result<Self> {
+        let fit = UslFit::fit(&single_host)?;
+        let host_peak_gib_s = single_host.iter().map(|p| p.throughput_gib_s).fold(0.0, f64::max);
+        let contention = options.contention.unwrap_or(fit.sigma);
+
+        let clients: BTreeSet<usize> = options.clients.iter().copied().chain(measured.iter().map(|p| p.concurrency)).collect();
+        let projections: Vec<Projection> = clients
+            .into_iter()
+            .filter(|&n| n > 0)
+            .map(|n| {
+                let uncapped = n as f64 * host_peak_gib_s / (1.0 + contention * (n as f64 - 1.0));
+                let projected_gib_s = options.storage_limit_gib_s.map_or(uncapped, |limit| uncapped.min(limit));
+                let measured_gib_s = measured.iter().find(|p| p.concurrency == n).map(|p| p.throughput_gib_s);
+                let deviation = measured_gib_s.filter(|_| projected_gib_s > 0.0).map(|m| (m - projected_gib_s) / projected_gib_s);
+                Projection {
+                    clients: n,
+                    projected_gib_s,
+                    measured_gib_s,
+                    deviation,
+                    flagged: deviation.is_some_and(|d| d.abs() > options.tolerance),
+                }
+            })
+            .collect();
+        let flagged = projections.iter().filter(|p| p.flagged).count();
+
+        Ok(Self {
+            single_host,
+            fit,
+            host_peak_gib_s,
+            contention,
+            storage_limit_gib_s: options.storage_limit_gib_s,
+            tolerance: options.tolerance,
+            projections,
+            flagged,
+        })
+    }
+
+    pub fn print_table(&self) {
+        println!("\n=== Client Scaling Projection ===");
+        println!(
+            "Single host: peak {:.3} GiB/s, USL λ={:.3} σ={:.4} κ={:.5}; cross-client contention {:.4}",
+            self.host_peak_gib_s, self.fit.lambda, self.fit.sigma, self.fit.kappa, self.contention
+        );
+        println!("{:>8} {:>14} {:>14} {:>10}", "clients", "projected", "measured", "deviation");
+        for p in &self.projections {
+            println!(
+                "{:>8} {:>10.3} GiB/s {:>14} {:>10}{}",
+                p.clients,
+                p.projected_gib_s,
+                p.measured_gib_s.map_or("-".to_string(), |m| format!("{:.3} GiB/s", m)),
+                p.deviation.map_or("-".to_string(), |d| format!("{:+.1}%", d * 100.0)),
+                if p.flagged { "  ⚠️" } else { "" }
+            );
+        }
+        if self.flagged > 0 {
+            println!("⚠️  {} measured result(s) deviate from the projection by more than {:.0}%", self.flagged, self.tolerance * 100.0);
+        }
+        println!("=================================\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usl_fit_and_projection_flags() {
+        let truth = UslFit { lambda: 1.0, sigma: 0.05, kappa: 0.002 };
+        let single_host: Vec<ScalingPoint> = [1, 2, 4, 8, 16]
+            .iter()
+            .map(|&n| ScalingPoint { source: format!("{}r", n), concurrency: n, throughput_gib_s: truth.throughput(n as f64) })
+            .collect();
+        let fit = UslFit::fit(&single_host).unwrap();
+        assert!((fit.sigma - 0.05).abs() < 1e-6 && (fit.kappa - 0.002).abs() < 1e-6, "{:?}", fit);
+
+        let measured = vec![
+            ScalingPoint { source: "a".into(), concurrency: 2, throughput_gib_s: 0.0 },
+            ScalingPoint { source: "b".into(), concurrency: 4, throughput_gib_s: 0.0 },
+        ];
+        let options = ProjectionOptions { clients: vec![1, 4], contention: Some(0.0), storage_limit_gib_s: Some(20.0), tolerance: 0.15 };
+        let mut report = ScalingReport::new(single_host.clone(), &measured, &options).unwrap();
+        let peak = report.host_peak_gib_s;
+        assert_eq!(report.projections.iter().map(|p| p.clients).collect::<Vec<_>>(), [1, 2, 4]);
+        assert!((report.projections[2].projected_gib_s - (4.0 * peak).min(20.0)).abs() < 1e-9);
+
+        let measured = vec![
+            ScalingPoint { source: "a".into(), concurrency: 2, throughput_gib_s: 2.0 * peak * 0.95 },
+            ScalingPoint { source: "b".into(), concurrency: 4, throughput_gib_s: (4.0 * peak).min(20.0) * 0.5 },
+        ];
+        report = ScalingReport::new(single_host, &measured, &options).unwrap();
+        assert_eq!(report.projections.iter().map(|p| p.flagged).collect::<Vec<_>>(), [false, false, true]);
+        assert_eq!(report.flagged, 1);
+
+        let agg = serde_json::json!({"aggregated_results": {"total_ranks": 4, "global_metrics": {"total_throughput_gib_s": 3.5},
+            "rank_details": [{"hostname": "h1"}, {"hostname": "h1"}, {"hostname": "h2"}, {"hostname": "h2"}]}});
+        assert_eq!(ScalingPoint::single_host("x", &agg).unwrap().concurrency, 4);
+        assert_eq!(ScalingPoint::multi_client("x", &agg).unwrap().concurrency, 2);
+    }
+}
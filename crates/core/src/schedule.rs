@@ -0,0 +1,218 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/schedule.rs
+//
+// Scheduled actions within one measured run (`schedule:` config block)
+//
+//   schedule:
+//     - at: 10m
+//       action: checkpoint_storm      # background burst of large writes
+//       objects: 64
+//       size_bytes: 268435456
+//     - at: 20m
+//       action: inject_latency        # added to every storage read
+//       latency_ms: 100
+//     - at: 25m
+//       action: clear_latency
+//     - at: 30m
+//       action: stop
+//
+// Other actions: pause, resume, set_target_throughput (throughput_mib_s),
+// failover_start / failover_end (window marks for failover.rs) and mark
+// (annotation only, with `label`). Offsets are relative to the start of
+// the training phase; every executed action lands on the results timeline.
+//
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::control::RunControl;
+use crate::dlio_compat::{DlioConfig, OverwriteConfig};
+pub use crate::dlio_compat::ScheduleEntry;
+use crate::overwrite::OverwriteRunner;
+
+/// Parse an offset such as "500ms", "90s", "10m", "1h" or a bare number of seconds
+pub fn parse_offset(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => (value[..i].trim(), &value[i..]),
+        None => (value, "s"),
+    };
+    let number: f64 = number.parse().with_context(|| format!("Invalid schedule offset '{}'", value))?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        other => anyhow::bail!("Unknown unit '{}' in schedule offset '{}' (use ms, s, m or h)", other, value),
+    };
+    if seconds < 0.0 {
+        anyhow::bail!("Schedule offset '{}' is negative", value);
+    }
+    Duration::try_from_secs_f64(seconds).with_context(|| format!("Schedule offset '{}' is out of range", value))
+}
+
+/// A validated action
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    Pause,
+    Resume,
+    SetTargetThroughput(f64),
+    InjectLatency(Duration),
+    ClearLatency,
+    CheckpointStorm { objects: usize, size_bytes: Option<usize> },
+    Mark,
+    Stop,
+}
+
+fn parse_action(entry: &ScheduleEntry) -> Result<Action> {
+    Ok(match entry.action.as_str() {
+        "pause" => Action::Pause,
+        "resume" => Action::Resume,
+        "set_target_throughput" => Action::SetTargetThroughput(
+            entry.throughput_mib_s.context("set_target_throughput needs throughput_mib_s")?,
+        ),
+        "inject_latency" => {
+            Action::InjectLatency(Duration::from_millis(entry.latency_ms.context("inject_latency needs latency_ms")?))
+        }
+        "clear_latency" => Action::ClearLatency,
+        "checkpoint_storm" => Action::CheckpointStorm { objects: entry.objects.unwrap_or(16).max(1), size_bytes: entry.size_bytes },
+        // Failover window marks are timeline annotations read by the failover report
+        "mark" | "failover_start" | "failover_end" => Action::Mark,
+        "stop" => Action::Stop,
+        other => anyhow::bail!(
+            "Unknown schedule action '{}' (expected pause, resume, set_target_throughput, inject_latency, \
+             clear_latency, checkpoint_storm, failover_start, failover_end, mark or stop)",
+            other
+        ),
+    })
+}
+
+/// The schedule, validated and sorted by offset
+pub struct Schedule {
+    steps: Vec<(Duration, Action, ScheduleEntry)>,
+}
+
+impl Schedule {
+    /// From the config's `schedule:` block; None when there is none
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let Some(entries) = config.schedule.as_ref().filter(|e| !e.is_empty()) else {
+            return Ok(None);
+        };
+        let mut steps = entries
+            .iter()
+            .map(|entry| {
+                let at = parse_offset(&entry.at)?;
+                let action = parse_action(entry).with_context(|| format!("Invalid schedule entry at {}", entry.at))?;
+                Ok((at, action, entry.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        steps.sort_by_key(|(at, _, _)| *at);
+        Ok(Some(Self { steps }))
+    }
+
+    /// Whether any action injects latency, which needs reads through a DataBackend
+    pub fn injects_latency(&self) -> bool {
+        self.steps.iter().any(|(_, action, _)| matches!(action, Action::InjectLatency(_)))
+    }
+
+    /// Run the schedule against `control` in the background; abort the handle when training ends
+    pub fn spawn(self, control: Arc<RunControl>, config: Arc<DlioConfig>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            for (at, action, entry) in self.steps {
+                tokio::time::sleep(at.saturating_sub(control.elapsed())).await;
+                info!("📅 Schedule at {}: {}", entry.at, entry.action);
+                execute(&control, &config, action, &entry);
+            }
+        })
+    }
+}
+
+fn execute(control: &Arc<RunControl>, config: &Arc<DlioConfig>, action: Action, entry: &ScheduleEntry) {
+    let detail = entry.label.clone();
+    match action {
+        Action::Pause => control.set_paused(true),
+        Action::Resume => control.set_paused(false),
+        Action::SetTargetThroughput(mib_s) => control.set_target_throughput(mib_s * crate::units::MIB),
+        Action::InjectLatency(latency) => control.set_injected_latency(latency),
+        Action::ClearLatency => control.set_injected_latency(Duration::ZERO),
+        Action::Mark => {}
+        Action::Stop => control.request_stop(),
+        Action::CheckpointStorm { objects, size_bytes } => {
+            // Writes run alongside training; completion is its own timeline event
+            let mut storm_config = (**config).clone();
+            storm_config.overwrite = Some(OverwriteConfig {
+                working_set: Some(objects),
+                object_size_bytes: size_bytes,
+                iterations: Some(1),
+                prefix: Some("checkpoint_storm".to_string()),
+            });
+            let control = Arc::clone(control);
+            tokio::spawn(async move {
+                let outcome = match OverwriteRunner::new(storm_config).run().await {
+                    Ok(report) => {
+                        let pass = &report.iterations[0];
+                        format!("{} objects, {:.1}s, p99 {:.1}ms", report.working_set, pass.elapsed_s, pass.p99_ms)
+                    }
+                    Err(e) => {
+                        warn!("Checkpoint storm failed: {:#}", e);
+                        format!("failed: {:#}", e)
+                    }
+                };
+                control.record("schedule", "checkpoint_storm_done", Some(outcome));
+            });
+        }
+    }
+    control.record("schedule", &entry.action, detail.or_else(|| describe(entry)));
+}
+
+fn describe(entry: &ScheduleEntry) -> Option<String> {
+    entry
+        .latency_ms
+        .map(|ms| format!("{} ms", ms))
+        .or_else(|| entry.throughput_mib_s.map(|t| format!("{} MiB/s", t)))
+        .or_else(|| entry.objects.map(|n| format!("{} objects", n)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_out_of_range_is_an_error() {
+        assert!(parse_offset("20000000000000000h").is_err());
+        assert!(parse_offset("1e20").is_err());
+        assert!(parse_offset("nan").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_parses_sorts_and_executes() {
+        assert_eq!(parse_offset("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_offset("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_offset("2").unwrap(), Duration::from_secs(2));
+        assert!(parse_offset("5 days").is_err());
+
+        let yaml = "dataset:\n  data_folder: /tmp/d\nreader: {}\nschedule:\n  \
+                    - {at: 60ms, action: stop}\n  \
+                    - {at: 20ms, action: inject_latency, latency_ms: 5}\n  \
+                    - {at: 40ms, action: mark, label: failover}\n";
+        let config = DlioConfig::from_yaml(yaml).unwrap();
+        let schedule = Schedule::from_config(&config).unwrap().unwrap();
+        assert_eq!(schedule.steps[0].1, Action::InjectLatency(Duration::from_millis(5)));
+        assert!(schedule.injects_latency());
+
+        let control = Arc::new(RunControl::default());
+        schedule.spawn(Arc::clone(&control), Arc::new(config)).await.unwrap();
+        assert!(control.stop_requested());
+        let events: Vec<(String, Option<String>)> = control.timeline().into_iter().map(|e| (e.action, e.detail)).collect();
+        assert_eq!(events[0], ("inject_latency".to_string(), Some("5 ms".to_string())));
+        assert_eq!(events[1], ("mark".to_string(), Some("failover".to_string())));
+        assert_eq!(events[2].0, "stop");
+
+        let bad = DlioConfig::from_yaml("dataset:\n  data_folder: /tmp/d\nreader: {}\nschedule:\n  - {at: 1s, action: explode}\n").unwrap();
+        assert!(Schedule::from_config(&bad).is_err());
+    }
+}
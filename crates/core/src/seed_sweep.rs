@@ -0,0 +1,288 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/seed_sweep.rs
+//
+// Shuffle seed sweep (`run --seed-sweep 1..10`)
+//
+// Throughput that moves from run to run comes either from the storage being
+// nondeterministic or from the workload being sensitive to the order samples
+// are read in. A sweep reruns the training phase once per shuffle seed and
+// changes nothing else: generated content, key prefixes and sample sizes keep
+// the seeds they would otherwise take from `reader.seed`, so every run reads
+// the same dataset. With `--repeats N` each seed runs N times, round-robin
+// (every seed once, then every seed again) so drift over the session such as
+// cache warm-up spreads across all seeds instead of favouring the later ones,
+// and the variance splits as in a one-way ANOVA:
+//
+//   within seed    spread of runs with an identical access order: storage noise
+//   between seeds  spread of the seed means beyond what that noise explains:
+//                  access-order sensitivity
+//
+// `access_order_share` is the between-seed component over the total. With one
+// run per seed the two cannot be separated: the share is omitted and the
+// spread of the seeds is an upper bound on the access-order effect.
+// Latencies are per-batch read times from the runs' timing details.
+//
+// A sweep needs shuffling enabled, since the seed is otherwise unused, and
+// reports every run rather than aggregating repeats, so `--aggregate median`
+// and `--discard-outliers` are rejected alongside it.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::dlio_compat::DlioConfig;
+use crate::repeats::{Aggregate, RepeatSettings};
+use crate::shuffle::ShuffleStrategy;
+use crate::suite::RunMetrics;
+
+/// Most seeds one sweep accepts
+pub const MAX_SWEEP_SEEDS: usize = 1000;
+
+/// Seeds from "1..10" (inclusive), "1..=10" or "3,7,42"
+pub fn parse_seeds(spec: &str) -> Result<Vec<u64>> {
+    let parse = |v: &str| v.trim().parse::<u64>().with_context(|| format!("Invalid seed '{}' in --seed-sweep {}", v, spec));
+    let seeds: Vec<u64> = match spec.split_once("..") {
+        Some((first, last)) => {
+            let (first, last) = (parse(first)?, parse(last.trim_start_matches('='))?);
+            if last < first {
+                anyhow::bail!("--seed-sweep {}: the range is empty", spec);
+            }
+            if last - first >= MAX_SWEEP_SEEDS as u64 {
+                anyhow::bail!("--seed-sweep {}: more than {} seeds", spec, MAX_SWEEP_SEEDS);
+            }
+            (first..=last).collect()
+        }
+        None => spec.split(',').map(parse).collect::<Result<_>>()?,
+    };
+    if seeds.len() < 2 {
+        anyhow::bail!("--seed-sweep {}: a sweep needs at least two seeds", spec);
+    }
+    Ok(seeds)
+}
+
+/// Reject a sweep whose seeds could not change anything, or whose repeat options it would ignore
+pub fn validate(config: &DlioConfig, repeat: &RepeatSettings) -> Result<()> {
+    if ShuffleStrategy::from_config(config)? == ShuffleStrategy::None {
+        anyhow::bail!("--seed-sweep varies the shuffle seed, but shuffling is off: set reader.shuffle: true");
+    }
+    if repeat.aggregate != Aggregate::Mean || repeat.discard_outliers {
+        anyhow::bail!("--seed-sweep reports every run; --aggregate median and --discard-outliers do not apply to it");
+    }
+    Ok(())
+}
+
+/// Seed and 1-based repetition of run `index` of a sweep, going round-robin over the seeds
+pub fn run_seed(seeds: &[u64], index: usize) -> (u64, u32) {
+    (seeds[index % seeds.len()], (index / seeds.len()) as u32 + 1)
+}
+
+/// Nearest-rank percentile of unsorted values
+fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+/// One training run of the sweep
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedRun {
+    pub seed: u64,
+    /// 1-based repetition of this seed
+    pub repetition: u32,
+    #[serde(flatten)]
+    pub metrics: RunMetrics,
+    pub read_p50_ms: Option<f64>,
+    pub read_p99_ms: Option<f64>,
+}
+
+impl SeedRun {
+    pub fn from_results(seed: u64, repetition: u32, results: &Value) -> Self {
+        let reads: Vec<f64> = results["timing_details"]["read_times_ms"]
+            .as_array()
+            .map(|times| times.iter().filter_map(Value::as_f64).collect())
+            .unwrap_or_default();
+        Self {
+            seed,
+            repetition,
+            metrics: RunMetrics::from_results(results),
+            read_p50_ms: percentile(&reads, 50.0),
+            read_p99_ms: percentile(&reads, 99.0),
+        }
+    }
+}
+
+/// One metric's variance across the sweep
+#[derive(Debug, Clone, Serialize)]
+pub struct VarianceSplit {
+    pub mean: f64,
+    /// Over every run
+    pub stddev: f64,
+    pub cv: f64,
+    /// Lowest and highest per-seed mean
+    pub seed_mean_min: f64,
+    pub seed_mean_max: f64,
+    pub seed_mean_stddev: f64,
+    /// Pooled spread of repeats of the same seed (with --repeats)
+    pub within_seed_stddev: Option<f64>,
+    /// Share of the variance attributable to access order (with --repeats)
+    pub access_order_share: Option<f64>,
+}
+
+fn sample_variance(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+impl VarianceSplit {
+    /// From each seed's values; None when no run reported the metric
+    pub fn new(groups: &[Vec<f64>]) -> Option<Self> {
+        let groups: Vec<&Vec<f64>> = groups.iter().filter(|g| !g.is_empty()).collect();
+        let all: Vec<f64> = groups.iter().flat_map(|g| g.iter().copied()).collect();
+        if all.is_empty() {
+            return None;
+        }
+        let mean = all.iter().sum::<f64>() / all.len() as f64;
+        let means: Vec<f64> = groups.iter().map(|g| g.iter().sum::<f64>() / g.len() as f64).collect();
+        let stddev = sample_variance(&all).sqrt();
+
+        // Pooled within-seed variance needs some seed with more than one run
+        let within_df = all.len() - groups.len();
+        let within = (within_df > 0).then(|| {
+            groups
+                .iter()
+                .zip(&means)
+                .map(|(g, m)| g.iter().map(|v| (v - m).powi(2)).sum::<f64>())
+                .sum::<f64>()
+                / within_df as f64
+        });
+        let runs_per_seed = all.len() as f64 / groups.len() as f64;
+        let access_order_share = within.map(|within| {
+            let between = (sample_variance(&means) - within / runs_per_seed).max(0.0);
+            if between + within > 0.0 { between / (between + within) } else { 0.0 }
+        });
+        Some(Self {
+            mean,
+            stddev,
+            cv: if mean != 0.0 { stddev / mean.abs() } else { 0.0 },
+            seed_mean_min: means.iter().copied().fold(f64::INFINITY, f64::min),
+            seed_mean_max: means.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            seed_mean_stddev: sample_variance(&means).sqrt(),
+            within_seed_stddev: within.map(f64::sqrt),
+            access_order_share,
+        })
+    }
+}
+
+/// `seed_sweep` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedSweepReport {
+    pub seeds: Vec<u64>,
+    pub runs_per_seed: u32,
+    pub runs: Vec<SeedRun>,
+    pub throughput_gib_s: Option<VarianceSplit>,
+    pub samples_per_second: Option<VarianceSplit>,
+    pub au_percent: Option<VarianceSplit>,
+    pub read_p50_ms: Option<VarianceSplit>,
+    pub read_p99_ms: Option<VarianceSplit>,
+}
+
+impl SeedSweepReport {
+    pub fn new(seeds: &[u64], runs_per_seed: u32, runs: Vec<SeedRun>) -> Self {
+        let split = |field: fn(&SeedRun) -> Option<f64>| {
+            let groups: Vec<Vec<f64>> = seeds
+                .iter()
+                .map(|seed| runs.iter().filter(|r| r.seed == *seed).filter_map(field).collect())
+                .collect();
+            VarianceSplit::new(&groups)
+        };
+        Self {
+            seeds: seeds.to_vec(),
+            runs_per_seed,
+            throughput_gib_s: split(|r| r.metrics.throughput_gib_s),
+            samples_per_second: split(|r| r.metrics.samples_per_second),
+            au_percent: split(|r| r.metrics.au_percent),
+            read_p50_ms: split(|r| r.read_p50_ms),
+            read_p99_ms: split(|r| r.read_p99_ms),
+            runs,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n=== Seed Sweep ({} seeds x {} runs) ===", self.seeds.len(), self.runs_per_seed);
+        let rows = [
+            ("Throughput (GiB/s)", &self.throughput_gib_s),
+            ("Samples/s", &self.samples_per_second),
+            ("AU %", &self.au_percent),
+            ("Read p50 (ms)", &self.read_p50_ms),
+            ("Read p99 (ms)", &self.read_p99_ms),
+        ];
+        for (label, split) in rows {
+            if let Some(s) = split {
+                let order = match s.access_order_share {
+                    Some(share) => format!("  access order {:.0}% of variance", share * 100.0),
+                    None => String::new(),
+                };
+                println!("{:<20} {:>12.3} ± {:<10.3} (cv {:.1}%, seed means {:.3}-{:.3}){}",
+                         label, s.mean, s.stddev, s.cv * 100.0, s.seed_mean_min, s.seed_mean_max, order);
+            }
+        }
+        if self.runs_per_seed < 2 {
+            println!("One run per seed: add --repeats to separate storage noise from access order");
+        }
+        println!("==============================\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_sweep_splits_access_order_from_storage_noise() {
+        assert_eq!(parse_seeds("1..4").unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(parse_seeds("1..=3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_seeds("7, 42").unwrap(), vec![7, 42]);
+        assert!(parse_seeds("5..1").is_err() && parse_seeds("3").is_err() && parse_seeds("a..b").is_err());
+
+        // Two seeds 2 GiB/s apart, each repeat within 0.1 GiB/s of its seed's mean
+        let run = |seed: u64, repetition: u32, gib_s: f64| {
+            let results = serde_json::json!({
+                "metrics": { "storage_throughput_gib_s": gib_s },
+                "timing_details": { "read_times_ms": [10, 20, 30, 40] },
+            });
+            SeedRun::from_results(seed, repetition, &results)
+        };
+        let runs = vec![run(1, 1, 4.9), run(1, 2, 5.1), run(2, 1, 6.9), run(2, 2, 7.1)];
+        let report = SeedSweepReport::new(&[1, 2], 2, runs);
+        let throughput = report.throughput_gib_s.unwrap();
+        assert_eq!((throughput.seed_mean_min, throughput.seed_mean_max), (5.0, 7.0));
+        assert!((throughput.within_seed_stddev.unwrap() - 0.1414).abs() < 1e-3);
+        assert!(throughput.access_order_share.unwrap() > 0.98);
+        assert_eq!((report.runs[0].read_p50_ms, report.runs[0].read_p99_ms), (Some(20.0), Some(40.0)));
+        // Identical latencies everywhere: nothing to attribute
+        assert_eq!(report.read_p50_ms.unwrap().access_order_share, Some(0.0));
+
+        // One run per seed cannot separate the two
+        let single = SeedSweepReport::new(&[1, 2], 1, vec![run(1, 1, 5.0), run(2, 1, 7.0)]);
+        assert!(single.throughput_gib_s.unwrap().access_order_share.is_none());
+    }
+
+    #[test]
+    fn test_sweep_runs_round_robin_and_needs_shuffling() {
+        let order: Vec<(u64, u32)> = (0..6).map(|i| run_seed(&[3, 7, 42], i)).collect();
+        assert_eq!(order, [(3, 1), (7, 1), (42, 1), (3, 2), (7, 2), (42, 2)]);
+
+        let config = |reader: &str| DlioConfig::from_yaml(&format!("dataset:\n  data_folder: /tmp/d\nreader: {}\n", reader)).unwrap();
+        let shuffled = config("{shuffle: true}");
+        assert!(validate(&shuffled, &RepeatSettings::default()).is_ok());
+        assert!(validate(&config("{}"), &RepeatSettings::default()).is_err());
+        assert!(validate(&shuffled, &RepeatSettings { discard_outliers: true, ..Default::default() }).is_err());
+        assert!(validate(&shuffled, &RepeatSettings { aggregate: Aggregate::Median, ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/shuffle.rs
+//
+// Epoch shuffling strategies
+//
+// `permutation` reorders the whole file list every epoch and shuffles the
+// samples within each file; it needs memory proportional to the dataset.
+// `buffer` reads files in order and passes samples through a fixed-size
+// shuffle buffer (tf.data `shuffle(buffer_size)` semantics), so memory is
+// bounded by the buffer no matter how many samples the dataset holds.
+// `sample` (DLIO's `sample_shuffle`) permutes every (file, sample) pair of
+// the dataset and cuts the permutation into batches; each batch fetches the
+// files its samples live in, so a file is read once per batch that draws on
+// it and the epoch records how many reads and bytes that costs.
+//
+// Every epoch's seed and order digests are recorded so runs can be audited
+// for determinism. Seeds and the generator come from `rng`.
+//
+// The access-order digest identifies items by key rather than position, so it
+// can be compared across runs, ranks and tools (dl-driver vs DLIO): FNV-1a 64
+// over the UTF-8 key of each item read, relative to the data folder, each
+// followed by a newline, printed as 16 hex digits. In Python:
+//
+//   h = 0xcbf29ce484222325
+//   for key in keys:
+//       for b in key.encode() + b"\n":
+//           h = ((h ^ b) * 0x100000001b3) & 0xffffffffffffffff
+//
+
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::dlio_compat::DlioConfig;
+use crate::rng::{self, Prng, RngReport, SeedTree};
+
+/// Default shuffle buffer size, in samples
+pub const DEFAULT_SHUFFLE_BUFFER: usize = 1024;
+
+/// How samples are shuffled each epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShuffleStrategy {
+    None,
+    Permutation,
+    Buffer { size: usize },
+    Sample,
+}
+
+impl ShuffleStrategy {
+    /// From `reader.shuffle`, `reader.shuffle_strategy` and `reader.shuffle_size`;
+    /// DLIO's `reader.sample_shuffle` picks `sample` when no strategy is named
+    pub fn from_config(config: &DlioConfig) -> Result<Self> {
+        let reader = &config.reader;
+        if !reader.shuffle.unwrap_or(false) {
+            return Ok(Self::None);
+        }
+        let sample_shuffle = matches!(reader.sample_shuffle.as_deref(), Some("seed" | "random"));
+        let default = if sample_shuffle { "sample" } else { "permutation" };
+        match reader.shuffle_strategy.as_deref().unwrap_or(default) {
+            "permutation" => Ok(Self::Permutation),
+            "buffer" => Ok(Self::Buffer { size: reader.shuffle_size.unwrap_or(DEFAULT_SHUFFLE_BUFFER).max(1) }),
+            "sample" => Ok(Self::Sample),
+            other => anyhow::bail!("Unknown reader.shuffle_strategy '{}' (expected 'permutation', 'buffer' or 'sample')", other),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Permutation => "permutation",
+            Self::Buffer { .. } => "buffer",
+            Self::Sample => "sample",
+        }
+    }
+}
+
+/// Full Fisher-Yates permutation of `items`
+pub fn permute<T>(items: &mut [T], seed: u64) {
+    items.shuffle(&mut rng::prng(seed));
+}
+
+/// One batch of a sample-shuffled epoch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedBatch {
+    /// Files to fetch, by dataset index, in first-use order
+    pub files: Vec<usize>,
+    /// (file index, sample) pairs in delivery order
+    pub samples: Vec<(usize, usize)>,
+}
+
+/// Sample-shuffled batches for an epoch: every sample of `files` files in one
+/// seeded permutation, cut into batches of `batch_size`
+pub fn plan_sample_batches(files: usize, samples_per_file: usize, batch_size: usize, drop_last: bool, seed: u64) -> Vec<PlannedBatch> {
+    let samples_per_file = samples_per_file.max(1);
+    let mut samples: Vec<(usize, usize)> = (0..files).flat_map(|f| (0..samples_per_file).map(move |s| (f, s))).collect();
+    permute(&mut samples, seed);
+    samples
+        .chunks(batch_size.max(1))
+        .filter(|chunk| !drop_last || chunk.len() == batch_size.max(1))
+        .map(|chunk| {
+            let mut files: Vec<usize> = Vec::new();
+            for &(file, _) in chunk {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+            PlannedBatch { files, samples: chunk.to_vec() }
+        })
+        .collect()
+}
+
+/// tf.data-style shuffle buffer
+pub struct ShuffleBuffer<T> {
+    items: Vec<T>,
+    size: usize,
+    rng: Prng,
+}
+
+impl<T> ShuffleBuffer<T> {
+    pub fn new(size: usize, seed: u64) -> Self {
+        Self { items: Vec::with_capacity(size.max(1)), size: size.max(1), rng: rng::prng(seed) }
+    }
+
+    /// Add an item; once the buffer is full, a uniformly chosen buffered item is returned in its place
+    pub fn push(&mut self, item: T) -> Option<T> {
+        if self.items.len() < self.size {
+            self.items.push(item);
+            return None;
+        }
+        let slot = self.rng.random_range(0..self.size);
+        Some(std::mem::replace(&mut self.items[slot], item))
+    }
+
+    /// Remaining items in random order, at the end of an epoch
+    pub fn drain(&mut self) -> Vec<T> {
+        self.items.shuffle(&mut self.rng);
+        std::mem::take(&mut self.items)
+    }
+}
+
+/// Running FNV-1a digest of an ordering
+#[derive(Debug, Clone, Copy)]
+pub struct OrderDigest(u64);
+
+impl Default for OrderDigest {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl OrderDigest {
+    pub fn add(&mut self, value: u64) {
+        self.add_bytes(&value.to_le_bytes());
+    }
+
+    fn add_bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub fn hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// Order-sensitive digest of the items read in one epoch, by key
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessOrder {
+    digest: OrderDigest,
+    items: u64,
+}
+
+impl AccessOrder {
+    pub fn record(&mut self, key: &str) {
+        self.digest.add_bytes(key.as_bytes());
+        self.digest.add_bytes(b"\n");
+        self.items += 1;
+    }
+
+    pub fn items(&self) -> u64 {
+        self.items
+    }
+
+    pub fn hex(&self) -> String {
+        self.digest.hex()
+    }
+}
+
+/// Keys of an epoch's first reads kept verbatim (e.g. in the MLPerf report)
+pub const ACCESS_ORDER_SAMPLE: usize = 10;
+
+/// Key of an object relative to the data folder (the whole URI outside it)
+pub fn access_key<'a>(uri: &'a str, data_folder: &str) -> &'a str {
+    uri.strip_prefix(data_folder.trim_end_matches('/'))
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(uri)
+}
+
+/// Shuffle record for one epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochShuffle {
+    pub epoch: u32,
+    pub seed: u64,
+    /// Digest of the file load order, when dl-driver chose it
+    pub file_order_digest: Option<String>,
+    /// Digest of the (file position, sample) order delivered to training
+    pub sample_order_digest: String,
+    /// Digest of the keys read, in order, when dl-driver issued the reads
+    pub access_order_digest: Option<String>,
+    /// Items covered by the access-order digest
+    pub access_order_items: Option<u64>,
+    /// First ACCESS_ORDER_SAMPLE keys read, when dl-driver issued the reads
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub access_order_sample: Vec<String>,
+    /// File reads and bytes fetched under sample shuffling (a file is read per batch that uses it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_reads: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_fetched: Option<u64>,
+}
+
+/// Shuffle strategy and per-epoch orderings, for determinism audits
+#[derive(Debug, Clone, Serialize)]
+pub struct ShuffleReport {
+    pub strategy: String,
+    pub buffer_size: Option<usize>,
+    pub seed: u64,
+    /// Whether the seed came from `reader.seed` (false: drawn at random for this run)
+    pub seed_configured: bool,
+    /// Generator and seed hierarchy
+    pub rng: RngReport,
+    pub epochs: Vec<EpochShuffle>,
+}
+
+impl ShuffleReport {
+    pub fn new(strategy: ShuffleStrategy, seeds: &SeedTree, seed_configured: bool) -> Self {
+        Self {
+            strategy: strategy.name().to_string(),
+            buffer_size: match strategy {
+                ShuffleStrategy::Buffer { size } => Some(size),
+                _ => None,
+            },
+            seed: seeds.run_seed,
+            seed_configured,
+            rng: seeds.report(seed_configured),
+            epochs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permutation_known_answer() {
+        // Pinned order: a failure here means seeded runs no longer visit files in the same order
+        let mut items: Vec<u32> = (0..10).collect();
+        permute(&mut items, 42);
+        assert_eq!(items, [7, 1, 5, 4, 6, 9, 3, 8, 2, 0]);
+    }
+
+    #[test]
+    fn test_shuffle_buffer_is_seeded_and_complete() {
+        let run = |seed| {
+            let mut buffer = ShuffleBuffer::new(8, seed);
+            let mut out: Vec<u32> = (0..100).filter_map(|i| buffer.push(i)).collect();
+            out.extend(buffer.drain());
+            out
+        };
+        let a = run(7);
+        assert_eq!(a, run(7));
+        assert_ne!(a, run(8));
+        assert_ne!(a, (0..100).collect::<Vec<_>>());
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+        // An item can only leave the buffer after `size` later items arrived
+        assert!(a.iter().enumerate().all(|(pos, &item)| item as usize <= pos + 8));
+    }
+
+    #[test]
+    fn test_sample_shuffle_plan_covers_every_sample_once() {
+        // 4 files x 3 samples in batches of 5: 5 + 5 + 2, or the trailing 2 dropped
+        let plan = plan_sample_batches(4, 3, 5, false, 11);
+        assert_eq!(plan.iter().map(|b| b.samples.len()).collect::<Vec<_>>(), vec![5, 5, 2]);
+        let mut all: Vec<(usize, usize)> = plan.iter().flat_map(|b| b.samples.clone()).collect();
+        assert_ne!(all, (0..4).flat_map(|f| (0..3).map(move |s| (f, s))).collect::<Vec<_>>());
+        all.sort();
+        assert_eq!(all, (0..4).flat_map(|f| (0..3).map(move |s| (f, s))).collect::<Vec<_>>());
+        // Each batch fetches exactly the files its samples come from, once
+        for batch in &plan {
+            let mut files: Vec<usize> = batch.samples.iter().map(|s| s.0).collect();
+            files.sort();
+            files.dedup();
+            let mut planned = batch.files.clone();
+            planned.sort();
+            assert_eq!(planned, files);
+        }
+        assert_eq!(plan, plan_sample_batches(4, 3, 5, false, 11));
+        assert_ne!(plan, plan_sample_batches(4, 3, 5, false, 12));
+        assert_eq!(plan_sample_batches(4, 3, 5, true, 11).len(), 2);
+
+        let yaml = |reader: &str| DlioConfig::from_yaml(&format!("dataset:\n  data_folder: /tmp/d\nreader:\n{}", reader)).unwrap();
+        let strategy = |reader: &str| ShuffleStrategy::from_config(&yaml(reader)).unwrap();
+        assert_eq!(strategy("  shuffle: true\n  sample_shuffle: seed\n"), ShuffleStrategy::Sample);
+        assert_eq!(strategy("  shuffle: true\n  shuffle_strategy: sample\n"), ShuffleStrategy::Sample);
+        assert_eq!(strategy("  shuffle: true\n  sample_shuffle: 'off'\n"), ShuffleStrategy::Permutation);
+        assert_eq!(strategy("  sample_shuffle: seed\n"), ShuffleStrategy::None);
+    }
+
+    #[test]
+    fn test_access_order_digest_is_key_based_and_order_sensitive() {
+        let digest = |keys: &[&str]| {
+            let mut order = AccessOrder::default();
+            keys.iter().for_each(|k| order.record(access_key(k, "s3://bucket/data/")));
+            (order.hex(), order.items())
+        };
+        let a = digest(&["s3://bucket/data/train/img_0.npz", "s3://bucket/data/train/img_1.npz"]);
+        assert_eq!(a.1, 2);
+        // Same keys under another data folder match; a different order does not
+        assert_eq!(a.0, digest(&["train/img_0.npz", "train/img_1.npz"]).0);
+        assert_ne!(a.0, digest(&["train/img_1.npz", "train/img_0.npz"]).0);
+        // Newline separators keep key boundaries significant
+        assert_ne!(digest(&["ab", "c"]).0, digest(&["a", "bc"]).0);
+        // Published algorithm: FNV-1a 64 over "a\n"
+        assert_eq!(digest(&["a"]).0, "089bdc07b544e7b2");
+    }
+}
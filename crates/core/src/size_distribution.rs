@@ -0,0 +1,203 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/size_distribution.rs
+//
+// Variable record sizes for generated datasets (`dataset.record_length_bytes_stdev`)
+//
+// As in DLIO, the record size is drawn once per file around
+// `record_length_bytes`, so every record of a file has the same size and
+// files differ. `dataset.record_length_distribution` picks the shape:
+//
+//   normal      (default, DLIO) mean ± stdev, clamped to at least one byte
+//   lognormal   same mean and stdev, right-skewed and always positive; closer
+//               to real image and document datasets
+//
+// Sizes are drawn from a SplitMix64 stream keyed by the seed (`reader.seed`,
+// else 0) and the file index, so a seed names the same dataset whatever
+// order or concurrency files are written in. Generation reports the sizes it
+// wrote as a histogram.
+//
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::dlio_compat::DlioConfig;
+use crate::rng::{splitmix64, stable_hash, standard_normal};
+
+/// Equal-width buckets of the generation summary's size histogram
+pub const HISTOGRAM_BUCKETS: usize = 10;
+
+/// Shape of the record-size distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeDistribution {
+    Normal,
+    LogNormal,
+}
+
+impl SizeDistribution {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::LogNormal => "lognormal",
+        }
+    }
+}
+
+/// Draws each file's record size
+#[derive(Debug, Clone)]
+pub struct RecordSizeSampler {
+    mean: usize,
+    stdev: usize,
+    distribution: SizeDistribution,
+    seed: u64,
+}
+
+impl RecordSizeSampler {
+    /// None when `record_length_bytes_stdev` is absent or 0 (fixed-size records)
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let distribution = match config.dataset.record_length_distribution.as_deref().unwrap_or("normal") {
+            "normal" => SizeDistribution::Normal,
+            "lognormal" | "log_normal" => SizeDistribution::LogNormal,
+            other => anyhow::bail!("Unknown dataset.record_length_distribution '{}' (expected 'normal' or 'lognormal')", other),
+        };
+        let stdev = config.dataset.record_length_bytes_stdev.unwrap_or(0);
+        if stdev == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            mean: config.dataset.record_length_bytes.unwrap_or(1024),
+            stdev,
+            distribution,
+            seed: config.reader.seed.unwrap_or(0),
+        }))
+    }
+
+    pub fn distribution(&self) -> SizeDistribution {
+        self.distribution
+    }
+
+    /// Record size of the file at `file_index`
+    pub fn record_size(&self, file_index: u64) -> usize {
+        let (mean, stdev) = (self.mean as f64, self.stdev as f64);
+        let mut key = file_index;
+        let mut state = self.seed ^ stable_hash(b"record_length_bytes") ^ splitmix64(&mut key);
+        let z = standard_normal(|| (splitmix64(&mut state) >> 11) as f64 / (1u64 << 53) as f64);
+        let size = match self.distribution {
+            SizeDistribution::Normal => mean + stdev * z,
+            SizeDistribution::LogNormal => {
+                // Parameters of the underlying normal that give this mean and stdev
+                let sigma2 = (1.0 + (stdev / mean.max(1.0)).powi(2)).ln();
+                (mean.max(1.0).ln() - sigma2 / 2.0 + sigma2.sqrt() * z).exp()
+            }
+        };
+        size.round().max(1.0) as usize
+    }
+}
+
+/// One histogram bucket: record sizes in [from_bytes, to_bytes)
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeBucket {
+    pub from_bytes: usize,
+    pub to_bytes: usize,
+    pub files: usize,
+}
+
+/// Record sizes written by generation
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeHistogram {
+    pub distribution: SizeDistribution,
+    pub files: usize,
+    pub mean_bytes: f64,
+    pub stdev_bytes: f64,
+    pub min_bytes: usize,
+    pub max_bytes: usize,
+    pub buckets: Vec<SizeBucket>,
+}
+
+impl SizeHistogram {
+    /// Histogram of per-file record sizes, in `HISTOGRAM_BUCKETS` equal-width buckets
+    pub fn new(distribution: SizeDistribution, sizes: &[usize]) -> Self {
+        let (min, max) = (sizes.iter().copied().min().unwrap_or(0), sizes.iter().copied().max().unwrap_or(0));
+        let n = sizes.len().max(1) as f64;
+        let mean = sizes.iter().map(|&s| s as f64).sum::<f64>() / n;
+        let variance = sizes.iter().map(|&s| (s as f64 - mean).powi(2)).sum::<f64>() / n;
+
+        let width = (max - min) / HISTOGRAM_BUCKETS + 1;
+        let mut buckets: Vec<SizeBucket> = (0..HISTOGRAM_BUCKETS)
+            .map(|i| SizeBucket { from_bytes: min + i * width, to_bytes: min + (i + 1) * width, files: 0 })
+            .collect();
+        for &size in sizes {
+            buckets[(size - min) / width].files += 1;
+        }
+        // Trailing empty buckets (all of them past the first when every size is equal) say nothing
+        while buckets.len() > 1 && buckets.last().is_some_and(|b| b.files == 0) {
+            buckets.pop();
+        }
+        Self {
+            distribution,
+            files: sizes.len(),
+            mean_bytes: mean,
+            stdev_bytes: variance.sqrt(),
+            min_bytes: min,
+            max_bytes: max,
+            buckets,
+        }
+    }
+
+    /// One text line per bucket with a bar scaled to the fullest bucket
+    pub fn lines(&self) -> Vec<String> {
+        let fullest = self.buckets.iter().map(|b| b.files).max().unwrap_or(0).max(1);
+        self.buckets
+            .iter()
+            .map(|b| {
+                format!("{:>10} - {:<10} {:>8} {}", crate::units::fmt_bytes(b.from_bytes as u64),
+                        crate::units::fmt_bytes(b.to_bytes as u64), b.files, "█".repeat(b.files * 30 / fullest))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(extra: &str) -> DlioConfig {
+        DlioConfig::from_yaml(&format!(
+            "dataset:\n  data_folder: /data\n  record_length_bytes: 100000\n{}reader:\n  seed: 7\n", extra
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sampled_sizes_match_distribution_and_seed() {
+        assert!(RecordSizeSampler::from_config(&config("")).unwrap().is_none());
+        assert!(RecordSizeSampler::from_config(&config("  record_length_bytes_stdev: 0\n")).unwrap().is_none());
+        assert!(RecordSizeSampler::from_config(&config("  record_length_bytes_stdev: 5\n  record_length_distribution: pareto\n")).is_err());
+
+        for (extra, distribution) in [
+            ("  record_length_bytes_stdev: 20000\n", SizeDistribution::Normal),
+            ("  record_length_bytes_stdev: 20000\n  record_length_distribution: lognormal\n", SizeDistribution::LogNormal),
+        ] {
+            let sampler = RecordSizeSampler::from_config(&config(extra)).unwrap().unwrap();
+            assert_eq!(sampler.distribution(), distribution);
+            let sizes: Vec<usize> = (0..20_000).map(|i| sampler.record_size(i)).collect();
+            let histogram = SizeHistogram::new(distribution, &sizes);
+            assert!((histogram.mean_bytes - 100_000.0).abs() < 1_000.0, "{:?} mean {}", distribution, histogram.mean_bytes);
+            assert!((histogram.stdev_bytes - 20_000.0).abs() < 1_000.0, "{:?} stdev {}", distribution, histogram.stdev_bytes);
+            assert_eq!(histogram.buckets.iter().map(|b| b.files).sum::<usize>(), 20_000);
+            assert_eq!(histogram.lines().len(), histogram.buckets.len());
+            // Same seed and file, same size, whatever order files are drawn in
+            assert_eq!(sampler.record_size(1234), sizes[1234]);
+        }
+
+        // Lognormal is right-skewed: the median sits below the mean
+        let sampler = RecordSizeSampler::from_config(&config("  record_length_bytes_stdev: 50000\n  record_length_distribution: lognormal\n")).unwrap().unwrap();
+        let mut sizes: Vec<usize> = (0..10_000).map(|i| sampler.record_size(i)).collect();
+        sizes.sort_unstable();
+        assert!(sizes[5_000] < 95_000 && sizes[0] >= 1);
+
+        let fixed = SizeHistogram::new(SizeDistribution::Normal, &[64; 5]);
+        assert_eq!((fixed.buckets.len(), fixed.buckets[0].files, fixed.stdev_bytes), (1, 5, 0.0));
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/startup.rs
+//
+// Time to first batch ("startup" in the results)
+//
+// Elastic training restarts and rescales jobs often, so how long a job takes
+// to deliver its first batch matters next to its steady-state throughput.
+// The time from process start to the first batch handed to the training loop
+// is split into:
+//
+//   config_parse    process start to the config parsed (includes runtime init)
+//   dataset_list    dataset listing and backend setup (the "list" phase)
+//   loader_warmup   first batch request to the first batch delivered
+//   other           the rest: data generation, rank coordination, endpoint
+//                   probes, plugin setup
+//
+// The CLI and the workload runner record marks on the shared PhaseTimes; each
+// mark keeps its first time, so --repeats report the first run's startup.
+//
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::Instant;
+use tracing::info;
+
+use crate::phases::PhaseTimes;
+
+/// Config file read and parsed
+pub const CONFIG_PARSED: &str = "config_parsed";
+/// Dataset listing / backend setup started
+pub const LIST_START: &str = "list_start";
+/// First batch requested (listing done)
+pub const TRAIN_START: &str = "train_start";
+/// First batch delivered to the training loop
+pub const FIRST_BATCH: &str = "first_batch";
+
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+
+/// When the process started; call first thing in main (later calls return the first time)
+pub fn process_start() -> Instant {
+    *PROCESS_START.get_or_init(Instant::now)
+}
+
+/// Startup section of the results, in seconds
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StartupReport {
+    pub time_to_first_batch_s: f64,
+    pub config_parse_s: f64,
+    pub dataset_list_s: f64,
+    pub loader_warmup_s: f64,
+    pub other_s: f64,
+}
+
+impl StartupReport {
+    /// From the marks recorded on `phases`; None until a first batch was delivered
+    pub fn from_marks(process_start: Instant, phases: &PhaseTimes) -> Option<Self> {
+        let first_batch = phases.mark_time(FIRST_BATCH)?;
+        let between = |from: Option<Instant>, to: Option<Instant>| match (from, to) {
+            (Some(from), Some(to)) => to.saturating_duration_since(from).as_secs_f64(),
+            _ => 0.0,
+        };
+        let total = between(Some(process_start), Some(first_batch));
+        let config_parse = between(Some(process_start), phases.mark_time(CONFIG_PARSED));
+        let dataset_list = between(phases.mark_time(LIST_START), phases.mark_time(TRAIN_START));
+        let loader_warmup = between(phases.mark_time(TRAIN_START), Some(first_batch));
+        let report = Self {
+            time_to_first_batch_s: total,
+            config_parse_s: config_parse,
+            dataset_list_s: dataset_list,
+            loader_warmup_s: loader_warmup,
+            other_s: (total - config_parse - dataset_list - loader_warmup).max(0.0),
+        };
+        info!("🚦 Time to first batch: {:.3}s (config {:.3}s, listing {:.3}s, loader warmup {:.3}s, other {:.3}s)",
+              report.time_to_first_batch_s, report.config_parse_s, report.dataset_list_s,
+              report.loader_warmup_s, report.other_s);
+        Some(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_startup_breakdown_from_marks() {
+        let start = Instant::now();
+        let phases = PhaseTimes::new();
+        assert!(StartupReport::from_marks(start, &phases).is_none());
+
+        let ms = |n| start + Duration::from_millis(n);
+        phases.mark_at(CONFIG_PARSED, ms(100));
+        phases.mark_at(LIST_START, ms(400));
+        phases.mark_at(TRAIN_START, ms(1400));
+        phases.mark_at(FIRST_BATCH, ms(1900));
+        // Later marks (e.g. from a repeat) keep the first time
+        phases.mark_at(FIRST_BATCH, ms(9000));
+
+        let report = StartupReport::from_marks(start, &phases).unwrap();
+        let rounded = |s: f64| (s * 1000.0).round() / 1000.0;
+        assert_eq!(rounded(report.time_to_first_batch_s), 1.9);
+        assert_eq!(
+            [report.config_parse_s, report.dataset_list_s, report.loader_warmup_s, report.other_s].map(rounded),
+            [0.1, 1.0, 0.5, 0.3]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/striping.rs
+//
+// Lustre/GPFS striping hints for generation and stripe reporting
+//
+// Lustre layouts are set and queried through the `lfs` utility, which wraps the
+// LL_IOC_LOV_SETSTRIPE/GETSTRIPE ioctls, so no native Lustre headers are needed.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use tracing::{debug, info, warn};
+
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+use crate::mount_probe::MountInfo;
+
+/// Stripe configuration recorded for parallel filesystem targets
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StripeInfo {
+    pub fs_type: String,
+    pub stripe_count: Option<i64>,
+    pub stripe_size_bytes: Option<u64>,
+    /// Filesystem block size (the effective "stripe" unit on GPFS)
+    pub block_size_bytes: Option<u64>,
+    /// Whether configured hints were applied by this run
+    pub hints_applied: bool,
+}
+
+fn is_lustre(mount: &MountInfo) -> bool {
+    mount.fs_type == "lustre"
+}
+
+fn is_gpfs(mount: &MountInfo) -> bool {
+    mount.fs_type == "gpfs"
+}
+
+/// Apply configured striping hints to the data folder before generation
+///
+/// New files inherit the directory's default layout on Lustre. Failures are
+/// reported as warnings since hints never change correctness.
+pub fn apply_hints(config: &DlioConfig, mount: Option<&MountInfo>) -> Result<bool> {
+    let Some(hints) = config.storage.as_ref().and_then(|s| s.striping.as_ref()) else {
+        return Ok(false);
+    };
+    let Some(mount) = mount else {
+        debug!("Striping hints ignored: data folder is not a local mount");
+        return Ok(false);
+    };
+    if !is_lustre(mount) {
+        if hints.stripe_count.is_some() || hints.stripe_size_bytes.is_some() {
+            warn!("Striping hints are only applied on Lustre (target is {})", mount.fs_type);
+        }
+        return Ok(false);
+    }
+    let Some(path) = local_path_for_uri(&config.dataset.data_folder) else {
+        return Ok(false);
+    };
+
+    std::fs::create_dir_all(&path)
+        .with_context(|| format!("Failed to create data folder {:?}", path))?;
+
+    let mut cmd = Command::new("lfs");
+    cmd.arg("setstripe");
+    if let Some(count) = hints.stripe_count {
+        cmd.arg("-c").arg(count.to_string());
+    }
+    if let Some(size) = hints.stripe_size_bytes {
+        cmd.arg("-S").arg(size.to_string());
+    }
+    cmd.arg(&path);
+
+    match cmd.output() {
+        Ok(out) if out.status.success() => {
+            info!("📐 Lustre striping set on {:?}: count={:?}, size={:?}",
+                  path, hints.stripe_count, hints.stripe_size_bytes);
+            Ok(true)
+        }
+        Ok(out) => {
+            warn!("⚠️  lfs setstripe failed on {:?}: {}", path, String::from_utf8_lossy(&out.stderr).trim());
+            Ok(false)
+        }
+        Err(e) => {
+            warn!("⚠️  Could not run lfs to apply striping hints: {}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Record the stripe configuration of the data folder on Lustre/GPFS targets
+pub fn probe(config: &DlioConfig, mount: Option<&MountInfo>, hints_applied: bool) -> Option<StripeInfo> {
+    let mount = mount?;
+    if !is_lustre(mount) && !is_gpfs(mount) {
+        return None;
+    }
+    let path = local_path_for_uri(&config.dataset.data_folder)?;
+
+    let mut info = StripeInfo {
+        fs_type: mount.fs_type.clone(),
+        block_size_bytes: block_size(&path),
+        hints_applied,
+        ..Default::default()
+    };
+
+    if is_lustre(mount) {
+        match Command::new("lfs").arg("getstripe").arg("-d").arg(&path).output() {
+            Ok(out) if out.status.success() => {
+                let text = String::from_utf8_lossy(&out.stdout);
+                info.stripe_count = parse_lfs_field(&text, "stripe_count").and_then(|v| v.parse().ok());
+                info.stripe_size_bytes = parse_lfs_field(&text, "stripe_size").and_then(|v| v.parse().ok());
+            }
+            _ => debug!("lfs getstripe unavailable for {:?}", path),
+        }
+    }
+
+    Some(info)
+}
+
+/// Extract `key: value` from `lfs getstripe -d` output
+fn parse_lfs_field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let mut tokens = text.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token.trim_end_matches(':') == key && token.ends_with(':') {
+            return tokens.next();
+        }
+    }
+    None
+}
+
+#[cfg(unix)]
+fn block_size(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.blksize())
+}
+
+#[cfg(not(unix))]
+fn block_size(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lfs_getstripe() {
+        let out = "stripe_count:  4 stripe_size:   1048576 pattern:       raid0 stripe_offset: -1\n";
+        assert_eq!(parse_lfs_field(out, "stripe_count"), Some("4"));
+        assert_eq!(parse_lfs_field(out, "stripe_size"), Some("1048576"));
+        assert_eq!(parse_lfs_field(out, "stripe_offset"), Some("-1"));
+        assert_eq!(parse_lfs_field(out, "pool"), None);
+    }
+}
@@ -0,0 +1,282 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/suite.rs
+//
+// Benchmark suites: several configs run back to back with one combined report
+//
+// A suite is either a directory (every *.yaml / *.yml, run once each, in name
+// order) or a manifest file:
+//
+//   cleanup: true          # delete each generated dataset after its runs
+//   runs:
+//     - config: unet3d.yaml
+//       repetitions: 3
+//     - config: resnet50.yaml
+//       name: resnet
+//
+// Relative config paths resolve against the manifest's directory. Each config
+// is staged composed (its `defaults:` includes resolved in place) under a name
+// prefixed with its position, so entries sharing a stem don't overwrite each other.
+//
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::dlio_compat::{local_path_for_uri, DlioConfig};
+
+fn one() -> u32 {
+    1
+}
+
+/// One config in a suite
+#[derive(Debug, Clone, Deserialize)]
+pub struct SuiteEntry {
+    pub config: PathBuf,
+    /// Label in the report (default: config file stem)
+    pub name: Option<String>,
+    #[serde(default = "one")]
+    pub repetitions: u32,
+}
+
+impl SuiteEntry {
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.config.file_stem().map_or_else(|| self.config.display().to_string(), |s| s.to_string_lossy().to_string())
+        })
+    }
+}
+
+/// Configs to run and how to treat their datasets
+#[derive(Debug, Clone, Deserialize)]
+pub struct SuiteManifest {
+    #[serde(default)]
+    pub cleanup: bool,
+    pub runs: Vec<SuiteEntry>,
+}
+
+impl SuiteManifest {
+    /// Load a suite from a directory of configs or a manifest file
+    pub fn load(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            let mut configs: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("Failed to read suite directory {:?}", path))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"))
+                .collect();
+            configs.sort();
+            if configs.is_empty() {
+                anyhow::bail!("No YAML configs found in {:?}", path);
+            }
+            let runs = configs.into_iter().map(|config| SuiteEntry { config, name: None, repetitions: 1 }).collect();
+            return Ok(Self { cleanup: false, runs });
+        }
+
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read suite manifest {:?}", path))?;
+        let mut manifest: Self =
+            serde_yaml::from_str(&content).with_context(|| format!("Failed to parse suite manifest {:?}", path))?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        for entry in &mut manifest.runs {
+            if entry.config.is_relative() {
+                entry.config = base.join(&entry.config);
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+/// Config YAML to stage for repetitions: `path` composed, so `defaults:` includes
+/// resolve against its own directory, with data generation turned off
+pub fn staged_config(path: &Path) -> Result<String> {
+    let mut doc = crate::dlio_compat::hydra::compose_file(path)
+        .with_context(|| format!("Failed to compose config {:?}", path))?;
+    let root = doc.as_mapping_mut().context("Config YAML is not a mapping")?;
+    let workflow = root
+        .entry("workflow".into())
+        .or_insert_with(|| serde_yaml::Value::Mapping(Default::default()));
+    if let Some(workflow) = workflow.as_mapping_mut() {
+        workflow.insert("generate_data".into(), false.into());
+    }
+    Ok(serde_yaml::to_string(&doc)?)
+}
+
+/// File stem for an entry's staged config and results, unique by its position in the suite
+pub fn staged_stem(index: usize, name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{:02}_{}", index + 1, name)
+}
+
+/// Delete a config's dataset (local directory or object prefix)
+pub async fn cleanup_dataset(config: &DlioConfig) -> Result<()> {
+    let uri = config.data_folder_uri();
+    let lock = crate::dataset_lock::DatasetLock::acquire(config, "cleanup", false).await?;
+    match local_path_for_uri(uri) {
+        Some(path) if path.exists() => {
+            std::fs::remove_dir_all(&path).with_context(|| format!("Failed to remove dataset {:?}", path))?;
+        }
+        Some(_) => {}
+        None => {
+            let store = s3dlio::object_store::store_for_uri(uri)
+                .with_context(|| format!("Failed to create object store for {}", uri))?;
+            store.delete_prefix(uri).await.with_context(|| format!("Failed to delete dataset under {}", uri))?;
+        }
+    }
+    // The lock normally goes with the dataset; release covers a lock that outlived it
+    if let Some(lock) = lock {
+        lock.release().await?;
+    }
+    info!("🧹 Removed dataset {}", uri);
+    Ok(())
+}
+
+/// Headline metrics of one run, from its results JSON
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunMetrics {
+    pub throughput_gib_s: Option<f64>,
+    pub samples_per_second: Option<f64>,
+    pub au_percent: Option<f64>,
+    pub wall_clock_s: Option<f64>,
+}
+
+impl RunMetrics {
+    pub fn from_results(results: &serde_json::Value) -> Self {
+        let m = &results["metrics"];
+        Self {
+            throughput_gib_s: m["storage_throughput_gib_s"].as_f64(),
+            samples_per_second: m["samples_per_second"].as_f64(),
+            au_percent: m["au_percent"].as_f64(),
+            wall_clock_s: m["wall_clock_time_ms"].as_f64().map(|ms| ms / 1000.0),
+        }
+    }
+}
+
+/// One repetition of one config
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteRun {
+    pub name: String,
+    pub config: PathBuf,
+    pub repetition: u32,
+    pub passed: bool,
+    pub error: Option<String>,
+    pub metrics: RunMetrics,
+}
+
+/// Per-config means over passing repetitions
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteSummary {
+    pub name: String,
+    pub runs: usize,
+    pub passed: usize,
+    pub mean: RunMetrics,
+}
+
+/// Combined report of a suite
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    pub summaries: Vec<SuiteSummary>,
+    pub runs: Vec<SuiteRun>,
+}
+
+fn mean(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    let values: Vec<f64> = values.flatten().collect();
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+impl SuiteReport {
+    pub fn new(runs: Vec<SuiteRun>) -> Self {
+        let mut names: Vec<&str> = Vec::new();
+        for run in &runs {
+            if !names.contains(&run.name.as_str()) {
+                names.push(&run.name);
+            }
+        }
+        let summaries = names
+            .into_iter()
+            .map(|name| {
+                let all: Vec<&SuiteRun> = runs.iter().filter(|r| r.name == name).collect();
+                let ok: Vec<&RunMetrics> = all.iter().filter(|r| r.passed).map(|r| &r.metrics).collect();
+                SuiteSummary {
+                    name: name.to_string(),
+                    runs: all.len(),
+                    passed: ok.len(),
+                    mean: RunMetrics {
+                        throughput_gib_s: mean(ok.iter().map(|m| m.throughput_gib_s)),
+                        samples_per_second: mean(ok.iter().map(|m| m.samples_per_second)),
+                        au_percent: mean(ok.iter().map(|m| m.au_percent)),
+                        wall_clock_s: mean(ok.iter().map(|m| m.wall_clock_s)),
+                    },
+                }
+            })
+            .collect();
+        Self { summaries, runs }
+    }
+
+    pub fn failed(&self) -> usize {
+        self.runs.iter().filter(|r| !r.passed).count()
+    }
+
+    /// Print the comparison table
+    pub fn print_table(&self) {
+        let fmt = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.2}", v));
+        println!("\n=== Benchmark Suite ===");
+        println!("{:<24} {:>6} {:>10} {:>12} {:>8} {:>10}", "Config", "Passed", "GiB/s", "Samples/s", "AU %", "Wall (s)");
+        for s in &self.summaries {
+            println!(
+                "{:<24} {:>6} {:>10} {:>12} {:>8} {:>10}",
+                s.name,
+                format!("{}/{}", s.passed, s.runs),
+                fmt(s.mean.throughput_gib_s),
+                fmt(s.mean.samples_per_second),
+                fmt(s.mean.au_percent),
+                fmt(s.mean.wall_clock_s)
+            );
+        }
+        println!("=======================\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_staging_and_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("suite.yaml");
+        std::fs::write(&manifest_path, "cleanup: true\nruns:\n  - config: a.yaml\n    repetitions: 2\n  - config: /abs/b.yml\n    name: bee\n").unwrap();
+        let manifest = SuiteManifest::load(&manifest_path).unwrap();
+        assert!(manifest.cleanup);
+        assert_eq!(manifest.runs[0].config, dir.path().join("a.yaml"));
+        assert_eq!((manifest.runs[0].name(), manifest.runs[0].repetitions), ("a".to_string(), 2));
+        assert_eq!((manifest.runs[1].name(), manifest.runs[1].repetitions), ("bee".to_string(), 1));
+
+        let conf = dir.path().join("conf");
+        std::fs::create_dir(&conf).unwrap();
+        std::fs::write(conf.join("base.yaml"), "dataset:\n  data_folder: /tmp/d\n").unwrap();
+        std::fs::write(conf.join("a.yaml"), "defaults:\n  - base\n  - _self_\nworkflow:\n  generate_data: true\n").unwrap();
+        let staged = staged_config(&conf.join("a.yaml")).unwrap();
+        let staged: serde_yaml::Value = serde_yaml::from_str(&staged).unwrap();
+        assert_eq!(staged["workflow"]["generate_data"], serde_yaml::Value::Bool(false));
+        assert_eq!(staged["dataset"]["data_folder"], serde_yaml::Value::from("/tmp/d"));
+        assert_eq!(staged_stem(0, "a"), "01_a");
+        assert_eq!(staged_stem(11, "sub/a b"), "12_sub_a_b");
+
+        let run = |rep, passed, gib| SuiteRun {
+            name: "a".to_string(),
+            config: PathBuf::from("a.yaml"),
+            repetition: rep,
+            passed,
+            error: None,
+            metrics: RunMetrics { throughput_gib_s: Some(gib), ..Default::default() },
+        };
+        let report = SuiteReport::new(vec![run(1, true, 2.0), run(2, true, 4.0), run(3, false, 100.0)]);
+        assert_eq!(report.summaries.len(), 1);
+        assert_eq!((report.summaries[0].runs, report.summaries[0].passed), (3, 2));
+        assert_eq!(report.summaries[0].mean.throughput_gib_s, Some(3.0));
+        assert_eq!(report.failed(), 1);
+    }
+}
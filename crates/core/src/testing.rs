@@ -0,0 +1,272 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/testing.rs
+//
+// Test fixtures for plugin and backend authors (`dl_driver_core::testing`)
+//
+// Out-of-tree plugins and storage backends can be tested against the same
+// pieces dl-driver's own tests use, without copying setup code from cli/tests:
+//
+// - `ConfigBuilder`: a small DlioConfig from a few knobs, parsed from YAML
+//   exactly like a config file (extra sections via `yaml`)
+// - `mem_backend`: the mem:// backend holding the config's synthetic dataset
+// - `generate_dataset`: writes the config's dataset through any DataBackend,
+//   with generation's object naming and format payloads
+// - `check_backend`: put/list/get/head round trip every writable backend must pass
+// - `run_plugin`: drives a Plugin through its lifecycle over a backend's objects
+// - `ExpectedMetrics`: assertions on what a run recorded in Metrics
+//
+use anyhow::{Context, Result};
+use std::sync::Arc;
+
+use crate::backends::{DataBackend, MemBackend};
+use crate::dlio_compat::DlioConfig;
+use crate::key_layout::KeyLayout;
+use crate::metrics::Metrics;
+use crate::plugins::Plugin;
+
+/// Builds a small DlioConfig: 8 files of 2 × 1 KiB samples, batch size 4, 1 epoch
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    data_folder: String,
+    format: String,
+    files: usize,
+    samples_per_file: usize,
+    record_length: usize,
+    batch_size: usize,
+    read_threads: usize,
+    epochs: u32,
+    computation_time: f64,
+    extra: String,
+}
+
+impl ConfigBuilder {
+    pub fn new(data_folder: &str) -> Self {
+        Self {
+            data_folder: data_folder.to_string(),
+            format: "npz".to_string(),
+            files: 8,
+            samples_per_file: 2,
+            record_length: 1024,
+            batch_size: 4,
+            read_threads: 2,
+            epochs: 1,
+            computation_time: 0.0,
+            extra: String::new(),
+        }
+    }
+
+    pub fn format(mut self, format: &str) -> Self {
+        self.format = format.to_string();
+        self
+    }
+
+    pub fn files(mut self, files: usize) -> Self {
+        self.files = files;
+        self
+    }
+
+    pub fn samples_per_file(mut self, samples: usize) -> Self {
+        self.samples_per_file = samples;
+        self
+    }
+
+    pub fn record_length(mut self, bytes: usize) -> Self {
+        self.record_length = bytes;
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn read_threads(mut self, threads: usize) -> Self {
+        self.read_threads = threads;
+        self
+    }
+
+    pub fn epochs(mut self, epochs: u32) -> Self {
+        self.epochs = epochs;
+        self
+    }
+
+    /// Seconds of simulated compute per step
+    pub fn computation_time(mut self, seconds: f64) -> Self {
+        self.computation_time = seconds;
+        self
+    }
+
+    /// Additional top-level YAML sections, e.g. "storage:\n  request_timing: true\n"
+    pub fn yaml(mut self, sections: &str) -> Self {
+        self.extra.push_str(sections);
+        if !self.extra.ends_with('\n') {
+            self.extra.push('\n');
+        }
+        self
+    }
+
+    pub fn to_yaml(&self) -> String {
+        format!(
+            "dataset:\n  data_folder: {}\n  format: {}\n  num_files_train: {}\n  num_samples_per_file: {}\n  \
+             record_length_bytes: {}\nreader:\n  batch_size: {}\n  read_threads: {}\n\
+             train:\n  epochs: {}\n  computation_time: {}\n{}",
+            self.data_folder, self.format, self.files, self.samples_per_file, self.record_length,
+            self.batch_size, self.read_threads, self.epochs, self.computation_time, self.extra
+        )
+    }
+
+    pub fn build(&self) -> Result<DlioConfig> {
+        DlioConfig::from_yaml(&self.to_yaml()).context("Test config did not parse")
+    }
+}
+
+/// The mem:// backend, holding the config's synthetic dataset
+pub fn mem_backend(config: &DlioConfig) -> Result<Arc<dyn DataBackend>> {
+    Ok(Arc::new(MemBackend::new(config)?))
+}
+
+/// One generated object of the config's format, as `dl-driver generate` writes it
+pub fn dataset_object(config: &DlioConfig) -> Result<Vec<u8>> {
+    let record = config.dataset.record_length_bytes.unwrap_or(1024).max(1);
+    crate::payload::generate_file_payload(config, record, None)
+}
+
+/// Write the config's dataset to `backend` under generation's naming; returns the object URIs
+pub async fn generate_dataset(backend: &dyn DataBackend, config: &DlioConfig) -> Result<Vec<String>> {
+    let layout = KeyLayout::from_config(config)?;
+    let format = config.dataset.format.as_deref().unwrap_or("npz");
+    let data = dataset_object(config)?;
+    let mut uris = Vec::new();
+    for i in 0..config.dataset.num_files_train.unwrap_or(100) {
+        let uri = layout.uri(config.data_folder_uri(), i, format);
+        backend.put(&uri, &data).await.with_context(|| format!("Failed to write {}", uri))?;
+        uris.push(uri);
+    }
+    Ok(uris)
+}
+
+/// Round trip a writable backend must pass: written objects are listed, read back whole and by head
+pub async fn check_backend(backend: &dyn DataBackend, prefix: &str) -> Result<()> {
+    let prefix = prefix.trim_end_matches('/');
+    let objects: Vec<(String, Vec<u8>)> = (0..3)
+        .map(|i| (format!("{}/check_{}.bin", prefix, i), s3dlio::generate_controlled_data(4096 + i, 0, 0)[..4096 + i].to_vec()))
+        .collect();
+    for (uri, data) in &objects {
+        backend.put(uri, data).await.with_context(|| format!("{}: put {} failed", backend.name(), uri))?;
+    }
+
+    let listed = backend.list().await?;
+    for (uri, data) in &objects {
+        anyhow::ensure!(listed.contains(uri), "{}: {} was written but not listed", backend.name(), uri);
+        let read = backend.get(uri).await.with_context(|| format!("{}: get {} failed", backend.name(), uri))?;
+        anyhow::ensure!(&read == data, "{}: {} read back {} bytes that differ from the {} written",
+                        backend.name(), uri, read.len(), data.len());
+        let head = backend.head(uri, 100).await?;
+        anyhow::ensure!(head == data[..100], "{}: head of {} differs from its first 100 bytes", backend.name(), uri);
+    }
+    anyhow::ensure!(backend.get(&format!("{}/missing.bin", prefix)).await.is_err(),
+                    "{}: reading a missing object must fail", backend.name());
+    Ok(())
+}
+
+/// Drive `plugin` through one epoch over `backend`'s objects, `batch_size` objects per batch
+///
+/// Calls initialize, then after_batch and after_step per batch, after_epoch(1) and finalize.
+/// Returns the number of batches.
+pub async fn run_plugin(plugin: &mut dyn Plugin, config: &DlioConfig, backend: &dyn DataBackend, batch_size: usize) -> Result<u32> {
+    plugin.initialize(config).await?;
+    let uris = backend.list().await?;
+    let mut steps = 0;
+    for chunk in uris.chunks(batch_size.max(1)) {
+        let mut batch = Vec::with_capacity(chunk.len());
+        for uri in chunk {
+            batch.push(backend.get(uri).await?);
+        }
+        plugin.after_batch(&batch).await?;
+        steps += 1;
+        plugin.after_step(steps).await?;
+    }
+    plugin.after_epoch(1).await?;
+    plugin.finalize().await?;
+    Ok(steps)
+}
+
+/// What a run should have recorded; unset fields are not checked
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedMetrics {
+    pub bytes_read: Option<u64>,
+    pub samples_read: Option<u64>,
+    pub bytes_written: Option<u64>,
+    pub epochs: Option<usize>,
+}
+
+impl ExpectedMetrics {
+    /// Panics with every mismatch, as `assert_eq!` would
+    pub fn assert(&self, metrics: &Metrics) {
+        let checks = [
+            ("bytes_read", self.bytes_read, metrics.bytes_read()),
+            ("samples_read", self.samples_read, metrics.samples_read()),
+            ("bytes_written", self.bytes_written, metrics.bytes_written()),
+            ("epochs", self.epochs.map(|e| e as u64), metrics.epochs_completed() as u64),
+        ];
+        let mismatches: Vec<String> = checks
+            .iter()
+            .filter_map(|(name, expected, actual)| {
+                expected.filter(|e| e != actual).map(|e| format!("{}: expected {}, recorded {}", name, e, actual))
+            })
+            .collect();
+        assert!(mismatches.is_empty(), "metrics mismatch: {}", mismatches.join("; "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    #[derive(Default)]
+    struct CountingPlugin {
+        bytes: usize,
+        epochs: u32,
+    }
+
+    #[async_trait]
+    impl Plugin for CountingPlugin {
+        async fn after_batch(&mut self, batch: &[Vec<u8>]) -> Result<()> {
+            self.bytes += batch.iter().map(Vec::len).sum::<usize>();
+            Ok(())
+        }
+
+        async fn after_epoch(&mut self, epoch: u32) -> Result<()> {
+            self.epochs = epoch;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixtures_cover_backend_plugin_and_metrics() {
+        let config = ConfigBuilder::new("mem://fixtures").files(5).record_length(256).yaml("metric:\n  au: 0.9").build().unwrap();
+        assert_eq!(config.dataset.num_files_train, Some(5));
+        assert!(config.metric.is_some());
+
+        let backend = mem_backend(&config).unwrap();
+        check_backend(backend.as_ref(), "mem://scratch").await.unwrap();
+
+        // Regenerate over the synthetic objects with real NPZ payloads, then read them through a plugin
+        let uris = generate_dataset(backend.as_ref(), &config).await.unwrap();
+        assert_eq!(uris.len(), 5);
+        let mut plugin = CountingPlugin::default();
+        let steps = run_plugin(&mut plugin, &config, backend.as_ref(), 2).await.unwrap();
+        assert_eq!((steps, plugin.epochs), (4, 1)); // 5 dataset + 3 check objects
+        assert!(plugin.bytes > 0);
+
+        let metrics = Metrics::new();
+        metrics.record_bytes_read(512);
+        metrics.record_epoch_time(std::time::Duration::from_millis(5));
+        ExpectedMetrics { bytes_read: Some(512), epochs: Some(1), ..Default::default() }.assert(&metrics);
+        let wrong = std::panic::catch_unwind(|| ExpectedMetrics { samples_read: Some(1), ..Default::default() }.assert(&metrics));
+        assert!(wrong.is_err());
+    }
+}
@@ -0,0 +1,309 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/tier_migration.rs
+//
+// Data aging / tier-migration simulation (`tier_migration:` config block)
+//
+// Before every epoch after the first, a `fraction` of the files not migrated
+// yet (picked by a ChaCha8 permutation seeded with the epoch seed) moves to a
+// cold tier through a hook:
+//
+//   tier_migration:
+//     fraction: 0.2
+//     hook: copy                  # copy objects to `target` (default)
+//     target: s3://cold/train/    # default: data_folder + "_cold"
+//     # hook: shell, command: "hsm_release --files {list}"   # in place
+//
+// The shell hook gets the migrated URIs in a file (`{list}`, one per line);
+// without `target` the files stay where they are (storage-class change, HSM
+// release) and are read from the same URI. The loader keeps its object list:
+// reads of migrated files are redirected to the cold copy and attributed
+// separately from hot reads, the first read of each as a recall. Reported
+// under "tier_migration"; migration falls inside the measured training
+// phase, between epochs.
+//
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+use tracing::info;
+
+use crate::backends::stats::{ReadStats, ReadSummary, ReadWindow};
+use crate::backends::{backend_for_config, DataBackend, ObjectStoreBackend};
+use crate::clone_bench::{run_shell, uri_path};
+use crate::dlio_compat::DlioConfig;
+use crate::shuffle;
+
+/// How files reach the cold tier
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationHook {
+    /// Copy each object to the target prefix
+    Copy,
+    /// Run an operator-supplied command over the list of files
+    Shell(String),
+}
+
+/// Resolved `tier_migration:` settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationSettings {
+    pub fraction: f64,
+    pub hook: MigrationHook,
+    /// Cold prefix; None migrates in place
+    pub target: Option<String>,
+}
+
+impl MigrationSettings {
+    /// From the config's `tier_migration:` block; None when migration is off
+    pub fn from_config(config: &DlioConfig) -> Result<Option<Self>> {
+        let Some(migration) = &config.tier_migration else {
+            return Ok(None);
+        };
+        let fraction = migration.fraction.unwrap_or(0.1);
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            anyhow::bail!("tier_migration.fraction must be in (0, 1], got {}", fraction);
+        }
+        let hook = match (migration.hook.as_deref(), &migration.command) {
+            (Some("shell") | None, Some(command)) => MigrationHook::Shell(command.clone()),
+            (Some("shell"), None) => anyhow::bail!("tier_migration.hook 'shell' needs a command"),
+            (Some("copy") | None, _) => MigrationHook::Copy,
+            (Some(other), _) => anyhow::bail!("Unknown tier_migration.hook '{}' (expected 'copy' or 'shell')", other),
+        };
+        let target = match (&migration.target, &hook) {
+            (Some(target), _) => Some(target.trim_end_matches('/').to_string()),
+            (None, MigrationHook::Copy) => Some(format!("{}_cold", config.data_folder_uri().trim_end_matches('/'))),
+            (None, MigrationHook::Shell(_)) => None,
+        };
+        Ok(Some(Self { fraction, hook, target }))
+    }
+}
+
+/// One migration between epochs
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationEpoch {
+    /// Epoch the migration preceded
+    pub epoch: u32,
+    pub objects: usize,
+    pub seconds: f64,
+}
+
+/// Tier-migration section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct TierMigrationReport {
+    pub hook: String,
+    pub target: Option<String>,
+    pub fraction: f64,
+    pub migrated_objects: usize,
+    pub migrations: Vec<MigrationEpoch>,
+    /// Reads of files never migrated
+    pub hot: ReadSummary,
+    /// First read of each migrated file
+    pub recall: ReadSummary,
+    /// Later reads of migrated files
+    pub cold: ReadSummary,
+    /// Mean recall latency minus mean hot latency
+    pub recall_penalty_ms: f64,
+}
+
+/// Backend that migrates files between epochs and redirects reads of migrated files
+pub struct TierMigration {
+    settings: MigrationSettings,
+    data_folder: String,
+    hot_backend: Arc<dyn DataBackend>,
+    cold_backend: Option<Arc<dyn DataBackend>>,
+    /// Original URI -> URI of the cold copy
+    migrated: RwLock<HashMap<String, String>>,
+    recalled: Mutex<HashSet<String>>,
+    migrations: Mutex<Vec<MigrationEpoch>>,
+    hot: ReadStats,
+    recall: ReadStats,
+    cold: ReadStats,
+    window: ReadWindow,
+}
+
+impl TierMigration {
+    pub fn new(settings: MigrationSettings, config: &DlioConfig, hot_backend: Arc<dyn DataBackend>) -> Result<Self> {
+        let cold_backend = match &settings.target {
+            Some(target) => {
+                // The cold tier gets the backend a run on that prefix would use, minus any synthesized dataset
+                let mut cold_config = config.clone();
+                cold_config.dataset.data_folder = target.clone();
+                cold_config.dataset.data_folders = None;
+                cold_config.dataset.num_files_train = Some(0);
+                Some(match backend_for_config(&cold_config, None)? {
+                    Some(backend) => backend,
+                    None => Arc::new(ObjectStoreBackend::new(target)?) as Arc<dyn DataBackend>,
+                })
+            }
+            None => None,
+        };
+        Ok(Self {
+            settings,
+            data_folder: config.data_folder_uri().trim_end_matches('/').to_string(),
+            hot_backend,
+            cold_backend,
+            migrated: RwLock::new(HashMap::new()),
+            recalled: Mutex::new(HashSet::new()),
+            migrations: Mutex::new(Vec::new()),
+            hot: ReadStats::default(),
+            recall: ReadStats::default(),
+            cold: ReadStats::default(),
+            window: ReadWindow::default(),
+        })
+    }
+
+    fn cold_uri(&self, uri: &str) -> String {
+        match &self.settings.target {
+            Some(target) => format!("{}{}", target, uri.strip_prefix(&self.data_folder).unwrap_or(uri)),
+            None => uri.to_string(),
+        }
+    }
+
+    /// Migrate `fraction` of the not-yet-migrated objects of `groups` before `epoch`
+    pub async fn migrate(&self, groups: &[Vec<String>], epoch: u32, seed: u64) -> Result<usize> {
+        let start = Instant::now();
+        let mut candidates: Vec<&Vec<String>> = {
+            let migrated = self.migrated.read().unwrap();
+            groups.iter().filter(|g| !g.iter().any(|uri| migrated.contains_key(uri))).collect()
+        };
+        shuffle::permute(&mut candidates, seed);
+        let count = (candidates.len() as f64 * self.settings.fraction).ceil() as usize;
+        let uris: Vec<String> = candidates.into_iter().take(count).flatten().cloned().collect();
+
+        match &self.settings.hook {
+            MigrationHook::Copy => {
+                let cold = self.cold_backend.as_ref().expect("copy migration has a target");
+                for uri in &uris {
+                    let data = self.hot_backend.get(uri).await?;
+                    let dest = self.cold_uri(uri);
+                    cold.put(&dest, &data).await.with_context(|| format!("Failed to migrate {} to {}", uri, dest))?;
+                }
+            }
+            MigrationHook::Shell(command) => self.run_command(command, &uris, epoch).await?,
+        }
+
+        let mut migrated = self.migrated.write().unwrap();
+        migrated.extend(uris.iter().map(|uri| (uri.clone(), self.cold_uri(uri))));
+        let seconds = start.elapsed().as_secs_f64();
+        info!("🧊 Migrated {} objects to the cold tier before epoch {} in {:.2}s ({} cold in total)",
+              uris.len(), epoch + 1, seconds, migrated.len());
+        self.migrations.lock().unwrap().push(MigrationEpoch { epoch: epoch + 1, objects: uris.len(), seconds });
+        Ok(uris.len())
+    }
+
+    async fn run_command(&self, command: &str, uris: &[String], epoch: u32) -> Result<()> {
+        let mut list = tempfile::NamedTempFile::new().context("Failed to create migration list file")?;
+        for uri in uris {
+            writeln!(list, "{}", uri)?;
+        }
+        list.flush()?;
+        let target = self.settings.target.as_deref().unwrap_or(&self.data_folder);
+        let command = command
+            .replace("{list}", &list.path().display().to_string())
+            .replace("{target_path}", &uri_path(target))
+            .replace("{target}", target)
+            .replace("{epoch}", &(epoch + 1).to_string());
+        info!("🧊 Migration command: {}", command);
+        run_shell("Migration", &command).await
+    }
+
+    pub fn migration_report(&self) -> TierMigrationReport {
+        let total = self.hot.reads() + self.recall.reads() + self.cold.reads();
+        let window_s = self.window.seconds();
+        let (hot, recall) = (self.hot.summary(window_s, total), self.recall.summary(window_s, total));
+        TierMigrationReport {
+            hook: match self.settings.hook {
+                MigrationHook::Copy => "copy".to_string(),
+                MigrationHook::Shell(_) => "shell".to_string(),
+            },
+            target: self.settings.target.clone(),
+            fraction: self.settings.fraction,
+            migrated_objects: self.migrated.read().unwrap().len(),
+            migrations: self.migrations.lock().unwrap().clone(),
+            recall_penalty_ms: if recall.reads > 0 { recall.mean_ms - hot.mean_ms } else { 0.0 },
+            cold: self.cold.summary(window_s, total),
+            hot,
+            recall,
+        }
+    }
+}
+
+#[async_trait]
+impl DataBackend for TierMigration {
+    fn name(&self) -> &'static str {
+        self.hot_backend.name()
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        self.hot_backend.list().await
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let cold_uri = self.migrated.read().unwrap().get(uri).cloned();
+        let (stats, result, start) = match cold_uri {
+            Some(cold_uri) => {
+                let stats = if self.recalled.lock().unwrap().insert(uri.to_string()) { &self.recall } else { &self.cold };
+                let backend = self.cold_backend.as_ref().filter(|_| cold_uri != uri).unwrap_or(&self.hot_backend);
+                let start = Instant::now();
+                (stats, backend.get(&cold_uri).await, start)
+            }
+            None => {
+                let start = Instant::now();
+                (&self.hot, self.hot_backend.get(uri).await, start)
+            }
+        };
+        let data = result.inspect_err(|_| stats.record_error())?;
+        let end = Instant::now();
+        stats.record(start, end, data.len());
+        self.window.extend(start, end);
+        Ok(data)
+    }
+
+    async fn put(&self, uri: &str, data: &[u8]) -> Result<()> {
+        self.hot_backend.put(uri, data).await
+    }
+
+    fn report(&self) -> Option<serde_json::Value> {
+        self.hot_backend.report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::MemBackend;
+
+    #[tokio::test]
+    async fn test_migrated_reads_are_redirected_and_attributed() {
+        let yaml = "dataset:\n  data_folder: mem://hot\n  num_files_train: 10\n  record_length_bytes: 64\nreader: {}\n\
+                    tier_migration: {fraction: 0.5}\n";
+        let config = DlioConfig::from_yaml(yaml).unwrap();
+        let settings = MigrationSettings::from_config(&config).unwrap().unwrap();
+        assert_eq!((settings.hook.clone(), settings.target.as_deref()), (MigrationHook::Copy, Some("mem://hot_cold")));
+
+        let hot: Arc<dyn DataBackend> = Arc::new(MemBackend::new(&config).unwrap());
+        let tier = TierMigration::new(settings, &config, hot).unwrap();
+        let groups: Vec<Vec<String>> = tier.list().await.unwrap().into_iter().map(|uri| vec![uri]).collect();
+        assert_eq!(tier.migrate(&groups, 1, 7).await.unwrap(), 5);
+        // Half of the remaining five, rounded up
+        assert_eq!(tier.migrate(&groups, 2, 8).await.unwrap(), 3);
+
+        for _ in 0..2 {
+            for uri in groups.iter().flatten() {
+                assert_eq!(tier.get(uri).await.unwrap().len(), 64);
+            }
+        }
+        let report = tier.migration_report();
+        assert_eq!(report.migrated_objects, 8);
+        assert_eq!((report.hot.reads, report.recall.reads, report.cold.reads), (4, 8, 8));
+
+        let mut config = config;
+        config.tier_migration.as_mut().unwrap().hook = Some("shell".to_string());
+        assert!(MigrationSettings::from_config(&config).is_err());
+        config.tier_migration.as_mut().unwrap().command = Some("true {list}".to_string());
+        assert_eq!(MigrationSettings::from_config(&config).unwrap().unwrap().target, None);
+    }
+}
@@ -0,0 +1,319 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/timeseries.rs
+//
+// Per-epoch and per-step metrics over time (`--timeline`)
+//
+// The results' `metrics` are run totals; a slow first epoch, a mid-run stall
+// or one rank falling behind only shows over time. The `timeseries` results
+// section records:
+//
+//   epochs: one row per epoch - throughput, samples/s, I/O wait vs compute, AU
+//   steps:  one row per sampled step - time since training start, how long the
+//           loop waited for the batch, compute time and whole step latency
+//   checkpoints: each checkpoint write's window, whether training reads were
+//           paused meanwhile and how many batches the loader queued during it
+//
+// `--timeline` sets the granularity: `off`, `epoch` (default), `step` (every
+// step) or `step:N` (every Nth step). Step rows are capped at
+// MAX_STEP_POINTS; when the cap is hit every other row is dropped and the
+// sampling interval doubles, so a long run keeps evenly spaced rows from start
+// to end in bounded memory. `start_unix_ms` lets rows from different ranks be
+// lined up on one time axis.
+//
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Most step rows kept per rank
+pub const MAX_STEP_POINTS: usize = 10_000;
+
+/// What the timeline records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineGranularity {
+    Off,
+    Epoch,
+    /// Epoch rows plus every `every`th step
+    Step { every: u64 },
+}
+
+impl TimelineGranularity {
+    /// `off`, `epoch`, `step` or `step:N`
+    pub fn parse(spec: &str) -> Result<Self> {
+        match spec.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Ok(Self::Off),
+            "epoch" => Ok(Self::Epoch),
+            "step" => Ok(Self::Step { every: 1 }),
+            other => {
+                let every = other
+                    .strip_prefix("step:")
+                    .with_context(|| format!("Unknown timeline granularity '{}' (expected off, epoch, step or step:N)", spec))?;
+                let every: u64 = every
+                    .parse()
+                    .with_context(|| format!("Invalid step interval in timeline granularity '{}'", spec))?;
+                if every == 0 {
+                    anyhow::bail!("Timeline step interval must be at least 1");
+                }
+                Ok(Self::Step { every })
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Off => "off".to_string(),
+            Self::Epoch => "epoch".to_string(),
+            Self::Step { every: 1 } => "step".to_string(),
+            Self::Step { every } => format!("step:{}", every),
+        }
+    }
+}
+
+/// One epoch of a training run
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochPoint {
+    pub epoch: u32,
+    /// Seconds from training start to the start of the epoch
+    pub start_s: f64,
+    pub duration_s: f64,
+    pub steps: u64,
+    pub samples: u64,
+    pub bytes: u64,
+    pub throughput_gib_s: f64,
+    pub samples_per_s: f64,
+    /// Time the training loop waited for batches
+    pub io_wait_s: f64,
+    pub compute_s: f64,
+    pub au: f64,
+}
+
+/// One training step
+#[derive(Debug, Clone, Serialize)]
+pub struct StepPoint {
+    pub step: u64,
+    pub epoch: u32,
+    /// Seconds from training start to the end of the step
+    pub t_s: f64,
+    pub io_wait_ms: f64,
+    pub compute_ms: f64,
+    /// Wait plus processing: the step's whole latency
+    pub step_ms: f64,
+    pub samples: usize,
+    pub bytes: usize,
+}
+
+/// A checkpoint write during training
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointWindow {
+    pub step: u64,
+    pub epoch: u32,
+    /// Seconds from training start to the start of the write
+    pub start_s: f64,
+    pub duration_s: f64,
+    pub loading_paused: bool,
+    /// Batches the loader queued while the checkpoint was written
+    pub batches_loaded: u64,
+}
+
+/// `timeseries` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct Timeseries {
+    pub granularity: String,
+    pub start_unix_ms: u64,
+    /// Steps between recorded step rows at the end of the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_interval: Option<u64>,
+    pub epochs: Vec<EpochPoint>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<StepPoint>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<CheckpointWindow>,
+}
+
+/// Collects epoch and step rows during a training run
+#[derive(Debug)]
+pub struct TimeseriesRecorder {
+    granularity: TimelineGranularity,
+    max_step_points: usize,
+    start: Instant,
+    start_unix_ms: u64,
+    step_interval: u64,
+    epochs: Vec<EpochPoint>,
+    steps: Vec<StepPoint>,
+    checkpoints: Vec<CheckpointWindow>,
+    current: Option<(u32, Instant, EpochTotals)>,
+}
+
+#[derive(Debug, Default)]
+struct EpochTotals {
+    steps: u64,
+    samples: u64,
+    bytes: u64,
+    io_wait: Duration,
+    compute: Duration,
+}
+
+impl TimeseriesRecorder {
+    /// A recorder whose times are relative to `start`, the start of training
+    pub fn new(granularity: TimelineGranularity, start: Instant) -> Self {
+        let start_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_sub(start.elapsed())
+            .as_millis() as u64;
+        Self {
+            granularity,
+            max_step_points: MAX_STEP_POINTS,
+            start,
+            start_unix_ms,
+            step_interval: match granularity {
+                TimelineGranularity::Step { every } => every,
+                _ => 0,
+            },
+            epochs: Vec::new(),
+            steps: Vec::new(),
+            checkpoints: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Cap step rows at `max` (at least 2) instead of MAX_STEP_POINTS
+    pub fn with_max_step_points(mut self, max: usize) -> Self {
+        self.max_step_points = max.max(2);
+        self
+    }
+
+    /// `epoch` is 1-based, as in the rest of the results
+    pub fn start_epoch(&mut self, epoch: u32, start: Instant) {
+        self.current = Some((epoch, start, EpochTotals::default()));
+    }
+
+    /// Record finished step `step` (1-based, counted across epochs)
+    pub fn record_step(&mut self, step: u64, io_wait: Duration, compute: Duration, step_time: Duration, samples: usize, bytes: usize) {
+        if self.granularity == TimelineGranularity::Off {
+            return;
+        }
+        let Some((epoch, _, totals)) = self.current.as_mut() else { return };
+        totals.steps += 1;
+        totals.samples += samples as u64;
+        totals.bytes += bytes as u64;
+        totals.io_wait += io_wait;
+        totals.compute += compute;
+        let epoch = *epoch;
+
+        if self.step_interval == 0 || !step.is_multiple_of(self.step_interval) {
+            return;
+        }
+        self.steps.push(StepPoint {
+            step,
+            epoch,
+            t_s: self.start.elapsed().as_secs_f64(),
+            io_wait_ms: io_wait.as_secs_f64() * 1000.0,
+            compute_ms: compute.as_secs_f64() * 1000.0,
+            step_ms: step_time.as_secs_f64() * 1000.0,
+            samples,
+            bytes,
+        });
+        if self.steps.len() >= self.max_step_points {
+            self.step_interval *= 2;
+            let interval = self.step_interval;
+            self.steps.retain(|p| p.step.is_multiple_of(interval));
+        }
+    }
+
+    /// Record a checkpoint write that started at `start` after step `step`
+    pub fn record_checkpoint(&mut self, step: u64, epoch: u32, start: Instant, duration: Duration, loading_paused: bool, batches_loaded: u64) {
+        self.checkpoints.push(CheckpointWindow {
+            step,
+            epoch,
+            start_s: start.saturating_duration_since(self.start).as_secs_f64(),
+            duration_s: duration.as_secs_f64(),
+            loading_paused,
+            batches_loaded,
+        });
+    }
+
+    /// Close the current epoch; `duration` excludes checkpoint stalls like the epoch metrics
+    pub fn end_epoch(&mut self, duration: Duration) {
+        let Some((epoch, start, totals)) = self.current.take() else { return };
+        if self.granularity == TimelineGranularity::Off {
+            return;
+        }
+        let secs = duration.as_secs_f64();
+        let per_s = |n: f64| if secs > 0.0 { n / secs } else { 0.0 };
+        self.epochs.push(EpochPoint {
+            epoch,
+            start_s: start.saturating_duration_since(self.start).as_secs_f64(),
+            duration_s: secs,
+            steps: totals.steps,
+            samples: totals.samples,
+            bytes: totals.bytes,
+            throughput_gib_s: per_s(totals.bytes as f64 / crate::units::GIB),
+            samples_per_s: per_s(totals.samples as f64),
+            io_wait_s: totals.io_wait.as_secs_f64(),
+            compute_s: totals.compute.as_secs_f64(),
+            au: per_s(totals.compute.as_secs_f64()).min(1.0),
+        });
+    }
+
+    /// The recorded rows, or None with `--timeline off`
+    pub fn report(self) -> Option<Timeseries> {
+        (self.granularity != TimelineGranularity::Off).then(|| Timeseries {
+            granularity: self.granularity.name(),
+            start_unix_ms: self.start_unix_ms,
+            step_interval: (self.step_interval > 0).then_some(self.step_interval),
+            epochs: self.epochs,
+            steps: self.steps,
+            checkpoints: self.checkpoints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeseries_granularity_and_step_cap() {
+        assert_eq!(TimelineGranularity::parse("epoch").unwrap(), TimelineGranularity::Epoch);
+        assert_eq!(TimelineGranularity::parse("STEP").unwrap(), TimelineGranularity::Step { every: 1 });
+        assert_eq!(TimelineGranularity::parse("step:25").unwrap().name(), "step:25");
+        for bad in ["steps", "step:0", "step:x", ""] {
+            assert!(TimelineGranularity::parse(bad).is_err(), "{}", bad);
+        }
+
+        let ms = Duration::from_millis;
+        let start = Instant::now();
+        let mut recorder = TimeseriesRecorder::new(TimelineGranularity::Step { every: 1 }, start).with_max_step_points(8);
+        recorder.start_epoch(1, start);
+        for step in 1..=20 {
+            recorder.record_step(step, ms(1), ms(3), ms(4), 2, 1024);
+        }
+        recorder.record_checkpoint(20, 1, start, ms(30), true, 0);
+        recorder.end_epoch(ms(100));
+        let report = recorder.report().unwrap();
+        assert_eq!(report.checkpoints.len(), 1);
+        assert!(report.checkpoints[0].loading_paused && report.checkpoints[0].duration_s == 0.03);
+        // Capped: the interval doubled until the rows fit, keeping evenly spaced steps
+        assert!(report.steps.len() < 8);
+        let interval = report.step_interval.unwrap();
+        assert_eq!(interval, 4);
+        assert!(report.steps.iter().all(|p| p.step.is_multiple_of(interval)));
+        assert_eq!(report.steps.first().map(|p| p.step), Some(4));
+        // Epoch totals still count every step
+        let epoch = &report.epochs[0];
+        assert_eq!((epoch.steps, epoch.samples, epoch.bytes), (20, 40, 20 * 1024));
+        assert!((epoch.compute_s - 0.06).abs() < 1e-9 && (epoch.au - 0.6).abs() < 1e-9);
+        assert!((epoch.samples_per_s - 400.0).abs() < 1e-9);
+
+        let mut epochs_only = TimeseriesRecorder::new(TimelineGranularity::Epoch, start);
+        epochs_only.start_epoch(1, start);
+        epochs_only.record_step(1, ms(1), ms(1), ms(2), 1, 1);
+        epochs_only.end_epoch(ms(2));
+        let report = epochs_only.report().unwrap();
+        assert!(report.steps.is_empty() && report.step_interval.is_none());
+        assert_eq!(report.epochs.len(), 1);
+        assert!(TimeseriesRecorder::new(TimelineGranularity::Off, start).report().is_none());
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/units.rs
+//
+// Byte and throughput units used by every report
+//
+//   decimal   KB = 10^3,  MB = 10^6,  GB = 10^9     (MB/s, GB/s)
+//   binary    KiB = 2^10, MiB = 2^20, GiB = 2^30    (MiB/s, GiB/s)
+//
+// GiB is 7.4% larger than GB, which is exactly the gap vendors argue about when
+// one side quotes decimal and the other binary. Code computing a figure uses
+// these constants and labels it with the matching unit; JSON throughput objects
+// carry both (`Throughput`). Flat keys keep their suffix (`_gib_s`, `_mib_s`)
+// as the unit. The one exception is the DLIO/MLPerf summary, whose
+// `train_io_*_MB_per_second` keys are MiB/s in DLIO itself and are kept that way.
+//
+use serde::Serialize;
+
+pub const KB: f64 = 1e3;
+pub const MB: f64 = 1e6;
+pub const GB: f64 = 1e9;
+pub const KIB: f64 = 1024.0;
+pub const MIB: f64 = 1_048_576.0;
+pub const GIB: f64 = 1_073_741_824.0;
+
+pub fn mb(bytes: u64) -> f64 {
+    bytes as f64 / MB
+}
+
+pub fn gb(bytes: u64) -> f64 {
+    bytes as f64 / GB
+}
+
+pub fn mib(bytes: u64) -> f64 {
+    bytes as f64 / MIB
+}
+
+pub fn gib(bytes: u64) -> f64 {
+    bytes as f64 / GIB
+}
+
+/// A byte count in both unit systems, for logs: "1.50 GiB (1.61 GB)"
+pub fn fmt_bytes(bytes: u64) -> String {
+    if bytes as f64 >= GIB {
+        format!("{:.2} GiB ({:.2} GB)", gib(bytes), gb(bytes))
+    } else {
+        format!("{:.2} MiB ({:.2} MB)", mib(bytes), mb(bytes))
+    }
+}
+
+/// One throughput in decimal and binary units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Throughput {
+    pub bytes_per_s: f64,
+    pub mb_s: f64,
+    pub mib_s: f64,
+    pub gb_s: f64,
+    pub gib_s: f64,
+}
+
+impl Throughput {
+    pub fn from_bytes_per_s(bytes_per_s: f64) -> Self {
+        Self {
+            bytes_per_s,
+            mb_s: bytes_per_s / MB,
+            mib_s: bytes_per_s / MIB,
+            gb_s: bytes_per_s / GB,
+            gib_s: bytes_per_s / GIB,
+        }
+    }
+
+    /// `bytes` moved in `seconds` (zero when no time elapsed)
+    pub fn new(bytes: u64, seconds: f64) -> Self {
+        Self::from_bytes_per_s(if seconds > 0.0 { bytes as f64 / seconds } else { 0.0 })
+    }
+
+    pub fn from_gib_s(gib_s: f64) -> Self {
+        Self::from_bytes_per_s(gib_s * GIB)
+    }
+
+    /// "812.4 MiB/s (851.9 MB/s)", or GiB/s and GB/s from 1 GiB/s up
+    pub fn display(&self) -> String {
+        if self.gib_s >= 1.0 {
+            format!("{:.2} GiB/s ({:.2} GB/s)", self.gib_s, self.gb_s)
+        } else {
+            format!("{:.1} MiB/s ({:.1} MB/s)", self.mib_s, self.mb_s)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_do_not_drift() {
+        assert_eq!((KIB * KIB, MIB * KIB), (MIB, GIB));
+        assert_eq!((KB * KB, MB * KB), (MB, GB));
+        assert!((GIB / GB - 1.073_741_824).abs() < 1e-12, "GiB is 7.37% larger than GB");
+
+        let t = Throughput::new(3 * GIB as u64, 2.0);
+        assert_eq!(t.gib_s, 1.5);
+        assert_eq!(t.mib_s, 1536.0);
+        assert!((t.gb_s - 1.610_612_736).abs() < 1e-9);
+        assert!((t.mb_s - 1610.612_736).abs() < 1e-6);
+        assert!((t.gb_s / t.gib_s - GIB / GB).abs() < 1e-12);
+        assert_eq!(Throughput::from_gib_s(1.5), t);
+        assert_eq!(t.display(), "1.50 GiB/s (1.61 GB/s)");
+        assert_eq!(Throughput::new(100, 0.0).bytes_per_s, 0.0);
+
+        assert_eq!(fmt_bytes(512 * MIB as u64), "512.00 MiB (536.87 MB)");
+
+        // Run metrics carry the flat GiB/s key and both unit systems, consistently
+        let metrics = crate::metrics::Metrics::new();
+        metrics.record_bytes_read(10 * GIB as u64);
+        metrics.record_epoch_time(std::time::Duration::from_secs(4));
+        let config = crate::dlio_compat::DlioConfig::from_yaml("dataset:\n  data_folder: file:///tmp/d\nreader: {}\n").unwrap();
+        let json = metrics.to_json(0, &config);
+        let m = &json["metrics"];
+        assert_eq!(m["storage_throughput_gib_s"], 2.5);
+        assert_eq!(m["storage_throughput"]["gib_s"], 2.5);
+        assert_eq!(m["storage_throughput"]["mib_s"], 2560.0);
+        assert!((m["storage_throughput"]["gb_s"].as_f64().unwrap() - 2.684_354_56).abs() < 1e-9);
+    }
+}
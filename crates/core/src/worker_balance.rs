@@ -0,0 +1,201 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// src/worker_balance.rs
+//
+// Loader worker fairness ("worker_balance")
+//
+// The native loader keeps up to `read_threads` sample reads in flight. Each
+// read takes a pool slot (a logical worker) from a FIFO free list for its
+// duration, the way a worker thread picks up the next key when it goes idle,
+// so a slot stuck behind slow keys serves fewer requests than its peers.
+// Per-slot requests, bytes and latency are reported together with the
+// spread across slots; a busiest slot well above the mean points at skewed
+// keys (hot prefixes, a slow shard) rather than a uniformly slow store.
+// The s3dlio loader does not expose its workers, so its runs have no section.
+// With a read log, each read's latency and size is also kept until taken,
+// for cache hit inference (`cache_warming`).
+//
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::warn;
+
+use crate::backends::stats::{ReadStats, ReadSummary, ReadWindow};
+
+/// Busiest worker's requests over the mean at which the pool counts as skewed
+pub const SKEW_RATIO: f64 = 1.5;
+
+/// Requests per worker needed before skew is judged
+const MIN_REQUESTS_PER_WORKER: u64 = 10;
+
+/// Per-slot statistics of one loader pool
+pub struct WorkerBalance {
+    slots: Vec<ReadStats>,
+    free: Mutex<VecDeque<usize>>,
+    window: ReadWindow,
+    /// (latency ms, bytes) of reads since the log was last taken
+    read_log: Option<Mutex<Vec<(f64, u64)>>>,
+}
+
+/// One worker's share of the reads
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerSummary {
+    pub worker: usize,
+    #[serde(flatten)]
+    pub reads: ReadSummary,
+}
+
+/// Worker-balance section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerBalanceReport {
+    pub workers: usize,
+    pub requests: u64,
+    pub bytes: u64,
+    /// Workers that served no request
+    pub idle_workers: usize,
+    /// Busiest worker's requests over the mean
+    pub max_over_mean: f64,
+    /// Coefficient of variation of requests per worker
+    pub request_cv: f64,
+    pub skewed: bool,
+    pub per_worker: Vec<WorkerSummary>,
+}
+
+/// A read in progress on one slot; the slot returns to the pool when dropped
+pub struct SlotGuard<'a> {
+    balance: &'a WorkerBalance,
+    slot: usize,
+}
+
+impl SlotGuard<'_> {
+    /// Record one completed read
+    pub fn record(&self, start: Instant, bytes: usize) {
+        let end = Instant::now();
+        self.balance.slots[self.slot].record(start, end, bytes);
+        self.balance.window.extend(start, end);
+        if let Some(log) = &self.balance.read_log {
+            log.lock().unwrap().push(((end - start).as_secs_f64() * 1000.0, bytes as u64));
+        }
+    }
+
+    pub fn record_error(&self) {
+        self.balance.slots[self.slot].record_error();
+    }
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        self.balance.free.lock().unwrap().push_back(self.slot);
+    }
+}
+
+impl WorkerBalance {
+    pub fn new(workers: usize) -> Self {
+        let workers = workers.max(1);
+        Self {
+            slots: (0..workers).map(|_| ReadStats::default()).collect(),
+            free: Mutex::new((0..workers).collect()),
+            window: ReadWindow::default(),
+            read_log: None,
+        }
+    }
+
+    /// Also keep each read's latency and size until `take_read_log`
+    pub fn with_read_log(mut self) -> Self {
+        self.read_log = Some(Mutex::new(Vec::new()));
+        self
+    }
+
+    /// (latency ms, bytes) of the reads since the last call; empty without a read log
+    pub fn take_read_log(&self) -> Vec<(f64, u64)> {
+        self.read_log.as_ref().map(|log| std::mem::take(&mut *log.lock().unwrap())).unwrap_or_default()
+    }
+
+    /// Take the longest-idle slot; the loader never has more reads in flight than slots
+    pub fn acquire(&self) -> SlotGuard<'_> {
+        let slot = self.free.lock().unwrap().pop_front().expect("more loader reads in flight than workers");
+        SlotGuard { balance: self, slot }
+    }
+
+    /// Slots currently serving a read
+    pub fn in_flight(&self) -> usize {
+        self.slots.len() - self.free.lock().unwrap().len()
+    }
+
+    /// Reads completed and failed so far, across all slots
+    pub fn totals(&self) -> (u64, u64) {
+        self.slots.iter().fold((0, 0), |(reads, errors), s| (reads + s.reads(), errors + s.errors()))
+    }
+
+    pub fn report(&self) -> WorkerBalanceReport {
+        let total: u64 = self.slots.iter().map(|s| s.reads()).sum();
+        let window_s = self.window.seconds();
+        let per_worker: Vec<WorkerSummary> = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(worker, stats)| WorkerSummary { worker, reads: stats.summary(window_s, total) })
+            .collect();
+        let n = per_worker.len() as f64;
+        let mean = total as f64 / n;
+        let max = per_worker.iter().map(|w| w.reads.reads).max().unwrap_or(0) as f64;
+        let variance = per_worker.iter().map(|w| (w.reads.reads as f64 - mean).powi(2)).sum::<f64>() / n;
+        let max_over_mean = if mean > 0.0 { max / mean } else { 0.0 };
+        let report = WorkerBalanceReport {
+            workers: per_worker.len(),
+            requests: total,
+            bytes: per_worker.iter().map(|w| w.reads.bytes).sum(),
+            idle_workers: per_worker.iter().filter(|w| w.reads.reads == 0).count(),
+            max_over_mean,
+            request_cv: if mean > 0.0 { variance.sqrt() / mean } else { 0.0 },
+            skewed: total >= MIN_REQUESTS_PER_WORKER * per_worker.len() as u64 && max_over_mean > SKEW_RATIO,
+            per_worker,
+        };
+        if report.skewed {
+            warn!("⚠️  Loader worker skew: busiest worker served {:.1}x the mean requests (cv {:.2}); check key distribution",
+                  report.max_over_mean, report.request_cv);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_rotate_and_skew_is_flagged() {
+        let balance = WorkerBalance::new(4);
+        // Sequential reads rotate through every slot: balanced
+        for _ in 0..40 {
+            balance.acquire().record(Instant::now(), 100);
+        }
+        let even = balance.report();
+        assert_eq!((even.requests, even.bytes, even.idle_workers), (40, 4000, 0));
+        assert!(even.per_worker.iter().all(|w| w.reads.reads == 10 && w.reads.read_share == 0.25));
+        assert!(!even.skewed && even.request_cv == 0.0);
+
+        // Three slots held by slow reads while the last one keeps serving
+        let slow: Vec<SlotGuard> = (0..3).map(|_| balance.acquire()).collect();
+        assert_eq!(balance.in_flight(), 3);
+        for _ in 0..60 {
+            balance.acquire().record(Instant::now(), 100);
+        }
+        slow.iter().for_each(|guard| guard.record(Instant::now(), 100));
+        drop(slow);
+        assert_eq!((balance.in_flight(), balance.totals()), (0, (103, 0)));
+        assert!(balance.take_read_log().is_empty());
+        let skewed = balance.report();
+        assert_eq!(skewed.per_worker[0].reads.reads, 11);
+        assert_eq!(skewed.per_worker[3].reads.reads, 70);
+        assert!(skewed.skewed && skewed.max_over_mean > SKEW_RATIO, "{:?}", skewed.max_over_mean);
+
+        let logged = WorkerBalance::new(2).with_read_log();
+        logged.acquire().record(Instant::now(), 100);
+        logged.acquire().record(Instant::now(), 200);
+        assert_eq!(logged.take_read_log().iter().map(|r| r.1).collect::<Vec<_>>(), [100, 200]);
+        assert!(logged.take_read_log().is_empty());
+    }
+}
@@ -3,12 +3,42 @@
 
 use anyhow::{Context, Result};
 use futures_util::StreamExt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
-use crate::dlio_compat::DlioConfig;
+use crate::arrivals::{ArrivalRecorder, ArrivalTrace};
+use crate::timeseries::{TimelineGranularity, Timeseries, TimeseriesRecorder};
+use crate::backends;
+use crate::batch_channel::LoaderHandle;
+use crate::batching::{SampleBatch, SampleBatcher};
+use crate::shuffle::{self, AccessOrder, EpochShuffle, OrderDigest, PlannedBatch, ShuffleReport, ShuffleStrategy};
+use crate::rng::SeedTree;
+use crate::components;
+use crate::multipart::MultipartSettings;
+use crate::compute::ComputeSimulator;
+use crate::schedule::Schedule;
+use crate::failover::{FailoverReport, FailoverSettings, FailoverTracker, RetryingBackend};
+use crate::request_timing::{RequestTimingReport, RequestTimings, TimedBackend};
+use crate::worker_balance::{WorkerBalance, WorkerBalanceReport};
+use crate::pool_stats::{PoolInfo, PoolSampler, PoolStatsReport};
+use crate::cache_warming::{CacheWarming, CacheWarmingReport};
+use crate::oplog::{OpLogWriter, RecordingBackend};
+use crate::dataset_growth::{DatasetGrowth, DatasetGrowthReport, GrowthSettings};
+use crate::tier_migration::{MigrationSettings, TierMigration, TierMigrationReport};
+use crate::checkpoint::{CheckpointReport, CheckpointSettings, Checkpointer};
+use crate::evaluation::{EvalReport, EvalSettings, Evaluator};
+use crate::epoch_boundary::{EpochBoundary, EpochBoundaryPolicy, EpochBoundaryReport};
+use crate::list_interference::{ListInterference, ListInterferenceReport, ListInterferenceSettings};
+use crate::control::{ControlServer, DelayedBackend, MetricsSnapshot, RunControl, TimelineEvent};
+use crate::dlio_compat::{Concurrency, DlioConfig, ThreadModel};
+use crate::exit_status::ExitStatus;
 use crate::metrics::Metrics;
+use crate::phases::PhaseTimes;
+use crate::plugins::{
+    ContentVerificationPlugin, ContentVerificationReport, DecodeValidationPlugin, DecodeValidationReport,
+    PluginManager,
+};
 
 // Import s3dlio 0.8.0 functionality - using new advanced API
 use s3dlio::api::advanced::{AsyncPoolDataLoader, MultiBackendDataset, PoolConfig};
@@ -24,6 +54,47 @@ pub struct WorkloadRunner {
     rank: u32,
     world_size: u32,
     file_list: Option<Vec<String>>,
+    arrivals: Arc<ArrivalRecorder>,
+    plugins: PluginManager,
+    decode_report: Option<Arc<Mutex<DecodeValidationReport>>>,
+    /// Seeded content verification; its backend wrapper checks each object as it is read
+    content_verification: Option<ContentVerificationPlugin>,
+    shuffle_report: Option<ShuffleReport>,
+    compute: Option<ComputeSimulator>,
+    control_socket: Option<std::path::PathBuf>,
+    control: Option<Arc<RunControl>>,
+    /// The schedule injects latency, so reads go through a DelayedBackend
+    injects_latency: bool,
+    oplog: Option<Arc<OpLogWriter>>,
+    failover: Option<Arc<FailoverTracker>>,
+    list_interference: Option<Arc<ListInterference>>,
+    request_timing: Option<RequestTimingReport>,
+    worker_balance: Option<WorkerBalanceReport>,
+    pool_stats: Option<PoolStatsReport>,
+    cache_warming: Option<CacheWarmingReport>,
+    dataset_growth: Option<DatasetGrowthReport>,
+    tier_migration: Option<TierMigrationReport>,
+    checkpointing: Option<CheckpointReport>,
+    evaluation: Option<EvalReport>,
+    epoch_boundary: Option<EpochBoundaryReport>,
+    concurrency: Option<Concurrency>,
+    backend_report: Option<serde_json::Value>,
+    phases: Arc<PhaseTimes>,
+    timeline_granularity: TimelineGranularity,
+    timeseries: Option<Timeseries>,
+    shuffle_seed: Option<u64>,
+    access_order: bool,
+}
+
+/// One epoch's background loader and the order it reads in
+struct EpochLoader {
+    loader: LoaderHandle,
+    seed: u64,
+    file_order_digest: Option<String>,
+    access_order: Option<AccessOrder>,
+    access_sample: Vec<String>,
+    /// Sample-shuffle plan for the epoch's batches
+    plan: Option<Vec<PlannedBatch>>,
 }
 
 impl WorkloadRunner {
@@ -33,14 +104,54 @@ impl WorkloadRunner {
             warn!("Could not load .env file: {}", e);
         }
 
+        let metrics = Arc::new(Metrics::new());
+        let mut plugins = PluginManager::new();
+        let decode_validation = DecodeValidationPlugin::from_config(&config, Arc::clone(&metrics));
+        let decode_report = decode_validation.as_ref().map(|p| p.report());
+        if let Some(plugin) = decode_validation {
+            plugins.push(Box::new(plugin));
+        }
+        let content_verification = ContentVerificationPlugin::from_config(&config);
+        if let Some(plugin) = &content_verification {
+            plugins.push(Box::new(plugin.clone()));
+        }
+
         Self {
             config: Arc::new(config),
-            metrics: Arc::new(Metrics::new()),
+            metrics,
             accelerators: 1, // Default to 1 accelerator
             strict_au: false, // Default to non-strict mode
             rank: 0, // Default to single-process mode
             world_size: 1,
             file_list: None,
+            arrivals: Arc::new(ArrivalRecorder::new()),
+            plugins,
+            decode_report,
+            content_verification,
+            shuffle_report: None,
+            compute: None,
+            control_socket: None,
+            control: None,
+            injects_latency: false,
+            oplog: None,
+            failover: None,
+            list_interference: None,
+            request_timing: None,
+            worker_balance: None,
+            pool_stats: None,
+            cache_warming: None,
+            dataset_growth: None,
+            tier_migration: None,
+            checkpointing: None,
+            evaluation: None,
+            epoch_boundary: None,
+            concurrency: None,
+            backend_report: None,
+            phases: Arc::new(PhaseTimes::new()),
+            timeline_granularity: TimelineGranularity::Epoch,
+            timeseries: None,
+            shuffle_seed: None,
+            access_order: false,
         }
     }
 
@@ -59,6 +170,43 @@ impl WorkloadRunner {
         self
     }
 
+    /// Accept live control commands (pause, resume, pacing, metrics dumps) on a Unix socket
+    pub fn with_control_socket(mut self, path: std::path::PathBuf) -> Self {
+        self.control_socket = Some(path);
+        self
+    }
+
+    /// Record list/train/checkpoint wall-clock times into `phases`, shared with the caller
+    pub fn with_phase_times(mut self, phases: Arc<PhaseTimes>) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    /// Log the training phase's storage operations to `log` (reads then go through a dl-driver backend)
+    pub fn with_oplog(mut self, log: Arc<OpLogWriter>) -> Self {
+        self.oplog = Some(log);
+        self
+    }
+
+    /// Per-epoch / per-step timeseries granularity (default: one row per epoch)
+    pub fn with_timeline(mut self, granularity: TimelineGranularity) -> Self {
+        self.timeline_granularity = granularity;
+        self
+    }
+
+    /// Shuffle with this run seed instead of `reader.seed`; content and key seeds still follow `reader.seed`
+    pub fn with_shuffle_seed(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+
+    /// Digest the keys read each epoch (MLPerf access-order reports); reads then go
+    /// through a dl-driver backend, since the s3dlio loader does not expose its keys
+    pub fn with_access_order(mut self) -> Self {
+        self.access_order = true;
+        self
+    }
+
     /// Execute ONLY the training phase for DLIO compliance measurement
     /// Data generation should be done separately and is NOT measured
     pub async fn run_training_phase(&mut self) -> Result<()> {
@@ -67,11 +215,47 @@ impl WorkloadRunner {
             self.config.model
         );
 
+        // Calibrate the compute model before the measured phase starts
+        self.compute = Some(ComputeSimulator::from_config(&self.config)?);
+
+        // The control socket and the schedule live exactly as long as the training phase
+        let schedule = Schedule::from_config(&self.config).context(ExitStatus::ConfigError)?;
+        self.injects_latency = schedule.as_ref().is_some_and(Schedule::injects_latency);
+        let failover = FailoverSettings::from_config(&self.config).context(ExitStatus::ConfigError)?;
+        self.control = (self.control_socket.is_some() || schedule.is_some() || failover.is_some())
+            .then(|| Arc::new(RunControl::default()));
+        self.failover = failover.zip(self.control.clone()).map(|(settings, control)| {
+            info!("🧯 Failover test mode: reads retry every {:?}, window marked via control socket or schedule", settings.retry_delay);
+            Arc::new(FailoverTracker::new(settings, control))
+        });
+        let _control_server = match (&self.control_socket, &self.control) {
+            (Some(path), Some(control)) => {
+                let (metrics, config, rank) = (Arc::clone(&self.metrics), Arc::clone(&self.config), self.rank);
+                let snapshot: MetricsSnapshot = Arc::new(move || metrics.to_json(rank, &config));
+                Some(ControlServer::bind(path, Arc::clone(control), snapshot)?)
+            }
+            _ => None,
+        };
+        let schedule_task = match (schedule, &self.control) {
+            (Some(schedule), Some(control)) => Some(schedule.spawn(Arc::clone(control), Arc::clone(&self.config))),
+            _ => None,
+        };
+        let list_settings = ListInterferenceSettings::from_config(&self.config).context(ExitStatus::ConfigError)?;
+        self.list_interference = list_settings.map(|settings| Arc::new(ListInterference::new(settings)));
+        let list_task = match &self.list_interference {
+            Some(lister) => Some(lister.spawn(&self.config).context(ExitStatus::StorageError)?),
+            None => None,
+        };
+
         // Only measure the training phase - data generation is separate
         let training_start = Instant::now();
         
         info!("Phase: Training (MEASURED for AU calculation)");
-        self.run_training().await?;
+        let outcome = self.run_training().await;
+        for task in [schedule_task, list_task].into_iter().flatten() {
+            task.abort();
+        }
+        outcome?;
         
         let training_time = training_start.elapsed();
         info!("Training phase completed in {:?}", training_time);
@@ -79,7 +263,7 @@ impl WorkloadRunner {
         // Record training time (NOT total time) for AU calculation
         self.metrics.set_total_time(training_time);
         self.metrics.print_summary();
-        
+
         // Calculate Accelerator Utilization (AU) if metric configuration is present
         debug!("Checking for metric configuration");
         if let Some(metric_config) = &self.config.metric {
@@ -104,7 +288,7 @@ impl WorkloadRunner {
                             return Err(anyhow::anyhow!(
                                 "Strict AU mode: AU {:.1}% is below threshold {:.1}% - storage system is too slow for MLPerf compliance", 
                                 au_result.au_percent, threshold * 100.0
-                            ));
+                            ).context(ExitStatus::AuFail));
                         }
                     }
                 } else {
@@ -205,22 +389,236 @@ impl WorkloadRunner {
     /// TRUE DLIO PARALLEL I/O MODEL - Background workers + instant batch retrieval
     async fn run_training(&mut self) -> Result<()> {
         let epochs = self.config.train.as_ref().and_then(|t| t.epochs).unwrap_or(1);
-        let batch_size = self.config.reader.batch_size.unwrap_or(16);
-        let read_threads = self.config.reader.read_threads.unwrap_or(8) as usize;
+        // batch_size counts samples; loaders fetch just enough files per sample batch
+        let sizing = SampleBatcher::from_config(&self.config);
+        let batch_size = sizing.batch_size();
+        let thread_model = ThreadModel::from_config(&self.config).context(ExitStatus::ConfigError)?;
         let prefetch_size = self.config.reader.prefetch.unwrap_or(4);
 
-        info!("🚀 TRUE DLIO PARALLEL MODEL: {} epochs, batch_size={}, read_threads={}, prefetch_queue={}", 
-              epochs, batch_size, read_threads, prefetch_size);
+        info!("🚀 TRUE DLIO PARALLEL MODEL: {} epochs, batch_size={}, read_threads={:?} ({} thread model), prefetch_queue={}", 
+              epochs, batch_size, self.config.reader.read_threads, thread_model.name(), prefetch_size);
 
-        // Create s3dlio dataset
-        let data_folder = &self.config.dataset.data_folder;
-        let dataset = self.create_multi_backend_dataset(data_folder).await?;
-        let total_files = dataset.len();
-        
-        info!("📂 Dataset: {} files, ~{} batches per epoch", total_files, (total_files + batch_size - 1) / batch_size);
+        // Native dl-driver backend (e.g. https://) or s3dlio dataset. Multi-component
+        // datasets always use a DataBackend so each sample's objects stay together.
+        let list_start = Instant::now();
+        self.phases.mark(crate::startup::LIST_START);
+        let data_folder = self.config.dataset.data_folder.clone();
+        let comps = components::components(&self.config);
+        let mut native_backend = backends::backend_for_config(&self.config, self.file_list.as_deref())?;
+        // Failover tests also read through a DataBackend, so failed reads can be retried,
+        // as does request timing, so each GET can be timed
+        let timings = self.config.storage.as_ref().and_then(|s| s.request_timing).unwrap_or(false)
+            .then(|| Arc::new(RequestTimings::default()));
+        // and tier migration, so reads of migrated files can be redirected, and the op-log
+        let migration = MigrationSettings::from_config(&self.config).context(ExitStatus::ConfigError)?;
+        // and sample shuffling, so each batch can fetch the files its samples live in,
+        // and access-order digests and content verification, so the keys read are known
+        let shuffle_strategy = ShuffleStrategy::from_config(&self.config)?;
+        // and scheduled latency injection, so it delays reads rather than training steps,
+        // and a storage.multipart section, so large objects are read with ranged GETs
+        let multipart = self.config.storage.as_ref().is_some_and(|s| s.multipart.is_some())
+            .then(|| MultipartSettings::from_config(&self.config));
+        let store_components = native_backend.is_none()
+            && (comps.is_some() || self.failover.is_some() || timings.is_some() || migration.is_some() || self.oplog.is_some()
+                || shuffle_strategy == ShuffleStrategy::Sample || self.access_order || multipart.is_some()
+                || self.content_verification.is_some() || self.injects_latency);
+        if store_components {
+            if timings.is_some() {
+                warn!("⚠️  storage.request_timing reads through dl-driver's per-object reader instead of the s3dlio loader; \
+                       timings describe that read path");
+            }
+            let backend = backends::ObjectStoreBackend::new(&data_folder)?;
+            native_backend = Some(Arc::new(match multipart {
+                Some(settings) => backend.with_multipart(settings),
+                None => backend,
+            }));
+        }
+        let tier = match (migration, &native_backend) {
+            (Some(settings), Some(backend)) => Some(Arc::new(TierMigration::new(settings, &self.config, backend.clone())?)),
+            _ => None,
+        };
+        if let Some(tier) = &tier {
+            native_backend = Some(tier.clone() as Arc<dyn backends::DataBackend>);
+        }
+        // Injected latency slows storage itself, so every wrapper above sees it
+        if let Some(control) = self.control.as_ref().filter(|_| self.injects_latency) {
+            native_backend = native_backend
+                .map(|backend| Arc::new(DelayedBackend::new(backend, Arc::clone(control))) as Arc<dyn backends::DataBackend>);
+        }
+        if let Some(log) = &self.oplog {
+            native_backend = native_backend
+                .map(|backend| Arc::new(RecordingBackend::new(backend, Arc::clone(log), &data_folder)) as Arc<dyn backends::DataBackend>);
+        }
+        if let Some(tracker) = &self.failover {
+            native_backend = native_backend
+                .map(|backend| Arc::new(RetryingBackend::new(backend, Arc::clone(tracker))) as Arc<dyn backends::DataBackend>);
+        }
+        if let Some(timings) = &timings {
+            native_backend = native_backend
+                .map(|backend| Arc::new(TimedBackend::new(backend, Arc::clone(timings))) as Arc<dyn backends::DataBackend>);
+        }
+        // Outermost, so request timings exclude the check and it sees the key that was asked for
+        if let Some(verification) = &self.content_verification {
+            native_backend = native_backend.map(|backend| Arc::new(verification.backend(backend)) as Arc<dyn backends::DataBackend>);
+        }
+        let (mut dataset, mut native_groups) = match &native_backend {
+            Some(backend) => {
+                // Rank file lists are already applied by native backends
+                let uris = match &self.file_list {
+                    Some(list) if store_components => list.clone(),
+                    _ => backend.list().await.context(ExitStatus::StorageError)?,
+                };
+                let groups = match comps {
+                    Some(comps) => components::group_by_sample(uris, comps)?,
+                    None => backends::single_object_groups(uris),
+                };
+                (None, groups)
+            }
+            None => (
+                Some(self.create_multi_backend_dataset(&data_folder).await.context(ExitStatus::StorageError)?),
+                Vec::new(),
+            ),
+        };
+        let mut total_files = dataset.as_ref().map_or(native_groups.len(), |d| d.len());
+
+        // read_threads maps to workers and in-flight requests per the thread model
+        let concurrency = thread_model.concurrency(
+            self.config.reader.read_threads,
+            native_backend.as_ref().map_or("s3dlio", |b| b.name()),
+            native_backend.is_some(),
+        );
+        info!("🧵 Concurrency ({}): {} workers, up to {} requests in flight ({} loader)",
+              concurrency.thread_model, concurrency.workers, concurrency.max_inflight, concurrency.loader);
+        let read_threads = concurrency.workers;
+        self.phases.record("list", list_start.elapsed());
+        let train_start = Instant::now();
+        self.phases.mark(crate::startup::TRAIN_START);
 
+        let files_per_batch = sizing.files_per_batch();
+        let total_samples_per_epoch = sizing.total_samples(total_files);
+
+        info!("📂 Dataset: {} files, {} samples, ~{} batches of {} samples per epoch ({} files per load)",
+              total_files, total_samples_per_epoch, total_samples_per_epoch.div_ceil(batch_size.max(1)),
+              batch_size, files_per_batch);
+        if let Some(bytes) = sizing.batch_bytes() {
+            info!("📏 Batch size: {} bytes ({}) = ~{} samples of {} bytes; samples per batch vary with sample size",
+                  bytes, crate::units::fmt_bytes(bytes as u64), batch_size, components::sample_bytes(&self.config));
+        }
+
+        // An unseeded run draws a shuffle seed and records it for audits
+        let (shuffle_seed, seed_configured) = match self.shuffle_seed.or(self.config.reader.seed) {
+            Some(seed) => (seed, true),
+            None => (rand::random(), false),
+        };
+        if shuffle_strategy != ShuffleStrategy::None {
+            info!("🔀 Shuffle: {:?} (seed {}{})", shuffle_strategy, shuffle_seed,
+                  if seed_configured { "" } else { ", random" });
+        }
+        let seeds = SeedTree::new(shuffle_seed, self.rank);
+        let samples_per_file = self.config.dataset.num_samples_per_file.unwrap_or(1);
+        let drop_last = self.config.reader.drop_last.unwrap_or(false);
+        let mut shuffle_report = ShuffleReport::new(shuffle_strategy, &seeds, seed_configured);
+        let mut timeseries = TimeseriesRecorder::new(self.timeline_granularity, train_start);
+        // Cache vs backend bytes per epoch; latency attribution needs the native loader's reads
+        let mut cache_warming = CacheWarming::from_config(&self.config, native_backend.is_some());
+        let read_log = cache_warming.as_ref().is_some_and(CacheWarming::needs_read_log);
+        // Native loader slots, tracked across epochs for the worker-balance report
+        let workers = native_backend.is_some().then(|| {
+            let balance = WorkerBalance::new(read_threads);
+            Arc::new(if read_log { balance.with_read_log() } else { balance })
+        });
+        // Pool telemetry, sampled in the background for the whole training phase
+        let pool_sampler = self.config.storage.as_ref().and_then(|s| s.pool_stats_interval_ms).map(|ms| {
+            let info = PoolInfo {
+                loader: if native_backend.is_some() { "native" } else { "s3dlio" }.to_string(),
+                pool_size: read_threads,
+                max_inflight: concurrency.max_inflight,
+                queue_capacity: prefetch_size * 2,
+            };
+            info!("📡 Sampling loader pool stats every {} ms", ms);
+            PoolSampler::spawn(info, Duration::from_millis(ms), workers.clone())
+        });
+        // Retries are counted per run, by reads through the sampler's wrapper
+        if let Some(sampler) = &pool_sampler {
+            native_backend = native_backend.map(|backend| Arc::new(sampler.backend(backend)) as Arc<dyn backends::DataBackend>);
+        }
+        let mut growth = match GrowthSettings::from_config(&self.config).context(ExitStatus::ConfigError)? {
+            Some(_) if self.file_list.is_some() => {
+                return Err(anyhow::anyhow!("dataset_growth re-lists the dataset between epochs; it cannot be combined with --filelist")
+                    .context(ExitStatus::ConfigError));
+            }
+            Some(settings) => Some(DatasetGrowth::new(settings, &self.config, self.rank)?),
+            None => None,
+        };
+        let mut checkpointer = match CheckpointSettings::from_config(&self.config, self.rank, self.world_size)
+            .context(ExitStatus::ConfigError)?
+        {
+            Some(settings) => {
+                if settings.pause_loading && native_backend.is_none() {
+                    warn!("⚠️  checkpointing.pause_loading on the s3dlio loader only stops pulling batches; \
+                           s3dlio's pool keeps reading ahead (up to {} batches) during checkpoint writes",
+                          prefetch_size * 2);
+                }
+                Some(Checkpointer::new(settings).context(ExitStatus::StorageError)?)
+            }
+            None => None,
+        };
+        let mut evaluator = match EvalSettings::from_config(&self.config).context(ExitStatus::ConfigError)? {
+            Some(settings) => Some(Evaluator::new(settings, &self.config, self.rank, self.world_size).context(ExitStatus::StorageError)?),
+            None => None,
+        };
+        let boundary_policy = EpochBoundaryPolicy::from_config(&self.config).context(ExitStatus::ConfigError)?;
+        if boundary_policy.runs_ahead() && (growth.is_some() || tier.is_some()) {
+            return Err(anyhow::anyhow!("reader.epoch_boundary '{}' prefetches the next epoch; it cannot be combined with dataset_growth or tier_migration",
+                                       boundary_policy.name())
+                .context(ExitStatus::ConfigError));
+        }
+        let mut boundary = EpochBoundary::new(boundary_policy);
+        let mut next_loader: Option<EpochLoader> = None;
+        let mut step = 0;
+        // DLIO's train.total_training_steps ends the run early, mid-epoch if need be
+        let max_steps = self.config.train.as_ref().and_then(|t| t.total_training_steps).filter(|&s| s > 0).map(|s| s as usize);
+        let mut reached_max_steps = false;
+        let mut checkpoint_time = Duration::ZERO;
+        let mut eval_time = Duration::ZERO;
+
+        let mut awaiting_first_batch = true;
         for epoch in 0..epochs {
+            // Growing dataset: append files, then re-list so this epoch reads them
+            let (mut files_added, mut ingest_time, mut refresh_time) = (0, Duration::ZERO, Duration::ZERO);
+            if let Some(growth) = growth.as_mut().filter(|_| epoch > 0) {
+                let writer: Arc<dyn backends::DataBackend> = match &native_backend {
+                    Some(backend) => backend.clone(),
+                    None => Arc::new(backends::ObjectStoreBackend::new(&data_folder)?),
+                };
+                (files_added, ingest_time) = growth.ingest(writer.as_ref(), epoch).await.context(ExitStatus::StorageError)?;
+                let refresh_start = Instant::now();
+                match &native_backend {
+                    Some(backend) => {
+                        let uris = backend.list().await.context(ExitStatus::StorageError)?;
+                        native_groups = match components::components(&self.config) {
+                            Some(comps) => components::group_by_sample(uris, comps)?,
+                            None => backends::single_object_groups(uris),
+                        };
+                    }
+                    None => {
+                        dataset = Some(self.create_multi_backend_dataset(&data_folder).await.context(ExitStatus::StorageError)?);
+                    }
+                }
+                refresh_time = refresh_start.elapsed();
+                total_files = dataset.as_ref().map_or(native_groups.len(), |d| d.len());
+                info!("🔁 Dataset refreshed in {:?}: {} files", refresh_time, total_files);
+            }
+            if let Some(tier) = tier.as_ref().filter(|_| epoch > 0) {
+                tier.migrate(&native_groups, epoch, seeds.epoch(epoch)).await.context(ExitStatus::StorageError)?;
+            }
+
             let epoch_start = Instant::now();
+            self.arrivals.start_epoch(epoch, epoch_start);
+            timeseries.start_epoch(epoch + 1, epoch_start);
+            if let Some(cache) = &mut cache_warming {
+                cache.start_epoch();
+            }
             info!("🏃 Epoch {}/{} - Starting TRUE parallel I/O + compute", epoch + 1, epochs);
 
             let mut batch_count = 0;
@@ -228,122 +626,298 @@ impl WorkloadRunner {
             let mut total_bytes = 0;
             let mut total_io_time = Duration::ZERO;
             let mut total_compute_time = Duration::ZERO;
+            let mut epoch_checkpoint_time = Duration::ZERO;
+            // End of the previous step: the loop waits on the loader from here to the next batch
+            let mut last_step_end = epoch_start;
 
-            // === CRITICAL: TRUE DLIO PARALLEL MODEL ===
-            // Background I/O workers continuously load batches into channel
-            // Main thread gets batches instantly while background loads next batches
-            let (batch_tx, mut batch_rx) = tokio::sync::mpsc::channel::<Result<Vec<Vec<u8>>>>(prefetch_size * 2);
+            // Starts the background loader for an epoch; under a run-ahead epoch_boundary
+            // policy it is also called for the next epoch while this one is still training
+            let start_loader = |epoch: u32| -> EpochLoader {
+                // Permutation reorders the whole file list each epoch
+                let seed = seeds.epoch(epoch);
+                let mut epoch_groups = native_groups.clone();
+                let mut file_order_digest = None;
+                if shuffle_strategy == ShuffleStrategy::Permutation && native_backend.is_some() {
+                    let mut order: Vec<usize> = (0..epoch_groups.len()).collect();
+                    shuffle::permute(&mut order, seed);
+                    let mut digest = OrderDigest::default();
+                    order.iter().for_each(|&i| digest.add(i as u64));
+                    file_order_digest = Some(digest.hex());
+                    epoch_groups = order.into_iter().map(|i| native_groups[i].clone()).collect();
+                }
+                // Sample shuffle plans every batch; each loads the files its samples come from
+                let mut plan = None;
+                if shuffle_strategy == ShuffleStrategy::Sample && native_backend.is_some() {
+                    let planned = shuffle::plan_sample_batches(native_groups.len(), samples_per_file, batch_size, drop_last, seed);
+                    let mut digest = OrderDigest::default();
+                    planned.iter().flat_map(|b| &b.files).for_each(|&i| digest.add(i as u64));
+                    file_order_digest = Some(digest.hex());
+                    epoch_groups = planned.iter().flat_map(|b| &b.files).map(|&i| native_groups[i].clone()).collect();
+                    plan = Some(planned);
+                }
+                // Native loaders read in group order, so the access order is known up front
+                let access_order = native_backend.is_some().then(|| {
+                    let mut access = AccessOrder::default();
+                    epoch_groups.iter().flatten().for_each(|uri| access.record(shuffle::access_key(uri, &data_folder)));
+                    access
+                });
+                let access_sample: Vec<String> = match &native_backend {
+                    Some(_) => epoch_groups.iter().flatten().take(shuffle::ACCESS_ORDER_SAMPLE)
+                        .map(|uri| shuffle::access_key(uri, &data_folder).to_string()).collect(),
+                    None => Vec::new(),
+                };
+
+                // === CRITICAL: TRUE DLIO PARALLEL MODEL ===
+                // Background I/O workers continuously load batches into a bounded queue
+                // Main thread gets batches instantly while background loads next batches
             
-            // Configure aggressive s3dlio loading
-            let pool_config = PoolConfig {
-                pool_size: read_threads,
-                readahead_batches: prefetch_size * 2, // Aggressive prefetching
-                batch_timeout: Duration::from_secs(30),
-                max_inflight: read_threads * 4, // Very high concurrency
-            };
+                // Configure aggressive s3dlio loading
+                let pool_config = PoolConfig {
+                    pool_size: read_threads,
+                    readahead_batches: prefetch_size * 2, // Aggressive prefetching
+                    batch_timeout: Duration::from_secs(30),
+                    max_inflight: concurrency.max_inflight,
+                };
 
-            let loader_options = LoaderOptions {
-                batch_size: batch_size,
-                prefetch: prefetch_size,
-                shuffle: false, // Consistent ordering for debugging
-                num_workers: read_threads,
-                reader_mode: ReaderMode::Sequential,
-                loading_mode: LoadingMode::AsyncPool(pool_config.clone()),
-                ..Default::default()
-            };
+                let loader_options = LoaderOptions {
+                    batch_size: files_per_batch,
+                    prefetch: prefetch_size,
+                    shuffle: shuffle_strategy == ShuffleStrategy::Permutation,
+                    seed,
+                    num_workers: read_threads,
+                    reader_mode: ReaderMode::Sequential,
+                    loading_mode: LoadingMode::AsyncPool(pool_config.clone()),
+                    ..Default::default()
+                };
 
-            // === BACKGROUND I/O WORKER TASK ===
-            let dataset_clone = dataset.clone();
-            let background_io = tokio::spawn(async move {
-                info!("🔄 Background I/O workers starting with {} threads, {} prefetch", read_threads, prefetch_size);
+                // === BACKGROUND I/O WORKER TASK ===
+                let loader = if let Some(backend) = &native_backend {
+                    info!("🔄 Background I/O workers starting with {} threads ({} backend)", read_threads, backend.name());
+                    let workers = workers.clone().expect("worker balance when a native backend is used");
+                    let batch_sizes: Box<dyn Iterator<Item = usize> + Send> = match &plan {
+                        Some(plan) => Box::new(plan.iter().map(|b| b.files.len()).collect::<Vec<_>>().into_iter()),
+                        None => Box::new(std::iter::repeat(files_per_batch)),
+                    };
+                    backends::spawn_batch_loader(backend.clone(), epoch_groups, batch_sizes, read_threads, false, workers, prefetch_size * 2)
+                } else {
+                    let dataset_clone = dataset.clone().expect("s3dlio dataset when no native backend");
+                    LoaderHandle::spawn(prefetch_size * 2, move |tx| async move {
+                        info!("🔄 Background I/O workers starting with {} threads, {} prefetch", read_threads, prefetch_size);
                 
-                let async_loader = AsyncPoolDataLoader::new(dataset_clone, loader_options);
-                let mut stream = async_loader.stream_with_pool(pool_config);
+                        let async_loader = AsyncPoolDataLoader::new(dataset_clone, loader_options);
+                        let mut stream = async_loader.stream_with_pool(pool_config);
                 
-                let mut bg_batch_count = 0;
-                while let Some(batch_result) = stream.next().await {
-                    bg_batch_count += 1;
+                        let mut bg_batch_count = 0;
+                        loop {
+                            // Paused (checkpoint write): stop pulling from the pool until resumed;
+                            // the pool itself keeps reading until its readahead is full
+                            tx.resumed().await;
+                            let batch_result = tokio::select! {
+                                _ = tx.cancelled() => {
+                                    debug!("Background I/O cancelled at batch {}", bg_batch_count);
+                                    break;
+                                }
+                                next = stream.next() => match next {
+                                    Some(batch_result) => batch_result,
+                                    None => break,
+                                },
+                            };
+                            bg_batch_count += 1;
                     
-                    if batch_tx.send(batch_result.map_err(anyhow::Error::from)).await.is_err() {
-                        debug!("Main thread finished, stopping background I/O at batch {}", bg_batch_count);
-                        break;
-                    }
+                            let stamped = batch_result.map(|b| (Instant::now(), b)).map_err(anyhow::Error::from);
+                            if !tx.send(stamped).await {
+                                debug!("Main thread finished, stopping background I/O at batch {}", bg_batch_count);
+                                break;
+                            }
                     
-                    if bg_batch_count % 10 == 0 {
-                        debug!("Background I/O: loaded {} batches, queue filling continuously...", bg_batch_count);
-                    }
+                            if bg_batch_count % 10 == 0 {
+                                debug!("Background I/O: loaded {} batches, queue filling continuously...", bg_batch_count);
+                            }
+                        }
+                        info!("🛑 Background I/O completed: {} batches loaded", bg_batch_count);
+                    })
+                };
+                EpochLoader { loader, seed, file_order_digest, access_order, access_sample, plan }
+            };
+            let EpochLoader { mut loader, seed, file_order_digest, access_order, access_sample, plan } = match next_loader.take() {
+                Some(ahead) if boundary.policy() == EpochBoundaryPolicy::CarryOver => {
+                    boundary.record_carried(epoch, ahead.loader.queued() as u64);
+                    ahead
                 }
-                info!("🛑 Background I/O completed: {} batches loaded", bg_batch_count);
-            });
+                Some(ahead) => {
+                    let discarded = ahead.loader.shutdown().await;
+                    info!("⏭️  Epoch boundary: discarded {} prefetched batches ({})", discarded.0, crate::units::fmt_bytes(discarded.1));
+                    boundary.record_discarded(epoch, discarded);
+                    start_loader(epoch)
+                }
+                None => start_loader(epoch),
+            };
+            if let Some(sampler) = &pool_sampler {
+                sampler.watch_queue(loader.queue_probe());
+            }
 
             info!("⚡ PARALLEL MODE ACTIVE: Background loading batches, main thread consuming with compute overlap");
 
             // === MAIN COMPUTE THREAD ===
-            // This should get batches INSTANTLY from prefetch queue
-            while let Some(batch_result) = batch_rx.recv().await {
-                match batch_result {
-                    Ok(batch) => {
-                        let batch_start = Instant::now();
-                        
-                        // === I/O TIME MEASUREMENT ===
-                        // With proper background I/O, this should be microseconds
-                        let io_start = Instant::now();
-                        let batch_size_actual = batch.len();
-                        let batch_bytes: usize = batch.iter().map(|item| item.len()).sum();
-                        
-                        // Minimal validation (represents data preprocessing)
-                        let _checksum: u64 = batch.iter().take(1)
-                            .map(|item| item.iter().take(10).map(|&b| b as u64).sum::<u64>())
-                            .sum();
-                        let io_time = io_start.elapsed(); // Should be ~microseconds!
-                        
-                        // === COMPUTE TIME ===
-                        // While we compute, background workers load next batches = TRUE PARALLELISM
-                        let compute_start = Instant::now();
-                        self.process_batch(&batch).await?;
-                        let compute_time = compute_start.elapsed();
-                        
-                        let batch_total_time = batch_start.elapsed();
+            // This should get batches INSTANTLY from prefetch queue; file batches are
+            // repacked into batches of exactly batch_size samples (or batch_size_bytes bytes)
+            let mut batcher = SampleBatcher::from_config(&self.config).with_shuffle(shuffle_strategy, seed);
+            if let Some(plan) = plan {
+                batcher = batcher.with_plan(plan);
+            }
+            let mut loader_done = false;
+            while !loader_done {
+                let sample_batches: Vec<SampleBatch> = match loader.recv().await {
+                    Some(Ok((ready_at, files))) => batcher.push(ready_at, files),
+                    Some(Err(e)) => {
+                        error!("Background I/O error: {}", e);
+                        // Stop both loaders before returning rather than leaving them to the drop
+                        loader.shutdown().await;
+                        if let Some(ahead) = next_loader.take() {
+                            ahead.loader.shutdown().await;
+                        }
+                        return Err(e.context(ExitStatus::StorageError));
+                    }
+                    None => {
+                        loader_done = true;
+                        batcher.finish()
+                    }
+                };
+                // This epoch's files are all read: a run-ahead policy starts prefetching the next epoch
+                if boundary.policy().runs_ahead() && next_loader.is_none() && epoch + 1 < epochs
+                    && (loader_done || loader.is_finished())
+                {
+                    next_loader = Some(start_loader(epoch + 1));
+                }
 
-                        // Accumulate for AU calculation
-                        total_io_time += io_time;
-                        total_compute_time += compute_time;
-                        
-                        // Record metrics
-                        self.metrics.record_bytes_read(batch_bytes as u64);
-                        self.metrics.record_read_time(io_time);
-                        self.metrics.record_compute_time(compute_time);
-                        self.metrics.record_batch_time(batch_total_time);
-
-                        batch_count += 1;
-                        total_samples += batch_size_actual;
-                        total_bytes += batch_bytes;
-
-                        // Show parallel processing effectiveness
-                        if batch_count % 5 == 0 || batch_count < 5 {
-                            let io_ms = io_time.as_secs_f64() * 1000.0;
-                            let compute_ms = compute_time.as_secs_f64() * 1000.0;
-                            info!(
-                                "PARALLEL Batch {} | {} files, {:.1}MB | I/O: {:.2}ms, Compute: {:.1}ms | Background: loading next...",
-                                batch_count, batch_size_actual, batch_bytes as f64 / 1_000_000.0, io_ms, compute_ms
-                            );
+                for SampleBatch { ready_at, samples: batch_size_actual, bytes: batch_bytes, fetched, objects: batch } in sample_batches {
+                    if awaiting_first_batch {
+                        self.phases.mark(crate::startup::FIRST_BATCH);
+                        awaiting_first_batch = false;
+                    }
+                    let io_wait = last_step_end.elapsed();
+                    // Operator pause and pacing (control socket / schedule) hold the next step
+                    if let Some(control) = &self.control {
+                        control.checkpoint(fetched).await;
+                        if control.stop_requested() {
+                            loader_done = true;
+                            break;
                         }
                     }
-                    Err(e) => {
-                        error!("Background I/O error: {}", e);
-                        return Err(e.into());
+                    let batch_start = Instant::now();
+                    
+                    // === I/O TIME MEASUREMENT ===
+                    // With proper background I/O, this should be microseconds
+                    let io_start = Instant::now();
+                    
+                    // Minimal validation (represents data preprocessing)
+                    let _checksum: u64 = batch.iter().take(1)
+                        .map(|item| item.iter().take(10).map(|&b| b as u64).sum::<u64>())
+                        .sum();
+                    let io_time = io_start.elapsed(); // Should be ~microseconds!
+
+                    // Sampled decode validation (reported as decode latency) runs on whole
+                    // objects, in the batch holding their first sample
+                    if !batch.is_empty() {
+                        self.plugins.after_batch(&batch).await?;
+                    }
+                    
+                    // === COMPUTE TIME ===
+                    // While we compute, background workers load next batches = TRUE PARALLELISM
+                    let compute_start = Instant::now();
+                    self.process_batch(&batch).await?;
+                    let compute_time = compute_start.elapsed();
+                    
+                    let batch_total_time = batch_start.elapsed();
+
+                    // Accumulate for AU calculation
+                    total_io_time += io_time;
+                    total_compute_time += compute_time;
+                    
+                    // Record metrics: storage traffic is the whole files read, which under
+                    // sample shuffling exceeds the bytes of the samples they delivered
+                    self.metrics.record_bytes_read(fetched as u64);
+                    self.metrics.record_sample_bytes(batch_bytes as u64);
+                    self.metrics.record_samples_read(batch_size_actual as u64);
+                    self.metrics.record_read_time(io_time);
+                    self.metrics.record_wait_time(io_wait);
+                    self.metrics.record_compute_time(compute_time);
+                    self.metrics.record_batch_time(batch_total_time);
+                    self.arrivals.record_batch(ready_at, batch_start, batch_size_actual, batch_bytes, compute_time);
+                    if let Some(tracker) = &self.failover {
+                        tracker.record_batch(fetched as u64);
+                    }
+                    if let Some(lister) = &self.list_interference {
+                        lister.record_batch(fetched as u64);
+                    }
+
+                    batch_count += 1;
+                    total_samples += batch_size_actual;
+                    total_bytes += fetched;
+
+                    step += 1;
+                    timeseries.record_step(step as u64, io_wait, compute_time, io_wait + batch_total_time, batch_size_actual, batch_bytes);
+                    if let Some(ckpt) = checkpointer.as_mut().filter(|c| c.schedule().due_after_step(step)) {
+                        let (ckpt_start, queued) = (Instant::now(), loader.queued());
+                        if ckpt.pauses_loading() {
+                            loader.pause();
+                        }
+                        let stall = ckpt.write(step, epoch + 1, &self.metrics).await.context(ExitStatus::StorageError)?;
+                        loader.resume();
+                        epoch_checkpoint_time += stall;
+                        timeseries.record_checkpoint(step as u64, epoch + 1, ckpt_start, stall, ckpt.pauses_loading(),
+                                                     loader.queued().saturating_sub(queued) as u64);
+                    }
+                    last_step_end = Instant::now();
+                    if max_steps.is_some_and(|max| step >= max) {
+                        reached_max_steps = true;
+                        loader_done = true;
+                        break;
+                    }
+
+                    // Show parallel processing effectiveness
+                    if batch_count % 5 == 0 || batch_count < 5 {
+                        let io_ms = io_time.as_secs_f64() * 1000.0;
+                        let compute_ms = compute_time.as_secs_f64() * 1000.0;
+                        info!(
+                            "PARALLEL Batch {} | {} samples, {:.1} MB | I/O: {:.2}ms, Compute: {:.1}ms | Background: loading next...",
+                            batch_count, batch_size_actual, crate::units::mb(batch_bytes as u64), io_ms, compute_ms
+                        );
                     }
                 }
             }
 
-            // Wait for background task
-            if let Err(e) = background_io.await {
-                warn!("Background I/O task error: {:?}", e);
-            }
+            // Shut the loader down (it is still reading after a stop request or step limit);
+            // batches still queued then were read for nothing
+            boundary.record_discarded(epoch + 1, loader.shutdown().await);
+            shuffle_report.epochs.push(EpochShuffle {
+                epoch: epoch + 1,
+                seed,
+                file_order_digest,
+                sample_order_digest: batcher.sample_order_digest(),
+                access_order_digest: access_order.map(|a| a.hex()),
+                access_order_items: access_order.map(|a| a.items()),
+                access_order_sample: access_sample,
+                file_reads: batcher.fetched().map(|f| f.0),
+                bytes_fetched: batcher.fetched().map(|f| f.1),
+            });
             
             // === EPOCH ANALYSIS ===
-            let epoch_total_time = epoch_start.elapsed();
+            // Checkpoint stalls are reported separately rather than counted against AU
+            let epoch_total_time = epoch_start.elapsed().saturating_sub(epoch_checkpoint_time);
+            checkpoint_time += epoch_checkpoint_time;
             self.metrics.record_epoch_time(epoch_total_time);
+            if let Some(growth) = &mut growth {
+                growth.record_epoch(epoch + 1, total_files, files_added, ingest_time, refresh_time, epoch_total_time);
+            }
+            if let Some(cache) = &mut cache_warming {
+                let reads = workers.as_ref().map(|w| w.take_read_log()).unwrap_or_default();
+                cache.end_epoch(epoch + 1, total_bytes as u64, epoch_total_time, &reads);
+            }
+            self.arrivals.end_epoch(epoch_total_time);
+            timeseries.end_epoch(epoch_total_time);
+            self.metrics.record_epoch_compute_time(total_compute_time);
+            self.plugins.after_epoch(epoch).await?;
             
             let au_percentage = if epoch_total_time.as_secs_f64() > 0.0 {
                 (total_compute_time.as_secs_f64() / epoch_total_time.as_secs_f64()) * 100.0
@@ -352,8 +926,8 @@ impl WorkloadRunner {
             };
 
             info!(
-                "✅ Epoch {} COMPLETE | {} batches, {} samples, {:.1}MB in {:?}",
-                epoch + 1, batch_count, total_samples, total_bytes as f64 / 1_000_000.0, epoch_total_time
+                "✅ Epoch {} COMPLETE | {} batches, {} samples, {} in {:?}",
+                epoch + 1, batch_count, total_samples, crate::units::fmt_bytes(total_bytes as u64), epoch_total_time
             );
             
             if batch_count > 0 {
@@ -375,17 +949,77 @@ impl WorkloadRunner {
                     warn!("⚠️  HIGH AU: {:.1}% suggests sequential processing, not parallel I/O", au_percentage);
                 }
             }
+
+            if let Some(ckpt) = checkpointer.as_mut().filter(|c| c.schedule().due_after_epoch(epoch + 1)) {
+                // Only a run-ahead epoch_boundary policy has a loader reading at this point
+                let ahead = next_loader.as_ref().map(|a| &a.loader);
+                let (ckpt_start, queued) = (Instant::now(), ahead.map_or(0, |l| l.queued()));
+                if let Some(loader) = ahead.filter(|_| ckpt.pauses_loading()) {
+                    loader.pause();
+                }
+                let stall = ckpt.write(step, epoch + 1, &self.metrics).await.context(ExitStatus::StorageError)?;
+                if let Some(loader) = ahead {
+                    loader.resume();
+                }
+                checkpoint_time += stall;
+                timeseries.record_checkpoint(step as u64, epoch + 1, ckpt_start, stall, ckpt.pauses_loading(),
+                                             ahead.map_or(0, |l| l.queued().saturating_sub(queued)) as u64);
+            }
+
+            // Evaluation runs between epochs, outside the training epoch time
+            if let Some(eval) = evaluator.as_mut().filter(|e| e.settings().due_after_epoch(epoch + 1)) {
+                eval_time += eval.run(epoch + 1).await.context(ExitStatus::StorageError)?;
+            }
+
+            let stop_requested = self.control.as_ref().is_some_and(|c| c.stop_requested());
+            if stop_requested || reached_max_steps {
+                if let Some(ahead) = next_loader.take() {
+                    boundary.record_discarded(epoch + 1, ahead.loader.shutdown().await);
+                }
+                if reached_max_steps {
+                    info!("🏁 Reached train.total_training_steps ({}): ending the run after epoch {}", step, epoch + 1);
+                } else {
+                    info!("🛑 Stop requested: ending the run after epoch {}", epoch + 1);
+                }
+                break;
+            }
         }
 
-        info!("🏁 DLIO parallel training completed");
-        Ok(())
-    }
+        if let Some(growth) = &mut growth {
+            if let Err(e) = growth.cleanup().await {
+                warn!("⚠️  Failed to remove files appended by dataset_growth: {:#}", e);
+            }
+        }
+
+        // Backend read statistics, e.g. the per-source breakdown of weighted data folders
+        self.backend_report = native_backend.as_ref().and_then(|b| b.report());
+        self.request_timing = timings.zip(native_backend.as_ref()).map(|(t, b)| RequestTimingReport {
+            read_path: store_components.then(|| {
+                "dl-driver per-object reader on the s3dlio store, in place of the s3dlio loader's pool".to_string()
+            }),
+            ..t.report(b.name())
+        });
+        self.pool_stats = pool_sampler.map(|s| s.finish());
+        self.cache_warming = cache_warming.map(|c| c.report());
+        self.worker_balance = workers.map(|w| w.report());
+        self.dataset_growth = growth.map(|g| g.report());
+        self.tier_migration = tier.map(|t| t.migration_report());
+        self.checkpointing = checkpointer.map(|c| c.report());
+        self.evaluation = evaluator.map(|e| e.report());
+        self.epoch_boundary = boundary.report();
+        self.concurrency = Some(concurrency);
+        self.phases.record("train", train_start.elapsed().saturating_sub(checkpoint_time + eval_time));
+        if self.checkpointing.is_some() {
+            self.phases.record("checkpoint", checkpoint_time);
+        }
+        if self.evaluation.is_some() {
+            self.phases.record("eval", eval_time);
+        }
 
-    /// Checkpointing phase (placeholder for future implementation)
-    #[allow(dead_code)]
-    async fn run_checkpointing(&mut self) -> Result<()> {
-        info!("Checkpointing phase - placeholder");
-        // TODO: Implement checkpointing using s3dlio's checkpoint module
+        self.plugins.finalize().await?;
+        self.shuffle_report = Some(shuffle_report);
+        self.timeseries = timeseries.report();
+        info!("🏁 DLIO parallel training completed");
         Ok(())
     }
 
@@ -422,6 +1056,110 @@ impl WorkloadRunner {
         &self.metrics
     }
 
+    /// Per-batch ready/consume timing recorded during training, for replay
+    pub fn arrival_trace(&self) -> ArrivalTrace {
+        self.arrivals.trace(
+            self.config.data_folder_uri(),
+            SampleBatcher::from_config(&self.config).batch_size(),
+            self.config.reader.prefetch.unwrap_or(4),
+        )
+    }
+
+    /// Sampled decode validation results, when `validation.decode_sample_rate` is set
+    pub fn decode_validation_report(&self) -> Option<DecodeValidationReport> {
+        self.decode_report.as_ref().map(|r| r.lock().unwrap().clone())
+    }
+
+    /// Shuffle strategy and per-epoch order digests of the last training run
+    pub fn shuffle_report(&self) -> Option<&ShuffleReport> {
+        self.shuffle_report.as_ref()
+    }
+
+    /// Per-epoch and sampled per-step rows of the last training run, unless `--timeline off`
+    pub fn timeseries(&self) -> Option<&Timeseries> {
+        self.timeseries.as_ref()
+    }
+
+    /// Control and schedule events of the last training run, when either was active
+    pub fn timeline(&self) -> Option<Vec<TimelineEvent>> {
+        self.control.as_ref().map(|c| c.timeline())
+    }
+
+    /// Wall-clock time per phase recorded so far
+    pub fn phase_times(&self) -> &PhaseTimes {
+        &self.phases
+    }
+
+    /// Backend-specific read statistics of the last training run, if the backend keeps any
+    pub fn backend_report(&self) -> Option<&serde_json::Value> {
+        self.backend_report.as_ref()
+    }
+
+    /// Per-request GET timings of the last training run, when `storage.request_timing` is set
+    pub fn request_timing_report(&self) -> Option<&RequestTimingReport> {
+        self.request_timing.as_ref()
+    }
+
+    /// Effective loader concurrency (thread model mapping) of the last training run
+    pub fn concurrency(&self) -> Option<&Concurrency> {
+        self.concurrency.as_ref()
+    }
+
+    /// Per-worker request spread of the last training run, for native loaders
+    pub fn worker_balance_report(&self) -> Option<&WorkerBalanceReport> {
+        self.worker_balance.as_ref()
+    }
+
+    /// Sampled loader pool stats of the last training run, when `storage.pool_stats_interval_ms` is set
+    pub fn pool_stats_report(&self) -> Option<&PoolStatsReport> {
+        self.pool_stats.as_ref()
+    }
+
+    /// Per-epoch cache vs backend bytes, when `storage.cache_analysis` is set and bytes can be attributed
+    pub fn cache_warming_report(&self) -> Option<&CacheWarmingReport> {
+        self.cache_warming.as_ref()
+    }
+
+    /// Per-epoch ingest/refresh cost and epoch times, when `dataset_growth:` is configured
+    pub fn dataset_growth_report(&self) -> Option<&DatasetGrowthReport> {
+        self.dataset_growth.as_ref()
+    }
+
+    /// Migrations and hot/recall/cold read latencies, when `tier_migration:` is configured
+    pub fn tier_migration_report(&self) -> Option<&TierMigrationReport> {
+        self.tier_migration.as_ref()
+    }
+
+    /// Checkpoints written during the last training run, when `workflow.checkpoint` is on
+    pub fn checkpoint_report(&self) -> Option<&CheckpointReport> {
+        self.checkpointing.as_ref()
+    }
+
+    /// Evaluations run between epochs, when `workflow.evaluation` is on
+    pub fn evaluation_report(&self) -> Option<&EvalReport> {
+        self.evaluation.as_ref()
+    }
+
+    /// Prefetch carried over or discarded at epoch boundaries (`reader.epoch_boundary`)
+    pub fn epoch_boundary_report(&self) -> Option<&EpochBoundaryReport> {
+        self.epoch_boundary.as_ref()
+    }
+
+    /// Disruption around the marked failover window, when `failover:` is configured
+    pub fn failover_report(&self) -> Option<FailoverReport> {
+        self.failover.as_ref().map(|t| t.report())
+    }
+
+    /// Training impact of the background LIST load, when `list_interference:` is configured
+    pub fn list_interference_report(&self) -> Option<ListInterferenceReport> {
+        self.list_interference.as_ref().map(|l| l.report())
+    }
+
+    /// Seeded content verification results, when `validation.verify_content` is set
+    pub fn content_verification_report(&self) -> Option<ContentVerificationReport> {
+        self.content_verification.as_ref().map(|p| p.report().lock().unwrap().clone())
+    }
+
     /// Create MultiBackendDataset for unified access across all storage backends
     async fn create_multi_backend_dataset(&self, data_folder: &str) -> Result<MultiBackendDataset> {
         info!("Creating MultiBackendDataset for folder: {}", data_folder);
@@ -437,14 +1175,11 @@ impl WorkloadRunner {
 
     /// Process a batch of data (simulate training computation with exact DLIO timing)
     async fn process_batch(&self, _batch: &[Vec<u8>]) -> Result<()> {
-        // Use exact computation_time from DLIO config (per step, not per sample)
-        if let Some(computation_time) = self.config.train.as_ref().and_then(|t| t.computation_time) {
-            if computation_time > 0.0 {
-                let processing_delay = std::time::Duration::from_secs_f64(computation_time);
-                tokio::time::sleep(processing_delay).await;
-            }
+        // Exact computation_time from DLIO config (per step, not per sample), spent by the
+        // configured compute model; without computation_time there is no artificial delay
+        match &self.compute {
+            Some(compute) => compute.step().await,
+            None => Ok(()),
         }
-        // If no computation_time specified, no artificial delay (matches DLIO behavior)
-        Ok(())
     }
 }
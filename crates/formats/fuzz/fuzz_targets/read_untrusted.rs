@@ -0,0 +1,11 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Every format reader over arbitrary bytes: errors are fine, panics are bugs
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    real_dlio_formats::fuzz::read_untrusted(data);
+});
@@ -0,0 +1,16 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Open-ended TFRecord reading, record by record, as the loader streams it
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use real_dlio_formats::TfRecordReader;
+
+fuzz_target!(|data: &[u8]| {
+    for record in TfRecordReader::new(data) {
+        if record.is_err() {
+            break;
+        }
+    }
+});
@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/formats/src/csv.rs
+//
+// CSV format for DLIO `format: csv`
+//
+// A header line ("id,label,data") followed by one row per sample. Each row is
+// padded with synthetic digits in its data column to the record size, so a
+// row is one record of `record_length_bytes` (newline included).
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::{Format, FormatMetadata, StreamingFormat};
+
+/// Header line of generated CSV files
+pub const CSV_HEADER: &str = "id,label,data";
+
+/// CSV format generator and reader
+pub struct CsvFormat {
+    num_rows: usize,
+    row_size: usize,
+}
+
+impl CsvFormat {
+    /// Create with the number of rows (samples) and the size of each row in bytes
+    pub fn new(num_rows: usize, row_size: usize) -> Self {
+        CsvFormat { num_rows, row_size }
+    }
+
+    fn write_row(&self, buffer: &mut Vec<u8>, index: usize) {
+        let prefix = format!("{},{},", index, index % 10);
+        // At least one data digit, even when the prefix alone exceeds the row size
+        let digits = self.row_size.saturating_sub(prefix.len() + 1).max(1);
+        buffer.extend_from_slice(prefix.as_bytes());
+        let data = s3dlio::generate_controlled_data(digits, index, 0);
+        buffer.extend(data[..digits].iter().map(|b| b'0' + b % 10));
+        buffer.push(b'\n');
+    }
+
+    fn check_count(&self, rows: usize) -> Result<()> {
+        if rows != self.num_rows {
+            anyhow::bail!("CSV row count mismatch: expected {} rows, got {}", self.num_rows, rows);
+        }
+        Ok(())
+    }
+}
+
+impl Format for CsvFormat {
+    fn generate(&self, path: &Path) -> Result<()> {
+        let bytes = self.generate_bytes("")?;
+        std::fs::write(path, bytes).with_context(|| format!("Failed to write CSV file at {:?}", path))
+    }
+
+    fn read(&self, path: &Path) -> Result<()> {
+        let data = std::fs::read(path).with_context(|| format!("Failed to open CSV file at {:?}", path))?;
+        self.read_from_bytes(&data).with_context(|| format!("Invalid CSV file {:?}", path))
+    }
+}
+
+impl StreamingFormat for CsvFormat {
+    fn generate_bytes(&self, _filename: &str) -> Result<Vec<u8>> {
+        let mut buffer = Vec::with_capacity(CSV_HEADER.len() + 1 + self.num_rows * self.row_size);
+        buffer.extend_from_slice(CSV_HEADER.as_bytes());
+        buffer.push(b'\n');
+        for i in 0..self.num_rows {
+            self.write_row(&mut buffer, i);
+        }
+        Ok(buffer)
+    }
+
+    fn read_from_bytes(&self, data: &[u8]) -> Result<()> {
+        self.check_count(scan_csv(data)?.len())
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "csv"
+    }
+
+    fn format_metadata(&self) -> FormatMetadata {
+        FormatMetadata {
+            expected_size_bytes: Some(CSV_HEADER.len() + 1 + self.num_rows * self.row_size),
+            compression_ratio: Some(1.0),
+            is_binary: false,
+            supports_streaming: true,
+        }
+    }
+}
+
+/// Validate a CSV object and return the size of each data row (newline included)
+///
+/// The first line is a header; every row must have as many fields as the header.
+/// Files dl-driver did not generate are accepted as long as they are well-formed.
+pub fn scan_csv(data: &[u8]) -> Result<Vec<usize>> {
+    let text = std::str::from_utf8(data).context("CSV is not valid UTF-8")?;
+    let mut lines = text.split_inclusive('\n');
+    let header = lines.next().context("Empty CSV (no header line)")?;
+    let columns = header.trim_end_matches(['\r', '\n']).split(',').count();
+
+    let mut sizes = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let fields = line.trim_end_matches(['\r', '\n']).split(',').count();
+        if fields != columns {
+            anyhow::bail!("CSV row {} has {} fields, header has {}", i, fields, columns);
+        }
+        sizes.push(line.len());
+    }
+    Ok(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_generate_and_scan() {
+        let fmt = CsvFormat::new(5, 64);
+        let bytes = fmt.generate_bytes("a.csv").unwrap();
+        assert!(bytes.starts_with(b"id,label,data\n"));
+        assert_eq!(scan_csv(&bytes).unwrap(), vec![64; 5]);
+        fmt.read_from_bytes(&bytes).unwrap();
+        assert!(CsvFormat::new(4, 64).read_from_bytes(&bytes).is_err());
+
+        // Rows shorter than their id prefix still get a data column
+        assert_eq!(scan_csv(&CsvFormat::new(2, 3).generate_bytes("").unwrap()).unwrap().len(), 2);
+
+        assert!(scan_csv(b"id,label,data\n1,2\n").is_err());
+        assert!(scan_csv(b"").is_err());
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fmt.generate(tmp.path()).unwrap();
+        fmt.read(tmp.path()).unwrap();
+    }
+}
@@ -0,0 +1,306 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/formats/src/decode.rs
+//
+// Full in-memory decode of one dataset object, used for sampled validation
+// and for discovering record sizes of existing datasets, and the sample
+// boundaries within an object for sample-level access
+//
+use anyhow::{Context, Result};
+use std::io::{Cursor, Read};
+use std::ops::Range;
+
+use crate::csv::scan_csv;
+use crate::detect::{detect_format, DetectedFormat};
+use crate::image::{check_jpeg, check_png};
+use crate::npy::parse_npy_header;
+use crate::tfrecord::{record_extents, scan_tfrecords};
+
+/// Longest .npy v1 header (10-byte preamble plus a u16 length)
+const NPY_HEADER_MAX: u64 = 10 + u16::MAX as u64;
+
+/// What a successful decode found in an object
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeSummary {
+    /// Records (TFRecord) or arrays (NPZ) decoded
+    pub items: usize,
+    /// Payload bytes after removing container framing
+    pub payload_bytes: usize,
+}
+
+/// Fully decode an object in the given format ("npz", "tfrecord", "hdf5", "csv", "jpeg", "png")
+///
+/// An unknown or empty format name falls back to content detection.
+pub fn decode_object(format: &str, data: &[u8]) -> Result<DecodeSummary> {
+    let format = match format.to_lowercase().as_str() {
+        "csv" => {
+            let rows = scan_csv(data)?;
+            return Ok(DecodeSummary { items: rows.len(), payload_bytes: rows.iter().sum() });
+        }
+        "jpeg" | "jpg" => DetectedFormat::Jpeg,
+        "png" => DetectedFormat::Png,
+        "npz" => DetectedFormat::Npz,
+        "tfrecord" => DetectedFormat::TfRecord,
+        "hdf5" | "h5" => DetectedFormat::Hdf5,
+        _ => detect_format(data).context("Unrecognized object format")?,
+    };
+
+    match format {
+        DetectedFormat::Npz => decode_npz(data),
+        DetectedFormat::TfRecord => {
+            let stats = scan_tfrecords(data)?;
+            Ok(DecodeSummary { items: stats.num_records, payload_bytes: stats.record_sizes.iter().sum() })
+        }
+        DetectedFormat::Hdf5 => decode_hdf5(data),
+        DetectedFormat::Parquet => anyhow::bail!("No Parquet decoder available"),
+        DetectedFormat::Jpeg => check_jpeg(data).map(|_| DecodeSummary { items: 1, payload_bytes: data.len() }),
+        DetectedFormat::Png => check_png(data).map(|_| DecodeSummary { items: 1, payload_bytes: data.len() }),
+    }
+}
+
+/// Payload bytes of each sample (record) in an object, from its format metadata
+///
+/// TFRecord records and rows of the NPZ data array (first axis, or the last
+/// axis of DLIO's `x` array), CSV rows and rows of dl-driver's SHD5 dataset
+/// are samples; other objects (images, real HDF5) count as a single sample.
+pub fn sample_sizes(format: &str, data: &[u8]) -> Result<Vec<usize>> {
+    let format = match format.to_lowercase().as_str() {
+        "csv" => return scan_csv(data),
+        "npz" => Some(DetectedFormat::Npz),
+        "tfrecord" => Some(DetectedFormat::TfRecord),
+        "hdf5" | "h5" => Some(DetectedFormat::Hdf5),
+        _ => detect_format(data),
+    };
+    match format {
+        Some(DetectedFormat::TfRecord) => Ok(scan_tfrecords(data)?.record_sizes),
+        Some(DetectedFormat::Npz) => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Not a valid NPZ (ZIP) archive")?;
+            let name = npz_sample_array(&archive)?;
+            let mut buf = Vec::new();
+            archive.by_name(&name)?.read_to_end(&mut buf).with_context(|| format!("Failed to read {}", name))?;
+            let header = parse_npy_header(&buf).with_context(|| format!("Invalid array {}", name))?;
+            let axis = if name == "x.npy" { header.shape.last() } else { header.shape.first() };
+            Ok(split_evenly(header.data_len(), axis.copied().unwrap_or(1)))
+        }
+        Some(DetectedFormat::Hdf5) if data.starts_with(b"SHD5") => {
+            let shape = shd5_shape(data)?;
+            let payload = decode_hdf5(data)?.payload_bytes;
+            Ok(split_evenly(payload, shape.first().copied().unwrap_or(1)))
+        }
+        _ => Ok(vec![data.len()]),
+    }
+}
+
+/// Byte range of each sample's payload within an object, for sample-level access
+///
+/// TFRecord records, CSV rows, rows of dl-driver's SHD5 dataset and rows
+/// (first axis) of a stored NPZ data array are contiguous and addressable.
+/// None when the samples are not: real HDF5, compressed NPZ entries, DLIO's
+/// sample-last `x` array, images and other objects.
+pub fn sample_extents(format: &str, data: &[u8]) -> Result<Option<Vec<Range<usize>>>> {
+    let format = match format.to_lowercase().as_str() {
+        "csv" => return csv_row_extents(data).map(Some),
+        "npz" => Some(DetectedFormat::Npz),
+        "tfrecord" => Some(DetectedFormat::TfRecord),
+        "hdf5" | "h5" => Some(DetectedFormat::Hdf5),
+        _ => detect_format(data),
+    };
+    match format {
+        Some(DetectedFormat::TfRecord) => record_extents(data).map(Some),
+        Some(DetectedFormat::Npz) => npz_row_extents(data),
+        Some(DetectedFormat::Hdf5) if data.starts_with(b"SHD5") => {
+            let payload = decode_hdf5(data)?.payload_bytes;
+            let rows = shd5_shape(data)?.first().copied().unwrap_or(1).max(1);
+            let row = payload / rows;
+            let start = data.len() - payload;
+            Ok(Some((0..rows).map(|i| start + i * row..start + (i + 1) * row).collect()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The array holding an NPZ's samples: DLIO's `x`, dl-driver's `data`, else the first
+fn npz_sample_array(archive: &zip::ZipArchive<Cursor<&[u8]>>) -> Result<String> {
+    let names: Vec<&str> = archive.file_names().collect();
+    ["data.npy", "x.npy"]
+        .into_iter()
+        .find(|n| names.contains(n))
+        .or_else(|| names.first().copied())
+        .map(str::to_string)
+        .context("Empty NPZ archive")
+}
+
+fn npz_row_extents(data: &[u8]) -> Result<Option<Vec<Range<usize>>>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Not a valid NPZ (ZIP) archive")?;
+    let name = npz_sample_array(&archive)?;
+    let entry = archive.by_name(&name)?;
+    if entry.compression() != zip::CompressionMethod::Stored || name == "x.npy" {
+        return Ok(None);
+    }
+    let entry_start = entry.data_start() as usize;
+    let mut head = Vec::new();
+    entry.take(NPY_HEADER_MAX).read_to_end(&mut head).with_context(|| format!("Failed to read {}", name))?;
+    let header = parse_npy_header(&head).with_context(|| format!("Invalid array {}", name))?;
+    if header.fortran_order {
+        return Ok(None);
+    }
+    let rows = header.shape.first().copied().unwrap_or(1).max(1);
+    let start = entry_start + header.data_offset;
+    if start.checked_add(header.data_len()).is_none_or(|end| end > data.len()) {
+        anyhow::bail!("{} extends past the end of the archive", name);
+    }
+    let row = header.data_len() / rows;
+    Ok(Some((0..rows).map(|i| start + i * row..start + (i + 1) * row).collect()))
+}
+
+/// Rows after the header line, each with its line ending
+fn csv_row_extents(data: &[u8]) -> Result<Vec<Range<usize>>> {
+    let header = data.iter().position(|&b| b == b'\n').context("Empty CSV (no header line)")? + 1;
+    let mut extents = Vec::new();
+    let mut start = header;
+    for (i, &b) in data[header..].iter().enumerate() {
+        if b == b'\n' {
+            extents.push(start..header + i + 1);
+            start = header + i + 1;
+        }
+    }
+    if start < data.len() {
+        extents.push(start..data.len());
+    }
+    Ok(extents)
+}
+
+/// `bytes` split over `samples` equal samples (at least one)
+fn split_evenly(bytes: usize, samples: usize) -> Vec<usize> {
+    let samples = samples.max(1);
+    vec![bytes / samples; samples]
+}
+
+/// Read every array of an NPZ archive, checking CRCs and .npy headers
+fn decode_npz(data: &[u8]) -> Result<DecodeSummary> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Not a valid NPZ (ZIP) archive")?;
+    let mut summary = DecodeSummary::default();
+    let mut buf = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).with_context(|| format!("Failed to open NPZ entry {}", i))?;
+        let name = entry.name().to_string();
+        buf.clear();
+        // Reading to the end makes the zip crate verify the entry CRC
+        entry.read_to_end(&mut buf).with_context(|| format!("Failed to decode {}", name))?;
+        let header = parse_npy_header(&buf).with_context(|| format!("Invalid array {}", name))?;
+        if header.data_offset + header.data_len() != buf.len() {
+            anyhow::bail!("{} holds {} bytes of data but its header declares {}",
+                          name, buf.len() - header.data_offset.min(buf.len()), header.data_len());
+        }
+        summary.items += 1;
+        summary.payload_bytes += header.data_len();
+    }
+    Ok(summary)
+}
+
+/// HDF5 from memory: dl-driver's SHD5 layout is decoded; real HDF5 is signature-checked only
+fn decode_hdf5(data: &[u8]) -> Result<DecodeSummary> {
+    if !data.starts_with(b"SHD5") {
+        if detect_format(data) != Some(DetectedFormat::Hdf5) {
+            anyhow::bail!("Missing HDF5 signature");
+        }
+        return Ok(DecodeSummary { items: 1, payload_bytes: data.len() });
+    }
+
+    let shape = shd5_shape(data)?;
+    let name_len = u32_at(data, 4)?;
+    let header_len = 12 + name_len + shape.len() * 4;
+    let expected = shape
+        .iter()
+        .try_fold(4usize, |n, &d| n.checked_mul(d))
+        .with_context(|| format!("SHD5 dataset shape {:?} is too large", shape))?;
+    if header_len.checked_add(expected) != Some(data.len()) {
+        anyhow::bail!("SHD5 dataset shape {:?} needs {} bytes, found {}", shape, expected, data.len().saturating_sub(header_len));
+    }
+    Ok(DecodeSummary { items: 1, payload_bytes: expected })
+}
+
+/// Dataset shape from dl-driver's SHD5 header
+fn shd5_shape(data: &[u8]) -> Result<Vec<usize>> {
+    let name_len = u32_at(data, 4)?;
+    let ndim = u32_at(data, 8 + name_len)?;
+    (0..ndim).map(|i| u32_at(data, 12 + name_len + i * 4)).collect()
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<usize> {
+    let bytes = data.get(offset..offset + 4).context("Truncated SHD5 header")?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Hdf5Format, NpzStreamingFormat, StreamingFormat, TfRecordFormat};
+
+    #[test]
+    fn test_decode_generated_objects() {
+        let npz = NpzStreamingFormat::new(vec![3, 4], 3).generate_bytes("a.npz").unwrap();
+        let summary = decode_object("npz", &npz).unwrap();
+        assert_eq!(summary.items, 3);
+        assert_eq!(summary.payload_bytes, 3 * 4 * 4 + 3 * 8 + 3 * 4);
+
+        let tfr = TfRecordFormat::new(4, 256).generate_bytes("a.tfrecord").unwrap();
+        assert_eq!(decode_object("", &tfr).unwrap().items, 4);
+
+        let h5 = Hdf5Format::new(vec![2, 2], None).generate_bytes("a.h5").unwrap();
+        assert_eq!(decode_object("hdf5", &h5).unwrap().payload_bytes, 16);
+
+        let csv = crate::CsvFormat::new(3, 50).generate_bytes("a.csv").unwrap();
+        assert_eq!(decode_object("csv", &csv).unwrap(), DecodeSummary { items: 3, payload_bytes: 150 });
+        let png = crate::PngFormat::new(1000).generate_bytes("a.png").unwrap();
+        assert_eq!(decode_object("", &png).unwrap().items, 1);
+        assert!(decode_object("jpeg", &png).is_err());
+    }
+
+    #[test]
+    fn test_sample_sizes_from_format_metadata() {
+        let npz = NpzStreamingFormat::new(vec![5, 64], 2).with_dtype(crate::NpyDtype::U8).generate_bytes("a.npz").unwrap();
+        assert_eq!(sample_sizes("npz", &npz).unwrap(), vec![64; 5]);
+
+        let tfr = TfRecordFormat::new(3, 100).generate_bytes("a.tfrecord").unwrap();
+        let sizes = sample_sizes("", &tfr).unwrap();
+        assert_eq!(sizes.len(), 3);
+
+        let h5 = Hdf5Format::new(vec![4, 2], None).generate_bytes("a.h5").unwrap();
+        assert_eq!(sample_sizes("hdf5", &h5).unwrap(), vec![8; 4]);
+        assert_eq!(sample_sizes("jpeg", b"\xff\xd8 image").unwrap(), vec![8]);
+    }
+
+    #[test]
+    fn test_sample_extents_locate_samples_in_objects() {
+        let npz = NpzStreamingFormat::new(vec![4, 16], 2).with_dtype(crate::NpyDtype::U8).generate_bytes("a.npz").unwrap();
+        let rows = sample_extents("npz", &npz).unwrap().unwrap();
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|r| r.len() == 16) && rows[1].start == rows[0].end);
+
+        let tfr = TfRecordFormat::new(3, 100).generate_bytes("a.tfrecord").unwrap();
+        let records = sample_extents("tfrecord", &tfr).unwrap().unwrap();
+        let sizes: Vec<usize> = records.iter().map(|r| r.len()).collect();
+        assert_eq!(sizes, sample_sizes("tfrecord", &tfr).unwrap());
+
+        let h5 = Hdf5Format::new(vec![4, 2], None).generate_bytes("a.h5").unwrap();
+        let rows = sample_extents("hdf5", &h5).unwrap().unwrap();
+        assert_eq!((rows.len(), rows[3].end), (4, h5.len()));
+
+        let csv = b"id,value\n1,a\n2,bb\n3,c";
+        assert_eq!(sample_extents("csv", csv).unwrap().unwrap(), vec![9..13, 13..18, 18..21]);
+
+        let png = crate::PngFormat::new(1000).generate_bytes("a.png").unwrap();
+        assert!(sample_extents("", &png).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_detects_corruption() {
+        let mut npz = NpzStreamingFormat::new(vec![8, 8], 2).generate_bytes("a.npz").unwrap();
+        // Flip a byte inside the stored data array; the entry CRC no longer matches
+        npz[200] ^= 0xff;
+        assert!(decode_object("npz", &npz).is_err());
+        assert!(decode_object("tfrecord", b"not a tfrecord").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/formats/src/detect.rs
+//
+// Format detection from the leading bytes of a file or object
+//
+use crate::image::PNG_SIGNATURE;
+use crate::tfrecord::TfRecordFormat;
+
+/// Bytes needed from the start of an object to detect its format
+pub const DETECT_HEAD_BYTES: usize = 64;
+
+/// HDF5 superblock signature (at offset 0 for files written by h5py/libhdf5)
+const HDF5_MAGIC: &[u8] = b"\x89HDF\r\n\x1a\n";
+
+/// Data formats that can be recognized from content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetectedFormat {
+    Npz,
+    Hdf5,
+    TfRecord,
+    Parquet,
+    Jpeg,
+    Png,
+}
+
+impl DetectedFormat {
+    /// Name as used in DLIO `dataset.format`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Npz => "npz",
+            Self::Hdf5 => "hdf5",
+            Self::TfRecord => "tfrecord",
+            Self::Parquet => "parquet",
+            Self::Jpeg => "jpeg",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// Detect the format of an object from its first bytes
+///
+/// NPZ is a ZIP archive ("PK"), HDF5 starts with its superblock signature
+/// (or "SHD5" for dl-driver's in-memory variant), Parquet with "PAR1", JPEG
+/// and PNG with their signatures, and a TFRecord stream with a length whose
+/// masked CRC32C checks out. CSV has no signature and is never detected.
+pub fn detect_format(head: &[u8]) -> Option<DetectedFormat> {
+    if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+        return Some(DetectedFormat::Npz);
+    }
+    if head.starts_with(HDF5_MAGIC) || head.starts_with(b"SHD5") {
+        return Some(DetectedFormat::Hdf5);
+    }
+    if head.starts_with(b"PAR1") {
+        return Some(DetectedFormat::Parquet);
+    }
+    if head.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(DetectedFormat::Jpeg);
+    }
+    if head.starts_with(PNG_SIGNATURE) {
+        return Some(DetectedFormat::Png);
+    }
+    if head.len() >= 12 {
+        let (len, crc) = (&head[..8], &head[8..12]);
+        if TfRecordFormat::masked_crc32c(len) == u32::from_le_bytes([crc[0], crc[1], crc[2], crc[3]]) {
+            return Some(DetectedFormat::TfRecord);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NpzStreamingFormat, StreamingFormat, TfRecordFormat};
+
+    #[test]
+    fn test_detects_generated_formats() {
+        let npz = NpzStreamingFormat::new(vec![2, 4], 2).generate_bytes("a.npz").unwrap();
+        assert_eq!(detect_format(&npz), Some(DetectedFormat::Npz));
+
+        let tfr = TfRecordFormat::new(2, 128).generate_bytes("a.tfrecord").unwrap();
+        assert_eq!(detect_format(&tfr[..DETECT_HEAD_BYTES]), Some(DetectedFormat::TfRecord));
+
+        assert_eq!(detect_format(b"\x89HDF\r\n\x1a\n\0\0"), Some(DetectedFormat::Hdf5));
+        assert_eq!(detect_format(b"PAR1\x15\x04"), Some(DetectedFormat::Parquet));
+        let jpeg = crate::JpegFormat::new(512).generate_bytes("a.jpeg").unwrap();
+        assert_eq!(detect_format(&jpeg), Some(DetectedFormat::Jpeg));
+        let png = crate::PngFormat::new(512).generate_bytes("a.png").unwrap();
+        assert_eq!(detect_format(&png), Some(DetectedFormat::Png));
+        assert_eq!(detect_format(&[0u8; 32]), None);
+    }
+}
@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/formats/src/fuzz.rs
+//
+// Fuzzing entry points for the format readers
+//
+// Every reader here parses bytes from storage, which may be truncated or
+// corrupt; a reader must return an error, never panic mid-benchmark. These
+// entry points run all readers over one input and are shared by the
+// cargo-fuzz targets (crates/formats/fuzz) and the property tests below,
+// which mutate generated files of every format.
+
+use std::io::Cursor;
+
+use crate::{
+    check_jpeg, check_png, decode_object, detect_format, npy::parse_npy_header, npz::read_array,
+    npz::read_array_headers, record_extents, sample_extents, sample_sizes, scan_csv, scan_tfrecords,
+};
+
+/// Format names the readers accept, plus "" for content detection
+pub const READER_FORMATS: &[&str] = &["", "npz", "tfrecord", "hdf5", "csv", "jpeg", "png"];
+
+/// Run every reader over `data`; results are discarded, only panics matter
+pub fn read_untrusted(data: &[u8]) {
+    let _ = detect_format(data);
+    for format in READER_FORMATS {
+        let _ = decode_object(format, data);
+        let _ = sample_sizes(format, data);
+        let _ = sample_extents(format, data);
+    }
+    let _ = scan_tfrecords(data);
+    let _ = record_extents(data);
+    let _ = scan_csv(data);
+    let _ = check_jpeg(data);
+    let _ = check_png(data);
+    let _ = parse_npy_header(data);
+    let _ = read_array_headers(Cursor::new(data));
+    let _ = read_array(data, "data.npy");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CsvFormat, Hdf5Format, JpegFormat, NpzStreamingFormat, PngFormat, StreamingFormat, TfRecordFormat};
+    use proptest::prelude::*;
+
+    /// Damage applied to a valid file
+    #[derive(Debug, Clone)]
+    enum Mutation {
+        Flip { at: usize, mask: u8 },
+        Truncate { at: usize },
+        Insert { at: usize, bytes: Vec<u8> },
+        Overwrite { at: usize, bytes: Vec<u8> },
+    }
+
+    fn mutation() -> impl Strategy<Value = Mutation> {
+        prop_oneof![
+            (any::<usize>(), 1..=255u8).prop_map(|(at, mask)| Mutation::Flip { at, mask }),
+            any::<usize>().prop_map(|at| Mutation::Truncate { at }),
+            (any::<usize>(), prop::collection::vec(any::<u8>(), 1..16)).prop_map(|(at, bytes)| Mutation::Insert { at, bytes }),
+            // Overwrites with 0xFF bytes stress length and size fields
+            (any::<usize>(), prop::collection::vec(prop_oneof![Just(0xFFu8), any::<u8>()], 1..9))
+                .prop_map(|(at, bytes)| Mutation::Overwrite { at, bytes }),
+        ]
+    }
+
+    fn apply(mut data: Vec<u8>, mutations: &[Mutation]) -> Vec<u8> {
+        for m in mutations {
+            let len = data.len().max(1);
+            match m {
+                Mutation::Flip { at, mask } => {
+                    if let Some(b) = data.get_mut(at % len) {
+                        *b ^= mask;
+                    }
+                }
+                Mutation::Truncate { at } => data.truncate(at % len),
+                Mutation::Insert { at, bytes } => {
+                    let at = (at % len).min(data.len());
+                    data.splice(at..at, bytes.iter().copied());
+                }
+                Mutation::Overwrite { at, bytes } => {
+                    for (i, b) in bytes.iter().enumerate() {
+                        if let Some(slot) = data.get_mut(at % len + i) {
+                            *slot = *b;
+                        }
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    /// A valid file of every format, sized by the strategy
+    fn generated(samples: usize, record: usize) -> Vec<(&'static str, Box<dyn StreamingFormat>)> {
+        vec![
+            ("npz", Box::new(NpzStreamingFormat::new(vec![samples, record], 2)) as Box<dyn StreamingFormat>),
+            ("tfrecord", Box::new(TfRecordFormat::new(samples, record))),
+            ("hdf5", Box::new(Hdf5Format::new(vec![samples, record], None))),
+            ("csv", Box::new(CsvFormat::new(samples, record))),
+            ("jpeg", Box::new(JpegFormat::new(record * samples))),
+            ("png", Box::new(PngFormat::new(record * samples))),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn generated_files_round_trip(samples in 1usize..6, record in 1usize..300) {
+            for (name, format) in generated(samples, record) {
+                let bytes = format.generate_bytes("").unwrap();
+                prop_assert!(format.read_from_bytes(&bytes).is_ok(), "{} did not read back", name);
+                prop_assert!(decode_object(name, &bytes).is_ok(), "{} did not decode", name);
+            }
+        }
+
+        #[test]
+        fn mutated_files_never_panic(samples in 1usize..4, record in 1usize..200,
+                                     mutations in prop::collection::vec(mutation(), 1..4)) {
+            for (_, format) in generated(samples, record) {
+                let bytes = apply(format.generate_bytes("").unwrap(), &mutations);
+                let _ = format.read_from_bytes(&bytes);
+                read_untrusted(&bytes);
+            }
+        }
+
+        #[test]
+        fn arbitrary_bytes_never_panic(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            read_untrusted(&data);
+        }
+    }
+
+    #[test]
+    fn test_oversized_declared_shapes_are_errors() {
+        // An SHD5 header declaring 2^32-1 × 2^32-1 elements overflows usize arithmetic
+        let mut shd5 = b"SHD5\0\0\0\0\x02\0\0\0".to_vec();
+        shd5.extend_from_slice(&[0xFF; 8]);
+        assert!(decode_object("hdf5", &shd5).is_err());
+
+        let dict = "{'descr': '<f8', 'fortran_order': False, 'shape': (18446744073709551615, 2), }\n";
+        let mut npy = b"\x93NUMPY\x01\x00".to_vec();
+        npy.extend_from_slice(&(dict.len() as u16).to_le_bytes());
+        npy.extend_from_slice(dict.as_bytes());
+        assert!(parse_npy_header(&npy).is_err());
+    }
+}
@@ -4,13 +4,23 @@
 // crates/formats/src/hdf5.rs
 //
 // HDF5 format implementation for DLIO compatibility
+//
+// Real .h5 files need libhdf5 and the `hdf5` feature (on by default). Without
+// it the crate still builds; file generation/reading fails with a clear error
+// and the in-memory streaming format keeps working.
 
 use crate::{Format, FormatMetadata, StreamingFormat};
 use anyhow::{Context, Result};
+#[cfg(feature = "hdf5")]
 use hdf5_metno::File;
+#[cfg(feature = "hdf5")]
 use ndarray::{ArrayD, IxDyn};
 use std::path::Path;
 
+/// Error text for HDF5 file operations in builds without libhdf5
+pub const HDF5_DISABLED_MSG: &str = "HDF5 support is not compiled in: rebuild with `--features hdf5` \
+     (requires libhdf5), or use the npz format instead";
+
 /// HDF5 format generator and reader
 pub struct Hdf5Format {
     shape: Vec<usize>,
@@ -25,8 +35,22 @@ impl Hdf5Format {
             dataset_name: dataset_name.unwrap_or_else(|| "data".to_string()),
         }
     }
+
+    /// Contents of a real HDF5 file (libhdf5 writes to a path, so via a temp file)
+    ///
+    /// Unlike `generate_bytes` (dl-driver's in-memory SHD5 layout), the result
+    /// opens with h5py and any other HDF5 reader.
+    pub fn file_bytes(&self) -> Result<Vec<u8>> {
+        let tmp = tempfile::Builder::new()
+            .suffix(".h5")
+            .tempfile()
+            .context("Failed to create a temporary HDF5 file")?;
+        self.generate(tmp.path())?;
+        std::fs::read(tmp.path()).with_context(|| format!("Failed to read back {:?}", tmp.path()))
+    }
 }
 
+#[cfg(feature = "hdf5")]
 impl Format for Hdf5Format {
     fn generate(&self, path: &Path) -> Result<()> {
         // Create HDF5 file
@@ -79,6 +103,18 @@ impl Format for Hdf5Format {
     }
 }
 
+#[cfg(not(feature = "hdf5"))]
+impl Format for Hdf5Format {
+    fn generate(&self, path: &Path) -> Result<()> {
+        anyhow::bail!("Cannot create {:?}: {}", path, HDF5_DISABLED_MSG)
+    }
+
+    fn read(&self, path: &Path) -> Result<()> {
+        anyhow::bail!("Cannot read {:?}: {}", path, HDF5_DISABLED_MSG)
+    }
+}
+
+#[cfg(feature = "hdf5")]
 impl Hdf5Format {
     /// Create synthetic array data using s3dlio utilities with diverse patterns
     fn create_synthetic_array(&self) -> Result<ArrayD<f32>> {
@@ -210,7 +246,10 @@ impl StreamingFormat for Hdf5Format {
         }
 
         // Verify data size (f32 = 4 bytes per element)
-        let expected_data_size = shape.iter().product::<usize>() * 4;
+        let expected_data_size = shape
+            .iter()
+            .try_fold(4usize, |n, &d| n.checked_mul(d))
+            .with_context(|| format!("HDF5 shape {:?} is too large", shape))?;
         let actual_data_size = data.len() - (offset + 4 + ndim * 4);
         if actual_data_size != expected_data_size {
             anyhow::bail!(
@@ -246,6 +285,7 @@ mod tests {
     use super::*;
     use tempfile::NamedTempFile;
 
+    #[cfg(feature = "hdf5")]
     #[test]
     fn hdf5_generate_and_read() {
         // Skip test if HDF5 is not available
@@ -262,6 +302,7 @@ mod tests {
         fmt.read(&path).unwrap();
     }
 
+    #[cfg(feature = "hdf5")]
     #[test]
     fn hdf5_custom_dataset_name() {
         if std::env::var("SKIP_HDF5_TESTS").is_ok() {
@@ -275,4 +316,30 @@ mod tests {
         fmt.generate(&path).unwrap();
         fmt.read(&path).unwrap();
     }
+
+    #[cfg(feature = "hdf5")]
+    #[test]
+    fn hdf5_file_bytes_are_real_hdf5() {
+        if std::env::var("SKIP_HDF5_TESTS").is_ok() {
+            return;
+        }
+
+        let bytes = Hdf5Format::new(vec![4, 8], Some("records".to_string())).file_bytes().unwrap();
+        assert_eq!(crate::detect_format(&bytes), Some(crate::DetectedFormat::Hdf5));
+        assert!(!bytes.starts_with(b"SHD5"));
+    }
+
+    #[cfg(not(feature = "hdf5"))]
+    #[test]
+    fn hdf5_disabled_fails_with_clear_message() {
+        let fmt = Hdf5Format::new(vec![2, 3], None);
+        let tmp = NamedTempFile::new().unwrap();
+        let err = fmt.generate(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("--features hdf5"));
+        assert!(fmt.file_bytes().unwrap_err().to_string().contains("--features hdf5"));
+
+        // In-memory streaming does not need libhdf5
+        let bytes = fmt.generate_bytes("a.h5").unwrap();
+        fmt.read_from_bytes(&bytes).unwrap();
+    }
 }
@@ -0,0 +1,337 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/formats/src/image.rs
+//
+// JPEG and PNG formats for DLIO `format: jpeg` / `format: png`
+//
+// One 8-bit grayscale image per file, as DLIO writes image datasets, sized
+// to `record_length_bytes`:
+//
+//   jpeg  a baseline JFIF image (a uniform gray frame with minimal Huffman
+//         tables) padded to the record size with COM segments of synthetic
+//         bytes; every segment is well-formed, so image decoders open it
+//   png   synthetic pixels in stored (uncompressed) deflate blocks, so the
+//         file size tracks the pixel count; chunk CRCs and the zlib Adler-32
+//         are real
+//
+// Readers walk the JPEG segments / PNG chunks and check the frame header,
+// chunk CRCs and end markers; pixel data is not decoded.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::{Format, FormatMetadata, StreamingFormat};
+
+/// PNG file signature
+pub const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Largest COM segment payload (the 16-bit length includes its own 2 bytes)
+const MAX_SEGMENT_PAYLOAD: usize = 65533;
+
+/// Largest stored deflate block
+const MAX_STORED_BLOCK: usize = 65535;
+
+/// Width and height of a decoded image header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// JPEG format generator and reader
+pub struct JpegFormat {
+    record_size: usize,
+}
+
+impl JpegFormat {
+    /// Create with the target file size in bytes
+    pub fn new(record_size: usize) -> Self {
+        JpegFormat { record_size }
+    }
+
+    /// Side of the square frame: the largest multiple of 8 whose pixel count fits the record
+    fn side(&self) -> usize {
+        (((self.record_size as f64).sqrt() as usize) / 8 * 8).clamp(8, 65528)
+    }
+
+    fn segment(out: &mut Vec<u8>, marker: u8, payload: &[u8]) {
+        out.extend_from_slice(&[0xFF, marker]);
+        out.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(payload);
+    }
+}
+
+impl StreamingFormat for JpegFormat {
+    fn generate_bytes(&self, _filename: &str) -> Result<Vec<u8>> {
+        let side = self.side() as u16;
+        let mut out = vec![0xFF, 0xD8];
+        Self::segment(&mut out, 0xE0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        // Quantization table 0, all ones
+        Self::segment(&mut out, 0xDB, &[[0u8].as_slice(), &[1u8; 64]].concat());
+        // Baseline frame, one 8-bit component using table 0
+        let [h0, h1] = side.to_be_bytes();
+        Self::segment(&mut out, 0xC0, &[8, h0, h1, h0, h1, 1, 1, 0x11, 0]);
+        // One-code Huffman tables: DC difference 0 and AC end-of-block, both coded "0"
+        let mut single = [0u8; 17];
+        single[0] = 1;
+        Self::segment(&mut out, 0xC4, &[[0x00].as_slice(), &single].concat());
+        Self::segment(&mut out, 0xC4, &[[0x10].as_slice(), &single].concat());
+
+        // Pad with COM segments to the record size, less the scan still to come
+        let blocks = (side as usize / 8).pow(2);
+        let scan_len = 10 + (blocks * 2).div_ceil(8) + 2;
+        let mut remaining = self.record_size.saturating_sub(out.len() + scan_len);
+        let mut seed = 0;
+        while remaining >= 4 {
+            let mut take = (remaining - 4).min(MAX_SEGMENT_PAYLOAD);
+            // Never leave a remainder too small for another segment
+            if (1..4).contains(&(remaining - 4 - take)) {
+                take -= 4;
+            }
+            Self::segment(&mut out, 0xFE, &s3dlio::generate_controlled_data(take, seed, 0)[..take]);
+            remaining -= take + 4;
+            seed += 1;
+        }
+
+        Self::segment(&mut out, 0xDA, &[1, 1, 0x00, 0, 63, 0]);
+        // Two zero bits per block; the final byte is padded with ones
+        let mut scan = vec![0u8; (blocks * 2).div_ceil(8)];
+        if !(blocks * 2).is_multiple_of(8) {
+            *scan.last_mut().expect("at least one block") = 0xFF >> ((blocks * 2) % 8);
+        }
+        out.extend_from_slice(&scan);
+        out.extend_from_slice(&[0xFF, 0xD9]);
+        Ok(out)
+    }
+
+    fn read_from_bytes(&self, data: &[u8]) -> Result<()> {
+        check_jpeg(data).map(|_| ())
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "jpeg"
+    }
+
+    fn format_metadata(&self) -> FormatMetadata {
+        FormatMetadata {
+            expected_size_bytes: Some(self.record_size),
+            compression_ratio: None,
+            is_binary: true,
+            supports_streaming: true,
+        }
+    }
+}
+
+/// Walk the segments of a JPEG up to its scan and check it ends with EOI
+pub fn check_jpeg(data: &[u8]) -> Result<ImageInfo> {
+    anyhow::ensure!(data.starts_with(&[0xFF, 0xD8]), "Missing JPEG SOI marker");
+    let mut pos = 2;
+    let mut info = None;
+    loop {
+        let header = data.get(pos..pos + 4).with_context(|| format!("JPEG truncated in segment at byte {}", pos))?;
+        anyhow::ensure!(header[0] == 0xFF, "Expected a JPEG marker at byte {}", pos);
+        let (marker, len) = (header[1], u16::from_be_bytes([header[2], header[3]]) as usize);
+        anyhow::ensure!(len >= 2, "JPEG segment at byte {} has invalid length {}", pos, len);
+        let payload = data.get(pos + 4..pos + 2 + len).with_context(|| format!("JPEG segment at byte {} runs past the end", pos))?;
+        match marker {
+            // SOF0-SOF15, except DHT (C4), JPG (C8) and DAC (CC)
+            0xC0..=0xCF if ![0xC4, 0xC8, 0xCC].contains(&marker) => {
+                anyhow::ensure!(payload.len() >= 6, "JPEG frame header too short");
+                let height = u16::from_be_bytes([payload[1], payload[2]]) as u32;
+                let width = u16::from_be_bytes([payload[3], payload[4]]) as u32;
+                anyhow::ensure!(width > 0 && height > 0, "JPEG frame has zero size");
+                info = Some(ImageInfo { width, height });
+            }
+            0xDA => break,
+            _ => {}
+        }
+        pos += 2 + len;
+    }
+    let info = info.context("JPEG has no frame header before its scan")?;
+    anyhow::ensure!(data.ends_with(&[0xFF, 0xD9]), "Missing JPEG EOI marker");
+    Ok(info)
+}
+
+impl Format for JpegFormat {
+    fn generate(&self, path: &Path) -> Result<()> {
+        let bytes = self.generate_bytes("")?;
+        std::fs::write(path, bytes).with_context(|| format!("Failed to write JPEG file at {:?}", path))
+    }
+
+    fn read(&self, path: &Path) -> Result<()> {
+        let data = std::fs::read(path).with_context(|| format!("Failed to open JPEG file at {:?}", path))?;
+        self.read_from_bytes(&data).with_context(|| format!("Invalid JPEG file {:?}", path))
+    }
+}
+
+/// PNG format generator and reader
+pub struct PngFormat {
+    record_size: usize,
+}
+
+impl PngFormat {
+    /// Create with the target file size in bytes
+    pub fn new(record_size: usize) -> Self {
+        PngFormat { record_size }
+    }
+
+    /// Width and height whose scanlines (filter byte + pixels) fill about the record size
+    fn dimensions(&self) -> (usize, usize) {
+        let width = ((self.record_size as f64).sqrt() as usize).max(1);
+        (width, (self.record_size / (width + 1)).max(1))
+    }
+
+    fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(kind);
+        hasher.update(data);
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        out.extend_from_slice(&hasher.finalize().to_be_bytes());
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+impl StreamingFormat for PngFormat {
+    fn generate_bytes(&self, _filename: &str) -> Result<Vec<u8>> {
+        let (width, height) = self.dimensions();
+        let pixels = s3dlio::generate_controlled_data(width * height, 0, 0);
+        let mut raw = Vec::with_capacity((width + 1) * height);
+        for row in pixels[..width * height].chunks(width) {
+            raw.push(0); // filter type None
+            raw.extend_from_slice(row);
+        }
+
+        // zlib stream of stored deflate blocks
+        let mut zlib = vec![0x78, 0x01];
+        let blocks: Vec<&[u8]> = raw.chunks(MAX_STORED_BLOCK).collect();
+        for (i, block) in blocks.iter().enumerate() {
+            zlib.push(u8::from(i + 1 == blocks.len()));
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut out = PNG_SIGNATURE.to_vec();
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 0, 0, 0, 0]); // 8-bit grayscale, deflate, no interlace
+        Self::chunk(&mut out, b"IHDR", &ihdr);
+        Self::chunk(&mut out, b"IDAT", &zlib);
+        Self::chunk(&mut out, b"IEND", &[]);
+        Ok(out)
+    }
+
+    fn read_from_bytes(&self, data: &[u8]) -> Result<()> {
+        check_png(data).map(|_| ())
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "png"
+    }
+
+    fn format_metadata(&self) -> FormatMetadata {
+        FormatMetadata {
+            expected_size_bytes: None, // Rounded to whole scanlines
+            compression_ratio: Some(1.0),
+            is_binary: true,
+            supports_streaming: true,
+        }
+    }
+}
+
+/// Walk the chunks of a PNG, checking every CRC, IHDR first, an IDAT and IEND last
+pub fn check_png(data: &[u8]) -> Result<ImageInfo> {
+    anyhow::ensure!(data.starts_with(PNG_SIGNATURE), "Missing PNG signature");
+    let mut pos = PNG_SIGNATURE.len();
+    let (mut info, mut has_data) = (None, false);
+    loop {
+        let header = data.get(pos..pos + 8).with_context(|| format!("PNG truncated in chunk at byte {}", pos))?;
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let kind = &header[4..8];
+        let body = data.get(pos + 8..pos + 8 + len).with_context(|| format!("PNG chunk at byte {} runs past the end", pos))?;
+        let crc = data.get(pos + 8 + len..pos + 12 + len).context("PNG truncated in chunk CRC")?;
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(kind);
+        hasher.update(body);
+        anyhow::ensure!(hasher.finalize().to_be_bytes() == crc,
+                        "CRC mismatch in PNG {} chunk", String::from_utf8_lossy(kind));
+        match kind {
+            b"IHDR" => {
+                anyhow::ensure!(pos == PNG_SIGNATURE.len() && len == 13, "Misplaced or malformed PNG IHDR");
+                let width = u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+                let height = u32::from_be_bytes([body[4], body[5], body[6], body[7]]);
+                anyhow::ensure!(width > 0 && height > 0, "PNG image has zero size");
+                info = Some(ImageInfo { width, height });
+            }
+            b"IDAT" => has_data = true,
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    let info = info.context("PNG has no IHDR chunk")?;
+    anyhow::ensure!(has_data, "PNG has no IDAT chunk");
+    Ok(info)
+}
+
+impl Format for PngFormat {
+    fn generate(&self, path: &Path) -> Result<()> {
+        let bytes = self.generate_bytes("")?;
+        std::fs::write(path, bytes).with_context(|| format!("Failed to write PNG file at {:?}", path))
+    }
+
+    fn read(&self, path: &Path) -> Result<()> {
+        let data = std::fs::read(path).with_context(|| format!("Failed to open PNG file at {:?}", path))?;
+        self.read_from_bytes(&data).with_context(|| format!("Invalid PNG file {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jpeg_and_png_headers_and_sizes() {
+        for size in [100, 4096, 150_000] {
+            let jpeg = JpegFormat::new(size).generate_bytes("a.jpeg").unwrap();
+            let info = check_jpeg(&jpeg).unwrap();
+            assert_eq!(info.width, info.height);
+            assert!(info.width.is_multiple_of(8) && (info.width * info.height) as usize <= size.max(64));
+            if size > 1000 {
+                assert_eq!(jpeg.len(), size);
+            }
+
+            let png = PngFormat::new(size).generate_bytes("a.png").unwrap();
+            let info = check_png(&png).unwrap();
+            assert!(((info.width + 1) * info.height) as usize <= size);
+            // Stored blocks keep the file within a few hundred bytes of the record size
+            assert!(png.len().abs_diff(size) < 300, "{} vs {}", png.len(), size);
+        }
+
+        // Corruption is caught: a flipped pixel breaks the IDAT CRC, a cut file loses EOI
+        let mut png = PngFormat::new(2048).generate_bytes("").unwrap();
+        png[100] ^= 0xff;
+        assert!(check_png(&png).is_err());
+        let jpeg = JpegFormat::new(2048).generate_bytes("").unwrap();
+        assert!(check_jpeg(&jpeg[..jpeg.len() - 1]).is_err());
+        assert!(check_jpeg(b"\xff\xd8\xff\xd9").is_err());
+    }
+}
@@ -3,15 +3,26 @@
 
 // crates/formats/src/lib.rs
 //
+pub mod csv;
+pub mod decode;
+pub mod detect;
+pub mod fuzz;
 pub mod hdf5;
+pub mod image;
+pub mod npy;
 pub mod npz;
 pub mod tfrecord;
 // TODO: Re-enable integration layer after core functionality is stable
 // pub mod formats_integration;
 
-pub use hdf5::{Hdf5Format, Hdf5StreamingFormat};
+pub use csv::{scan_csv, CsvFormat};
+pub use decode::{decode_object, sample_extents, sample_sizes, DecodeSummary};
+pub use detect::{detect_format, DetectedFormat, DETECT_HEAD_BYTES};
+pub use hdf5::{Hdf5Format, Hdf5StreamingFormat, HDF5_DISABLED_MSG};
+pub use image::{check_jpeg, check_png, ImageInfo, JpegFormat, PngFormat};
+pub use npy::{NpyDtype, NpyHeader};
 pub use npz::{NpzFormat, NpzStreamingFormat};
-pub use tfrecord::{TfRecordFormat, TfRecordStreamingFormat};
+pub use tfrecord::{record_extents, scan_tfrecords, TfRecordFormat, TfRecordReader, TfRecordStats, TfRecordStreamingFormat};
 
 /// A simple data‐format interface.
 pub trait Format {
@@ -45,10 +56,28 @@ pub struct FormatMetadata {
     pub supports_streaming: bool,
 }
 
+/// Whether this build can write and read real HDF5 files
+pub const HDF5_ENABLED: bool = cfg!(feature = "hdf5");
+
 /// Format factory for creating format instances from DLIO config strings
 pub struct FormatFactory;
 
 impl FormatFactory {
+    /// Resolve a configured format name against what this build supports
+    ///
+    /// Without the `hdf5` feature, "hdf5" fails with a clear message unless
+    /// `substitute_npz` is set, in which case "npz" is returned instead.
+    pub fn resolve_format(format_name: &str, substitute_npz: bool) -> anyhow::Result<String> {
+        let name = format_name.to_lowercase();
+        if name == "hdf5" && !HDF5_ENABLED {
+            if substitute_npz {
+                return Ok("npz".to_string());
+            }
+            anyhow::bail!("Dataset format 'hdf5': {}", HDF5_DISABLED_MSG);
+        }
+        Ok(name)
+    }
+
     /// Create a format instance based on the format string and optional configuration
     pub fn create_format(
         format_name: &str,
@@ -66,6 +95,9 @@ impl FormatFactory {
                 Ok(Box::new(NpzFormat::new(shape, 3))) // Default: data, labels, metadata arrays
             }
             "hdf5" => {
+                if !HDF5_ENABLED {
+                    anyhow::bail!("Dataset format 'hdf5': {}", HDF5_DISABLED_MSG);
+                }
                 let shape = shape.unwrap_or(default_shape);
                 Ok(Box::new(Hdf5Format::new(shape, None)))
             }
@@ -74,6 +106,13 @@ impl FormatFactory {
                 let record_size = record_length.unwrap_or(default_record_length);
                 Ok(Box::new(TfRecordFormat::new(num_records, record_size)))
             }
+            "csv" => {
+                let num_records = num_records.unwrap_or(default_num_records);
+                let record_size = record_length.unwrap_or(default_record_length);
+                Ok(Box::new(CsvFormat::new(num_records, record_size)))
+            }
+            "jpeg" | "jpg" => Ok(Box::new(JpegFormat::new(record_length.unwrap_or(default_record_length)))),
+            "png" => Ok(Box::new(PngFormat::new(record_length.unwrap_or(default_record_length)))),
             _ => {
                 anyhow::bail!("Unsupported format: {}", format_name)
             }
@@ -105,6 +144,13 @@ impl FormatFactory {
                 let record_size = record_length.unwrap_or(default_record_length);
                 Ok(Box::new(TfRecordFormat::new(num_records, record_size)))
             }
+            "csv" => {
+                let num_records = num_records.unwrap_or(default_num_records);
+                let record_size = record_length.unwrap_or(default_record_length);
+                Ok(Box::new(CsvFormat::new(num_records, record_size)))
+            }
+            "jpeg" | "jpg" => Ok(Box::new(JpegFormat::new(record_length.unwrap_or(default_record_length)))),
+            "png" => Ok(Box::new(PngFormat::new(record_length.unwrap_or(default_record_length)))),
             _ => {
                 anyhow::bail!("Unsupported format: {}", format_name)
             }
@@ -113,6 +159,6 @@ impl FormatFactory {
 
     /// Get all supported format names
     pub fn supported_formats() -> Vec<&'static str> {
-        vec!["npz", "hdf5", "tfrecord"]
+        vec!["npz", "hdf5", "tfrecord", "csv", "jpeg", "png"]
     }
 }
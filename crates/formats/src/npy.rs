@@ -0,0 +1,221 @@
+// SPDX-FileCopyrightText: 2025 Russ Fellows <russ.fellows@gmail.com>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// crates/formats/src/npy.rs
+//
+// Minimal NumPy .npy (format version 1.0) header writer and parser
+//
+// Arrays are written from raw little-endian bytes so any DLIO
+// `record_element_type` can be produced without converting element by element.
+//
+use anyhow::{Context, Result};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+const HEADER_ALIGN: usize = 64;
+
+/// NumPy element types supported for generated records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NpyDtype {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F16,
+    F32,
+    F64,
+}
+
+impl NpyDtype {
+    /// Parse a DLIO `record_element_type` (numpy names such as "uint8", "float32")
+    pub fn from_dlio(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().trim_start_matches("np.") {
+            "uint8" | "u1" => Ok(Self::U8),
+            "int8" | "i1" => Ok(Self::I8),
+            "uint16" | "u2" => Ok(Self::U16),
+            "int16" | "i2" => Ok(Self::I16),
+            "uint32" | "u4" => Ok(Self::U32),
+            "int32" | "i4" => Ok(Self::I32),
+            "uint64" | "u8" => Ok(Self::U64),
+            "int64" | "i8" => Ok(Self::I64),
+            "float16" | "half" | "f2" => Ok(Self::F16),
+            "float32" | "float" | "f4" => Ok(Self::F32),
+            "float64" | "double" | "f8" => Ok(Self::F64),
+            other => anyhow::bail!(
+                "Unsupported record_element_type '{}'. Valid options: uint8, int8, uint16, int16, \
+                 uint32, int32, uint64, int64, float16, float32, float64",
+                other
+            ),
+        }
+    }
+
+    /// numpy `descr` string, e.g. "<f4"
+    pub fn descr(&self) -> &'static str {
+        match self {
+            Self::U8 => "|u1",
+            Self::I8 => "|i1",
+            Self::U16 => "<u2",
+            Self::I16 => "<i2",
+            Self::U32 => "<u4",
+            Self::I32 => "<i4",
+            Self::U64 => "<u8",
+            Self::I64 => "<i8",
+            Self::F16 => "<f2",
+            Self::F32 => "<f4",
+            Self::F64 => "<f8",
+        }
+    }
+
+    pub fn from_descr(descr: &str) -> Option<Self> {
+        [
+            Self::U8, Self::I8, Self::U16, Self::I16, Self::U32, Self::I32,
+            Self::U64, Self::I64, Self::F16, Self::F32, Self::F64,
+        ]
+        .into_iter()
+        .find(|d| d.descr() == descr)
+    }
+
+    pub fn item_size(&self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 | Self::F16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+        }
+    }
+}
+
+/// Parsed .npy header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpyHeader {
+    pub dtype: NpyDtype,
+    pub fortran_order: bool,
+    pub shape: Vec<usize>,
+    /// Offset of the array data from the start of the .npy bytes
+    pub data_offset: usize,
+}
+
+impl NpyHeader {
+    pub fn num_elements(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    pub fn data_len(&self) -> usize {
+        self.num_elements() * self.dtype.item_size()
+    }
+}
+
+/// Serialize an array as .npy bytes; `data` must hold exactly shape × item size bytes
+pub fn write_npy(dtype: NpyDtype, shape: &[usize], data: &[u8]) -> Result<Vec<u8>> {
+    let expected = shape.iter().product::<usize>() * dtype.item_size();
+    if data.len() != expected {
+        anyhow::bail!("Array data is {} bytes but shape {:?} of {} needs {}",
+                      data.len(), shape, dtype.descr(), expected);
+    }
+
+    let shape_str = match shape {
+        [single] => format!("({},)", single),
+        dims => format!("({})", dims.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")),
+    };
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", dtype.descr(), shape_str);
+    // Magic(6) + version(2) + header length(2) + header, padded with spaces and ending in '\n'
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((HEADER_ALIGN - unpadded % HEADER_ALIGN) % HEADER_ALIGN));
+    header.push('\n');
+
+    let mut out = Vec::with_capacity(MAGIC.len() + 4 + header.len() + data.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&[1, 0]);
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    out.extend_from_slice(data);
+    Ok(out)
+}
+
+/// Parse the header of .npy bytes (versions 1.0 - 3.0)
+pub fn parse_npy_header(bytes: &[u8]) -> Result<NpyHeader> {
+    if bytes.len() < 10 || &bytes[..MAGIC.len()] != MAGIC {
+        anyhow::bail!("Not a .npy array (missing \\x93NUMPY magic)");
+    }
+    let major = bytes[6];
+    let (header_len, start) = match major {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 => {
+            if bytes.len() < 12 {
+                anyhow::bail!("Truncated .npy v{} header", major);
+            }
+            (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12)
+        }
+        v => anyhow::bail!("Unsupported .npy format version {}", v),
+    };
+    let end = start + header_len;
+    let text = std::str::from_utf8(bytes.get(start..end).context("Truncated .npy header")?)
+        .context(".npy header is not valid text")?;
+
+    let descr = dict_value(text, "descr")
+        .map(|v| v.trim_matches(|c| c == '\'' || c == '"'))
+        .context(".npy header has no 'descr'")?;
+    let dtype = NpyDtype::from_descr(descr)
+        .with_context(|| format!("Unsupported .npy dtype '{}'", descr))?;
+    let fortran_order = dict_value(text, "fortran_order").map_or(false, |v| v == "True");
+    let shape_text = dict_value(text, "shape").context(".npy header has no 'shape'")?;
+    let shape = shape_text
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>().with_context(|| format!("Bad .npy shape entry '{}'", s)))
+        .collect::<Result<Vec<_>>>()?;
+    // A corrupt shape must not overflow data_len() (or data_offset + data_len()) later
+    shape
+        .iter()
+        .try_fold(dtype.item_size(), |n, &d| n.checked_mul(d))
+        .and_then(|n| n.checked_add(end))
+        .with_context(|| format!(".npy shape {:?} is too large", shape))?;
+
+    Ok(NpyHeader { dtype, fortran_order, shape, data_offset: end })
+}
+
+/// Value text for `'key': value` in a numpy header dict
+fn dict_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("'{}':", key);
+    let rest = text[text.find(&pattern)? + pattern.len()..].trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')')? + 1
+    } else {
+        rest.find(',').or_else(|| rest.find('}'))?
+    };
+    Some(rest[..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_roundtrip_and_alignment() {
+        let data = vec![0u8; 2 * 3 * 4];
+        let npy = write_npy(NpyDtype::F32, &[2, 3], &data).unwrap();
+        let header = parse_npy_header(&npy).unwrap();
+
+        assert_eq!(header.data_offset % HEADER_ALIGN, 0);
+        assert_eq!(header.dtype, NpyDtype::F32);
+        assert_eq!(header.shape, vec![2, 3]);
+        assert!(!header.fortran_order);
+        assert_eq!(npy.len() - header.data_offset, header.data_len());
+
+        let one_d = write_npy(NpyDtype::U8, &[5], &[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(parse_npy_header(&one_d).unwrap().shape, vec![5]);
+    }
+
+    #[test]
+    fn test_dtype_names() {
+        assert_eq!(NpyDtype::from_dlio("uint8").unwrap(), NpyDtype::U8);
+        assert_eq!(NpyDtype::from_dlio("np.float16").unwrap(), NpyDtype::F16);
+        assert!(NpyDtype::from_dlio("complex64").is_err());
+        assert!(write_npy(NpyDtype::I16, &[3], &[0u8; 5]).is_err());
+    }
+}
@@ -4,20 +4,27 @@
 // crates/formats/src/npz.rs
 
 use anyhow::{Context, Result};
-use ndarray::{ArrayD, IxDyn};
-use ndarray_npy::WriteNpyExt;
-use std::io::{Cursor, Write};
+use std::io::{Cursor, Read, Seek, Write};
 use std::path::Path;
 use zip::{write::FileOptions, CompressionMethod, ZipWriter};
 
+use crate::npy::{parse_npy_header, write_npy, NpyDtype, NpyHeader};
 use crate::Format;
 
+/// Bytes read from each archive entry to parse its .npy header
+const HEADER_PROBE_BYTES: u64 = 64 * 1024;
+
 /// NPZ format generator + reader
-/// Creates proper ZIP archives containing multiple .npy files
+/// Creates ZIP archives of real .npy arrays, stored uncompressed like `np.savez`:
+/// `data` (the records, with the configured dtype and shape), `labels` (one
+/// int64 per record along the first axis), then optional float32 `metadata`
+/// and `array_N` arrays of the same length.
 /// Leverages s3dlio's generate_controlled_data for synthetic data
 pub struct NpzFormat {
     shape: Vec<usize>,
     num_arrays: usize,
+    dtype: NpyDtype,
+    data: Option<Vec<u8>>,
 }
 
 impl NpzFormat {
@@ -25,134 +32,189 @@ impl NpzFormat {
         Self {
             shape,
             num_arrays: num_arrays.max(1), // Ensure at least 1 array
+            dtype: NpyDtype::F32,
+            data: None,
         }
     }
 
-    /// Create synthetic array data using s3dlio utilities with diverse patterns
-    fn create_synthetic_array(&self, array_index: usize) -> Result<ArrayD<f32>> {
-        let total_elements = self.shape.iter().product::<usize>();
-        let total_bytes = total_elements * std::mem::size_of::<f32>();
+    /// Set the element type of the `data` array (default float32)
+    pub fn with_dtype(mut self, dtype: NpyDtype) -> Self {
+        self.dtype = dtype;
+        self
+    }
+
+    /// Use caller-provided bytes for the `data` array instead of s3dlio synthetic data
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.data = Some(data);
+        self
+    }
 
-        // Use s3dlio's controlled data generation for base synthetic data
-        let base_data = s3dlio::generate_controlled_data(total_bytes, array_index, 0);
+    fn array_name(index: usize) -> String {
+        match index {
+            0 => "data.npy".to_string(),
+            1 => "labels.npy".to_string(),
+            2 => "metadata.npy".to_string(),
+            _ => format!("array_{}.npy", index),
+        }
+    }
 
-        // Convert bytes to f32 array with proper patterns
-        let data: Vec<f32> = match array_index {
+    /// Number of records: the first axis of the data array
+    fn num_records(&self) -> usize {
+        self.shape.first().copied().unwrap_or(1)
+    }
+
+    /// Build the .npy bytes for one array of the archive
+    fn create_array(&self, array_index: usize) -> Result<Vec<u8>> {
+        let records = self.num_records();
+        match array_index {
             0 => {
-                // Main data array: use s3dlio data + sine wave pattern
-                base_data
-                    .chunks_exact(4)
-                    .enumerate()
-                    .map(|(i, chunk)| {
-                        let base_val = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-                        (base_val * 0.1) + (i as f32 * 0.1).sin()
-                    })
-                    .take(total_elements)
-                    .collect()
+                // Main data array: provided bytes, or s3dlio synthetic bytes reinterpreted as the target dtype
+                let len = self.shape.iter().product::<usize>() * self.dtype.item_size();
+                match &self.data {
+                    Some(data) => write_npy(self.dtype, &self.shape, data),
+                    None => {
+                        let data = s3dlio::generate_controlled_data(len, 0, 0);
+                        write_npy(self.dtype, &self.shape, &data[..len])
+                    }
+                }
             }
             1 => {
-                // Labels array: categorical pattern derived from s3dlio data
-                base_data
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &byte)| ((byte as usize + i) % 10) as f32 / 10.0)
-                    .take(total_elements)
-                    .collect()
-            }
-            2 => {
-                // Metadata array: gradient based on s3dlio data
-                base_data
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &byte)| (byte as f32 + i as f32) / (total_elements as f32 + 255.0))
-                    .take(total_elements)
-                    .collect()
+                // Labels array: categorical class id per record
+                let data: Vec<u8> = (0..records).flat_map(|i| ((i % 10) as i64).to_le_bytes()).collect();
+                write_npy(NpyDtype::I64, &[records], &data)
             }
             _ => {
-                // Additional arrays: controlled randomness from s3dlio
-                base_data
-                    .chunks_exact(4)
-                    .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                    .take(total_elements)
-                    .collect()
+                // Metadata/additional arrays: per-record float32 gradient
+                let data: Vec<u8> = (0..records)
+                    .flat_map(|i| ((i + array_index) as f32 / (records + array_index) as f32).to_le_bytes())
+                    .collect();
+                write_npy(NpyDtype::F32, &[records], &data)
             }
-        };
-
-        // Reshape to target shape
-        ArrayD::from_shape_vec(IxDyn(&self.shape), data)
-            .with_context(|| "Failed to reshape synthetic data array")
+        }
     }
-}
-
-impl Format for NpzFormat {
-    fn generate(&self, path: &Path) -> Result<()> {
-        // Create a proper NPZ file (ZIP archive containing multiple .npy files)
-        let file = std::fs::File::create(path)
-            .with_context(|| format!("Failed to create NPZ file at {:?}", path))?;
 
-        let mut zip = ZipWriter::new(file);
+    /// Write the NPZ archive to any seekable writer
+    fn write_archive<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        let mut zip = ZipWriter::new(writer);
         let options = FileOptions::<()>::default()
-            .compression_method(CompressionMethod::Deflated)
-            .unix_permissions(0o755);
+            .compression_method(CompressionMethod::Stored)
+            .unix_permissions(0o644);
 
-        // Generate diverse synthetic data arrays using s3dlio utilities
         for i in 0..self.num_arrays {
-            let array_name = match i {
-                0 => "data.npy",
-                1 => "labels.npy",
-                2 => "metadata.npy",
-                _ => &format!("array_{}.npy", i),
-            };
-
-            // Create diverse synthetic data using s3dlio + patterns
-            let synthetic_array = self.create_synthetic_array(i)?;
-
-            // Write array to memory buffer first
-            let mut buffer = Vec::new();
-            {
-                let mut cursor = Cursor::new(&mut buffer);
-                synthetic_array
-                    .write_npy(&mut cursor)
-                    .with_context(|| format!("Failed to serialize array {}", array_name))?;
-            }
+            let array_name = Self::array_name(i);
+            let npy = self
+                .create_array(i)
+                .with_context(|| format!("Failed to serialize array {}", array_name))?;
 
-            // Add to ZIP archive
-            zip.start_file(array_name, options)
+            zip.start_file(array_name.as_str(), options)
                 .with_context(|| format!("Failed to start ZIP file entry for {}", array_name))?;
-            zip.write_all(&buffer)
+            zip.write_all(&npy)
                 .with_context(|| format!("Failed to write array {} to ZIP", array_name))?;
         }
 
         zip.finish()
             .with_context(|| "Failed to finalize NPZ ZIP archive")?;
-
         Ok(())
     }
 
-    fn read(&self, path: &Path) -> Result<()> {
-        // Validate that it's a proper ZIP file with .npy entries
-        let file = std::fs::File::open(path)
-            .with_context(|| format!("Failed to open NPZ file at {:?}", path))?;
-
-        let mut archive =
-            zip::ZipArchive::new(file).with_context(|| "Failed to read NPZ as ZIP archive")?;
+    /// Generate the NPZ archive in memory
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_archive(Cursor::new(&mut buffer))?;
+        Ok(buffer)
+    }
 
-        if archive.is_empty() {
-            anyhow::bail!("NPZ file is empty");
+    /// Validate an NPZ archive: every entry must be a well-formed .npy whose
+    /// declared size matches its contents, and `data`/`labels` must match this
+    /// format's dtype and shape.
+    pub fn validate_archive<R: Read + Seek>(&self, reader: R) -> Result<Vec<(String, NpyHeader)>> {
+        let arrays = read_array_headers(reader)?;
+
+        let data = arrays
+            .iter()
+            .find(|(name, _)| name == "data.npy")
+            .map(|(_, h)| h)
+            .context("NPZ has no data.npy array")?;
+        if data.dtype != self.dtype {
+            anyhow::bail!("data.npy dtype is {} but config expects {}", data.dtype.descr(), self.dtype.descr());
+        }
+        if data.shape != self.shape {
+            anyhow::bail!("data.npy shape is {:?} but config expects {:?}", data.shape, self.shape);
+        }
+        if let Some((_, labels)) = arrays.iter().find(|(name, _)| name == "labels.npy") {
+            if labels.shape.first() != self.shape.first() {
+                anyhow::bail!("labels.npy has {:?} entries but data.npy has {:?} records",
+                              labels.shape.first(), self.shape.first());
+            }
         }
+        Ok(arrays)
+    }
+}
 
-        // Verify all entries are .npy files
-        for i in 0..archive.len() {
-            let entry = archive
-                .by_index(i)
-                .with_context(|| format!("Failed to read ZIP entry {}", i))?;
-            let name = entry.name();
+/// Parse the .npy header of every entry in an NPZ archive
+///
+/// Fails if an entry is not a .npy array or its size disagrees with its header.
+pub fn read_array_headers<R: Read + Seek>(reader: R) -> Result<Vec<(String, NpyHeader)>> {
+    let mut archive = zip::ZipArchive::new(reader).with_context(|| "Failed to read NPZ as ZIP archive")?;
+    if archive.is_empty() {
+        anyhow::bail!("NPZ file is empty");
+    }
 
-            if !name.ends_with(".npy") {
-                anyhow::bail!("NPZ contains non-.npy file: {}", name);
-            }
+    let mut arrays = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .with_context(|| format!("Failed to read ZIP entry {}", i))?;
+        let name = entry.name().to_string();
+        if !name.ends_with(".npy") {
+            anyhow::bail!("NPZ contains non-.npy file: {}", name);
+        }
+
+        let size = entry.size() as usize;
+        let mut head = Vec::new();
+        entry
+            .take(HEADER_PROBE_BYTES)
+            .read_to_end(&mut head)
+            .with_context(|| format!("Failed to read {}", name))?;
+        let header = parse_npy_header(&head).with_context(|| format!("Invalid array {}", name))?;
+        if header.data_offset + header.data_len() != size {
+            anyhow::bail!("{} is {} bytes but its header declares {:?} {} ({} bytes of data)",
+                          name, size, header.shape, header.dtype.descr(), header.data_len());
         }
+        arrays.push((name, header));
+    }
+    Ok(arrays)
+}
+
+/// Read one array of an in-memory NPZ archive, returning its header and data bytes
+pub fn read_array(npz: &[u8], name: &str) -> Result<(NpyHeader, Vec<u8>)> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(npz)).with_context(|| "Failed to read NPZ as ZIP archive")?;
+    let mut entry = archive.by_name(name).with_context(|| format!("NPZ has no {} array", name))?;
+    // The entry size comes from the archive; don't trust it for more than the archive holds
+    let mut bytes = Vec::with_capacity(entry.size().min(npz.len() as u64) as usize);
+    entry.read_to_end(&mut bytes).with_context(|| format!("Failed to read {}", name))?;
+
+    let header = parse_npy_header(&bytes).with_context(|| format!("Invalid array {}", name))?;
+    if header.data_offset + header.data_len() != bytes.len() {
+        anyhow::bail!("{} is {} bytes but its header declares {} bytes of data", name, bytes.len(), header.data_len());
+    }
+    let data = bytes.split_off(header.data_offset);
+    Ok((header, data))
+}
 
+impl Format for NpzFormat {
+    fn generate(&self, path: &Path) -> Result<()> {
+        // Create a proper NPZ file (ZIP archive containing multiple .npy files)
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create NPZ file at {:?}", path))?;
+        self.write_archive(file)
+    }
+
+    fn read(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open NPZ file at {:?}", path))?;
+        self.validate_archive(file)
+            .with_context(|| format!("NPZ validation failed for {:?}", path))?;
         Ok(())
     }
 }
@@ -160,16 +222,24 @@ impl Format for NpzFormat {
 /// Streaming format implementation for NPZ
 /// Uses s3dlio utilities for data generation
 pub struct NpzStreamingFormat {
-    shape: Vec<usize>,
-    num_arrays: usize,
+    inner: NpzFormat,
 }
 
 impl NpzStreamingFormat {
     pub fn new(shape: Vec<usize>, num_arrays: usize) -> Self {
-        Self {
-            shape,
-            num_arrays: num_arrays.max(1),
-        }
+        Self { inner: NpzFormat::new(shape, num_arrays) }
+    }
+
+    /// Set the element type of the `data` array (default float32)
+    pub fn with_dtype(mut self, dtype: NpyDtype) -> Self {
+        self.inner = self.inner.with_dtype(dtype);
+        self
+    }
+
+    /// Use caller-provided bytes for the `data` array
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.inner = self.inner.with_data(data);
+        self
     }
 }
 
@@ -177,82 +247,21 @@ use crate::{FormatMetadata, StreamingFormat};
 
 impl Format for NpzStreamingFormat {
     fn generate(&self, path: &Path) -> Result<()> {
-        let format = NpzFormat::new(self.shape.clone(), self.num_arrays);
-        format.generate(path)
+        self.inner.generate(path)
     }
 
     fn read(&self, path: &Path) -> Result<()> {
-        let format = NpzFormat::new(self.shape.clone(), self.num_arrays);
-        format.read(path)
+        self.inner.read(path)
     }
 }
 
 impl StreamingFormat for NpzStreamingFormat {
     fn generate_bytes(&self, _filename: &str) -> Result<Vec<u8>> {
-        // Generate NPZ data in memory
-        let mut buffer = Vec::new();
-        {
-            let mut zip = ZipWriter::new(Cursor::new(&mut buffer));
-            let options =
-                FileOptions::<()>::default().compression_method(CompressionMethod::Deflated);
-
-            // Generate diverse synthetic data arrays using s3dlio utilities
-            for i in 0..self.num_arrays {
-                let array_name = match i {
-                    0 => "data.npy",
-                    1 => "labels.npy",
-                    2 => "metadata.npy",
-                    _ => &format!("array_{}.npy", i),
-                };
-
-                // Create diverse synthetic data using s3dlio + patterns
-                let synthetic_array = self.create_synthetic_array(i)?;
-
-                // Write array to memory buffer first
-                let mut npy_buffer = Vec::new();
-                {
-                    let mut cursor = Cursor::new(&mut npy_buffer);
-                    synthetic_array
-                        .write_npy(&mut cursor)
-                        .with_context(|| format!("Failed to serialize array {}", array_name))?;
-                }
-
-                // Add to ZIP archive
-                zip.start_file(array_name, options).with_context(|| {
-                    format!("Failed to start ZIP file entry for {}", array_name)
-                })?;
-                zip.write_all(&npy_buffer)
-                    .with_context(|| format!("Failed to write array {} to ZIP", array_name))?;
-            }
-
-            zip.finish()
-                .with_context(|| "Failed to finalize NPZ ZIP archive")?;
-        }
-        Ok(buffer)
+        self.inner.to_bytes()
     }
 
     fn read_from_bytes(&self, data: &[u8]) -> Result<()> {
-        // Validate NPZ data from bytes
-        let cursor = Cursor::new(data);
-        let mut archive = zip::ZipArchive::new(cursor)
-            .with_context(|| "Failed to read NPZ data as ZIP archive")?;
-
-        if archive.is_empty() {
-            anyhow::bail!("NPZ data is empty");
-        }
-
-        // Verify all entries are .npy files
-        for i in 0..archive.len() {
-            let entry = archive
-                .by_index(i)
-                .with_context(|| format!("Failed to read ZIP entry {}", i))?;
-            let name = entry.name();
-
-            if !name.ends_with(".npy") {
-                anyhow::bail!("NPZ contains non-.npy file: {}", name);
-            }
-        }
-
+        self.inner.validate_archive(Cursor::new(data))?;
         Ok(())
     }
 
@@ -261,24 +270,60 @@ impl StreamingFormat for NpzStreamingFormat {
     }
 
     fn format_metadata(&self) -> FormatMetadata {
-        let total_elements = self.shape.iter().product::<usize>();
-        let size_per_array = total_elements * std::mem::size_of::<f32>();
-        let estimated_size = size_per_array * self.num_arrays;
+        let f = &self.inner;
+        let records = f.num_records();
+        let data_size = f.shape.iter().product::<usize>() * f.dtype.item_size();
+        let extra_size = records * 8 + records * 4 * f.num_arrays.saturating_sub(2);
+        let estimated_size = data_size + if f.num_arrays > 1 { extra_size } else { 0 };
 
         FormatMetadata {
             expected_size_bytes: Some(estimated_size),
-            compression_ratio: Some(0.7), // ZIP compression typically achieves ~30% reduction
+            compression_ratio: Some(1.0), // Stored uncompressed, like np.savez
             is_binary: true,
             supports_streaming: true,
         }
     }
 }
 
-impl NpzStreamingFormat {
-    /// Create synthetic array data using s3dlio utilities with diverse patterns
-    fn create_synthetic_array(&self, array_index: usize) -> Result<ArrayD<f32>> {
-        // Reuse the same logic as NpzFormat
-        let format = NpzFormat::new(self.shape.clone(), self.num_arrays);
-        format.create_synthetic_array(array_index)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npz_arrays_have_numpy_headers() {
+        let format = NpzStreamingFormat::new(vec![4, 8, 8], 3).with_dtype(NpyDtype::U8);
+        let bytes = format.generate_bytes("sample.npz").unwrap();
+        format.read_from_bytes(&bytes).unwrap();
+
+        let arrays = read_array_headers(Cursor::new(&bytes)).unwrap();
+        let names: Vec<&str> = arrays.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["data.npy", "labels.npy", "metadata.npy"]);
+        assert_eq!(arrays[0].1.shape, vec![4, 8, 8]);
+        assert_eq!(arrays[0].1.dtype, NpyDtype::U8);
+        assert_eq!(arrays[1].1.shape, vec![4]);
+    }
+
+    #[test]
+    fn test_npz_read_rejects_mismatched_metadata() {
+        let bytes = NpzStreamingFormat::new(vec![2, 16], 2).generate_bytes("a.npz").unwrap();
+
+        let wrong_dtype = NpzStreamingFormat::new(vec![2, 16], 2).with_dtype(NpyDtype::F64);
+        assert!(wrong_dtype.read_from_bytes(&bytes).is_err());
+
+        let wrong_shape = NpzStreamingFormat::new(vec![4, 8], 2);
+        assert!(wrong_shape.read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_npz_with_data_roundtrip() {
+        let data: Vec<u8> = (0..24).collect();
+        let bytes = NpzStreamingFormat::new(vec![3, 8], 2)
+            .with_dtype(NpyDtype::U8)
+            .with_data(data.clone())
+            .generate_bytes("a.npz")
+            .unwrap();
+        let (header, read) = read_array(&bytes, "data.npy").unwrap();
+        assert_eq!(header.shape, vec![3, 8]);
+        assert_eq!(read, data);
     }
 }
@@ -9,6 +9,7 @@
 use anyhow::{Context, Result};
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::Range;
 use std::path::Path;
 
 use crate::{Format, FormatMetadata, StreamingFormat};
@@ -36,7 +37,7 @@ impl TfRecordFormat {
 
     /// CRC32C masking function as defined in TFRecord specification
     /// TensorFlow uses CRC-32C (Castagnoli), not CRC-32 (IEEE)
-    fn masked_crc32c(bytes: &[u8]) -> u32 {
+    pub(crate) fn masked_crc32c(bytes: &[u8]) -> u32 {
         let crc = crc32c::crc32c(bytes);
         // TensorFlow's mask formula
         crc.rotate_right(15).wrapping_add(0xa282_ead8)
@@ -190,60 +191,152 @@ impl Format for TfRecordFormat {
         let file = File::open(path)
             .with_context(|| format!("Failed to open TFRecord file at {:?}", path))?;
 
-        let mut reader = BufReader::new(file);
-        let mut records_read = 0;
-
-        // Read records with proper TFRecord format structure
-        while records_read < self.num_records {
-            // Read length (8 bytes)
-            let mut length_bytes = [0u8; 8];
-            match reader.read_exact(&mut length_bytes) {
-                Ok(()) => {}
-                Err(_) if records_read == self.num_records => break,
-                Err(e) => return Err(e).with_context(|| "Failed to read record length")?,
-            }
+        let stats = scan_tfrecords(BufReader::new(file))
+            .with_context(|| format!("Invalid TFRecord file {:?}", path))?;
+        self.check_count(&stats)
+    }
+}
+
+impl TfRecordFormat {
+    fn check_count(&self, stats: &TfRecordStats) -> Result<()> {
+        if stats.num_records != self.num_records {
+            anyhow::bail!(
+                "TFRecord count mismatch: expected {} records, got {}",
+                self.num_records,
+                stats.num_records
+            );
+        }
+        Ok(())
+    }
+}
 
-            let length = u64::from_le_bytes(length_bytes) as usize;
+/// Record count and sizes found by scanning a TFRecord stream
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TfRecordStats {
+    pub num_records: usize,
+    /// Payload size of each record, in file order
+    pub record_sizes: Vec<usize>,
+    /// Total stream size including the 16 bytes of framing per record
+    pub total_bytes: u64,
+}
 
-            // TFRecord payloads are variable-length, validate CRC instead
-            // Read and validate length CRC
-            let mut length_crc_bytes = [0u8; 4];
-            reader.read_exact(&mut length_crc_bytes).with_context(|| {
-                format!("Failed to read length CRC for record {}", records_read)
-            })?;
+/// Open-ended TFRecord reader: yields record payloads until EOF
+///
+/// Both CRCs are checked for every record; no record count is needed, so this
+/// works for datasets dl-driver did not generate.
+pub struct TfRecordReader<R: Read> {
+    reader: R,
+    index: usize,
+    done: bool,
+}
 
-            let expected_len_crc = u32::from_le_bytes(length_crc_bytes);
-            let actual_len_crc = Self::masked_crc32c(&length_bytes);
-            if actual_len_crc != expected_len_crc {
-                anyhow::bail!("Length CRC32C mismatch at record {}", records_read);
+impl<R: Read> TfRecordReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, index: 0, done: false }
+    }
+
+    fn read_record(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut length_bytes = [0u8; 8];
+        // A clean EOF is only allowed on a record boundary
+        let mut filled = 0;
+        while filled < length_bytes.len() {
+            let n = self
+                .reader
+                .read(&mut length_bytes[filled..])
+                .with_context(|| format!("Failed to read length of record {}", self.index))?;
+            if n == 0 {
+                if filled == 0 {
+                    return Ok(None);
+                }
+                anyhow::bail!("TFRecord truncated in length of record {}", self.index);
             }
+            filled += n;
+        }
 
-            // Read record data
-            let mut record_data = vec![0u8; length];
-            reader
-                .read_exact(&mut record_data)
-                .with_context(|| format!("Failed to read record {} data", records_read))?;
+        let mut crc_bytes = [0u8; 4];
+        self.reader
+            .read_exact(&mut crc_bytes)
+            .with_context(|| format!("Failed to read length CRC for record {}", self.index))?;
+        if TfRecordFormat::masked_crc32c(&length_bytes) != u32::from_le_bytes(crc_bytes) {
+            anyhow::bail!("Length CRC32C mismatch at record {}", self.index);
+        }
 
-            // Read data CRC (4 bytes, ignored for now)
-            let mut data_crc_bytes = [0u8; 4];
-            reader
-                .read_exact(&mut data_crc_bytes)
-                .with_context(|| format!("Failed to read record {} data CRC", records_read))?;
+        let length = u64::from_le_bytes(length_bytes) as usize;
+        let mut data = Vec::new();
+        (&mut self.reader)
+            .take(length as u64)
+            .read_to_end(&mut data)
+            .with_context(|| format!("Failed to read record {} data", self.index))?;
+        if data.len() != length {
+            anyhow::bail!("TFRecord truncated in data of record {} ({} of {} bytes)", self.index, data.len(), length);
+        }
 
-            records_read += 1;
+        self.reader
+            .read_exact(&mut crc_bytes)
+            .with_context(|| format!("Failed to read data CRC for record {}", self.index))?;
+        if TfRecordFormat::masked_crc32c(&data) != u32::from_le_bytes(crc_bytes) {
+            anyhow::bail!("Data CRC32C mismatch at record {}", self.index);
         }
 
-        // Verify we read the expected number of records
-        if records_read != self.num_records {
-            anyhow::bail!(
-                "TFRecord count mismatch: expected {} records, got {}",
-                self.num_records,
-                records_read
-            );
+        self.index += 1;
+        Ok(Some(data))
+    }
+}
+
+impl<R: Read> Iterator for TfRecordReader<R> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_record().transpose();
+        // Stop after EOF or the first error; the stream position is unknown
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
         }
+        result
+    }
+}
 
-        Ok(())
+/// Byte range of each record's data in an in-memory TFRecord stream
+///
+/// Walks the record headers checking only the length CRCs (`scan_tfrecords`
+/// validates the data), so sample boundaries are cheap to find.
+pub fn record_extents(data: &[u8]) -> Result<Vec<Range<usize>>> {
+    let mut extents = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let index = extents.len();
+        let header = data
+            .get(pos..pos + 12)
+            .with_context(|| format!("TFRecord truncated in length of record {}", index))?;
+        let length_bytes: [u8; 8] = header[..8].try_into().unwrap();
+        if TfRecordFormat::masked_crc32c(&length_bytes) != u32::from_le_bytes(header[8..].try_into().unwrap()) {
+            anyhow::bail!("Length CRC32C mismatch at record {}", index);
+        }
+        let start = pos + 12;
+        let end = usize::try_from(u64::from_le_bytes(length_bytes))
+            .ok()
+            .and_then(|len| start.checked_add(len))
+            .filter(|end| end.checked_add(4).is_some_and(|next| next <= data.len()))
+            .with_context(|| format!("TFRecord truncated in data of record {}", index))?;
+        extents.push(start..end);
+        pos = end + 4;
     }
+    Ok(extents)
+}
+
+/// Validate a whole TFRecord stream and report its record count and sizes
+pub fn scan_tfrecords<R: Read>(reader: R) -> Result<TfRecordStats> {
+    let mut stats = TfRecordStats::default();
+    for record in TfRecordReader::new(reader) {
+        let len = record?.len();
+        stats.num_records += 1;
+        stats.record_sizes.push(len);
+        stats.total_bytes += (len + 16) as u64;
+    }
+    Ok(stats)
 }
 
 #[cfg(test)]
@@ -281,6 +374,22 @@ mod tests {
         fmt.generate(&path).unwrap();
         fmt.read(&path).unwrap();
     }
+
+    #[test]
+    fn tfrecord_scan_without_known_count() {
+        let bytes = TfRecordFormat::new(7, 512).generate_bytes("a.tfrecord").unwrap();
+        let stats = scan_tfrecords(bytes.as_slice()).unwrap();
+        assert_eq!(stats.num_records, 7);
+        assert_eq!(stats.total_bytes, bytes.len() as u64);
+        assert_eq!(stats.record_sizes.iter().sum::<usize>() + 7 * 16, bytes.len());
+
+        // Truncation and corruption are reported, not treated as EOF
+        assert!(scan_tfrecords(&bytes[..bytes.len() - 3]).is_err());
+        let mut corrupt = bytes.clone();
+        corrupt[20] ^= 0xff;
+        assert!(scan_tfrecords(corrupt.as_slice()).is_err());
+        assert_eq!(scan_tfrecords(&[][..]).unwrap().num_records, 0);
+    }
 }
 
 impl StreamingFormat for TfRecordFormat {
@@ -302,112 +411,8 @@ impl StreamingFormat for TfRecordFormat {
     }
 
     fn read_from_bytes(&self, data: &[u8]) -> Result<()> {
-        // Parse proper TFRecord format from memory
-        let mut offset = 0;
-        let mut records_read = 0;
-
-        while records_read < self.num_records && offset < data.len() {
-            // Read length (8 bytes)
-            if offset + 8 > data.len() {
-                anyhow::bail!(
-                    "TFRecord: insufficient data for length at record {}",
-                    records_read
-                );
-            }
-
-            let length = u64::from_le_bytes([
-                data[offset],
-                data[offset + 1],
-                data[offset + 2],
-                data[offset + 3],
-                data[offset + 4],
-                data[offset + 5],
-                data[offset + 6],
-                data[offset + 7],
-            ]) as usize;
-            offset += 8;
-
-            // TFRecord payloads are variable-length, validate CRC instead
-            // Validate length CRC (4 bytes)
-            if offset + 4 > data.len() {
-                anyhow::bail!(
-                    "TFRecord: insufficient data for length CRC at record {}",
-                    records_read
-                );
-            }
-
-            let length_bytes = [
-                data[offset - 8],
-                data[offset - 7],
-                data[offset - 6],
-                data[offset - 5],
-                data[offset - 4],
-                data[offset - 3],
-                data[offset - 2],
-                data[offset - 1],
-            ];
-            let expected_len_crc = u32::from_le_bytes([
-                data[offset],
-                data[offset + 1],
-                data[offset + 2],
-                data[offset + 3],
-            ]);
-            let actual_len_crc = Self::masked_crc32c(&length_bytes);
-            if actual_len_crc != expected_len_crc {
-                anyhow::bail!("Length CRC32C mismatch at record {}", records_read);
-            }
-
-            // Skip past length CRC (already processed)
-            if offset + 4 > data.len() {
-                anyhow::bail!(
-                    "TFRecord: insufficient data for length CRC at record {}",
-                    records_read
-                );
-            }
-            offset += 4;
-
-            // Read and validate record data
-            if offset + length > data.len() {
-                anyhow::bail!(
-                    "TFRecord: insufficient data for record {} data",
-                    records_read
-                );
-            }
-            let record_data = &data[offset..offset + length];
-            offset += length;
-
-            // Validate data CRC (4 bytes)
-            if offset + 4 > data.len() {
-                anyhow::bail!(
-                    "TFRecord: insufficient data for data CRC at record {}",
-                    records_read
-                );
-            }
-            let expected_data_crc = u32::from_le_bytes([
-                data[offset],
-                data[offset + 1],
-                data[offset + 2],
-                data[offset + 3],
-            ]);
-            let actual_data_crc = Self::masked_crc32c(record_data);
-            if actual_data_crc != expected_data_crc {
-                anyhow::bail!("Data CRC32C mismatch at record {}", records_read);
-            }
-            offset += 4;
-
-            records_read += 1;
-        }
-
-        // Verify we read the expected number of records
-        if records_read != self.num_records {
-            anyhow::bail!(
-                "TFRecord count mismatch: expected {} records, got {}",
-                self.num_records,
-                records_read
-            );
-        }
-
-        Ok(())
+        let stats = scan_tfrecords(data)?;
+        self.check_count(&stats)
     }
 
     fn file_extension(&self) -> &'static str {
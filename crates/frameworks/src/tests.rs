@@ -18,12 +18,22 @@ fn create_test_dlio_config() -> DlioConfig {
             num_files_train: Some(100),
             num_files_eval: None,
             record_length_bytes: Some(1024),
+            record_length_bytes_stdev: None,
+            record_length_distribution: None,
             num_samples_per_file: Some(10),
             compression: None,
+            record_dims: None,
+            record_element_type: None,
+            components: None,
+            data_folders: None,
+            key_layout: None,
+            lock: None,
+            eval_folder: None,
         },
         reader: ReaderConfig {
             data_loader: Some("pytorch".to_string()),
             batch_size: Some(32),
+            batch_size_bytes: None,
             prefetch: Some(4),
             shuffle: Some(true),
             read_threads: Some(2),
@@ -31,6 +41,18 @@ fn create_test_dlio_config() -> DlioConfig {
             transfer_size: None,
             file_access_type: None,
             seed: Some(42),
+            drop_last: None,
+            shuffle_strategy: None,
+            sample_shuffle: None,
+            shuffle_size: None,
+            thread_model: None,
+            batch_size_eval: None,
+            prefetch_eval: None,
+            read_threads_eval: None,
+            batch_size_checkpoint: None,
+            prefetch_checkpoint: None,
+            read_threads_checkpoint: None,
+            epoch_boundary: None,
         },
         checkpointing: None,
         profiling: None,
@@ -169,7 +169,7 @@ test_data_loading_basic() {
     # Test basic DLIO data loading
     if timeout 120 $DL_DRIVER run --config "$config_file" --max-steps $MAX_STEPS > "$RESULTS_DIR/${backend}_basic_loading.log" 2>&1; then
         # Check if the command ran and processed any data
-        if grep -q "CheckpointPlugin::after_step" "$RESULTS_DIR/${backend}_basic_loading.log" || grep -q "� Final Stats:" "$RESULTS_DIR/${backend}_basic_loading.log"; then
+        if grep -q "� Final Stats:" "$RESULTS_DIR/${backend}_basic_loading.log"; then
             log_result "PASS" $backend "basic" "data_loading" "Data loading executed and processed batches"
         else
             log_result "FAIL" $backend "basic" "data_loading" "No data processing detected"
@@ -193,10 +193,10 @@ test_checkpointing_basic() {
     
     # Test basic DLIO with checkpointing
     if timeout 120 $DL_DRIVER run --config "$config_file" --max-steps $MAX_STEPS > "$RESULTS_DIR/${backend}_basic_checkpoint.log" 2>&1; then
-        if grep -q "Checkpointing is enabled!" "$RESULTS_DIR/${backend}_basic_checkpoint.log" && grep -q "CheckpointPlugin::after_step" "$RESULTS_DIR/${backend}_basic_checkpoint.log"; then
-            log_result "PASS" $backend "basic" "checkpointing" "Checkpoint plugin enabled and executed steps"
+        if grep -q "Checkpoint at step" "$RESULTS_DIR/${backend}_basic_checkpoint.log"; then
+            log_result "PASS" $backend "basic" "checkpointing" "Checkpoints written during training"
         else
-            log_result "FAIL" $backend "basic" "checkpointing" "Checkpoint plugin not working properly"
+            log_result "FAIL" $backend "basic" "checkpointing" "No checkpoint writes logged"
         fi
     else
         log_result "FAIL" $backend "basic" "checkpointing" "Basic checkpointing command failed"